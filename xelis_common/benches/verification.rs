@@ -0,0 +1,39 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use curve25519_dalek::Scalar;
+use rand::rngs::OsRng;
+use xelis_common::crypto::{
+    proofs::{BalanceProof, OwnershipProof, G_TABLE, H_TABLE, PC_GENS},
+    KeyPair
+};
+
+// Compare the fixed-base tables against a variable-base multiplication
+fn bench_generators(c: &mut Criterion) {
+    let scalar = Scalar::random(&mut OsRng);
+
+    let mut group = c.benchmark_group("generators");
+    group.bench_function("G variable-base", |b| b.iter(|| black_box(scalar) * PC_GENS.B));
+    group.bench_function("G fixed-base", |b| b.iter(|| &black_box(scalar) * G_TABLE));
+    group.bench_function("H variable-base", |b| b.iter(|| black_box(scalar) * PC_GENS.B_blinding));
+    group.bench_function("H fixed-base", |b| b.iter(|| &black_box(scalar) * &*H_TABLE));
+    group.finish();
+}
+
+// Verification paths using the fixed-base tables
+fn bench_verification(c: &mut Criterion) {
+    let keypair = KeyPair::new();
+    let public_key = keypair.get_public_key();
+
+    let message = b"benchmark";
+    let signature = keypair.sign(message);
+    c.bench_function("signature verify", |b| b.iter(|| assert!(signature.verify(message, public_key))));
+
+    let ct = public_key.encrypt(100u64);
+    let proof = BalanceProof::new(&keypair, 100, ct.clone());
+    c.bench_function("balance proof verify", |b| b.iter(|| proof.verify(public_key, ct.clone()).unwrap()));
+
+    let proof = OwnershipProof::new(&keypair, 100, 10, ct.clone()).unwrap();
+    c.bench_function("ownership proof verify", |b| b.iter(|| proof.verify(public_key, ct.clone()).unwrap()));
+}
+
+criterion_group!(benches, bench_generators, bench_verification);
+criterion_main!(benches);
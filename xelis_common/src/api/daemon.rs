@@ -15,13 +15,16 @@ use xelis_vm::Constant;
 use crate::{
     account::{Nonce, CiphertextCache, VersionedBalance, VersionedNonce},
     block::{TopoHeight, Algorithm, BlockVersion, EXTRA_NONCE_SIZE},
-    crypto::{Address, Hash},
+    crypto::{elgamal::CompressedCiphertext, merkle::MerkleProof, Address, Hash, HumanReadableProof},
     difficulty::{CumulativeDifficulty, Difficulty},
     network::Network,
     time::{TimestampMillis, TimestampSeconds},
-    transaction::extra_data::{SharedKey, UnknownExtraDataFormat},
+    transaction::{
+        extra_data::{SharedKey, UnknownExtraDataFormat},
+        ContractManagementAction
+    },
 };
-use super::{default_true_value, DataElement, RPCContractOutput, RPCTransaction};
+use super::{default_true_value, DataElement, RPCContractOutput, RPCContractReceipt, RPCTransaction};
 
 #[derive(Serialize, Deserialize, PartialEq, Eq)]
 pub enum BlockType {
@@ -62,6 +65,17 @@ pub struct RPCBlockResponse<'a> {
     pub cumulative_difficulty: Cow<'a, CumulativeDifficulty>,
     pub total_fees: Option<u64>,
     pub total_size_in_bytes: usize,
+    // Size of the block header only
+    #[serde(default)]
+    pub header_size_in_bytes: usize,
+    #[serde(default)]
+    pub txs_count: usize,
+    // Only set when the transactions are loaded
+    #[serde(default)]
+    pub transfers_count: Option<usize>,
+    // Total fees paid per kB of transactions
+    #[serde(default)]
+    pub fee_rate: Option<u64>,
     pub version: BlockVersion,
     pub tips: Cow<'a, IndexSet<Hash>>,
     pub timestamp: TimestampMillis,
@@ -106,6 +120,44 @@ pub struct GetBlockByHashParams<'a> {
     pub include_txs: bool
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct GetBlockExecutionOrderParams<'a> {
+    // Only one of them must be set
+    #[serde(default)]
+    pub hash: Option<Cow<'a, Hash>>,
+    #[serde(default)]
+    pub topoheight: Option<TopoHeight>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionExecutionStatus<'a> {
+    // Executed by this block
+    Executed,
+    // Executed by another block ordered before this one
+    ExecutedIn(Cow<'a, Hash>),
+    // Skipped (invalid with the current DAG order or double spending)
+    NotExecuted
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TransactionExecutionEntry<'a> {
+    pub hash: Cow<'a, Hash>,
+    pub status: TransactionExecutionStatus<'a>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetBlockExecutionOrderResult<'a> {
+    pub hash: Cow<'a, Hash>,
+    // None if the block is not ordered in the DAG
+    pub topoheight: Option<TopoHeight>,
+    // Position in which the block got added to the chain by this node
+    // This is local to the node and independent of the DAG order
+    pub execution_position: Option<u64>,
+    // Transactions in the order they were processed
+    pub transactions: Vec<TransactionExecutionEntry<'a>>
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct GetBlockTemplateParams<'a> {
     pub address: Cow<'a, Address>
@@ -282,11 +334,113 @@ pub struct GetTransactionParams<'a> {
 
 pub type GetTransactionExecutorParams<'a> = GetTransactionParams<'a>;
 
+pub type IsTxExecutedParams<'a> = GetTransactionParams<'a>;
+
+pub type GetTransactionBlocksParams<'a> = GetTransactionParams<'a>;
+
+#[derive(Serialize, Deserialize)]
+pub struct TransactionBlockEntry<'a> {
+    pub hash: Cow<'a, Hash>,
+    // None if the block is not ordered in the DAG
+    pub topoheight: Option<TopoHeight>,
+    // Is this block the one that executed the TX
+    pub executed: bool
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetTransactionBlocksResult<'a> {
+    // All blocks including the TX, sorted by topoheight
+    pub blocks: Vec<TransactionBlockEntry<'a>>,
+    // Block that executed the TX, if any
+    pub executed_in_block: Option<Cow<'a, Hash>>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetBlockSkippedTransactionsParams<'a> {
+    pub hash: Cow<'a, Hash>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SkippedTransactionEntry<'a> {
+    pub hash: Cow<'a, Hash>,
+    // Block that executed the TX instead, None if it was never executed
+    pub executed_in_block: Option<Cow<'a, Hash>>
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct GetTransactionExecutorResult<'a> {
     pub block_topoheight: TopoHeight,
     pub block_timestamp: TimestampMillis,
-    pub block_hash: Cow<'a, Hash>
+    pub block_hash: Cow<'a, Hash>,
+    // Topoheights built on top of the executor block
+    // This is the confirmations count of the TX in the DAG
+    #[serde(default)]
+    pub confirmations: u64,
+    // Is the executor block under the stable topoheight
+    #[serde(default)]
+    pub in_stable_height: bool
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VerifyPaymentProofParams {
+    pub proof: HumanReadableProof
+}
+
+// Transfer revealed by a valid payment proof
+#[derive(Serialize, Deserialize)]
+pub struct VerifyPaymentProofResult<'a> {
+    pub transaction: Cow<'a, Hash>,
+    pub index: u8,
+    pub source: Address,
+    pub destination: Address,
+    pub asset: Cow<'a, Hash>,
+    pub amount: u64,
+    // Topoheight of the block that executed the transaction
+    pub block_topoheight: TopoHeight
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VerifyReserveProofParams<'a> {
+    // Account that created the proof
+    pub address: Cow<'a, Address>,
+    pub proof: HumanReadableProof
+}
+
+// Reserve revealed by a valid reserve proof
+#[derive(Serialize, Deserialize)]
+pub struct VerifyReserveProofResult<'a> {
+    pub address: Cow<'a, Address>,
+    pub asset: Cow<'a, Hash>,
+    // Minimum amount owned by the account
+    pub amount: u64,
+    // Topoheight of the balance version used by the proof
+    pub topoheight: TopoHeight,
+    // Challenge in hex format, it must be the one given to the prover
+    pub challenge: String,
+    // No newer balance version exists for this asset
+    pub latest: bool
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VerifyAuditProofParams<'a> {
+    // Account that created the proof
+    pub address: Cow<'a, Address>,
+    pub proof: HumanReadableProof
+}
+
+// Ciphertext revealed to the auditor by a valid audit proof
+#[derive(Serialize, Deserialize)]
+pub struct VerifyAuditProofResult<'a> {
+    pub address: Cow<'a, Address>,
+    pub auditor: Address,
+    pub asset: Cow<'a, Hash>,
+    // Topoheight of the balance version, set for a balance audit
+    pub topoheight: Option<TopoHeight>,
+    // Transfer audited, set for a transfer audit
+    pub transaction: Option<Cow<'a, Hash>>,
+    pub index: Option<u8>,
+    // Audited amount encrypted for the auditor
+    pub ciphertext: CompressedCiphertext
 }
 
 // Direction is used for cache to knows from which context it got added
@@ -352,6 +506,26 @@ pub struct PeerEntry<'a> {
     pub bytes_recv: usize,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct GetWorkMinerEntry<'a> {
+    pub address: Address,
+    pub name: Cow<'a, String>,
+    pub connected_since: TimestampMillis,
+    // last block or share submitted, or connection time
+    pub last_activity: TimestampMillis,
+    // last job sent to the miner
+    pub last_job: TimestampMillis,
+    pub blocks_accepted: usize,
+    pub blocks_rejected: usize,
+    // blocks submitted for a height already found
+    pub blocks_stale: usize,
+    // stale blocks over all the blocks submitted
+    pub stale_rate: f64,
+    pub shares_accepted: usize,
+    // None if only blocks are accepted
+    pub share_difficulty: Option<Difficulty>
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct P2pStatusResult<'a> {
     pub peer_count: usize,
@@ -363,12 +537,123 @@ pub struct P2pStatusResult<'a> {
     pub peer_id: u64
 }
 
+// Chain sub-system state for the node status
+#[derive(Serialize, Deserialize)]
+pub struct ChainStatus {
+    pub height: u64,
+    pub topoheight: TopoHeight,
+    pub stable_height: u64,
+    pub stable_topoheight: TopoHeight,
+    // Best topoheight known from our peers
+    // None if P2P is disabled
+    pub best_topoheight: Option<TopoHeight>,
+    // How many blocks we are behind the network
+    pub blocks_behind: u64,
+    // Is the P2P engine currently syncing the chain
+    pub syncing: bool,
+    // Are we within the configured blocks behind the network
+    pub synced: bool
+}
+
+// Storage sub-system state for the node status
+#[derive(Serialize, Deserialize)]
+pub struct StorageStatus {
+    pub writable: bool,
+    pub pruned_topoheight: Option<TopoHeight>
+}
+
+// P2P sub-system state for the node status
+#[derive(Serialize, Deserialize)]
+pub struct P2pNodeStatus {
+    pub enabled: bool,
+    pub peer_count: usize,
+    pub max_peers: usize,
+    pub connected: bool
+}
+
+// Mempool sub-system state for the node status
+#[derive(Serialize, Deserialize)]
+pub struct MempoolStatus {
+    pub size: usize
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetNodeStatusResult {
+    // Is the node ready to serve requests
+    // This is true only if all sub-systems are ready
+    pub ready: bool,
+    // Maximum blocks behind the network allowed to be considered as ready
+    pub max_blocks_behind: u64,
+    pub chain: ChainStatus,
+    pub storage: StorageStatus,
+    pub p2p: P2pNodeStatus,
+    pub mempool: MempoolStatus,
+    // software version on which the daemon is running
+    pub version: String,
+    pub network: Network
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetDagInfoParams {
+    // Number of heights / topoheights to scan
+    // If not set, default window is used
+    #[serde(default)]
+    pub window: Option<u64>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DagTip<'a> {
+    pub hash: Cow<'a, Hash>,
+    pub height: u64,
+    // Topoheight is only set if the tip is ordered
+    pub topoheight: Option<TopoHeight>,
+    pub difficulty: Difficulty,
+    pub cumulative_difficulty: CumulativeDifficulty
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DagReachabilityStats {
+    // Number of tips currently in the DAG
+    pub tips_count: usize,
+    // Height difference between the highest and the lowest tip
+    pub tips_height_spread: u64,
+    // Total blocks found in the window
+    pub blocks_count: u64,
+    // Highest count of blocks at the same height in the window
+    pub max_blocks_at_height: usize
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetDagInfoResult<'a> {
+    pub height: u64,
+    pub topoheight: TopoHeight,
+    pub stable_height: u64,
+    pub stable_topoheight: TopoHeight,
+    pub tips: Vec<DagTip<'a>>,
+    // Window used for the stats below
+    pub window: u64,
+    // Blocks not topologically ordered in the window
+    pub orphaned_count: u64,
+    // Average block time over the window
+    pub average_block_time: u64,
+    pub reachability: DagReachabilityStats
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct GetTopoHeightRangeParams {
     pub start_topoheight: Option<TopoHeight>,
     pub end_topoheight: Option<TopoHeight>
 }
 
+// Query of the `/blocks_range` HTTP endpoint
+#[derive(Serialize, Deserialize)]
+pub struct StreamBlocksRangeParams {
+    pub start_topoheight: Option<TopoHeight>,
+    pub end_topoheight: Option<TopoHeight>,
+    #[serde(default)]
+    pub include_txs: bool
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct GetHeightRangeParams {
     pub start_height: Option<u64>,
@@ -392,6 +677,10 @@ pub struct TransactionResponse<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub first_seen: Option<TimestampSeconds>,
+    // Receipts of the contract invocations executed by this TX
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub contract_receipts: Option<Vec<RPCContractReceipt<'a>>>,
     #[serde(flatten)]
     pub data: RPCTransaction<'a>
 }
@@ -434,6 +723,12 @@ pub enum AccountHistoryType {
     // Contract hash is already stored
     // by the parent struct
     DeployContract,
+    ManageContract {
+        contract: Hash,
+        action: ContractManagementAction,
+    },
+    DepositExecutionCredits { amount: u64 },
+    WithdrawExecutionCredits { amount: u64 },
 }
 
 #[derive(Serialize, Deserialize)]
@@ -447,7 +742,11 @@ pub struct AccountHistoryEntry {
 
 #[derive(Serialize, Deserialize)]
 pub struct GetAccountAssetsParams<'a> {
-    pub address: Cow<'a, Address>
+    pub address: Cow<'a, Address>,
+    // Last asset received, next assets are returned after it
+    #[serde(default)]
+    pub cursor: Option<Cow<'a, Hash>>,
+    pub maximum: Option<usize>
 }
 
 #[derive(Serialize, Deserialize)]
@@ -458,6 +757,10 @@ pub struct GetAssetParams<'a> {
 #[derive(Serialize, Deserialize)]
 pub struct GetAssetsParams {
     pub skip: Option<usize>,
+    // Last asset received, next assets are returned after it
+    // This is preferred over skip for large listings
+    #[serde(default)]
+    pub cursor: Option<Hash>,
     pub maximum: Option<usize>,
     pub minimum_topoheight: Option<TopoHeight>,
     pub maximum_topoheight: Option<TopoHeight>
@@ -466,6 +769,10 @@ pub struct GetAssetsParams {
 #[derive(Serialize, Deserialize)]
 pub struct GetAccountsParams {
     pub skip: Option<usize>,
+    // Last account received, next accounts are returned after it
+    // This is preferred over skip for large listings
+    #[serde(default)]
+    pub cursor: Option<Address>,
     pub maximum: Option<usize>,
     pub minimum_topoheight: Option<TopoHeight>,
     pub maximum_topoheight: Option<TopoHeight>
@@ -498,8 +805,29 @@ pub struct DevFeeThreshold {
     pub fee_percentage: u64
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct SimulateEmissionParams {
+    pub start_height: u64,
+    pub end_height: u64,
+    // Simulate only every N heights, default to 1
+    #[serde(default)]
+    pub step: Option<u64>
+}
+
+// Emission simulated at a height
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmissionAtHeight {
+    pub height: u64,
+    // Reward of the block, including the dev reward
+    pub block_reward: u64,
+    pub dev_reward: u64,
+    pub miner_reward: u64,
+    // Supply emitted including this block
+    pub supply: u64
+}
+
 // Struct to define hard fork
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct HardFork {
     // block height to start hard fork
     pub height: u64,
@@ -536,6 +864,59 @@ pub struct GetMempoolCacheResult {
     balances: HashMap<Hash, CiphertextCache>
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct GetTransactionBroadcastReceiptParams<'a> {
+    pub hash: Cow<'a, Hash>
+}
+
+// Propagation of a TX hash announced by the node to its peers
+#[derive(Serialize, Deserialize)]
+pub struct TransactionBroadcastReceipt {
+    pub first_announced_at: TimestampMillis,
+    pub last_announced_at: TimestampMillis,
+    // peers to which the TX hash was sent
+    pub announced_peers: usize,
+    // peers skipped as their mempool policy rejects the TX
+    pub skipped_by_policy: usize,
+    // peers skipped as they already know the TX
+    pub skipped_known: usize,
+    // peers skipped as they are too far from our chain
+    pub skipped_out_of_sync: usize,
+    // peers for which the announcement failed
+    pub send_errors: usize,
+    // distinct peers which requested the TX
+    pub requested_by_peers: usize,
+    pub first_requested_at: Option<TimestampMillis>,
+    pub in_mempool: bool
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetAccountPendingParams<'a> {
+    pub address: Cow<'a, Address>
+}
+
+// A pending TX of an account
+#[derive(Serialize, Deserialize)]
+pub struct AccountPendingTx<'a> {
+    pub hash: Cow<'a, Hash>,
+    pub nonce: Nonce,
+    pub fee: u64,
+    pub size: usize,
+    pub first_seen: TimestampSeconds,
+    // Balances expected once this TX and the previous ones are executed
+    pub balances: HashMap<Hash, CompressedCiphertext>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetAccountPendingResult<'a> {
+    // Nonce to use for the next TX of the account
+    pub next_nonce: Nonce,
+    // Pending TXs ordered by nonce
+    pub txs: Vec<AccountPendingTx<'a>>,
+    // Balances expected once all the pending TXs are executed
+    pub balances: HashMap<Hash, CompressedCiphertext>
+}
+
 // This struct is used to store the fee rate estimation for the following priority levels:
 // 1. Low
 // 2. Medium
@@ -624,7 +1005,11 @@ pub struct GetMultisigAtTopoHeightResult {
 
 #[derive(Serialize, Deserialize)]
 pub struct GetMultisigParams<'a> {
-    pub address: Cow<'a, Address>
+    pub address: Cow<'a, Address>,
+    // Get the multisig state at this topoheight
+    // If not set, the last version is returned
+    #[serde(default)]
+    pub topoheight: Option<TopoHeight>
 }
 
 #[derive(Serialize, Deserialize)]
@@ -635,6 +1020,69 @@ pub struct GetMultisigResult {
     pub topoheight: TopoHeight
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct GetStateAtTopoHeightParams<'a> {
+    pub address: Cow<'a, Address>,
+    pub topoheight: TopoHeight,
+    // Assets to resolve the balances, only XELIS if empty
+    #[serde(default)]
+    pub assets: IndexSet<Hash>
+}
+
+// State of an account resolved at a topoheight
+// Each field is the last version at or below the requested topoheight
+// and is None if the account had no version of it yet
+#[derive(Serialize, Deserialize)]
+pub struct GetStateAtTopoHeightResult {
+    pub topoheight: TopoHeight,
+    pub nonce: Option<GetNonceResult>,
+    pub balances: HashMap<Hash, GetBalanceResult>,
+    pub multisig: Option<GetMultisigResult>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetBalancesRootAtTopoHeightParams {
+    pub topoheight: TopoHeight
+}
+
+// Merkle root of all the balances versions written at a topoheight
+#[derive(Serialize, Deserialize)]
+pub struct GetBalancesRootAtTopoHeightResult {
+    pub topoheight: TopoHeight,
+    pub block_hash: Hash,
+    pub root: Hash,
+    pub leaves: usize
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetBalanceProofParams<'a> {
+    pub address: Cow<'a, Address>,
+    pub asset: Cow<'a, Hash>,
+    // Use the current topoheight if not set
+    #[serde(default)]
+    pub topoheight: Option<TopoHeight>
+}
+
+// Last balance version at or below the requested topoheight
+// with its inclusion proof in the balances root of its topoheight
+#[derive(Serialize, Deserialize)]
+pub struct GetBalanceProofResult<'a> {
+    pub topoheight: TopoHeight,
+    pub block_hash: Cow<'a, Hash>,
+    pub version: VersionedBalance,
+    pub proof: MerkleProof,
+    pub root: Hash
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BlockHeaderEntry<'a> {
+    pub topoheight: TopoHeight,
+    pub hash: Cow<'a, Hash>,
+    pub difficulty: Difficulty,
+    // Hex encoded header
+    pub header: String
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct HasMultisigParams<'a> {
     pub address: Cow<'a, Address>
@@ -657,6 +1105,29 @@ pub struct GetContractModuleParams<'a> {
     pub contract: Cow<'a, Hash>
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct GetContractManagementResult {
+    // Account allowed to pause or unpause the contract
+    pub owner: Address,
+    // Are the invocations rejected
+    pub paused: bool,
+    // Topoheight of the last change
+    pub topoheight: TopoHeight
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetExecutionCreditsParams<'a> {
+    pub address: Cow<'a, Address>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetExecutionCreditsResult {
+    // XELIS locked to pay the gas of the contract invocations
+    pub credits: u64,
+    // Topoheight of the last change, None if the account never deposited
+    pub topoheight: Option<TopoHeight>
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct GetContractDataParams<'a> {
     pub contract: Cow<'a, Hash>,
@@ -683,6 +1154,183 @@ pub struct GetContractBalanceAtTopoHeightParams<'a> {
     pub topoheight: TopoHeight
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct SimulateContractInvokeParams {
+    // Transaction in hex format
+    // It must be an InvokeContract transaction
+    pub data: String
+}
+
+// A contract storage entry changed by the simulated invocation
+#[derive(Serialize, Deserialize)]
+pub struct ContractStorageChange {
+    pub key: Constant,
+    // Value before the invocation, None if it was not set
+    pub previous: Option<Constant>,
+    // Value after the invocation, None if it got deleted
+    pub new: Option<Constant>
+}
+
+// A contract balance changed by the simulated invocation
+#[derive(Serialize, Deserialize)]
+pub struct ContractBalanceChange {
+    pub asset: Hash,
+    // Balance before the invocation
+    pub previous: u64,
+    // Balance after the invocation
+    pub new: u64
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SimulateContractInvokeResult<'a> {
+    pub contract: Cow<'a, Hash>,
+    pub chunk_id: u16,
+    // Topoheight at which the invocation was simulated
+    pub topoheight: TopoHeight,
+    // Exit code returned by the contract
+    // None if an error occurred
+    pub exit_code: Option<u64>,
+    // Gas used by the invocation
+    pub gas_used: u64,
+    // Part of the used gas that is burned
+    pub burned_gas: u64,
+    // Part of the used gas given to the miners
+    pub gas_fee: u64,
+    // Gas refunded to the caller
+    pub refunded_gas: u64,
+    // All the outputs (transfers, refunds, exit code) produced
+    pub outputs: Vec<RPCContractOutput<'a>>,
+    // Contract storage diff, empty if the invocation failed
+    pub storage_changes: Vec<ContractStorageChange>,
+    // Contract balances diff, empty if the invocation failed
+    pub balance_changes: Vec<ContractBalanceChange>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ValidateContractParams {
+    // Contract module in hex format
+    pub module: String
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ValidateContractResult {
+    // Is the module accepted for deployment
+    pub valid: bool,
+    // Size in bytes of the serialized module
+    pub size: usize,
+    // Reason of the rejection, None if valid
+    pub error: Option<String>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ModuleLogLevel<'a> {
+    pub module: Cow<'a, str>,
+    pub level: Cow<'a, str>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetLogLevelsResult<'a> {
+    // Level of the logs written to stdout
+    pub level: Cow<'a, str>,
+    // Level of the logs written to the file
+    pub file_level: Cow<'a, str>,
+    // Levels overriding the ones above for a module and its submodules
+    pub modules: Vec<ModuleLogLevel<'a>>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SetLogLevelParams<'a> {
+    // Module to configure, the default levels are set if not provided
+    #[serde(default)]
+    pub module: Option<Cow<'a, str>>,
+    // Level to set, the module level is removed if not provided
+    #[serde(default)]
+    pub level: Option<Cow<'a, str>>
+}
+
+// Node parameters that can be updated at runtime
+// When updating them, only the provided parameters are changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuntimeParams {
+    // Level of both stdout and file logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<String>,
+    // Maximum count of txs in mempool, 0 for no limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_mempool_txs: Option<usize>,
+    // Maximum count of connected peers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_peers: Option<usize>,
+    // Size of each storage cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_size: Option<usize>
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LockAccess {
+    Read,
+    Write
+}
+
+// Current holder of an instrumented lock
+#[derive(Serialize, Deserialize)]
+pub struct LockHolder<'a> {
+    pub access: LockAccess,
+    // Source location where the lock was acquired
+    pub location: Cow<'a, str>,
+    // Time since the lock is held in milliseconds
+    pub held_for_ms: u64
+}
+
+// Contention stats of an instrumented lock since the start
+// Wait times are in microseconds
+#[derive(Serialize, Deserialize)]
+pub struct LockStats<'a> {
+    pub name: Cow<'a, str>,
+    pub read_acquisitions: u64,
+    pub write_acquisitions: u64,
+    pub read_wait_total_us: u64,
+    pub read_wait_max_us: u64,
+    pub write_wait_total_us: u64,
+    pub write_wait_max_us: u64,
+    // Tasks currently waiting to acquire the lock
+    pub waiting: usize,
+    pub holders: Vec<LockHolder<'a>>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RuntimeWorkerStats {
+    // Total time spent busy in milliseconds
+    pub busy_duration_ms: u64,
+    // Ratio of time spent busy since the previous sample
+    pub utilization: f64,
+    // Number of times the worker parked
+    pub park_count: u64,
+    // Only available when built with tokio_unstable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_queue_depth: Option<usize>
+}
+
+// Stats of the tokio runtime
+#[derive(Serialize, Deserialize)]
+pub struct RuntimeStats {
+    pub num_workers: usize,
+    pub alive_tasks: usize,
+    // Tasks pending in the global queue
+    pub global_queue_depth: usize,
+    // Only available when built with tokio_unstable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocking_queue_depth: Option<usize>,
+    pub workers: Vec<RuntimeWorkerStats>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetRuntimeStatsResult<'a> {
+    pub runtime: RuntimeStats,
+    pub locks: Vec<LockStats<'a>>
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct RPCVersioned<T> {
     pub topoheight: TopoHeight,
@@ -743,6 +1391,34 @@ pub enum NotifyEvent {
     // and that he notified us
     // It contains PeerPeerDisconnectedEvent as value
     PeerPeerDisconnected,
+    // When a transaction is added, replaced or removed from the mempool
+    // If set, only the transactions sent by the source
+    // and/or to the destination are notified
+    // It contains MempoolTransactionEvent as value
+    MempoolTransaction {
+        #[serde(default)]
+        source: Option<Address>,
+        #[serde(default)]
+        destination: Option<Address>
+    },
+    // When a miner has connected to the GetWork server
+    // It contains GetWorkMinerConnectedEvent as value
+    GetWorkMinerConnected,
+    // When a miner has disconnected from the GetWork server
+    // It contains GetWorkMinerDisconnectedEvent as value
+    GetWorkMinerDisconnected,
+    // When the multisig of an account is created, rotated or reset
+    // If set, only the changes of this account are notified
+    // It contains MultisigChangedEvent as value
+    MultisigChanged {
+        #[serde(default)]
+        address: Option<Address>
+    },
+    // When the nonce or a balance of the account has changed at a new topoheight
+    // It contains AccountActivityEvent as value
+    AccountActivity {
+        address: Address
+    },
 }
 
 // Value of NotifyEvent::NewBlock
@@ -794,6 +1470,71 @@ pub struct TransactionExecutedEvent<'a> {
     pub topoheight: TopoHeight,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolTransactionStatus {
+    // Admitted in mempool
+    Added,
+    // Replaced by a TX with the same nonce and a higher fee
+    Replaced,
+    // Included in a block or not valid anymore
+    Removed
+}
+
+// Value of NotifyEvent::MempoolTransaction
+#[derive(Serialize, Deserialize)]
+pub struct MempoolTransactionEvent<'a> {
+    pub tx_hash: Cow<'a, Hash>,
+    pub source: Address,
+    pub fee: u64,
+    // fee paid per kB of the TX
+    pub fee_per_kb: u64,
+    pub size: usize,
+    pub status: MempoolTransactionStatus
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MultisigChangeKind {
+    // Account had no multisig before
+    Created,
+    // Participants and/or threshold of the existing multisig were updated
+    Rotated,
+    // Multisig has been deleted
+    Reset
+}
+
+// Value of NotifyEvent::MultisigChanged
+#[derive(Serialize, Deserialize)]
+pub struct MultisigChangedEvent<'a> {
+    pub block_hash: Cow<'a, Hash>,
+    pub tx_hash: Cow<'a, Hash>,
+    pub topoheight: TopoHeight,
+    pub address: Address,
+    pub kind: MultisigChangeKind,
+    // New state of the multisig
+    pub state: MultisigState
+}
+
+// Value of NotifyEvent::AccountActivity
+#[derive(Serialize, Deserialize)]
+pub struct AccountActivityEvent<'a> {
+    pub block_hash: Cow<'a, Hash>,
+    pub topoheight: TopoHeight,
+    pub address: Cow<'a, Address>,
+    // New nonce if it has changed at this topoheight
+    pub nonce: Option<VersionedNonce>,
+    // Balances versions updated at this topoheight
+    // Ciphertexts are included so the wallet can decrypt them directly
+    pub balances: HashMap<Hash, VersionedBalance>
+}
+
+// Value of NotifyEvent::GetWorkMinerConnected
+pub type GetWorkMinerConnectedEvent = GetWorkMinerEntry<'static>;
+
+// Value of NotifyEvent::GetWorkMinerDisconnected
+pub type GetWorkMinerDisconnectedEvent = GetWorkMinerEntry<'static>;
+
 // Value of NotifyEvent::PeerConnected
 pub type PeerConnectedEvent = PeerEntry<'static>;
 
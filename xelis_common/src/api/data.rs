@@ -8,6 +8,13 @@ use crate::{
     crypto::Hash
 };
 
+// Payment ID used by services to attribute deposits
+// It is integrated in an address and sent in the transfer extra data
+pub type PaymentId = u64;
+
+// Key of the payment ID in the integrated data
+pub const PAYMENT_ID_KEY: &str = "payment_id";
+
 #[derive(Debug, Error)]
 pub enum DataConversionError {
     #[error("Expected a value")]
@@ -137,6 +144,21 @@ impl DataElement {
         self.get_value_by_key(&DataValue::String(name), Some(value_type))
     }
 
+    // Build the standardized data for a payment ID
+    pub fn from_payment_id(payment_id: PaymentId) -> Self {
+        let mut fields = HashMap::new();
+        fields.insert(DataValue::String(PAYMENT_ID_KEY.to_owned()), Self::Value(DataValue::U64(payment_id)));
+        Self::Fields(fields)
+    }
+
+    // Retrieve the standardized payment ID if present
+    pub fn get_payment_id(&self) -> Option<PaymentId> {
+        match self.get_value_by_string_key(PAYMENT_ID_KEY.to_owned(), ValueType::U64)? {
+            DataValue::U64(payment_id) => Some(*payment_id),
+            _ => None
+        }
+    }
+
     pub fn kind(&self) -> ElementType {
         match self {
             Self::Array(_) => ElementType::Array,
@@ -437,7 +459,7 @@ impl DataValue {
             ValueType::U64 => Self::U64(reader.read_u64()?),
             ValueType::U128 => Self::U128(reader.read_u128()?),
             ValueType::Hash => Self::Hash(reader.read_hash()?),
-            ValueType::Blob => Self::Blob(Vec::read(reader)?)
+            ValueType::Blob => Self::Blob(reader.read_owned::<&[u8]>()?)
         })
     }
 
@@ -630,6 +652,17 @@ impl From<Vec<&str>> for DataElement {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_payment_id() {
+        let data = DataElement::from_payment_id(1337);
+        assert_eq!(data.get_payment_id(), Some(1337));
+
+        let data = DataElement::from_bytes(&data.to_bytes()).unwrap();
+        assert_eq!(data.get_payment_id(), Some(1337));
+
+        assert_eq!(DataElement::Value(DataValue::U64(1337)).get_payment_id(), None);
+    }
+
     #[test]
     fn test_from_into() {
         let value = DataValue::U8(10);
@@ -2,12 +2,12 @@ mod data;
 pub mod wallet;
 pub mod daemon;
 pub mod query;
+pub mod stratum;
 
 use std::borrow::Cow;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use bulletproofs::RangeProof;
-use xelis_vm::Module;
 use crate::{
     account::Nonce,
     crypto::{
@@ -18,12 +18,15 @@ use crate::{
         Signature
     },
     serializer::Serializer,
-    contract::ContractOutput,
+    contract::{ContractEvent, ContractOutput, ContractReceipt},
     transaction::{
         extra_data::UnknownExtraDataFormat,
         multisig::MultiSig,
         BurnPayload,
+        DeployContractPayload,
+        ExecutionCreditsPayload,
         InvokeContractPayload,
+        ManageContractPayload,
         MultiSigPayload,
         Reference,
         SourceCommitment,
@@ -86,7 +89,9 @@ pub enum RPCTransactionType<'a> {
     Burn(Cow<'a, BurnPayload>),
     MultiSig(Cow<'a, MultiSigPayload>),
     InvokeContract(Cow<'a, InvokeContractPayload>),
-    DeployContract(Cow<'a, Module>)
+    DeployContract(Cow<'a, DeployContractPayload>),
+    ManageContract(Cow<'a, ManageContractPayload>),
+    ExecutionCredits(Cow<'a, ExecutionCreditsPayload>)
 }
 
 impl<'a> RPCTransactionType<'a> {
@@ -110,7 +115,9 @@ impl<'a> RPCTransactionType<'a> {
             TransactionType::Burn(burn) => Self::Burn(Cow::Borrowed(burn)),
             TransactionType::MultiSig(payload) => Self::MultiSig(Cow::Borrowed(payload)),
             TransactionType::InvokeContract(payload) => Self::InvokeContract(Cow::Borrowed(payload)),
-            TransactionType::DeployContract(module) => Self::DeployContract(Cow::Borrowed(module))
+            TransactionType::DeployContract(payload) => Self::DeployContract(Cow::Borrowed(payload)),
+            TransactionType::ManageContract(payload) => Self::ManageContract(Cow::Borrowed(payload)),
+            TransactionType::ExecutionCredits(payload) => Self::ExecutionCredits(Cow::Borrowed(payload))
         }
     }
 }
@@ -124,7 +131,9 @@ impl From<RPCTransactionType<'_>> for TransactionType {
             RPCTransactionType::Burn(burn) => TransactionType::Burn(burn.into_owned()),
             RPCTransactionType::MultiSig(payload) => TransactionType::MultiSig(payload.into_owned()),
             RPCTransactionType::InvokeContract(payload) => TransactionType::InvokeContract(payload.into_owned()),
-            RPCTransactionType::DeployContract(module) => TransactionType::DeployContract(module.into_owned())
+            RPCTransactionType::DeployContract(payload) => TransactionType::DeployContract(payload.into_owned()),
+            RPCTransactionType::ManageContract(payload) => TransactionType::ManageContract(payload.into_owned()),
+            RPCTransactionType::ExecutionCredits(payload) => TransactionType::ExecutionCredits(payload.into_owned())
         }
     }
 }
@@ -158,11 +167,18 @@ pub struct RPCTransaction<'a> {
     /// Signature of the transaction
     pub signature: Cow<'a, Signature>,
     /// TX size in bytes
-    pub size: usize
+    pub size: usize,
+    /// Fees paid per kB
+    #[serde(default)]
+    pub fee_rate: u64,
+    /// Count of transfers, 0 if its not a transfers TX
+    #[serde(default)]
+    pub transfers_count: usize
 }
 
 impl<'a> RPCTransaction<'a> {
     pub fn from_tx(tx: &'a Transaction, hash: &'a Hash, mainnet: bool) -> Self {
+        let size = tx.size();
         Self {
             hash: Cow::Borrowed(hash),
             version: tx.get_version(),
@@ -175,7 +191,9 @@ impl<'a> RPCTransaction<'a> {
             reference: Cow::Borrowed(tx.get_reference()),
             multisig: Cow::Borrowed(tx.get_multisig()),
             signature: Cow::Borrowed(tx.get_signature()),
-            size: tx.size()
+            size,
+            fee_rate: tx.get_fee_rate_with_size(size),
+            transfers_count: tx.get_transfers_count()
         }
     }
 }
@@ -229,7 +247,10 @@ pub enum RPCContractOutput<'a> {
         destination: Cow<'a, Address>
     },
     ExitCode(Option<u64>),
-    RefundDeposits
+    RefundDeposits,
+    ExecutionCredits {
+        amount: u64
+    }
 }
 
 impl<'a> RPCContractOutput<'a> {
@@ -243,6 +264,7 @@ impl<'a> RPCContractOutput<'a> {
             },
             ContractOutput::ExitCode(code) => RPCContractOutput::ExitCode(code),
             ContractOutput::RefundDeposits => RPCContractOutput::RefundDeposits,
+            ContractOutput::ExecutionCredits { amount } => RPCContractOutput::ExecutionCredits { amount },
         }
     }
 }
@@ -258,6 +280,49 @@ impl<'a> From<RPCContractOutput<'a>> for ContractOutput {
             },
             RPCContractOutput::ExitCode(code) => ContractOutput::ExitCode(code),
             RPCContractOutput::RefundDeposits => ContractOutput::RefundDeposits,
+            RPCContractOutput::ExecutionCredits { amount } => ContractOutput::ExecutionCredits { amount },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RPCTransferOutput<'a> {
+    pub destination: Cow<'a, Address>,
+    pub amount: u64,
+    pub asset: Cow<'a, Hash>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RPCContractReceipt<'a> {
+    pub contract: Cow<'a, Hash>,
+    pub chunk_id: u16,
+    pub max_gas: u64,
+    pub gas_used: u64,
+    pub exit_code: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub error: Option<String>,
+    pub events: Vec<ContractEvent>,
+    pub transfers: Vec<RPCTransferOutput<'a>>
+}
+
+impl<'a> RPCContractReceipt<'a> {
+    pub fn from_receipt(receipt: ContractReceipt, mainnet: bool) -> Self {
+        Self {
+            contract: Cow::Owned(receipt.contract),
+            chunk_id: receipt.chunk_id,
+            max_gas: receipt.max_gas,
+            gas_used: receipt.gas_used,
+            exit_code: receipt.exit_code,
+            error: receipt.error,
+            events: receipt.events,
+            transfers: receipt.transfers.into_iter()
+                .map(|transfer| RPCTransferOutput {
+                    destination: Cow::Owned(transfer.destination.to_address(mainnet)),
+                    amount: transfer.amount,
+                    asset: Cow::Owned(transfer.asset)
+                })
+                .collect()
         }
     }
 }
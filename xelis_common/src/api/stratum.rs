@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::{
+    block::{Algorithm, EXTRA_NONCE_SIZE},
+    difficulty::Difficulty
+};
+
+// Stratum v1 protocol used between the miner and a daemon or pool
+//
+// Messages are JSON objects separated by a new line over a TCP connection.
+// The miner subscribes, authorizes itself using its address as username,
+// then receives the jobs to mine and submits its shares.
+//
+// The extra nonce of the block work is split in two parts:
+// - extranonce1: assigned by the server to each connection, so no miner works on the same range
+// - extranonce2: the remaining bytes, filled by the miner as it wants
+// The job sent already contains the extranonce1 and the miner public key.
+
+// Prefix of the addresses using the Stratum protocol
+pub const STRATUM_PREFIX: &str = "stratum+tcp://";
+
+// Size of the extranonce1 assigned by the server
+pub const STRATUM_EXTRANONCE1_SIZE: usize = 16;
+// Size of the extranonce2 left to the miner
+pub const STRATUM_EXTRANONCE2_SIZE: usize = EXTRA_NONCE_SIZE - STRATUM_EXTRANONCE1_SIZE;
+
+// Maximum size of a line received, a submit is far below it
+pub const STRATUM_MAX_LINE_SIZE: usize = 4096;
+
+pub const METHOD_SUBSCRIBE: &str = "mining.subscribe";
+pub const METHOD_AUTHORIZE: &str = "mining.authorize";
+pub const METHOD_SUBMIT: &str = "mining.submit";
+pub const METHOD_SUGGEST_DIFFICULTY: &str = "mining.suggest_difficulty";
+pub const METHOD_SET_DIFFICULTY: &str = "mining.set_difficulty";
+pub const METHOD_NOTIFY: &str = "mining.notify";
+
+// Request or notification, a notification has no id
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StratumRequest {
+    pub id: Option<u64>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StratumResponse {
+    pub id: Option<u64>,
+    pub result: Value,
+    pub error: Option<StratumError>
+}
+
+// Error as [code, message, data]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StratumError(pub i64, pub String, pub Value);
+
+impl StratumError {
+    pub const UNKNOWN: i64 = 20;
+    pub const JOB_NOT_FOUND: i64 = 21;
+    pub const DUPLICATE_SHARE: i64 = 22;
+    pub const LOW_DIFFICULTY_SHARE: i64 = 23;
+    pub const UNAUTHORIZED: i64 = 24;
+    pub const NOT_SUBSCRIBED: i64 = 25;
+
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self(code, message.into(), Value::Null)
+    }
+}
+
+// Any message received, responses have a result or an error
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum StratumMessage {
+    Request(StratumRequest),
+    Response(StratumResponse)
+}
+
+// mining.subscribe result: [subscriptions, extranonce1, extranonce2 size]
+#[derive(Serialize, Deserialize)]
+pub struct SubscribeResult(pub Vec<(String, String)>, pub String, pub usize);
+
+// mining.authorize params: [username, password]
+// Username is the miner address, optionally followed by ".<worker>"
+#[derive(Serialize, Deserialize)]
+pub struct AuthorizeParams(pub String, #[serde(default)] pub Option<String>);
+
+// mining.set_difficulty params: [share difficulty]
+// Also used by mining.suggest_difficulty
+#[derive(Serialize, Deserialize)]
+pub struct SetDifficultyParams(pub Difficulty);
+
+// mining.notify params: [job id, miner work, algorithm, height, topoheight, block difficulty, clean jobs]
+#[derive(Serialize, Deserialize)]
+pub struct NotifyParams(pub String, pub String, pub Algorithm, pub u64, pub u64, pub Difficulty, pub bool);
+
+// mining.submit params: [username, job id, extranonce2, timestamp, nonce]
+#[derive(Serialize, Deserialize)]
+pub struct SubmitParams(pub String, pub String, pub String, pub u64, pub u64);
+
+// Split the username into the address and the worker name
+pub fn split_username(username: &str) -> (&str, &str) {
+    match username.split_once('.') {
+        Some((address, worker)) => (address, worker),
+        None => (username, "default")
+    }
+}
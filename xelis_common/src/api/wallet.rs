@@ -1,15 +1,26 @@
 use std::{borrow::Cow, collections::HashMap};
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use serde::{Deserialize, Serialize};
 use crate::{
     account::CiphertextCache,
     block::TopoHeight,
-    crypto::{elgamal::CompressedCiphertext, Address, Hash, PrivateKey},
-    serializer::Hexable,
+    config::XELIS_ASSET,
+    crypto::{
+        elgamal::{AdaptorPoint, AdaptorSecret, AdaptorSignature, CompressedCiphertext},
+        Address,
+        Hash,
+        PrivateKey,
+        Signature
+    },
+    payment_uri::PaymentRequest,
+    serializer::{Hexable, Reader, ReaderError, Serializer, Writer},
+    time::TimestampMillis,
     transaction::{
         builder::{FeeBuilder, TransactionTypeBuilder, UnsignedTransaction},
         extra_data::{PlaintextExtraData, UnknownExtraDataFormat},
         multisig::SignatureId,
+        ContractManagementAction,
+        ExecutionCreditsPayload,
         Reference,
         Role,
         Transaction,
@@ -20,6 +31,7 @@ use super::{
     DataHash,
     DataElement,
     DataValue,
+    PaymentId,
     query::Query,
     default_false_value,
     default_true_value,
@@ -58,6 +70,71 @@ pub struct BuildTransactionParams {
     pub signers: Vec<SignerId>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct BumpTransactionFeeParams<'a> {
+    // Hash of the pending transaction to replace
+    pub hash: Cow<'a, Hash>,
+    // Fee to use for the replacement
+    // If not present, the minimum fee accepted by the daemon is used
+    pub fee: Option<FeeBuilder>,
+    // Returns the TX in HEX format also
+    #[serde(default = "default_false_value")]
+    pub tx_as_hex: bool
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreatePaymentProofParams<'a> {
+    // Hash of the transaction sent by the wallet
+    pub hash: Cow<'a, Hash>,
+    // Index of the transfer in the transaction
+    pub index: u8
+}
+
+pub type VerifyPaymentProofParams = daemon::VerifyPaymentProofParams;
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateReserveProofParams<'a> {
+    pub asset: Cow<'a, Hash>,
+    // Minimum amount to prove
+    pub amount: u64,
+    // Challenge given by the verifier in hex format
+    pub challenge: String
+}
+
+pub type VerifyReserveProofParams<'a> = daemon::VerifyReserveProofParams<'a>;
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateAuditProofParams<'a> {
+    // Auditor allowed to decrypt the balance
+    pub auditor: Cow<'a, Address>,
+    pub asset: Cow<'a, Hash>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateTransferAuditProofParams<'a> {
+    // Auditor allowed to decrypt the transfer amount
+    pub auditor: Cow<'a, Address>,
+    // Hash of the transaction sent or received by the wallet
+    pub hash: Cow<'a, Hash>,
+    // Index of the transfer in the transaction
+    pub index: u8
+}
+
+pub type VerifyAuditProofParams<'a> = daemon::VerifyAuditProofParams<'a>;
+
+// Amount decrypted by the auditor of a valid audit proof
+#[derive(Serialize, Deserialize)]
+pub struct AuditProofResult<'a> {
+    pub address: Cow<'a, Address>,
+    pub asset: Cow<'a, Hash>,
+    // Topoheight of the balance version, set for a balance audit
+    pub topoheight: Option<TopoHeight>,
+    // Transfer audited, set for a transfer audit
+    pub transaction: Option<Cow<'a, Hash>>,
+    pub index: Option<u8>,
+    pub amount: u64
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct BuildTransactionOfflineParams {
     #[serde(flatten)]
@@ -85,6 +162,51 @@ pub struct BuildTransactionOfflineParams {
     pub signers: Vec<SignerId>,
 }
 
+// Transaction prepared by an online wallet
+// It contains everything required by an offline wallet
+// to generate the proofs and sign the transaction
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PreparedTransaction {
+    // Account that will send the transaction
+    pub source: Address,
+    #[serde(flatten)]
+    pub tx_type: TransactionTypeBuilder,
+    // Fee is computed by the online wallet
+    pub fee: u64,
+    // Version to use for the TX
+    pub tx_version: TxVersion,
+    // Encrypted balances used by the transaction
+    pub balances: HashMap<Hash, CiphertextCache>,
+    // Reference to use for the transaction
+    pub reference: Reference,
+    // Nonce to use for the transaction
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PrepareTransactionParams {
+    #[serde(flatten)]
+    pub tx_type: TransactionTypeBuilder,
+    // Fee to use, if value is fixed,
+    // it will be used as is, otherwise it will be calculated
+    pub fee: Option<FeeBuilder>,
+    // Nonce to use for the transaction
+    // If not present, it will be generated by the wallet
+    pub nonce: Option<u64>,
+    // Version to use for the TX
+    // By default, grab the version from wallet
+    pub tx_version: Option<TxVersion>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SignPreparedTransactionParams {
+    // Transaction prepared by the online wallet
+    pub prepared: PreparedTransaction,
+    // Returns the TX in HEX format also
+    #[serde(default = "default_false_value")]
+    pub tx_as_hex: bool,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct BuildUnsignedTransactionParams {
     #[serde(flatten)]
@@ -127,6 +249,52 @@ pub struct SignUnsignedTransactionParams {
     pub signer_id: u8
 }
 
+// Create a multisig transaction kept by the wallet
+// until enough participants have signed it
+pub type CreateMultiSigTransactionParams = BuildUnsignedTransactionParams;
+
+#[derive(Serialize, Deserialize)]
+pub struct MultiSigTransactionParams {
+    // Hash to sign by each participant
+    pub hash: Hash
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AddMultiSigSignatureParams {
+    // Hash to sign by each participant
+    pub hash: Hash,
+    // Signature of the participant
+    #[serde(flatten)]
+    pub signature: SignatureId
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FinalizeMultiSigTransactionParams {
+    // Hash to sign by each participant
+    pub hash: Hash,
+    // Cannot be broadcasted if set to false
+    #[serde(default = "default_true_value")]
+    pub broadcast: bool,
+    // Returns the TX in HEX format also
+    #[serde(default = "default_false_value")]
+    pub tx_as_hex: bool,
+}
+
+// Signing progress of a pending multisig transaction
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MultiSigSigningStatus {
+    // Hash to sign by each participant
+    pub hash: Hash,
+    // Signatures required to finalize the transaction
+    pub threshold: u8,
+    // Participant IDs that have already signed
+    pub signers: Vec<u8>,
+    // Participant IDs that haven't signed yet
+    pub missing: Vec<u8>,
+    // Threshold has been reached
+    pub ready: bool
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct UnsignedTransactionResponse {
     #[serde(flatten)]
@@ -164,7 +332,11 @@ pub struct ListTransactionsParams {
     #[serde(default = "default_true_value")]
     pub accept_burn: bool,
     // Filter by extra data
-    pub query: Option<Query>
+    pub query: Option<Query>,
+    // Filter by local tag
+    pub tag: Option<String>,
+    // Filter by payment ID
+    pub payment_id: Option<PaymentId>
 }
 
 #[derive(Serialize, Deserialize)]
@@ -184,7 +356,10 @@ pub struct GetAssetPrecisionParams<'a> {
 pub struct GetAddressParams {
     // Data to use for creating an integrated address
     // Returned address will contains all the data provided here
-    pub integrated_data: Option<DataElement>
+    pub integrated_data: Option<DataElement>,
+    // Payment ID to integrate in the address
+    // It can't be used with integrated_data
+    pub payment_id: Option<PaymentId>
 }
 
 #[derive(Serialize, Deserialize)]
@@ -194,6 +369,235 @@ pub struct RescanParams {
     pub auto_reconnect: bool
 }
 
+// Progress of a rescan in progress
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RescanProgress {
+    // Topoheight until which the history is rescanned
+    pub start_topoheight: TopoHeight,
+    // Asset being rescanned
+    pub asset: Option<Hash>,
+    // Last topoheight processed for this asset
+    // History is scanned from the highest topoheight to the start one
+    pub topoheight: Option<TopoHeight>,
+    // Assets fully rescanned
+    pub completed_assets: usize,
+    // Assets to rescan
+    pub total_assets: usize,
+    // Is the rescan paused
+    pub paused: bool
+}
+
+// Scheduled payment sent by the wallet
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScheduledPaymentExecuted {
+    pub name: String,
+    // Transaction broadcasted
+    pub hash: Hash,
+    // Next time the payment is due, none if it was the last one
+    pub next_execution: Option<TimestampMillis>
+}
+
+// Scheduled payment that couldn't be sent
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScheduledPaymentFailed {
+    pub name: String,
+    pub error: String,
+    // Consecutive failures for this payment
+    pub failures: u32,
+    // Next time the payment will be retried
+    pub next_retry: TimestampMillis
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SetScheduledPaymentParams {
+    pub name: String,
+    pub destination: Address,
+    // XELIS if not set
+    pub asset: Option<Hash>,
+    // Amount in atomic units
+    pub amount: u64,
+    // Interval between two payments in seconds
+    pub interval: u64,
+    // First execution, now if not set
+    pub start: Option<TimestampMillis>,
+    // Number of payments to send, unlimited if not set
+    pub count: Option<u64>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ScheduledPaymentParams {
+    pub name: String
+}
+
+// Permissions granted by the user to a XSWD application
+// They are persisted by the wallet and reused at each connection of the application
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct XSWDApplicationScope {
+    // Methods allowed without asking the user
+    #[serde(default)]
+    pub methods: IndexSet<String>,
+    // Assets the application can access, all if empty
+    #[serde(default)]
+    pub assets: IndexSet<Hash>,
+    // Maximum amount per asset the application can transfer per day without asking the user
+    #[serde(default)]
+    pub daily_limits: IndexMap<Hash, u64>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct XSWDApplicationParams<'a> {
+    pub id: Cow<'a, str>
+}
+
+// Assets tracked by the wallet during the sync
+// Ignored assets are not decrypted and hidden from balances and history
+// XELIS asset is always tracked as it is used to pay the fees
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "mode", content = "assets")]
+pub enum AssetFilter {
+    // Track every asset
+    #[default]
+    All,
+    // Track only these assets
+    Allow(IndexSet<Hash>),
+    // Track every asset except these ones
+    Deny(IndexSet<Hash>)
+}
+
+impl AssetFilter {
+    // Check if the asset must be tracked by the wallet
+    pub fn is_tracked(&self, asset: &Hash) -> bool {
+        if *asset == XELIS_ASSET {
+            return true
+        }
+
+        match self {
+            Self::All => true,
+            Self::Allow(assets) => assets.contains(asset),
+            Self::Deny(assets) => !assets.contains(asset)
+        }
+    }
+
+    // Start tracking an asset
+    pub fn track(&mut self, asset: Hash) {
+        match self {
+            Self::All => {},
+            Self::Allow(assets) => {
+                assets.insert(asset);
+            },
+            Self::Deny(assets) => {
+                assets.shift_remove(&asset);
+                if assets.is_empty() {
+                    *self = Self::All;
+                }
+            }
+        }
+    }
+
+    // Stop tracking an asset
+    pub fn ignore(&mut self, asset: Hash) {
+        match self {
+            Self::All => {
+                *self = Self::Deny(IndexSet::from([asset]));
+            },
+            Self::Allow(assets) => {
+                assets.shift_remove(&asset);
+            },
+            Self::Deny(assets) => {
+                assets.insert(asset);
+            }
+        }
+    }
+}
+
+impl Serializer for AssetFilter {
+    fn write(&self, writer: &mut Writer) {
+        match self {
+            Self::All => writer.write_u8(0),
+            Self::Allow(assets) => {
+                writer.write_u8(1);
+                assets.write(writer);
+            },
+            Self::Deny(assets) => {
+                writer.write_u8(2);
+                assets.write(writer);
+            }
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        Ok(match reader.read_u8()? {
+            0 => Self::All,
+            1 => Self::Allow(IndexSet::read(reader)?),
+            2 => Self::Deny(IndexSet::read(reader)?),
+            _ => return Err(ReaderError::InvalidValue)
+        })
+    }
+}
+
+// How a wallet opened by the wallet manager connects to a daemon
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ManagedWalletConnection {
+    // Use the daemon connection shared by all managed wallets
+    #[default]
+    Shared,
+    // Open its own connection to the daemon
+    Dedicated {
+        daemon_address: String
+    },
+    // Don't connect to any daemon
+    Offline
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OpenWalletParams<'a> {
+    pub name: Cow<'a, str>,
+    pub password: Cow<'a, str>,
+    #[serde(default)]
+    pub connection: ManagedWalletConnection,
+    // Sub-account to open, 0 for the master account
+    #[serde(default)]
+    pub account: u32
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateWalletParams<'a> {
+    pub name: Cow<'a, str>,
+    pub password: Cow<'a, str>,
+    // Recover the wallet from this seed
+    pub seed: Option<Cow<'a, str>>,
+    #[serde(default)]
+    pub connection: ManagedWalletConnection
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CloseWalletParams<'a> {
+    pub name: Cow<'a, str>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ManagedWalletInfo<'a> {
+    pub name: Cow<'a, str>,
+    pub address: Address,
+    pub online: bool
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SetAddressBookEntryParams {
+    // Unique name of the contact
+    pub name: String,
+    pub address: Address,
+    // Asset to use by default when sending to this contact
+    pub default_asset: Option<Hash>,
+    pub notes: Option<String>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AddressBookEntryParams {
+    pub name: String
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SetOnlineModeParams {
     pub daemon_address: String,
@@ -201,6 +605,12 @@ pub struct SetOnlineModeParams {
     pub auto_reconnect: bool,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct SetOnlineModeWithFailoverParams {
+    // Daemons sorted by priority
+    pub daemon_addresses: Vec<String>
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct NetworkInfoResult {
     #[serde(flatten)]
@@ -218,6 +628,54 @@ pub struct GetTransactionParams {
     pub hash: Hash
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryExportFormat {
+    #[default]
+    Csv,
+    Json
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExportHistoryParams {
+    #[serde(default)]
+    pub format: HistoryExportFormat,
+    // Annotate entries with their fiat value if a price source is set
+    #[serde(default = "default_false_value")]
+    pub fiat_valuation: bool
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreatePaymentUriParams {
+    // Amount in atomic units
+    pub amount: Option<u64>,
+    pub asset: Option<Hash>,
+    pub extra_data: Option<DataElement>,
+    pub label: Option<String>,
+    pub message: Option<String>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreatePaymentUriResult {
+    pub uri: String,
+    pub request: PaymentRequest
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ParsePaymentUriParams<'a> {
+    pub uri: Cow<'a, str>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SetTransactionLabelParams {
+    pub hash: Hash,
+    // Local note, never shared
+    pub note: Option<String>,
+    // Local categories
+    #[serde(default)]
+    pub tags: IndexSet<String>
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct BalanceChanged {
     pub asset: Hash,
@@ -312,6 +770,19 @@ pub enum NotifyEvent {
     Online,
     // Same here
     Offline,
+    // When a rescan made progress or got paused
+    // Contains a RescanProgress as value
+    RescanProgress,
+    // When a scheduled payment has been sent
+    // Contains a ScheduledPaymentExecuted as value
+    ScheduledPaymentExecuted,
+    // When a scheduled payment couldn't be sent
+    // Contains a ScheduledPaymentFailed as value
+    ScheduledPaymentFailed,
+    // When the keypair got wiped from memory
+    Locked,
+    // When the wallet got unlocked using its password
+    Unlocked,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -323,7 +794,10 @@ pub struct TransferOut {
     // Plaintext amount
     pub amount: u64,
     // extra data
-    pub extra_data: Option<PlaintextExtraData>
+    pub extra_data: Option<PlaintextExtraData>,
+    // Payment ID found in the extra data
+    #[serde(default)]
+    pub payment_id: Option<PaymentId>
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -333,7 +807,10 @@ pub struct TransferIn {
     // Plaintext amount
     pub amount: u64,
     // extra data
-    pub extra_data: Option<PlaintextExtraData>
+    pub extra_data: Option<PlaintextExtraData>,
+    // Payment ID found in the extra data
+    #[serde(default)]
+    pub payment_id: Option<PaymentId>
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -387,6 +864,24 @@ pub enum EntryType {
         fee: u64,
         // Nonce used
         nonce: u64
+    },
+    ManageContract {
+        // Contract address
+        contract: Hash,
+        // Action applied
+        action: ContractManagementAction,
+        // Fee paid
+        fee: u64,
+        // Nonce used
+        nonce: u64
+    },
+    ExecutionCredits {
+        // Deposit or withdraw of credits
+        payload: ExecutionCreditsPayload,
+        // Fee paid
+        fee: u64,
+        // Nonce used
+        nonce: u64
     }
 }
 
@@ -424,4 +919,75 @@ pub struct EstimateExtraDataSizeParams {
 pub struct EstimateExtraDataSizeResult {
     // Integrated data size
     pub size: usize,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DaemonEndpointStatus {
+    pub address: String,
+    // If the daemon was reachable during the last health check
+    pub online: bool,
+    // Topoheight reported during the last health check
+    pub topoheight: Option<u64>,
+    // If the wallet is currently synced with this daemon
+    pub active: bool
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetSubAccountAddressParams {
+    pub index: u32
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SubAccountEntry {
+    pub index: u32,
+    pub address: Address
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UnlockWalletParams<'a> {
+    pub password: Cow<'a, str>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SetAutoLockTimeoutParams {
+    // Inactivity in seconds before locking the wallet, 0 to disable it
+    pub timeout: u64
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GenerateAdaptorSecretResult {
+    // Kept by the swap initiator until the counterparty funds are claimed
+    pub secret: AdaptorSecret,
+    // Shared with the counterparty
+    pub adaptor: AdaptorPoint
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateAdaptorSignatureParams {
+    pub data: DataElement,
+    pub adaptor: AdaptorPoint
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VerifyAdaptorSignatureParams<'a> {
+    pub data: DataElement,
+    pub adaptor: AdaptorPoint,
+    pub signature: AdaptorSignature,
+    // Signer of the pre-signature
+    pub address: Cow<'a, Address>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CompleteAdaptorSignatureParams {
+    pub signature: AdaptorSignature,
+    pub secret: AdaptorSecret
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExtractAdaptorSecretParams {
+    pub adaptor: AdaptorPoint,
+    // Pre-signature received from the counterparty
+    pub pre_signature: AdaptorSignature,
+    // Completed signature published by the counterparty
+    pub signature: Signature
+}
@@ -53,6 +53,13 @@ impl Block {
         self.transactions.len()
     }
 
+    // Get the count of transfers of all its transactions
+    pub fn get_transfers_count(&self) -> usize {
+        self.transactions.iter()
+            .map(|tx| tx.get_transfers_count())
+            .sum()
+    }
+
     pub fn get_transactions(&self) -> &Vec<Immutable<Transaction>> {
         &self.transactions
     }
@@ -72,7 +79,7 @@ impl Serializer for Block {
 
     fn read(reader: &mut Reader) -> Result<Block, ReaderError> {
         let block = BlockHeader::read(reader)?;
-        let mut txs: Vec<Immutable<Transaction>> = Vec::new();
+        let mut txs: Vec<Immutable<Transaction>> = Vec::with_capacity(block.get_txs_count());
         for _ in 0..block.get_txs_count() {
             let tx = Transaction::read(reader)?;
             txs.push(Immutable::Owned(tx));     
@@ -108,4 +115,23 @@ impl Display for Block {
         }
         write!(f, "Block[height: {}, tips: [{}], timestamp: {}, nonce: {}, extra_nonce: {}, txs: {}]", self.height, tips.join(", "), self.timestamp, self.nonce, hex::encode(self.extra_nonce), self.txs_hashes.len())
     }
-}
\ No newline at end of file
+}
+
+// The header references the hashes of the generated transactions
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for Block {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut header: BlockHeader = u.arbitrary()?;
+        header.txs_hashes.clear();
+
+        let mut transactions = Vec::new();
+        for _ in 0..u.int_in_range(0..=4)? {
+            let tx: Transaction = u.arbitrary()?;
+            if header.txs_hashes.insert(tx.hash()) {
+                transactions.push(Immutable::Owned(tx));
+            }
+        }
+
+        Ok(Block::new(Immutable::Owned(header), transactions))
+    }
+}
@@ -294,10 +294,38 @@ impl Display for BlockHeader {
     }
 }
 
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for BlockHeader {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut tips = IndexSet::new();
+        for _ in 0..u.int_in_range(0..=TIPS_LIMIT)? {
+            tips.insert(u.arbitrary()?);
+        }
+
+        let mut txs_hashes = IndexSet::new();
+        for _ in 0..u.int_in_range(0..=16)? {
+            txs_hashes.insert(u.arbitrary()?);
+        }
+
+        Ok(BlockHeader {
+            version: u.arbitrary()?,
+            tips,
+            timestamp: u.arbitrary()?,
+            height: u.arbitrary()?,
+            nonce: u.arbitrary()?,
+            extra_nonce: u.arbitrary()?,
+            miner: u.arbitrary()?,
+            txs_hashes
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use indexmap::IndexSet;
-    use crate::{block::BlockVersion, crypto::{Hash, Hashable, KeyPair}, serializer::Serializer};
+    use arbitrary::{Arbitrary, Unstructured};
+    use rand::{rngs::OsRng, RngCore};
+    use crate::{block::{Block, BlockVersion}, crypto::{Hash, Hashable, KeyPair}, serializer::Serializer};
     use super::BlockHeader;
 
     #[test]
@@ -321,4 +349,20 @@ mod tests {
         let header = BlockHeader::from_hex(serialized).unwrap();
         assert!(header.to_hex() == serialized);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_arbitrary_block_roundtrip() {
+        let mut data = vec![0u8; 64 * 1024];
+        for _ in 0..32 {
+            OsRng.fill_bytes(&mut data);
+            let Ok(block) = Block::arbitrary(&mut Unstructured::new(&data)) else {
+                continue
+            };
+
+            let bytes = block.to_bytes();
+            let read = Block::from_bytes(&bytes).unwrap();
+            assert_eq!(read.to_bytes(), bytes);
+            assert_eq!(read.hash(), block.hash());
+        }
+    }
+}
@@ -10,6 +10,7 @@ use crate::{
         Hash,
         Hashable,
         PublicKey,
+        HASH_SIZE
     },
     serializer::{Reader, ReaderError, Serializer, Writer},
     time::TimestampMillis,
@@ -198,6 +199,40 @@ impl<'a> Worker<'a> {
         Ok(hash)
     }
 
+    // Compute up to `count` POW hashes, increasing the nonce after each one
+    // Stops on the first hash below the target (big endian) and returns it,
+    // the nonce of the work is left on the one that produced it
+    // Inlined so the caller can compile the loop for its own instruction set
+    #[inline(always)]
+    pub fn find_pow_hash(&mut self, count: u64, target: &[u8; HASH_SIZE]) -> Result<Option<Hash>, WorkerError> {
+        let (work, input) = self.work.as_mut()
+            .ok_or(WorkerError::MissingWork)?;
+
+        // Aligned input is only allocated once for the whole batch
+        let mut aligned = v1::AlignedInput::default();
+        for _ in 0..count {
+            let hash = match &mut self.variant {
+                WorkVariant::Uninitialized => return Err(WorkerError::Uninitialized),
+                WorkVariant::V1(scratch_pad) => {
+                    let slice = aligned.as_mut_slice()?;
+                    slice[0..BLOCK_WORK_SIZE].copy_from_slice(input.as_ref());
+                    v1::xelis_hash(slice, scratch_pad)?
+                },
+                WorkVariant::V2(scratch_pad) => v2::xelis_hash(&input[..], scratch_pad)?
+            };
+
+            // Big endian bytes are compared in the same order as the numbers
+            if hash <= *target {
+                return Ok(Some(Hash::new(hash)))
+            }
+
+            work.increase_nonce();
+            input[40..48].copy_from_slice(&work.nonce().to_be_bytes());
+        }
+
+        Ok(None)
+    }
+
     // Compute the block hash based on the current work
     // This is used to get the expected block hash
     pub fn get_block_hash(&self) -> Result<Hash, WorkerError> {
@@ -267,6 +302,11 @@ impl<'a> MinerWork<'a> {
         self.nonce += 1;
     }
 
+    #[inline(always)]
+    pub fn set_nonce(&mut self, nonce: u64) {
+        self.nonce = nonce;
+    }
+
     #[inline(always)]
     pub fn set_miner(&mut self, miner: Cow<'a, PublicKey>) {
         self.miner = Some(miner);
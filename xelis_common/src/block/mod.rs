@@ -5,7 +5,7 @@ mod version;
 
 pub use header::BlockHeader;
 pub use block::Block;
-pub use miner::{MinerWork, Worker, Algorithm};
+pub use miner::{MinerWork, Worker, WorkerError, Algorithm};
 pub use version::BlockVersion;
 
 use crate::crypto::{Hash, HASH_SIZE};
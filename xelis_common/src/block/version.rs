@@ -16,6 +16,8 @@ pub enum BlockVersion {
     V2,
     // Smart Contracts
     V3,
+    // Dynamic block size
+    V4,
 }
 
 impl BlockVersion {
@@ -24,7 +26,7 @@ impl BlockVersion {
         match self {
             BlockVersion::V0 | BlockVersion::V1 => matches!(tx_version, TxVersion::V0),
             BlockVersion::V2 => matches!(tx_version, TxVersion::V1),
-            BlockVersion::V3 => matches!(tx_version, TxVersion::V2),
+            BlockVersion::V3 | BlockVersion::V4 => matches!(tx_version, TxVersion::V2),
         }
     }
 
@@ -33,7 +35,7 @@ impl BlockVersion {
         match self {
             BlockVersion::V0 | BlockVersion::V1 => TxVersion::V0,
             BlockVersion::V2 => TxVersion::V1,
-            BlockVersion::V3 => TxVersion::V2,
+            BlockVersion::V3 | BlockVersion::V4 => TxVersion::V2,
         }
     }
 }
@@ -47,6 +49,7 @@ impl TryFrom<u8> for BlockVersion {
             1 => Ok(BlockVersion::V1),
             2 => Ok(BlockVersion::V2),
             3 => Ok(BlockVersion::V3),
+            4 => Ok(BlockVersion::V4),
             _ => Err(()),
         }
     }
@@ -59,6 +62,7 @@ impl Serializer for BlockVersion {
             BlockVersion::V1 => writer.write_u8(1),
             BlockVersion::V2 => writer.write_u8(2),
             BlockVersion::V3 => writer.write_u8(3),
+            BlockVersion::V4 => writer.write_u8(4),
         }
     }
 
@@ -80,6 +84,7 @@ impl fmt::Display for BlockVersion {
             BlockVersion::V1 => write!(f, "V1"),
             BlockVersion::V2 => write!(f, "V2"),
             BlockVersion::V3 => write!(f, "V3"),
+            BlockVersion::V4 => write!(f, "V4"),
         }
     }
 }
@@ -99,6 +104,13 @@ impl<'de> serde::Deserialize<'de> for BlockVersion {
     }
 }
 
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for BlockVersion {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        u.choose(&[BlockVersion::V0, BlockVersion::V1, BlockVersion::V2, BlockVersion::V3, BlockVersion::V4]).copied()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,5 +129,6 @@ mod tests {
     fn test_block_version_ord() {
         assert!(BlockVersion::V0 < BlockVersion::V1);
         assert!(BlockVersion::V1 < BlockVersion::V2);
+        assert!(BlockVersion::V3 < BlockVersion::V4);
     }
 }
\ No newline at end of file
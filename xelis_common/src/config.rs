@@ -6,6 +6,9 @@ pub const XELIS_ASSET: Hash = Hash::zero();
 // Lowest fee per KB possible on the network
 // 0.00010000 XEL per KB
 pub const FEE_PER_KB: u64 = 10000;
+// Minimum fee increase in percent to replace a pending transaction
+// using the same nonce
+pub const TX_REPLACEMENT_MIN_FEE_INCREASE_PERCENT: u64 = 10;
 // 0.00100000 XEL per account creation
 // User can create an account with 0.001 XEL
 // Or can mine a block to be registered for free
@@ -39,6 +42,14 @@ pub const FEE_PER_STORE_CONTRACT: u64 = 100;
 // Each byte of data stored (key + value) in a contract has a fixed cost
 // 0.00000005 XEL per byte
 pub const FEE_PER_BYTE_STORED_CONTRACT: u64 = 5;
+// Maximum size in bytes of a contract module once serialized
+pub const MAX_CONTRACT_MODULE_SIZE: usize = 256 * BYTES_PER_KB;
+// Maximum constants declared in a contract module
+pub const MAX_CONTRACT_CONSTANTS: usize = 1024;
+// Maximum chunks (functions) in a contract module
+pub const MAX_CONTRACT_CHUNKS: usize = 1024;
+// Maximum entrypoints that can be invoked in a contract module
+pub const MAX_CONTRACT_ENTRYPOINTS: usize = 256;
 
 // 8 decimals numbers
 pub const COIN_DECIMALS: u8 = 8;
@@ -66,5 +77,14 @@ pub const MAX_TRANSACTION_SIZE: usize = BYTES_PER_KB * BYTES_PER_KB; // 1 MB
 // 1024 * 1024 + (256 * 1024) bytes = 1.25 MB maximum size per block with txs
 pub const MAX_BLOCK_SIZE: usize = (BYTES_PER_KB * BYTES_PER_KB) + (256 * BYTES_PER_KB);
 
+// Hard cap of the block size once it follows the recent blocks usage
+// 4 * 1.25 MB = 5 MB maximum size per block with txs
+pub const MAX_DYNAMIC_BLOCK_SIZE: usize = MAX_BLOCK_SIZE * 4;
+
+// Millis per second, it is used to prevent having random 1000 values anywhere
+pub const MILLIS_PER_SECOND: u64 = 1000;
+// Block Time in milliseconds
+pub const BLOCK_TIME_MILLIS: u64 = 15 * MILLIS_PER_SECOND; // 15s block time
+
 // BlockDAG rules
-pub const TIPS_LIMIT: usize = 3; // maximum 3 TIPS per block
\ No newline at end of file
+pub const TIPS_LIMIT: usize = 3; // maximum 3 TIPS per block
@@ -0,0 +1,113 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+// Sensitive natives a contract can call
+// Each network decides at which hard fork a capability is allowed,
+// so testnet can trial a feature before mainnet without forking the code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractCapability {
+    // Transfer assets owned by the contract
+    Transfer,
+    // Burn assets owned by the contract
+    Burn,
+    // Emit events stored in the TX receipt
+    Events,
+    // Create new assets
+    AssetCreation,
+    // Call another contract
+    CrossContractCall,
+    // Be invoked at each block
+    OnBlockHook
+}
+
+impl ContractCapability {
+    pub const ALL: [ContractCapability; 6] = [
+        Self::Transfer,
+        Self::Burn,
+        Self::Events,
+        Self::AssetCreation,
+        Self::CrossContractCall,
+        Self::OnBlockHook
+    ];
+
+    const fn bit(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+impl fmt::Display for ContractCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Transfer => "transfer",
+            Self::Burn => "burn",
+            Self::Events => "events",
+            Self::AssetCreation => "asset_creation",
+            Self::CrossContractCall => "cross_contract_call",
+            Self::OnBlockHook => "on_block_hook"
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+// Set of capabilities allowed for a contract execution
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContractCapabilities(u8);
+
+impl ContractCapabilities {
+    pub const fn none() -> Self {
+        Self(0)
+    }
+
+    pub fn all() -> Self {
+        ContractCapability::ALL.into_iter().collect()
+    }
+
+    pub const fn with(self, capability: ContractCapability) -> Self {
+        Self(self.0 | capability.bit())
+    }
+
+    pub const fn allows(&self, capability: ContractCapability) -> bool {
+        self.0 & capability.bit() != 0
+    }
+
+    // Return an error if the capability is not allowed
+    // Used by the natives before doing anything
+    pub fn require(&self, capability: ContractCapability) -> Result<(), anyhow::Error> {
+        if !self.allows(capability) {
+            return Err(anyhow::anyhow!("capability {} is not enabled", capability));
+        }
+
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ContractCapability> + '_ {
+        ContractCapability::ALL.into_iter().filter(|c| self.allows(*c))
+    }
+}
+
+impl FromIterator<ContractCapability> for ContractCapabilities {
+    fn from_iter<T: IntoIterator<Item = ContractCapability>>(iter: T) -> Self {
+        iter.into_iter().fold(Self::none(), |set, capability| set.with(capability))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_set() {
+        let set = ContractCapabilities::none()
+            .with(ContractCapability::Transfer)
+            .with(ContractCapability::Events);
+
+        assert!(set.allows(ContractCapability::Transfer));
+        assert!(set.allows(ContractCapability::Events));
+        assert!(!set.allows(ContractCapability::Burn));
+        assert!(set.require(ContractCapability::OnBlockHook).is_err());
+        assert_eq!(set.iter().count(), 2);
+        assert_eq!(ContractCapabilities::all().iter().count(), ContractCapability::ALL.len());
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    crypto::PublicKey,
+    serializer::*
+};
+
+// Consensus-level management state of a deployed contract
+// It is stored outside of the contract storage so the contract can't update it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ContractManagementState {
+    // Key allowed to manage the contract, set to the deployer
+    pub owner: PublicKey,
+    // No invocation of the contract is accepted while paused
+    pub paused: bool
+}
+
+impl ContractManagementState {
+    pub fn new(owner: PublicKey) -> Self {
+        Self {
+            owner,
+            paused: false
+        }
+    }
+}
+
+impl Serializer for ContractManagementState {
+    fn write(&self, writer: &mut Writer) {
+        self.owner.write(writer);
+        writer.write_bool(self.paused);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let owner = PublicKey::read(reader)?;
+        let paused = reader.read_bool()?;
+        Ok(Self { owner, paused })
+    }
+
+    fn size(&self) -> usize {
+        self.owner.size() + self.paused.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::KeyPair;
+    use super::*;
+
+    #[test]
+    fn test_management_state_roundtrip() {
+        let mut state = ContractManagementState::new(KeyPair::new().get_public_key().compress());
+        state.paused = true;
+
+        let bytes = state.to_bytes();
+        assert_eq!(bytes.len(), state.size());
+        assert_eq!(ContractManagementState::from_bytes(&bytes).unwrap(), state);
+    }
+}
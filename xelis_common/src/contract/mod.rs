@@ -3,6 +3,10 @@ mod opaque;
 mod random;
 mod output;
 mod provider;
+mod validation;
+mod management;
+mod receipt;
+mod capabilities;
 
 use std::{any::TypeId, collections::{hash_map::Entry, HashMap}};
 use anyhow::Context as AnyhowContext;
@@ -24,7 +28,10 @@ use xelis_vm::{
 };
 use crate::{
     block::{Block, TopoHeight},
+    config::FEE_PER_BYTE_STORED_CONTRACT,
     crypto::{Address, Hash, PublicKey},
+    fixed_point,
+    serializer::Serializer,
     transaction::ContractDeposit,
     versioned_type::VersionedState
 };
@@ -35,6 +42,10 @@ pub use output::*;
 
 pub use opaque::ContractStorage;
 pub use provider::*;
+pub use validation::*;
+pub use management::ContractManagementState;
+pub use receipt::*;
+pub use capabilities::*;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TransferOutput {
@@ -55,6 +66,8 @@ pub struct ChainState<'a> {
     pub random: DeterministicRandom,
     // Are we in mainnet
     pub mainnet: bool,
+    // Capabilities allowed by the network at this block
+    pub capabilities: ContractCapabilities,
     // The contract hash
     pub contract: &'a Hash,
     // The topoheight of the block
@@ -87,6 +100,9 @@ pub struct ContractCache {
     // Those already present are loaded due to the deposits to be added
     // If its none, it means we don't have any balance yet
     pub balances: HashMap<Hash, Option<(VersionedState, u64)>>,
+    // Events emitted by the contract during the current invocation
+    // They are taken in the receipt and never merged
+    pub events: Vec<ContractEvent>,
 }
 
 impl ContractCache {
@@ -95,6 +111,7 @@ impl ContractCache {
             transfers: Vec::new(),
             storage: HashMap::new(),
             balances: HashMap::new(),
+            events: Vec::new(),
         }
     }
 
@@ -107,6 +124,8 @@ impl ContractCache {
 }
 
 // Build the environment for the contract
+// Natives bound to a capability are always registered so the functions ids stay the same
+// on every network, they check at runtime if the capability is allowed
 pub fn build_environment<P: ContractProvider>() -> EnvironmentBuilder<'static> {
     debug!("Building environment for contract");
     register_opaque_types();
@@ -319,12 +338,55 @@ pub fn build_environment<P: ContractProvider>() -> EnvironmentBuilder<'static> {
             Some(Type::Array(Box::new(Type::U8)))
         );
 
+        env.register_native_function(
+            "to_string",
+            Some(address_type.clone()),
+            vec![],
+            address_to_string,
+            10,
+            Some(Type::String)
+        );
+        env.register_native_function(
+            "to_network",
+            Some(address_type.clone()),
+            vec![("mainnet", Type::Bool)],
+            address_to_network,
+            5,
+            Some(address_type.clone())
+        );
+        env.register_native_function(
+            "to_normal",
+            Some(address_type.clone()),
+            vec![],
+            address_to_normal,
+            5,
+            Some(address_type.clone())
+        );
+        env.register_native_function(
+            "get_integrated_data",
+            Some(address_type.clone()),
+            vec![],
+            address_get_integrated_data,
+            25,
+            Some(Type::Optional(Box::new(Type::Any)))
+        );
+
         env.register_const_function(
             "from_string",
             address_type.clone(),
             vec![("address", Type::String)],
             address_from_string
         );
+
+        // Parse an address at runtime, None if invalid or for another network
+        env.register_native_function(
+            "parse_address",
+            None,
+            vec![("address", Type::String)],
+            parse_address,
+            25,
+            Some(Type::Optional(Box::new(address_type.clone())))
+        );
     }
 
     // Get the current contract hash
@@ -357,6 +419,19 @@ pub fn build_environment<P: ContractProvider>() -> EnvironmentBuilder<'static> {
         Some(Type::U64)
     );
 
+    // Emit an event that will be stored in the TX receipt
+    env.register_native_function(
+        "emit_event",
+        None,
+        vec![
+            ("id", Type::U64),
+            ("data", Type::Any),
+        ],
+        emit_event,
+        50,
+        None
+    );
+
     env.register_native_function(
         "transfer",
         None,
@@ -382,6 +457,45 @@ pub fn build_environment<P: ContractProvider>() -> EnvironmentBuilder<'static> {
         Some(Type::Bool)
     );
 
+    // Fixed point math
+    // Values are u128 scaled by 10^decimals, None is returned on overflow or division by zero
+    {
+        let fixed_params = || vec![
+            ("a", Type::U128),
+            ("b", Type::U128),
+            ("decimals", Type::U8),
+        ];
+
+        env.register_native_function(
+            "fixed_mul",
+            None,
+            fixed_params(),
+            fixed_mul_fn,
+            10,
+            Some(Type::Optional(Box::new(Type::U128)))
+        );
+        env.register_native_function(
+            "fixed_div",
+            None,
+            fixed_params(),
+            fixed_div_fn,
+            10,
+            Some(Type::Optional(Box::new(Type::U128)))
+        );
+        env.register_native_function(
+            "fixed_rescale",
+            None,
+            vec![
+                ("value", Type::U128),
+                ("from_decimals", Type::U8),
+                ("to_decimals", Type::U8),
+            ],
+            fixed_rescale_fn,
+            10,
+            Some(Type::Optional(Box::new(Type::U128)))
+        );
+    }
+
     // Hash
     {
         env.register_native_function(
@@ -584,8 +698,38 @@ fn get_balance_for_asset<P: ContractProvider>(_: FnInstance, mut params: FnParam
     Ok(Some(ValueCell::Optional(balance.map(|(_, v)| Value::U64(v).into()))))
 }
 
+fn emit_event(_: FnInstance, mut params: FnParams, context: &mut Context) -> FnReturnType {
+    let data: Constant = params.remove(1)
+        .into_owned()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Invalid event data"))?;
+
+    let id = params.remove(0)
+        .into_owned()
+        .to_u64()?;
+
+    let size = data.size();
+    if size > MAX_EVENT_DATA_SIZE {
+        return Err(anyhow::anyhow!("Event data is too large").into());
+    }
+
+    context.increase_gas_usage(size as u64 * FEE_PER_BYTE_STORED_CONTRACT)?;
+
+    let state: &mut ChainState = context.get_mut().context("chain state not found")?;
+    state.capabilities.require(ContractCapability::Events)?;
+
+    if state.changes.events.len() >= MAX_EVENTS_PER_INVOCATION {
+        return Err(anyhow::anyhow!("Too many events emitted").into());
+    }
+
+    state.changes.events.push(ContractEvent { id, data });
+
+    Ok(None)
+}
+
 fn transfer<P: ContractProvider>(_: FnInstance, mut params: FnParams, context: &mut Context) -> FnReturnType {
     let (provider, state) = from_context::<P>(context)?;
+    state.capabilities.require(ContractCapability::Transfer)?;
 
     let amount = params.remove(2)
         .into_owned()
@@ -635,6 +779,7 @@ fn transfer<P: ContractProvider>(_: FnInstance, mut params: FnParams, context: &
 
 fn burn<P: ContractProvider>(_: FnInstance, mut params: FnParams, context: &mut Context) -> FnReturnType {
     let (provider, state) = from_context::<P>(context)?;
+    state.capabilities.require(ContractCapability::Burn)?;
 
     let asset: Hash = params.remove(1)
         .into_owned()
@@ -662,6 +807,35 @@ fn burn<P: ContractProvider>(_: FnInstance, mut params: FnParams, context: &mut
     Ok(Some(Value::Boolean(true).into()))
 }
 
+// Convert the result of a fixed point operation into an optional u128
+fn fixed_point_result(res: Result<u128, fixed_point::FixedPointError>) -> FnReturnType {
+    Ok(Some(ValueCell::Optional(res.ok().map(|v| Value::U128(v).into()))))
+}
+
+fn fixed_mul_fn(_: FnInstance, params: FnParams, _: &mut Context) -> FnReturnType {
+    let a = params[0].as_ref().as_value().as_u128()?;
+    let b = params[1].as_ref().as_value().as_u128()?;
+    let decimals = params[2].as_ref().as_value().as_u8()?;
+
+    fixed_point_result(fixed_point::scale_for(decimals).and_then(|scale| fixed_point::mul_div(a, b, scale)))
+}
+
+fn fixed_div_fn(_: FnInstance, params: FnParams, _: &mut Context) -> FnReturnType {
+    let a = params[0].as_ref().as_value().as_u128()?;
+    let b = params[1].as_ref().as_value().as_u128()?;
+    let decimals = params[2].as_ref().as_value().as_u8()?;
+
+    fixed_point_result(fixed_point::scale_for(decimals).and_then(|scale| fixed_point::mul_div(a, scale, b)))
+}
+
+fn fixed_rescale_fn(_: FnInstance, params: FnParams, _: &mut Context) -> FnReturnType {
+    let value = params[0].as_ref().as_value().as_u128()?;
+    let from = params[1].as_ref().as_value().as_u8()?;
+    let to = params[2].as_ref().as_value().as_u8()?;
+
+    fixed_point_result(fixed_point::rescale(value, from, to))
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -1,7 +1,13 @@
 use std::any::TypeId;
+use anyhow::Context as AnyhowContext;
+use indexmap::IndexMap;
 use xelis_builder::ConstFnParams;
 use xelis_vm::{traits::Serializable, Constant, Context, FnInstance, FnParams, FnReturnType, Opaque, OpaqueWrapper, Value, ValueCell};
-use crate::crypto::Address;
+use crate::{
+    api::{DataElement, DataValue},
+    contract::ChainState,
+    crypto::{Address, AddressType}
+};
 
 use super::{Serializer, Writer, ADDRESS_OPAQUE_ID};
 
@@ -44,9 +50,112 @@ pub fn address_public_key_bytes(zelf: FnInstance, _: FnParams, _: &mut Context)
     Ok(Some(ValueCell::Array(bytes)))
 }
 
+pub fn address_to_string(zelf: FnInstance, _: FnParams, _: &mut Context) -> FnReturnType {
+    let address: &Address = zelf?.as_opaque_type()?;
+    Ok(Some(Value::String(address.to_string()).into()))
+}
+
+// Same address with the requested network
+pub fn address_to_network(zelf: FnInstance, params: FnParams, _: &mut Context) -> FnReturnType {
+    let address: &Address = zelf?.as_opaque_type()?;
+    let mainnet = params[0].as_ref().as_value().as_bool()?;
+
+    let address = Address::new(mainnet, address.get_type().clone(), address.get_public_key().clone());
+    Ok(Some(Value::Opaque(OpaqueWrapper::new(address)).into()))
+}
+
+// Same address without its integrated data
+pub fn address_to_normal(zelf: FnInstance, _: FnParams, _: &mut Context) -> FnReturnType {
+    let address: &Address = zelf?.as_opaque_type()?;
+
+    let address = Address::new(address.is_mainnet(), AddressType::Normal, address.get_public_key().clone());
+    Ok(Some(Value::Opaque(OpaqueWrapper::new(address)).into()))
+}
+
+pub fn address_get_integrated_data(zelf: FnInstance, _: FnParams, _: &mut Context) -> FnReturnType {
+    let address: &Address = zelf?.as_opaque_type()?;
+    let data = address.get_extra_data()
+        .map(|data| data_element_to_constant(data).into());
+
+    Ok(Some(ValueCell::Optional(data)))
+}
+
+// Parse an address at runtime
+// None is returned if it is invalid or not for the current network
+pub fn parse_address(_: FnInstance, params: FnParams, context: &mut Context) -> FnReturnType {
+    let value = params[0].as_ref().as_value().as_string()?;
+    let state: &ChainState = context.get().context("chain state not found")?;
+
+    let address = Address::from_string(value).ok()
+        .filter(|address| address.is_mainnet() == state.mainnet)
+        .map(|address| Value::Opaque(OpaqueWrapper::new(address)).into());
+
+    Ok(Some(ValueCell::Optional(address)))
+}
+
 pub fn address_from_string(params: ConstFnParams) -> Result<Constant, anyhow::Error> {
     let addr = params[0].as_string()?;
     let addr = Address::from_string(addr)?;
 
     Ok(Constant::Default(Value::Opaque(OpaqueWrapper::new(addr))))
-}
\ No newline at end of file
+}
+
+fn data_value_to_constant(value: &DataValue) -> Constant {
+    let value = match value {
+        DataValue::Bool(v) => Value::Boolean(*v),
+        DataValue::String(v) => Value::String(v.clone()),
+        DataValue::U8(v) => Value::U8(*v),
+        DataValue::U16(v) => Value::U16(*v),
+        DataValue::U32(v) => Value::U32(*v),
+        DataValue::U64(v) => Value::U64(*v),
+        DataValue::U128(v) => Value::U128(*v),
+        DataValue::Hash(v) => Value::Opaque(OpaqueWrapper::new(v.clone())),
+        DataValue::Blob(v) => return Constant::Array(v.iter().map(|b| Constant::Default(Value::U8(*b))).collect())
+    };
+
+    Constant::Default(value)
+}
+
+// Convert the integrated data of an address into a VM constant
+// Fields are sorted by their serialized key to stay deterministic
+fn data_element_to_constant(element: &DataElement) -> Constant {
+    match element {
+        DataElement::Value(value) => data_value_to_constant(value),
+        DataElement::Array(values) => Constant::Array(values.iter().map(data_element_to_constant).collect()),
+        DataElement::Fields(fields) => {
+            let mut entries = fields.iter().collect::<Vec<_>>();
+            entries.sort_by_cached_key(|(key, _)| key.to_bytes());
+
+            let map = entries.into_iter()
+                .map(|(key, value)| (data_value_to_constant(key), data_element_to_constant(value)))
+                .collect::<IndexMap<_, _>>();
+
+            Constant::Map(map)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use super::*;
+
+    #[test]
+    fn test_integrated_data_to_constant() {
+        let mut fields = HashMap::new();
+        for i in 0..16u8 {
+            fields.insert(DataValue::U8(i), DataElement::Value(DataValue::Blob(vec![i])));
+        }
+
+        let constant = data_element_to_constant(&DataElement::Fields(fields.clone()));
+        // Order must not depend on the HashMap iteration
+        assert_eq!(constant, data_element_to_constant(&DataElement::Fields(fields.into_iter().rev().collect())));
+
+        let Constant::Map(map) = constant else {
+            panic!("expected a map");
+        };
+        assert_eq!(map.len(), 16);
+        assert_eq!(map.get_index(0).map(|(k, _)| k), Some(&Constant::Default(Value::U8(0))));
+        assert_eq!(map.get_index(0).map(|(_, v)| v), Some(&Constant::Array(vec![Constant::Default(Value::U8(0))])));
+    }
+}
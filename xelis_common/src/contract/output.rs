@@ -23,7 +23,12 @@ pub enum ContractOutput {
     // If Some(n), the contract exited with code n (state not applied!)
     ExitCode(Option<u64>),
     // Inform that we refund the deposits
-    RefundDeposits
+    RefundDeposits,
+    // Used gas paid by the execution credits of the sender
+    ExecutionCredits {
+        /// The amount of credits drawn and given back to the sender balance
+        amount: u64
+    }
 }
 
 impl Serializer for ContractOutput {
@@ -45,6 +50,10 @@ impl Serializer for ContractOutput {
             },
             ContractOutput::RefundDeposits => {
                 writer.write_u8(3);
+            },
+            ContractOutput::ExecutionCredits { amount } => {
+                writer.write_u8(4);
+                amount.write(writer);
             }
         }
     }
@@ -63,6 +72,10 @@ impl Serializer for ContractOutput {
             },
             2 => Ok(ContractOutput::ExitCode(Option::read(reader)?)),
             3 => Ok(ContractOutput::RefundDeposits),
+            4 => {
+                let amount = u64::read(reader)?;
+                Ok(ContractOutput::ExecutionCredits { amount })
+            },
             _ => Err(ReaderError::InvalidValue)
         }
     }
@@ -72,7 +85,8 @@ impl Serializer for ContractOutput {
             ContractOutput::RefundGas { amount } => 1 + amount.size(),
             ContractOutput::Transfer { amount, asset, destination } => 1 + amount.size() + asset.size() + destination.size(),
             ContractOutput::ExitCode(code) => 1 + code.size(),
-            ContractOutput::RefundDeposits => 1
+            ContractOutput::RefundDeposits => 1,
+            ContractOutput::ExecutionCredits { amount } => 1 + amount.size()
         }
     }
 }
\ No newline at end of file
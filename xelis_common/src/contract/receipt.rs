@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use xelis_vm::Constant;
+use crate::{crypto::Hash, serializer::*};
+use super::TransferOutput;
+
+// Maximum size in bytes of the data attached to an event
+pub const MAX_EVENT_DATA_SIZE: usize = 1024;
+// Maximum events a contract can emit in a single invocation
+pub const MAX_EVENTS_PER_INVOCATION: usize = 64;
+
+// Event emitted by a contract during its execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractEvent {
+    // Identifier chosen by the contract
+    pub id: u64,
+    // Data attached to the event
+    pub data: Constant
+}
+
+impl Serializer for ContractEvent {
+    fn write(&self, writer: &mut Writer) {
+        self.id.write(writer);
+        self.data.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let id = u64::read(reader)?;
+        let data = Constant::read(reader)?;
+
+        Ok(Self { id, data })
+    }
+
+    fn size(&self) -> usize {
+        self.id.size() + self.data.size()
+    }
+}
+
+// Receipt of a contract invocation
+// It is stored per TX so users can see what their call actually did
+#[derive(Debug, Clone)]
+pub struct ContractReceipt {
+    // Contract that got invoked
+    pub contract: Hash,
+    // Entry chunk called
+    pub chunk_id: u16,
+    // Maximum gas paid by the caller
+    pub max_gas: u64,
+    // Gas used by the execution
+    pub gas_used: u64,
+    // Exit code returned by the contract
+    // None if the execution failed
+    pub exit_code: Option<u64>,
+    // Reason of the failure, if any
+    pub error: Option<String>,
+    // Events emitted, only kept if the execution was successful
+    pub events: Vec<ContractEvent>,
+    // Transfers produced, only kept if the execution was successful
+    pub transfers: Vec<TransferOutput>
+}
+
+impl ContractReceipt {
+    // Did the invocation succeed
+    pub fn is_success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+impl Serializer for TransferOutput {
+    fn write(&self, writer: &mut Writer) {
+        self.destination.write(writer);
+        self.amount.write(writer);
+        self.asset.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        Ok(Self {
+            destination: Serializer::read(reader)?,
+            amount: u64::read(reader)?,
+            asset: Hash::read(reader)?
+        })
+    }
+
+    fn size(&self) -> usize {
+        self.destination.size() + self.amount.size() + self.asset.size()
+    }
+}
+
+impl Serializer for ContractReceipt {
+    fn write(&self, writer: &mut Writer) {
+        self.contract.write(writer);
+        self.chunk_id.write(writer);
+        self.max_gas.write(writer);
+        self.gas_used.write(writer);
+        self.exit_code.write(writer);
+        self.error.write(writer);
+        self.events.write(writer);
+        self.transfers.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        Ok(Self {
+            contract: Hash::read(reader)?,
+            chunk_id: u16::read(reader)?,
+            max_gas: u64::read(reader)?,
+            gas_used: u64::read(reader)?,
+            exit_code: Option::read(reader)?,
+            error: Option::read(reader)?,
+            events: Vec::read(reader)?,
+            transfers: Vec::read(reader)?
+        })
+    }
+
+    fn size(&self) -> usize {
+        self.contract.size()
+            + self.chunk_id.size()
+            + self.max_gas.size()
+            + self.gas_used.size()
+            + self.exit_code.size()
+            + self.error.size()
+            + self.events.size()
+            + self.transfers.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use xelis_vm::Value;
+    use crate::crypto::KeyPair;
+    use super::*;
+
+    #[test]
+    fn test_receipt_serde() {
+        let receipt = ContractReceipt {
+            contract: Hash::zero(),
+            chunk_id: 1,
+            max_gas: 1000,
+            gas_used: 250,
+            exit_code: Some(0),
+            error: None,
+            events: vec![ContractEvent { id: 7, data: Constant::Default(Value::U64(42)) }],
+            transfers: vec![TransferOutput {
+                destination: KeyPair::new().get_public_key().compress(),
+                amount: 10,
+                asset: Hash::zero()
+            }]
+        };
+
+        let bytes = receipt.to_bytes();
+        assert_eq!(bytes.len(), receipt.size());
+
+        let decoded = ContractReceipt::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.contract, receipt.contract);
+        assert_eq!(decoded.chunk_id, receipt.chunk_id);
+        assert_eq!(decoded.gas_used, receipt.gas_used);
+        assert_eq!(decoded.exit_code, receipt.exit_code);
+        assert_eq!(decoded.events, receipt.events);
+        assert_eq!(decoded.transfers, receipt.transfers);
+        assert!(decoded.is_success());
+    }
+}
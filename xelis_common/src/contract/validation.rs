@@ -0,0 +1,124 @@
+use thiserror::Error;
+use xelis_vm::{Module, ModuleValidator, Environment};
+use crate::{
+    config::{
+        MAX_CONTRACT_CHUNKS,
+        MAX_CONTRACT_CONSTANTS,
+        MAX_CONTRACT_ENTRYPOINTS,
+        MAX_CONTRACT_MODULE_SIZE
+    },
+    serializer::Serializer
+};
+
+// Deploy-time checks of a contract module
+// They are cheap and done before the VM module validator
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ModuleValidationError {
+    #[error("Module size {} bytes is above the maximum of {} bytes", _0, _1)]
+    ModuleTooBig(usize, usize),
+    #[error("Module has {} constants, maximum is {}", _0, _1)]
+    TooManyConstants(usize, usize),
+    #[error("Module has {} chunks, maximum is {}", _0, _1)]
+    TooManyChunks(usize, usize),
+    #[error("Module has {} entrypoints, maximum is {}", _0, _1)]
+    TooManyEntrypoints(usize, usize),
+    #[error("Module has no entrypoint")]
+    NoEntrypoint,
+    #[error("Entrypoint {} is not an existing chunk, module has {} chunks", _0, _1)]
+    InvalidEntrypoint(usize, usize),
+    #[error("Chunk {} has no instruction", _0)]
+    EmptyChunk(usize),
+    #[error("Module is rejected by the VM validator: {}", _0)]
+    Validator(String)
+}
+
+// Check the limits and the entrypoints of a module to deploy
+pub fn validate_module_limits(module: &Module) -> Result<(), ModuleValidationError> {
+    let size = module.size();
+    if size > MAX_CONTRACT_MODULE_SIZE {
+        return Err(ModuleValidationError::ModuleTooBig(size, MAX_CONTRACT_MODULE_SIZE))
+    }
+
+    let constants = module.constants().len();
+    if constants > MAX_CONTRACT_CONSTANTS {
+        return Err(ModuleValidationError::TooManyConstants(constants, MAX_CONTRACT_CONSTANTS))
+    }
+
+    let chunks = module.chunks();
+    if chunks.len() > MAX_CONTRACT_CHUNKS {
+        return Err(ModuleValidationError::TooManyChunks(chunks.len(), MAX_CONTRACT_CHUNKS))
+    }
+
+    if let Some(id) = chunks.iter().position(|chunk| chunk.get_instructions().is_empty()) {
+        return Err(ModuleValidationError::EmptyChunk(id))
+    }
+
+    let entry_ids = module.chunks_entry_ids();
+    if entry_ids.is_empty() {
+        return Err(ModuleValidationError::NoEntrypoint)
+    }
+
+    if entry_ids.len() > MAX_CONTRACT_ENTRYPOINTS {
+        return Err(ModuleValidationError::TooManyEntrypoints(entry_ids.len(), MAX_CONTRACT_ENTRYPOINTS))
+    }
+
+    // Entrypoints must be callable chunks
+    if let Some(id) = entry_ids.iter().find(|id| **id >= chunks.len()) {
+        return Err(ModuleValidationError::InvalidEntrypoint(*id, chunks.len()))
+    }
+
+    Ok(())
+}
+
+// Full validation pipeline of a module to deploy
+// Limits are checked first, then the opcodes, types and constants by the VM validator
+pub fn validate_module(module: &Module, environment: &Environment) -> Result<(), ModuleValidationError> {
+    validate_module_limits(module)?;
+
+    ModuleValidator::new(module, environment)
+        .verify()
+        .map_err(|err| ModuleValidationError::Validator(format!("{:#}", err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexSet;
+    use xelis_vm::Chunk;
+    use super::*;
+
+    fn module(chunks: Vec<Vec<u8>>, entry_ids: Vec<usize>) -> Module {
+        let chunks = chunks.into_iter().map(Chunk::from_instructions).collect();
+        Module::with(IndexSet::new(), chunks, entry_ids.into_iter().collect(), IndexSet::new(), IndexSet::new())
+    }
+
+    #[test]
+    fn test_no_entrypoint() {
+        let module = module(vec![vec![0]], vec![]);
+        assert_eq!(validate_module_limits(&module), Err(ModuleValidationError::NoEntrypoint));
+    }
+
+    #[test]
+    fn test_invalid_entrypoint() {
+        let module = module(vec![vec![0]], vec![1]);
+        assert_eq!(validate_module_limits(&module), Err(ModuleValidationError::InvalidEntrypoint(1, 1)));
+    }
+
+    #[test]
+    fn test_empty_chunk() {
+        let module = module(vec![vec![0], vec![]], vec![0]);
+        assert_eq!(validate_module_limits(&module), Err(ModuleValidationError::EmptyChunk(1)));
+    }
+
+    #[test]
+    fn test_module_too_big() {
+        let module = module(vec![vec![0; MAX_CONTRACT_MODULE_SIZE]], vec![0]);
+        assert!(matches!(validate_module_limits(&module), Err(ModuleValidationError::ModuleTooBig(_, MAX_CONTRACT_MODULE_SIZE))));
+    }
+
+    #[test]
+    fn test_valid_limits() {
+        let module = module(vec![vec![0], vec![0]], vec![1]);
+        assert!(validate_module_limits(&module).is_ok());
+    }
+}
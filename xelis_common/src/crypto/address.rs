@@ -5,7 +5,7 @@ use std::{
     str::FromStr
 };
 use crate::{
-    api::{DataElement, ValueType, DataValue},
+    api::{DataElement, ValueType, DataValue, PaymentId},
     serializer::{Serializer, Writer, Reader, ReaderError},
     config::{PREFIX_ADDRESS, TESTNET_PREFIX_ADDRESS},
     transaction::EXTRA_DATA_LIMIT_SIZE
@@ -98,6 +98,12 @@ impl Address {
         }
     }
 
+    // Get the standardized payment ID integrated in the address if present
+    pub fn get_payment_id(&self) -> Option<PaymentId> {
+        self.get_extra_data()
+            .and_then(DataElement::get_payment_id)
+    }
+
     // Check if the address is a normal address (no data integrated)
     pub fn is_normal(&self) -> bool {
         match self.addr_type {
@@ -0,0 +1,106 @@
+use curve25519_dalek::Scalar;
+
+// Re-export the subtle traits used by the constant-time helpers
+pub use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+// Constant-time helpers for the comparisons of secret-derived data
+//
+// Comparing a secret with `==` may return early on the first different byte,
+// leaking through timing how much of it matches.
+// Verification paths (signatures, commitments, keys) use these helpers instead.
+// Types holding secrets don't provide a variable-time `PartialEq`:
+// their `==` delegates to `ct_eq`, so it is constant-time too,
+// and debug builds check its result against a plain byte comparison.
+
+// Constant-time equality of two values
+pub fn eq<T: ConstantTimeEq + ?Sized>(a: &T, b: &T) -> bool {
+    a.ct_eq(b).into()
+}
+
+// Constant-time equality of two byte slices
+// Only the length is compared in variable time, it must not be secret
+pub fn eq_bytes(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+// Select `b` if choice is true, `a` otherwise, without branching on choice
+pub fn select<T: ConditionallySelectable>(a: &T, b: &T, choice: bool) -> T {
+    T::conditional_select(a, b, Choice::from(choice as u8))
+}
+
+// Constant-time check that a scalar is zero
+pub fn is_zero(scalar: &Scalar) -> bool {
+    eq(scalar, &Scalar::ZERO)
+}
+
+// Implement a constant-time `PartialEq` for a type holding a secret
+// The type must implement `ConstantTimeEq`,
+// the closure gives the bytes used by the debug check
+macro_rules! impl_secret_eq {
+    ($type: ty, |$value: ident| $bytes: expr) => {
+        impl PartialEq for $type {
+            fn eq(&self, other: &Self) -> bool {
+                let equal = $crate::crypto::ct::eq(self, other);
+                debug_assert_eq!(
+                    equal,
+                    { let $value = self; $bytes } == { let $value = other; $bytes },
+                    concat!("constant-time comparison of ", stringify!($type), " doesn't match its bytes")
+                );
+                equal
+            }
+        }
+
+        impl Eq for $type {}
+    };
+}
+
+pub(crate) use impl_secret_eq;
+
+#[cfg(test)]
+mod tests {
+    use curve25519_dalek::RistrettoPoint;
+    use rand::rngs::OsRng;
+    use crate::crypto::elgamal::{PedersenOpening, PrivateKey};
+    use super::*;
+
+    #[test]
+    fn test_eq() {
+        let a = Scalar::random(&mut OsRng);
+        let b = Scalar::random(&mut OsRng);
+        assert!(eq(&a, &a));
+        assert!(!eq(&a, &b));
+
+        let p = RistrettoPoint::random(&mut OsRng);
+        assert!(eq(&p.compress(), &p.compress()));
+        assert!(!eq(&p, &RistrettoPoint::random(&mut OsRng)));
+
+        assert!(eq_bytes(b"secret", b"secret"));
+        assert!(!eq_bytes(b"secret", b"secreT"));
+        assert!(!eq_bytes(b"secret", b"secrets"));
+    }
+
+    #[test]
+    fn test_select() {
+        assert_eq!(select(&1u64, &2u64, false), 1);
+        assert_eq!(select(&1u64, &2u64, true), 2);
+        assert!(is_zero(&Scalar::ZERO));
+        assert!(!is_zero(&Scalar::ONE));
+    }
+
+    #[test]
+    fn test_secret_eq() {
+        let key = PrivateKey::from_scalar(Scalar::ONE);
+        assert!(eq(&key, &key.duplicate()));
+        assert!(!eq(&key, &PrivateKey::from_scalar(Scalar::from(2u64))));
+
+        let opening = PedersenOpening::generate_new();
+        assert!(eq(&opening, &PedersenOpening::from_scalar(opening.as_scalar())));
+    }
+
+    #[test]
+    fn test_secret_partial_eq() {
+        let key = PrivateKey::from_scalar(Scalar::ONE);
+        assert!(key == key.duplicate());
+        assert!(key != PrivateKey::from_scalar(Scalar::from(2u64)));
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+#[cfg(not(all(
+    target_arch = "wasm32",
+    target_vendor = "unknown",
+    target_os = "unknown"
+)))]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use curve25519_dalek::{ristretto::CompressedRistretto, RistrettoPoint};
+use crate::metrics::{
+    increment_counter,
+    HistogramTimer,
+    ECDLP_DECODED_POINTS,
+    ECDLP_DECODE_SECONDS
+};
+
+// Re-export the curve25519-dalek ecdlp module
+pub use curve25519_dalek::ecdlp::*;
+
+// Decode several points in the range [0, max]
+// This is not a batched search: each unique point is decoded on its own using `decode`,
+// the ecdlp API of the curve25519-dalek fork has no shared baby-step lookup to reuse.
+// Only the duplicated work is saved: identical points, common when decoding
+// many versions of the same balance, are decoded once
+// If threads is greater than 1, scoped threads pick the next unique point to decode
+// so a slow decoding doesn't stall the others
+// Results are returned in the same order as the points
+pub fn decode_batch(tables: &ECDLPTablesFileView, points: &[RistrettoPoint], max: i64, threads: usize) -> Vec<Option<i64>> {
+    let _timer = HistogramTimer::new(ECDLP_DECODE_SECONDS);
+    increment_counter(ECDLP_DECODED_POINTS, points.len() as u64);
+
+    // Deduplicate the points and keep the index of each one in the unique list
+    let mut unique = Vec::new();
+    let mut positions: HashMap<CompressedRistretto, usize> = HashMap::new();
+    let indexes: Vec<usize> = points.iter()
+        .map(|point| *positions.entry(point.compress())
+            .or_insert_with(|| {
+                unique.push(*point);
+                unique.len() - 1
+            })
+        )
+        .collect();
+
+    let decoded = decode_unique(tables, &unique, max, threads);
+    indexes.into_iter()
+        .map(|index| decoded[index])
+        .collect()
+}
+
+fn decode_point(tables: &ECDLPTablesFileView, point: RistrettoPoint, max: i64) -> Option<i64> {
+    decode(tables, point, ECDLPArguments::new_with_range(0, max))
+}
+
+fn decode_sequential(tables: &ECDLPTablesFileView, points: &[RistrettoPoint], max: i64) -> Vec<Option<i64>> {
+    points.iter()
+        .map(|point| decode_point(tables, *point, max))
+        .collect()
+}
+
+#[cfg(not(all(
+    target_arch = "wasm32",
+    target_vendor = "unknown",
+    target_os = "unknown"
+)))]
+fn decode_unique(tables: &ECDLPTablesFileView, points: &[RistrettoPoint], max: i64, threads: usize) -> Vec<Option<i64>> {
+    let threads = threads.min(points.len());
+    if threads <= 1 {
+        return decode_sequential(tables, points, max)
+    }
+
+    let next = AtomicUsize::new(0);
+    let results = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| scope.spawn(|| {
+                let mut results = Vec::new();
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(point) = points.get(index) else {
+                        break;
+                    };
+                    results.push((index, decode_point(tables, *point, max)));
+                }
+
+                results
+            }))
+            .collect();
+
+        handles.into_iter()
+            .flat_map(|handle| handle.join().expect("ecdlp decoding thread panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    let mut decoded = vec![None; points.len()];
+    for (index, value) in results {
+        decoded[index] = value;
+    }
+
+    decoded
+}
+
+// Threads are not available in wasm
+#[cfg(all(
+    target_arch = "wasm32",
+    target_vendor = "unknown",
+    target_os = "unknown"
+))]
+fn decode_unique(tables: &ECDLPTablesFileView, points: &[RistrettoPoint], max: i64, _: usize) -> Vec<Option<i64>> {
+    decode_sequential(tables, points, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+    use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_POINT, Scalar};
+    use super::*;
+
+    struct NoReport;
+
+    impl ProgressTableGenerationReportFunction for NoReport {
+        fn report(&self, _: f64, _: ReportStep) -> ControlFlow<()> {
+            ControlFlow::Continue(())
+        }
+    }
+
+    const MAX: i64 = 1 << 28;
+
+    fn points(values: &[u64]) -> Vec<RistrettoPoint> {
+        values.iter()
+            .map(|value| Scalar::from(*value) * RISTRETTO_BASEPOINT_POINT)
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_batch_matches_decode() {
+        let tables = ECDLPTables::generate_with_progress_report(13, NoReport).unwrap();
+        let view = tables.view();
+        // Duplicated values and a value out of range
+        let values = [0, 1, 42, 1_000_000, 42, 0, 123_456_789, 1, 1_000_000, MAX as u64 + 1];
+        let points = points(&values);
+
+        let expected: Vec<Option<i64>> = points.iter()
+            .map(|point| decode(&view, *point, ECDLPArguments::new_with_range(0, MAX)))
+            .collect();
+        assert_eq!(expected[..9], values[..9].iter().map(|v| Some(*v as i64)).collect::<Vec<_>>());
+        assert_eq!(expected[9], None);
+
+        for threads in [0, 1, 3, 16] {
+            assert_eq!(decode_batch(&view, &points, MAX, threads), expected);
+        }
+    }
+
+    #[test]
+    fn test_decode_batch_empty() {
+        let tables = ECDLPTables::generate_with_progress_report(13, NoReport).unwrap();
+        assert!(decode_batch(&tables.view(), &[], MAX, 4).is_empty());
+    }
+}
@@ -0,0 +1,295 @@
+use curve25519_dalek::{ristretto::CompressedRistretto, RistrettoPoint, Scalar};
+use rand::rngs::OsRng;
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use crate::{
+    crypto::{
+        ct::{self, impl_secret_eq, Choice, ConstantTimeEq},
+        proofs::H_TABLE
+    },
+    encoding::{decode_hex, encode_hex, impl_hex_display},
+    serializer::{
+        Reader,
+        ReaderError,
+        Serializer,
+        Writer
+    }
+};
+use super::{
+    derive_nonce,
+    hash_and_point_to_scalar,
+    KeyPair,
+    PublicKey,
+    Signature,
+    RISTRETTO_COMPRESSED_SIZE,
+    SCALAR_SIZE
+};
+
+pub const ADAPTOR_SIGNATURE_SIZE: usize = SCALAR_SIZE * 2;
+
+// Adaptor signatures are used for atomic swaps
+//
+// The adaptor secret t is chosen by one party, which shares the adaptor point T = t * H.
+// A pre-signature is created for the nonce R + T, so it is only a valid signature
+// once completed with t. Publishing the completed signature reveals t
+// to the holder of the pre-signature, which can then complete its own side of the swap.
+// The other chain must use an adaptor point over the same secret
+// (a Ristretto based chain, or a cross-group proof of discrete log equality).
+
+// Secret of the adaptor point, wiped from memory once dropped
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct AdaptorSecret(Scalar);
+
+// Point shared with the counterparty to create a pre-signature
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdaptorPoint(RistrettoPoint);
+
+// Signature that can only be completed with the adaptor secret
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdaptorSignature {
+    s: Scalar,
+    e: Scalar,
+}
+
+impl AdaptorSecret {
+    // Generate a new random adaptor secret
+    pub fn generate_new() -> Self {
+        Self(Scalar::random(&mut OsRng))
+    }
+
+    // Returns the secret as a scalar
+    pub fn as_scalar(&self) -> &Scalar {
+        &self.0
+    }
+
+    // Adaptor point to share with the counterparty
+    pub fn to_point(&self) -> AdaptorPoint {
+        AdaptorPoint(&self.0 * &*H_TABLE)
+    }
+}
+
+impl AdaptorPoint {
+    pub fn as_point(&self) -> &RistrettoPoint {
+        &self.0
+    }
+}
+
+impl AdaptorSignature {
+    pub fn new(s: Scalar, e: Scalar) -> Self {
+        Self { s, e }
+    }
+
+    // Create a pre-signature of the message for the adaptor point
+    // The adaptor point is part of the nonce derivation, as the challenge depends on it
+    pub fn sign(keypair: &KeyPair, message: &[u8], adaptor: &AdaptorPoint) -> Self {
+        let key = keypair.get_public_key().compress();
+        let mut k = derive_nonce(keypair.get_private_key(), &key, message, adaptor.0.compress().as_bytes());
+        let r = &k * &*H_TABLE;
+        let e = hash_and_point_to_scalar(&key, message, &(r + adaptor.0));
+        let s = keypair.get_private_key().as_scalar().invert() * e + k;
+        k.zeroize();
+        Self::new(s, e)
+    }
+
+    // Verify the pre-signature using the Public Key, the message and the adaptor point
+    // A valid pre-signature is completed into a valid signature using the adaptor secret
+    pub fn verify(&self, message: &[u8], key: &PublicKey, adaptor: &AdaptorPoint) -> bool {
+        let r = &self.s * &*H_TABLE + key.as_point() * -self.e;
+        let calculated = hash_and_point_to_scalar(&key.compress(), message, &(r + adaptor.0));
+        ct::eq(&self.e, &calculated)
+    }
+
+    // Complete the pre-signature using the adaptor secret
+    pub fn complete(&self, secret: &AdaptorSecret) -> Signature {
+        Signature::new(self.s + secret.0, self.e)
+    }
+
+    // Extract the adaptor secret from the completed signature
+    // Returns None if the signature wasn't completed from this pre-signature
+    pub fn extract_secret(&self, signature: &Signature, adaptor: &AdaptorPoint) -> Option<AdaptorSecret> {
+        if !ct::eq(&signature.e, &self.e) {
+            return None
+        }
+
+        let secret = AdaptorSecret(signature.s - self.s);
+        if !ct::eq(&secret.to_point().0, &adaptor.0) {
+            return None
+        }
+
+        Some(secret)
+    }
+}
+
+impl ConstantTimeEq for AdaptorSecret {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl_secret_eq!(AdaptorSecret, |secret| secret.0.as_bytes());
+
+impl Serializer for AdaptorSecret {
+    fn write(&self, writer: &mut Writer) {
+        self.0.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        Ok(Self(Scalar::read(reader)?))
+    }
+
+    fn size(&self) -> usize {
+        SCALAR_SIZE
+    }
+}
+
+impl Serializer for AdaptorPoint {
+    fn write(&self, writer: &mut Writer) {
+        self.0.compress().write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let point = CompressedRistretto::read(reader)?
+            .decompress()
+            .ok_or(ReaderError::InvalidValue)?;
+        Ok(Self(point))
+    }
+
+    fn size(&self) -> usize {
+        RISTRETTO_COMPRESSED_SIZE
+    }
+}
+
+impl Serializer for AdaptorSignature {
+    fn write(&self, writer: &mut Writer) {
+        self.s.write(writer);
+        self.e.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let s = Scalar::read(reader)?;
+        let e = Scalar::read(reader)?;
+        Ok(Self::new(s, e))
+    }
+
+    fn size(&self) -> usize {
+        ADAPTOR_SIGNATURE_SIZE
+    }
+}
+
+impl Serialize for AdaptorSecret {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let hex = Zeroizing::new(self.to_hex());
+        serializer.serialize_str(&hex)
+    }
+}
+
+impl<'a> Deserialize<'a> for AdaptorSecret {
+    fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = Zeroizing::new(String::deserialize(deserializer)?);
+        AdaptorSecret::from_hex(&hex).map_err(D::Error::custom)
+    }
+}
+
+impl_hex_display!(AdaptorPoint);
+impl_hex_display!(AdaptorSignature);
+
+impl Serialize for AdaptorPoint {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode_hex(self))
+    }
+}
+
+impl<'a> Deserialize<'a> for AdaptorPoint {
+    fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        decode_hex(&hex).map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for AdaptorSignature {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode_hex(self))
+    }
+}
+
+impl<'a> Deserialize<'a> for AdaptorSignature {
+    fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        decode_hex(&hex).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptor_signature() {
+        let keypair = KeyPair::new();
+        let secret = AdaptorSecret::generate_new();
+        let adaptor = secret.to_point();
+
+        let message = b"atomic swap";
+        let pre_signature = AdaptorSignature::sign(&keypair, message, &adaptor);
+        assert!(pre_signature.verify(message, keypair.get_public_key(), &adaptor));
+
+        // Not a valid signature until completed
+        let incomplete = Signature::new(pre_signature.s, pre_signature.e);
+        assert!(!incomplete.verify(message, keypair.get_public_key()));
+
+        let signature = pre_signature.complete(&secret);
+        assert!(signature.verify(message, keypair.get_public_key()));
+
+        let extracted = pre_signature.extract_secret(&signature, &adaptor).unwrap();
+        assert_eq!(extracted.as_scalar(), secret.as_scalar());
+    }
+
+    #[test]
+    fn test_adaptor_signature_wrong_adaptor() {
+        let keypair = KeyPair::new();
+        let adaptor = AdaptorSecret::generate_new().to_point();
+        let other = AdaptorSecret::generate_new();
+
+        let message = b"atomic swap";
+        let pre_signature = AdaptorSignature::sign(&keypair, message, &adaptor);
+        assert!(!pre_signature.verify(message, keypair.get_public_key(), &other.to_point()));
+
+        // Completing with another secret doesn't produce a valid signature
+        let signature = pre_signature.complete(&other);
+        assert!(!signature.verify(message, keypair.get_public_key()));
+        assert!(pre_signature.extract_secret(&signature, &adaptor).is_none());
+    }
+
+    #[test]
+    fn test_adaptor_signature_nonce() {
+        let keypair = KeyPair::new();
+        let adaptor = AdaptorSecret::generate_new().to_point();
+        let other = AdaptorSecret::generate_new().to_point();
+
+        // The same message signed for two adaptor points must not reuse the nonce
+        let first = AdaptorSignature::sign(&keypair, b"swap", &adaptor);
+        let second = AdaptorSignature::sign(&keypair, b"swap", &other);
+        assert_eq!(first, AdaptorSignature::sign(&keypair, b"swap", &adaptor));
+
+        let k1 = first.s - keypair.get_private_key().as_scalar().invert() * first.e;
+        let k2 = second.s - keypair.get_private_key().as_scalar().invert() * second.e;
+        assert_ne!(k1, k2);
+    }
+
+    #[test]
+    fn test_adaptor_serde() {
+        let keypair = KeyPair::new();
+        let secret = AdaptorSecret::generate_new();
+        let adaptor = secret.to_point();
+        let pre_signature = AdaptorSignature::sign(&keypair, b"swap", &adaptor);
+
+        let json = serde_json::to_string(&pre_signature).unwrap();
+        assert_eq!(serde_json::from_str::<AdaptorSignature>(&json).unwrap(), pre_signature);
+
+        let json = serde_json::to_string(&adaptor).unwrap();
+        assert_eq!(serde_json::from_str::<AdaptorPoint>(&json).unwrap(), adaptor);
+
+        let json = serde_json::to_string(&secret).unwrap();
+        assert_eq!(serde_json::from_str::<AdaptorSecret>(&json).unwrap().as_scalar(), secret.as_scalar());
+    }
+}
@@ -1,8 +1,25 @@
-use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+use std::{
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, Sub, SubAssign}
+};
 
 use curve25519_dalek::{traits::Identity, RistrettoPoint, Scalar};
 use serde::{Deserialize, Deserializer, Serialize};
-use super::{pedersen::{DecryptHandle, PedersenCommitment}, CompressedCiphertext, CompressedCommitment, CompressedHandle};
+use crate::{
+    encoding::impl_hex_display,
+    serializer::{Reader, ReaderError, Serializer, Writer}
+};
+use super::{
+    pedersen::{DecryptHandle, PedersenCommitment},
+    CompressedCiphertext,
+    CompressedCommitment,
+    CompressedHandle,
+    DecompressionError,
+    RISTRETTO_COMPRESSED_SIZE
+};
+
+// Size in bytes of a serialized ciphertext
+pub const CIPHERTEXT_SIZE: usize = RISTRETTO_COMPRESSED_SIZE * 2;
 
 // Represents a twisted ElGamal Ciphertext
 // One part is a Pedersen commitment to be bulletproofs compatible
@@ -44,6 +61,80 @@ impl Ciphertext {
             CompressedHandle::new(self.handle.as_point().compress())
         )
     }
+
+    // Homomorphic operations without consuming the ciphertext
+    // The amounts are not checked: the result of a subtraction going below zero
+    // can't be decrypted in the usual range and must be prevented by the caller
+
+    // Add a plaintext amount to the encrypted amount
+    pub fn add_amount(&self, amount: u64) -> Self {
+        self.clone() + amount
+    }
+
+    // Subtract a plaintext amount from the encrypted amount
+    pub fn sub_amount(&self, amount: u64) -> Self {
+        self.clone() - amount
+    }
+
+    // Add the encrypted amount of another ciphertext of the same public key
+    pub fn add_ciphertext(&self, other: &Self) -> Self {
+        self.clone() + other
+    }
+
+    // Subtract the encrypted amount of another ciphertext of the same public key
+    pub fn sub_ciphertext(&self, other: &Self) -> Self {
+        self.clone() - other
+    }
+
+    // Compress several ciphertexts
+    pub fn compress_batch(ciphertexts: &[Self]) -> Vec<CompressedCiphertext> {
+        ciphertexts.iter().map(Self::compress).collect()
+    }
+
+    // Decompress several ciphertexts, fails if any of them is invalid
+    pub fn decompress_batch(ciphertexts: &[CompressedCiphertext]) -> Result<Vec<Self>, DecompressionError> {
+        ciphertexts.iter().map(CompressedCiphertext::decompress).collect()
+    }
+
+    // Canonical encoding of the ciphertext: compressed commitment followed by compressed handle
+    pub fn to_canonical_bytes(&self) -> [u8; CIPHERTEXT_SIZE] {
+        self.compress().to_bytes()
+    }
+
+    // Decode a ciphertext from its canonical encoding
+    // Non canonical point encodings are rejected
+    pub fn from_canonical_bytes(bytes: &[u8; CIPHERTEXT_SIZE]) -> Result<Self, DecompressionError> {
+        Self::from_bytes(bytes).map_err(|_| DecompressionError)
+    }
+}
+
+impl Sum for Ciphertext {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, ciphertext| acc + ciphertext)
+    }
+}
+
+impl<'a> Sum<&'a Ciphertext> for Ciphertext {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, ciphertext| acc + ciphertext)
+    }
+}
+
+// Serialized using the canonical encoding
+impl Serializer for Ciphertext {
+    fn write(&self, writer: &mut Writer) {
+        self.compress().write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        CompressedCiphertext::read(reader)?
+            .decompress()
+            .map_err(|_| ReaderError::InvalidValue)
+    }
+
+    fn size(&self) -> usize {
+        CIPHERTEXT_SIZE
+    }
 }
 
 // ADD TRAITS
@@ -270,6 +361,8 @@ impl SubAssign<&Scalar> for Ciphertext {
     }
 }
 
+impl_hex_display!(Ciphertext);
+
 impl Serialize for Ciphertext {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -334,4 +427,43 @@ mod tests {
 
         assert_eq!(dec, Scalar::from(10000u64) * PC_GENS.B);
     }
+
+    #[test]
+    fn test_amount_ops() {
+        let keypair = KeyPair::new();
+        let ct = keypair.get_public_key().encrypt(5000u64);
+
+        let added = ct.add_amount(250);
+        assert_eq!(keypair.decrypt_to_point(&added), Scalar::from(5250u64) * PC_GENS.B);
+
+        let subtracted = added.sub_ciphertext(&ct).sub_amount(50);
+        assert_eq!(keypair.decrypt_to_point(&subtracted), Scalar::from(200u64) * PC_GENS.B);
+    }
+
+    #[test]
+    fn test_sum() {
+        let keypair = KeyPair::new();
+        let ciphertexts: Vec<Ciphertext> = (1..=4u64)
+            .map(|amount| keypair.get_public_key().encrypt(amount))
+            .collect();
+
+        let total: Ciphertext = ciphertexts.iter().sum();
+        assert_eq!(keypair.decrypt_to_point(&total), Scalar::from(10u64) * PC_GENS.B);
+    }
+
+    #[test]
+    fn test_canonical_bytes() {
+        let keypair = KeyPair::new();
+        let ciphertexts = vec![keypair.get_public_key().encrypt(10u64), Ciphertext::zero()];
+
+        let compressed = Ciphertext::compress_batch(&ciphertexts);
+        assert_eq!(Ciphertext::decompress_batch(&compressed).unwrap(), ciphertexts);
+
+        let bytes = ciphertexts[0].to_canonical_bytes();
+        assert_eq!(bytes.to_vec(), ciphertexts[0].to_bytes());
+        assert_eq!(Ciphertext::from_canonical_bytes(&bytes).unwrap(), ciphertexts[0]);
+
+        // Not a valid point encoding
+        assert!(Ciphertext::from_canonical_bytes(&[0xff; CIPHERTEXT_SIZE]).is_err());
+    }
 }
\ No newline at end of file
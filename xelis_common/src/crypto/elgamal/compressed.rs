@@ -1,7 +1,7 @@
 use curve25519_dalek::{ristretto::CompressedRistretto, Scalar};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use crate::{api::DataElement, crypto::{Address, AddressType}, serializer::{Reader, ReaderError, Serializer, Writer}};
+use crate::{api::DataElement, crypto::{Address, AddressType}, encoding::impl_hex_display, serializer::{Reader, ReaderError, Serializer, Writer}};
 use super::{Ciphertext, DecryptHandle, PedersenCommitment, PublicKey};
 
 // Compressed point size in bytes
@@ -218,6 +218,11 @@ impl Serializer for CompressedCiphertext {
     }
 }
 
+impl_hex_display!(CompressedCommitment);
+impl_hex_display!(CompressedHandle);
+impl_hex_display!(CompressedPublicKey);
+impl_hex_display!(CompressedCiphertext);
+
 impl Serializer for Scalar {
     fn write(&self, writer: &mut Writer) {
         writer.write_bytes(self.as_bytes());
@@ -234,6 +239,40 @@ impl Serializer for Scalar {
     }
 }
 
+// Points are generated from any bytes, they may not be valid on the curve
+// as the decompression is only done during the verification
+#[cfg(any(test, feature = "arbitrary"))]
+pub(crate) fn arbitrary_point(u: &mut arbitrary::Unstructured) -> arbitrary::Result<CompressedRistretto> {
+    Ok(CompressedRistretto(u.arbitrary()?))
+}
+
+// Scalars must be canonical to be read back
+#[cfg(any(test, feature = "arbitrary"))]
+pub(crate) fn arbitrary_scalar(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Scalar> {
+    Ok(Scalar::from_bytes_mod_order(u.arbitrary()?))
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for CompressedCommitment {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        arbitrary_point(u).map(Self)
+    }
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for CompressedHandle {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        arbitrary_point(u).map(Self)
+    }
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for CompressedPublicKey {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        arbitrary_point(u).map(Self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
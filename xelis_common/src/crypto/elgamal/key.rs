@@ -1,16 +1,24 @@
 use curve25519_dalek::{
-    ecdlp::{self, ECDLPArguments, ECDLPTablesFileView},
     ristretto::RistrettoPoint,
     Scalar
 };
 use rand::rngs::OsRng;
 use serde::{Deserialize, Deserializer, Serialize};
-use zeroize::Zeroize;
+use sha3::{Digest, Sha3_512};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 use crate::{
     api::DataElement,
     config::MAXIMUM_SUPPLY,
+    metrics::{
+        increment_counter,
+        HistogramTimer,
+        ECDLP_DECODED_POINTS,
+        ECDLP_DECODE_SECONDS
+    },
     crypto::{
-        proofs::PC_GENS,
+        ct::{self, impl_secret_eq, Choice, ConstantTimeEq},
+        ecdlp::{self, ECDLPArguments, ECDLPTablesFileView},
+        proofs::H_TABLE,
         Address,
         AddressType
     },
@@ -23,19 +31,26 @@ use crate::{
 };
 use super::{
     ciphertext::Ciphertext,
+    derive_nonce,
     hash_and_point_to_scalar,
     pedersen::{DecryptHandle, PedersenCommitment, PedersenOpening},
     CompressedPublicKey,
     Signature
 };
 
+// Domain separator used to derive the sub-accounts keys
+const SUB_ACCOUNT_DOMAIN: &[u8] = b"xelis-sub-account";
+
 #[derive(Clone)]
 pub struct PublicKey(RistrettoPoint);
 
-#[derive(Clone, Zeroize)]
+// Private key is wiped from memory once dropped
+// It doesn't implement Clone to prevent copies of it being made by mistake,
+// use `PrivateKey::duplicate` when a copy is really needed
+#[derive(Zeroize, ZeroizeOnDrop)]
 pub struct PrivateKey(Scalar);
 
-#[derive(Clone)]
+// KeyPair is wiped from memory once dropped through its private key
 pub struct KeyPair {
     public_key: PublicKey,
     private_key: PrivateKey,
@@ -52,9 +67,9 @@ impl PublicKey {
     // Private key must not be zero
     pub fn new(secret: &PrivateKey) -> Self {
         let s = &secret.0;
-        assert!(s != &Scalar::ZERO);
+        assert!(!ct::is_zero(s));
 
-        Self(s.invert() * PC_GENS.B_blinding)
+        Self(&s.invert() * &*H_TABLE)
     }
 
     // Encrypt an amount to a Ciphertext
@@ -103,16 +118,51 @@ impl PrivateKey {
     // Create a new private key from a scalar
     // The scalar must not be zero
     pub fn from_scalar(scalar: Scalar) -> Self {
-        assert!(scalar != Scalar::ZERO);
+        assert!(!ct::is_zero(&scalar));
 
         Self(scalar)
     }
 
+    // Create a private key by reducing 64 bytes modulo the group order
+    // Used to derive a private key from the output of a hash function
+    pub fn from_wide_bytes(bytes: &[u8; 64]) -> Self {
+        Self::from_scalar(Scalar::from_bytes_mod_order_wide(bytes))
+    }
+
     // Returns the private key as a scalar
     pub fn as_scalar(&self) -> &Scalar {
         &self.0
     }
 
+    // Explicit copy of the private key, also wiped from memory once dropped
+    pub fn duplicate(&self) -> Self {
+        Self(self.0)
+    }
+
+    // Serialized private key that is wiped from memory once dropped
+    pub fn to_zeroizing_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.to_bytes())
+    }
+
+    // Derive deterministically the private key of a sub-account
+    // Index 0 is the account itself
+    pub fn derive_sub_account(&self, index: u32) -> Self {
+        if index == 0 {
+            return self.duplicate()
+        }
+
+        let mut hasher = Sha3_512::new();
+        hasher.update(SUB_ACCOUNT_DOMAIN);
+        hasher.update(self.0.as_bytes());
+        hasher.update(index.to_be_bytes());
+
+        let mut bytes: [u8; 64] = hasher.finalize().try_into().unwrap();
+        let key = Self::from_wide_bytes(&bytes);
+        bytes.zeroize();
+
+        key
+    }
+
     // Decrypt a Ciphertext to a point
     pub fn decrypt_to_point(&self, ciphertext: &Ciphertext) -> RistrettoPoint {
         let commitment = ciphertext.commitment().as_point();
@@ -123,6 +173,8 @@ impl PrivateKey {
 
     // Decode a point to a u64 with precomputed tables
     pub fn decode_point(&self, precomputed_tables: &ECDLPTablesFileView, point: RistrettoPoint) -> Option<u64> {
+        let _timer = HistogramTimer::new(ECDLP_DECODE_SECONDS);
+        increment_counter(ECDLP_DECODED_POINTS, 1);
         ecdlp::decode(precomputed_tables, point, ECDLPArguments::new_with_range(0, MAXIMUM_SUPPLY as i64))
             .map(|x| x as u64)
     }
@@ -132,6 +184,19 @@ impl PrivateKey {
         let point = self.decrypt_to_point(ciphertext);
         self.decode_point(precomputed_tables, point)
     }
+
+    // Decrypt several Ciphertexts at once with precomputed tables
+    // See `ecdlp::decode_batch`, results are in the same order as the ciphertexts
+    pub fn decrypt_batch(&self, precomputed_tables: &ECDLPTablesFileView, ciphertexts: &[Ciphertext], threads: usize) -> Vec<Option<u64>> {
+        let points: Vec<RistrettoPoint> = ciphertexts.iter()
+            .map(|ciphertext| self.decrypt_to_point(ciphertext))
+            .collect();
+
+        ecdlp::decode_batch(precomputed_tables, &points, MAXIMUM_SUPPLY as i64, threads)
+            .into_iter()
+            .map(|value| value.map(|x| x as u64))
+            .collect()
+    }
 }
 
 impl KeyPair {
@@ -165,16 +230,24 @@ impl KeyPair {
         self.private_key.decrypt(precomputed_tables, ciphertext)
     }
 
+    // Decrypt several Ciphertexts at once with precomputed tables
+    pub fn decrypt_batch(&self, precomputed_tables: &ECDLPTablesFileView, ciphertexts: &[Ciphertext], threads: usize) -> Vec<Option<u64>> {
+        self.private_key.decrypt_batch(precomputed_tables, ciphertexts, threads)
+    }
+
     pub fn decrypt_to_point(&self, ciphertext: &Ciphertext) -> RistrettoPoint {
         self.private_key.decrypt_to_point(ciphertext)
     }
 
     // Sign a message with the private key
+    // The nonce is derived deterministically from the private key and the message
     pub fn sign(&self, message: &[u8]) -> Signature {
-        let k = Scalar::random(&mut OsRng);
-        let r = k * PC_GENS.B_blinding;
-        let e = hash_and_point_to_scalar(&self.public_key.compress(), message, &r);
+        let key = self.public_key.compress();
+        let mut k = derive_nonce(&self.private_key, &key, message, &[]);
+        let r = &k * &*H_TABLE;
+        let e = hash_and_point_to_scalar(&key, message, &r);
         let s = self.private_key.as_scalar().invert() * e + k;
+        k.zeroize();
         Signature::new(s, e)
     }
 
@@ -194,6 +267,14 @@ impl KeyPair {
     }
 }
 
+impl ConstantTimeEq for PrivateKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl_secret_eq!(PrivateKey, |key| key.0.as_bytes());
+
 impl Serializer for PrivateKey {
     fn write(&self, writer: &mut Writer) {
         self.0.write(writer);
@@ -211,13 +292,14 @@ impl Serializer for PrivateKey {
 
 impl Serialize for PrivateKey {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        serializer.serialize_str(&self.to_hex())
+        let hex = Zeroizing::new(self.to_hex());
+        serializer.serialize_str(&hex)
     }
 }
 
 impl<'a> Deserialize<'a> for PrivateKey {
     fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Self, D::Error> {
-        let hex = String::deserialize(deserializer)?;
+        let hex = Zeroizing::new(String::deserialize(deserializer)?);
         PrivateKey::from_hex(&hex).map_err(serde::de::Error::custom)
     }
 }
@@ -226,6 +308,7 @@ impl<'a> Deserialize<'a> for PrivateKey {
 mod tests {
     use curve25519_dalek::traits::Identity;
 
+    use crate::crypto::proofs::{G_TABLE, PC_GENS};
     use super::*;
     use super::super::G;
 
@@ -239,6 +322,37 @@ mod tests {
         assert!(signature.verify(message, public_key));
     }
 
+    #[test]
+    fn test_deterministic_signature() {
+        let keypair = KeyPair::new();
+        let signature = keypair.sign(b"Hello, world!");
+
+        // Same key and message always give the same nonce
+        assert_eq!(signature, keypair.sign(b"Hello, world!"));
+        assert_ne!(signature, keypair.sign(b"Hello, world?"));
+        assert_ne!(signature, KeyPair::new().sign(b"Hello, world!"));
+    }
+
+    #[test]
+    fn test_fixed_base_tables() {
+        let scalar = Scalar::random(&mut OsRng);
+        assert_eq!(&scalar * &*H_TABLE, scalar * PC_GENS.B_blinding);
+        assert_eq!(&scalar * G_TABLE, scalar * PC_GENS.B);
+    }
+
+    #[test]
+    fn test_derive_sub_account() {
+        let keypair = KeyPair::new();
+        let private_key = keypair.get_private_key();
+
+        assert_eq!(private_key.derive_sub_account(0).as_scalar(), private_key.as_scalar());
+
+        let first = private_key.derive_sub_account(1);
+        assert_eq!(first.as_scalar(), private_key.derive_sub_account(1).as_scalar());
+        assert_ne!(first.as_scalar(), private_key.as_scalar());
+        assert_ne!(first.as_scalar(), private_key.derive_sub_account(2).as_scalar());
+    }
+
     #[test]
     fn test_encrypt_decrypt() {
         let keypair = KeyPair::new();
@@ -3,11 +3,13 @@ mod ciphertext;
 mod key;
 mod signature;
 mod pedersen;
+mod adaptor;
 
 pub use compressed::*;
-pub use ciphertext::Ciphertext;
+pub use ciphertext::{Ciphertext, CIPHERTEXT_SIZE};
 pub use key::*;
 pub use pedersen::*;
 pub use signature::*;
+pub use adaptor::*;
 
 pub use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT as G;
\ No newline at end of file
@@ -1,10 +1,15 @@
-use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Sub, SubAssign}
+};
 
 use curve25519_dalek::{RistrettoPoint, Scalar};
 use rand::rngs::OsRng;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::crypto::{
-    proofs::PC_GENS,
+    ct::{impl_secret_eq, Choice, ConstantTimeEq},
+    proofs::{G_TABLE, H_TABLE},
     elgamal::{
         key::PublicKey,
         CompressedCommitment,
@@ -12,7 +17,9 @@ use crate::crypto::{
     }
 };
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+// Opening is wiped from memory once dropped
+// It can't be cloned and its Debug output never shows the scalar
+#[derive(Zeroize, ZeroizeOnDrop)]
 pub struct PedersenOpening(Scalar);
 
 impl PedersenOpening {
@@ -29,6 +36,20 @@ impl PedersenOpening {
     }
 }
 
+impl ConstantTimeEq for PedersenOpening {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl_secret_eq!(PedersenOpening, |opening| opening.0.as_bytes());
+
+impl fmt::Debug for PedersenOpening {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PedersenOpening(<redacted>)")
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PedersenCommitment(RistrettoPoint);
 
@@ -48,7 +69,7 @@ impl PedersenCommitment {
         let x: Scalar = amount.into();
         let r = opening.as_scalar();
 
-        Self(PC_GENS.commit(x, r))
+        Self(&x * G_TABLE + &r * &*H_TABLE)
     }
 
     pub fn as_point(&self) -> &RistrettoPoint {
@@ -108,7 +129,7 @@ impl Add<Scalar> for PedersenCommitment {
     type Output = Self;
 
     fn add(self, rhs: Scalar) -> Self {
-        Self(self.0 + (rhs * PC_GENS.B))
+        Self(self.0 + (&rhs * G_TABLE))
     }
 }
 
@@ -116,7 +137,7 @@ impl Add<&Scalar> for PedersenCommitment {
     type Output = Self;
 
     fn add(self, rhs: &Scalar) -> Self {
-        Self(self.0 + (rhs * PC_GENS.B))
+        Self(self.0 + (rhs * G_TABLE))
     }
 }
 
@@ -152,13 +173,13 @@ impl AddAssign<&PedersenCommitment> for PedersenCommitment {
 
 impl AddAssign<Scalar> for PedersenCommitment {
     fn add_assign(&mut self, rhs: Scalar) {
-        self.0 += rhs * PC_GENS.B;
+        self.0 += &rhs * G_TABLE;
     }
 }
 
 impl AddAssign<&Scalar> for PedersenCommitment {
     fn add_assign(&mut self, rhs: &Scalar) {
-        self.0 += rhs * PC_GENS.B;
+        self.0 += rhs * G_TABLE;
     }
 }
 
@@ -196,7 +217,7 @@ impl Sub<Scalar> for PedersenCommitment {
     type Output = Self;
 
     fn sub(self, rhs: Scalar) -> Self {
-        Self(self.0 - rhs * PC_GENS.B)
+        Self(self.0 - &rhs * G_TABLE)
     }
 }
 
@@ -204,7 +225,7 @@ impl Sub<&Scalar> for PedersenCommitment {
     type Output = Self;
 
     fn sub(self, rhs: &Scalar) -> Self {
-        Self(self.0 - rhs * PC_GENS.B)
+        Self(self.0 - rhs * G_TABLE)
     }
 }
 
@@ -240,13 +261,13 @@ impl SubAssign<&PedersenCommitment> for PedersenCommitment {
 
 impl SubAssign<Scalar> for PedersenCommitment {
     fn sub_assign(&mut self, rhs: Scalar) {
-        self.0 -= rhs * PC_GENS.B;
+        self.0 -= &rhs * G_TABLE;
     }
 }
 
 impl SubAssign<&Scalar> for PedersenCommitment {
     fn sub_assign(&mut self, rhs: &Scalar) {
-        self.0 -= rhs * PC_GENS.B;
+        self.0 -= rhs * G_TABLE;
     }
 }
 
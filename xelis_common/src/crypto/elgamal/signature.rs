@@ -2,7 +2,8 @@ use curve25519_dalek::{RistrettoPoint, Scalar};
 use serde::{de::Error, Serialize};
 use sha3::{Digest, Sha3_512};
 use crate::{
-    crypto::proofs::PC_GENS,
+    crypto::{ct, proofs::H_TABLE},
+    encoding::{decode_hex, encode_hex, impl_hex_display},
     serializer::{
         Reader,
         ReaderError,
@@ -10,14 +11,18 @@ use crate::{
         Writer
     }
 };
-use super::{CompressedPublicKey, PublicKey, SCALAR_SIZE};
+use zeroize::Zeroize;
+use super::{CompressedPublicKey, PrivateKey, PublicKey, SCALAR_SIZE};
 
 pub const SIGNATURE_SIZE: usize = SCALAR_SIZE * 2;
 
+// Domain separator used to derive the signing nonces
+const NONCE_DOMAIN: &[u8] = b"xelis-signature-nonce";
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Signature {
-    s: Scalar,
-    e: Scalar,
+    pub(super) s: Scalar,
+    pub(super) e: Scalar,
 }
 
 impl Signature {
@@ -27,12 +32,31 @@ impl Signature {
 
     // Verify the signature using the Public Key and the hash of the message
     pub fn verify(&self, message: &[u8], key: &PublicKey) -> bool {
-        let r = PC_GENS.B_blinding * &self.s + key.as_point() * -self.e;
+        let r = &self.s * &*H_TABLE + key.as_point() * -self.e;
         let calculated = hash_and_point_to_scalar(&key.compress(), message, &r);
-        self.e == calculated
+        ct::eq(&self.e, &calculated)
     }
 }
 
+// Derive deterministically the nonce of a signature from the private key and the message (RFC6979 style)
+// A weak or broken RNG can't make two different messages share the same nonce, which would leak the private key
+// Extra data must contain anything else changing the challenge, like the adaptor point
+pub fn derive_nonce(private_key: &PrivateKey, key: &CompressedPublicKey, message: &[u8], extra: &[u8]) -> Scalar {
+    let mut hasher = Sha3_512::new();
+    hasher.update(NONCE_DOMAIN);
+    hasher.update(private_key.as_scalar().as_bytes());
+    hasher.update(key.as_bytes());
+    hasher.update((message.len() as u64).to_be_bytes());
+    hasher.update(message);
+    hasher.update(extra);
+
+    let mut bytes: [u8; 64] = hasher.finalize().try_into().unwrap();
+    let nonce = Scalar::from_bytes_mod_order_wide(&bytes);
+    bytes.zeroize();
+
+    nonce
+}
+
 // Create a Scalar from Public Key, Hash of the message, and selected point
 pub fn hash_and_point_to_scalar(key: &CompressedPublicKey, message: &[u8], point: &RistrettoPoint) -> Scalar {
     let mut hasher = Sha3_512::new();
@@ -49,7 +73,7 @@ impl Serialize for Signature {
     where
         S: serde::Serializer
     {
-        serializer.serialize_str(&hex::encode(&self.to_bytes()))
+        serializer.serialize_str(&encode_hex(self))
     }
 }
 
@@ -59,10 +83,12 @@ impl<'de> serde::Deserialize<'de> for Signature {
         D: serde::Deserializer<'de>
     {
         let s = String::deserialize(deserializer)?;
-        Ok(Self::from_hex(&s).map_err(D::Error::custom)?)
+        decode_hex(&s).map_err(D::Error::custom)
     }
 }
 
+impl_hex_display!(Signature);
+
 impl Serializer for Signature {
     fn write(&self, writer: &mut Writer) {
         self.s.write(writer);
@@ -78,4 +104,12 @@ impl Serializer for Signature {
     fn size(&self) -> usize {
         SIGNATURE_SIZE
     }
-}
\ No newline at end of file
+}
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for Signature {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let s = super::arbitrary_scalar(u)?;
+        let e = super::arbitrary_scalar(u)?;
+        Ok(Signature::new(s, e))
+    }
+}
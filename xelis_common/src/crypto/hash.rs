@@ -1,4 +1,4 @@
-use crate::{block::Algorithm, serializer::{Reader, ReaderError, Serializer, Writer}};
+use crate::{block::Algorithm, encoding::HEX_PREFIX, serializer::{Reader, ReaderError, Serializer, Writer}};
 use std::{
     borrow::Cow,
     convert::TryInto,
@@ -15,7 +15,9 @@ use xelis_hash::{v1, v2};
 
 pub const HASH_SIZE: usize = 32; // 32 bytes / 256 bits
 
+// Transparent to allow borrowing a Hash directly from bytes
 #[derive(Eq, PartialEq, PartialOrd, Ord, Clone, Debug)]
+#[repr(transparent)]
 pub struct Hash([u8; HASH_SIZE]);
 
 impl Hash {
@@ -35,6 +37,14 @@ impl Hash {
         &self.0
     }
 
+    // Borrow the bytes as a Hash without copying them
+    pub fn from_bytes_ref(bytes: &[u8; HASH_SIZE]) -> &Self {
+        // SAFETY: Hash is `repr(transparent)` over `[u8; HASH_SIZE]`, both have the same
+        // size and an alignment of 1, so any reference to the array is a valid reference
+        // to a Hash and the returned reference keeps the lifetime of the borrowed bytes
+        unsafe { &*(bytes as *const [u8; HASH_SIZE] as *const Self) }
+    }
+
     pub fn to_bytes(self) -> [u8; HASH_SIZE] {
         self.0
     }
@@ -48,6 +58,7 @@ impl FromStr for Hash {
     type Err = &'static str;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix(HEX_PREFIX).unwrap_or(s);
         let bytes = hex::decode(s).map_err(|_| "Invalid hex string")?;
         let bytes: [u8; HASH_SIZE] = bytes.try_into().map_err(|_| "Invalid hash")?;
         Ok(Hash::new(bytes))
@@ -96,6 +107,13 @@ impl Serializer for Hash {
     }
 }
 
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for Hash {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Hash(u.arbitrary()?))
+    }
+}
+
 impl std::hash::Hash for Hash {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.0.hash(state);
@@ -126,6 +144,7 @@ impl<'a> Deserialize<'a> for Hash {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where D: serde::Deserializer<'a> {
         let hex = String::deserialize(deserializer)?;
+        let hex = hex.strip_prefix(HEX_PREFIX).unwrap_or(&hex);
         if hex.len() != HASH_SIZE * 2 {
             return Err(SerdeError::custom("Invalid hex length"))
         }
@@ -18,7 +18,15 @@ use super::{
         encode,
         Bech32Error
     },
-    proofs::{BalanceProof, OwnershipProof},
+    elgamal::CompressedPublicKey,
+    proofs::{
+        AuditProof,
+        BalanceProof,
+        OwnershipProof,
+        PaymentProof,
+        ReserveProof,
+        MAX_RESERVE_CHALLENGE_SIZE
+    },
     Hash
 };
 
@@ -41,6 +49,48 @@ pub enum HumanReadableProof {
         asset: Hash,
         /// The topological height of the balance ciphertext.
         topoheight: u64
+    },
+    /// Payment proofs are used to prove the amount sent in a transfer by the prover.
+    Payment {
+        /// The payment proof.
+        proof: PaymentProof,
+        /// The transaction containing the transfer.
+        transaction: Hash,
+        /// The index of the transfer in the transaction.
+        index: u8
+    },
+    /// Reserve proofs are used to prove that the prover controls at least a certain amount of an asset.
+    Reserve {
+        /// The reserve proof.
+        proof: ReserveProof,
+        /// The asset of the proof.
+        asset: Hash,
+        /// The topological height of the balance ciphertext.
+        topoheight: u64,
+        /// The challenge given by the verifier.
+        challenge: Vec<u8>
+    },
+    /// Audit proofs re-encrypt the balance of the prover for a designated auditor.
+    Audit {
+        /// The audit proof.
+        proof: AuditProof,
+        /// The public key of the auditor.
+        auditor: CompressedPublicKey,
+        /// The asset of the proof.
+        asset: Hash,
+        /// The topological height of the balance ciphertext.
+        topoheight: u64
+    },
+    /// Transfer audit proofs re-encrypt the amount of a transfer sent or received by the prover for a designated auditor.
+    TransferAudit {
+        /// The audit proof.
+        proof: AuditProof,
+        /// The public key of the auditor.
+        auditor: CompressedPublicKey,
+        /// The transaction containing the transfer.
+        transaction: Hash,
+        /// The index of the transfer in the transaction.
+        index: u8
     }
 }
 
@@ -81,6 +131,34 @@ impl Serializer for HumanReadableProof {
                 proof.write(writer);
                 asset.write(writer);
                 topoheight.write(writer);
+            },
+            HumanReadableProof::Payment { proof, transaction, index } => {
+                writer.write_u8(2);
+                proof.write(writer);
+                transaction.write(writer);
+                index.write(writer);
+            },
+            HumanReadableProof::Reserve { proof, asset, topoheight, challenge } => {
+                writer.write_u8(3);
+                proof.write(writer);
+                asset.write(writer);
+                topoheight.write(writer);
+                writer.write_u8(challenge.len() as u8);
+                writer.write_bytes(challenge);
+            },
+            HumanReadableProof::Audit { proof, auditor, asset, topoheight } => {
+                writer.write_u8(4);
+                proof.write(writer);
+                auditor.write(writer);
+                asset.write(writer);
+                topoheight.write(writer);
+            },
+            HumanReadableProof::TransferAudit { proof, auditor, transaction, index } => {
+                writer.write_u8(5);
+                proof.write(writer);
+                auditor.write(writer);
+                transaction.write(writer);
+                index.write(writer);
             }
         }
     }
@@ -101,6 +179,41 @@ impl Serializer for HumanReadableProof {
         
                 HumanReadableProof::Ownership { proof, asset, topoheight }
             },
+            2 => {
+                let proof = PaymentProof::read(reader)?;
+                let transaction = Hash::read(reader)?;
+                let index = u8::read(reader)?;
+
+                HumanReadableProof::Payment { proof, transaction, index }
+            },
+            3 => {
+                let proof = ReserveProof::read(reader)?;
+                let asset = Hash::read(reader)?;
+                let topoheight = u64::read(reader)?;
+                let len = reader.read_u8()? as usize;
+                if len > MAX_RESERVE_CHALLENGE_SIZE {
+                    return Err(ReaderError::InvalidSize)
+                }
+                let challenge = reader.read_bytes_ref(len)?.to_vec();
+
+                HumanReadableProof::Reserve { proof, asset, topoheight, challenge }
+            },
+            4 => {
+                let proof = AuditProof::read(reader)?;
+                let auditor = CompressedPublicKey::read(reader)?;
+                let asset = Hash::read(reader)?;
+                let topoheight = u64::read(reader)?;
+
+                HumanReadableProof::Audit { proof, auditor, asset, topoheight }
+            },
+            5 => {
+                let proof = AuditProof::read(reader)?;
+                let auditor = CompressedPublicKey::read(reader)?;
+                let transaction = Hash::read(reader)?;
+                let index = u8::read(reader)?;
+
+                HumanReadableProof::TransferAudit { proof, auditor, transaction, index }
+            },
             _ => return Err(ReaderError::InvalidValue)
         };
 
@@ -115,6 +228,18 @@ impl Serializer for HumanReadableProof {
             },
             HumanReadableProof::Ownership { proof, asset, topoheight } => {
                 size += proof.size() + asset.size() + topoheight.size();
+            },
+            HumanReadableProof::Payment { proof, transaction, index } => {
+                size += proof.size() + transaction.size() + index.size();
+            },
+            HumanReadableProof::Reserve { proof, asset, topoheight, challenge } => {
+                size += proof.size() + asset.size() + topoheight.size() + 1 + challenge.len();
+            },
+            HumanReadableProof::Audit { proof, auditor, asset, topoheight } => {
+                size += proof.size() + auditor.size() + asset.size() + topoheight.size();
+            },
+            HumanReadableProof::TransferAudit { proof, auditor, transaction, index } => {
+                size += proof.size() + auditor.size() + transaction.size() + index.size();
             }
         }
 
@@ -154,6 +279,7 @@ mod tests {
     use crate::{
         config::XELIS_ASSET,
         crypto::{
+            elgamal::{Ciphertext, PedersenCommitment, PedersenOpening},
             proofs::BatchCollector,
             KeyPair
         }
@@ -218,4 +344,60 @@ mod tests {
         assert!(proof.pre_verify(keypair.get_public_key(), ct, &mut transcript, &mut batch_collector).is_ok());
         assert!(batch_collector.verify().is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_hr_payment_proof() {
+        let sender = KeyPair::new();
+        // Generate the transfer ciphertext of the sender
+        let amount = 100u64;
+        let opening = PedersenOpening::generate_new();
+        let commitment = PedersenCommitment::new_with_opening(amount, &opening);
+        let ct = Ciphertext::new(commitment, sender.get_public_key().decrypt_handle(&opening));
+        let tx_hash = Hash::new([1u8; 32]);
+
+        // Create proof
+        let proof = PaymentProof::new(&sender, &tx_hash, 3, amount, ct.clone());
+        let shareable = HumanReadableProof::Payment { proof, transaction: tx_hash.clone(), index: 3 };
+
+        // Transform to string and back to a shareable proof
+        let string = shareable.as_string().unwrap();
+        assert!(string.starts_with(PREFIX_PROOF));
+
+        let HumanReadableProof::Payment { proof, transaction, index } = HumanReadableProof::from_string(&string).unwrap() else {
+            panic!("Failed to parse the shareable proof");
+        };
+        assert_eq!(transaction, tx_hash);
+        assert_eq!(index, 3);
+        assert_eq!(proof.get_amount(), amount);
+
+        // Verify it
+        assert!(proof.verify(sender.get_public_key(), &transaction, index, ct).is_ok());
+    }
+
+    #[test]
+    fn test_hr_reserve_proof() {
+        let keypair = KeyPair::new();
+        // Generate the balance
+        let balance = 100u64;
+        let amount = 10u64;
+        let ct = keypair.get_public_key().encrypt(balance);
+
+        // Create proof
+        let proof = ReserveProof::new(&keypair, balance, amount, ct.clone(), b"challenge").unwrap();
+        let shareable = HumanReadableProof::Reserve { proof, asset: XELIS_ASSET, topoheight: 0, challenge: b"challenge".to_vec() };
+
+        // Transform to string and back to a shareable proof
+        let string = shareable.as_string().unwrap();
+        assert!(string.starts_with(PREFIX_PROOF));
+
+        let HumanReadableProof::Reserve { proof, asset, topoheight, challenge } = HumanReadableProof::from_string(&string).unwrap() else {
+            panic!("Failed to parse the shareable proof");
+        };
+        assert_eq!(topoheight, 0);
+        assert_eq!(asset, XELIS_ASSET);
+        assert_eq!(challenge, b"challenge");
+
+        // Verify it
+        assert!(proof.verify(keypair.get_public_key(), ct, &challenge).is_ok());
+    }
+}
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    account::VersionedBalance,
+    serializer::{Reader, ReaderError, Serializer, Writer}
+};
+use super::{hash, Hash, PublicKey};
+
+// Maximum depth of a merkle proof, enough for 2^32 leaves
+pub const MAX_MERKLE_PROOF_DEPTH: usize = 32;
+
+// Hash two nodes together to build their parent
+fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+    hash(&[left.as_bytes().as_ref(), right.as_bytes().as_ref()].concat())
+}
+
+// Compute the merkle root of the leaves
+// Pairs are hashed together, the last node is duplicated when a level has an odd count
+// An empty list of leaves has a zero root
+pub fn merkle_root(leaves: &[Hash]) -> Hash {
+    if leaves.is_empty() {
+        return Hash::zero()
+    }
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level.chunks(2)
+            .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+            .collect();
+    }
+
+    level.remove(0)
+}
+
+// Hash of a balance leaf in the balances merkle tree of a topoheight
+// It commits to the account, the asset and the full versioned balance
+pub fn hash_balance_leaf(key: &PublicKey, asset: &Hash, version: &VersionedBalance) -> Hash {
+    let mut bytes = Vec::with_capacity(64 + version.size());
+    bytes.extend_from_slice(key.as_bytes());
+    bytes.extend_from_slice(asset.as_bytes());
+    bytes.extend_from_slice(&version.to_bytes());
+    hash(&bytes)
+}
+
+// Proof that a leaf is included in a merkle tree
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    // Position of the leaf in the tree
+    index: u32,
+    // Sibling of each level, from the leaf to the root
+    siblings: Vec<Hash>
+}
+
+impl MerkleProof {
+    // Build the inclusion proof of the leaf at the given index
+    pub fn new(leaves: &[Hash], index: usize) -> Option<Self> {
+        if index >= leaves.len() || index > u32::MAX as usize {
+            return None
+        }
+
+        let mut siblings = Vec::new();
+        let mut level = leaves.to_vec();
+        let mut position = index;
+        while level.len() > 1 {
+            let sibling = level.get(position ^ 1).unwrap_or(&level[position]);
+            siblings.push(sibling.clone());
+
+            level = level.chunks(2)
+                .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+                .collect();
+            position /= 2;
+        }
+
+        Some(Self {
+            index: index as u32,
+            siblings
+        })
+    }
+
+    pub fn get_index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn get_siblings(&self) -> &Vec<Hash> {
+        &self.siblings
+    }
+
+    // Compute the root from the leaf and the siblings
+    pub fn compute_root(&self, leaf: &Hash) -> Hash {
+        let mut position = self.index;
+        let mut current = leaf.clone();
+        for sibling in self.siblings.iter() {
+            current = if position % 2 == 0 {
+                hash_pair(&current, sibling)
+            } else {
+                hash_pair(sibling, &current)
+            };
+            position /= 2;
+        }
+
+        current
+    }
+
+    // Verify that the leaf is included in the tree with the given root
+    pub fn verify(&self, leaf: &Hash, root: &Hash) -> bool {
+        self.compute_root(leaf) == *root
+    }
+}
+
+impl Serializer for MerkleProof {
+    fn write(&self, writer: &mut Writer) {
+        writer.write_u32(&self.index);
+        writer.write_u8(self.siblings.len() as u8);
+        for sibling in self.siblings.iter() {
+            writer.write_hash(sibling);
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let index = reader.read_u32()?;
+        let len = reader.read_u8()? as usize;
+        if len > MAX_MERKLE_PROOF_DEPTH {
+            return Err(ReaderError::InvalidSize)
+        }
+
+        let mut siblings = Vec::with_capacity(len);
+        for _ in 0..len {
+            siblings.push(reader.read_hash()?);
+        }
+
+        Ok(Self {
+            index,
+            siblings
+        })
+    }
+
+    fn size(&self) -> usize {
+        4 + 1 + self.siblings.len() * 32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: u8) -> Vec<Hash> {
+        (0..count).map(|i| hash(&[i])).collect()
+    }
+
+    #[test]
+    fn test_empty_root() {
+        assert_eq!(merkle_root(&[]), Hash::zero());
+        assert!(MerkleProof::new(&[], 0).is_none());
+    }
+
+    #[test]
+    fn test_single_leaf() {
+        let leaves = leaves(1);
+        assert_eq!(merkle_root(&leaves), leaves[0]);
+
+        let proof = MerkleProof::new(&leaves, 0).unwrap();
+        assert!(proof.get_siblings().is_empty());
+        assert!(proof.verify(&leaves[0], &leaves[0]));
+    }
+
+    #[test]
+    fn test_proofs() {
+        // Odd and even counts to cover the duplicated node
+        for count in 2..12 {
+            let leaves = leaves(count);
+            let root = merkle_root(&leaves);
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = MerkleProof::new(&leaves, index).unwrap();
+                assert!(proof.verify(leaf, &root));
+
+                let proof = MerkleProof::from_bytes(&proof.to_bytes()).unwrap();
+                assert!(proof.verify(leaf, &root));
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_proof() {
+        let leaves = leaves(5);
+        let root = merkle_root(&leaves);
+
+        let proof = MerkleProof::new(&leaves, 1).unwrap();
+        assert!(!proof.verify(&leaves[2], &root));
+        assert!(MerkleProof::new(&leaves, 5).is_none());
+    }
+}
@@ -6,6 +6,9 @@ mod human_readable_proof;
 pub mod elgamal;
 pub mod proofs;
 pub mod bech32;
+pub mod ecdlp;
+pub mod ct;
+pub mod merkle;
 
 pub use hash::*;
 pub use address::*;
@@ -14,8 +17,5 @@ pub use human_readable_proof::*;
 
 pub use elgamal::{PrivateKey, KeyPair, Signature, SIGNATURE_SIZE};
 
-/// Re-export the curve25519-dalek ecdlp module
-pub use curve25519_dalek::ecdlp;
-
 /// Public Key type used in the system
 pub type PublicKey = elgamal::CompressedPublicKey;
\ No newline at end of file
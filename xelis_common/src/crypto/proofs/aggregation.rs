@@ -0,0 +1,355 @@
+use bulletproofs::{
+    range_proof_mpc::{
+        dealer::Dealer,
+        messages::{
+            BitChallenge,
+            BitCommitment,
+            PolyChallenge,
+            PolyCommitment,
+            ProofShare
+        },
+        party::{
+            Party,
+            PartyAwaitingBitChallenge,
+            PartyAwaitingPolyChallenge,
+            PartyAwaitingPosition
+        }
+    },
+    ProofError,
+    RangeProof
+};
+use curve25519_dalek::{
+    ristretto::CompressedRistretto,
+    traits::Identity,
+    RistrettoPoint,
+    Scalar
+};
+use merlin::Transcript;
+use crate::{
+    crypto::{
+        elgamal::{
+            CompressedCommitment,
+            PedersenCommitment,
+            PedersenOpening
+        },
+        ProtocolTranscript
+    },
+    serializer::{
+        Reader,
+        ReaderError,
+        Serializer,
+        Writer
+    },
+    transaction::MAX_TRANSFER_COUNT
+};
+use super::{
+    ProofGenerationError,
+    ProofVerificationError,
+    BP_GENS,
+    BULLET_PROOF_SIZE,
+    PC_GENS
+};
+
+/// Maximum number of commitments in an aggregated range proof.
+/// This is the party capacity of the bulletproof generators.
+pub const MAX_AGGREGATED_COMMITMENTS: usize = MAX_TRANSFER_COUNT * 2 + 2;
+
+/// A participant of a cooperative range proof aggregation.
+/// Each participant owns the opening of its commitment and never shares it:
+/// the aggregator only receives the messages of the bulletproofs MPC protocol.
+/// It is implemented by `RangeProofParty` for a local participant,
+/// and can be implemented by a proxy forwarding the messages to a remote sender.
+pub trait RangeProofParticipant {
+    /// Get the commitment proven to be in range.
+    fn get_commitment(&self) -> CompressedCommitment;
+
+    /// First round: commit to the bits of the amount at the given position.
+    fn commit_bits(&mut self, position: usize) -> Result<BitCommitment, ProofGenerationError>;
+
+    /// Second round: commit to the polynomial using the challenge of the aggregator.
+    fn commit_poly(&mut self, challenge: &BitChallenge) -> Result<PolyCommitment, ProofGenerationError>;
+
+    /// Last round: create the proof share using the challenge of the aggregator.
+    fn prove_share(&mut self, challenge: &PolyChallenge) -> Result<ProofShare, ProofGenerationError>;
+}
+
+enum PartyState {
+    AwaitingPosition(PartyAwaitingPosition<'static>),
+    AwaitingBitChallenge(PartyAwaitingBitChallenge<'static>),
+    AwaitingPolyChallenge(PartyAwaitingPolyChallenge),
+    Done
+}
+
+/// A local participant holding the amount and the opening of its commitment.
+pub struct RangeProofParty {
+    /// The commitment of the amount.
+    commitment: CompressedCommitment,
+    /// Current round of the protocol.
+    state: PartyState,
+}
+
+impl RangeProofParty {
+    /// Create a new participant for an amount committed with the given opening.
+    pub fn new(amount: u64, opening: &PedersenOpening) -> Result<Self, ProofGenerationError> {
+        let commitment = PedersenCommitment::new_with_opening(amount, opening).compress();
+        Self::with_commitment(commitment, amount, opening.as_scalar())
+    }
+
+    /// Create a participant used to pad the number of commitments to a power of two.
+    fn padding() -> Result<Self, ProofGenerationError> {
+        Self::with_commitment(CompressedCommitment::new(CompressedRistretto::identity()), 0, Scalar::ZERO)
+    }
+
+    fn with_commitment(commitment: CompressedCommitment, amount: u64, blinding: Scalar) -> Result<Self, ProofGenerationError> {
+        let party = Party::new(&BP_GENS, &PC_GENS, amount, blinding, BULLET_PROOF_SIZE)
+            .map_err(mpc_error)?;
+
+        Ok(Self { commitment, state: PartyState::AwaitingPosition(party) })
+    }
+}
+
+impl RangeProofParticipant for RangeProofParty {
+    fn get_commitment(&self) -> CompressedCommitment {
+        self.commitment.clone()
+    }
+
+    fn commit_bits(&mut self, position: usize) -> Result<BitCommitment, ProofGenerationError> {
+        let PartyState::AwaitingPosition(party) = std::mem::replace(&mut self.state, PartyState::Done) else {
+            return Err(ProofGenerationError::Format)
+        };
+
+        let (party, commitment) = party.assign_position(position)
+            .map_err(mpc_error)?;
+        self.state = PartyState::AwaitingBitChallenge(party);
+        Ok(commitment)
+    }
+
+    fn commit_poly(&mut self, challenge: &BitChallenge) -> Result<PolyCommitment, ProofGenerationError> {
+        let PartyState::AwaitingBitChallenge(party) = std::mem::replace(&mut self.state, PartyState::Done) else {
+            return Err(ProofGenerationError::Format)
+        };
+
+        let (party, commitment) = party.apply_challenge(challenge);
+        self.state = PartyState::AwaitingPolyChallenge(party);
+        Ok(commitment)
+    }
+
+    fn prove_share(&mut self, challenge: &PolyChallenge) -> Result<ProofShare, ProofGenerationError> {
+        let PartyState::AwaitingPolyChallenge(party) = std::mem::replace(&mut self.state, PartyState::Done) else {
+            return Err(ProofGenerationError::Format)
+        };
+
+        party.apply_challenge(challenge)
+            .map_err(mpc_error)
+    }
+}
+
+fn mpc_error<E>(e: E) -> ProofGenerationError
+where
+    ProofError: From<E>
+{
+    ProofGenerationError::RangeProof(ProofError::from(e))
+}
+
+/// A single range proof covering the commitments of several senders.
+/// A block producer (or a batching service) runs the bulletproofs MPC protocol with the senders
+/// to replace their range proofs by one proof verified once, which is logarithmic in the number of commitments.
+/// The proof is bound to a context (for example the block hash) so it can't be replayed elsewhere.
+pub struct AggregatedRangeProof {
+    /// The commitments proven to be in range, in the order of the participants.
+    commitments: Vec<CompressedCommitment>,
+    /// The aggregated range proof.
+    range_proof: RangeProof,
+}
+
+impl AggregatedRangeProof {
+    /// Create a new aggregated range proof.
+    pub fn from(commitments: Vec<CompressedCommitment>, range_proof: RangeProof) -> Self {
+        Self { commitments, range_proof }
+    }
+
+    fn transcript(context: &[u8], commitments: &[CompressedCommitment]) -> Transcript {
+        let mut transcript = Transcript::new(b"aggregated_range_proof");
+        transcript.aggregated_range_proof_domain_separator();
+        transcript.append_message(b"context", context);
+        for commitment in commitments {
+            transcript.append_commitment(b"commitment", commitment);
+        }
+
+        transcript
+    }
+
+    /// Run the aggregation protocol with the participants.
+    /// Participants are padded with zero commitments up to the next power of two.
+    pub fn prove<P: RangeProofParticipant>(context: &[u8], participants: &mut [P]) -> Result<Self, ProofGenerationError> {
+        let count = participants.len();
+        if count == 0 || count > MAX_AGGREGATED_COMMITMENTS {
+            return Err(ProofGenerationError::Format);
+        }
+        let size = count.next_power_of_two();
+
+        let commitments: Vec<CompressedCommitment> = participants.iter()
+            .map(|participant| participant.get_commitment())
+            .collect();
+
+        let mut padding = (count..size)
+            .map(|_| RangeProofParty::padding())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut transcript = Self::transcript(context, &commitments);
+        let dealer = Dealer::new(&BP_GENS, &PC_GENS, &mut transcript, BULLET_PROOF_SIZE, size)
+            .map_err(mpc_error)?;
+
+        // Round 1: bit commitments
+        let mut bit_commitments = Vec::with_capacity(size);
+        for (position, participant) in participants.iter_mut().enumerate() {
+            bit_commitments.push(participant.commit_bits(position)?);
+        }
+        for (position, participant) in padding.iter_mut().enumerate() {
+            bit_commitments.push(participant.commit_bits(count + position)?);
+        }
+        let (dealer, bit_challenge) = dealer.receive_bit_commitments(bit_commitments)
+            .map_err(mpc_error)?;
+
+        // Round 2: polynomial commitments
+        let mut poly_commitments = Vec::with_capacity(size);
+        for participant in participants.iter_mut() {
+            poly_commitments.push(participant.commit_poly(&bit_challenge)?);
+        }
+        for participant in padding.iter_mut() {
+            poly_commitments.push(participant.commit_poly(&bit_challenge)?);
+        }
+        let (dealer, poly_challenge) = dealer.receive_poly_commitments(poly_commitments)
+            .map_err(mpc_error)?;
+
+        // Round 3: proof shares, each share is verified to detect a malicious participant
+        let mut shares = Vec::with_capacity(size);
+        for participant in participants.iter_mut() {
+            shares.push(participant.prove_share(&poly_challenge)?);
+        }
+        for participant in padding.iter_mut() {
+            shares.push(participant.prove_share(&poly_challenge)?);
+        }
+        let range_proof = dealer.receive_shares(&shares)
+            .map_err(mpc_error)?;
+
+        Ok(Self::from(commitments, range_proof))
+    }
+
+    /// Get the commitments proven to be in range.
+    pub fn get_commitments(&self) -> &[CompressedCommitment] {
+        &self.commitments
+    }
+
+    /// Get the aggregated range proof.
+    pub fn get_range_proof(&self) -> &RangeProof {
+        &self.range_proof
+    }
+
+    /// Verify the aggregated range proof for the given context.
+    pub fn verify(&self, context: &[u8]) -> Result<(), ProofVerificationError> {
+        let count = self.commitments.len();
+        if count == 0 || count > MAX_AGGREGATED_COMMITMENTS {
+            return Err(ProofVerificationError::Format);
+        }
+
+        let mut transcript = Self::transcript(context, &self.commitments);
+        let commitments: Vec<(RistrettoPoint, CompressedRistretto)> = self.commitments.iter()
+            .map(|commitment| Ok((*commitment.decompress()?.as_point(), *commitment.as_point())))
+            .chain((count..count.next_power_of_two()).map(|_| Ok((RistrettoPoint::identity(), CompressedRistretto::identity()))))
+            .collect::<Result<_, ProofVerificationError>>()?;
+
+        self.range_proof.verify_multiple(
+            &BP_GENS,
+            &PC_GENS,
+            &mut transcript,
+            &commitments,
+            BULLET_PROOF_SIZE,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Serializer for AggregatedRangeProof {
+    fn write(&self, writer: &mut Writer) {
+        writer.write_u16(self.commitments.len() as u16);
+        for commitment in &self.commitments {
+            commitment.write(writer);
+        }
+        self.range_proof.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let count = reader.read_u16()? as usize;
+        if count == 0 || count > MAX_AGGREGATED_COMMITMENTS {
+            return Err(ReaderError::InvalidSize);
+        }
+
+        let mut commitments = Vec::with_capacity(count);
+        for _ in 0..count {
+            commitments.push(CompressedCommitment::read(reader)?);
+        }
+        let range_proof = RangeProof::read(reader)?;
+
+        Ok(Self::from(commitments, range_proof))
+    }
+
+    fn size(&self) -> usize {
+        2 + self.commitments.iter().map(|commitment| commitment.size()).sum::<usize>()
+            + self.range_proof.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parties(amounts: &[u64]) -> Vec<RangeProofParty> {
+        amounts.iter()
+            .map(|amount| RangeProofParty::new(*amount, &PedersenOpening::generate_new()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_aggregated_range_proof() {
+        let mut parties = parties(&[10, 500, u64::MAX]);
+        let proof = AggregatedRangeProof::prove(b"block", &mut parties).unwrap();
+        assert_eq!(proof.get_commitments().len(), 3);
+        assert!(proof.verify(b"block").is_ok());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof_serialization() {
+        let mut parties = parties(&[1, 2]);
+        let proof = AggregatedRangeProof::prove(b"block", &mut parties).unwrap();
+        let proof = AggregatedRangeProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(proof.verify(b"block").is_ok());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof_wrong_context() {
+        let mut parties = parties(&[10, 20, 30, 40]);
+        let proof = AggregatedRangeProof::prove(b"block", &mut parties).unwrap();
+        assert!(proof.verify(b"other block").is_err());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof_replaced_commitment() {
+        let mut parties = parties(&[10, 20]);
+        let proof = AggregatedRangeProof::prove(b"block", &mut parties).unwrap();
+
+        let mut commitments = proof.get_commitments().to_vec();
+        commitments[1] = PedersenCommitment::new(20u64).0.compress();
+        let proof = AggregatedRangeProof::from(commitments, proof.get_range_proof().clone());
+        assert!(proof.verify(b"block").is_err());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof_party_replay() {
+        // A participant can't be used in two protocols
+        let mut parties = parties(&[10]);
+        assert!(AggregatedRangeProof::prove(b"block", &mut parties).is_ok());
+        assert!(AggregatedRangeProof::prove(b"block", &mut parties).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use merlin::Transcript;
+use crate::{
+    crypto::{
+        elgamal::{
+            Ciphertext,
+            CompressedCiphertext,
+            CompressedCommitment,
+            CompressedHandle,
+            DecompressionError,
+            PedersenCommitment,
+            PedersenOpening,
+            PublicKey
+        },
+        KeyPair,
+        ProtocolTranscript
+    },
+    serializer::{
+        Reader,
+        ReaderError,
+        Serializer,
+        Writer
+    },
+    transaction::TxVersion
+};
+use super::{
+    BatchCollector,
+    CiphertextValidityProof,
+    CommitmentEqProof,
+    ProofVerificationError
+};
+
+/// An audit proof re-encrypts a ciphertext of the prover (balance or transfer) for a designated auditor.
+/// The auditor decrypts the amount using its own private key: it never learns the key of the account,
+/// so it can read the audited amounts but can't sign anything on behalf of the account.
+/// The commitment equality proof ensures that the new commitment contains the amount of the source ciphertext,
+/// and the ciphertext validity proof ensures that the auditor handle was created using the same opening.
+pub struct AuditProof {
+    /// The commitment of the amount for the auditor.
+    commitment: CompressedCommitment,
+    /// The decrypt handle of the auditor.
+    handle: CompressedHandle,
+    /// The commitment proof.
+    commitment_eq_proof: CommitmentEqProof,
+    /// The validity proof of the auditor ciphertext.
+    ciphertext_validity_proof: CiphertextValidityProof,
+}
+
+impl AuditProof {
+    /// Create a new audit proof.
+    pub fn from(commitment: CompressedCommitment, handle: CompressedHandle, commitment_eq_proof: CommitmentEqProof, ciphertext_validity_proof: CiphertextValidityProof) -> Self {
+        Self { commitment, handle, commitment_eq_proof, ciphertext_validity_proof }
+    }
+
+    /// Create a new audit proof with default transcript.
+    pub fn new(keypair: &KeyPair, auditor: &PublicKey, amount: u64, ciphertext: Ciphertext) -> Self {
+        Self::prove(keypair, auditor, amount, ciphertext, &mut Transcript::new(b"audit_proof"))
+    }
+
+    /// Prove that the auditor ciphertext contains the amount of the source ciphertext.
+    pub fn prove(keypair: &KeyPair, auditor: &PublicKey, amount: u64, ciphertext: Ciphertext, transcript: &mut Transcript) -> Self {
+        // Use a new opening so the auditor learns nothing from the source ciphertext
+        let opening = PedersenOpening::generate_new();
+        let commitment = PedersenCommitment::new_with_opening(amount, &opening).compress();
+        let handle = auditor.decrypt_handle(&opening).compress();
+
+        transcript.audit_proof_domain_separator();
+        transcript.append_public_key(b"auditor", &auditor.compress());
+        transcript.append_ciphertext(b"source_ct", &ciphertext.compress());
+        transcript.append_commitment(b"commitment", &commitment);
+        transcript.append_handle(b"handle", &handle);
+
+        let commitment_eq_proof = CommitmentEqProof::new(keypair, &ciphertext, &opening, amount, transcript);
+        let ciphertext_validity_proof = CiphertextValidityProof::new(auditor, None, amount, &opening, transcript);
+
+        Self::from(commitment, handle, commitment_eq_proof, ciphertext_validity_proof)
+    }
+
+    /// Get the ciphertext to be decrypted by the auditor.
+    pub fn get_ciphertext(&self) -> CompressedCiphertext {
+        CompressedCiphertext::new(self.commitment.clone(), self.handle.clone())
+    }
+
+    /// Decompress the ciphertext to be decrypted by the auditor.
+    pub fn decompress_ciphertext(&self) -> Result<Ciphertext, DecompressionError> {
+        Ok(Ciphertext::new(self.commitment.decompress()?, self.handle.decompress()?))
+    }
+
+    /// Verify the audit proof using transcript.
+    pub fn verify_with(&self, public_key: &PublicKey, auditor: &PublicKey, source_ciphertext: Ciphertext, transcript: &mut Transcript) -> Result<(), ProofVerificationError> {
+        transcript.audit_proof_domain_separator();
+        transcript.append_public_key(b"auditor", &auditor.compress());
+        transcript.append_ciphertext(b"source_ct", &source_ciphertext.compress());
+        transcript.append_commitment(b"commitment", &self.commitment);
+        transcript.append_handle(b"handle", &self.handle);
+
+        let commitment = self.commitment.decompress()?;
+        let handle = self.handle.decompress()?;
+
+        let mut batch_collector = BatchCollector::default();
+        self.commitment_eq_proof.pre_verify(public_key, &source_ciphertext, &commitment, transcript, &mut batch_collector)?;
+        // There is no source handle, only the auditor one is verified
+        self.ciphertext_validity_proof.pre_verify(&commitment, auditor, auditor, &handle, &handle, false, transcript, &mut batch_collector)?;
+        batch_collector.verify()?;
+
+        Ok(())
+    }
+
+    /// Verify the audit proof.
+    pub fn verify(&self, public_key: &PublicKey, auditor: &PublicKey, source_ciphertext: Ciphertext) -> Result<(), ProofVerificationError> {
+        let mut transcript = Transcript::new(b"audit_proof");
+        self.verify_with(public_key, auditor, source_ciphertext, &mut transcript)
+    }
+}
+
+impl Serializer for AuditProof {
+    fn write(&self, writer: &mut Writer) {
+        self.commitment.write(writer);
+        self.handle.write(writer);
+        self.commitment_eq_proof.write(writer);
+        self.ciphertext_validity_proof.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let commitment = CompressedCommitment::read(reader)?;
+        let handle = CompressedHandle::read(reader)?;
+        let commitment_eq_proof = CommitmentEqProof::read(reader)?;
+        // The validity proof has no source handle, which is the V0 format
+        reader.context_mut().store(TxVersion::V0);
+        let ciphertext_validity_proof = CiphertextValidityProof::read(reader)?;
+
+        Ok(Self::from(commitment, handle, commitment_eq_proof, ciphertext_validity_proof))
+    }
+
+    fn size(&self) -> usize {
+        self.commitment.size()
+            + self.handle.size()
+            + self.commitment_eq_proof.size()
+            + self.ciphertext_validity_proof.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use curve25519_dalek::Scalar;
+    use crate::crypto::elgamal::G;
+    use super::*;
+
+    #[test]
+    fn test_audit_proof() {
+        let keypair = KeyPair::new();
+        let auditor = KeyPair::new();
+        let amount = 100u64;
+        let ct = keypair.get_public_key().encrypt(amount);
+
+        let proof = AuditProof::new(&keypair, auditor.get_public_key(), amount, ct.clone());
+        assert!(proof.verify(keypair.get_public_key(), auditor.get_public_key(), ct).is_ok());
+
+        // The auditor can decrypt the amount with its own key
+        let audit_ct = proof.decompress_ciphertext().unwrap();
+        assert_eq!(auditor.decrypt_to_point(&audit_ct), Scalar::from(amount) * G);
+    }
+
+    #[test]
+    fn test_audit_proof_serialization() {
+        let keypair = KeyPair::new();
+        let auditor = KeyPair::new();
+        let ct = keypair.get_public_key().encrypt(50u64);
+
+        let proof = AuditProof::new(&keypair, auditor.get_public_key(), 50, ct.clone());
+        let proof = AuditProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(proof.verify(keypair.get_public_key(), auditor.get_public_key(), ct).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_amount_audit_proof() {
+        let keypair = KeyPair::new();
+        let auditor = KeyPair::new();
+        let ct = keypair.get_public_key().encrypt(100u64);
+
+        // The auditor ciphertext must contain the real amount
+        let proof = AuditProof::new(&keypair, auditor.get_public_key(), 10, ct.clone());
+        assert!(proof.verify(keypair.get_public_key(), auditor.get_public_key(), ct).is_err());
+    }
+
+    #[test]
+    fn test_wrong_auditor_audit_proof() {
+        let keypair = KeyPair::new();
+        let auditor = KeyPair::new();
+        let other = KeyPair::new();
+        let ct = keypair.get_public_key().encrypt(100u64);
+
+        let proof = AuditProof::new(&keypair, auditor.get_public_key(), 100, ct.clone());
+        assert!(proof.verify(keypair.get_public_key(), other.get_public_key(), ct).is_err());
+    }
+}
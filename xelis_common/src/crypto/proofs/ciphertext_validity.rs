@@ -217,6 +217,22 @@ impl Serializer for CiphertextValidityProof {
     }
 }
 
+// Y_2 is only present since TxVersion::V1
+#[cfg(any(test, feature = "arbitrary"))]
+impl CiphertextValidityProof {
+    pub fn arbitrary_for_version(u: &mut arbitrary::Unstructured, version: TxVersion) -> arbitrary::Result<Self> {
+        use crate::crypto::elgamal::{arbitrary_point, arbitrary_scalar};
+
+        Ok(Self {
+            Y_0: arbitrary_point(u)?,
+            Y_1: arbitrary_point(u)?,
+            Y_2: if version >= TxVersion::V1 { Some(arbitrary_point(u)?) } else { None },
+            z_r: arbitrary_scalar(u)?,
+            z_x: arbitrary_scalar(u)?
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::crypto::KeyPair;
@@ -189,6 +189,22 @@ impl Serializer for CommitmentEqProof {
     }    
 }
 
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for CommitmentEqProof {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use crate::crypto::elgamal::{arbitrary_point, arbitrary_scalar};
+
+        Ok(Self {
+            Y_0: arbitrary_point(u)?,
+            Y_1: arbitrary_point(u)?,
+            Y_2: arbitrary_point(u)?,
+            z_s: arbitrary_scalar(u)?,
+            z_x: arbitrary_scalar(u)?,
+            z_r: arbitrary_scalar(u)?
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,9 +3,15 @@ mod commitment_eq;
 mod range_proof;
 mod balance;
 mod ownership;
+mod payment;
+mod reserve;
+mod audit;
+mod aggregation;
 
 use std::iter;
 use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_TABLE,
+    ristretto::RistrettoBasepointTable,
     traits::{IsIdentity, VartimeMultiscalarMul},
     RistrettoPoint,
     Scalar
@@ -21,6 +27,17 @@ pub use commitment_eq::CommitmentEqProof;
 pub use ciphertext_validity::CiphertextValidityProof;
 pub use balance::BalanceProof;
 pub use ownership::OwnershipProof;
+pub use payment::PaymentProof;
+pub use reserve::{ReserveProof, MAX_RESERVE_CHALLENGE_SIZE};
+pub use audit::AuditProof;
+pub use aggregation::{
+    AggregatedRangeProof,
+    RangeProofParticipant,
+    RangeProofParty,
+    MAX_AGGREGATED_COMMITMENTS
+};
+#[cfg(any(test, feature = "arbitrary"))]
+pub use range_proof::arbitrary_range_proof;
 
 // We are using 64 bits for the bulletproofs
 pub const BULLET_PROOF_SIZE: usize = 64;
@@ -30,8 +47,19 @@ lazy_static! {
     // * 2 in case each transfer use a unique asset + 1 for xelis asset as fee and + 1 to be a power of 2
     pub static ref BP_GENS: BulletproofGens = BulletproofGens::new(BULLET_PROOF_SIZE, MAX_TRANSFER_COUNT * 2 + 2);
     pub static ref PC_GENS: PedersenGens = PedersenGens::default();
+    // Fixed-base table for the blinding generator H (PC_GENS.B_blinding)
+    // Used by signatures, public keys, decrypt handles and the commitments
+    // rebuilt by the balance, ownership and payment proofs pre_verify
+    // Other proofs add their G and H terms to the BatchCollector instead
+    pub static ref H_TABLE: RistrettoBasepointTable = RistrettoBasepointTable::create(&PC_GENS.B_blinding);
 }
 
+// Fixed-base table for the generator G (PC_GENS.B), shipped by curve25519-dalek
+// Used when adding or subtracting plaintext amounts to commitments,
+// such as the fee and amounts of the sender output ciphertext
+// See the `verification` bench against the variable-base multiplication
+pub static G_TABLE: &RistrettoBasepointTable = RISTRETTO_BASEPOINT_TABLE;
+
 #[derive(Error, Debug, Clone, Copy, Eq, PartialEq)]
 #[error("batch multiscalar mul returned non identity point")]
 pub struct MultiscalarMulVerificationError;
@@ -0,0 +1,183 @@
+use curve25519_dalek::Scalar;
+use merlin::Transcript;
+use crate::{
+    crypto::{
+        elgamal::{
+            Ciphertext,
+            PedersenCommitment,
+            PedersenOpening,
+            PublicKey
+        },
+        proofs::{
+            BatchCollector,
+            CommitmentEqProof,
+            ProofVerificationError,
+        },
+        Hash,
+        KeyPair,
+        ProtocolTranscript
+    },
+    serializer::{
+        Reader,
+        ReaderError,
+        Serializer,
+        Writer
+    }
+};
+
+/// A payment proof is a cryptographic proof to reveal the amount of a transfer sent by the prover.
+/// The sender ciphertext of a transfer is built using the transfer commitment and the sender handle.
+/// The payment proof proves that the amount encrypted in it is the revealed amount.
+/// Because the ciphertext validity proof of the transfer ensures that the receiver handle uses the same opening,
+/// the receiver got exactly the revealed amount, without revealing anything else from the sender wallet.
+pub struct PaymentProof {
+    /// The revealed transfer amount.
+    amount: u64,
+    /// The commitment proof.
+    commitment_eq_proof: CommitmentEqProof,
+}
+
+impl PaymentProof {
+    /// The opening used for the payment proof.
+    /// Same as the balance proof, we don't need to hide the opening.
+    const OPENING: PedersenOpening = PedersenOpening::from_scalar(Scalar::ONE);
+
+    /// Create a new payment proof.
+    pub fn from(amount: u64, commitment_eq_proof: CommitmentEqProof) -> Self {
+        Self { amount, commitment_eq_proof }
+    }
+
+    /// Create a new payment proof for the transfer at index in the transaction
+    pub fn new(keypair: &KeyPair, tx_hash: &Hash, transfer_index: u8, amount: u64, ciphertext: Ciphertext) -> Self {
+        Self::prove(keypair, tx_hash, transfer_index, amount, ciphertext, &mut Transcript::new(b"payment_proof"))
+    }
+
+    /// Prove the payment proof using transcript.
+    pub fn prove(keypair: &KeyPair, tx_hash: &Hash, transfer_index: u8, amount: u64, ciphertext: Ciphertext, transcript: &mut Transcript) -> Self {
+        transcript.payment_proof_domain_separator();
+        transcript.append_hash(b"tx_hash", tx_hash);
+        transcript.append_u64(b"transfer_index", transfer_index as u64);
+        transcript.append_u64(b"amount", amount);
+        transcript.append_ciphertext(b"sender_ct", &ciphertext.compress());
+
+        // Compute the zeroed ciphertext
+        let ct = keypair.get_public_key().encrypt_with_opening(amount, &Self::OPENING);
+        let zeroed_ct = ciphertext - ct;
+
+        // Generate the proof that the ciphertext is 0 after removing the amount.
+        let commitment_eq_proof = CommitmentEqProof::new(keypair, &zeroed_ct, &Self::OPENING, 0, transcript);
+
+        Self::from(amount, commitment_eq_proof)
+    }
+
+    /// Get the revealed transfer amount.
+    pub fn get_amount(&self) -> u64 {
+        self.amount
+    }
+
+    /// Verify the payment proof.
+    pub fn pre_verify(&self, public_key: &PublicKey, tx_hash: &Hash, transfer_index: u8, sender_ciphertext: Ciphertext, transcript: &mut Transcript, batch_collector: &mut BatchCollector) -> Result<(), ProofVerificationError> {
+        transcript.payment_proof_domain_separator();
+        transcript.append_hash(b"tx_hash", tx_hash);
+        transcript.append_u64(b"transfer_index", transfer_index as u64);
+        transcript.append_u64(b"amount", self.amount);
+        transcript.append_ciphertext(b"sender_ct", &sender_ciphertext.compress());
+
+        // Calculate the commitment that corresponds to a zero amount.
+        let destination_commitment = PedersenCommitment::new_with_opening(Scalar::ZERO, &Self::OPENING);
+
+        // Compute the zeroed ciphertext
+        let ct = public_key.encrypt_with_opening(self.amount, &Self::OPENING);
+        let zeroed_ct = sender_ciphertext - ct;
+
+        self.commitment_eq_proof.pre_verify(public_key, &zeroed_ct, &destination_commitment, transcript, batch_collector)?;
+
+        Ok(())
+    }
+
+    /// Verify the payment proof.
+    pub fn verify(&self, public_key: &PublicKey, tx_hash: &Hash, transfer_index: u8, sender_ciphertext: Ciphertext) -> Result<(), ProofVerificationError> {
+        let mut transcript = Transcript::new(b"payment_proof");
+        let mut batch_collector = BatchCollector::default();
+
+        self.pre_verify(public_key, tx_hash, transfer_index, sender_ciphertext, &mut transcript, &mut batch_collector)?;
+
+        batch_collector.verify()?;
+        Ok(())
+    }
+}
+
+impl Serializer for PaymentProof {
+    fn write(&self, writer: &mut Writer) {
+        self.amount.write(writer);
+        self.commitment_eq_proof.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let amount = u64::read(reader)?;
+        let commitment_eq_proof = CommitmentEqProof::read(reader)?;
+
+        Ok(Self::from(amount, commitment_eq_proof))
+    }
+
+    fn size(&self) -> usize {
+        self.amount.size() + self.commitment_eq_proof.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Build the sender ciphertext of a transfer
+    fn transfer_ciphertexts(sender: &KeyPair, receiver: &KeyPair, amount: u64) -> (Ciphertext, Ciphertext) {
+        let opening = PedersenOpening::generate_new();
+        let commitment = PedersenCommitment::new_with_opening(amount, &opening);
+        let sender_handle = sender.get_public_key().decrypt_handle(&opening);
+        let receiver_handle = receiver.get_public_key().decrypt_handle(&opening);
+
+        (Ciphertext::new(commitment.clone(), sender_handle), Ciphertext::new(commitment, receiver_handle))
+    }
+
+    #[test]
+    fn test_payment_proof() {
+        let sender = KeyPair::new();
+        let receiver = KeyPair::new();
+        let tx_hash = Hash::new([1u8; 32]);
+        let (ct, receiver_ct) = transfer_ciphertexts(&sender, &receiver, 100);
+
+        let proof = PaymentProof::new(&sender, &tx_hash, 0, 100, ct.clone());
+        assert!(proof.verify(sender.get_public_key(), &tx_hash, 0, ct.clone()).is_ok());
+        assert_eq!(proof.get_amount(), 100);
+
+        // Receiver decrypts the same amount as the one revealed
+        assert_eq!(receiver.decrypt_to_point(&receiver_ct), sender.decrypt_to_point(&ct));
+    }
+
+    #[test]
+    fn test_invalid_amount_payment_proof() {
+        let sender = KeyPair::new();
+        let receiver = KeyPair::new();
+        let tx_hash = Hash::new([1u8; 32]);
+        let (ct, _) = transfer_ciphertexts(&sender, &receiver, 100);
+
+        let proof = PaymentProof::new(&sender, &tx_hash, 0, 95, ct.clone());
+        assert!(proof.verify(sender.get_public_key(), &tx_hash, 0, ct).is_err());
+    }
+
+    #[test]
+    fn test_invalid_transfer_payment_proof() {
+        let sender = KeyPair::new();
+        let receiver = KeyPair::new();
+        let tx_hash = Hash::new([1u8; 32]);
+        let (ct, _) = transfer_ciphertexts(&sender, &receiver, 100);
+
+        // Proof can't be reused for another transfer
+        let proof = PaymentProof::new(&sender, &tx_hash, 0, 100, ct.clone());
+        assert!(proof.verify(sender.get_public_key(), &tx_hash, 1, ct.clone()).is_err());
+
+        // Nor by another sender
+        let other = KeyPair::new();
+        assert!(proof.verify(other.get_public_key(), &tx_hash, 0, ct).is_err());
+    }
+}
@@ -52,3 +52,28 @@ impl Serializer for RangeProof {
         self.to_bytes().len() + 2
     }
 }
+
+// Build a range proof from its encoding: 4 points, 3 scalars then the inner product proof
+// with lg_n pairs of points and 2 scalars
+// The proof is well formed but not valid
+#[cfg(any(test, feature = "arbitrary"))]
+pub fn arbitrary_range_proof(u: &mut arbitrary::Unstructured) -> arbitrary::Result<RangeProof> {
+    use crate::crypto::elgamal::{arbitrary_point, arbitrary_scalar};
+
+    let lg_n: usize = u.int_in_range(1..=8)?;
+    let mut bytes = Vec::with_capacity((4 + 3 + lg_n * 2 + 2) * 32);
+    for _ in 0..4 {
+        bytes.extend(arbitrary_point(u)?.as_bytes());
+    }
+    for _ in 0..3 {
+        bytes.extend(arbitrary_scalar(u)?.as_bytes());
+    }
+    for _ in 0..lg_n * 2 {
+        bytes.extend(arbitrary_point(u)?.as_bytes());
+    }
+    for _ in 0..2 {
+        bytes.extend(arbitrary_scalar(u)?.as_bytes());
+    }
+
+    RangeProof::from_bytes(&bytes).map_err(|_| arbitrary::Error::IncorrectFormat)
+}
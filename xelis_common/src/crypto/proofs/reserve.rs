@@ -0,0 +1,241 @@
+use bulletproofs::RangeProof;
+use curve25519_dalek::Scalar;
+use merlin::Transcript;
+use crate::{
+    crypto::{
+        elgamal::{
+            Ciphertext,
+            CompressedCommitment,
+            PedersenCommitment,
+            PedersenOpening,
+            PublicKey
+        },
+        KeyPair,
+        ProtocolTranscript
+    },
+    serializer::{
+        Reader,
+        ReaderError,
+        Serializer,
+        Writer
+    }
+};
+use super::{
+    BatchCollector,
+    CommitmentEqProof,
+    ProofGenerationError,
+    ProofVerificationError,
+    BP_GENS,
+    BULLET_PROOF_SIZE,
+    PC_GENS
+};
+
+/// Maximum size in bytes of the challenge used by a reserve proof.
+pub const MAX_RESERVE_CHALLENGE_SIZE: usize = 64;
+
+/// Prove that the prover controls at least a certain amount (N > 0) of a given asset.
+/// Unlike the ownership proof, the balance left is proven to be in range using a Bulletproof,
+/// so the prover can't claim more than its balance.
+/// The proof is bound to a challenge chosen by the verifier to prove its freshness,
+/// and can only be created by the owner of the private key.
+pub struct ReserveProof {
+    /// The amount of the asset.
+    amount: u64,
+    /// The commitment of the left balance.
+    commitment: CompressedCommitment,
+    /// The commitment proof.
+    commitment_eq_proof: CommitmentEqProof,
+    /// The range proof of the left balance.
+    range_proof: RangeProof,
+}
+
+impl ReserveProof {
+    /// The opening used for the proof.
+    /// It is used to encrypt the amount of the asset that we want to prove.
+    const OPENING: PedersenOpening = PedersenOpening::from_scalar(Scalar::ONE);
+
+    /// Create a new reserve proof.
+    pub fn from(amount: u64, commitment: CompressedCommitment, commitment_eq_proof: CommitmentEqProof, range_proof: RangeProof) -> Self {
+        Self { amount, commitment, commitment_eq_proof, range_proof }
+    }
+
+    /// Create a new reserve proof with default transcript
+    pub fn new(keypair: &KeyPair, balance: u64, amount: u64, ciphertext: Ciphertext, challenge: &[u8]) -> Result<Self, ProofGenerationError> {
+        let mut transcript = Transcript::new(b"reserve_proof");
+        Self::prove(keypair, balance, amount, ciphertext, challenge, &mut transcript)
+    }
+
+    /// Prove the reserve of the asset.
+    pub fn prove(keypair: &KeyPair, balance: u64, amount: u64, ciphertext: Ciphertext, challenge: &[u8], transcript: &mut Transcript) -> Result<Self, ProofGenerationError> {
+        if amount == 0 || challenge.len() > MAX_RESERVE_CHALLENGE_SIZE {
+            return Err(ProofGenerationError::Format);
+        }
+
+        let left = balance.checked_sub(amount)
+            .ok_or(ProofGenerationError::InsufficientFunds)?;
+
+        // We don't want to reveal the whole balance, so we create a new Commitment with a random opening.
+        let opening = PedersenOpening::generate_new();
+        let left_commitment = PedersenCommitment::new_with_opening(left, &opening)
+            .compress();
+
+        transcript.reserve_proof_domain_separator();
+        transcript.append_message(b"challenge", challenge);
+        transcript.append_u64(b"amount", amount);
+        transcript.append_commitment(b"commitment", &left_commitment);
+        transcript.append_ciphertext(b"source_ct", &ciphertext.compress());
+
+        // Compute the balance left
+        let ct = keypair.get_public_key().encrypt_with_opening(amount, &Self::OPENING);
+        let ct_left = ciphertext - ct;
+
+        // Generate the proof that the commitment contains the balance left.
+        let commitment_eq_proof = CommitmentEqProof::new(keypair, &ct_left, &opening, left, transcript);
+
+        // Generate the proof that the balance left is not negative.
+        let (range_proof, _) = RangeProof::prove_multiple(
+            &BP_GENS,
+            &PC_GENS,
+            transcript,
+            &vec![left],
+            &vec![opening.as_scalar()],
+            BULLET_PROOF_SIZE,
+        )?;
+
+        Ok(Self::from(amount, left_commitment, commitment_eq_proof, range_proof))
+    }
+
+    /// Get the amount proven to be owned.
+    pub fn get_amount(&self) -> u64 {
+        self.amount
+    }
+
+    /// Verify the reserve proof using transcript.
+    pub fn verify_with(&self, public_key: &PublicKey, source_ciphertext: Ciphertext, challenge: &[u8], transcript: &mut Transcript) -> Result<(), ProofVerificationError> {
+        if self.amount == 0 || challenge.len() > MAX_RESERVE_CHALLENGE_SIZE {
+            return Err(ProofVerificationError::Format);
+        }
+
+        transcript.reserve_proof_domain_separator();
+        transcript.append_message(b"challenge", challenge);
+        transcript.append_u64(b"amount", self.amount);
+        transcript.append_commitment(b"commitment", &self.commitment);
+        transcript.append_ciphertext(b"source_ct", &source_ciphertext.compress());
+
+        // Decompress the commitment
+        let commitment = self.commitment.decompress()?;
+
+        // Compute the balance left
+        let ct = public_key.encrypt_with_opening(self.amount, &Self::OPENING);
+        let balance_left = source_ciphertext - ct;
+
+        let mut batch_collector = BatchCollector::default();
+        self.commitment_eq_proof.pre_verify(public_key, &balance_left, &commitment, transcript, &mut batch_collector)?;
+        batch_collector.verify()?;
+
+        // Range proof must be verified after the sigma proof as they share the transcript
+        self.range_proof.verify_multiple(
+            &BP_GENS,
+            &PC_GENS,
+            transcript,
+            &vec![(commitment.as_point().clone(), self.commitment.as_point().clone())],
+            BULLET_PROOF_SIZE,
+        )?;
+
+        Ok(())
+    }
+
+    /// Verify the reserve proof.
+    pub fn verify(&self, public_key: &PublicKey, source_ciphertext: Ciphertext, challenge: &[u8]) -> Result<(), ProofVerificationError> {
+        let mut transcript = Transcript::new(b"reserve_proof");
+        self.verify_with(public_key, source_ciphertext, challenge, &mut transcript)
+    }
+}
+
+impl Serializer for ReserveProof {
+    fn write(&self, writer: &mut Writer) {
+        self.amount.write(writer);
+        self.commitment.write(writer);
+        self.commitment_eq_proof.write(writer);
+        self.range_proof.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let amount = u64::read(reader)?;
+        let commitment = CompressedCommitment::read(reader)?;
+        let commitment_eq_proof = CommitmentEqProof::read(reader)?;
+        let range_proof = RangeProof::read(reader)?;
+
+        Ok(Self::from(amount, commitment, commitment_eq_proof, range_proof))
+    }
+
+    fn size(&self) -> usize {
+        self.amount.size()
+            + self.commitment.size()
+            + self.commitment_eq_proof.size()
+            + self.range_proof.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_proof() {
+        let keypair = KeyPair::new();
+        // Generate the balance
+        let balance = 100u64;
+        let amount = 10u64;
+        let ct = keypair.get_public_key().encrypt(balance);
+
+        // Create proof
+        let proof = ReserveProof::new(&keypair, balance, amount, ct.clone(), b"challenge").unwrap();
+
+        // Verify the proof
+        assert!(proof.verify(keypair.get_public_key(), ct, b"challenge").is_ok());
+    }
+
+    #[test]
+    fn test_whole_balance_reserve_proof() {
+        let keypair = KeyPair::new();
+        let balance = 100u64;
+        let ct = keypair.get_public_key().encrypt(balance);
+
+        let proof = ReserveProof::new(&keypair, balance, balance, ct.clone(), b"challenge").unwrap();
+        assert!(proof.verify(keypair.get_public_key(), ct, b"challenge").is_ok());
+    }
+
+    #[test]
+    fn test_invalid_challenge_reserve_proof() {
+        let keypair = KeyPair::new();
+        let balance = 100u64;
+        let amount = 10u64;
+        let ct = keypair.get_public_key().encrypt(balance);
+
+        let proof = ReserveProof::new(&keypair, balance, amount, ct.clone(), b"challenge").unwrap();
+
+        // A proof can't be replayed for another challenge
+        assert!(proof.verify(keypair.get_public_key(), ct, b"another challenge").is_err());
+    }
+
+    #[test]
+    fn test_invalid_balance_reserve_proof() {
+        let keypair = KeyPair::new();
+        // Real balance is lower than the one claimed by the prover
+        let balance = 100u64;
+        let ct = keypair.get_public_key().encrypt(balance);
+
+        let proof = ReserveProof::new(&keypair, 1000, 500, ct.clone(), b"challenge").unwrap();
+        assert!(proof.verify(keypair.get_public_key(), ct, b"challenge").is_err());
+    }
+
+    #[test]
+    fn test_insufficient_funds_reserve_proof() {
+        let keypair = KeyPair::new();
+        let balance = 5u64;
+        let ct = keypair.get_public_key().encrypt(balance);
+
+        assert!(ReserveProof::new(&keypair, balance, 10, ct, b"challenge").is_err());
+    }
+}
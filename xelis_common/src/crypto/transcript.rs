@@ -30,9 +30,15 @@ pub trait ProtocolTranscript {
     fn multisig_proof_domain_separator(&mut self);
     fn invoke_contract_proof_domain_separator(&mut self);
     fn deploy_contract_proof_domain_separator(&mut self);
+    fn manage_contract_proof_domain_separator(&mut self);
+    fn execution_credits_proof_domain_separator(&mut self);
     fn ciphertext_validity_proof_domain_separator(&mut self);
     fn balance_proof_domain_separator(&mut self);
     fn ownership_proof_domain_separator(&mut self);
+    fn payment_proof_domain_separator(&mut self);
+    fn reserve_proof_domain_separator(&mut self);
+    fn audit_proof_domain_separator(&mut self);
+    fn aggregated_range_proof_domain_separator(&mut self);
 }
 
 impl ProtocolTranscript for Transcript {
@@ -107,6 +113,14 @@ impl ProtocolTranscript for Transcript {
         self.append_message(b"dom-sep", b"deploy-contract-proof");
     }
 
+    fn manage_contract_proof_domain_separator(&mut self) {
+        self.append_message(b"dom-sep", b"manage-contract-proof");
+    }
+
+    fn execution_credits_proof_domain_separator(&mut self) {
+        self.append_message(b"dom-sep", b"execution-credits-proof");
+    }
+
     fn equality_proof_domain_separator(&mut self) {
         self.append_message(b"dom-sep", b"equality-proof");
     }
@@ -122,4 +136,20 @@ impl ProtocolTranscript for Transcript {
     fn ownership_proof_domain_separator(&mut self) {
         self.append_message(b"dom-sep", b"ownership-proof");
     }
+
+    fn payment_proof_domain_separator(&mut self) {
+        self.append_message(b"dom-sep", b"payment-proof");
+    }
+
+    fn reserve_proof_domain_separator(&mut self) {
+        self.append_message(b"dom-sep", b"reserve-proof");
+    }
+
+    fn audit_proof_domain_separator(&mut self) {
+        self.append_message(b"dom-sep", b"audit-proof");
+    }
+
+    fn aggregated_range_proof_domain_separator(&mut self) {
+        self.append_message(b"dom-sep", b"aggregated-range-proof");
+    }
 }
@@ -16,7 +16,11 @@ pub enum DifficultyError {
     #[error("Difficulty cannot be a value zero")]
     DifficultyCannotBeZero,
     #[error("Error while converting value to BigUint")]
-    ErrorOnConversionBigUint
+    ErrorOnConversionBigUint,
+    #[error("Cumulative difficulty overflow")]
+    CumulativeDifficultyOverflow,
+    #[error("Cumulative difficulty {0} is below the checkpoint baseline {1}")]
+    CumulativeDifficultyBelowBaseline(CumulativeDifficulty, CumulativeDifficulty)
 }
 
 // Verify the validity of a block difficulty against the current network difficulty
@@ -48,4 +52,44 @@ pub fn check_difficulty_against_target(hash: &Hash, target: &U256) -> bool {
 #[inline(always)]
 pub fn difficulty_from_hash(hash: &Hash) -> Difficulty {
     (U256::max_value() / U256::from_big_endian(hash.as_bytes())).into()
-}
\ No newline at end of file
+}
+
+// Sum difficulties into a cumulative difficulty
+// Fails instead of wrapping around if the sum doesn't fit anymore
+pub fn checked_cumulative_difficulty<I: IntoIterator<Item = CumulativeDifficulty>>(values: I) -> Result<CumulativeDifficulty, DifficultyError> {
+    values.into_iter()
+        .try_fold(CumulativeDifficulty::zero(), |acc, value| acc.checked_add(value))
+        .ok_or(DifficultyError::CumulativeDifficultyOverflow)
+}
+
+// Work done above a checkpoint baseline
+// A chain sharing the checkpoint can't have less cumulative difficulty than it
+pub fn work_above_baseline(cumulative_difficulty: &CumulativeDifficulty, baseline: &CumulativeDifficulty) -> Result<CumulativeDifficulty, DifficultyError> {
+    cumulative_difficulty.checked_sub(*baseline)
+        .ok_or(DifficultyError::CumulativeDifficultyBelowBaseline(*cumulative_difficulty, *baseline))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_cumulative_difficulty() {
+        let values = [VarUint::from_u64(5), VarUint::from_u64(10)];
+        assert_eq!(checked_cumulative_difficulty(values).unwrap(), VarUint::from_u64(15));
+
+        let values = [VarUint::new(U256::max_value()), VarUint::one()];
+        assert!(matches!(checked_cumulative_difficulty(values), Err(DifficultyError::CumulativeDifficultyOverflow)));
+    }
+
+    #[test]
+    fn test_work_above_baseline() {
+        let baseline = VarUint::from_u64(100);
+        assert_eq!(work_above_baseline(&VarUint::from_u64(150), &baseline).unwrap(), VarUint::from_u64(50));
+        assert_eq!(work_above_baseline(&baseline, &baseline).unwrap(), VarUint::zero());
+        assert!(matches!(
+            work_above_baseline(&VarUint::from_u64(50), &baseline),
+            Err(DifficultyError::CumulativeDifficultyBelowBaseline(_, _))
+        ));
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{
+    api::daemon::{DevFeeThreshold, EmissionAtHeight},
+    config::{BLOCK_TIME_MILLIS, MAXIMUM_SUPPLY, MILLIS_PER_SECOND}
+};
+
+// Emission rules
+// 15% (6 months), 10% (6 months), 5% per block going to dev address
+// NOTE: The explained emission above was the expected one
+// But due to a bug in the function to calculate the dev fee reward,
+// the actual emission was directly set to 10% per block
+// New emission rules are: 10% during 1.5 years, then 5% for the rest
+// This is the same for the project but reduce a bit the mining cost as they earn 5% more
+pub const DEV_FEES: [DevFeeThreshold; 2] = [
+    // Activated for 3M blocks
+    DevFeeThreshold {
+        height: 0,
+        fee_percentage: 10
+    },
+    // Activated for the rest
+    DevFeeThreshold {
+        // after ~1.5 year it's reduced to 5%
+        // 3 250 000 blocks * 15s of block time / 60s / 60m / 24h / 365d = 1.5 years
+        height: 3_250_000,
+        fee_percentage: 5
+    }
+];
+// only 30% of reward for side block
+// This is to prevent spamming side blocks
+// and also give rewards for miners with valid work on main chain
+pub const SIDE_BLOCK_REWARD_PERCENT: u64 = 30;
+// maximum 3 blocks for side block reward
+// Each side block reward will be divided by the number of side blocks * 2
+// With a configuration of 3 blocks, we have the following percents:
+// 1 block: 30%
+// 2 blocks: 15%
+// 3 blocks: 7%
+// 4 blocks: minimum percentage set below
+pub const SIDE_BLOCK_REWARD_MAX_BLOCKS: u64 = 3;
+// minimum 5% of block reward for side block
+// This is the minimum given for all others valid side blocks
+pub const SIDE_BLOCK_REWARD_MIN_PERCENT: u64 = 5;
+// Emission speed factor for the emission curve
+// It is used to calculate based on the supply the block reward
+pub const EMISSION_SPEED_FACTOR: u64 = 20;
+
+// Get the block reward for a side block based on how many side blocks exists at same height
+pub fn side_block_reward_percentage(side_blocks: u64) -> u64 {
+    let mut side_block_percent = SIDE_BLOCK_REWARD_PERCENT;
+    if side_blocks > 0 {
+        if side_blocks < SIDE_BLOCK_REWARD_MAX_BLOCKS {
+            side_block_percent = SIDE_BLOCK_REWARD_PERCENT / (side_blocks * 2);
+        } else {
+            // If we have more than 3 side blocks at same height
+            // we reduce the reward to 5%
+            side_block_percent = SIDE_BLOCK_REWARD_MIN_PERCENT;
+        }
+    }
+
+    side_block_percent
+}
+
+// Calculate the block reward based on the current supply
+pub fn get_block_reward(supply: u64) -> u64 {
+    // Prevent any overflow
+    if supply >= MAXIMUM_SUPPLY {
+        // Max supply reached, do we want to generate small fixed amount of coins?
+        return 0
+    }
+
+    let base_reward = (MAXIMUM_SUPPLY - supply) >> EMISSION_SPEED_FACTOR;
+    base_reward * BLOCK_TIME_MILLIS / MILLIS_PER_SECOND / 180
+}
+
+// Returns the fee percentage for a block at a given height
+pub fn get_block_dev_fee(height: u64) -> u64 {
+    let mut percentage = 0;
+    for threshold in DEV_FEES.iter() {
+        if height >= threshold.height {
+            percentage = threshold.fee_percentage;
+        }
+    }
+
+    percentage
+}
+
+// Split the block reward between the dev and the miner
+// Returns (dev reward, miner reward)
+pub fn get_block_rewards(height: u64, reward: u64) -> (u64, u64) {
+    let dev_fee_percentage = get_block_dev_fee(height);
+    let dev_reward = reward * dev_fee_percentage / 100;
+    let miner_reward = reward - dev_reward;
+
+    (dev_reward, miner_reward)
+}
+
+// Simulated emission of the chain
+// It follows the emission curve with exactly one block per height,
+// so the height is also the topoheight and no side block is rewarded
+// Real supply may be a bit higher due to the side blocks rewards
+pub struct EmissionSimulator {
+    height: u64,
+    supply: u64
+}
+
+impl EmissionSimulator {
+    // Start at the genesis block with no supply emitted
+    pub fn new() -> Self {
+        Self {
+            height: 0,
+            supply: 0
+        }
+    }
+
+    // Next height to be emitted
+    pub fn get_height(&self) -> u64 {
+        self.height
+    }
+
+    // Supply emitted before the next height
+    pub fn get_supply(&self) -> u64 {
+        self.supply
+    }
+
+    // Emit the blocks until the requested height (excluded)
+    pub fn skip_to(&mut self, height: u64) {
+        while self.height < height {
+            self.supply += get_block_reward(self.supply);
+            self.height += 1;
+        }
+    }
+
+    // Emit the block at the next height
+    pub fn next_block(&mut self) -> EmissionAtHeight {
+        let block_reward = get_block_reward(self.supply);
+        let (dev_reward, miner_reward) = get_block_rewards(self.height, block_reward);
+        self.supply += block_reward;
+
+        let emission = EmissionAtHeight {
+            height: self.height,
+            block_reward,
+            dev_reward,
+            miner_reward,
+            supply: self.supply
+        };
+        self.height += 1;
+
+        emission
+    }
+}
+
+impl Default for EmissionSimulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Get the supply emitted once the block at this height is added
+pub fn get_simulated_supply_at_height(height: u64) -> u64 {
+    let mut simulator = EmissionSimulator::new();
+    simulator.skip_to(height);
+    simulator.next_block().supply
+}
+
+// Simulate the emission for the heights in the inclusive range, every `step` heights
+pub fn simulate_emission(start_height: u64, end_height: u64, step: u64) -> Vec<EmissionAtHeight> {
+    let step = step.max(1);
+    let mut simulator = EmissionSimulator::new();
+    let mut entries = Vec::new();
+    let mut height = start_height;
+    while height <= end_height {
+        simulator.skip_to(height);
+        entries.push(simulator.next_block());
+
+        height = match height.checked_add(step) {
+            Some(h) => h,
+            None => break
+        };
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reward_side_block_percentage() {
+        assert_eq!(side_block_reward_percentage(0), SIDE_BLOCK_REWARD_PERCENT);
+        assert_eq!(side_block_reward_percentage(1), SIDE_BLOCK_REWARD_PERCENT / 2);
+        assert_eq!(side_block_reward_percentage(2), SIDE_BLOCK_REWARD_PERCENT / 4);
+        assert_eq!(side_block_reward_percentage(3), SIDE_BLOCK_REWARD_MIN_PERCENT);
+    }
+
+    #[test]
+    fn test_block_dev_fee() {
+        assert_eq!(get_block_dev_fee(0), 10);
+        assert_eq!(get_block_dev_fee(1), 10);
+
+        // ~ current height
+        assert_eq!(get_block_dev_fee(55_000), 10);
+
+        // End of the first threshold, we pass to 5%
+        assert_eq!(get_block_dev_fee(3_250_000), 5);
+
+        assert_eq!(get_block_dev_fee(DEV_FEES[0].height), 10);
+        assert_eq!(get_block_dev_fee(DEV_FEES[1].height), 5);
+        assert_eq!(get_block_dev_fee(DEV_FEES[1].height + 1), 5);
+    }
+
+    #[test]
+    fn test_block_rewards() {
+        let (dev, miner) = get_block_rewards(0, 1000);
+        assert_eq!((dev, miner), (100, 900));
+
+        let (dev, miner) = get_block_rewards(DEV_FEES[1].height, 1000);
+        assert_eq!((dev, miner), (50, 950));
+    }
+
+    #[test]
+    fn test_simulate_emission() {
+        let entries = simulate_emission(0, 10, 5);
+        assert_eq!(entries.iter().map(|e| e.height).collect::<Vec<_>>(), vec![0, 5, 10]);
+
+        // Genesis block is rewarded from an empty supply
+        assert_eq!(entries[0].block_reward, get_block_reward(0));
+        assert_eq!(entries[0].supply, entries[0].block_reward);
+
+        // Same result as a full simulation
+        let full = simulate_emission(0, 10, 1);
+        assert_eq!(full.len(), 11);
+        assert_eq!(full[5].supply, entries[1].supply);
+        assert_eq!(full[10].supply, entries[2].supply);
+        assert_eq!(get_simulated_supply_at_height(10), full[10].supply);
+
+        // Reward is decreasing with the supply
+        assert!(full[10].block_reward <= full[0].block_reward);
+        for entry in full {
+            assert_eq!(entry.dev_reward + entry.miner_reward, entry.block_reward);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt::{self, Display, Formatter};
+use serde::{de::Error as SerdeError, Deserialize, Deserializer, Serialize};
+use crate::{
+    crypto::{elgamal::CompressedPublicKey, Address},
+    serializer::{Reader, ReaderError, Serializer},
+    utils::{format_coin, from_coin}
+};
+
+// Canonical text representations of the public types
+//
+// Any type encoded with `Serializer` is represented as the lowercase hex of its bytes.
+// `Display`, `FromStr` and serde of Hash, keys, signatures and ciphertexts
+// all go through this module so RPC, wallet and explorer outputs can't drift.
+// Parsing accepts an optional "0x" prefix and uppercase digits,
+// but rejects any trailing byte.
+
+pub const HEX_PREFIX: &str = "0x";
+
+// Encode a value as lowercase hex
+pub fn encode_hex<T: Serializer + ?Sized>(value: &T) -> String {
+    value.to_hex()
+}
+
+// Encode a value as lowercase hex prefixed by "0x"
+pub fn encode_prefixed_hex<T: Serializer + ?Sized>(value: &T) -> String {
+    format!("{}{}", HEX_PREFIX, value.to_hex())
+}
+
+// Decode a value from its hex, with or without the "0x" prefix
+pub fn decode_hex<T: Serializer>(value: &str) -> Result<T, ReaderError> {
+    let hex = value.strip_prefix(HEX_PREFIX).unwrap_or(value);
+    let bytes = hex::decode(hex).map_err(|_| ReaderError::InvalidHex)?;
+
+    let mut reader = Reader::new(&bytes);
+    let value = T::read(&mut reader)?;
+    if reader.size() != 0 {
+        return Err(ReaderError::InvalidSize)
+    }
+
+    Ok(value)
+}
+
+// Encode a public key as an address
+pub fn encode_address(key: &CompressedPublicKey, mainnet: bool) -> String {
+    key.clone().to_address(mainnet).to_string()
+}
+
+// Decode a public key from an address or its hex
+// Data integrated in the address is ignored
+pub fn decode_public_key(value: &str) -> Result<CompressedPublicKey, ReaderError> {
+    match value.parse::<Address>() {
+        Ok(address) => Ok(address.to_public_key()),
+        Err(_) => decode_hex(value)
+    }
+}
+
+// Implement `Display` and `FromStr` using the canonical hex of the type
+macro_rules! impl_hex_display {
+    ($type: ty) => {
+        impl std::fmt::Display for $type {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", $crate::encoding::encode_hex(self))
+            }
+        }
+
+        impl std::str::FromStr for $type {
+            type Err = $crate::serializer::ReaderError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $crate::encoding::decode_hex(s)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_hex_display;
+
+// Serde representation as hex, to use with `#[serde(with = "hex_string")]`
+// Accepts the "0x" prefix when deserializing
+pub mod hex_string {
+    use super::*;
+
+    pub fn serialize<T: Serializer, S: serde::Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode_hex(value))
+    }
+
+    pub fn deserialize<'de, T: Serializer, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        decode_hex(&hex).map_err(D::Error::custom)
+    }
+}
+
+// Serde representation as "0x" prefixed hex, to use with `#[serde(with = "prefixed_hex")]`
+pub mod prefixed_hex {
+    use super::*;
+
+    pub fn serialize<T: Serializer, S: serde::Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode_prefixed_hex(value))
+    }
+
+    pub fn deserialize<'de, T: Serializer, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        hex_string::deserialize(deserializer)
+    }
+}
+
+// Amount of an asset, displayed using the decimals of the asset
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Amount {
+    value: u64,
+    decimals: u8
+}
+
+impl Amount {
+    pub fn new(value: u64, decimals: u8) -> Self {
+        Self { value, decimals }
+    }
+
+    // Parse a formatted amount using the decimals of the asset
+    pub fn parse(value: &str, decimals: u8) -> Option<Self> {
+        from_coin(value, decimals).map(|value| Self::new(value, decimals))
+    }
+
+    // Atomic units of the amount
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+}
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format_coin(self.value, self.decimals))
+    }
+}
+
+// Both the atomic units and the formatted value are exposed,
+// so clients never have to apply the decimals themselves
+impl Serialize for Amount {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut s = serializer.serialize_struct("Amount", 3)?;
+        s.serialize_field("value", &self.value)?;
+        s.serialize_field("decimals", &self.decimals)?;
+        s.serialize_field("formatted", &self.to_string())?;
+        s.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Inner {
+            value: u64,
+            decimals: u8
+        }
+
+        let inner = Inner::deserialize(deserializer)?;
+        Ok(Self::new(inner.value, inner.decimals))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{elgamal::CompressedCiphertext, Hash, KeyPair, Signature};
+    use super::*;
+
+    #[test]
+    fn test_hex_roundtrip() {
+        let keypair = KeyPair::new();
+        let key = keypair.get_public_key().compress();
+        let hex = key.to_string();
+        assert_eq!(hex, key.to_hex());
+        assert_eq!(hex.parse::<CompressedPublicKey>().unwrap(), key);
+        assert_eq!(encode_prefixed_hex(&key).parse::<CompressedPublicKey>().unwrap(), key);
+        assert_eq!(hex.to_uppercase().parse::<CompressedPublicKey>().unwrap(), key);
+
+        let signature = keypair.sign(b"hello");
+        assert_eq!(signature.to_string().parse::<Signature>().unwrap(), signature);
+
+        let ciphertext = keypair.get_public_key().encrypt(10u64).compress();
+        assert_eq!(ciphertext.to_string().parse::<CompressedCiphertext>().unwrap(), ciphertext);
+
+        let hash = Hash::max();
+        assert_eq!(format!("0x{}", hash).parse::<Hash>().unwrap(), hash);
+    }
+
+    #[test]
+    fn test_hex_trailing_bytes() {
+        let hex = format!("{}00", Hash::zero());
+        assert!(decode_hex::<Hash>(&hex).is_err());
+    }
+
+    #[test]
+    fn test_public_key_from_address() {
+        let key = KeyPair::new().get_public_key().compress();
+        let address = encode_address(&key, true);
+        assert_eq!(decode_public_key(&address).unwrap(), key);
+        assert_eq!(decode_public_key(&key.to_hex()).unwrap(), key);
+    }
+
+    #[test]
+    fn test_serde_with() {
+        #[derive(Serialize, Deserialize)]
+        struct Test {
+            #[serde(with = "prefixed_hex")]
+            key: CompressedPublicKey
+        }
+
+        let key = KeyPair::new().get_public_key().compress();
+        let json = serde_json::to_string(&Test { key: key.clone() }).unwrap();
+        assert_eq!(json, format!("{{\"key\":\"0x{}\"}}", key.to_hex()));
+        assert_eq!(serde_json::from_str::<Test>(&json).unwrap().key, key);
+    }
+
+    #[test]
+    fn test_amount() {
+        let amount = Amount::parse("1.5", 8).unwrap();
+        assert_eq!(amount.value(), 150_000_000);
+        assert_eq!(amount.to_string(), "1.50000000");
+
+        let json = serde_json::to_value(&amount).unwrap();
+        assert_eq!(json["formatted"], "1.50000000");
+        assert_eq!(serde_json::from_value::<Amount>(json).unwrap(), amount);
+    }
+}
@@ -0,0 +1,295 @@
+use std::{fmt, str::FromStr};
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::serializer::{Reader, ReaderError, Serializer, Writer};
+
+// Deterministic fixed-point numbers backed by a u128
+// No floating point is involved so the same computation
+// gives the exact same result in contracts and in any off-chain tooling
+// All operations are rounding toward zero
+
+// Maximum decimals supported
+// 10^38 is the biggest power of ten fitting in a u128
+pub const MAX_DECIMALS: u8 = 38;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedPointError {
+    #[error("invalid decimals {0}, maximum is {MAX_DECIMALS}")]
+    InvalidDecimals(u8),
+    #[error("decimals mismatch: {0} != {1}")]
+    DecimalsMismatch(u8, u8),
+    #[error("overflow")]
+    Overflow,
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("invalid format")]
+    InvalidFormat
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FixedPoint {
+    // Scaled value: value * 10^decimals
+    raw: u128,
+    // Precision used
+    decimals: u8
+}
+
+// Compute 10^decimals
+pub fn scale_for(decimals: u8) -> Result<u128, FixedPointError> {
+    if decimals > MAX_DECIMALS {
+        return Err(FixedPointError::InvalidDecimals(decimals));
+    }
+
+    Ok(10u128.pow(decimals as u32))
+}
+
+// Compute (a * b) / c without intermediate overflow
+pub fn mul_div(a: u128, b: u128, c: u128) -> Result<u128, FixedPointError> {
+    if c == 0 {
+        return Err(FixedPointError::DivisionByZero);
+    }
+
+    let result = U256::from(a) * U256::from(b) / U256::from(c);
+    if result > U256::from(u128::MAX) {
+        return Err(FixedPointError::Overflow);
+    }
+
+    Ok(result.as_u128())
+}
+
+// Convert a raw value from a precision to another one
+pub fn rescale(raw: u128, from: u8, to: u8) -> Result<u128, FixedPointError> {
+    let from_scale = scale_for(from)?;
+    let to_scale = scale_for(to)?;
+
+    mul_div(raw, to_scale, from_scale)
+}
+
+impl FixedPoint {
+    // Create a fixed point from its raw (already scaled) value
+    pub fn from_raw(raw: u128, decimals: u8) -> Result<Self, FixedPointError> {
+        scale_for(decimals)?;
+        Ok(Self { raw, decimals })
+    }
+
+    // Create a fixed point from an integer
+    pub fn from_integer(value: u128, decimals: u8) -> Result<Self, FixedPointError> {
+        let raw = value.checked_mul(scale_for(decimals)?)
+            .ok_or(FixedPointError::Overflow)?;
+
+        Ok(Self { raw, decimals })
+    }
+
+    // Create a fixed point from the ratio numerator / denominator
+    pub fn from_ratio(numerator: u128, denominator: u128, decimals: u8) -> Result<Self, FixedPointError> {
+        let raw = mul_div(numerator, scale_for(decimals)?, denominator)?;
+        Ok(Self { raw, decimals })
+    }
+
+    pub fn zero(decimals: u8) -> Result<Self, FixedPointError> {
+        Self::from_raw(0, decimals)
+    }
+
+    pub fn raw(&self) -> u128 {
+        self.raw
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    // Integer part of the value
+    pub fn to_integer(&self) -> u128 {
+        self.raw / self.scale()
+    }
+
+    fn scale(&self) -> u128 {
+        10u128.pow(self.decimals as u32)
+    }
+
+    fn ensure_same_decimals(&self, other: &Self) -> Result<(), FixedPointError> {
+        if self.decimals != other.decimals {
+            return Err(FixedPointError::DecimalsMismatch(self.decimals, other.decimals));
+        }
+
+        Ok(())
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Result<Self, FixedPointError> {
+        self.ensure_same_decimals(other)?;
+        let raw = self.raw.checked_add(other.raw)
+            .ok_or(FixedPointError::Overflow)?;
+
+        Ok(Self { raw, decimals: self.decimals })
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, FixedPointError> {
+        self.ensure_same_decimals(other)?;
+        let raw = self.raw.checked_sub(other.raw)
+            .ok_or(FixedPointError::Overflow)?;
+
+        Ok(Self { raw, decimals: self.decimals })
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, FixedPointError> {
+        self.ensure_same_decimals(other)?;
+        let raw = mul_div(self.raw, other.raw, self.scale())?;
+
+        Ok(Self { raw, decimals: self.decimals })
+    }
+
+    pub fn checked_div(&self, other: &Self) -> Result<Self, FixedPointError> {
+        self.ensure_same_decimals(other)?;
+        let raw = mul_div(self.raw, self.scale(), other.raw)?;
+
+        Ok(Self { raw, decimals: self.decimals })
+    }
+
+    // Multiply by an integer, useful to apply a rate to an amount
+    pub fn checked_mul_integer(&self, value: u128) -> Result<Self, FixedPointError> {
+        let raw = self.raw.checked_mul(value)
+            .ok_or(FixedPointError::Overflow)?;
+
+        Ok(Self { raw, decimals: self.decimals })
+    }
+
+    // Change the precision used
+    pub fn with_decimals(&self, decimals: u8) -> Result<Self, FixedPointError> {
+        let raw = rescale(self.raw, self.decimals, decimals)?;
+        Ok(Self { raw, decimals })
+    }
+
+    // Parse a decimal string such as "12.345" using the requested precision
+    // Extra fractional digits are truncated
+    pub fn parse(value: &str, decimals: u8) -> Result<Self, FixedPointError> {
+        let scale = scale_for(decimals)?;
+        let (integer, fraction) = match value.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (value, "")
+        };
+
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(FixedPointError::InvalidFormat);
+        }
+
+        if !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(FixedPointError::InvalidFormat);
+        }
+
+        let integer: u128 = if integer.is_empty() {
+            0
+        } else {
+            integer.parse().map_err(|_| FixedPointError::Overflow)?
+        };
+
+        let mut raw = integer.checked_mul(scale)
+            .ok_or(FixedPointError::Overflow)?;
+
+        let mut unit = scale;
+        for c in fraction.chars().take(decimals as usize) {
+            unit /= 10;
+            raw = raw.checked_add(c.to_digit(10).unwrap_or(0) as u128 * unit)
+                .ok_or(FixedPointError::Overflow)?;
+        }
+
+        Ok(Self { raw, decimals })
+    }
+}
+
+impl fmt::Display for FixedPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.decimals == 0 {
+            return write!(f, "{}", self.raw);
+        }
+
+        let scale = self.scale();
+        write!(f, "{}.{:0width$}", self.raw / scale, self.raw % scale, width = self.decimals as usize)
+    }
+}
+
+impl FromStr for FixedPoint {
+    type Err = FixedPointError;
+
+    // Precision is deduced from the number of fractional digits
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let decimals = s.split_once('.')
+            .map(|(_, fraction)| fraction.len())
+            .unwrap_or(0);
+
+        if decimals > MAX_DECIMALS as usize {
+            return Err(FixedPointError::InvalidDecimals(decimals.min(u8::MAX as usize) as u8));
+        }
+
+        Self::parse(s, decimals as u8)
+    }
+}
+
+impl Serializer for FixedPoint {
+    fn write(&self, writer: &mut Writer) {
+        self.raw.write(writer);
+        writer.write_u8(self.decimals);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let raw = u128::read(reader)?;
+        let decimals = reader.read_u8()?;
+
+        Self::from_raw(raw, decimals)
+            .map_err(|_| ReaderError::InvalidValue)
+    }
+
+    fn size(&self) -> usize {
+        self.raw.size() + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div() {
+        let a = FixedPoint::parse("1.5", 8).unwrap();
+        let b = FixedPoint::parse("2.25", 8).unwrap();
+
+        assert_eq!(a.checked_mul(&b).unwrap().to_string(), "3.37500000");
+        assert_eq!(b.checked_div(&a).unwrap().to_string(), "1.50000000");
+    }
+
+    #[test]
+    fn test_rounding_toward_zero() {
+        let third = FixedPoint::from_ratio(1, 3, 4).unwrap();
+        assert_eq!(third.raw(), 3333);
+
+        let value = FixedPoint::parse("1.99999", 2).unwrap();
+        assert_eq!(value.to_string(), "1.99");
+        assert_eq!(value.with_decimals(0).unwrap().raw(), 1);
+    }
+
+    #[test]
+    fn test_no_intermediate_overflow() {
+        // raw values product doesn't fit in a u128
+        let a = FixedPoint::from_integer(u64::MAX as u128, 18).unwrap();
+        let one = FixedPoint::from_integer(1, 18).unwrap();
+        assert_eq!(a.checked_mul(&one).unwrap(), a);
+    }
+
+    #[test]
+    fn test_errors() {
+        let a = FixedPoint::from_integer(1, 8).unwrap();
+        let b = FixedPoint::from_integer(1, 6).unwrap();
+        assert_eq!(a.checked_add(&b), Err(FixedPointError::DecimalsMismatch(8, 6)));
+        assert_eq!(a.checked_div(&FixedPoint::zero(8).unwrap()), Err(FixedPointError::DivisionByZero));
+        assert_eq!(FixedPoint::from_integer(u128::MAX, 1), Err(FixedPointError::Overflow));
+        assert_eq!(FixedPoint::from_raw(0, 39), Err(FixedPointError::InvalidDecimals(39)));
+        assert_eq!(FixedPoint::parse("1.a", 2), Err(FixedPointError::InvalidFormat));
+    }
+
+    #[test]
+    fn test_serializer() {
+        let value = FixedPoint::parse("42.42", 10).unwrap();
+        assert_eq!(FixedPoint::from_bytes(&value.to_bytes()).unwrap(), value);
+        assert_eq!("42.42".parse::<FixedPoint>().unwrap().raw(), 4242);
+    }
+}
@@ -17,6 +17,11 @@ pub mod queue;
 pub mod varuint;
 pub mod time;
 pub mod versioned_type;
+pub mod payment_uri;
+pub mod encoding;
+pub mod emission;
+pub mod fixed_point;
+pub mod metrics;
 
 #[cfg(feature = "tokio")]
 pub mod tokio;
@@ -0,0 +1,106 @@
+// Lightweight instrumentation of the hot functions using the `metrics` facade
+// The application (daemon, wallet) is responsible for installing a recorder to expose them,
+// using `install_prometheus_exporter` with the `prometheus` feature
+// Without the `metrics` feature, all the helpers below do nothing and are compiled out
+
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+
+// Number of values deserialized from bytes
+pub const SERIALIZER_DECODED_TOTAL: &str = "xelis_serializer_decoded_total";
+// Number of bytes read while deserializing
+pub const SERIALIZER_DECODED_BYTES: &str = "xelis_serializer_decoded_bytes_total";
+// Number of values that couldn't be deserialized
+pub const SERIALIZER_DECODE_ERRORS: &str = "xelis_serializer_decode_errors_total";
+// Number of bytes written while serializing
+pub const SERIALIZER_ENCODED_BYTES: &str = "xelis_serializer_encoded_bytes_total";
+
+// Time spent to verify the proofs of a single transaction
+pub const TX_VERIFICATION_SECONDS: &str = "xelis_tx_verification_seconds";
+// Time spent to verify the proofs of a batch of transactions
+pub const TX_BATCH_VERIFICATION_SECONDS: &str = "xelis_tx_batch_verification_seconds";
+// Number of transactions with valid proofs
+pub const TX_VERIFIED_TOTAL: &str = "xelis_tx_verified_total";
+
+// Time spent to decode points using the precomputed tables
+pub const ECDLP_DECODE_SECONDS: &str = "xelis_ecdlp_decode_seconds";
+// Number of points decoded
+pub const ECDLP_DECODED_POINTS: &str = "xelis_ecdlp_decoded_points_total";
+
+// Install the Prometheus recorder and serve the metrics over HTTP on the bind address
+// It must be called from a Tokio runtime as the HTTP listener is spawned on it
+#[cfg(all(feature = "prometheus", not(target_arch = "wasm32")))]
+pub fn install_prometheus_exporter(bind_address: &str) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    let address: std::net::SocketAddr = bind_address.parse()
+        .context("Invalid metrics bind address")?;
+
+    metrics_exporter_prometheus::PrometheusBuilder::new()
+        .with_http_listener(address)
+        .install()
+        .context("Error while installing the Prometheus exporter")
+}
+
+// Increment a counter by the value
+#[inline(always)]
+pub fn increment_counter(name: &'static str, value: u64) {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!(name).increment(value);
+
+    #[cfg(not(feature = "metrics"))]
+    let _ = (name, value);
+}
+
+// Record the elapsed time in seconds in a histogram once dropped
+#[must_use]
+pub struct HistogramTimer {
+    #[cfg(feature = "metrics")]
+    inner: (&'static str, Instant)
+}
+
+impl HistogramTimer {
+    #[inline(always)]
+    pub fn new(name: &'static str) -> Self {
+        #[cfg(not(feature = "metrics"))]
+        let _ = name;
+
+        Self {
+            #[cfg(feature = "metrics")]
+            inner: (name, Instant::now())
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl Drop for HistogramTimer {
+    fn drop(&mut self) {
+        let (name, start) = self.inner;
+        ::metrics::histogram!(name).record(start.elapsed().as_secs_f64());
+    }
+}
+
+#[cfg(all(test, feature = "prometheus", not(target_arch = "wasm32")))]
+mod tests {
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use super::*;
+
+    #[test]
+    fn test_recorded_metrics() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        ::metrics::with_local_recorder(&recorder, || {
+            increment_counter(ECDLP_DECODED_POINTS, 3);
+            drop(HistogramTimer::new(ECDLP_DECODE_SECONDS));
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains(&format!("{} 3", ECDLP_DECODED_POINTS)));
+        assert!(rendered.contains(&format!("{}_count 1", ECDLP_DECODE_SECONDS)));
+    }
+
+    #[test]
+    fn test_invalid_bind_address() {
+        assert!(install_prometheus_exporter("localhost").is_err());
+    }
+}
@@ -0,0 +1,266 @@
+use std::{fmt::{self, Display, Formatter}, str::FromStr};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::{
+    api::DataElement,
+    crypto::{Address, Hash},
+    serializer::Serializer
+};
+
+// Scheme used by payment URIs
+// Format: xelis:<address>?amount=<atomic units>&asset=<hash>&data=<hex>&label=<text>&message=<text>
+pub const PAYMENT_URI_SCHEME: &str = "xelis";
+
+#[derive(Debug, Error)]
+pub enum PaymentUriError {
+    #[error("Invalid scheme, expected '{}'", PAYMENT_URI_SCHEME)]
+    InvalidScheme,
+    #[error("Invalid address")]
+    InvalidAddress,
+    #[error("Invalid amount")]
+    InvalidAmount,
+    #[error("Invalid asset")]
+    InvalidAsset,
+    #[error("Invalid extra data")]
+    InvalidExtraData,
+    #[error("Invalid percent encoding")]
+    InvalidEncoding,
+    #[error("Duplicated parameter '{}'", _0)]
+    DuplicatedParameter(String)
+}
+
+// A payment request that can be shared as a URI or a QR code
+// Amount is in atomic units so it doesn't depend on the asset decimals
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentRequest {
+    pub address: Address,
+    pub amount: Option<u64>,
+    // Asset requested, XELIS if not set
+    pub asset: Option<Hash>,
+    // Extra data to attach to the transfer
+    pub extra_data: Option<DataElement>,
+    // Name of the merchant or recipient
+    pub label: Option<String>,
+    // Message describing the payment
+    pub message: Option<String>
+}
+
+impl PaymentRequest {
+    pub fn new(address: Address) -> Self {
+        Self {
+            address,
+            amount: None,
+            asset: None,
+            extra_data: None,
+            label: None,
+            message: None
+        }
+    }
+
+    pub fn with_amount(mut self, amount: u64) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    pub fn with_asset(mut self, asset: Hash) -> Self {
+        self.asset = Some(asset);
+        self
+    }
+
+    pub fn with_extra_data(mut self, extra_data: DataElement) -> Self {
+        self.extra_data = Some(extra_data);
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    // Build the URI of this payment request
+    pub fn to_uri(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(amount) = self.amount {
+            params.push(format!("amount={}", amount));
+        }
+
+        if let Some(asset) = &self.asset {
+            params.push(format!("asset={}", asset));
+        }
+
+        if let Some(extra_data) = &self.extra_data {
+            params.push(format!("data={}", extra_data.to_hex()));
+        }
+
+        if let Some(label) = &self.label {
+            params.push(format!("label={}", percent_encode(label)));
+        }
+
+        if let Some(message) = &self.message {
+            params.push(format!("message={}", percent_encode(message)));
+        }
+
+        let mut uri = format!("{}:{}", PAYMENT_URI_SCHEME, self.address);
+        if !params.is_empty() {
+            uri.push('?');
+            uri.push_str(&params.join("&"));
+        }
+
+        uri
+    }
+
+    // Parse a payment URI
+    // Unknown parameters are ignored to stay compatible with future versions
+    pub fn from_uri(uri: &str) -> Result<Self, PaymentUriError> {
+        let (scheme, rest) = uri.trim().split_once(':')
+            .ok_or(PaymentUriError::InvalidScheme)?;
+        if !scheme.eq_ignore_ascii_case(PAYMENT_URI_SCHEME) {
+            return Err(PaymentUriError::InvalidScheme)
+        }
+
+        let (address, query) = match rest.split_once('?') {
+            Some((address, query)) => (address, Some(query)),
+            None => (rest, None)
+        };
+
+        let address = Address::from_string(&address.to_owned())
+            .map_err(|_| PaymentUriError::InvalidAddress)?;
+        let mut request = Self::new(address);
+
+        for param in query.into_iter().flat_map(|q| q.split('&')).filter(|p| !p.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            match key {
+                "amount" => {
+                    let amount = value.parse().map_err(|_| PaymentUriError::InvalidAmount)?;
+                    set_once(&mut request.amount, amount, key)?;
+                },
+                "asset" => {
+                    let asset = Hash::from_str(value).map_err(|_| PaymentUriError::InvalidAsset)?;
+                    set_once(&mut request.asset, asset, key)?;
+                },
+                "data" => {
+                    let extra_data = DataElement::from_hex(value).map_err(|_| PaymentUriError::InvalidExtraData)?;
+                    set_once(&mut request.extra_data, extra_data, key)?;
+                },
+                "label" => set_once(&mut request.label, percent_decode(value)?, key)?,
+                "message" => set_once(&mut request.message, percent_decode(value)?, key)?,
+                _ => {}
+            }
+        }
+
+        Ok(request)
+    }
+}
+
+impl Display for PaymentRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_uri())
+    }
+}
+
+impl FromStr for PaymentRequest {
+    type Err = PaymentUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_uri(s)
+    }
+}
+
+fn set_once<T>(field: &mut Option<T>, value: T, key: &str) -> Result<(), PaymentUriError> {
+    if field.is_some() {
+        return Err(PaymentUriError::DuplicatedParameter(key.to_owned()))
+    }
+
+    *field = Some(value);
+    Ok(())
+}
+
+// Encode all bytes except the unreserved characters from RFC 3986
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte))
+        }
+    }
+
+    encoded
+}
+
+fn percent_decode(value: &str) -> Result<String, PaymentUriError> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = bytes.get(i + 1..i + 3).ok_or(PaymentUriError::InvalidEncoding)?;
+                let hex = std::str::from_utf8(hex).map_err(|_| PaymentUriError::InvalidEncoding)?;
+                decoded.push(u8::from_str_radix(hex, 16).map_err(|_| PaymentUriError::InvalidEncoding)?);
+                i += 3;
+            },
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            },
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|_| PaymentUriError::InvalidEncoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::{DataElement, DataValue},
+        config::XELIS_ASSET,
+        crypto::{AddressType, KeyPair}
+    };
+    use super::*;
+
+    #[test]
+    fn test_payment_uri() {
+        let (pub_key, _) = KeyPair::new().split();
+        let address = Address::new(true, AddressType::Normal, pub_key.compress());
+        let request = PaymentRequest::new(address)
+            .with_amount(100_000_000)
+            .with_asset(XELIS_ASSET)
+            .with_extra_data(DataElement::Value(DataValue::U64(42)))
+            .with_label("XELIS shop")
+            .with_message("Order #1 & more");
+
+        let uri = request.to_uri();
+        assert!(uri.starts_with("xelis:xel:"));
+
+        let parsed = PaymentRequest::from_uri(&uri).unwrap();
+        assert_eq!(request, parsed);
+    }
+
+    #[test]
+    fn test_payment_uri_address_only() {
+        let (pub_key, _) = KeyPair::new().split();
+        let address = Address::new(false, AddressType::Normal, pub_key.compress());
+        let uri = format!("xelis:{}", address);
+        let parsed = PaymentRequest::from_uri(&uri).unwrap();
+        assert_eq!(parsed, PaymentRequest::new(address));
+    }
+
+    #[test]
+    fn test_payment_uri_invalid() {
+        let (pub_key, _) = KeyPair::new().split();
+        let address = Address::new(true, AddressType::Normal, pub_key.compress());
+        assert!(PaymentRequest::from_uri(&address.to_string()).is_err());
+        assert!(PaymentRequest::from_uri(&format!("xelis:{}?amount=abc", address)).is_err());
+        assert!(PaymentRequest::from_uri(&format!("xelis:{}?amount=1&amount=2", address)).is_err());
+    }
+}
@@ -2,7 +2,7 @@ use std::{collections::HashMap, pin::Pin, future::Future, fmt::Display, time::{I
 
 use crate::{config::VERSION, async_handler, context::Context};
 
-use super::{argument::*, ShareablePrompt, LogLevel};
+use super::{argument::*, logger, ShareablePrompt, LogLevel};
 use anyhow::Error;
 use thiserror::Error;
 use log::{info, warn, error};
@@ -30,7 +30,9 @@ pub enum CommandError {
     #[error(transparent)]
     Any(#[from] Error),
     #[error("Poison Error: {}", _0)]
-    PoisonError(String)
+    PoisonError(String),
+    #[error("Command {} is already registered", _0)]
+    CommandAlreadyRegistered(String)
 }
 
 impl<T> From<PoisonError<T>> for CommandError {
@@ -138,6 +140,61 @@ impl Command {
     }
 }
 
+// Module providing its own commands to the prompt
+// Crates building on top of an application can implement it
+// to add their commands without patching the prompt
+pub trait CommandModule {
+    // Name of the module
+    fn name(&self) -> &str;
+
+    // All the commands provided by the module
+    fn commands(&self) -> Vec<Command>;
+}
+
+// Collect the commands of each module before registering them in the CommandManager
+#[derive(Default)]
+pub struct CommandRegistry {
+    modules: Vec<(String, Vec<Command>)>
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Add a module to the registry
+    // A command name can only be provided by one module
+    pub fn register<M: CommandModule>(&mut self, module: M) -> Result<(), CommandError> {
+        let commands = module.commands();
+        for (i, command) in commands.iter().enumerate() {
+            let duplicated = commands[..i].iter().any(|cmd| cmd.get_name() == command.get_name())
+                || self.get_module_of(command.get_name()).is_some();
+
+            if duplicated {
+                return Err(CommandError::CommandAlreadyRegistered(command.get_name().clone()));
+            }
+        }
+
+        self.modules.push((module.name().to_owned(), commands));
+        Ok(())
+    }
+
+    // Name of the module providing this command
+    pub fn get_module_of(&self, command_name: &str) -> Option<&str> {
+        self.modules.iter()
+            .find(|(_, commands)| commands.iter().any(|cmd| cmd.get_name() == command_name))
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn get_modules(&self) -> impl Iterator<Item = &str> {
+        self.modules.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn into_commands(self) -> impl Iterator<Item = Command> {
+        self.modules.into_iter().flat_map(|(_, commands)| commands)
+    }
+}
+
 // We use Mutex from std instead of tokio so we can use it in sync code too
 pub struct CommandManager {
     commands: Mutex<Vec<Rc<Command>>>,
@@ -197,25 +254,49 @@ impl CommandManager {
     pub fn add_command(&self, command: Command) -> Result<(), CommandError> {
         let mut commands = self.commands.lock()?;
         commands.push(Rc::new(command));
+        self.update_completions(&commands)
+    }
+
+    // Register all the commands of a registry
+    pub fn add_registry(&self, registry: CommandRegistry) -> Result<(), CommandError> {
+        for command in registry.into_commands() {
+            if self.has_command(command.get_name())? {
+                return Err(CommandError::CommandAlreadyRegistered(command.get_name().clone()));
+            }
+            self.add_command(command)?;
+        }
         Ok(())
     }
 
+    pub fn has_command(&self, command_name: &str) -> Result<bool, CommandError> {
+        let commands = self.commands.lock()?;
+        Ok(commands.iter().any(|cmd| cmd.get_name() == command_name))
+    }
+
     pub fn remove_all_commands(&self) -> Result<(), CommandError> {
         let mut commands = self.commands.lock()?;
         commands.clear();
-        Ok(())
+        self.update_completions(&commands)
     }
 
     pub fn remove_command(&self, command_name: &str) -> Result<bool, CommandError> {
         let mut commands = self.commands.lock()?;
         if let Some(index) = commands.iter().position(|cmd| cmd.get_name() == command_name) {
             commands.remove(index);
+            self.update_completions(&commands)?;
             Ok(true)
         } else {
             Ok(false)
         }
     }
 
+    // Keep the prompt completion in sync with the commands registered
+    fn update_completions(&self, commands: &[Rc<Command>]) -> Result<(), CommandError> {
+        let names = commands.iter().map(|cmd| cmd.get_name().clone()).collect();
+        self.prompt.set_completions(names)?;
+        Ok(())
+    }
+
     pub fn get_commands(&self) -> &Mutex<Vec<Rc<Command>>> {
         &self.commands
     }
@@ -301,8 +382,48 @@ fn version(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandEr
 fn set_log_level(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
     let arg_value = args.get_value("level")?.to_string_value()?;
     let level = LogLevel::from_str(&arg_value).map_err(|e| CommandError::InvalidArgument(e.to_owned()))?;
-    log::set_max_level(level.into());
+    logger::set_log_level(level);
     manager.message(format!("Log level set to {}", level));
 
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestModule(&'static str, Vec<&'static str>);
+
+    impl CommandModule for TestModule {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn commands(&self) -> Vec<Command> {
+            self.1.iter()
+                .map(|name| Command::new(name, "test", CommandHandler::Sync(version)))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_registry_modules() {
+        let mut registry = CommandRegistry::new();
+        registry.register(TestModule("a", vec!["foo", "bar"])).unwrap();
+        registry.register(TestModule("b", vec!["baz"])).unwrap();
+
+        assert_eq!(registry.get_modules().collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(registry.get_module_of("baz"), Some("b"));
+        assert_eq!(registry.get_module_of("unknown"), None);
+        assert_eq!(registry.into_commands().count(), 3);
+    }
+
+    #[test]
+    fn test_registry_duplicated_command() {
+        let mut registry = CommandRegistry::new();
+        registry.register(TestModule("a", vec!["foo"])).unwrap();
+
+        assert!(matches!(registry.register(TestModule("b", vec!["foo"])), Err(CommandError::CommandAlreadyRegistered(_))));
+        assert!(matches!(registry.register(TestModule("c", vec!["bar", "bar"])), Err(CommandError::CommandAlreadyRegistered(_))));
+        assert_eq!(registry.get_modules().count(), 1);
+    }
+}
@@ -0,0 +1,279 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Result as IOResult, Write},
+    path::PathBuf,
+    sync::RwLock,
+    time::{Duration, Instant}
+};
+use chrono::SecondsFormat;
+use log::{
+    kv::{Error as KvError, Key, Value, VisitSource},
+    LevelFilter,
+    Metadata,
+    Record
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use super::{LogLevel, ModuleConfig};
+
+// Format of the log lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum LogFormat {
+    #[default]
+    Text,
+    // One JSON object per line with the structured fields of the record
+    Json
+}
+
+// Rotation of the log file based on its size and/or age
+// It replaces the daily rotation of the log file
+#[derive(Debug, Clone)]
+pub struct LogRotation {
+    // Maximum size in bytes of the log file
+    pub max_size: Option<u64>,
+    // Maximum duration before rotating the log file
+    pub interval: Option<Duration>,
+    // Rotated files to keep, 0 to keep all of them
+    pub max_files: usize
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LogOptions {
+    pub format: LogFormat,
+    pub rotation: Option<LogRotation>
+}
+
+// Levels used to filter the logs, they can be updated at runtime
+struct LogLevels {
+    stdout: LevelFilter,
+    file: LevelFilter,
+    // Level per module, it overrides the levels above
+    modules: Vec<(String, LevelFilter)>
+}
+
+static LOG_LEVELS: RwLock<LogLevels> = RwLock::new(LogLevels {
+    stdout: LevelFilter::Info,
+    file: LevelFilter::Info,
+    modules: Vec::new()
+});
+
+// Check if a target is the module or one of its submodules
+fn is_in_module(target: &str, module: &str) -> bool {
+    target.strip_prefix(module).is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+impl LogLevels {
+    // Level of the most specific module configured for this target
+    fn get_module_level(&self, target: &str) -> Option<LevelFilter> {
+        self.modules.iter()
+            .filter(|(module, _)| is_in_module(target, module))
+            .max_by_key(|(module, _)| module.len())
+            .map(|(_, level)| *level)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.modules.iter()
+            .map(|(_, level)| *level)
+            .fold(self.stdout.max(self.file), LevelFilter::max)
+    }
+
+    // Update the global max level so disabled records are skipped by the log macros directly
+    fn apply_max_level(&self) {
+        log::set_max_level(self.max_level());
+    }
+}
+
+// Initialize the levels used by the logger
+pub(super) fn init_log_levels(stdout: LogLevel, file: LogLevel, modules: Vec<(String, LevelFilter)>) {
+    let mut levels = LOG_LEVELS.write().unwrap();
+    levels.stdout = stdout.into();
+    levels.file = file.into();
+    levels.modules = modules;
+}
+
+// Apply the max level once the logger is set
+pub(super) fn apply_max_level() {
+    LOG_LEVELS.read().unwrap().apply_max_level();
+}
+
+// Check if the record should be written to stdout
+pub(super) fn is_stdout_enabled(metadata: &Metadata) -> bool {
+    let levels = LOG_LEVELS.read().unwrap();
+    metadata.level() <= levels.get_module_level(metadata.target()).unwrap_or(levels.stdout)
+}
+
+// Check if the record should be written to the log file
+pub(super) fn is_file_enabled(metadata: &Metadata) -> bool {
+    let levels = LOG_LEVELS.read().unwrap();
+    metadata.level() <= levels.get_module_level(metadata.target()).unwrap_or(levels.file)
+}
+
+// Set the level of both stdout and file logs
+pub fn set_log_level(level: LogLevel) {
+    let mut levels = LOG_LEVELS.write().unwrap();
+    levels.stdout = level.into();
+    levels.file = level.into();
+    levels.apply_max_level();
+}
+
+// Set the level of a module, it overrides the default levels
+pub fn set_module_log_level(module: String, level: LogLevel) {
+    let mut levels = LOG_LEVELS.write().unwrap();
+    match levels.modules.iter_mut().find(|(m, _)| *m == module) {
+        Some((_, l)) => *l = level.into(),
+        None => levels.modules.push((module, level.into()))
+    };
+    levels.apply_max_level();
+}
+
+// Remove the level of a module, returns false if it had none
+pub fn remove_module_log_level(module: &str) -> bool {
+    let mut levels = LOG_LEVELS.write().unwrap();
+    let len = levels.modules.len();
+    levels.modules.retain(|(m, _)| m != module);
+    levels.apply_max_level();
+    len != levels.modules.len()
+}
+
+fn to_log_level(level: LevelFilter) -> LogLevel {
+    match level {
+        LevelFilter::Off => LogLevel::Off,
+        LevelFilter::Error => LogLevel::Error,
+        LevelFilter::Warn => LogLevel::Warn,
+        LevelFilter::Info => LogLevel::Info,
+        LevelFilter::Debug => LogLevel::Debug,
+        LevelFilter::Trace => LogLevel::Trace
+    }
+}
+
+// Levels of stdout and file logs
+pub fn get_log_levels() -> (LogLevel, LogLevel) {
+    let levels = LOG_LEVELS.read().unwrap();
+    (to_log_level(levels.stdout), to_log_level(levels.file))
+}
+
+pub fn get_module_log_levels() -> Vec<ModuleConfig> {
+    LOG_LEVELS.read().unwrap().modules.iter()
+        .map(|(module, level)| ModuleConfig {
+            module: module.clone(),
+            level: to_log_level(*level)
+        })
+        .collect()
+}
+
+// Collect the key-values of a record such as peer, block_hash or tx_hash
+struct FieldsVisitor<'a>(&'a mut Map<String, JsonValue>);
+
+impl<'kvs> VisitSource<'kvs> for FieldsVisitor<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), KvError> {
+        self.0.insert(key.as_str().to_owned(), JsonValue::String(value.to_string()));
+        Ok(())
+    }
+}
+
+// Build the JSON line of a record
+pub(super) fn format_json(message: &std::fmt::Arguments, record: &Record) -> String {
+    let mut fields = Map::new();
+    fields.insert("timestamp".to_owned(), JsonValue::String(chrono::Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)));
+    fields.insert("level".to_owned(), JsonValue::String(record.level().to_string()));
+    fields.insert("module".to_owned(), JsonValue::String(record.target().to_owned()));
+    fields.insert("message".to_owned(), JsonValue::String(message.to_string()));
+
+    if let Err(e) = record.key_values().visit(&mut FieldsVisitor(&mut fields)) {
+        fields.insert("fields_error".to_owned(), JsonValue::String(e.to_string()));
+    }
+
+    JsonValue::Object(fields).to_string()
+}
+
+// Log file rotated when it reaches the maximum size or age
+// Rotated files are renamed with the date of the rotation as suffix
+pub struct RotatingFile {
+    dir: PathBuf,
+    filename: String,
+    file: File,
+    size: u64,
+    opened_at: Instant,
+    rotation: LogRotation
+}
+
+impl RotatingFile {
+    pub fn new(dir: PathBuf, filename: String, rotation: LogRotation) -> IOResult<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(dir.join(&filename))?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            dir,
+            filename,
+            file,
+            size,
+            opened_at: Instant::now(),
+            rotation
+        })
+    }
+
+    fn should_rotate(&self, len: usize) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+
+        self.rotation.max_size.is_some_and(|max| self.size + len as u64 > max)
+            || self.rotation.interval.is_some_and(|interval| self.opened_at.elapsed() >= interval)
+    }
+
+    fn rotate(&mut self) -> IOResult<()> {
+        self.file.flush()?;
+
+        let suffix = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S%.3f");
+        let path = self.dir.join(&self.filename);
+        fs::rename(&path, self.dir.join(format!("{}.{}", self.filename, suffix)))?;
+
+        self.file = OpenOptions::new().create(true).append(true).open(&path)?;
+        self.size = 0;
+        self.opened_at = Instant::now();
+
+        self.delete_old_files()
+    }
+
+    // Delete the oldest rotated files above the limit
+    fn delete_old_files(&self) -> IOResult<()> {
+        if self.rotation.max_files == 0 {
+            return Ok(());
+        }
+
+        let prefix = format!("{}.", self.filename);
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            if entry.file_name().to_str().is_some_and(|name| name.starts_with(&prefix)) {
+                files.push(entry.path());
+            }
+        }
+
+        // Suffix is the date so the oldest files are first
+        files.sort();
+        let count = files.len().saturating_sub(self.rotation.max_files);
+        for path in files.into_iter().take(count) {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> IOResult<usize> {
+        if self.should_rotate(buf.len()) {
+            self.rotate()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> IOResult<()> {
+        self.file.flush()
+    }
+}
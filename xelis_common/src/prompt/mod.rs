@@ -1,5 +1,6 @@
 pub mod command;
 pub mod argument;
+pub mod logger;
 
 use crate::{
     tokio::{
@@ -41,7 +42,10 @@ use crossterm::{
     event::{self, Event, KeyCode, KeyModifiers, KeyEventKind},
     terminal as crossterminal,
 };
-use self::command::{CommandError, CommandManager};
+use self::{
+    command::{CommandError, CommandManager},
+    logger::{LogFormat, LogOptions, RotatingFile}
+};
 use anyhow::Error;
 use fern::colors::ColoredLevelConfig;
 use regex::Regex;
@@ -187,7 +191,9 @@ struct State {
     prompt_sender: Mutex<Option<oneshot::Sender<String>>>,
     has_exited: AtomicBool,
     ascii_escape_regex: Regex,
-    interactive: bool
+    interactive: bool,
+    // command names used to complete the input
+    completions: Mutex<Vec<String>>
 }
 
 impl State {
@@ -206,7 +212,8 @@ impl State {
             prompt_sender: Mutex::new(None),
             has_exited: AtomicBool::new(false),
             ascii_escape_regex: Regex::new("\x1B\\[[0-9;]*[A-Za-z]").unwrap(),
-            interactive
+            interactive,
+            completions: Mutex::new(Vec::new())
         }
     }
 
@@ -309,6 +316,13 @@ impl State {
                                     buffer.push(c);
                                     self.show_input(&buffer)?;
                                 },
+                                KeyCode::Tab => {
+                                    is_in_history = false;
+                                    let mut buffer = self.user_input.lock()?;
+                                    if self.complete_input(&mut buffer)? {
+                                        self.show_input(&buffer)?;
+                                    }
+                                },
                                 KeyCode::Backspace => {
                                     is_in_history = false;
                                     let mut buffer = self.user_input.lock()?;
@@ -380,6 +394,44 @@ impl State {
         self.mask_input.load(Ordering::SeqCst)
     }
 
+    // Complete the command name typed with the longest common prefix of the matching commands
+    // Returns true if the input has been updated
+    fn complete_input(&self, buffer: &mut String) -> Result<bool, PromptError> {
+        // only the command name is completed
+        if buffer.is_empty() || buffer.contains(char::is_whitespace) {
+            return Ok(false)
+        }
+
+        let completions = self.completions.lock()?;
+        let mut matches = completions.iter().filter(|name| name.starts_with(buffer.as_str()));
+        let Some(first) = matches.next() else {
+            return Ok(false)
+        };
+
+        let mut prefix_len = first.len();
+        let mut unique = true;
+        for name in matches {
+            unique = false;
+            prefix_len = first.chars()
+                .zip(name.chars())
+                .take_while(|(a, b)| a == b)
+                .count()
+                .min(prefix_len);
+        }
+
+        let mut completed: String = first.chars().take(prefix_len).collect();
+        if unique {
+            completed.push(' ');
+        }
+
+        if completed == *buffer {
+            return Ok(false)
+        }
+
+        *buffer = completed;
+        Ok(true)
+    }
+
     fn count_lines(&self, value: &String) -> usize {
         let width = self.width.load(Ordering::SeqCst);
 
@@ -495,6 +547,7 @@ impl Prompt {
         interactive: bool,
         module_logs: Vec<ModuleConfig>,
         file_level: LogLevel,
+        options: LogOptions
     ) -> Result<ShareablePrompt, PromptError> {
         if !dir_path.ends_with("/") {
             return Err(PromptError::LogsPathNotFolder);
@@ -508,7 +561,7 @@ impl Prompt {
             read_input_sender,
             disable_colors
         };
-        prompt.setup_logger(level, dir_path, filename_log, disable_file_logging, disable_file_log_date_based, module_logs, file_level)?;
+        prompt.setup_logger(level, dir_path, filename_log, disable_file_logging, disable_file_log_date_based, module_logs, file_level, options)?;
 
         // Logs all the panics into the log file
         log_panics::init();
@@ -757,6 +810,13 @@ impl Prompt {
         input
     }
 
+    // Set the command names used for the input completion
+    pub fn set_completions(&self, completions: Vec<String>) -> Result<(), PromptError> {
+        let mut values = self.state.completions.lock()?;
+        *values = completions;
+        Ok(())
+    }
+
     // should we replace user input by * ?
     pub fn should_mask_input(&self) -> bool {
         self.state.should_mask_input()
@@ -776,7 +836,8 @@ impl Prompt {
         disable_file_logging: bool,
         disable_file_log_date_based: bool,
         module_logs: Vec<ModuleConfig>,
-        file_level: LogLevel
+        file_level: LogLevel,
+        options: LogOptions
     ) -> Result<(), fern::InitError> {
         let colors = ColoredLevelConfig::new()
             .debug(Color::Green)
@@ -789,8 +850,26 @@ impl Prompt {
         let disable_colors = self.disable_colors;
         let interactive = self.state.is_interactive();
         let state = Arc::clone(&self.state);
+        let format = options.format;
         let stdout_log = fern::Dispatch::new()
+            .filter(logger::is_stdout_enabled)
             .format(move |out, message, record| {
+                if format == LogFormat::Json {
+                    let res = out.finish(format_args!(
+                        "\x1b[2K{}{}",
+                        if interactive { "\r" } else { "" },
+                        logger::format_json(message, record)
+                    ));
+
+                    if interactive {
+                        if let Err(e) = state.show() {
+                            error!("Error on prompt refresh: {}", e);
+                        }
+                    }
+
+                    return res;
+                }
+
                 let target = record.target();
                 let mut target_with_pad = " ".repeat((30i16 - target.len() as i16).max(0) as usize) + target;
                 if record.level() != Level::Error && record.level() != Level::Debug {
@@ -825,8 +904,7 @@ impl Prompt {
 
                 res
             })
-            .chain(std::io::stdout())
-            .level(level.into());
+            .chain(std::io::stdout());
 
         let mut base = base.chain(stdout_log);
         if !disable_file_logging {
@@ -836,8 +914,12 @@ impl Prompt {
             }
 
             let mut file_log = fern::Dispatch::new()
-            .level(file_level.into())
+            .filter(logger::is_file_enabled)
             .format(move |out, message, record| {
+                if format == LogFormat::Json {
+                    return out.finish(format_args!("{}", logger::format_json(message, record)));
+                }
+
                 let pad = " ".repeat((30i16 - record.target().len() as i16).max(0) as usize);
                 let level_pad = if record.level() == Level::Error || record.level() == Level::Debug { "" } else { " " };
                 out.finish(format_args!(
@@ -851,8 +933,12 @@ impl Prompt {
                 ))
             });
 
-            // Don't rotate the log file based on date ourself if its disabled
-            if !disable_file_log_date_based {
+            // Rotation by size or age replaces the daily rotation
+            if let Some(rotation) = options.rotation {
+                let file: Box<dyn Write + Send> = Box::new(RotatingFile::new(logs_path.to_path_buf(), filename_log.to_owned(), rotation)?);
+                file_log = file_log.chain(file);
+            } else if !disable_file_log_date_based {
+                // Don't rotate the log file based on date ourself if its disabled
                 file_log = file_log.chain(fern::DateBased::new(logs_path, format!("%Y-%m-%d.{filename_log}")));
             } else {
                 file_log = file_log.chain(fern::log_file(format!("{}/{}", dir_path, filename_log))?)
@@ -863,22 +949,28 @@ impl Prompt {
 
         // Default log level modules
         // It can be overriden by the user below
-        base = base.level_for("sled", log::LevelFilter::Warn)
-            .level_for("actix_server", log::LevelFilter::Warn)
-            .level_for("actix_web", log::LevelFilter::Off)
-            .level_for("actix_http", log::LevelFilter::Off)
-            .level_for("tracing", log::LevelFilter::Off)
-            .level_for("runtime", log::LevelFilter::Off)
-            .level_for("tokio", log::LevelFilter::Off)
-            .level_for("mio", log::LevelFilter::Warn)
-            .level_for("tokio_tungstenite", log::LevelFilter::Warn)
-            .level_for("tungstenite", log::LevelFilter::Warn);
+        let mut modules = vec![
+            ("sled".to_owned(), LevelFilter::Warn),
+            ("actix_server".to_owned(), LevelFilter::Warn),
+            ("actix_web".to_owned(), LevelFilter::Off),
+            ("actix_http".to_owned(), LevelFilter::Off),
+            ("tracing".to_owned(), LevelFilter::Off),
+            ("runtime".to_owned(), LevelFilter::Off),
+            ("tokio".to_owned(), LevelFilter::Off),
+            ("mio".to_owned(), LevelFilter::Warn),
+            ("tokio_tungstenite".to_owned(), LevelFilter::Warn),
+            ("tungstenite".to_owned(), LevelFilter::Warn)
+        ];
 
         for m in module_logs {
-            base = base.level_for(m.module, m.level.into());
+            modules.retain(|(module, _)| *module != m.module);
+            modules.push((m.module, m.level.into()));
         }
 
+        // Levels are checked by the filters above so they can be updated at runtime
+        logger::init_log_levels(level, file_level, modules);
         base.apply()?;
+        logger::apply_max_level();
 
         Ok(())
     }
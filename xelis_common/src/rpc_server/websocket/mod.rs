@@ -5,7 +5,7 @@ use std::{
     collections::HashSet,
     hash::{Hash, Hasher},
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc
     },
     time::{Duration, Instant}
@@ -36,6 +36,7 @@ use crate::{
                 UnboundedSender
             },
             Mutex,
+            Notify,
             RwLock
         },
         time::{
@@ -59,6 +60,29 @@ const MESSAGE_TIME_OUT: Duration = Duration::from_secs(1);
 const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
 // timeout in seconds to receive a pong message
 const KEEP_ALIVE_TIME_OUT: Duration = Duration::from_secs(30);
+// default maximum of outbound messages queued per session
+pub const DEFAULT_MAX_QUEUED_MESSAGES: usize = 1024;
+// default maximum size of a received frame
+pub const DEFAULT_MAX_FRAME_SIZE: usize = MAX_BLOCK_SIZE;
+
+// Limits applied to each session of a WebSocket server
+#[derive(Debug, Clone, Copy)]
+pub struct WebSocketServerConfig {
+    // Maximum outbound messages waiting to be sent to a session
+    // If a session reach this limit, it is considered as a slow consumer and disconnected
+    pub max_queued_messages: usize,
+    // Maximum size in bytes of a frame received from a session
+    pub max_frame_size: usize
+}
+
+impl Default for WebSocketServerConfig {
+    fn default() -> Self {
+        Self {
+            max_queued_messages: DEFAULT_MAX_QUEUED_MESSAGES,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE
+        }
+    }
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum WebSocketError {
@@ -72,6 +96,8 @@ pub enum WebSocketError {
     ChannelAlreadyClosed,
     #[error(transparent)]
     Elapsed(#[from] Elapsed),
+    #[error("session has too many queued messages ({}), disconnecting it", _0)]
+    SlowConsumer(usize),
 }
 
 enum InnerMessage {
@@ -85,7 +111,12 @@ pub struct WebSocketSession<H: WebSocketHandler + 'static> {
     server: WebSocketServerShared<H>,
     inner: Mutex<Option<Session>>,
     // Sender to send messages to the session
-    channel: UnboundedSender<InnerMessage>
+    channel: UnboundedSender<InnerMessage>,
+    // Count of text messages waiting in the channel
+    queued_messages: AtomicUsize,
+    // Notified when the session must be disconnected
+    // without waiting for the queued messages to be sent
+    disconnect: Notify
 }
 
 impl<H> WebSocketSession<H>
@@ -93,13 +124,30 @@ where
     H: WebSocketHandler + 'static
 {
     // Send a text message to the session
+    // If the session has too many messages queued, it is disconnected
     pub async fn send_text<S: Into<String>>(self: &Arc<Self>, value: S) -> Result<(), WebSocketError> {
-        self.channel.send(InnerMessage::Text(value.into()))
-            .map_err(|e| WebSocketError::ChannelClosed(e.to_string()))?;
+        let max = self.server.config.max_queued_messages;
+        let queued = self.queued_messages.fetch_add(1, Ordering::SeqCst);
+        if queued >= max {
+            self.queued_messages.fetch_sub(1, Ordering::SeqCst);
+            debug!("session #{} is a slow consumer with {} queued messages", self.id, queued);
+            self.disconnect.notify_one();
+            return Err(WebSocketError::SlowConsumer(queued));
+        }
+
+        if let Err(e) = self.channel.send(InnerMessage::Text(value.into())) {
+            self.queued_messages.fetch_sub(1, Ordering::SeqCst);
+            return Err(WebSocketError::ChannelClosed(e.to_string()));
+        }
 
         Ok(())
     }
 
+    // Returns the number of text messages waiting to be sent
+    pub fn count_queued_messages(&self) -> usize {
+        self.queued_messages.load(Ordering::SeqCst)
+    }
+
     // Send a ping message to the session
     // this must be called from the task handling the session only
     async fn ping(&self) -> Result<(), WebSocketError> {
@@ -200,18 +248,29 @@ pub trait WebSocketHandler: Sized + Sync + Send {
 pub struct WebSocketServer<H: WebSocketHandler + 'static> {
     sessions: RwLock<HashSet<WebSocketSessionShared<H>>>,
     id_counter: AtomicU64,
-    handler: H
+    handler: H,
+    config: WebSocketServerConfig
 }
 
 impl<H> WebSocketServer<H> where H: WebSocketHandler + 'static {
     pub fn new(handler: H) -> WebSocketServerShared<H> {
+        Self::with_config(handler, WebSocketServerConfig::default())
+    }
+
+    pub fn with_config(handler: H, config: WebSocketServerConfig) -> WebSocketServerShared<H> {
         Arc::new(Self {
             sessions: RwLock::new(HashSet::new()),
             id_counter: AtomicU64::new(0),
-            handler
+            handler,
+            config
         })
     }
 
+    // Returns the limits applied to each session
+    pub fn get_config(&self) -> &WebSocketServerConfig {
+        &self.config
+    }
+
     // Turns off all connections
     pub async fn stop(&self) {
         if let Err(e) = self.clear_connections().await {
@@ -261,7 +320,9 @@ impl<H> WebSocketServer<H> where H: WebSocketHandler + 'static {
             request: request.into(),
             server: Arc::clone(&self),
             inner: Mutex::new(Some(session)),
-            channel: tx
+            channel: tx,
+            queued_messages: AtomicUsize::new(0),
+            disconnect: Notify::new()
         });
 
         {
@@ -271,7 +332,7 @@ impl<H> WebSocketServer<H> where H: WebSocketHandler + 'static {
             debug!("Session #{} has been inserted into sessions: {}", id, res);
         }
 
-        actix_rt::spawn(Arc::clone(self).handle_ws_internal(session, stream.max_frame_size(MAX_BLOCK_SIZE).aggregate_continuations(), rx));
+        actix_rt::spawn(Arc::clone(self).handle_ws_internal(session, stream.max_frame_size(self.config.max_frame_size).aggregate_continuations(), rx));
         Ok(response)
     }
 
@@ -338,9 +399,18 @@ impl<H> WebSocketServer<H> where H: WebSocketHandler + 'static {
                         break None;
                     }
                 },
+                // slow consumer, don't wait for the queued messages
+                _ = session.disconnect.notified() => {
+                    debug!("Disconnecting slow consumer session #{}", session.id);
+                    break Some(CloseReason {
+                        code: CloseCode::Policy,
+                        description: Some("Too many queued messages".to_owned())
+                    });
+                },
                 Some(msg) = rx.recv() => {
                     match msg {
                         InnerMessage::Text(text) => {
+                            session.queued_messages.fetch_sub(1, Ordering::SeqCst);
                             trace!("Sending text message to session #{}: {}", session.id, text);
                             if let Err(e) = session.send_text_internal(text).await {
                                 debug!("Error while sending text message to session #{}: {}", session.id, e);
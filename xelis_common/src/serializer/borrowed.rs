@@ -0,0 +1,130 @@
+use log::warn;
+use crate::crypto::Hash;
+use super::{Reader, ReaderError, Serializer, DEFAULT_MAX_ITEMS};
+
+// Read a value borrowing the bytes of the reader instead of allocating
+// Every `Serializer` can be read this way, it simply returns an owned value
+// Borrowed values use the same encoding as their owned version:
+// `&[u8]` is read as a `Vec<u8>` and `&str` as a `String`
+pub trait SerializerBorrowed<'a>: Sized {
+    fn read_borrowed(reader: &mut Reader<'a>) -> Result<Self, ReaderError>;
+}
+
+// Escape hatch to convert a borrowed value into an owned one
+// when it must outlive the bytes it was read from
+pub trait SerializerOwned<'a>: SerializerBorrowed<'a> {
+    type Owned: Serializer;
+
+    fn into_owned(self) -> Self::Owned;
+}
+
+impl<'a, T: Serializer> SerializerBorrowed<'a> for T {
+    fn read_borrowed(reader: &mut Reader<'a>) -> Result<Self, ReaderError> {
+        T::read(reader)
+    }
+}
+
+impl<'a> SerializerBorrowed<'a> for &'a [u8] {
+    fn read_borrowed(reader: &mut Reader<'a>) -> Result<Self, ReaderError> {
+        let count = reader.read_u16()? as usize;
+        if count > DEFAULT_MAX_ITEMS {
+            warn!("Received {} while maximum is set to {}", count, DEFAULT_MAX_ITEMS);
+            return Err(ReaderError::InvalidSize)
+        }
+
+        reader.read_bytes_ref(count)
+    }
+}
+
+impl<'a> SerializerOwned<'a> for &'a [u8] {
+    type Owned = Vec<u8>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.to_vec()
+    }
+}
+
+impl<'a> SerializerBorrowed<'a> for &'a str {
+    fn read_borrowed(reader: &mut Reader<'a>) -> Result<Self, ReaderError> {
+        let size = reader.read_u8()?;
+        reader.read_str_with_size_ref(size as usize)
+    }
+}
+
+impl<'a> SerializerOwned<'a> for &'a str {
+    type Owned = String;
+
+    fn into_owned(self) -> Self::Owned {
+        self.to_owned()
+    }
+}
+
+impl<'a> SerializerBorrowed<'a> for &'a Hash {
+    fn read_borrowed(reader: &mut Reader<'a>) -> Result<Self, ReaderError> {
+        reader.read_hash_ref()
+    }
+}
+
+impl<'a> SerializerOwned<'a> for &'a Hash {
+    type Owned = Hash;
+
+    fn into_owned(self) -> Self::Owned {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serializer::Writer;
+
+    #[test]
+    fn test_read_borrowed() {
+        let hash = Hash::new([7u8; 32]);
+        let mut bytes = Vec::new();
+        let mut writer = Writer::new(&mut bytes);
+        vec![1u8, 2, 3].write(&mut writer);
+        writer.write_string("xelis");
+        hash.write(&mut writer);
+
+        let mut reader = Reader::new(&bytes);
+        let slice: &[u8] = reader.read_borrowed().unwrap();
+        let string: &str = reader.read_borrowed().unwrap();
+        let borrowed: &Hash = reader.read_borrowed().unwrap();
+        assert_eq!(reader.size(), 0);
+
+        assert_eq!(slice, &[1, 2, 3]);
+        assert_eq!(string, "xelis");
+        assert_eq!(*borrowed, hash);
+
+        // Values point into the reader bytes, nothing was copied
+        let range = bytes.as_ptr_range();
+        assert!(range.contains(&slice.as_ptr()));
+        assert!(range.contains(&string.as_ptr()));
+        assert!(range.contains(&borrowed.as_bytes().as_ptr()));
+    }
+
+    #[test]
+    fn test_read_owned() {
+        let bytes = vec![1u8, 2, 3].to_bytes();
+
+        // Same encoding as the owned version
+        let owned = Reader::new(&bytes).read_owned::<&[u8]>().unwrap();
+        assert_eq!(owned, Vec::<u8>::from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn test_read_borrowed_invalid() {
+        // Not enough bytes for a hash
+        let mut reader = Reader::new(&[0u8; 31]);
+        assert!(reader.read_borrowed::<&Hash>().is_err());
+
+        // Size prefix bigger than the remaining bytes
+        let mut reader = Reader::new(&[0u8, 4, 1, 2]);
+        assert!(reader.read_borrowed::<&[u8]>().is_err());
+
+        // Invalid UTF-8
+        let mut reader = Reader::new(&[2u8, 0xC3, 0x28]);
+        assert!(reader.read_borrowed::<&str>().is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use log::debug;
+use super::{Reader, ReaderError, Serializer, Writer};
+
+// Size of the envelope header: tag (u16) + version (u8) + length (u32)
+pub const ENVELOPE_HEADER_SIZE: usize = 2 + 1 + 4;
+
+// A structure stored or sent inside a versioned envelope
+//
+// The envelope is made of a type tag, the version of the writer and the length of the fields.
+// New optional fields are appended at the end of the fields in a new version:
+// - an older node reads the fields it knows and skips the rest using the length
+// - a newer node reads an older version and uses a default value for the missing fields
+//
+// Only for non-consensus data (P2P metadata, local storage) as different nodes
+// may read different values from the same bytes.
+pub trait Versioned: Sized {
+    // Tag identifying the type of the structure
+    const TAG: u16;
+    // Version written by this node
+    const VERSION: u8;
+
+    // Write all the fields of the current version
+    fn write_fields(&self, writer: &mut Writer);
+
+    // Read the fields written by the given version
+    // Fields appended after it are not present, see `Reader::read_if_remaining`
+    fn read_fields(reader: &mut Reader, version: u8) -> Result<Self, ReaderError>;
+}
+
+// Wrap a versioned structure to serialize it in its envelope
+pub struct Envelope<T: Versioned>(pub T);
+
+impl<T: Versioned> Envelope<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Versioned> Serializer for Envelope<T> {
+    fn write(&self, writer: &mut Writer) {
+        let mut fields = Vec::new();
+        self.0.write_fields(&mut Writer::new(&mut fields));
+
+        writer.write_u16(T::TAG);
+        writer.write_u8(T::VERSION);
+        writer.write_u32(&(fields.len() as u32));
+        writer.write_bytes(&fields);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let tag = reader.read_u16()?;
+        if tag != T::TAG {
+            debug!("Invalid envelope tag {}, expected {}", tag, T::TAG);
+            return Err(ReaderError::InvalidValue)
+        }
+
+        let version = reader.read_u8()?;
+        let len = reader.read_u32()? as usize;
+        let bytes = reader.read_bytes_ref(len)?;
+
+        // Fields are read from their own reader, so unknown fields
+        // appended by a newer version are skipped
+        let mut fields = Reader::new(bytes);
+        T::read_fields(&mut fields, version).map(Self)
+    }
+}
+
+// Read a structure stored in its envelope, or in its raw format if it was written
+// before being wrapped in an envelope
+// The tag must be chosen so it can't be the start of the raw format
+pub fn read_versioned_or_legacy<T: Versioned + Serializer>(bytes: &[u8]) -> Result<T, ReaderError> {
+    if bytes.len() >= ENVELOPE_HEADER_SIZE && bytes[0..2] == T::TAG.to_be_bytes() {
+        Envelope::<T>::from_bytes(bytes).map(Envelope::into_inner)
+    } else {
+        T::from_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // First version of a structure
+    struct EntryV0 {
+        port: u16
+    }
+
+    // Same structure with a field appended in a new version
+    struct EntryV1 {
+        port: u16,
+        score: Option<u64>
+    }
+
+    impl Versioned for EntryV0 {
+        const TAG: u16 = 0xAB01;
+        const VERSION: u8 = 0;
+
+        fn write_fields(&self, writer: &mut Writer) {
+            writer.write_u16(self.port);
+        }
+
+        fn read_fields(reader: &mut Reader, _: u8) -> Result<Self, ReaderError> {
+            Ok(Self { port: reader.read_u16()? })
+        }
+    }
+
+    impl Versioned for EntryV1 {
+        const TAG: u16 = 0xAB01;
+        const VERSION: u8 = 1;
+
+        fn write_fields(&self, writer: &mut Writer) {
+            writer.write_u16(self.port);
+            writer.write_u64(&self.score.unwrap_or_default());
+        }
+
+        fn read_fields(reader: &mut Reader, version: u8) -> Result<Self, ReaderError> {
+            let port = reader.read_u16()?;
+            let score = if version >= 1 {
+                reader.read_if_remaining()?
+            } else {
+                None
+            };
+
+            Ok(Self { port, score })
+        }
+    }
+
+    #[test]
+    fn test_forward_compatibility() {
+        // An older node reads the fields it knows
+        let bytes = Envelope(EntryV1 { port: 8080, score: Some(42) }).to_bytes();
+        let entry = Envelope::<EntryV0>::from_bytes(&bytes).unwrap().into_inner();
+        assert_eq!(entry.port, 8080);
+    }
+
+    #[test]
+    fn test_backward_compatibility() {
+        // A newer node reads an older version
+        let bytes = Envelope(EntryV0 { port: 8080 }).to_bytes();
+        let entry = Envelope::<EntryV1>::from_bytes(&bytes).unwrap().into_inner();
+        assert_eq!(entry.port, 8080);
+        assert_eq!(entry.score, None);
+    }
+
+    #[test]
+    fn test_invalid_tag() {
+        let mut bytes = Envelope(EntryV0 { port: 8080 }).to_bytes();
+        bytes[0] = 0;
+        assert!(Envelope::<EntryV0>::from_bytes(&bytes).is_err());
+    }
+}
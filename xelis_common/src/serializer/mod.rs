@@ -2,12 +2,27 @@ mod defaults;
 mod reader;
 mod writer;
 mod hexable;
+mod borrowed;
+mod envelope;
+#[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+mod stream;
 
 use std::marker::Sized;
+use crate::metrics::{
+    increment_counter,
+    SERIALIZER_DECODED_BYTES,
+    SERIALIZER_DECODED_TOTAL,
+    SERIALIZER_DECODE_ERRORS,
+    SERIALIZER_ENCODED_BYTES
+};
 pub use reader::*;
 pub use writer::Writer;
 pub use defaults::DEFAULT_MAX_ITEMS;
 pub use hexable::*;
+pub use borrowed::*;
+pub use envelope::*;
+#[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+pub use stream::*;
 
 pub trait Serializer {
     fn write(&self, writer: &mut Writer);
@@ -16,6 +31,7 @@ pub trait Serializer {
         let mut buffer = Vec::new();
         let mut writer = Writer::new(&mut buffer);
         self.write(&mut writer);
+        increment_counter(SERIALIZER_ENCODED_BYTES, buffer.len() as u64);
         buffer
     }
 
@@ -50,6 +66,13 @@ pub trait Serializer {
     fn from_bytes(bytes: &[u8]) -> Result<Self, ReaderError>
     where Self: Sized {
         let mut reader = Reader::new(bytes);
-        Self::read(&mut reader)
+        let res = Self::read(&mut reader);
+        if res.is_ok() {
+            increment_counter(SERIALIZER_DECODED_TOTAL, 1);
+            increment_counter(SERIALIZER_DECODED_BYTES, bytes.len() as u64);
+        } else {
+            increment_counter(SERIALIZER_DECODE_ERRORS, 1);
+        }
+        res
     }
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ use std::array::TryFromSliceError;
 use thiserror::Error;
 
 use crate::{context::Context, crypto::Hash};
-use super::{Serializer, Writer};
+use super::{Serializer, SerializerBorrowed, SerializerOwned, Writer};
 
 #[derive(Error, Debug)]
 pub enum ReaderError {
@@ -91,7 +91,8 @@ impl<'a> Reader<'a> {
         result
     }
 
-    pub fn read_bytes_ref(&mut self, n: usize) -> Result<&[u8], ReaderError> {
+    // The slice borrows the bytes of the reader, not the reader itself
+    pub fn read_bytes_ref(&mut self, n: usize) -> Result<&'a [u8], ReaderError> {
         if n > self.size() {
             return Err(ReaderError::InvalidSize)
         }
@@ -101,6 +102,21 @@ impl<'a> Reader<'a> {
         Ok(bytes)
     }
 
+    pub fn read_array_ref<const N: usize>(&mut self) -> Result<&'a [u8; N], ReaderError> {
+        Ok(self.read_bytes_ref(N)?.try_into()?)
+    }
+
+    // Read a value borrowing the bytes of the reader when possible
+    pub fn read_borrowed<T: SerializerBorrowed<'a>>(&mut self) -> Result<T, ReaderError> {
+        T::read_borrowed(self)
+    }
+
+    // Read a value that doesn't borrow the reader bytes
+    // Used when the value must outlive the bytes it was read from
+    pub fn read_owned<T: SerializerOwned<'a>>(&mut self) -> Result<T::Owned, ReaderError> {
+        T::read_borrowed(self).map(T::into_owned)
+    }
+
     pub fn read_bytes_32(&mut self) -> Result<[u8; 32], ReaderError> {
         self.read_bytes(32)
     }
@@ -113,6 +129,10 @@ impl<'a> Reader<'a> {
         Ok(Hash::new(self.read_bytes_32()?))
     }
 
+    pub fn read_hash_ref(&mut self) -> Result<&'a Hash, ReaderError> {
+        self.read_array_ref().map(Hash::from_bytes_ref)
+    }
+
     pub fn read_u8(&mut self) -> Result<u8, ReaderError> {
         if self.size() == 0 {
             return Err(ReaderError::InvalidSize)
@@ -139,11 +159,12 @@ impl<'a> Reader<'a> {
     }
 
     pub fn read_string_with_size(&mut self, size: usize) -> Result<String, ReaderError> {
-        let bytes: Vec<u8> = self.read_bytes(size)?;
-        match String::from_utf8(bytes) {
-            Ok(v) => Ok(v),
-            Err(_) => Err(ReaderError::InvalidValue)
-        }
+        self.read_str_with_size_ref(size).map(str::to_owned)
+    }
+
+    pub fn read_str_with_size_ref(&mut self, size: usize) -> Result<&'a str, ReaderError> {
+        let bytes = self.read_bytes_ref(size)?;
+        std::str::from_utf8(bytes).map_err(|_| ReaderError::InvalidValue)
     }
 
     pub fn read_string(&mut self) -> Result<String, ReaderError> {
@@ -185,6 +206,16 @@ impl<'a> Reader<'a> {
         Ok(Some(value))
     }
 
+    // Read a field appended in a newer version of a structure
+    // Returns None if the bytes written by an older version are exhausted
+    pub fn read_if_remaining<T: Serializer>(&mut self) -> Result<Option<T>, ReaderError> {
+        if self.size() == 0 {
+            return Ok(None)
+        }
+
+        T::read(self).map(Some)
+    }
+
     pub fn total_size(&self) -> usize {
         self.bytes.len()
     }
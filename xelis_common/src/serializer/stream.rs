@@ -0,0 +1,226 @@
+use std::io;
+use log::debug;
+use crate::tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use super::{Serializer, Writer};
+
+// Buffered bytes before flushing them to the inner stream
+pub const DEFAULT_STREAM_CHUNK_SIZE: usize = 64 * 1024;
+// Maximum size of one item read from a stream
+pub const DEFAULT_STREAM_MAX_ITEM_SIZE: usize = 16 * 1024 * 1024;
+
+// Streaming serializers over async I/O
+//
+// Large payloads (bootstrap, state sync, chain export) are a sequence of items,
+// each one is serialized alone and written to the stream in chunks,
+// so the full payload is never held in memory.
+// Every item is prefixed by its size (u32) to be read back
+// without knowing its encoded size in advance.
+
+pub struct StreamWriter<W: AsyncWrite + Unpin> {
+    inner: W,
+    buffer: Vec<u8>,
+    chunk_size: usize,
+    // total bytes written, including the buffered ones
+    total: usize
+}
+
+impl<W: AsyncWrite + Unpin> StreamWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self::with_chunk_size(inner, DEFAULT_STREAM_CHUNK_SIZE)
+    }
+
+    pub fn with_chunk_size(inner: W, chunk_size: usize) -> Self {
+        Self {
+            inner,
+            buffer: Vec::with_capacity(chunk_size),
+            chunk_size,
+            total: 0
+        }
+    }
+
+    // Write a size-prefixed item
+    pub async fn write_item<T: Serializer>(&mut self, item: &T) -> io::Result<()> {
+        let start = self.buffer.len();
+        // Reserve the size prefix, filled once the item is written
+        self.buffer.extend([0u8; 4]);
+        item.write(&mut Writer::new(&mut self.buffer));
+
+        let size = self.buffer.len() - start - 4;
+        if size > u32::MAX as usize {
+            self.buffer.truncate(start);
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "item too large"))
+        }
+        self.buffer[start..start + 4].copy_from_slice(&(size as u32).to_be_bytes());
+        self.total += size + 4;
+
+        self.flush_if_full().await
+    }
+
+    // Write raw bytes without size prefix
+    pub async fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.buffer.extend_from_slice(bytes);
+        self.total += bytes.len();
+        self.flush_if_full().await
+    }
+
+    pub async fn write_u8(&mut self, value: u8) -> io::Result<()> {
+        self.write_bytes(&[value]).await
+    }
+
+    pub async fn write_u32(&mut self, value: u32) -> io::Result<()> {
+        self.write_bytes(&value.to_be_bytes()).await
+    }
+
+    pub async fn write_u64(&mut self, value: u64) -> io::Result<()> {
+        self.write_bytes(&value.to_be_bytes()).await
+    }
+
+    async fn flush_if_full(&mut self) -> io::Result<()> {
+        if self.buffer.len() >= self.chunk_size {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    // Write all buffered bytes to the inner stream
+    pub async fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.inner.write_all(&self.buffer).await?;
+            self.buffer.clear();
+        }
+
+        self.inner.flush().await
+    }
+
+    // Total bytes written since the creation
+    pub fn total_write(&self) -> usize {
+        self.total
+    }
+
+    // Flush the remaining bytes and returns the inner stream
+    pub async fn finish(mut self) -> io::Result<W> {
+        self.flush().await?;
+        Ok(self.inner)
+    }
+}
+
+pub struct StreamReader<R: AsyncRead + Unpin> {
+    inner: R,
+    buffer: Vec<u8>,
+    max_item_size: usize,
+    total: usize
+}
+
+impl<R: AsyncRead + Unpin> StreamReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self::with_max_item_size(inner, DEFAULT_STREAM_MAX_ITEM_SIZE)
+    }
+
+    pub fn with_max_item_size(inner: R, max_item_size: usize) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+            max_item_size,
+            total: 0
+        }
+    }
+
+    // Read a size-prefixed item
+    pub async fn read_item<T: Serializer>(&mut self) -> io::Result<T> {
+        self.read_optional_item().await?
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))
+    }
+
+    // Read the next item, or None if the stream ended before it
+    pub async fn read_optional_item<T: Serializer>(&mut self) -> io::Result<Option<T>> {
+        let mut size = [0u8; 4];
+        match self.inner.read_exact(&mut size).await {
+            Ok(_) => {},
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e)
+        };
+        self.total += 4;
+
+        let size = u32::from_be_bytes(size) as usize;
+        if size > self.max_item_size {
+            debug!("Item of {} bytes while maximum is set to {}", size, self.max_item_size);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "item too large"))
+        }
+
+        // Buffer is reused between items to not allocate on each read
+        self.buffer.resize(size, 0);
+        self.inner.read_exact(&mut self.buffer).await?;
+        self.total += size;
+
+        T::from_bytes(&self.buffer)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub async fn read_bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut bytes = [0u8; N];
+        self.inner.read_exact(&mut bytes).await?;
+        self.total += N;
+        Ok(bytes)
+    }
+
+    pub async fn read_u8(&mut self) -> io::Result<u8> {
+        self.read_bytes::<1>().await.map(|b| b[0])
+    }
+
+    pub async fn read_u32(&mut self) -> io::Result<u32> {
+        self.read_bytes().await.map(u32::from_be_bytes)
+    }
+
+    pub async fn read_u64(&mut self) -> io::Result<u64> {
+        self.read_bytes().await.map(u64::from_be_bytes)
+    }
+
+    // Total bytes read since the creation
+    pub fn total_read(&self) -> usize {
+        self.total
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::Hash;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stream_roundtrip() {
+        // Small chunks to flush many times
+        let mut writer = StreamWriter::with_chunk_size(Vec::new(), 50);
+        writer.write_u64(1000).await.unwrap();
+        for i in 0..1000u64 {
+            writer.write_item(&Hash::new([(i % 256) as u8; 32])).await.unwrap();
+        }
+        let total = writer.total_write();
+        let bytes = writer.finish().await.unwrap();
+        assert_eq!(bytes.len(), total);
+
+        let mut reader = StreamReader::new(bytes.as_slice());
+        let count = reader.read_u64().await.unwrap();
+        for i in 0..count {
+            let hash: Hash = reader.read_item().await.unwrap();
+            assert_eq!(hash, Hash::new([(i % 256) as u8; 32]));
+        }
+        assert!(reader.read_optional_item::<Hash>().await.unwrap().is_none());
+        assert_eq!(reader.total_read(), total);
+    }
+
+    #[tokio::test]
+    async fn test_stream_max_item_size() {
+        let mut writer = StreamWriter::new(Vec::new());
+        writer.write_item(&Hash::zero()).await.unwrap();
+        let bytes = writer.finish().await.unwrap();
+
+        let mut reader = StreamReader::with_max_item_size(bytes.as_slice(), 16);
+        assert!(reader.read_item::<Hash>().await.is_err());
+    }
+}
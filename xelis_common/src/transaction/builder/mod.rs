@@ -55,7 +55,11 @@ use super::{
     BurnPayload,
     CompressedConstant,
     ContractDeposit,
+    DeployContractPayload,
+    ExecutionCreditsPayload,
+    InvokeConstructorPayload,
     InvokeContractPayload,
+    ManageContractPayload,
     MultiSigPayload,
     Role,
     SourceCommitment,
@@ -101,6 +105,10 @@ pub enum GenerationError<T> {
     BurnZero,
     #[error("Invalid module hexadecimal")]
     InvalidModule,
+    #[error("Execution credits amount is zero")]
+    ExecutionCreditsZero,
+    #[error("Private deposits are not supported yet")]
+    PrivateDepositsNotSupported,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -111,7 +119,11 @@ pub enum TransactionTypeBuilder {
     Burn(BurnPayload),
     MultiSig(MultiSigBuilder),
     InvokeContract(InvokeContractBuilder),
-    DeployContract(String),
+    DeployContract(DeployContractBuilder),
+    // Same as final transaction
+    ManageContract(ManageContractPayload),
+    // Same as final transaction
+    ExecutionCredits(ExecutionCreditsPayload),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -164,6 +176,11 @@ impl TransactionTypeBuilder {
             TransactionTypeBuilder::InvokeContract(payload) => {
                 consumed.extend(payload.deposits.keys());
             },
+            TransactionTypeBuilder::DeployContract(payload) => {
+                if let Some(invoke) = payload.invoke.as_ref() {
+                    consumed.extend(invoke.deposits.keys());
+                }
+            },
             _ => {},
         }
 
@@ -292,9 +309,36 @@ impl TransactionBuilder {
 
                 size += payload_size;
             },
-            TransactionTypeBuilder::DeployContract(module) => {
+            TransactionTypeBuilder::DeployContract(payload) => {
                 // Module size
-                size += module.size() / 2;
+                size += payload.module.len() / 2;
+
+                if let Some(invoke) = payload.invoke.as_ref() {
+                    size += invoke.chunk_id.size()
+                    + invoke.max_gas.size()
+                    + 1 // byte for params len
+                    // 4 is for the compressed constant len
+                    + invoke.parameters.iter().map(|param| 4 + param.size()).sum::<usize>()
+                    + 1; // byte for deposits len
+
+                    for (asset, deposit) in &invoke.deposits {
+                        // 1 is for the deposit variant
+                        size += asset.size() + 1;
+                        if deposit.private {
+                            size += RISTRETTO_COMPRESSED_SIZE;
+                        } else {
+                            size += deposit.amount.size();
+                        }
+                    }
+                }
+            },
+            TransactionTypeBuilder::ManageContract(payload) => {
+                // Payload size
+                size += payload.size();
+            },
+            TransactionTypeBuilder::ExecutionCredits(payload) => {
+                // Payload size
+                size += payload.size();
             }
         };
 
@@ -362,7 +406,13 @@ impl TransactionBuilder {
                     ct -= Scalar::from(payload.amount)
                 }
             },
-            _ => {}
+            TransactionTypeBuilder::MultiSig(_) | TransactionTypeBuilder::ManageContract(_) => {},
+            // Contract costs are public, same as the expected sender output
+            TransactionTypeBuilder::InvokeContract(_)
+            | TransactionTypeBuilder::DeployContract(_)
+            | TransactionTypeBuilder::ExecutionCredits(_) => {
+                ct -= Scalar::from(self.get_transaction_cost(0, asset));
+            }
         }
 
         ct
@@ -400,10 +450,29 @@ impl TransactionBuilder {
                     cost += payload.max_gas;
                 }
             },
-            TransactionTypeBuilder::DeployContract(_) => {
+            TransactionTypeBuilder::DeployContract(payload) => {
                 if *asset == XELIS_ASSET {
                     cost += BURN_PER_CONTRACT;
                 }
+
+                if let Some(invoke) = payload.invoke.as_ref() {
+                    if let Some(deposit) = invoke.deposits.get(asset) {
+                        cost += deposit.amount;
+                    }
+
+                    if *asset == XELIS_ASSET {
+                        cost += invoke.max_gas;
+                    }
+                }
+            },
+            TransactionTypeBuilder::ManageContract(_) => {},
+            TransactionTypeBuilder::ExecutionCredits(payload) => {
+                // Withdrawn credits are added back by the chain
+                if let ExecutionCreditsPayload::Deposit { amount } = payload {
+                    if *asset == XELIS_ASSET {
+                        cost += amount;
+                    }
+                }
             }
         }
 
@@ -745,10 +814,68 @@ impl TransactionBuilder {
                     deposits,
                 })
             },
-            TransactionTypeBuilder::DeployContract(module) => {
+            TransactionTypeBuilder::DeployContract(payload) => {
                 transcript.deploy_contract_proof_domain_separator();
-                let module = Module::from_hex(&module).map_err(|_| GenerationError::InvalidModule)?;
-                TransactionType::DeployContract(module)
+                let module = Module::from_hex(&payload.module).map_err(|_| GenerationError::InvalidModule)?;
+
+                let invoke = match payload.invoke {
+                    Some(invoke) => {
+                        transcript.append_u64(b"constructor_chunk_id", invoke.chunk_id as u64);
+                        transcript.append_u64(b"constructor_max_gas", invoke.max_gas);
+
+                        let mut deposits = IndexMap::new();
+                        for (asset, deposit) in invoke.deposits {
+                            transcript.append_hash(b"deposit_asset", &asset);
+                            if deposit.private {
+                                return Err(GenerationError::PrivateDepositsNotSupported);
+                            } else {
+                                transcript.append_u64(b"deposit_plain", deposit.amount);
+                                deposits.insert(asset, ContractDeposit::Public(deposit.amount));
+                            }
+                        }
+
+                        let mut parameters = Vec::with_capacity(invoke.parameters.len());
+                        for param in invoke.parameters {
+                            let compressed = CompressedConstant::new(&param);
+                            transcript.append_message(b"contract_param", compressed.as_bytes());
+
+                            parameters.push(compressed);
+                        }
+
+                        Some(InvokeConstructorPayload {
+                            chunk_id: invoke.chunk_id,
+                            max_gas: invoke.max_gas,
+                            deposits,
+                            parameters
+                        })
+                    },
+                    None => None
+                };
+
+                TransactionType::DeployContract(DeployContractPayload {
+                    module,
+                    invoke
+                })
+            },
+            TransactionTypeBuilder::ManageContract(payload) => {
+                transcript.manage_contract_proof_domain_separator();
+                transcript.append_hash(b"contract_hash", &payload.contract);
+                transcript.append_u64(b"contract_paused", payload.action.is_pause() as u64);
+
+                TransactionType::ManageContract(payload)
+            },
+            TransactionTypeBuilder::ExecutionCredits(payload) => {
+                if payload.get_amount() == 0 {
+                    return Err(GenerationError::ExecutionCreditsZero);
+                }
+
+                transcript.execution_credits_proof_domain_separator();
+                match payload {
+                    ExecutionCreditsPayload::Deposit { amount } => transcript.append_u64(b"credits_deposit", amount),
+                    ExecutionCreditsPayload::Withdraw { amount } => transcript.append_u64(b"credits_withdraw", amount)
+                };
+
+                TransactionType::ExecutionCredits(payload)
             }
         };
 
@@ -38,6 +38,25 @@ pub struct InvokeContractBuilder {
     pub deposits: IndexMap<Hash, ContractDepositBuilder>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConstructorBuilder {
+    pub chunk_id: u16,
+    pub max_gas: u64,
+    #[serde(default)]
+    pub parameters: Vec<Constant>,
+    #[serde(default)]
+    pub deposits: IndexMap<Hash, ContractDepositBuilder>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeployContractBuilder {
+    // Module in hex format
+    pub module: String,
+    // Constructor called in the same TX
+    #[serde(default)]
+    pub invoke: Option<ConstructorBuilder>,
+}
+
 #[cfg(test)]
 mod tests {
     use indexmap::indexmap;
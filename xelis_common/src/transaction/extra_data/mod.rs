@@ -26,7 +26,7 @@ use crate::{
             PedersenOpening,
             PrivateKey,
         },
-        proofs::PC_GENS
+        proofs::H_TABLE
     },
     serializer::{
         Reader,
@@ -74,7 +74,7 @@ pub struct PlaintextData(pub Vec<u8>);
 
 /// See [`derive_shared_key`].
 pub fn derive_shared_key_from_opening(opening: &PedersenOpening) -> SharedKey {
-    derive_shared_key(&(opening.as_scalar() * &PC_GENS.B_blinding).compress())
+    derive_shared_key(&(&opening.as_scalar() * &*H_TABLE).compress())
 }
 
 /// See [`derive_shared_key`].
@@ -189,7 +189,8 @@ impl Serializer for AEADCipher {
     }
 
     fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
-        Ok(Self(Vec::read(reader)?))
+        // Copied at once from the borrowed bytes
+        Ok(Self(reader.read_owned::<&[u8]>()?))
     }
 
     fn size(&self) -> usize {
@@ -203,7 +204,8 @@ impl Serializer for Cipher {
     }
 
     fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
-        Ok(Self(Vec::read(reader)?))
+        // Copied at once from the borrowed bytes
+        Ok(Self(reader.read_owned::<&[u8]>()?))
     }
 
     fn size(&self) -> usize {
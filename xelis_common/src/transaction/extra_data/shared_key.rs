@@ -1,10 +1,31 @@
+use std::fmt;
 use serde::{Deserialize, Serialize};
-use crate::serializer::*;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use crate::{
+    crypto::ct::{impl_secret_eq, Choice, ConstantTimeEq},
+    serializer::*
+};
 
 /// Shared key used to encrypt/decrypt the extra data
-#[derive(Debug, Clone)]
+/// It is wiped from memory once dropped
+/// Clone is required by the decrypted extra data kept in the wallet entries
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
 pub struct SharedKey(pub [u8; 32]);
 
+impl ConstantTimeEq for SharedKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
+}
+
+impl_secret_eq!(SharedKey, |key| &key.0);
+
+impl fmt::Debug for SharedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedKey(<redacted>)")
+    }
+}
+
 impl Serializer for SharedKey {
     fn write(&self, writer: &mut Writer) {
         self.0.write(writer);
@@ -80,10 +80,19 @@ impl Serializer for UnknownExtraDataFormat {
     }
 
     fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
-        Ok(Self(Vec::read(reader)?))
+        // Copied at once from the borrowed bytes
+        Ok(Self(reader.read_owned::<&[u8]>()?))
     }
 
     fn size(&self) -> usize {
         self.0.size()
     }
 }
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for UnknownExtraDataFormat {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.int_in_range(0..=crate::transaction::EXTRA_DATA_LIMIT_SIZE)?;
+        Ok(Self(u.bytes(len)?.to_vec()))
+    }
+}
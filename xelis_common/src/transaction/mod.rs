@@ -12,7 +12,8 @@ use crate::{
         Hashable,
         Signature,
     },
-    serializer::*
+    serializer::*,
+    utils::calculate_fee_rate_per_kb
 };
 
 use bulletproofs::RangeProof;
@@ -99,7 +100,9 @@ pub enum TransactionType {
     Burn(BurnPayload),
     MultiSig(MultiSigPayload),
     InvokeContract(InvokeContractPayload),
-    DeployContract(Module),
+    DeployContract(DeployContractPayload),
+    ManageContract(ManageContractPayload),
+    ExecutionCredits(ExecutionCreditsPayload),
 }
 
 // Transaction to be sent over the network
@@ -208,6 +211,25 @@ impl Transaction {
         self.multisig.as_ref().map(|m| m.len()).unwrap_or(0)
     }
 
+    // Get the count of transfers, 0 if its not a transfers transaction
+    pub fn get_transfers_count(&self) -> usize {
+        match &self.data {
+            TransactionType::Transfers(transfers) => transfers.len(),
+            _ => 0
+        }
+    }
+
+    // Get the fee paid per kB of serialized transaction
+    pub fn get_fee_rate(&self) -> u64 {
+        self.get_fee_rate_with_size(self.size())
+    }
+
+    // Same as get_fee_rate with the size already computed
+    // This prevents to serialize again the transaction
+    pub fn get_fee_rate_with_size(&self, size: usize) -> u64 {
+        calculate_fee_rate_per_kb(self.fee, size)
+    }
+
     // Get the signature of source key
     pub fn get_signature(&self) -> &Signature {
         &self.signature
@@ -283,9 +305,25 @@ impl Serializer for TransactionType {
                 writer.write_u8(3);
                 payload.write(writer);
             },
-            TransactionType::DeployContract(module) => {
-                writer.write_u8(4);
-                module.write(writer);
+            // Deployments without constructor keep the module only layout
+            TransactionType::DeployContract(payload) => match &payload.invoke {
+                None => {
+                    writer.write_u8(4);
+                    payload.module.write(writer);
+                },
+                Some(invoke) => {
+                    writer.write_u8(7);
+                    payload.module.write(writer);
+                    invoke.write(writer);
+                }
+            },
+            TransactionType::ManageContract(payload) => {
+                writer.write_u8(5);
+                payload.write(writer);
+            },
+            TransactionType::ExecutionCredits(payload) => {
+                writer.write_u8(6);
+                payload.write(writer);
             }
         };
     }
@@ -318,7 +356,20 @@ impl Serializer for TransactionType {
             },
             4 => {
                 let module = Module::read(reader)?;
-                TransactionType::DeployContract(module)
+                TransactionType::DeployContract(DeployContractPayload { module, invoke: None })
+            },
+            5 => {
+                let payload = ManageContractPayload::read(reader)?;
+                TransactionType::ManageContract(payload)
+            },
+            6 => {
+                let payload = ExecutionCreditsPayload::read(reader)?;
+                TransactionType::ExecutionCredits(payload)
+            },
+            7 => {
+                let module = Module::read(reader)?;
+                let invoke = InvokeConstructorPayload::read(reader)?;
+                TransactionType::DeployContract(DeployContractPayload { module, invoke: Some(invoke) })
             },
             _ => {
                 return Err(ReaderError::InvalidValue)
@@ -342,7 +393,9 @@ impl Serializer for TransactionType {
                 1 + 1 + payload.participants.iter().map(|p| p.size()).sum::<usize>()
             },
             TransactionType::InvokeContract(payload) => payload.size(),
-            TransactionType::DeployContract(module) => module.size(),
+            TransactionType::DeployContract(payload) => payload.module.size() + payload.invoke.as_ref().map_or(0, |invoke| invoke.size()),
+            TransactionType::ManageContract(payload) => payload.size(),
+            TransactionType::ExecutionCredits(payload) => payload.size(),
         }
     }
 }
@@ -437,6 +490,82 @@ impl Serializer for Transaction {
     }
 }
 
+// Payloads are generated only for the version supporting them
+#[cfg(any(test, feature = "arbitrary"))]
+impl TransactionType {
+    pub fn arbitrary_for_version(u: &mut arbitrary::Unstructured, version: TxVersion) -> arbitrary::Result<Self> {
+        let max = match version {
+            TxVersion::V0 => 1,
+            TxVersion::V1 => 2,
+            TxVersion::V2 => 6
+        };
+
+        Ok(match u.int_in_range(0..=max)? {
+            0 => TransactionType::Burn(u.arbitrary()?),
+            1 => {
+                let mut transfers = Vec::new();
+                for _ in 0..u.int_in_range(1..=MAX_TRANSFER_COUNT.min(8))? {
+                    transfers.push(TransferPayload::arbitrary_for_version(u, version)?);
+                }
+                TransactionType::Transfers(transfers)
+            },
+            2 => TransactionType::MultiSig(u.arbitrary()?),
+            3 => TransactionType::InvokeContract(u.arbitrary()?),
+            // With and without constructor, each one has its own tag
+            4 => TransactionType::DeployContract(u.arbitrary()?),
+            5 => TransactionType::ManageContract(u.arbitrary()?),
+            _ => TransactionType::ExecutionCredits(u.arbitrary()?)
+        })
+    }
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for SourceCommitment {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(SourceCommitment::new(u.arbitrary()?, u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+// Generated transactions are well formed but invalid:
+// they are used to exercise the deserialization and the verification against malformed inputs
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for Transaction {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let version: TxVersion = u.arbitrary()?;
+        let source = u.arbitrary()?;
+        let data = TransactionType::arbitrary_for_version(u, version)?;
+        let fee = u.arbitrary()?;
+        let nonce = u.arbitrary()?;
+
+        let mut source_commitments = Vec::new();
+        for _ in 0..u.int_in_range(1..=MAX_TRANSFER_COUNT.min(8))? {
+            source_commitments.push(u.arbitrary()?);
+        }
+
+        let range_proof = crate::crypto::proofs::arbitrary_range_proof(u)?;
+        let reference = u.arbitrary()?;
+        let multisig = if version == TxVersion::V0 {
+            None
+        } else {
+            u.arbitrary()?
+        };
+        let signature = u.arbitrary()?;
+
+        Ok(Transaction::new(
+            version,
+            source,
+            data,
+            fee,
+            nonce,
+            source_commitments,
+            range_proof,
+            reference,
+            multisig,
+            signature
+        ))
+    }
+}
+
 impl Hashable for Transaction {}
 
 impl AsRef<Transaction> for Transaction {
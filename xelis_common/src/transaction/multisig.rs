@@ -112,6 +112,19 @@ impl Serializer for SignatureId {
     }
 }
 
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for MultiSig {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut multisig = MultiSig::new();
+        for _ in 0..u.int_in_range(0..=16)? {
+            multisig.add_signature(SignatureId {
+                id: u.arbitrary()?,
+                signature: u.arbitrary()?
+            });
+        }
+        Ok(multisig)
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
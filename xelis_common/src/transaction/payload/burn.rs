@@ -28,3 +28,13 @@ impl Serializer for BurnPayload {
         self.asset.size() + self.amount.size()
     }
 }
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for BurnPayload {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(BurnPayload {
+            asset: u.arbitrary()?,
+            amount: u.arbitrary()?
+        })
+    }
+}
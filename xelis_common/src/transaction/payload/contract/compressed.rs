@@ -290,6 +290,15 @@ pub fn decompress_type(reader: &mut Reader, structures: &IndexSet<StructType>, e
     values.pop().ok_or(ReaderError::InvalidValue)
 }
 
+// Bytes are not a valid constant, they are only decompressed during the execution
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for CompressedConstant {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.int_in_range(0..=256)?;
+        Ok(Self(u.bytes(len)?.to_vec()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
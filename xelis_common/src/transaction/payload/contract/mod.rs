@@ -63,6 +63,58 @@ pub struct InvokeContractPayload {
     pub parameters: Vec<CompressedConstant>
 }
 
+// Constructor executed atomically in the transaction deploying the contract
+// Its storage changes are committed only if it exits with code 0
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InvokeConstructorPayload {
+    // The entry chunk called as constructor
+    pub chunk_id: u16,
+    // Maximum of gas that can be used by the constructor
+    pub max_gas: u64,
+    // Assets deposited in the contract before calling the constructor
+    pub deposits: IndexMap<Hash, ContractDeposit>,
+    // The parameters to call the constructor
+    pub parameters: Vec<CompressedConstant>
+}
+
+// DeployContractPayload is a public payload deploying a new contract
+// The contract address is the hash of the transaction
+// Serialized by the TX type: the module alone without constructor,
+// or followed by the constructor under its own TX type tag
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeployContractPayload {
+    pub module: Module,
+    // Optional constructor to call once deployed
+    pub invoke: Option<InvokeConstructorPayload>
+}
+
+// Action applied by the owner on a deployed contract
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractManagementAction {
+    // Reject all the invocations of the contract
+    Pause,
+    // Accept again the invocations of the contract
+    Unpause
+}
+
+impl ContractManagementAction {
+    // Is the contract paused after this action
+    pub fn is_pause(&self) -> bool {
+        matches!(self, Self::Pause)
+    }
+}
+
+// ManageContractPayload is a public payload signed by the owner of a contract
+// It is enforced by the nodes and not by the contract itself
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ManageContractPayload {
+    // The contract address
+    pub contract: Hash,
+    // Action to apply
+    pub action: ContractManagementAction
+}
+
 impl Serializer for ContractDeposit {
     fn write(&self, writer: &mut Writer) {
         match self {
@@ -611,3 +663,257 @@ impl Serializer for InvokeContractPayload {
         size
     }
 }
+
+impl Serializer for InvokeConstructorPayload {
+    fn write(&self, writer: &mut Writer) {
+        writer.write_u16(self.chunk_id);
+        self.max_gas.write(writer);
+
+        writer.write_u8(self.deposits.len() as u8);
+        for (asset, deposit) in &self.deposits {
+            asset.write(writer);
+            deposit.write(writer);
+        }
+
+        writer.write_u8(self.parameters.len() as u8);
+        for parameter in &self.parameters {
+            parameter.write(writer);
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Result<InvokeConstructorPayload, ReaderError> {
+        let chunk_id = reader.read_u16()?;
+        let max_gas = reader.read_u64()?;
+
+        let len = reader.read_u8()? as usize;
+        let mut deposits = IndexMap::new();
+        for _ in 0..len {
+            let asset = Hash::read(reader)?;
+            let deposit = ContractDeposit::read(reader)?;
+            deposits.insert(asset, deposit);
+        }
+
+        let len = reader.read_u8()? as usize;
+        let mut parameters = Vec::with_capacity(len);
+        for _ in 0..len {
+            parameters.push(CompressedConstant::read(reader)?);
+        }
+
+        Ok(InvokeConstructorPayload { chunk_id, max_gas, deposits, parameters })
+    }
+
+    fn size(&self) -> usize {
+        let mut size = self.chunk_id.size()
+            + self.max_gas.size()
+        // 1 byte for the deposits length
+            + 1;
+
+        for (asset, deposit) in &self.deposits {
+            size += asset.size() + deposit.size();
+        }
+
+        size += 1;
+        for parameter in &self.parameters {
+            size += parameter.size();
+        }
+        size
+    }
+}
+
+impl Serializer for ContractManagementAction {
+    fn write(&self, writer: &mut Writer) {
+        writer.write_u8(match self {
+            Self::Pause => 0,
+            Self::Unpause => 1
+        });
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        Ok(match reader.read_u8()? {
+            0 => Self::Pause,
+            1 => Self::Unpause,
+            _ => return Err(ReaderError::InvalidValue)
+        })
+    }
+
+    fn size(&self) -> usize {
+        1
+    }
+}
+
+impl Serializer for ManageContractPayload {
+    fn write(&self, writer: &mut Writer) {
+        self.contract.write(writer);
+        self.action.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<ManageContractPayload, ReaderError> {
+        let contract = Hash::read(reader)?;
+        let action = ContractManagementAction::read(reader)?;
+
+        Ok(ManageContractPayload { contract, action })
+    }
+
+    fn size(&self) -> usize {
+        self.contract.size() + self.action.size()
+    }
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for ContractDeposit {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(ContractDeposit::Public(u.arbitrary()?))
+    }
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for InvokeContractPayload {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let contract = u.arbitrary()?;
+        let mut deposits = IndexMap::new();
+        for _ in 0..u.int_in_range(0..=8)? {
+            deposits.insert(u.arbitrary()?, u.arbitrary()?);
+        }
+
+        let chunk_id = u.arbitrary()?;
+        let max_gas = u.arbitrary()?;
+        let mut parameters = Vec::new();
+        for _ in 0..u.int_in_range(0..=8)? {
+            parameters.push(u.arbitrary()?);
+        }
+
+        Ok(InvokeContractPayload { contract, deposits, chunk_id, max_gas, parameters })
+    }
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for InvokeConstructorPayload {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let chunk_id = u.arbitrary()?;
+        let max_gas = u.arbitrary()?;
+        let mut deposits = IndexMap::new();
+        for _ in 0..u.int_in_range(0..=8)? {
+            deposits.insert(u.arbitrary()?, u.arbitrary()?);
+        }
+
+        let mut parameters = Vec::new();
+        for _ in 0..u.int_in_range(0..=8)? {
+            parameters.push(u.arbitrary()?);
+        }
+
+        Ok(InvokeConstructorPayload { chunk_id, max_gas, deposits, parameters })
+    }
+}
+
+// Instructions are random bytes, they are only decoded during the execution
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for DeployContractPayload {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut chunks = Vec::new();
+        let mut entry_ids = IndexSet::new();
+        for id in 0..u.int_in_range(1..=4)? {
+            let len = u.int_in_range(0..=256)?;
+            chunks.push(Chunk::from_instructions(u.bytes(len)?.to_vec()));
+            if u.arbitrary()? {
+                entry_ids.insert(id);
+            }
+        }
+
+        let module = Module::with(IndexSet::new(), chunks, entry_ids, IndexSet::new(), IndexSet::new());
+        Ok(DeployContractPayload { module, invoke: u.arbitrary()? })
+    }
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for ContractManagementAction {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(if u.arbitrary()? {
+            ContractManagementAction::Pause
+        } else {
+            ContractManagementAction::Unpause
+        })
+    }
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for ManageContractPayload {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(ManageContractPayload { contract: u.arbitrary()?, action: u.arbitrary()? })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use xelis_vm::{Constant, Value};
+    use crate::{config::XELIS_ASSET, transaction::TransactionType};
+    use super::*;
+
+    fn test_module() -> Module {
+        Module::with(IndexSet::new(), vec![Chunk::from_instructions(vec![0])], [0].into_iter().collect(), IndexSet::new(), IndexSet::new())
+    }
+
+    #[test]
+    fn test_deploy_contract_payload_roundtrip() {
+        let data = TransactionType::DeployContract(DeployContractPayload {
+            module: test_module(),
+            invoke: Some(InvokeConstructorPayload {
+                chunk_id: 0,
+                max_gas: 1000,
+                deposits: [(XELIS_ASSET, ContractDeposit::Public(100))].into_iter().collect(),
+                parameters: vec![CompressedConstant::new(&Constant::Default(Value::U64(42)))]
+            })
+        });
+
+        let bytes = data.to_bytes();
+        assert_eq!(bytes[0], 7);
+        assert_eq!(bytes.len(), data.size());
+
+        let TransactionType::DeployContract(decoded) = TransactionType::from_bytes(&bytes).unwrap() else {
+            panic!("expected a deploy contract payload");
+        };
+        let invoke = decoded.invoke.unwrap();
+        assert_eq!(invoke.chunk_id, 0);
+        assert_eq!(invoke.max_gas, 1000);
+        assert_eq!(invoke.deposits.len(), 1);
+        assert_eq!(invoke.parameters.len(), 1);
+    }
+
+    #[test]
+    fn test_legacy_deploy_contract_payload() {
+        // Deployments already on chain only contain the module
+        let module = test_module();
+        let mut bytes = vec![4];
+        bytes.extend(module.to_bytes());
+
+        let data = TransactionType::from_bytes(&bytes).unwrap();
+        let TransactionType::DeployContract(payload) = &data else {
+            panic!("expected a deploy contract payload");
+        };
+        assert!(payload.invoke.is_none());
+        assert_eq!(payload.module.to_bytes(), module.to_bytes());
+
+        // Same bytes once written again, so the TX hash doesn't change
+        assert_eq!(data.to_bytes(), bytes);
+        assert_eq!(data.size(), bytes.len());
+    }
+
+    #[test]
+    fn test_manage_contract_payload_roundtrip() {
+        let payload = ManageContractPayload {
+            contract: Hash::zero(),
+            action: ContractManagementAction::Unpause
+        };
+
+        let bytes = payload.to_bytes();
+        assert_eq!(bytes.len(), payload.size());
+
+        let decoded = ManageContractPayload::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.contract, payload.contract);
+        assert_eq!(decoded.action, ContractManagementAction::Unpause);
+
+        // Unknown action
+        let mut bytes = bytes;
+        *bytes.last_mut().unwrap() = 2;
+        assert!(ManageContractPayload::from_bytes(&bytes).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use crate::serializer::*;
+
+// Execution credits are XELIS locked by an account
+// Contract invocations draw their gas from them before touching the balance
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionCreditsPayload {
+    // Lock XELIS from the balance into execution credits
+    Deposit {
+        amount: u64
+    },
+    // Unlock execution credits back to the balance
+    Withdraw {
+        amount: u64
+    }
+}
+
+impl ExecutionCreditsPayload {
+    pub fn get_amount(&self) -> u64 {
+        match self {
+            Self::Deposit { amount } | Self::Withdraw { amount } => *amount
+        }
+    }
+
+    pub fn is_deposit(&self) -> bool {
+        matches!(self, Self::Deposit { .. })
+    }
+}
+
+impl Serializer for ExecutionCreditsPayload {
+    fn write(&self, writer: &mut Writer) {
+        match self {
+            Self::Deposit { amount } => {
+                writer.write_u8(0);
+                amount.write(writer);
+            },
+            Self::Withdraw { amount } => {
+                writer.write_u8(1);
+                amount.write(writer);
+            }
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Result<ExecutionCreditsPayload, ReaderError> {
+        Ok(match reader.read_u8()? {
+            0 => Self::Deposit { amount: reader.read_u64()? },
+            1 => Self::Withdraw { amount: reader.read_u64()? },
+            _ => return Err(ReaderError::InvalidValue)
+        })
+    }
+
+    fn size(&self) -> usize {
+        1 + self.get_amount().size()
+    }
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for ExecutionCreditsPayload {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let amount = u.arbitrary()?;
+        Ok(if u.arbitrary()? {
+            Self::Deposit { amount }
+        } else {
+            Self::Withdraw { amount }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_credits_payload_serde() {
+        for payload in [ExecutionCreditsPayload::Deposit { amount: 100 }, ExecutionCreditsPayload::Withdraw { amount: 42 }] {
+            let bytes = payload.to_bytes();
+            assert_eq!(bytes.len(), payload.size());
+            assert_eq!(ExecutionCreditsPayload::from_bytes(&bytes).unwrap(), payload);
+        }
+    }
+}
@@ -2,8 +2,10 @@ mod transfer;
 mod burn;
 mod multisig;
 mod contract;
+mod credits;
 
 pub use transfer::*;
 pub use burn::*;
 pub use multisig::*;
-pub use contract::*;
\ No newline at end of file
+pub use contract::*;
+pub use credits::*;
\ No newline at end of file
@@ -66,3 +66,26 @@ impl Serializer for MultiSigPayload {
         1 + 1 + self.participants.iter().map(|p| p.size()).sum::<usize>()
     }
 }
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for MultiSigPayload {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut threshold: u8 = u.arbitrary()?;
+        let mut participants = IndexSet::new();
+        if threshold != 0 {
+            for _ in 0..u.int_in_range(1..=MAX_MULTISIG_PARTICIPANTS.min(16))? {
+                participants.insert(u.arbitrary()?);
+            }
+        }
+
+        // A payload with a threshold requires at least one participant
+        if participants.is_empty() {
+            threshold = 0;
+        }
+
+        Ok(MultiSigPayload {
+            threshold,
+            participants
+        })
+    }
+}
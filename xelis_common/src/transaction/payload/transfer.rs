@@ -151,3 +151,19 @@ impl Serializer for TransferPayload {
         + self.ct_validity_proof.size()
     }
 }
+
+// The validity proof depends on the transaction version
+#[cfg(any(test, feature = "arbitrary"))]
+impl TransferPayload {
+    pub fn arbitrary_for_version(u: &mut arbitrary::Unstructured, version: crate::transaction::TxVersion) -> arbitrary::Result<Self> {
+        Ok(Self {
+            asset: u.arbitrary()?,
+            destination: u.arbitrary()?,
+            extra_data: u.arbitrary()?,
+            commitment: u.arbitrary()?,
+            sender_handle: u.arbitrary()?,
+            receiver_handle: u.arbitrary()?,
+            ct_validity_proof: CiphertextValidityProof::arbitrary_for_version(u, version)?
+        })
+    }
+}
@@ -47,4 +47,14 @@ impl Serializer for Reference {
     fn size(&self) -> usize {
         self.hash.size() + self.topoheight.size()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for Reference {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Reference {
+            hash: u.arbitrary()?,
+            topoheight: u.arbitrary()?
+        })
+    }
+}
@@ -1,13 +1,28 @@
 use std::{borrow::Cow, collections::HashMap};
+use arbitrary::{Arbitrary, Unstructured};
 use async_trait::async_trait;
-use indexmap::IndexSet;
-use xelis_vm::{Environment, Module};
+use curve25519_dalek::Scalar;
+use indexmap::{IndexMap, IndexSet};
+use rand::{rngs::OsRng, RngCore};
+use xelis_vm::{Chunk, Constant, Environment, Module};
 use crate::{
     account::{Nonce, CiphertextCache},
     api::{DataElement, DataValue},
     config::{COIN_VALUE, XELIS_ASSET},
+    contract::{
+        build_environment,
+        ChainState as ContractChainState,
+        ContractCache,
+        ContractCapabilities,
+        ContractManagementState,
+        ContractOutput,
+        ContractProvider,
+        ContractReceipt,
+        ContractStorage,
+        DeterministicRandom
+    },
     crypto::{
-        elgamal::{Ciphertext, PedersenOpening},
+        elgamal::{Ciphertext, PedersenOpening, G},
         Address,
         Hash,
         Hashable,
@@ -21,7 +36,9 @@ use crate::{
         MultiSigPayload,
         MAX_TRANSFER_COUNT
     },
-    block::BlockVersion
+    block::{Block, BlockHeader, BlockVersion, TopoHeight, EXTRA_NONCE_SIZE},
+    immutable::Immutable,
+    utils::calculate_fee_rate_per_kb
 };
 use super::{
     extra_data::{
@@ -30,15 +47,29 @@ use super::{
     },
     builder::{
         AccountState,
+        ConstructorBuilder,
+        ContractDepositBuilder,
+        DeployContractBuilder,
         FeeBuilder,
         FeeHelper,
+        InvokeContractBuilder,
         TransactionBuilder,
         TransactionTypeBuilder,
         TransferBuilder,
-        MultiSigBuilder
+        MultiSigBuilder,
+        GenerationError
+    },
+    verify::{
+        BlockchainApplyState,
+        BlockchainVerificationState,
+        ContractEnvironment,
+        VerificationError
     },
-    verify::BlockchainVerificationState,
     BurnPayload,
+    ContractDeposit,
+    ContractManagementAction,
+    ExecutionCreditsPayload,
+    ManageContractPayload,
     Reference,
     Role,
     Transaction
@@ -52,6 +83,84 @@ struct AccountChainState {
 struct ChainState {
     accounts: HashMap<PublicKey, AccountChainState>,
     multisig: HashMap<PublicKey, MultiSigPayload>,
+    block_version: BlockVersion,
+    contracts: HashMap<Hash, Module>,
+    management: HashMap<Hash, ContractManagementState>,
+    credits: HashMap<PublicKey, u64>,
+    block: Block,
+    block_hash: Hash,
+    burned_coins: u64,
+    gas_fee: u64,
+    receipts: HashMap<Hash, Vec<ContractReceipt>>,
+    outputs: HashMap<Hash, Vec<ContractOutput>>,
+    caches: HashMap<Hash, ContractCache>,
+    environment: Environment,
+    provider: MockProvider,
+}
+
+impl ChainState {
+    fn new() -> Self {
+        let header = BlockHeader::new(BlockVersion::V0, 0, 0, IndexSet::new(), [0u8; EXTRA_NONCE_SIZE], KeyPair::new().get_public_key().compress(), IndexSet::new());
+
+        Self {
+            accounts: HashMap::new(),
+            multisig: HashMap::new(),
+            block_version: BlockVersion::V0,
+            contracts: HashMap::new(),
+            management: HashMap::new(),
+            credits: HashMap::new(),
+            block: Block::new(Immutable::Owned(header), Vec::new()),
+            block_hash: Hash::zero(),
+            burned_coins: 0,
+            gas_fee: 0,
+            receipts: HashMap::new(),
+            outputs: HashMap::new(),
+            caches: HashMap::new(),
+            environment: build_environment::<MockProvider>().build(),
+            provider: MockProvider,
+        }
+    }
+
+    fn add_account(&mut self, account: &Account) {
+        let mut balances = HashMap::new();
+        for (asset, balance) in &account.balances {
+            balances.insert(asset.clone(), balance.ciphertext.clone().take_ciphertext().unwrap());
+        }
+
+        self.accounts.insert(account.keypair.get_public_key().compress(), AccountChainState {
+            balances,
+            nonce: account.nonce,
+        });
+    }
+
+    // Decrypt the XELIS balance of an account to compare it with the expected amount
+    fn has_balance(&self, account: &Account, amount: u64) -> bool {
+        let ciphertext = &self.accounts[&account.keypair.get_public_key().compress()].balances[&XELIS_ASSET];
+        account.keypair.decrypt_to_point(ciphertext) == Scalar::from(amount) * G
+    }
+}
+
+// Contracts storage is never read by the tests
+struct MockProvider;
+
+impl ContractStorage for MockProvider {
+    fn load(&self, _: &Hash, _: &Constant, _: TopoHeight) -> Result<Option<(TopoHeight, Option<Constant>)>, anyhow::Error> {
+        Ok(None)
+    }
+
+    fn load_latest_topoheight(&self, _: &Hash, _: &Constant, _: TopoHeight) -> Result<Option<TopoHeight>, anyhow::Error> {
+        Ok(None)
+    }
+
+    fn has(&self, _: &Hash, _: &Constant, _: TopoHeight) -> Result<bool, anyhow::Error> {
+        Ok(false)
+    }
+}
+
+impl ContractProvider for MockProvider {
+    fn get_contract_balance_for_asset(&self, _: &Hash, _: &Hash, _: TopoHeight) -> Result<Option<(TopoHeight, u64)>, anyhow::Error> {
+        Ok(None)
+    }
 }
 
 #[derive(Clone)]
@@ -60,13 +169,23 @@ struct Balance {
     balance: u64,
 }
 
-#[derive(Clone)]
 struct Account {
     balances: HashMap<Hash, Balance>,
     keypair: KeyPair,
     nonce: Nonce,
 }
 
+// KeyPair doesn't implement Clone
+impl Clone for Account {
+    fn clone(&self) -> Self {
+        Self {
+            balances: self.balances.clone(),
+            keypair: KeyPair::from_private_key(self.keypair.get_private_key().duplicate()),
+            nonce: self.nonce,
+        }
+    }
+}
+
 impl Account {
     fn new() -> Self {
         Self {
@@ -173,6 +292,46 @@ fn test_encrypt_decrypt_two_parties() {
 }
 
 
+#[test]
+fn test_fee_rate() {
+    let mut alice = Account::new();
+    alice.set_balance(XELIS_ASSET, 100 * COIN_VALUE);
+
+    let tx = create_tx_for(alice, Account::new().address(), 50, None);
+    let size = tx.size();
+    assert_eq!(tx.get_fee_rate(), tx.get_fee_rate_with_size(size));
+    assert_eq!(tx.get_fee_rate_with_size(size), calculate_fee_rate_per_kb(tx.get_fee(), size));
+}
+
+#[test]
+fn test_deploy_contract_private_deposit() {
+    let mut alice = Account::new();
+    alice.set_balance(XELIS_ASSET, 100 * COIN_VALUE);
+
+    let mut state = AccountStateImpl {
+        balances: alice.balances,
+        nonce: alice.nonce,
+        reference: Reference {
+            topoheight: 0,
+            hash: Hash::zero(),
+        },
+    };
+
+    // Empty module
+    let data = TransactionTypeBuilder::DeployContract(DeployContractBuilder {
+        module: "00000000000000000000".to_owned(),
+        invoke: Some(ConstructorBuilder {
+            chunk_id: 0,
+            max_gas: 1000,
+            parameters: Vec::new(),
+            deposits: [(XELIS_ASSET, ContractDepositBuilder { amount: 100, private: true })].into_iter().collect(),
+        }),
+    });
+
+    let builder = TransactionBuilder::new(TxVersion::V2, alice.keypair.get_public_key().compress(), 0, data, FeeBuilder::Multiplier(1f64));
+    assert!(matches!(builder.build(&mut state, &alice.keypair), Err(GenerationError::PrivateDepositsNotSupported)));
+}
+
 #[tokio::test]
 async fn test_tx_verify() {
     let mut alice = Account::new();
@@ -184,10 +343,7 @@ async fn test_tx_verify() {
     // Alice account is cloned to not be updated as it is used for verification and need current state
     let tx = create_tx_for(alice.clone(), bob.address(), 50, None);
 
-    let mut state = ChainState {
-        accounts: HashMap::new(),
-        multisig: HashMap::new(),
-    };
+    let mut state = ChainState::new();
 
     // Create the chain state
     {
@@ -247,10 +403,7 @@ async fn test_burn_tx_verify() {
         tx
     };
 
-    let mut state = ChainState {
-        accounts: HashMap::new(),
-        multisig: HashMap::new(),
-    };
+    let mut state = ChainState::new();
 
     // Create the chain state
     {
@@ -318,10 +471,7 @@ async fn test_max_transfers() {
     };
 
     // Create the chain state
-    let mut state = ChainState {
-        accounts: HashMap::new(),
-        multisig: HashMap::new(),
-    };
+    let mut state = ChainState::new();
 
     // Alice
     {
@@ -382,10 +532,7 @@ async fn test_multisig_setup() {
         tx
     };
 
-    let mut state = ChainState {
-        accounts: HashMap::new(),
-        multisig: HashMap::new(),
-    };
+    let mut state = ChainState::new();
 
     // Create the chain state
     {
@@ -454,10 +601,7 @@ async fn test_multisig() {
     };
 
     // Create the chain state
-    let mut state = ChainState {
-        accounts: HashMap::new(),
-        multisig: HashMap::new(),
-    };
+    let mut state = ChainState::new();
 
     // Alice
     {
@@ -493,6 +637,203 @@ async fn test_multisig() {
     tx.verify(&hash, &mut state).await.unwrap();
 }
 
+// Build and sign a TX from the current state of the account
+fn build_tx_for(account: &Account, data: TransactionTypeBuilder) -> Transaction {
+    let mut state = AccountStateImpl {
+        balances: account.balances.clone(),
+        nonce: account.nonce,
+        reference: Reference {
+            topoheight: 0,
+            hash: Hash::zero(),
+        },
+    };
+
+    let builder = TransactionBuilder::new(TxVersion::V2, account.keypair.get_public_key().compress(), 0, data, FeeBuilder::Multiplier(1f64));
+    builder.build(&mut state, &account.keypair).unwrap()
+}
+
+fn test_module() -> Module {
+    Module::with(IndexSet::new(), vec![Chunk::from_instructions(vec![0])], [0].into_iter().collect(), IndexSet::new(), IndexSet::new())
+}
+
+#[tokio::test]
+async fn test_paused_contract_invoke() {
+    let mut alice = Account::new();
+    let bob = Account::new();
+    alice.set_balance(XELIS_ASSET, 100 * COIN_VALUE);
+
+    let contract = Hash::max();
+    let tx = build_tx_for(&alice, TransactionTypeBuilder::InvokeContract(InvokeContractBuilder {
+        contract: contract.clone(),
+        max_gas: 1000,
+        chunk_id: 0,
+        parameters: Vec::new(),
+        deposits: [(XELIS_ASSET, ContractDepositBuilder { amount: 100, private: false })].into_iter().collect(),
+    }));
+
+    let mut state = ChainState::new();
+    state.block_version = BlockVersion::V4;
+    state.add_account(&alice);
+    state.contracts.insert(contract.clone(), test_module());
+    state.management.insert(contract.clone(), ContractManagementState {
+        owner: bob.keypair.get_public_key().compress(),
+        paused: true,
+    });
+
+    // Invocations of a paused contract are rejected from the mempool
+    let hash = tx.hash();
+    assert!(matches!(tx.verify(&hash, &mut state).await, Err(VerificationError::ContractPaused)));
+
+    // But if the contract got paused in the same block, the call is refunded
+    tx.apply_without_verify(&hash, &mut state).await.unwrap();
+    assert!(state.has_balance(&alice, 100 * COIN_VALUE - tx.get_fee()));
+    assert_eq!(state.burned_coins, 0);
+    assert_eq!(state.gas_fee, 0);
+
+    let receipts = &state.receipts[&hash];
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].gas_used, 0);
+    assert_eq!(receipts[0].error.as_deref(), Some("contract is paused"));
+
+    let outputs = &state.outputs[&hash];
+    assert!(outputs.iter().any(|output| matches!(output, ContractOutput::RefundDeposits)));
+    assert!(outputs.iter().any(|output| matches!(output, ContractOutput::RefundGas { amount: 1000 })));
+}
+
+#[tokio::test]
+async fn test_manage_contract_not_owner() {
+    let mut alice = Account::new();
+    let bob = Account::new();
+    alice.set_balance(XELIS_ASSET, 100 * COIN_VALUE);
+
+    let contract = Hash::max();
+    let tx = build_tx_for(&alice, TransactionTypeBuilder::ManageContract(ManageContractPayload {
+        contract: contract.clone(),
+        action: ContractManagementAction::Pause,
+    }));
+
+    let mut state = ChainState::new();
+    state.block_version = BlockVersion::V4;
+    state.add_account(&alice);
+    state.management.insert(contract, ContractManagementState {
+        owner: bob.keypair.get_public_key().compress(),
+        paused: false,
+    });
+
+    let hash = tx.hash();
+    assert!(matches!(tx.verify(&hash, &mut state).await, Err(VerificationError::NotContractOwner)));
+}
+
+#[tokio::test]
+async fn test_manage_contract_noop() {
+    let mut alice = Account::new();
+    alice.set_balance(XELIS_ASSET, 100 * COIN_VALUE);
+
+    let contract = Hash::max();
+    let mut state = ChainState::new();
+    state.block_version = BlockVersion::V4;
+    state.add_account(&alice);
+    state.management.insert(contract.clone(), ContractManagementState {
+        owner: alice.keypair.get_public_key().compress(),
+        paused: false,
+    });
+
+    // Contract is not paused, unpausing it does nothing
+    let tx = build_tx_for(&alice, TransactionTypeBuilder::ManageContract(ManageContractPayload {
+        contract: contract.clone(),
+        action: ContractManagementAction::Unpause,
+    }));
+    let hash = tx.hash();
+    assert!(matches!(tx.verify(&hash, &mut state).await, Err(VerificationError::ContractManagementNoop)));
+
+    // The owner can pause it
+    let tx = build_tx_for(&alice, TransactionTypeBuilder::ManageContract(ManageContractPayload {
+        contract: contract.clone(),
+        action: ContractManagementAction::Pause,
+    }));
+    let hash = tx.hash();
+    tx.verify(&hash, &mut state).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_execution_credits_drawn_after_gas() {
+    let mut alice = Account::new();
+    alice.set_balance(XELIS_ASSET, 100 * COIN_VALUE);
+
+    let tx = build_tx_for(&alice, TransactionTypeBuilder::InvokeContract(InvokeContractBuilder {
+        contract: Hash::max(),
+        max_gas: 1000,
+        chunk_id: 0,
+        parameters: Vec::new(),
+        deposits: IndexMap::new(),
+    }));
+
+    let mut state = ChainState::new();
+    state.block_version = BlockVersion::V4;
+    state.add_account(&alice);
+    state.credits.insert(alice.keypair.get_public_key().compress(), 300);
+
+    // Credits cover only a part of the used gas
+    let mut outputs = Vec::new();
+    tx.draw_execution_credits(&mut state, 500, &mut outputs).await.unwrap();
+    assert_eq!(state.credits[tx.get_source()], 0);
+    assert!(state.has_balance(&alice, 100 * COIN_VALUE + 300));
+    assert!(matches!(outputs.as_slice(), [ContractOutput::ExecutionCredits { amount: 300 }]));
+
+    // Credits cover the whole used gas
+    state.credits.insert(alice.keypair.get_public_key().compress(), 1000);
+    let mut outputs = Vec::new();
+    tx.draw_execution_credits(&mut state, 400, &mut outputs).await.unwrap();
+    assert_eq!(state.credits[tx.get_source()], 600);
+    assert!(state.has_balance(&alice, 100 * COIN_VALUE + 700));
+    assert!(matches!(outputs.as_slice(), [ContractOutput::ExecutionCredits { amount: 400 }]));
+
+    // Nothing is drawn without credits
+    state.credits.insert(alice.keypair.get_public_key().compress(), 0);
+    let mut outputs = Vec::new();
+    tx.draw_execution_credits(&mut state, 400, &mut outputs).await.unwrap();
+    assert!(outputs.is_empty());
+}
+
+#[tokio::test]
+async fn test_execution_credits_withdraw_capped() {
+    let mut alice = Account::new();
+    alice.set_balance(XELIS_ASSET, 100 * COIN_VALUE);
+
+    let tx = build_tx_for(&alice, TransactionTypeBuilder::ExecutionCredits(ExecutionCreditsPayload::Withdraw {
+        amount: 10 * COIN_VALUE
+    }));
+    let hash = tx.hash();
+
+    // Not enough credits to withdraw
+    let mut state = ChainState::new();
+    state.block_version = BlockVersion::V4;
+    state.add_account(&alice);
+    state.credits.insert(alice.keypair.get_public_key().compress(), 5 * COIN_VALUE);
+    assert!(matches!(
+        tx.verify(&hash, &mut state).await,
+        Err(VerificationError::InsufficientExecutionCredits(credits, amount)) if credits == 5 * COIN_VALUE && amount == 10 * COIN_VALUE
+    ));
+
+    // Enough credits when verified
+    let mut state = ChainState::new();
+    state.block_version = BlockVersion::V4;
+    state.add_account(&alice);
+    state.credits.insert(alice.keypair.get_public_key().compress(), 10 * COIN_VALUE);
+    tx.verify(&hash, &mut state).await.unwrap();
+
+    // An invocation executed before it in the block drew a part of the credits
+    let mut state = ChainState::new();
+    state.block_version = BlockVersion::V4;
+    state.add_account(&alice);
+    state.credits.insert(alice.keypair.get_public_key().compress(), 4 * COIN_VALUE);
+    tx.apply_without_verify(&hash, &mut state).await.unwrap();
+
+    // Only the remaining credits are unlocked
+    assert_eq!(state.credits[tx.get_source()], 0);
+    assert!(state.has_balance(&alice, 104 * COIN_VALUE - tx.get_fee()));
+}
+
 #[async_trait]
 impl<'a> BlockchainVerificationState<'a, ()> for ChainState {
 
@@ -551,7 +892,7 @@ impl<'a> BlockchainVerificationState<'a, ()> for ChainState {
     }
 
     fn get_block_version(&self) -> BlockVersion {
-        BlockVersion::V0
+        self.block_version
     }
 
     async fn set_multisig_state(
@@ -571,29 +912,149 @@ impl<'a> BlockchainVerificationState<'a, ()> for ChainState {
     }
 
     async fn get_environment(&mut self) -> Result<&Environment, ()> {
-        unimplemented!()
+        Ok(&self.environment)
     }
 
     async fn set_contract_module(
         &mut self,
-        _: &'a Hash,
-        _: &'a Module
+        hash: &'a Hash,
+        module: &'a Module
     ) -> Result<(), ()> {
-        unimplemented!()
+        self.contracts.insert(hash.clone(), module.clone());
+        Ok(())
     }
 
     async fn load_contract_module(
         &mut self,
-        _: &'a Hash
+        hash: &'a Hash
     ) -> Result<(), ()> {
-        unimplemented!()
+        self.contracts.contains_key(hash).then_some(()).ok_or(())
     }
 
     async fn get_contract_module_with_environment(
         &self,
-        _: &'a Hash
+        hash: &'a Hash
     ) -> Result<(&Module, &Environment), ()> {
-        unimplemented!()
+        self.contracts.get(hash).map(|module| (module, &self.environment)).ok_or(())
+    }
+
+    async fn get_contract_management_state(
+        &mut self,
+        hash: &'a Hash
+    ) -> Result<Option<&ContractManagementState>, ()> {
+        Ok(self.management.get(hash))
+    }
+
+    async fn set_contract_management_state(
+        &mut self,
+        hash: &'a Hash,
+        state: ContractManagementState
+    ) -> Result<(), ()> {
+        self.management.insert(hash.clone(), state);
+        Ok(())
+    }
+
+    async fn get_execution_credits(
+        &mut self,
+        account: &'a PublicKey
+    ) -> Result<u64, ()> {
+        Ok(self.credits.get(account).copied().unwrap_or(0))
+    }
+
+    async fn set_execution_credits(
+        &mut self,
+        account: &'a PublicKey,
+        credits: u64
+    ) -> Result<(), ()> {
+        self.credits.insert(account.clone(), credits);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<'a> BlockchainApplyState<'a, MockProvider, ()> for ChainState {
+    async fn add_burned_coins(&mut self, amount: u64) -> Result<(), ()> {
+        self.burned_coins += amount;
+        Ok(())
+    }
+
+    async fn add_gas_fee(&mut self, amount: u64) -> Result<(), ()> {
+        self.gas_fee += amount;
+        Ok(())
+    }
+
+    fn get_block_hash(&self) -> &Hash {
+        &self.block_hash
+    }
+
+    fn get_block(&self) -> &Block {
+        &self.block
+    }
+
+    fn is_mainnet(&self) -> bool {
+        false
+    }
+
+    async fn set_contract_outputs(
+        &mut self,
+        tx_hash: &'a Hash,
+        outputs: Vec<ContractOutput>
+    ) -> Result<(), ()> {
+        self.outputs.entry(tx_hash.clone()).or_default().extend(outputs);
+        Ok(())
+    }
+
+    async fn set_contract_receipt(
+        &mut self,
+        tx_hash: &'a Hash,
+        receipt: ContractReceipt
+    ) -> Result<(), ()> {
+        self.receipts.entry(tx_hash.clone()).or_default().push(receipt);
+        Ok(())
+    }
+
+    async fn get_contract_environment_for<'b>(
+        &'b mut self,
+        contract: &'b Hash,
+        deposits: &'b IndexMap<Hash, ContractDeposit>,
+        tx_hash: &'b Hash
+    ) -> Result<(ContractEnvironment<'b, MockProvider>, ContractChainState<'b>), ()> {
+        let module = self.contracts.get(contract).ok_or(())?;
+
+        let state = ContractChainState {
+            debug_mode: true,
+            random: DeterministicRandom::new(contract, &self.block_hash, tx_hash),
+            mainnet: false,
+            capabilities: ContractCapabilities::all(),
+            contract,
+            topoheight: 0,
+            block_hash: &self.block_hash,
+            block: &self.block,
+            tx_hash,
+            deposits,
+            cache: self.caches.get(contract),
+            changes: ContractCache::new(),
+        };
+
+        let environment = ContractEnvironment {
+            environment: &self.environment,
+            module,
+            provider: &mut self.provider,
+        };
+
+        Ok((environment, state))
+    }
+
+    async fn merge_contract_cache(
+        &mut self,
+        hash: &'a Hash,
+        cache: ContractCache
+    ) -> Result<(), ()> {
+        self.caches.entry(hash.clone())
+            .or_insert_with(ContractCache::new)
+            .merge(cache);
+
+        Ok(())
     }
 }
 
@@ -638,4 +1099,61 @@ impl AccountState for AccountStateImpl {
         self.nonce = new_nonce;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+// Fill a buffer with random bytes to drive the structured generation
+fn random_unstructured_data() -> Vec<u8> {
+    let mut data = vec![0u8; 32 * 1024];
+    OsRng.fill_bytes(&mut data);
+    data
+}
+
+#[test]
+fn test_arbitrary_tx_roundtrip() {
+    for _ in 0..64 {
+        let data = random_unstructured_data();
+        let Ok(tx) = Transaction::arbitrary(&mut Unstructured::new(&data)) else {
+            continue
+        };
+
+        let bytes = tx.to_bytes();
+        let read = Transaction::from_bytes(&bytes).unwrap();
+        assert_eq!(read.to_bytes(), bytes);
+        assert_eq!(read.hash(), tx.hash());
+    }
+}
+
+#[test]
+fn test_malformed_tx_read() {
+    for _ in 0..64 {
+        let data = random_unstructured_data();
+        let Ok(tx) = Transaction::arbitrary(&mut Unstructured::new(&data)) else {
+            continue
+        };
+
+        // Corrupt one byte or truncate the encoding, reading it must fail gracefully
+        let mut bytes = tx.to_bytes();
+        let index = (OsRng.next_u32() as usize) % bytes.len();
+        bytes[index] ^= 0xFF;
+        let _ = Transaction::from_bytes(&bytes);
+        let _ = Transaction::from_bytes(&bytes[..index]);
+
+        // Random bytes are never a valid transaction
+        assert!(Transaction::from_bytes(&data[..index]).is_err());
+    }
+}
+
+#[tokio::test]
+async fn test_arbitrary_tx_verify() {
+    let mut state = ChainState::new();
+
+    for _ in 0..16 {
+        let data = random_unstructured_data();
+        let Ok(tx) = Transaction::arbitrary(&mut Unstructured::new(&data)) else {
+            continue
+        };
+
+        let hash = tx.hash();
+        assert!(tx.verify(&hash, &mut state).await.is_err());
+    }
+}
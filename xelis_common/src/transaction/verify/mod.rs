@@ -11,23 +11,37 @@ use curve25519_dalek::{
     Scalar
 };
 use log::{debug, trace};
+use indexmap::IndexMap;
 use merlin::Transcript;
 use xelis_vm::{ConstantWrapper, ModuleValidator, VM};
 use crate::{
     account::Nonce,
+    block::BlockVersion,
     config::{BURN_PER_CONTRACT, TRANSACTION_FEE_BURN_PERCENT, XELIS_ASSET},
-    contract::{get_balance_from_cache, ContractOutput, ContractProvider, ContractProviderWrapper},
+    contract::{
+        get_balance_from_cache,
+        validate_module,
+        ContractManagementState,
+        ContractOutput,
+        ContractProvider,
+        ContractProviderWrapper,
+        ContractReceipt,
+        ModuleValidationError
+    },
     crypto::{
         elgamal::{
             Ciphertext,
             CompressedPublicKey,
             DecompressionError,
             DecryptHandle,
-            PedersenCommitment
+            PedersenCommitment,
+            PublicKey
         },
         hash,
         proofs::{
+            AuditProof,
             BatchCollector,
+            PaymentProof,
             ProofVerificationError,
             BP_GENS,
             BULLET_PROOF_SIZE,
@@ -37,6 +51,13 @@ use crate::{
         ProtocolTranscript,
         SIGNATURE_SIZE
     },
+    metrics::{
+        increment_counter,
+        HistogramTimer,
+        TX_BATCH_VERIFICATION_SECONDS,
+        TX_VERIFICATION_SECONDS,
+        TX_VERIFIED_TOTAL
+    },
     serializer::Serializer,
     transaction::{
         TxVersion,
@@ -49,7 +70,9 @@ use crate::{
     versioned_type::VersionedState
 };
 use super::{
+    CompressedConstant,
     ContractDeposit,
+    ExecutionCreditsPayload,
     Role,
     Transaction,
     TransactionType,
@@ -96,6 +119,27 @@ pub enum VerificationError<T> {
     InvalidInvokeContract,
     #[error("overflow during gas calculation")]
     GasOverflow,
+    #[error("Invalid contract module: {0}")]
+    ModuleValidation(#[from] ModuleValidationError),
+    #[error("Contract is paused")]
+    ContractPaused,
+    #[error("Contract has no management state")]
+    ContractNotManaged,
+    #[error("Only the contract owner can manage it")]
+    NotContractOwner,
+    #[error("Contract is already in the requested state")]
+    ContractManagementNoop,
+    #[error("Insufficient execution credits, got {} requested {}", _0, _1)]
+    InsufficientExecutionCredits(u64, u64),
+}
+
+// Entry chunk call of a contract, from an invoke or a constructor
+struct ContractCall<'a> {
+    contract: &'a Hash,
+    deposits: &'a IndexMap<Hash, ContractDeposit>,
+    parameters: &'a [CompressedConstant],
+    chunk_id: u16,
+    max_gas: u64
 }
 
 struct DecompressedTransferCt {
@@ -195,11 +239,34 @@ impl Transaction {
                     }
                 }
             },
-            TransactionType::DeployContract(_) => {
+            TransactionType::DeployContract(payload) => {
                 // Burn a full coin for each contract deployed
                 if *asset == XELIS_ASSET {
                     output += Scalar::from(BURN_PER_CONTRACT);
                 }
+
+                if let Some(invoke) = payload.invoke.as_ref() {
+                    if *asset == XELIS_ASSET {
+                        output += Scalar::from(invoke.max_gas);
+                    }
+
+                    if let Some(deposit) = invoke.deposits.get(asset) {
+                        match deposit {
+                            ContractDeposit::Public(amount) => {
+                                output += Scalar::from(*amount);
+                            },
+                        }
+                    }
+                }
+            },
+            TransactionType::ManageContract(_) => {},
+            TransactionType::ExecutionCredits(payload) => {
+                // Only a deposit takes XELIS from the balance
+                if let ExecutionCreditsPayload::Deposit { amount } = payload {
+                    if *asset == XELIS_ASSET {
+                        output += Scalar::from(*amount);
+                    }
+                }
             }
         }
 
@@ -227,6 +294,47 @@ impl Transaction {
         Ok(balances)
     }
 
+    /// Verify a payment proof against the transfer at index
+    /// Returns the transfer that was proven to be sent by the source with the proof amount
+    pub fn verify_payment_proof<'a>(&'a self, hash: &Hash, index: u8, proof: &PaymentProof) -> Result<&'a TransferPayload, ProofVerificationError> {
+        let transfer = match &self.data {
+            TransactionType::Transfers(transfers) => transfers.get(index as usize),
+            _ => None
+        }.ok_or(ProofVerificationError::Format)?;
+
+        // The sender ciphertext shares the commitment with the receiver one
+        // which is bound by the ciphertext validity proof of the transfer
+        let source = self.source.decompress()?;
+        let ciphertext = DecompressedTransferCt::decompress(transfer)?
+            .get_ciphertext(Role::Sender);
+
+        proof.verify(&source, hash, index, ciphertext)?;
+
+        Ok(transfer)
+    }
+
+    // Verify an audit proof of the transfer at index, created by its sender or its receiver
+    pub fn verify_audit_proof<'a>(&'a self, account: &CompressedPublicKey, auditor: &PublicKey, index: u8, proof: &AuditProof) -> Result<&'a TransferPayload, ProofVerificationError> {
+        let transfer = match &self.data {
+            TransactionType::Transfers(transfers) => transfers.get(index as usize),
+            _ => None
+        }.ok_or(ProofVerificationError::Format)?;
+
+        let role = if self.source == *account {
+            Role::Sender
+        } else if transfer.get_destination() == account {
+            Role::Receiver
+        } else {
+            return Err(ProofVerificationError::Format)
+        };
+
+        let ciphertext = DecompressedTransferCt::decompress(transfer)?
+            .get_ciphertext(role);
+        proof.verify(&account.decompress()?, auditor, ciphertext)?;
+
+        Ok(transfer)
+    }
+
     pub(crate) fn prepare_transcript(
         version: TxVersion,
         source_pubkey: &CompressedPublicKey,
@@ -280,7 +388,10 @@ impl Transaction {
                 .deposits
                 .keys()
                 .all(|asset| has_commitment_for_asset(asset)),
-            TransactionType::DeployContract(_) => true,
+            TransactionType::DeployContract(payload) => payload.invoke.as_ref()
+                .map_or(true, |invoke| invoke.deposits.keys().all(|asset| has_commitment_for_asset(asset))),
+            TransactionType::ManageContract(_) => true,
+            TransactionType::ExecutionCredits(_) => true,
         }
     }
 
@@ -406,6 +517,17 @@ impl Transaction {
                 state.load_contract_module(&payload.contract).await
                     .map_err(VerificationError::State)?;
 
+                // Invocations are rejected while the owner paused the contract
+                // Contracts can only be paused since the V4 hard fork
+                let paused = state.get_block_version() >= BlockVersion::V4
+                    && state.get_contract_management_state(&payload.contract).await
+                        .map_err(VerificationError::State)?
+                        .is_some_and(|management| management.paused);
+
+                if paused {
+                    return Err(VerificationError::ContractPaused);
+                }
+
                 let (module, environment) = state.get_contract_module_with_environment(&payload.contract).await
                     .map_err(VerificationError::State)?;
 
@@ -425,13 +547,65 @@ impl Transaction {
                         .map_err(|err| VerificationError::ModuleError(format!("{:#}", err)))?;
                 }
             },
-            TransactionType::DeployContract(module) => {
+            TransactionType::DeployContract(payload) => {
                 let environment = state.get_environment().await
                     .map_err(VerificationError::State)?;
 
-                let validator = ModuleValidator::new(module, environment);
-                validator.verify()
-                    .map_err(|err| VerificationError::ModuleError(format!("{:#}", err)))?;
+                let module = &payload.module;
+                validate_module(module, environment)?;
+
+                if let Some(invoke) = payload.invoke.as_ref() {
+                    if invoke.deposits.len() > MAX_DEPOSIT_PER_INVOKE_CALL {
+                        return Err(VerificationError::TransferCount);
+                    }
+
+                    if !module.is_entry_chunk(invoke.chunk_id as usize) {
+                        return Err(VerificationError::InvalidInvokeContract);
+                    }
+
+                    let validator = ModuleValidator::new(module, environment);
+                    for constant in invoke.parameters.iter() {
+                        let decompressed = constant.decompress(module.structs(), module.enums())
+                            .context("decompress param")?;
+
+                        // For safety, we wrap it in our custom type in case of a potential stackoverflow attack
+                        let wrapped = ConstantWrapper(decompressed);
+
+                        validator.verify_constant(&wrapped.0)
+                            .map_err(|err| VerificationError::ModuleError(format!("{:#}", err)))?;
+                    }
+                }
+            },
+            TransactionType::ManageContract(payload) => {
+                let management = state.get_contract_management_state(&payload.contract).await
+                    .map_err(VerificationError::State)?
+                    .ok_or(VerificationError::ContractNotManaged)?;
+
+                if management.owner != self.source {
+                    return Err(VerificationError::NotContractOwner);
+                }
+
+                if management.paused == payload.action.is_pause() {
+                    return Err(VerificationError::ContractManagementNoop);
+                }
+            },
+            TransactionType::ExecutionCredits(payload) => {
+                let amount = payload.get_amount();
+                if amount == 0 {
+                    return Err(VerificationError::InvalidFormat);
+                }
+
+                self.fee.checked_add(amount)
+                    .ok_or(VerificationError::InvalidFormat)?;
+
+                if !payload.is_deposit() {
+                    let credits = state.get_execution_credits(&self.source).await
+                        .map_err(VerificationError::State)?;
+
+                    if credits < amount {
+                        return Err(VerificationError::InsufficientExecutionCredits(credits, amount));
+                    }
+                }
             }
         };
 
@@ -637,10 +811,64 @@ impl Transaction {
                     transcript.append_message(b"contract_param", param.as_bytes());
                 }
             },
-            TransactionType::DeployContract(module) => {
+            TransactionType::DeployContract(payload) => {
                 transcript.deploy_contract_proof_domain_separator();
+                if let Some(invoke) = payload.invoke.as_ref() {
+                    transcript.append_u64(b"constructor_chunk_id", invoke.chunk_id as u64);
+                    transcript.append_u64(b"constructor_max_gas", invoke.max_gas);
+                    for (asset, deposit) in &invoke.deposits {
+                        transcript.append_hash(b"deposit_asset", asset);
+                        match deposit {
+                            ContractDeposit::Public(amount) => {
+                                transcript.append_u64(b"deposit_plain", *amount);
+                            },
+                        }
+                    }
 
-                state.set_contract_module(tx_hash, module).await
+                    for param in invoke.parameters.iter() {
+                        transcript.append_message(b"contract_param", param.as_bytes());
+                    }
+                }
+
+                state.set_contract_module(tx_hash, &payload.module).await
+                    .map_err(VerificationError::State)?;
+
+                state.set_contract_management_state(tx_hash, ContractManagementState::new(self.source.clone())).await
+                    .map_err(VerificationError::State)?;
+            },
+            TransactionType::ManageContract(payload) => {
+                transcript.manage_contract_proof_domain_separator();
+                transcript.append_hash(b"contract_hash", &payload.contract);
+                transcript.append_u64(b"contract_paused", payload.action.is_pause() as u64);
+
+                // Update it for the next TXs of the batch
+                let management = ContractManagementState {
+                    owner: self.source.clone(),
+                    paused: payload.action.is_pause()
+                };
+                state.set_contract_management_state(&payload.contract, management).await
+                    .map_err(VerificationError::State)?;
+            },
+            TransactionType::ExecutionCredits(payload) => {
+                transcript.execution_credits_proof_domain_separator();
+                let credits = state.get_execution_credits(&self.source).await
+                    .map_err(VerificationError::State)?;
+
+                // Update it for the next TXs of the batch
+                let credits = match payload {
+                    ExecutionCreditsPayload::Deposit { amount } => {
+                        transcript.append_u64(b"credits_deposit", *amount);
+                        credits.checked_add(*amount)
+                            .ok_or(VerificationError::InvalidFormat)?
+                    },
+                    ExecutionCreditsPayload::Withdraw { amount } => {
+                        transcript.append_u64(b"credits_withdraw", *amount);
+                        credits.checked_sub(*amount)
+                            .ok_or(VerificationError::InsufficientExecutionCredits(credits, *amount))?
+                    }
+                };
+
+                state.set_execution_credits(&self.source, credits).await
                     .map_err(VerificationError::State)?;
             }
         }
@@ -683,6 +911,7 @@ impl Transaction {
         state: &mut B,
     ) -> Result<(), VerificationError<E>> {
         trace!("Verifying batch of {} transactions", txs.len());
+        let _timer = HistogramTimer::new(TX_BATCH_VERIFICATION_SECONDS);
         let mut sigma_batch_collector = BatchCollector::default();
         let mut prepared = Vec::with_capacity(txs.len());
         for (tx, hash) in txs {
@@ -708,6 +937,7 @@ impl Transaction {
         )
         .map_err(ProofVerificationError::from)?;
 
+        increment_counter(TX_VERIFIED_TOTAL, txs.len() as u64);
         Ok(())
     }
 
@@ -717,6 +947,7 @@ impl Transaction {
         tx_hash: &'a Hash,
         state: &mut B,
     ) -> Result<(), VerificationError<E>> {
+        let _timer = HistogramTimer::new(TX_VERIFICATION_SECONDS);
         let mut sigma_batch_collector = BatchCollector::default();
         let (mut transcript, commitments) = self.pre_verify(tx_hash, state, &mut sigma_batch_collector).await?;
 
@@ -736,6 +967,7 @@ impl Transaction {
         )
         .map_err(ProofVerificationError::from)?;
 
+        increment_counter(TX_VERIFIED_TOTAL, 1);
         Ok(())
     }
 
@@ -780,161 +1012,343 @@ impl Transaction {
                 state.set_multisig_state(&self.source, payload).await.map_err(VerificationError::State)?;
             },
             TransactionType::InvokeContract(payload) => {
-                state.load_contract_module(&payload.contract).await
+                // The contract may have been paused by a TX executed before this one
+                let paused = state.get_block_version() >= BlockVersion::V4
+                    && state.get_contract_management_state(&payload.contract).await
+                        .map_err(VerificationError::State)?
+                        .is_some_and(|management| management.paused);
+
+                let call = ContractCall {
+                    contract: &payload.contract,
+                    deposits: &payload.deposits,
+                    parameters: &payload.parameters,
+                    chunk_id: payload.chunk_id,
+                    max_gas: payload.max_gas
+                };
+
+                if paused {
+                    debug!("Contract {} is paused, refunding TX {}", payload.contract, tx_hash);
+                    self.refund_contract_call(tx_hash, state, call).await?;
+                } else {
+                    self.invoke_contract(tx_hash, state, call).await?;
+                }
+            },
+            TransactionType::DeployContract(payload) => {
+                state.set_contract_module(tx_hash, &payload.module).await
                     .map_err(VerificationError::State)?;
 
-                let (contract_environment, mut chain_state) = state.get_contract_environment_for(payload, tx_hash).await
+                state.set_contract_management_state(tx_hash, ContractManagementState::new(self.source.clone())).await
                     .map_err(VerificationError::State)?;
 
-                // We need to add the deposits to the balances
-                for (asset, deposit) in payload.deposits.iter() {
-                    match deposit {
-                        ContractDeposit::Public(amount) => {
-                            let (mut balance_state, mut balance) = get_balance_from_cache(contract_environment.provider, &mut chain_state, asset.clone())?
-                                .unwrap_or((VersionedState::New, 0));
+                // The contract address is the TX hash
+                if let Some(invoke) = payload.invoke.as_ref() {
+                    let call = ContractCall {
+                        contract: tx_hash,
+                        deposits: &invoke.deposits,
+                        parameters: &invoke.parameters,
+                        chunk_id: invoke.chunk_id,
+                        max_gas: invoke.max_gas
+                    };
+
+                    self.invoke_contract(tx_hash, state, call).await?;
+                }
+            },
+            TransactionType::ManageContract(payload) => {
+                let management = ContractManagementState {
+                    owner: self.source.clone(),
+                    paused: payload.action.is_pause()
+                };
+                state.set_contract_management_state(&payload.contract, management).await
+                    .map_err(VerificationError::State)?;
+            },
+            TransactionType::ExecutionCredits(payload) => {
+                let credits = state.get_execution_credits(&self.source).await
+                    .map_err(VerificationError::State)?;
 
-                            balance += amount;
-                            balance_state.mark_updated();
+                let credits = match payload {
+                    ExecutionCreditsPayload::Deposit { amount } => credits.checked_add(*amount)
+                        .ok_or(VerificationError::InvalidFormat)?,
+                    ExecutionCreditsPayload::Withdraw { amount } => {
+                        // Credits may have been drawn by an invocation executed
+                        // earlier in the same block, only unlock what is left
+                        let withdrawn = credits.min(*amount);
+                        let balance = state.get_receiver_balance(Cow::Borrowed(self.get_source()), Cow::Owned(XELIS_ASSET)).await
+                            .map_err(VerificationError::State)?;
 
-                            chain_state.changes.balances.insert(asset.clone(), Some((balance_state, balance)));
-                        },
+                        *balance += Scalar::from(withdrawn);
+                        credits - withdrawn
                     }
-                }
+                };
 
-                // Total used gas by the VM
-                let (used_gas, exit_code) = {
-                    // Create the VM
-                    let module = contract_environment.module;
-                    let mut vm = VM::new(module, contract_environment.environment);
+                state.set_execution_credits(&self.source, credits).await
+                    .map_err(VerificationError::State)?;
+            }
+        }
 
-                    // We need to push it in reverse order because the VM will pop them in reverse order
-                    for constant in payload.parameters.iter().rev() {
-                        let decompressed = constant.decompress(module.structs(), module.enums())
-                            .context("decompress param")?;
+        Ok(())
+    }
 
-                        trace!("Pushing constant: {}", decompressed);
-                        vm.push_stack(decompressed)
-                            .context("push param")?;
-                    }
+    // Refund the deposits and the whole gas of a call that can't be executed
+    async fn refund_contract_call<'a, P: ContractProvider, E, B: BlockchainApplyState<'a, P, E>>(
+        &'a self,
+        tx_hash: &'a Hash,
+        state: &mut B,
+        call: ContractCall<'a>
+    ) -> Result<(), VerificationError<E>> {
+        let max_gas = call.max_gas;
+        let mut outputs = Vec::new();
+        for (asset, deposit) in call.deposits.iter() {
+            match deposit {
+                ContractDeposit::Public(amount) => {
+                    let balance = state.get_receiver_balance(Cow::Borrowed(self.get_source()), Cow::Borrowed(asset)).await
+                        .map_err(VerificationError::State)?;
 
-                    // Invoke the entry chunk
-                    // This is the first chunk to be called
-                    vm.invoke_entry_chunk(payload.chunk_id)
-                        .context("invoke entry chunk")?;
-
-                    let context = vm.context_mut();
-
-                    // Set the gas limit for the VM
-                    context.set_gas_limit(payload.max_gas);
-
-                    // Configure the context
-                    // Note that the VM already include the environment in Context
-                    context.insert_ref(&self);
-                    // insert the chain state separetly to avoid to give the S type
-                    context.insert_mut(&mut chain_state);
-                    // insert the storage through our wrapper
-                    // so it can be easily mocked
-                    context.insert(ContractProviderWrapper(contract_environment.provider));
-
-                    // We need to handle the result of the VM
-                    let res = vm.run();
-
-                    // To be sure that we don't have any overflow
-                    // We take the minimum between the gas used and the max gas
-                    let gas_usage = vm.context()
-                        .current_gas_usage()
-                        .min(payload.max_gas);
-
-                    let exit_code = match res {
-                        Ok(res) => {
-                            debug!("Invoke contract {} from TX {} result: {:#}", payload.contract, tx_hash, res);
-                            // If the result return 0 as exit code, it means that everything went well
-                            let exit_code = res.as_u64().ok();
-                            exit_code
-                        },
-                        Err(err) => {
-                            debug!("Invoke contract {} from TX {} error: {:#}", payload.contract, tx_hash, err);
-                            None
-                        }
-                    };
+                    *balance += Scalar::from(*amount);
+                },
+            }
+        }
 
-                    (gas_usage, exit_code)
-                };
+        if !call.deposits.is_empty() {
+            outputs.push(ContractOutput::RefundDeposits);
+        }
 
-                let mut outputs = Vec::new();
-                if exit_code == Some(0) {
-                    let cache = chain_state.changes;
-                    outputs = cache.transfers.iter().map(|transfer| {
-                        // Track the output
-                        ContractOutput::Transfer {
-                            destination: transfer.destination.clone(),
-                            asset: transfer.asset.clone(),
-                            amount: transfer.amount,
-                        }
-                    }).collect::<Vec<_>>();
+        outputs.push(ContractOutput::ExitCode(None));
 
-                    state.merge_contract_cache(&payload.contract, cache).await
-                        .map_err(VerificationError::State)?;
-                } else if !payload.deposits.is_empty() {
-                    // It was not successful, we need to refund the deposits
-                    for (asset, deposit) in payload.deposits.iter() {
-                        match deposit {
-                            ContractDeposit::Public(amount) => {
-                                let balance = state.get_receiver_balance(Cow::Borrowed(self.get_source()), Cow::Owned(asset.clone())).await
-                                    .map_err(VerificationError::State)?;
+        if max_gas > 0 {
+            let balance = state.get_receiver_balance(Cow::Borrowed(self.get_source()), Cow::Owned(XELIS_ASSET)).await
+                .map_err(VerificationError::State)?;
 
-                                *balance += Scalar::from(*amount);
-                            },
-                        }
-                    }
+            *balance += Scalar::from(max_gas);
+            outputs.push(ContractOutput::RefundGas { amount: max_gas });
+        }
 
-                    outputs.push(ContractOutput::RefundDeposits);
-                }
+        let receipt = ContractReceipt {
+            contract: call.contract.clone(),
+            chunk_id: call.chunk_id,
+            max_gas,
+            gas_used: 0,
+            exit_code: None,
+            error: Some("contract is paused".to_owned()),
+            events: Vec::new(),
+            transfers: Vec::new()
+        };
 
-                // Push the exit code to the outputs
-                outputs.push(ContractOutput::ExitCode(exit_code));
-
-                if used_gas > 0 {
-                    // Part of the gas is burned
-                    let burned_gas = used_gas * TRANSACTION_FEE_BURN_PERCENT / 100;
-                    // Part of the gas is given to the miners as fees
-                    let gas_fee = used_gas.checked_sub(burned_gas)
-                        .ok_or(VerificationError::GasOverflow)?;
-                    // The remaining gas is refunded to the sender
-                    let refund_gas = payload.max_gas.checked_sub(used_gas)
-                        .ok_or(VerificationError::GasOverflow)?;
-
-                    debug!("Invoke contract used gas: {}, burned: {}, fee: {}, refund: {}", used_gas, burned_gas, gas_fee, refund_gas);
-                    state.add_burned_coins(burned_gas).await
-                        .map_err(VerificationError::State)?;
+        state.set_contract_receipt(tx_hash, receipt).await
+            .map_err(VerificationError::State)?;
 
-                    state.add_gas_fee(gas_fee).await
-                        .map_err(VerificationError::State)?;
-    
-                    if refund_gas > 0 {
-                        // If we have some funds to refund, we add it to the sender balance
-                        // But to prevent any front running, we add to the sender balance by considering him as a receiver.
-                        let balance = state.get_receiver_balance(Cow::Borrowed(self.get_source()), Cow::Owned(XELIS_ASSET)).await
-                            .map_err(VerificationError::State)?;
+        state.set_contract_outputs(tx_hash, outputs).await
+            .map_err(VerificationError::State)
+    }
+
+    // Used gas is paid with the execution credits of the sender first
+    // and the covered part is given back to the sender balance
+    pub(super) async fn draw_execution_credits<'a, E, B: BlockchainVerificationState<'a, E>>(
+        &'a self,
+        state: &mut B,
+        used_gas: u64,
+        outputs: &mut Vec<ContractOutput>
+    ) -> Result<(), VerificationError<E>> {
+        let credits = state.get_execution_credits(self.get_source()).await
+            .map_err(VerificationError::State)?;
+        let drawn_credits = credits.min(used_gas);
+
+        if drawn_credits > 0 {
+            state.set_execution_credits(self.get_source(), credits - drawn_credits).await
+                .map_err(VerificationError::State)?;
+
+            let balance = state.get_receiver_balance(Cow::Borrowed(self.get_source()), Cow::Owned(XELIS_ASSET)).await
+                .map_err(VerificationError::State)?;
+
+            *balance += Scalar::from(drawn_credits);
+            outputs.push(ContractOutput::ExecutionCredits { amount: drawn_credits });
+        }
+
+        Ok(())
+    }
+
+    // Execute an entry chunk of a contract with the deposits of this TX
+    // Storage changes are merged only if the contract exits with code 0,
+    // otherwise the deposits are refunded
+    async fn invoke_contract<'a, P: ContractProvider, E, B: BlockchainApplyState<'a, P, E>>(
+        &'a self,
+        tx_hash: &'a Hash,
+        state: &mut B,
+        call: ContractCall<'a>
+    ) -> Result<(), VerificationError<E>> {
+        state.load_contract_module(call.contract).await
+            .map_err(VerificationError::State)?;
+
+        let (contract_environment, mut chain_state) = state.get_contract_environment_for(call.contract, call.deposits, tx_hash).await
+            .map_err(VerificationError::State)?;
+
+        // We need to add the deposits to the balances
+        for (asset, deposit) in call.deposits.iter() {
+            match deposit {
+                ContractDeposit::Public(amount) => {
+                    let (mut balance_state, mut balance) = get_balance_from_cache(contract_environment.provider, &mut chain_state, asset.clone())?
+                        .unwrap_or((VersionedState::New, 0));
 
-                        *balance += Scalar::from(refund_gas);
+                    balance += amount;
+                    balance_state.mark_updated();
 
-                        // Track the refund
-                        let output = ContractOutput::RefundGas { amount: refund_gas };
-                        outputs.push(output);
+                    chain_state.changes.balances.insert(asset.clone(), Some((balance_state, balance)));
+                },
+            }
+        }
+
+        // Total used gas by the VM
+        let (used_gas, exit_code, error) = {
+            // Create the VM
+            let module = contract_environment.module;
+            let mut vm = VM::new(module, contract_environment.environment);
+
+            // We need to push it in reverse order because the VM will pop them in reverse order
+            for constant in call.parameters.iter().rev() {
+                let decompressed = constant.decompress(module.structs(), module.enums())
+                    .context("decompress param")?;
+
+                trace!("Pushing constant: {}", decompressed);
+                vm.push_stack(decompressed)
+                    .context("push param")?;
+            }
+
+            // Invoke the entry chunk
+            // This is the first chunk to be called
+            vm.invoke_entry_chunk(call.chunk_id)
+                .context("invoke entry chunk")?;
+
+            let context = vm.context_mut();
+
+            // Set the gas limit for the VM
+            context.set_gas_limit(call.max_gas);
+
+            // Configure the context
+            // Note that the VM already include the environment in Context
+            context.insert_ref(&self);
+            // insert the chain state separetly to avoid to give the S type
+            context.insert_mut(&mut chain_state);
+            // insert the storage through our wrapper
+            // so it can be easily mocked
+            context.insert(ContractProviderWrapper(contract_environment.provider));
+
+            // We need to handle the result of the VM
+            let res = vm.run();
+
+            // To be sure that we don't have any overflow
+            // We take the minimum between the gas used and the max gas
+            let gas_usage = vm.context()
+                .current_gas_usage()
+                .min(call.max_gas);
+
+            let (exit_code, error) = match res {
+                Ok(res) => {
+                    debug!("Invoke contract {} from TX {} result: {:#}", call.contract, tx_hash, res);
+                    // If the result return 0 as exit code, it means that everything went well
+                    match res.as_u64() {
+                        Ok(exit_code) => (Some(exit_code), None),
+                        Err(_) => (None, Some("invalid exit code returned".to_owned()))
                     }
+                },
+                Err(err) => {
+                    debug!("Invoke contract {} from TX {} error: {:#}", call.contract, tx_hash, err);
+                    (None, Some(format!("{:#}", err)))
                 }
+            };
 
-                // Track the outputs
-                state.set_contract_outputs(tx_hash, outputs).await
-                    .map_err(VerificationError::State)?;
-            },
-            TransactionType::DeployContract(module) => {
-                state.set_contract_module(tx_hash, module).await
+            (gas_usage, exit_code, error)
+        };
+
+        let mut receipt = ContractReceipt {
+            contract: call.contract.clone(),
+            chunk_id: call.chunk_id,
+            max_gas: call.max_gas,
+            gas_used: used_gas,
+            exit_code,
+            error,
+            events: Vec::new(),
+            transfers: Vec::new()
+        };
+
+        let mut outputs = Vec::new();
+        if exit_code == Some(0) {
+            let mut cache = chain_state.changes;
+            // Events are only kept in the receipt
+            receipt.events = std::mem::take(&mut cache.events);
+            receipt.transfers = cache.transfers.clone();
+
+            outputs = cache.transfers.iter().map(|transfer| {
+                // Track the output
+                ContractOutput::Transfer {
+                    destination: transfer.destination.clone(),
+                    asset: transfer.asset.clone(),
+                    amount: transfer.amount,
+                }
+            }).collect::<Vec<_>>();
+
+            state.merge_contract_cache(call.contract, cache).await
+                .map_err(VerificationError::State)?;
+        } else if !call.deposits.is_empty() {
+            // It was not successful, we need to refund the deposits
+            for (asset, deposit) in call.deposits.iter() {
+                match deposit {
+                    ContractDeposit::Public(amount) => {
+                        let balance = state.get_receiver_balance(Cow::Borrowed(self.get_source()), Cow::Owned(asset.clone())).await
+                            .map_err(VerificationError::State)?;
+
+                        *balance += Scalar::from(*amount);
+                    },
+                }
+            }
+
+            outputs.push(ContractOutput::RefundDeposits);
+        }
+
+        // Push the exit code to the outputs
+        outputs.push(ContractOutput::ExitCode(exit_code));
+
+        if used_gas > 0 {
+            // Part of the gas is burned
+            let burned_gas = used_gas * TRANSACTION_FEE_BURN_PERCENT / 100;
+            // Part of the gas is given to the miners as fees
+            let gas_fee = used_gas.checked_sub(burned_gas)
+                .ok_or(VerificationError::GasOverflow)?;
+            // The remaining gas is refunded to the sender
+            let refund_gas = call.max_gas.checked_sub(used_gas)
+                .ok_or(VerificationError::GasOverflow)?;
+
+            debug!("Invoke contract used gas: {}, burned: {}, fee: {}, refund: {}", used_gas, burned_gas, gas_fee, refund_gas);
+            state.add_burned_coins(burned_gas).await
+                .map_err(VerificationError::State)?;
+
+            state.add_gas_fee(gas_fee).await
+                .map_err(VerificationError::State)?;
+
+            if refund_gas > 0 {
+                // If we have some funds to refund, we add it to the sender balance
+                // But to prevent any front running, we add to the sender balance by considering him as a receiver.
+                let balance = state.get_receiver_balance(Cow::Borrowed(self.get_source()), Cow::Owned(XELIS_ASSET)).await
                     .map_err(VerificationError::State)?;
+
+                *balance += Scalar::from(refund_gas);
+
+                // Track the refund
+                let output = ContractOutput::RefundGas { amount: refund_gas };
+                outputs.push(output);
+            }
+
+            // Execution credits are only drawn since the V4 hard fork
+            if state.get_block_version() >= BlockVersion::V4 {
+                self.draw_execution_credits(state, used_gas, &mut outputs).await?;
             }
         }
 
+        state.set_contract_receipt(tx_hash, receipt).await
+            .map_err(VerificationError::State)?;
+
+        // Track the outputs
+        state.set_contract_outputs(tx_hash, outputs).await
+            .map_err(VerificationError::State)?;
+
         Ok(())
     }
 
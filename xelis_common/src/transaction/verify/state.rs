@@ -1,11 +1,19 @@
 use std::borrow::Cow;
 
 use async_trait::async_trait;
+use indexmap::IndexMap;
 use xelis_vm::{Environment, Module};
 use crate::{
     account::Nonce,
     block::{Block, BlockVersion},
-    contract::{ChainState, ContractCache, ContractOutput, ContractProvider},
+    contract::{
+        ChainState,
+        ContractCache,
+        ContractManagementState,
+        ContractOutput,
+        ContractProvider,
+        ContractReceipt
+    },
     crypto::{
         elgamal::{
             Ciphertext,
@@ -14,7 +22,7 @@ use crate::{
         Hash
     },
     transaction::{
-        InvokeContractPayload,
+        ContractDeposit,
         MultiSigPayload,
         Reference,
         Transaction
@@ -112,6 +120,32 @@ pub trait BlockchainVerificationState<'a, E> {
         &self,
         hash: &'a Hash
     ) -> Result<(&Module, &Environment), E>;
+
+    /// Get the management state (owner, paused) of a contract
+    async fn get_contract_management_state(
+        &mut self,
+        hash: &'a Hash
+    ) -> Result<Option<&ContractManagementState>, E>;
+
+    /// Set the management state of a contract
+    async fn set_contract_management_state(
+        &mut self,
+        hash: &'a Hash,
+        state: ContractManagementState
+    ) -> Result<(), E>;
+
+    /// Get the execution credits locked by an account
+    async fn get_execution_credits(
+        &mut self,
+        account: &'a CompressedPublicKey
+    ) -> Result<u64, E>;
+
+    /// Set the execution credits locked by an account
+    async fn set_execution_credits(
+        &mut self,
+        account: &'a CompressedPublicKey,
+        credits: u64
+    ) -> Result<(), E>;
 }
 
 pub struct ContractEnvironment<'a, P: ContractProvider> {
@@ -147,10 +181,18 @@ pub trait BlockchainApplyState<'a, P: ContractProvider, E>: BlockchainVerificati
         outputs: Vec<ContractOutput>
     ) -> Result<(), E>;
 
+    /// Track the receipt of a contract invocation
+    async fn set_contract_receipt(
+        &mut self,
+        tx_hash: &'a Hash,
+        receipt: ContractReceipt
+    ) -> Result<(), E>;
+
     /// Get the contract environment
     async fn get_contract_environment_for<'b>(
         &'b mut self,
-        payload: &'b InvokeContractPayload,
+        contract: &'b Hash,
+        deposits: &'b IndexMap<Hash, ContractDeposit>,
         tx_hash: &'b Hash
     ) -> Result<(ContractEnvironment<'b, P>, ChainState<'b>), E>;
 
@@ -93,6 +93,13 @@ impl<'de> serde::Deserialize<'de> for TxVersion {
     }
 }
 
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for TxVersion {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        u.choose(&[TxVersion::V0, TxVersion::V1, TxVersion::V2]).copied()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
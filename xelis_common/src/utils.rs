@@ -5,7 +5,8 @@ use crate::{
         FEE_PER_ACCOUNT_CREATION,
         FEE_PER_KB,
         FEE_PER_TRANSFER,
-        BYTES_PER_KB
+        BYTES_PER_KB,
+        TX_REPLACEMENT_MIN_FEE_INCREASE_PERCENT
     },
     difficulty::Difficulty,
     varuint::VarUint
@@ -21,8 +22,17 @@ macro_rules! async_handler {
 }
 
 // Format any coin value using the requested decimals count
+// Integer arithmetic is used to not lose precision on high values
 pub fn format_coin(value: u64, decimals: u8) -> String {
-    format!("{:.1$}", value as f64 / 10usize.pow(decimals as u32) as f64, decimals as usize)
+    if decimals == 0 {
+        return value.to_string()
+    }
+
+    match 10u64.checked_pow(decimals as u32) {
+        Some(unit) => format!("{}.{:0width$}", value / unit, value % unit, width = decimals as usize),
+        // Any value is lower than one coin
+        None => format!("0.{:0width$}", value, width = decimals as usize)
+    }
 }
 
 // Format value using XELIS decimals
@@ -36,14 +46,31 @@ pub fn from_xelis(value: impl Into<String>) -> Option<u64> {
 }
 
 // Convert a coin amount from string to a u64 based on the provided decimals
+// Returns None if the amount has more decimals than supported or overflows
 pub fn from_coin(value: impl Into<String>, coin_decimals: u8) -> Option<u64> {
     let value = value.into();
     let mut split = value.trim().split('.');
-    let value: u64 = split.next()?.parse::<u64>().ok()?;
-    let right_part = split.next().unwrap_or("0");
-    let decimals: String = right_part.chars().chain(std::iter::repeat('0')).take(coin_decimals as usize).collect();
-    let decimals_value = decimals.parse::<u64>().ok()?;
-    Some(value * 10u64.pow(coin_decimals as u32) + decimals_value)
+    let left_part = split.next()?;
+    // Trailing zeros don't change the amount
+    let right_part = split.next().unwrap_or("").trim_end_matches('0');
+    if split.next().is_some() || right_part.len() > coin_decimals as usize {
+        return None
+    }
+
+    if left_part.is_empty() || !left_part.chars().chain(right_part.chars()).all(|c| c.is_ascii_digit()) {
+        return None
+    }
+
+    let value: u64 = left_part.parse().ok()?;
+    let unit = 10u64.checked_pow(coin_decimals as u32)?;
+    let decimals_value = if right_part.is_empty() {
+        0
+    } else {
+        let decimals: String = right_part.chars().chain(std::iter::repeat('0')).take(coin_decimals as usize).collect();
+        decimals.parse::<u64>().ok()?
+    };
+
+    value.checked_mul(unit)?.checked_add(decimals_value)
 }
 
 // return the fee for a transaction based on its size in bytes
@@ -65,6 +92,17 @@ pub fn calculate_tx_fee(tx_size: usize, output_count: usize, new_addresses: usiz
     + multisig as u64 * FEE_PER_TRANSFER
 }
 
+// Fee rate per kB of a TX, used to compare TXs of different sizes
+pub fn calculate_fee_rate_per_kb(fee: u64, size: usize) -> u64 {
+    fee.saturating_mul(BYTES_PER_KB as u64) / (size as u64).max(1)
+}
+
+// Minimum fee required to replace a pending transaction paying `fee`
+pub fn calculate_replacement_min_fee(fee: u64) -> u64 {
+    let increase = fee.saturating_mul(TX_REPLACEMENT_MIN_FEE_INCREASE_PERCENT) / 100;
+    fee.saturating_add(increase.max(1))
+}
+
 const HASHRATE_FORMATS: [&str; 7] = ["H/s", "KH/s", "MH/s", "GH/s", "TH/s", "PH/s", "EH/s"];
 
 // Format a hashrate in human-readable format
@@ -144,6 +182,13 @@ mod tests {
         assert_eq!(format_xelis(1), "0.00000001");
     }
 
+    #[test]
+    fn test_replacement_min_fee() {
+        assert_eq!(calculate_replacement_min_fee(0), 1);
+        assert_eq!(calculate_replacement_min_fee(5), 6);
+        assert_eq!(calculate_replacement_min_fee(FEE_PER_KB), 11000);
+    }
+
     #[test]
     fn test_difficulty_format_zero() {
         let value = Difficulty::zero();
@@ -173,4 +218,25 @@ mod tests {
         let value = from_xelis("100.123");
         assert_eq!(value, Some(100_123_00000));
     }
+
+    #[test]
+    fn test_format_coin_decimals() {
+        assert_eq!(format_coin(5, 0), "5");
+        assert_eq!(format_coin(1_50, 2), "1.50");
+        assert_eq!(format_coin(u64::MAX, 8), "184467440737.09551615");
+        assert_eq!(format_coin(1, 20), "0.00000000000000000001");
+    }
+
+    #[test]
+    fn test_from_coin_decimals() {
+        assert_eq!(from_coin("1.5", 2), Some(1_50));
+        assert_eq!(from_coin("1.500", 2), Some(1_50));
+        assert_eq!(from_coin("7", 0), Some(7));
+        assert_eq!(from_coin("1.555", 2), None);
+        assert_eq!(from_coin("1.5", 0), None);
+        assert_eq!(from_coin("1.5.5", 2), None);
+        assert_eq!(from_coin("-1", 2), None);
+        assert_eq!(from_coin("184467440737.09551616", 8), None);
+        assert_eq!(from_coin(format_coin(u64::MAX, 8), 8), Some(u64::MAX));
+    }
 }
\ No newline at end of file
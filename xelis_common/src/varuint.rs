@@ -47,6 +47,31 @@ impl VarUint {
     pub const fn one() -> Self {
         Self(U256::one())
     }
+
+    // Checked addition, returns None on overflow
+    pub fn checked_add(&self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    // Checked subtraction, returns None on underflow
+    pub fn checked_sub(&self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    // Checked multiplication, returns None on overflow
+    pub fn checked_mul(&self, other: Self) -> Option<Self> {
+        self.0.checked_mul(other.0).map(Self)
+    }
+
+    // Addition capped to the maximum value
+    pub fn saturating_add(&self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0))
+    }
+
+    // Subtraction capped to zero
+    pub fn saturating_sub(&self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
 }
 
 impl Serializer for VarUint {
@@ -148,9 +173,10 @@ impl Display for VarUint {
     }
 }
 
+// Additions are checked, an overflow is never wrapped around
 impl AddAssign for VarUint {
     fn add_assign(&mut self, other: Self) {
-        self.0 += other.0;
+        *self = self.checked_add(other).expect("VarUint addition overflow");
     }
 }
 
@@ -198,7 +224,7 @@ impl Add for VarUint {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0)
+        self.checked_add(rhs).expect("VarUint addition overflow")
     }
 }
 
@@ -306,4 +332,28 @@ mod tests {
         assert_eq!(difficulty, difficulty2);
         assert_eq!(difficulty.to_string(), "71135336520");
     }
+
+    #[test]
+    fn test_checked_operations() {
+        let max = VarUint::new(U256::max_value());
+        assert_eq!(max.checked_add(VarUint::one()), None);
+        assert_eq!(max.saturating_add(VarUint::one()), max);
+        assert_eq!(VarUint::zero().checked_sub(VarUint::one()), None);
+        assert_eq!(VarUint::zero().saturating_sub(VarUint::one()), VarUint::zero());
+        assert_eq!(max.checked_mul(VarUint::from_u64(2)), None);
+        assert_eq!(VarUint::from_u64(2).checked_add(VarUint::from_u64(3)), Some(VarUint::from_u64(5)));
+    }
+
+    #[test]
+    #[should_panic(expected = "VarUint addition overflow")]
+    fn test_add_overflow() {
+        let _ = VarUint::new(U256::max_value()) + VarUint::one();
+    }
+
+    #[test]
+    #[should_panic(expected = "VarUint addition overflow")]
+    fn test_add_assign_overflow() {
+        let mut value = VarUint::new(U256::max_value());
+        value += VarUint::one();
+    }
 }
\ No newline at end of file
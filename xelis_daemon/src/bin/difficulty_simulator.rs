@@ -0,0 +1,207 @@
+use std::{
+    fs,
+    path::PathBuf
+};
+use anyhow::{Context, Result};
+use clap::Parser;
+use xelis_common::{
+    difficulty::Difficulty,
+    network::Network,
+    time::TimestampMillis,
+    utils::format_difficulty,
+    varuint::VarUint
+};
+use xelis_daemon::{
+    config::{get_minimum_difficulty, BLOCK_TIME_MILLIS, MILLIS_PER_SECOND},
+    core::difficulty::{
+        kalman_filter,
+        DifficultyAlgorithm,
+        KalmanV1,
+        KalmanV2
+    }
+};
+
+// Replay historical block timestamps against difficulty algorithms
+// Input file contains one block per line: `timestamp` or `timestamp,difficulty`
+// Timestamps are in milliseconds, lines starting with # are ignored
+// When the recorded difficulty is provided, the hashrate of each block is estimated
+// and the solve times are simulated as if the chain was using the tested algorithm
+// Otherwise, the historical solve times are replayed as is
+#[derive(Parser)]
+#[clap(version, about = "Difficulty algorithms simulator")]
+pub struct Config {
+    /// File containing the historical blocks to replay
+    input: PathBuf,
+    /// Algorithms to test: kalman-v1, kalman-v2, tuned
+    #[clap(long, value_delimiter = ',', default_value = "kalman-v1,kalman-v2,tuned")]
+    algorithms: Vec<String>,
+    /// Network used for the minimum difficulty
+    #[clap(long, value_enum, default_value_t = Network::Mainnet)]
+    network: Network,
+    /// Shift used by the tuned Kalman filter
+    #[clap(long, default_value_t = 20, value_parser = clap::value_parser!(u64).range(1..64))]
+    tuned_shift: u64,
+    /// Process noise covariance of the tuned Kalman filter, in per mille of the shift
+    #[clap(long, default_value_t = 20)]
+    tuned_noise_per_mille: u64
+}
+
+// Kalman filter using the v2 scaling with configurable parameters
+// Used to find better parameters before proposing them
+struct TunedKalman {
+    shift: u64,
+    left_shift: VarUint,
+    process_noise_covar: VarUint
+}
+
+impl TunedKalman {
+    fn new(shift: u64, noise_per_mille: u64) -> Self {
+        let left_shift = VarUint::from_u64(1 << shift);
+        Self {
+            shift,
+            left_shift,
+            process_noise_covar: left_shift * noise_per_mille / 1000
+        }
+    }
+}
+
+impl DifficultyAlgorithm for TunedKalman {
+    fn name(&self) -> &'static str {
+        "tuned"
+    }
+
+    fn initial_covariance(&self) -> VarUint {
+        self.left_shift
+    }
+
+    fn calculate_difficulty(&self, solve_time: TimestampMillis, previous_difficulty: Difficulty, p: VarUint, minimum_difficulty: Difficulty) -> (Difficulty, VarUint) {
+        let z = previous_difficulty * MILLIS_PER_SECOND / solve_time;
+        let (x_est_new, p_new) = kalman_filter(z, previous_difficulty * MILLIS_PER_SECOND / BLOCK_TIME_MILLIS, p, self.shift, self.left_shift, self.process_noise_covar);
+
+        let difficulty = x_est_new * BLOCK_TIME_MILLIS / MILLIS_PER_SECOND;
+        if difficulty < minimum_difficulty {
+            return (minimum_difficulty, self.initial_covariance());
+        }
+
+        (difficulty, p_new)
+    }
+}
+
+// Historical block
+struct Record {
+    timestamp: TimestampMillis,
+    difficulty: Option<Difficulty>
+}
+
+// Result of a simulation
+struct Report {
+    blocks: u64,
+    average_solve_time: f64,
+    deviation_solve_time: f64,
+    slow_blocks: u64,
+    min_difficulty: Difficulty,
+    max_difficulty: Difficulty,
+    final_difficulty: Difficulty
+}
+
+fn parse_records(content: &str) -> Result<Vec<Record>> {
+    let mut records = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.split(',').map(str::trim);
+        let timestamp = parts.next()
+            .unwrap_or_default()
+            .parse()
+            .with_context(|| format!("Invalid timestamp at line {}", i + 1))?;
+
+        let difficulty = match parts.next() {
+            Some(value) => Some(VarUint::from_u128(value.parse().with_context(|| format!("Invalid difficulty at line {}", i + 1))?)),
+            None => None
+        };
+
+        records.push(Record { timestamp, difficulty });
+    }
+
+    if records.len() < 2 {
+        anyhow::bail!("At least two blocks are required");
+    }
+
+    Ok(records)
+}
+
+fn simulate(algorithm: &dyn DifficultyAlgorithm, records: &[Record], minimum_difficulty: Difficulty) -> Report {
+    let mut difficulty = records[0].difficulty.unwrap_or(minimum_difficulty);
+    let mut p = algorithm.initial_covariance();
+
+    let mut min_difficulty = difficulty;
+    let mut max_difficulty = difficulty;
+    let mut solve_times = Vec::with_capacity(records.len() - 1);
+    for window in records.windows(2) {
+        let historical_solve_time = window[1].timestamp.saturating_sub(window[0].timestamp).max(1);
+        // Estimate the time it would have taken to find the block with our difficulty
+        // based on the hashrate of that time
+        let solve_time = match window[0].difficulty {
+            Some(recorded) if recorded > VarUint::zero() => {
+                let simulated = difficulty * historical_solve_time / recorded;
+                simulated.as_ref().low_u64().max(1)
+            },
+            _ => historical_solve_time
+        };
+
+        solve_times.push(solve_time);
+        (difficulty, p) = algorithm.calculate_difficulty(solve_time, difficulty, p, minimum_difficulty);
+        min_difficulty = min_difficulty.min(difficulty);
+        max_difficulty = max_difficulty.max(difficulty);
+    }
+
+    let blocks = solve_times.len() as u64;
+    let average_solve_time = solve_times.iter().sum::<u64>() as f64 / blocks as f64;
+    let variance = solve_times.iter()
+        .map(|t| (*t as f64 - average_solve_time).powi(2))
+        .sum::<f64>() / blocks as f64;
+    let slow_blocks = solve_times.iter()
+        .filter(|t| **t > BLOCK_TIME_MILLIS * 2)
+        .count() as u64;
+
+    Report {
+        blocks,
+        average_solve_time,
+        deviation_solve_time: variance.sqrt(),
+        slow_blocks,
+        min_difficulty,
+        max_difficulty,
+        final_difficulty: difficulty
+    }
+}
+
+fn main() -> Result<()> {
+    let config = Config::parse();
+    let content = fs::read_to_string(&config.input)
+        .with_context(|| format!("Error while reading {}", config.input.display()))?;
+    let records = parse_records(&content)?;
+
+    let minimum_difficulty = get_minimum_difficulty(&config.network);
+
+    let tuned = TunedKalman::new(config.tuned_shift, config.tuned_noise_per_mille);
+    let available: [&dyn DifficultyAlgorithm; 3] = [&KalmanV1, &KalmanV2, &tuned];
+
+    println!("Replaying {} blocks (target block time: {} ms)", records.len(), BLOCK_TIME_MILLIS);
+    for name in config.algorithms.iter() {
+        let algorithm = available.iter()
+            .find(|a| a.name() == name.as_str())
+            .with_context(|| format!("Unknown algorithm {}", name))?;
+
+        let report = simulate(*algorithm, &records, minimum_difficulty);
+        println!("{}:", algorithm.name());
+        println!("  blocks: {}", report.blocks);
+        println!("  average solve time: {:.0} ms (deviation: {:.0} ms)", report.average_solve_time, report.deviation_solve_time);
+        println!("  blocks slower than 2x target: {} ({:.2}%)", report.slow_blocks, report.slow_blocks as f64 * 100.0 / report.blocks as f64);
+        println!("  difficulty min: {}, max: {}, final: {}", format_difficulty(report.min_difficulty), format_difficulty(report.max_difficulty), format_difficulty(report.final_difficulty));
+    }
+
+    Ok(())
+}
@@ -1,8 +1,9 @@
 use lazy_static::lazy_static;
 use xelis_common::{
-    api::daemon::{DevFeeThreshold, HardFork},
+    api::daemon::HardFork,
     block::BlockVersion,
-    config::MAX_BLOCK_SIZE,
+    config::MAX_DYNAMIC_BLOCK_SIZE,
+    contract::ContractCapability,
     crypto::{
         Address,
         Hash,
@@ -21,14 +22,42 @@ pub const NETWORK_ID: [u8; NETWORK_ID_SIZE] = [0x73, 0x6c, 0x69, 0x78, 0x65, 0x5
 pub const DEFAULT_P2P_BIND_ADDRESS: &str = "0.0.0.0:2125";
 pub const DEFAULT_RPC_BIND_ADDRESS: &str = "0.0.0.0:8080";
 
+// Default maximum blocks behind the network to be considered as ready
+// This is used by the `/ready` endpoint of the RPC server
+pub const DEFAULT_RPC_READY_MAX_BLOCKS_BEHIND: u64 = STABLE_LIMIT;
+
+// Default maximum bytes of blocks serialized per tick by the `/blocks_range` endpoint
+pub const DEFAULT_RPC_STREAM_BUDGET: usize = 1024 * 1024;
+// Delay in ms between two ticks of the `/blocks_range` endpoint
+pub const RPC_STREAM_TICK_INTERVAL_MILLIS: u64 = 50;
+
+// Default minimum delay in ms between two GetWork jobs sent to a miner on mempool changes
+pub const DEFAULT_GETWORK_RATE_LIMIT_MS: u64 = 500;
+// Default expected time in seconds between two shares of a GetWork miner
+pub const DEFAULT_GETWORK_SHARE_TARGET_TIME: u64 = 10;
+
+// Default maximum notifications queued per publisher subscriber
+// A subscriber lagging behind loses the oldest ones
+pub const DEFAULT_PUBLISHER_MAX_QUEUED_MESSAGES: usize = 4096;
+
 // Default cache size for storage DB
 pub const DEFAULT_CACHE_SIZE: usize = 1024;
 
+// Default maximum offset in ms of the local clock before warning
+// Above the future limit, our blocks are rejected by synced peers
+pub const DEFAULT_CLOCK_SKEW_THRESHOLD_MS: u64 = TIMESTAMP_IN_FUTURE_LIMIT;
+// Minimum peers samples before considering the offset reported by peers
+pub const CLOCK_SKEW_MIN_PEER_SAMPLES: usize = 3;
+// Maximum peers samples kept, oldest ones are removed first
+pub const CLOCK_SKEW_MAX_PEER_SAMPLES: usize = 64;
+// Interval in seconds between two NTP checks
+pub const NTP_CHECK_INTERVAL: u64 = 30 * 60;
+// Timeout in ms of a NTP request
+pub const NTP_REQUEST_TIMEOUT_MS: u64 = 5000;
+
 // Block rules
-// Millis per second, it is used to prevent having random 1000 values anywhere
-pub const MILLIS_PER_SECOND: u64 = 1000;
-// Block Time in milliseconds
-pub const BLOCK_TIME_MILLIS: u64 = 15 * MILLIS_PER_SECOND; // 15s block time
+// Block time is shared with the emission curve
+pub use xelis_common::config::{BLOCK_TIME_MILLIS, MILLIS_PER_SECOND};
 // Minimum difficulty (each difficulty point is in H/s)
 // Current: BLOCK TIME in millis * 20 = 20 KH/s minimum
 // This is to prevent spamming the network with low difficulty blocks
@@ -40,6 +69,9 @@ pub const OTHER_MINIMUM_DIFFICULTY: Difficulty = Difficulty::from_u64(BLOCK_TIME
 pub const GENESIS_BLOCK_DIFFICULTY: Difficulty = Difficulty::from_u64(1);
 // 2 seconds maximum in future (prevent any attack on reducing difficulty but keep margin for unsynced devices)
 pub const TIMESTAMP_IN_FUTURE_LIMIT: TimestampSeconds = 2 * 1000;
+// Number of ancestors used to compute the median time past of a block
+// A block timestamp can't be below it
+pub const MEDIAN_TIME_PAST_WINDOW: usize = 11;
 
 // keep at least last N blocks until top topoheight when pruning the chain
 // WARNING: This must be at least 50 blocks for difficulty adjustement
@@ -48,45 +80,15 @@ pub const PRUNE_SAFETY_LIMIT: u64 = STABLE_LIMIT * 10;
 // BlockDAG rules
 pub const STABLE_LIMIT: u64 = 8; // in how many height we consider the block stable
 
-// Emission rules
-// 15% (6 months), 10% (6 months), 5% per block going to dev address
-// NOTE: The explained emission above was the expected one
-// But due to a bug in the function to calculate the dev fee reward,
-// the actual emission was directly set to 10% per block
-// New emission rules are: 10% during 1.5 years, then 5% for the rest
-// This is the same for the project but reduce a bit the mining cost as they earn 5% more
-pub const DEV_FEES: [DevFeeThreshold; 2] = [
-    // Activated for 3M blocks
-    DevFeeThreshold {
-        height: 0,
-        fee_percentage: 10
-    },
-    // Activated for the rest
-    DevFeeThreshold {
-        // after ~1.5 year it's reduced to 5%
-        // 3 250 000 blocks * 15s of block time / 60s / 60m / 24h / 365d = 1.5 years
-        height: 3_250_000, 
-        fee_percentage: 5
-    }
-];
-// only 30% of reward for side block
-// This is to prevent spamming side blocks
-// and also give rewards for miners with valid work on main chain
-pub const SIDE_BLOCK_REWARD_PERCENT: u64 = 30;
-// maximum 3 blocks for side block reward
-// Each side block reward will be divided by the number of side blocks * 2
-// With a configuration of 3 blocks, we have the following percents:
-// 1 block: 30%
-// 2 blocks: 15%
-// 3 blocks: 7%
-// 4 blocks: minimum percentage set below
-pub const SIDE_BLOCK_REWARD_MAX_BLOCKS: u64 = 3;
-// minimum 5% of block reward for side block
-// This is the minimum given for all others valid side blocks
-pub const SIDE_BLOCK_REWARD_MIN_PERCENT: u64 = 5;
-// Emission speed factor for the emission curve
-// It is used to calculate based on the supply the block reward
-pub const EMISSION_SPEED_FACTOR: u64 = 20;
+// Number of blocks of the best chain used for the median block size
+// It must stay below PRUNE_SAFETY_LIMIT so pruned and fast synced nodes can compute it
+pub const BLOCK_SIZE_MEDIAN_WINDOW: usize = 40;
+// The block size limit is the median block size increased by this percentage
+// It can't go below MAX_BLOCK_SIZE or above MAX_DYNAMIC_BLOCK_SIZE
+pub const BLOCK_SIZE_MEDIAN_GROWTH_PERCENT: usize = 50;
+
+// Emission rules are defined in xelis_common::emission
+// so they can be reused by explorers and wallets
 
 // Developer address for paying dev fees until Smart Contracts integration
 // (testnet/mainnet format is converted lazily later)
@@ -119,6 +121,8 @@ pub const P2P_PING_PEER_LIST_DELAY: u64 = 60 * 5;
 pub const P2P_PING_PEER_LIST_LIMIT: usize = 16;
 // default number of maximum peers
 pub const P2P_DEFAULT_MAX_PEERS: usize = 32;
+// default number of peers cross-checking the state chunks received during a fast sync
+pub const P2P_DEFAULT_FAST_SYNC_VERIFICATION_PEERS: usize = 2;
 // time in seconds between each time we try to connect to a new peer
 pub const P2P_EXTEND_PEERLIST_DELAY: u64 = 60;
 // time in seconds between each time we try to connect to a outgoing peer
@@ -136,6 +140,16 @@ pub const P2P_HEARTBEAT_INTERVAL: u64 = P2P_PING_DELAY / 2;
 // Timeout in seconds
 // If we didn't receive any packet from a peer during this time, we disconnect it
 pub const P2P_PING_TIMEOUT: u64 = P2P_PING_DELAY * 6;
+// maximum outgoing connections, including the anchors
+// at most half of the max peers is used for them
+pub const P2P_MAX_OUTBOUND_SLOTS: usize = 8;
+// outgoing connections kept across restarts
+pub const P2P_ANCHOR_SLOTS: usize = 2;
+// time in seconds between each feeler connection
+// used to verify that a stored peer is still reachable
+pub const P2P_FEELER_DELAY: u64 = 60 * 2;
+// longest connected inbound peers protected from eviction
+pub const P2P_EVICTION_PROTECTED_PEERS: usize = 4;
 
 // Peer rules
 // number of seconds to reset the counter
@@ -163,7 +177,8 @@ pub const PEER_TIMEOUT_INIT_OUTGOING_CONNECTION: u64 = 30_000;
 // millis until we timeout during a handshake
 pub const PEER_TIMEOUT_DISCONNECT: u64 = 1_500;
 // 16 additional bytes are for AEAD from ChaCha20Poly1305
-pub const PEER_MAX_PACKET_SIZE: u32 = MAX_BLOCK_SIZE as u32 + 16;
+// A block can't be bigger than the hard cap of the dynamic block size
+pub const PEER_MAX_PACKET_SIZE: u32 = MAX_DYNAMIC_BLOCK_SIZE as u32 + 16;
 // Peer TX cache size
 // This is how many elements are stored in the LRU cache at maximum
 pub const PEER_TX_CACHE_SIZE: usize = 10240;
@@ -200,7 +215,7 @@ const HARD_FORKS: [HardFork; 3] = [
 ];
 
 // Testnet / Devnet hard forks
-const TESTNET_HARD_FORKS: [HardFork; 4] = [
+const TESTNET_HARD_FORKS: [HardFork; 5] = [
     HardFork {
         height: 0,
         version: BlockVersion::V0,
@@ -224,9 +239,33 @@ const TESTNET_HARD_FORKS: [HardFork; 4] = [
         version: BlockVersion::V3,
         changelog: "Smart Contracts",
         version_requirement: Some(">=1.16.0")
+    },
+    HardFork {
+        // V3 is already active, the new consensus rules need their own fork
+        height: 2_500_000,
+        version: BlockVersion::V4,
+        changelog: "Dynamic block size, median time past",
+        version_requirement: Some(">=1.16.0")
     }
 ];
 
+// Contract capabilities allowed on mainnet with the block version enabling them
+const CONTRACT_CAPABILITIES: [(ContractCapability, BlockVersion); 3] = [
+    (ContractCapability::Transfer, BlockVersion::V3),
+    (ContractCapability::Burn, BlockVersion::V3),
+    (ContractCapability::Events, BlockVersion::V3)
+];
+
+// Testnet / Devnet can trial the risky capabilities before mainnet
+const TESTNET_CONTRACT_CAPABILITIES: [(ContractCapability, BlockVersion); 6] = [
+    (ContractCapability::Transfer, BlockVersion::V3),
+    (ContractCapability::Burn, BlockVersion::V3),
+    (ContractCapability::Events, BlockVersion::V3),
+    (ContractCapability::AssetCreation, BlockVersion::V3),
+    (ContractCapability::CrossContractCall, BlockVersion::V3),
+    (ContractCapability::OnBlockHook, BlockVersion::V3)
+];
+
 // Mainnet seed nodes
 const MAINNET_SEED_NODES: [&str; 7] = [
     // France
@@ -322,4 +361,12 @@ pub const fn get_hard_forks(network: &Network) -> &[HardFork] {
         Network::Mainnet => &HARD_FORKS,
         _ => &TESTNET_HARD_FORKS,
     }
+}
+
+// Get the contract capabilities allow-list based on the network
+pub const fn get_contract_capabilities(network: &Network) -> &[(ContractCapability, BlockVersion)] {
+    match network {
+        Network::Mainnet => &CONTRACT_CAPABILITIES,
+        _ => &TESTNET_CONTRACT_CAPABILITIES,
+    }
 }
\ No newline at end of file
@@ -15,11 +15,22 @@ use xelis_common::{
             TransactionResponse,
             NewContractEvent,
             InvokeContractEvent,
+            MempoolTransactionEvent,
+            MempoolTransactionStatus,
+            ContractBalanceChange,
+            ContractStorageChange,
+            SimulateContractInvokeResult,
+            MultisigChangedEvent,
+            MultisigChangeKind,
+            MultisigState,
+            AccountActivityEvent,
         },
         RPCContractOutput,
         RPCTransaction
     },
+    account::VersionedBalance,
     asset::AssetData,
+    emission::{get_block_dev_fee, get_block_reward, side_block_reward_percentage},
     block::{
         Block,
         BlockHeader,
@@ -29,14 +40,15 @@ use xelis_common::{
         get_combined_hash_for_tips
     },
     config::{
+        BYTES_PER_KB,
         COIN_DECIMALS,
         MAXIMUM_SUPPLY,
-        MAX_TRANSACTION_SIZE,
         MAX_BLOCK_SIZE,
         TIPS_LIMIT,
         XELIS_ASSET
     },
     crypto::{
+        Address,
         Hash,
         Hashable,
         PublicKey,
@@ -44,11 +56,13 @@ use xelis_common::{
     },
     difficulty::{
         check_difficulty,
+        checked_cumulative_difficulty,
         CumulativeDifficulty,
         Difficulty
     },
     immutable::Immutable,
     network::Network,
+    rpc_server::websocket::WebSocketServerConfig,
     serializer::Serializer,
     time::{
         get_current_time_in_millis,
@@ -60,19 +74,17 @@ use xelis_common::{
         Transaction,
         TransactionType
     },
-    utils::{calculate_tx_fee, format_xelis},
+    utils::{calculate_replacement_min_fee, calculate_tx_fee, format_xelis},
     tokio::spawn_task,
     varuint::VarUint,
-    contract::build_environment,
+    contract::{build_environment, ContractOutput},
 };
 use xelis_vm::Environment;
 use crate::{
     config::{
         get_genesis_block_hash, get_hex_genesis_block, get_minimum_difficulty, get_difficulty_at_hard_fork,
-        BLOCK_TIME_MILLIS, CHAIN_SYNC_RESPONSE_MAX_BLOCKS, CHAIN_SYNC_RESPONSE_MIN_BLOCKS,
-        DEV_FEES, DEV_PUBLIC_KEY, EMISSION_SPEED_FACTOR, GENESIS_BLOCK_DIFFICULTY,
-        MILLIS_PER_SECOND, SIDE_BLOCK_REWARD_MAX_BLOCKS, PRUNE_SAFETY_LIMIT,
-        SIDE_BLOCK_REWARD_PERCENT, SIDE_BLOCK_REWARD_MIN_PERCENT, STABLE_LIMIT, TIMESTAMP_IN_FUTURE_LIMIT,
+        BLOCK_SIZE_MEDIAN_GROWTH_PERCENT, BLOCK_SIZE_MEDIAN_WINDOW, BLOCK_TIME_MILLIS, CHAIN_SYNC_RESPONSE_MAX_BLOCKS, CHAIN_SYNC_RESPONSE_MIN_BLOCKS,
+        DEV_PUBLIC_KEY, GENESIS_BLOCK_DIFFICULTY, PRUNE_SAFETY_LIMIT, STABLE_LIMIT,
     },
     core::{
         config::Config,
@@ -81,18 +93,26 @@ use crate::{
         error::BlockchainError,
         mempool::Mempool,
         nonce_checker::NonceChecker,
+        clock::ClockMonitor,
+        diagnostics::{InstrumentedRwLock, RuntimeMonitor},
+        runtime_params::{apply_runtime_params, get_runtime_params_path, RuntimeParamsStore},
+        mempool_policy::MempoolPolicy,
+        mempool_spill::{get_mempool_spill_path, MempoolSpill},
         simulator::Simulator,
         storage::{DagOrderProvider, DifficultyProvider, Storage},
         tx_selector::{TxSelector, TxSelectorEntry},
         state::{ChainState, ApplicableChainState},
         hard_fork::*
     },
-    p2p::P2pServer,
+    p2p::{packet::commitment::ChainCommitment, P2pServer},
     rpc::{
         rpc::{
             get_block_type_for_block,
             get_block_response
         },
+        getwork_server::GetWorkConfig,
+        stratum_server::StratumServer,
+        publisher::Publisher,
         DaemonRpcServer,
         SharedDaemonRpcServer
     }
@@ -113,12 +133,13 @@ use std::{
     },
     time::Instant
 };
-use tokio::{sync::{Mutex, RwLock}, net::lookup_host};
+use tokio::{sync::{Mutex, RwLock}, net::lookup_host, runtime::Handle};
 use log::{info, error, debug, warn, trace};
 use rand::Rng;
 
 use super::storage::{
     AccountProvider,
+    BlockProvider,
     BlocksAtHeightProvider,
     ClientProtocolProvider,
     PrunedTopoheightProvider,
@@ -135,9 +156,9 @@ pub struct Blockchain<S: Storage> {
     // It is used mostly for chain rewind limit
     stable_topoheight: AtomicU64,
     // mempool to retrieve/add all txs
-    mempool: RwLock<Mempool>,
+    mempool: InstrumentedRwLock<Mempool>,
     // storage to retrieve/add blocks
-    storage: RwLock<S>,
+    storage: InstrumentedRwLock<S>,
     // Contract environment stdlib
     environment: Environment,
     // P2p module
@@ -165,8 +186,26 @@ pub struct Blockchain<S: Storage> {
     tip_work_score_cache: Mutex<LruCache<(Hash, Hash, u64), (HashSet<Hash>, CumulativeDifficulty)>>,
     // using base hash, current tip hash and base height, this cache is used to store the DAG order
     full_order_cache: Mutex<LruCache<(Hash, Hash, u64), IndexSet<Hash>>>,
+    // size of a block with its TXs, used by the dynamic block size
+    block_size_cache: Mutex<LruCache<Hash, usize>>,
     // auto prune mode if enabled, will delete all blocks every N and keep only N top blocks (topoheight based)
-    auto_prune_keep_n_blocks: Option<u64>
+    auto_prune_keep_n_blocks: Option<u64>,
+    // keep the full history, pruning is refused
+    archive: bool,
+    // parameters updated at runtime through the RPC API
+    runtime_params: RuntimeParamsStore,
+    // detect if the local clock is skewed
+    clock: Arc<ClockMonitor>,
+    // raw notifications publisher if enabled
+    publisher: Option<Arc<Publisher>>,
+    // operator policy to admit TXs in mempool
+    mempool_policy: MempoolPolicy,
+    // evicted TXs kept on disk if enabled
+    mempool_spill: Option<Mutex<MempoolSpill>>,
+    // hard forks and features activation heights
+    fork_schedule: ForkSchedule,
+    // metrics of the tokio runtime running the node
+    runtime_monitor: RuntimeMonitor
 }
 
 impl<S: Storage> Blockchain<S> {
@@ -197,11 +236,38 @@ impl<S: Storage> Blockchain<S> {
                 return Err(BlockchainError::ConfigSyncMode.into())
             }
 
+            if config.archive {
+                if config.auto_prune_keep_n_blocks.is_some() {
+                    error!("Auto prune mode can't be enabled in archive mode!");
+                    return Err(BlockchainError::ArchiveMode.into())
+                }
+
+                if config.p2p.allow_fast_sync {
+                    error!("Fast sync can't be enabled in archive mode!");
+                    return Err(BlockchainError::ArchiveMode.into())
+                }
+
+                if let Some(pruned_topoheight) = storage.get_pruned_topoheight().await? {
+                    error!("Archive mode requires the full chain, but it is pruned until topoheight {}", pruned_topoheight);
+                    return Err(BlockchainError::ArchiveMode.into())
+                }
+            }
+
             if config.skip_pow_verification {
                 warn!("PoW verification is disabled! This is dangerous in production!");
             }
         }
 
+        let fork_schedule = ForkSchedule::with_overrides(&network, &config.dev_fork_heights)?
+            .with_timestamp_overrides(&network, config.dev_median_time_past_window, config.dev_timestamp_future_limit)?;
+        if !config.dev_fork_heights.is_empty() {
+            warn!("Hard forks activation heights are overridden: {:?}", fork_schedule.get_hard_forks());
+        }
+
+        if config.dev_median_time_past_window.is_some() || config.dev_timestamp_future_limit.is_some() {
+            warn!("Block timestamp rules are overridden: {:?}", fork_schedule.get_timestamp_rules());
+        }
+
         let on_disk = storage.has_blocks().await;
         let (height, topoheight) = if on_disk {
             info!("Reading last metadata available...");
@@ -214,13 +280,31 @@ impl<S: Storage> Blockchain<S> {
         let environment = build_environment::<S>().build();
 
         info!("Initializing chain...");
+        let runtime_params = RuntimeParamsStore::new(get_runtime_params_path(config.dir_path.as_deref(), &network))?;
+        let publisher = match config.rpc.publisher_bind_address.as_ref() {
+            Some(bind_address) => match Publisher::start(bind_address, config.rpc.publisher_max_queued_messages).await {
+                Ok(publisher) => Some(publisher),
+                Err(e) => {
+                    error!("Error while starting Publisher: {}", e);
+                    None
+                }
+            },
+            None => None
+        };
+        let mempool_spill = if config.mempool_spill_size != 0 {
+            let path = get_mempool_spill_path(config.dir_path.as_deref(), &network);
+            Some(Mutex::new(MempoolSpill::new(path, config.mempool_spill_size)?))
+        } else {
+            None
+        };
+
         let blockchain = Self {
             height: AtomicU64::new(height),
             topoheight: AtomicU64::new(topoheight),
             stable_height: AtomicU64::new(0),
             stable_topoheight: AtomicU64::new(0),
-            mempool: RwLock::new(Mempool::new(network)),
-            storage: RwLock::new(storage),
+            mempool: InstrumentedRwLock::new("mempool", Mempool::with_max_size(network, (config.max_mempool_size != 0).then_some(config.max_mempool_size))),
+            storage: InstrumentedRwLock::new("storage", storage),
             environment,
             p2p: RwLock::new(None),
             rpc: RwLock::new(None),
@@ -232,8 +316,17 @@ impl<S: Storage> Blockchain<S> {
             tip_work_score_cache: Mutex::new(LruCache::new(NonZeroUsize::new(1024).unwrap())),
             common_base_cache: Mutex::new(LruCache::new(NonZeroUsize::new(1024).unwrap())),
             full_order_cache: Mutex::new(LruCache::new(NonZeroUsize::new(1024).unwrap())),
+            block_size_cache: Mutex::new(LruCache::new(NonZeroUsize::new(1024).unwrap())),
             auto_prune_keep_n_blocks: config.auto_prune_keep_n_blocks,
-            skip_block_template_txs_verification: config.skip_block_template_txs_verification
+            archive: config.archive,
+            runtime_params,
+            clock: Arc::new(ClockMonitor::new(config.clock_skew_threshold, config.refuse_mining_on_clock_skew, config.ntp_servers)),
+            publisher,
+            mempool_policy: MempoolPolicy::new(config.min_tx_fee_per_byte, config.max_tx_size, config.max_tx_extra_data_size, config.max_pending_txs_per_account, config.blocks_only),
+            mempool_spill,
+            skip_block_template_txs_verification: config.skip_block_template_txs_verification,
+            fork_schedule,
+            runtime_monitor: RuntimeMonitor::new(Handle::current())
         };
 
         // include genesis block
@@ -280,7 +373,7 @@ impl<S: Storage> Blockchain<S> {
                 }
             }
 
-            match P2pServer::new(config.p2p_concurrency_task_count_limit, dir_path, config.tag, config.max_peers, config.p2p_bind_address, Arc::clone(&arc), exclusive_nodes.is_empty(), exclusive_nodes, config.allow_fast_sync, config.allow_boost_sync, config.max_chain_response_size, !config.disable_ip_sharing, config.disable_p2p_outgoing_connections, config.p2p_private_key.map(|v| v.into()), config.p2p_on_dh_key_change) {
+            match P2pServer::new(config.p2p_concurrency_task_count_limit, dir_path, config.tag, config.max_peers, config.p2p_bind_address, Arc::clone(&arc), exclusive_nodes.is_empty(), exclusive_nodes, config.allow_fast_sync, config.fast_sync_verification_peers, config.allow_boost_sync, config.max_chain_response_size, !config.disable_ip_sharing, config.disable_p2p_outgoing_connections, config.p2p_private_key.map(|v| v.into()), config.p2p_on_dh_key_change) {
                 Ok(p2p) => {
                     // connect to priority nodes
                     for addr in config.priority_nodes {
@@ -313,10 +406,49 @@ impl<S: Storage> Blockchain<S> {
             };
         }
 
+        if arc.clock.has_ntp_servers() {
+            tokio::spawn(Arc::clone(&arc.clock).run_ntp_checks());
+        }
+
+        // apply the parameters updated at runtime during a previous run
+        let runtime_params = arc.runtime_params.get_persisted().await;
+        if let Err(e) = apply_runtime_params(&arc, &runtime_params).await {
+            error!("Error while applying persisted runtime params: {}", e);
+        }
+
         // create RPC Server
         if !config.rpc.disable_rpc_server {
             info!("RPC Server will listen on: {}", config.rpc.rpc_bind_address);
-            match DaemonRpcServer::new(config.rpc.rpc_bind_address, Arc::clone(&arc), config.rpc.disable_getwork_server, config.rpc.rpc_threads).await {
+            let ws_config = WebSocketServerConfig {
+                max_queued_messages: config.rpc.rpc_ws_max_queued_messages,
+                max_frame_size: config.rpc.rpc_ws_max_frame_size
+            };
+            // Stratum server is loaded on RPC server like the GetWork server
+            let stratum = match config.rpc.stratum_bind_address.as_ref() {
+                Some(bind_address) => {
+                    let server = Arc::new(StratumServer::new(Arc::clone(&arc), config.rpc.stratum_min_share_difficulty.map(Difficulty::from)));
+                    match server.start(bind_address).await {
+                        Ok(_) => Some(server),
+                        Err(e) => {
+                            error!("Error while starting Stratum server: {}", e);
+                            None
+                        }
+                    }
+                },
+                None => None
+            };
+
+            let getwork_config = if !config.rpc.disable_getwork_server {
+                Some(GetWorkConfig {
+                    rate_limit_ms: config.rpc.getwork_rate_limit_ms,
+                    min_share_difficulty: config.rpc.getwork_min_share_difficulty.map(Difficulty::from),
+                    share_target_time: config.rpc.getwork_share_target_time
+                })
+            } else {
+                None
+            };
+
+            match DaemonRpcServer::new(config.rpc.rpc_bind_address, Arc::clone(&arc), getwork_config, config.rpc.enable_rpc_log_methods, config.rpc.enable_rpc_admin_methods, config.rpc.rpc_threads, config.rpc.rpc_ready_max_blocks_behind, config.rpc.rpc_stream_budget, ws_config, stratum).await {
                 Ok(server) => *arc.rpc.write().await = Some(server),
                 Err(e) => error!("Error while starting RPC server: {}", e)
             };
@@ -339,6 +471,22 @@ impl<S: Storage> Blockchain<S> {
         self.simulator.is_some()
     }
 
+    // Is the full history of the chain kept
+    pub fn is_archive(&self) -> bool {
+        self.archive
+    }
+
+    // Policy applied to TXs before adding them in mempool
+    pub fn get_mempool_policy(&self) -> &MempoolPolicy {
+        &self.mempool_policy
+    }
+
+    // Hard forks schedule of the network
+    // Any consensus rule depending on the height must be checked through it
+    pub fn get_fork_schedule(&self) -> &ForkSchedule {
+        &self.fork_schedule
+    }
+
     // Skip PoW verification flag
     pub fn skip_pow_verification(&self) -> bool {
         self.skip_pow_verification
@@ -368,6 +516,10 @@ impl<S: Storage> Blockchain<S> {
             }
         }
 
+        if let Some(publisher) = self.publisher.as_ref() {
+            publisher.stop().await;
+        }
+
         {
             let mut storage = self.storage.write().await;
             if let Err(e) = storage.stop().await {
@@ -457,7 +609,7 @@ impl<S: Storage> Blockchain<S> {
         } else {
             warn!("No genesis block found!");
             info!("Generating a new genesis block...");
-            let header = BlockHeader::new(BlockVersion::V0, 0, get_current_time_in_millis(), IndexSet::new(), [0u8; EXTRA_NONCE_SIZE], DEV_PUBLIC_KEY.clone(), IndexSet::new());
+            let header = BlockHeader::new(self.fork_schedule.get_version_at_height(0), 0, get_current_time_in_millis(), IndexSet::new(), [0u8; EXTRA_NONCE_SIZE], DEV_PUBLIC_KEY.clone(), IndexSet::new());
             let block = Block::new(Immutable::Owned(header), Vec::new());
             let block_hash = block.hash();
             info!("Genesis generated: {} with {:?} {}", block.to_hex(), block_hash, block_hash);
@@ -516,6 +668,10 @@ impl<S: Storage> Blockchain<S> {
     // and then delete all blocks before it
     // keep a marge of PRUNE_SAFETY_LIMIT
     pub async fn prune_until_topoheight_for_storage(&self, topoheight: TopoHeight, storage: &mut S) -> Result<TopoHeight, BlockchainError> {
+        if self.archive {
+            return Err(BlockchainError::ArchiveMode)
+        }
+
         if topoheight == 0 {
             return Err(BlockchainError::PruneZero)
         }
@@ -1043,11 +1199,13 @@ impl<S: Storage> Blockchain<S> {
         map.insert(hash.clone(), provider.get_difficulty_for_block_hash(hash).await?.into());
 
         let mut set = HashSet::with_capacity(map.len());
-        let mut score = CumulativeDifficulty::zero();
+        let mut values = Vec::with_capacity(map.len());
         for (hash, value) in map {
             set.insert(hash);
-            score += value;
+            values.push(value);
         }
+        // A malicious chain could try to make the sum overflow
+        let score = checked_cumulative_difficulty(values)?;
 
         // save this result in cache
         cache.put((hash.clone(), base.clone(), base_height), (set.clone(), score));
@@ -1180,7 +1338,7 @@ impl<S: Storage> Blockchain<S> {
         let height = blockdag::calculate_height_at_tips(provider, tips.clone().into_iter()).await?;
 
         // Get the version at the current height
-        let (has_hard_fork, version) = has_hard_fork_at_height(self.get_network(), height);
+        let (has_hard_fork, version) = self.fork_schedule.has_hard_fork_at_height(height);
 
         if tips.len() == 0 { // Genesis difficulty
             return Ok((GENESIS_BLOCK_DIFFICULTY, difficulty::get_covariance_p(version)))
@@ -1211,6 +1369,58 @@ impl<S: Storage> Blockchain<S> {
         Ok((difficulty, p_new))
     }
 
+    // Get the size of a stored block with its TXs
+    async fn get_block_size_for_hash<P: BlockProvider>(&self, provider: &P, hash: &Hash) -> Result<usize, BlockchainError> {
+        // Don't keep the cache locked while reading the storage
+        if let Some(size) = self.block_size_cache.lock().await.get(hash).copied() {
+            return Ok(size)
+        }
+
+        let header = provider.get_block_header_by_hash(hash).await?;
+        let mut size = header.size();
+        for tx in header.get_txs_hashes() {
+            size += provider.get_transaction_size(tx).await?;
+        }
+
+        self.block_size_cache.lock().await.put(hash.clone(), size);
+        Ok(size)
+    }
+
+    // Maximum size of a block built on these tips
+    // Once the dynamic block size is enabled, it is based on the median size
+    // of the last blocks of the best chain (highest cumulative difficulty)
+    pub async fn get_max_block_size_at_tips<'a, P, I>(&self, provider: &P, tips: I, version: BlockVersion) -> Result<usize, BlockchainError>
+    where
+        P: BlockProvider,
+        I: Iterator<Item = &'a Hash> + ExactSizeIterator
+    {
+        if !Feature::DynamicBlockSize.is_enabled_in(version) {
+            return Ok(self.fork_schedule.get_max_block_size(version))
+        }
+
+        if tips.len() == 0 {
+            return Ok(MAX_BLOCK_SIZE)
+        }
+
+        let mut sizes = Vec::with_capacity(BLOCK_SIZE_MEDIAN_WINDOW);
+        let mut current = blockdag::find_best_tip_by_cumulative_difficulty(provider, tips).await?.clone();
+        loop {
+            sizes.push(self.get_block_size_for_hash(provider, &current).await?);
+            if sizes.len() >= BLOCK_SIZE_MEDIAN_WINDOW {
+                break;
+            }
+
+            let past_blocks = provider.get_past_blocks_for_block_hash(&current).await?;
+            if past_blocks.is_empty() {
+                break;
+            }
+
+            current = blockdag::find_best_tip_by_cumulative_difficulty(provider, past_blocks.iter()).await?.clone();
+        }
+
+        Ok(calculate_dynamic_block_size(&mut sizes, self.fork_schedule.get_max_block_size(version)))
+    }
+
     // Store the difficulty cache for the latest block
     async fn set_difficulty(&self, difficulty: Difficulty) {
         let mut lock = self.difficulty.lock().await;
@@ -1252,15 +1462,30 @@ impl<S: Storage> Blockchain<S> {
     }
 
     // Returns the storage used for blockchain
-    pub fn get_storage(&self) -> &RwLock<S> {
+    pub fn get_storage(&self) -> &InstrumentedRwLock<S> {
         &self.storage
     }
 
+    // Monitor of the local clock offset
+    pub fn get_clock(&self) -> &ClockMonitor {
+        &self.clock
+    }
+
+    // Parameters updated at runtime and persisted
+    pub fn get_runtime_params_store(&self) -> &RuntimeParamsStore {
+        &self.runtime_params
+    }
+
     // Returns the blockchain mempool used
-    pub fn get_mempool(&self) -> &RwLock<Mempool> {
+    pub fn get_mempool(&self) -> &InstrumentedRwLock<Mempool> {
         &self.mempool
     }
 
+    // Metrics of the tokio runtime running the node
+    pub fn get_runtime_monitor(&self) -> &RuntimeMonitor {
+        &self.runtime_monitor
+    }
+
     // Add a tx to the mempool, its hash will be computed
     pub async fn add_tx_to_mempool(&self, tx: Transaction, broadcast: bool) -> Result<(), BlockchainError> {
         let hash = tx.hash();
@@ -1277,9 +1502,16 @@ impl<S: Storage> Blockchain<S> {
     // and its validity (nonce, balance, etc...)
     pub async fn add_tx_to_mempool_with_storage_and_hash(&self, storage: &S, tx: Arc<Transaction>, hash: Hash, broadcast: bool) -> Result<(), BlockchainError>
     {
+        // Only the TXs to broadcast are loose ones,
+        // TXs of the blocks are still added to be executed
+        if broadcast && self.mempool_policy.is_blocks_only() {
+            return Err(BlockchainError::BlocksOnlyMode)
+        }
+
         let tx_size = tx.size();
-        if tx_size > MAX_TRANSACTION_SIZE {
-            return Err(BlockchainError::TxTooBig(tx_size, MAX_TRANSACTION_SIZE))
+        let max_tx_size = self.fork_schedule.get_max_transaction_size(self.fork_schedule.get_version_at_height(self.get_height()));
+        if tx_size > max_tx_size {
+            return Err(BlockchainError::TxTooBig(tx_size, max_tx_size))
         }
 
         {
@@ -1294,26 +1526,82 @@ impl<S: Storage> Blockchain<S> {
                 return Err(BlockchainError::TxAlreadyInBlockchain(hash))
             }
 
+            // a replaced TX doesn't increase the pending TXs of the sender
+            let pending_txs = mempool.get_cache_for(tx.get_source())
+                .filter(|cache| cache.has_tx_with_same_nonce(tx.get_nonce()).is_none())
+                .map_or(0, |cache| cache.get_txs().len());
+            self.mempool_policy.verify(&tx, tx_size, pending_txs)?;
+
             let stable_topoheight = self.get_stable_topoheight();
             let current_topoheight = self.get_topo_height();
+            // TX pending in mempool that will be replaced by this one
+            let mut replaced = None;
             // get the highest nonce available
             // if presents, it means we have at least one tx from this owner in mempool
             if let Some(cache) = mempool.get_cache_for(tx.get_source()) {
                 // we accept to delete a tx from mempool if the new one has a higher fee
-                if let Some(hash) = cache.has_tx_with_same_nonce(tx.get_nonce()) {
-                    // A TX with the same nonce is already in mempool
-                    return Err(BlockchainError::TxNonceAlreadyUsed(tx.get_nonce(), hash.as_ref().clone()))
-                }
+                if let Some(previous) = cache.has_tx_with_same_nonce(tx.get_nonce()) {
+                    let previous = previous.as_ref().clone();
+                    // Next TXs are built on the balances of the previous ones,
+                    // so only the single pending TX of an account can be replaced
+                    if cache.get_txs().len() != 1 {
+                        return Err(BlockchainError::TxReplacementNotAllowed(previous))
+                    }
+
+                    let min_fee = calculate_replacement_min_fee(mempool.get_sorted_tx(&previous)?.get_fee());
+                    if tx.get_fee() < min_fee {
+                        return Err(BlockchainError::TxReplacementFeeTooLow(previous, min_fee, tx.get_fee()))
+                    }
 
-                // check that the nonce is in the range
-                if !(tx.get_nonce() <= cache.get_max() + 1 && tx.get_nonce() >= cache.get_min()) {
+                    replaced = Some(previous);
+                } else if !(tx.get_nonce() <= cache.get_max() + 1 && tx.get_nonce() >= cache.get_min()) {
+                    // check that the nonce is in the range
                     debug!("TX {} nonce is not in the range of the pending TXs for this owner, received: {}, expected between {} and {}", hash, tx.get_nonce(), cache.get_min(), cache.get_max());
                     return Err(BlockchainError::InvalidTxNonceMempoolCache(tx.get_nonce(), cache.get_min(), cache.get_max()))
                 }
             }
 
-            let version = get_version_at_height(self.get_network(), self.get_height());
-            mempool.add_tx(storage, &self.environment, stable_topoheight, current_topoheight, hash.clone(), tx.clone(), tx_size, version).await?;
+            let version = self.fork_schedule.get_version_at_height(self.get_height());
+            let evicted = if let Some(previous) = replaced.as_ref() {
+                // Remove the previous TX first so the new one is verified against the account balances
+                let previous_size = mempool.get_sorted_tx(&previous)?.get_size();
+                let previous_tx = mempool.get_tx(&previous)?;
+                mempool.remove_tx(&previous)?;
+
+                let evicted = match mempool.add_tx(storage, &self.environment, stable_topoheight, current_topoheight, hash.clone(), tx.clone(), tx_size, version).await {
+                    Ok(evicted) => evicted,
+                    Err(e) => {
+                        debug!("TX {} can't replace TX {}: {}", hash, previous, e);
+                        // Restore the previous TX as it was valid
+                        if let Err(e) = mempool.add_tx(storage, &self.environment, stable_topoheight, current_topoheight, previous.clone(), previous_tx, previous_size, version).await {
+                            warn!("Error while restoring TX {} in mempool: {}", previous, e);
+                        }
+                        return Err(e)
+                    }
+                };
+
+                info!(tx_hash:% = hash; "TX {} has been replaced by TX {} with a higher fee", previous, hash);
+                self.notify_mempool_transaction(previous, &previous_tx, previous_size, MempoolTransactionStatus::Replaced).await;
+                evicted
+            } else {
+                mempool.add_tx(storage, &self.environment, stable_topoheight, current_topoheight, hash.clone(), tx.clone(), tx_size, version).await?
+            };
+
+            for (evicted_hash, sorted_tx) in evicted {
+                debug!("TX {} evicted from mempool for TX {}", evicted_hash, hash);
+                self.notify_mempool_transaction(&evicted_hash, sorted_tx.get_tx(), sorted_tx.get_size(), MempoolTransactionStatus::Removed).await;
+                if let Some(spill) = self.mempool_spill.as_ref() {
+                    if let Err(e) = spill.lock().await.spill(&evicted_hash, sorted_tx.get_tx(), sorted_tx.get_size()) {
+                        warn!("Error while spilling TX {} to disk: {}", evicted_hash, e);
+                    }
+                }
+            }
+        }
+
+        self.notify_mempool_transaction(&hash, &tx, tx_size, MempoolTransactionStatus::Added).await;
+
+        if let Some(publisher) = self.publisher.as_ref() {
+            publisher.publish_transaction(&hash, &tx);
         }
 
         if broadcast {
@@ -1321,8 +1609,9 @@ impl<S: Storage> Blockchain<S> {
             if let Some(p2p) = self.p2p.read().await.as_ref() {
                 let p2p = p2p.clone();
                 let hash = hash.clone();
+                let fee = tx.get_fee();
                 spawn_task("tx-notify-p2p", async move {
-                    p2p.broadcast_tx_hash(hash).await;
+                    p2p.broadcast_tx_hash(hash, fee, tx_size).await;
                 });
             }
 
@@ -1338,6 +1627,15 @@ impl<S: Storage> Blockchain<S> {
                     });
                 }
 
+                if let Some(stratum) = rpc.stratum_server() {
+                    let stratum = stratum.clone();
+                    spawn_task("tx-notify-stratum-job", async move {
+                        if let Err(e) = stratum.notify_new_job_rate_limited().await {
+                            debug!("Error while notifying Stratum miners for new tx: {}", e);
+                        }
+                    });
+                }
+
                 if rpc.is_event_tracked(&NotifyEvent::TransactionAddedInMempool).await {
                     let data = RPCTransaction::from_tx(&tx, &hash, storage.is_mainnet());
                     let data: TransactionResponse<'_> = TransactionResponse {
@@ -1345,6 +1643,7 @@ impl<S: Storage> Blockchain<S> {
                         executed_in_block: None,
                         in_mempool: true,
                         first_seen: Some(get_current_time_in_seconds()),
+                        contract_receipts: None,
                         data,
                     };
                     let json = json!(data);
@@ -1362,6 +1661,61 @@ impl<S: Storage> Blockchain<S> {
         Ok(())
     }
 
+    // Add back the spilled TXs with the highest fee rate fitting in the mempool
+    async fn reconsider_spilled_txs(&self, storage: &S) -> Result<(), BlockchainError> {
+        let Some(spill) = self.mempool_spill.as_ref() else {
+            return Ok(())
+        };
+
+        let (count, size) = self.mempool.read().await.get_available_room();
+        let txs = {
+            let mut spill = spill.lock().await;
+            if spill.count() == 0 {
+                return Ok(())
+            }
+            spill.take_best(count, size)?
+        };
+
+        for (hash, tx) in txs {
+            // Not broadcasted again, it was already relayed when received
+            if let Err(e) = self.add_tx_to_mempool_with_storage_and_hash(storage, tx, hash.clone(), false).await {
+                debug!("Spilled TX {} can't be added back to mempool: {}", hash, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Notify the subscribers of the mempool changes
+    // Only the subscriptions matching the TX source or destinations are notified
+    async fn notify_mempool_transaction(&self, hash: &Hash, tx: &Transaction, size: usize, status: MempoolTransactionStatus) {
+        let rpc = self.rpc.read().await;
+        let Some(rpc) = rpc.as_ref() else {
+            return;
+        };
+
+        let mut tracked = Vec::new();
+        for event in get_mempool_transaction_events(tx, self.network.is_mainnet()) {
+            if rpc.is_event_tracked(&event).await {
+                tracked.push(event);
+            }
+        }
+
+        if tracked.is_empty() {
+            return;
+        }
+
+        let value = build_mempool_transaction_event(hash, tx, size, status, self.network.is_mainnet());
+        let rpc = rpc.clone();
+        spawn_task("rpc-notify-mempool-tx", async move {
+            for event in tracked {
+                if let Err(e) = rpc.notify_clients(&event, value.clone()).await {
+                    debug!("Error while broadcasting event MempoolTransaction to websocket: {}", e);
+                }
+            }
+        });
+    }
+
     // Get a block template for the new block work (mining)
     pub async fn get_block_template(&self, address: PublicKey) -> Result<BlockHeader, BlockchainError> {
         let storage = self.storage.read().await;
@@ -1411,6 +1765,11 @@ impl<S: Storage> Blockchain<S> {
     // Generate a block header template without transactions
     pub async fn get_block_header_template_for_storage(&self, storage: &S, address: PublicKey) -> Result<BlockHeader, BlockchainError> {
         trace!("get block header template");
+        // a template with a skewed timestamp would be rejected by the network
+        if !self.clock.is_mining_allowed() {
+            return Err(BlockchainError::ClockSkewed)
+        }
+
         let extra_nonce: [u8; EXTRA_NONCE_SIZE] = rand::thread_rng().gen::<[u8; EXTRA_NONCE_SIZE]>(); // generate random bytes
         let tips_set = storage.get_tips().await?;
         let mut tips = Vec::with_capacity(tips_set.len());
@@ -1470,7 +1829,18 @@ impl<S: Storage> Blockchain<S> {
         }
 
         let height = blockdag::calculate_height_at_tips(storage, sorted_tips.iter()).await?;
-        let block = BlockHeader::new(get_version_at_height(self.get_network(), height), height, timestamp, sorted_tips, extra_nonce, address, IndexSet::new());
+        let version = self.fork_schedule.get_version_at_height(height);
+
+        // Respect the median time past so the template isn't rejected
+        if Feature::MedianTimePast.is_enabled_in(version) {
+            let median_time_past = blockdag::calculate_median_time_past(storage, sorted_tips.iter(), self.fork_schedule.get_timestamp_rules().median_time_past_window).await?;
+            if timestamp < median_time_past {
+                warn!("Block template timestamp is less than the median time past, using median time past");
+                timestamp = median_time_past;
+            }
+        }
+
+        let block = BlockHeader::new(version, height, timestamp, sorted_tips, extra_nonce, address, IndexSet::new());
 
         Ok(block)
     }
@@ -1515,10 +1885,11 @@ impl<S: Storage> Blockchain<S> {
         let mut chain_state = ChainState::new(storage, &self.environment, stable_topoheight, topoheight, block.get_version());
 
         if !tx_selector.is_empty() {
+            let max_block_size = self.get_max_block_size_at_tips(storage, block.get_tips().iter(), block.get_version()).await?;
             let mut failed_sources = HashSet::new();
             let processed_txs = self.get_all_txs_until_height(storage, stable_height, block.get_tips().iter().cloned(), false).await?;
             while let Some(TxSelectorEntry { size, hash, tx }) = tx_selector.next() {
-                if block_size + total_txs_size + size >= MAX_BLOCK_SIZE {
+                if block_size + total_txs_size + size >= max_block_size {
                     break;
                 }
 
@@ -1590,7 +1961,7 @@ impl<S: Storage> Blockchain<S> {
         let start = Instant::now();
 
         // Expected version for this block
-        let version = get_version_at_height(self.get_network(), block.get_height());
+        let version = self.fork_schedule.get_version_at_height(block.get_height());
 
         // Verify that the block is on the correct version
         if block.get_version() != version {
@@ -1605,9 +1976,10 @@ impl<S: Storage> Blockchain<S> {
         }
         debug!("Block {} is not in chain, processing it", block_hash);
 
+        let timestamp_rules = self.fork_schedule.get_timestamp_rules();
         let current_timestamp = get_current_time_in_millis(); 
-        if block.get_timestamp() > current_timestamp + TIMESTAMP_IN_FUTURE_LIMIT { // accept 2s in future
-            debug!("Block timestamp is too much in future!");
+        if block.get_timestamp() > current_timestamp + timestamp_rules.future_drift_limit { // accept 2s in future by default
+            debug!("Block timestamp is too much in future! Local clock skewed: {}", self.clock.is_skewed());
             return Err(BlockchainError::TimestampIsInFuture(current_timestamp, block.get_timestamp()));
         }
 
@@ -1637,9 +2009,10 @@ impl<S: Storage> Blockchain<S> {
 
         // block contains header and full TXs
         let block_size = block.size();
-        if block_size > MAX_BLOCK_SIZE {
-            debug!("Block size ({} bytes) is greater than the limit ({} bytes)", block.size(), MAX_BLOCK_SIZE);
-            return Err(BlockchainError::InvalidBlockSize(MAX_BLOCK_SIZE, block.size()));
+        let max_block_size = self.fork_schedule.get_max_block_size(version);
+        if block_size > max_block_size {
+            debug!("Block size ({} bytes) is greater than the limit ({} bytes)", block.size(), max_block_size);
+            return Err(BlockchainError::InvalidBlockSize(max_block_size, block.size()));
         }
 
         for tip in block.get_tips() {
@@ -1655,6 +2028,15 @@ impl<S: Storage> Blockchain<S> {
             return Err(BlockchainError::InvalidBlockHeight(block_height_by_tips, block.get_height()))
         }
 
+        // Above check was against the hard cap, verify now the limit at its tips
+        if Feature::DynamicBlockSize.is_enabled_in(version) {
+            let max_block_size = self.get_max_block_size_at_tips(storage, block.get_tips().iter(), version).await?;
+            if block_size > max_block_size {
+                debug!("Block size ({} bytes) is greater than the dynamic limit ({} bytes)", block_size, max_block_size);
+                return Err(BlockchainError::InvalidBlockSize(max_block_size, block_size));
+            }
+        }
+
         let stable_height = self.get_stable_height();
         if tips_count > 0 {
             debug!("Height by tips: {}, stable height: {}", block_height_by_tips, stable_height);
@@ -1689,6 +2071,9 @@ impl<S: Storage> Blockchain<S> {
             }
         }
 
+        // block timestamp can't be below the median of its last ancestors
+        blockdag::verify_median_time_past(storage, block.get_tips().iter(), block.get_timestamp(), version, timestamp_rules.median_time_past_window).await?;
+
         if tips_count > 1 {
             let best_tip = blockdag::find_best_tip_by_cumulative_difficulty(storage, block.get_tips().iter()).await?;
             debug!("Best tip selected for this new block is {}", best_tip);
@@ -1737,11 +2122,12 @@ impl<S: Storage> Blockchain<S> {
 
             // All transactions to be verified in one batch
             let mut batch = Vec::with_capacity(block.get_txs_count());
-            let is_v2_enabled = version >= BlockVersion::V2;
+            let is_v2_enabled = Feature::TipsTxsDuplication.is_enabled_in(version);
+            let max_tx_size = self.fork_schedule.get_max_transaction_size(version);
             for (tx, hash) in block.get_transactions().iter().zip(block.get_txs_hashes()) {
                 let tx_size = tx.size();
-                if tx_size > MAX_TRANSACTION_SIZE {
-                    return Err(BlockchainError::TxTooBig(tx_size, MAX_TRANSACTION_SIZE))
+                if tx_size > max_tx_size {
+                    return Err(BlockchainError::TxTooBig(tx_size, max_tx_size))
                 }
 
                 // verification that the real TX Hash is the same as in block header (and also check the correct order)
@@ -1860,8 +2246,18 @@ impl<S: Storage> Blockchain<S> {
             HashSet::new()
         };
 
+        // accounts subscribed to their activity
+        let tracked_accounts: Vec<&Address> = should_track_events.iter()
+            .filter_map(|event| match event {
+                NotifyEvent::AccountActivity { address } => Some(address),
+                _ => None
+            })
+            .collect();
+
         // track all events to notify websocket
         let mut events: HashMap<NotifyEvent, Vec<Value>> = HashMap::new();
+        // orphaned blocks to send through the publisher once the block is added
+        let mut orphaned_blocks: Vec<(Hash, TopoHeight)> = Vec::new();
         // Track all orphaned tranasctions
         let mut orphaned_transactions = HashSet::new();
 
@@ -1910,12 +2306,17 @@ impl<S: Storage> Blockchain<S> {
                         events.entry(NotifyEvent::BlockOrphaned).or_insert_with(Vec::new).push(value);
                     }
 
+                    if is_orphaned && self.publisher.is_some() {
+                        orphaned_blocks.push((hash_at_topo.clone(), topoheight));
+                    }
+
                     // mark txs as unexecuted if it was executed in this block
                     for tx_hash in block.get_txs_hashes() {
                         if storage.is_tx_executed_in_block(tx_hash, &hash_at_topo)? {
                             trace!("Removing execution of {}", tx_hash);
                             storage.remove_tx_executed(tx_hash)?;
                             storage.delete_contract_outputs_for_tx(tx_hash).await?;
+                            storage.delete_contract_receipts_for_tx(tx_hash).await?;
 
                             if is_orphaned {
                                 orphaned_transactions.insert(tx_hash.clone());
@@ -2008,6 +2409,7 @@ impl<S: Storage> Blockchain<S> {
                     past_burned_supply,
                     &hash,
                     &block,
+                    self.fork_schedule.get_contract_capabilities(version),
                 );
 
                 // compute rewards & execute txs
@@ -2094,7 +2496,49 @@ impl<S: Storage> Blockchain<S> {
                                     });
                                     events.entry(NotifyEvent::DeployContract).or_insert_with(Vec::new).push(value);
                                 }
-                            }
+                            },
+                            TransactionType::MultiSig(payload) => {
+                                let is_mainnet = self.network.is_mainnet();
+                                let address = tx.get_source().as_address(is_mainnet);
+                                let tracked = [
+                                    NotifyEvent::MultisigChanged { address: None },
+                                    NotifyEvent::MultisigChanged { address: Some(address.clone()) }
+                                ];
+
+                                if tracked.iter().any(|event| should_track_events.contains(event)) {
+                                    // an account with a multisig must sign its TXs using it
+                                    let (kind, state) = if payload.is_delete() {
+                                        (MultisigChangeKind::Reset, MultisigState::Deleted)
+                                    } else {
+                                        let kind = if tx.get_multisig().is_some() {
+                                            MultisigChangeKind::Rotated
+                                        } else {
+                                            MultisigChangeKind::Created
+                                        };
+
+                                        let state = MultisigState::Active {
+                                            participants: payload.participants.iter().map(|p| p.as_address(is_mainnet)).collect(),
+                                            threshold: payload.threshold
+                                        };
+                                        (kind, state)
+                                    };
+
+                                    let value = json!(MultisigChangedEvent {
+                                        block_hash: Cow::Borrowed(&hash),
+                                        tx_hash: Cow::Borrowed(&tx_hash),
+                                        topoheight: highest_topo,
+                                        address,
+                                        kind,
+                                        state
+                                    });
+
+                                    for event in tracked {
+                                        if should_track_events.contains(&event) {
+                                            events.entry(event).or_insert_with(Vec::new).push(value.clone());
+                                        }
+                                    }
+                                }
+                            },
                             _ => {}
                         }
 
@@ -2120,6 +2564,10 @@ impl<S: Storage> Blockchain<S> {
                 // apply changes from Chain State
                 chain_state.apply_changes().await?;
 
+                if !tracked_accounts.is_empty() {
+                    track_accounts_activity(storage, &tracked_accounts, &hash, highest_topo, &mut events).await?;
+                }
+
                 if should_track_events.contains(&NotifyEvent::BlockOrdered) {
                     let value = json!(BlockOrderedEvent {
                         block_hash: Cow::Borrowed(&hash),
@@ -2246,12 +2694,22 @@ impl<S: Storage> Blockchain<S> {
             debug!("Locking mempool write mode");
             let mut mempool = self.mempool.write().await;
             debug!("mempool write mode ok");
-            let version = get_version_at_height(self.get_network(), current_height);
+            let version = self.fork_schedule.get_version_at_height(current_height);
             mempool.clean_up(&*storage, &self.environment, base_topo_height, highest_topo, version).await
         } else {
             Vec::new()
         };
 
+        for (tx_hash, sorted_tx) in mempool_deleted_txs.iter() {
+            let mut value = None;
+            for event in get_mempool_transaction_events(sorted_tx.get_tx(), self.network.is_mainnet()) {
+                if should_track_events.contains(&event) {
+                    let value = value.get_or_insert_with(|| build_mempool_transaction_event(tx_hash, sorted_tx.get_tx(), sorted_tx.get_size(), MempoolTransactionStatus::Removed, self.network.is_mainnet()));
+                    events.entry(event).or_insert_with(Vec::new).push(value.clone());
+                }
+            }
+        }
+
         if orphan_event_tracked {
             for (tx_hash, sorted_tx) in mempool_deleted_txs {
                 // Delete it from our orphaned transactions list
@@ -2271,6 +2729,7 @@ impl<S: Storage> Blockchain<S> {
                     executed_in_block: None,
                     in_mempool: false,
                     first_seen: Some(sorted_tx.get_first_seen()),
+                    contract_receipts: None,
                     data,
                 };
                 events.entry(NotifyEvent::TransactionOrphaned).or_insert_with(Vec::new).push(json!(data));
@@ -2306,6 +2765,7 @@ impl<S: Storage> Blockchain<S> {
                             executed_in_block: None,
                             in_mempool: false,
                             first_seen: None,
+                            contract_receipts: None,
                             data,
                         };
                         events.entry(NotifyEvent::TransactionOrphaned).or_insert_with(Vec::new).push(json!(data));
@@ -2314,7 +2774,12 @@ impl<S: Storage> Blockchain<S> {
             }
         }
 
-        info!("Processed block {} at height {} in {}ms with {} txs (DAG: {})", block_hash, block.get_height(), start.elapsed().as_millis(), block.get_txs_count(), block_is_ordered);
+        // Space may be available again in the mempool
+        if highest_topo >= current_topoheight {
+            self.reconsider_spilled_txs(storage).await?;
+        }
+
+        info!(block_hash:% = block_hash; "Processed block {} at height {} in {}ms with {} txs (DAG: {})", block_hash, block.get_height(), start.elapsed().as_millis(), block.get_txs_count(), block_is_ordered);
 
         // Broadcast to p2p nodes
         if broadcast {
@@ -2323,14 +2788,23 @@ impl<S: Storage> Blockchain<S> {
                 trace!("P2p locked, broadcasting in new task");
                 let p2p = p2p.clone();
                 let pruned_topoheight = storage.get_pruned_topoheight().await?;
+                let stable_commitment = ChainCommitment::from_chain(self, storage).await;
                 let block = block.clone();
                 let block_hash = block_hash.clone();
                 spawn_task("broadcast-block", async move {
-                    p2p.broadcast_block(&block, cumulative_difficulty, current_topoheight, current_height, pruned_topoheight, &block_hash, mining).await;
+                    p2p.broadcast_block(&block, cumulative_difficulty, current_topoheight, current_height, pruned_topoheight, stable_commitment, &block_hash, mining).await;
                 });
             }
         }
 
+        // send the raw notifications
+        if let Some(publisher) = self.publisher.as_ref() {
+            for (hash, old_topoheight) in orphaned_blocks {
+                publisher.publish_block_orphaned(&hash, old_topoheight);
+            }
+            publisher.publish_block(&block_hash, &block, &txs);
+        }
+
         // broadcast to websocket new block
         if let Some(rpc) = rpc_server.as_ref() {
             // if we have a getwork server, and that its not from syncing, notify miners
@@ -2343,6 +2817,15 @@ impl<S: Storage> Blockchain<S> {
                         }
                     });
                 }
+
+                if let Some(stratum) = rpc.stratum_server() {
+                    let stratum = stratum.clone();
+                    spawn_task("notify-stratum-job", async move {
+                        if let Err(e) = stratum.notify_new_job().await {
+                            debug!("Error while notifying new job to Stratum miners: {}", e);
+                        }
+                    });
+                }
             }
 
             // atm, we always notify websocket clients
@@ -2611,17 +3094,25 @@ impl<S: Storage> Blockchain<S> {
     // It is the same as computing the average time between the last 50 blocks but much faster
     // Genesis block timestamp isn't take in count for this calculation
     pub async fn get_average_block_time<P>(&self, provider: &P) -> Result<TimestampMillis, BlockchainError>
+    where
+        P: DifficultyProvider + PrunedTopoheightProvider + DagOrderProvider
+    {
+        self.get_average_block_time_for_window(provider, 50).await
+    }
+
+    // Same as `get_average_block_time` but on the last `window` blocks
+    pub async fn get_average_block_time_for_window<P>(&self, provider: &P, window: u64) -> Result<TimestampMillis, BlockchainError>
     where
         P: DifficultyProvider + PrunedTopoheightProvider + DagOrderProvider
     {
         // current topoheight
         let topoheight = self.get_topo_height();
 
-        // we need to get the block hash at topoheight - 50 to compare
+        // we need to get the block hash at topoheight - window to compare
         // if topoheight is 0, returns the target as we don't have any block
         // otherwise returns topoheight
-        let mut count = if topoheight > 50 {
-            50
+        let mut count = if topoheight > window {
+            window
         } else if topoheight <= 1 {
             return Ok(BLOCK_TIME_MILLIS);
         } else {
@@ -2644,6 +3135,114 @@ impl<S: Storage> Blockchain<S> {
         let diff = now_timestamp - count_timestamp;
         Ok(diff / count)
     }
+
+    // Simulate the execution of an InvokeContract transaction against the current chain state
+    // Storage is locked in write mode to build the chain state, but no changes are applied:
+    // `apply_changes` is never called and the chain state is dropped once simulated
+    pub async fn simulate_contract_invoke(&self, tx: Transaction) -> Result<SimulateContractInvokeResult<'static>, BlockchainError> {
+        let payload = match tx.get_data() {
+            TransactionType::InvokeContract(payload) => payload,
+            _ => return Err(BlockchainError::NotInvokeContractTransaction)
+        };
+
+        let tx_hash = tx.hash();
+        let mut storage = self.storage.write().await;
+        if storage.has_transaction(&tx_hash).await? {
+            return Err(BlockchainError::TxAlreadyInBlockchain(tx_hash))
+        }
+
+        // Build a virtual block on top of our current tips
+        let header = self.get_block_header_template_for_storage(&storage, tx.get_source().clone()).await?;
+        let block_hash = header.hash();
+        let version = header.get_version();
+        let block = Block::new(Immutable::Owned(header), Vec::new());
+
+        verify_tx_for_block_version(&tx, version)?;
+
+        // The TX would be executed in the next topoheight
+        let topoheight = self.get_topo_height() + 1;
+        let mainnet = self.network.is_mainnet();
+
+        let (outputs, cache, gas_fee, burned_gas) = {
+            let mut chain_state = ApplicableChainState::new(
+                &mut *storage,
+                &self.environment,
+                self.get_stable_topoheight(),
+                topoheight,
+                version,
+                0,
+                &block_hash,
+                &block,
+                self.fork_schedule.get_contract_capabilities(version),
+            );
+
+            tx.apply_with_partial_verify(&tx_hash, &mut chain_state).await?;
+
+            let outputs = chain_state.get_contract_outputs_for_tx(&tx_hash).cloned().unwrap_or_default();
+            let cache = chain_state.get_contract_cache(&payload.contract).cloned();
+            (outputs, cache, chain_state.get_gas_fee(), chain_state.get_burned_supply())
+        };
+
+        let mut exit_code = None;
+        let mut refunded_gas = 0;
+        for output in outputs.iter() {
+            match output {
+                ContractOutput::ExitCode(code) => exit_code = *code,
+                ContractOutput::RefundGas { amount } => refunded_gas = *amount,
+                _ => {}
+            }
+        }
+
+        // Build the storage & balances diff against the latest stored versions
+        let mut storage_changes = Vec::new();
+        let mut balance_changes = Vec::new();
+        if let Some(cache) = cache {
+            for (key, (state, value)) in cache.storage {
+                if !state.should_be_stored() {
+                    continue;
+                }
+
+                let previous = storage.get_contract_data_at_maximum_topoheight_for(&payload.contract, &key, topoheight).await?
+                    .and_then(|(_, version)| version.take());
+
+                storage_changes.push(ContractStorageChange {
+                    key,
+                    previous,
+                    new: value
+                });
+            }
+
+            for (asset, data) in cache.balances {
+                if let Some((_, new)) = data.filter(|(state, _)| state.should_be_stored()) {
+                    let previous = storage.get_contract_balance_at_maximum_topoheight(&payload.contract, &asset, topoheight).await?
+                        .map(|(_, version)| version.take())
+                        .unwrap_or(0);
+
+                    balance_changes.push(ContractBalanceChange {
+                        asset,
+                        previous,
+                        new
+                    });
+                }
+            }
+        }
+
+        Ok(SimulateContractInvokeResult {
+            contract: Cow::Owned(payload.contract.clone()),
+            chunk_id: payload.chunk_id,
+            topoheight,
+            exit_code,
+            gas_used: payload.max_gas.saturating_sub(refunded_gas),
+            burned_gas,
+            gas_fee,
+            refunded_gas,
+            outputs: outputs.into_iter()
+                .map(|output| RPCContractOutput::from_output(output, mainnet))
+                .collect(),
+            storage_changes,
+            balance_changes
+        })
+    }
 }
 
 // Estimate the required fees for a transaction
@@ -2663,44 +3262,93 @@ pub async fn estimate_required_tx_fees<P: AccountProvider>(provider: &P, current
     Ok(calculate_tx_fee(tx.size(), output_count, processed_keys.len(), tx.get_multisig_count()))
 }
 
-// Get the block reward for a side block based on how many side blocks exists at same height
-pub fn side_block_reward_percentage(side_blocks: u64) -> u64 {
-    let mut side_block_percent = SIDE_BLOCK_REWARD_PERCENT;
-    if side_blocks > 0 {
-        if side_blocks < SIDE_BLOCK_REWARD_MAX_BLOCKS {
-            side_block_percent = SIDE_BLOCK_REWARD_PERCENT / (side_blocks * 2);
-        } else {
-            // If we have more than 3 side blocks at same height
-            // we reduce the reward to 5%
-            side_block_percent = SIDE_BLOCK_REWARD_MIN_PERCENT;
+// All the mempool events a TX can be notified to:
+// without filter, filtered on its source and/or on one of its destinations
+fn get_mempool_transaction_events(tx: &Transaction, mainnet: bool) -> Vec<NotifyEvent> {
+    let source = tx.get_source().as_address(mainnet);
+    let mut events = vec![
+        NotifyEvent::MempoolTransaction { source: None, destination: None },
+        NotifyEvent::MempoolTransaction { source: Some(source.clone()), destination: None }
+    ];
+
+    if let TransactionType::Transfers(transfers) = tx.get_data() {
+        let destinations: HashSet<_> = transfers.iter()
+            .map(|transfer| transfer.get_destination())
+            .collect();
+
+        for destination in destinations {
+            let destination = destination.as_address(mainnet);
+            events.push(NotifyEvent::MempoolTransaction { source: None, destination: Some(destination.clone()) });
+            events.push(NotifyEvent::MempoolTransaction { source: Some(source.clone()), destination: Some(destination) });
         }
     }
 
-    side_block_percent
+    events
 }
 
-// Calculate the block reward based on the current supply
-pub fn get_block_reward(supply: u64) -> u64 {
-    // Prevent any overflow
-    if supply >= MAXIMUM_SUPPLY {
-        // Max supply reached, do we want to generate small fixed amount of coins? 
-        return 0
+// Value of the MempoolTransaction event
+fn build_mempool_transaction_event(hash: &Hash, tx: &Transaction, size: usize, status: MempoolTransactionStatus, mainnet: bool) -> Value {
+    json!(MempoolTransactionEvent {
+        tx_hash: Cow::Borrowed(hash),
+        source: tx.get_source().as_address(mainnet),
+        fee: tx.get_fee(),
+        fee_per_kb: tx.get_fee() * BYTES_PER_KB as u64 / size.max(1) as u64,
+        size,
+        status
+    })
+}
+
+// Notify the subscribed accounts having their nonce or a balance updated at this topoheight
+async fn track_accounts_activity<S: Storage>(storage: &S, accounts: &[&Address], block_hash: &Hash, topoheight: TopoHeight, events: &mut HashMap<NotifyEvent, Vec<Value>>) -> Result<(), BlockchainError> {
+    let mut balances: HashMap<PublicKey, HashMap<Hash, VersionedBalance>> = HashMap::new();
+    for (key, asset, version) in storage.get_balances_at_exact_topoheight(topoheight).await? {
+        balances.entry(key).or_insert_with(HashMap::new).insert(asset, version);
     }
 
-    let base_reward = (MAXIMUM_SUPPLY - supply) >> EMISSION_SPEED_FACTOR;
-    base_reward * BLOCK_TIME_MILLIS / MILLIS_PER_SECOND / 180
-}
+    for address in accounts {
+        let key = address.get_public_key();
+        let nonce = if storage.has_nonce_at_exact_topoheight(key, topoheight).await? {
+            Some(storage.get_nonce_at_exact_topoheight(key, topoheight).await?)
+        } else {
+            None
+        };
 
-// Returns the fee percentage for a block at a given height
-pub fn get_block_dev_fee(height: u64) -> u64 {
-    let mut percentage = 0;
-    for threshold in DEV_FEES.iter() {
-        if height >= threshold.height {
-            percentage = threshold.fee_percentage;
+        let balances = balances.remove(key).unwrap_or_default();
+        if nonce.is_none() && balances.is_empty() {
+            continue;
         }
+
+        let value = json!(AccountActivityEvent {
+            block_hash: Cow::Borrowed(block_hash),
+            topoheight,
+            address: Cow::Borrowed(address),
+            nonce,
+            balances
+        });
+        events.entry(NotifyEvent::AccountActivity { address: (*address).clone() }).or_insert_with(Vec::new).push(value);
+    }
+
+    Ok(())
+}
+
+// Calculate the block size limit from the sizes of the last blocks
+// The median is increased by BLOCK_SIZE_MEDIAN_GROWTH_PERCENT so the limit
+// grows slowly while blocks are full, and it stays between MAX_BLOCK_SIZE and the hard cap
+pub fn calculate_dynamic_block_size(sizes: &mut [usize], hard_cap: usize) -> usize {
+    if sizes.is_empty() {
+        return MAX_BLOCK_SIZE
     }
 
-    percentage
+    sizes.sort_unstable();
+    let middle = sizes.len() / 2;
+    let median = if sizes.len() % 2 == 0 {
+        (sizes[middle - 1] + sizes[middle]) / 2
+    } else {
+        sizes[middle]
+    };
+
+    let limit = median + median * BLOCK_SIZE_MEDIAN_GROWTH_PERCENT / 100;
+    limit.clamp(MAX_BLOCK_SIZE, hard_cap.max(MAX_BLOCK_SIZE))
 }
 
 #[cfg(test)]
@@ -2708,26 +3356,26 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_reward_side_block_percentage() {
-        assert_eq!(side_block_reward_percentage(0), SIDE_BLOCK_REWARD_PERCENT);
-        assert_eq!(side_block_reward_percentage(1), SIDE_BLOCK_REWARD_PERCENT / 2);
-        assert_eq!(side_block_reward_percentage(2), SIDE_BLOCK_REWARD_PERCENT / 4);
-        assert_eq!(side_block_reward_percentage(3), SIDE_BLOCK_REWARD_MIN_PERCENT);
-    }
+    fn test_dynamic_block_size() {
+        let hard_cap = MAX_BLOCK_SIZE * 4;
 
-    #[test]
-    fn test_block_dev_fee() {
-        assert_eq!(get_block_dev_fee(0), 10);
-        assert_eq!(get_block_dev_fee(1), 10);
+        // No history, base limit
+        assert_eq!(calculate_dynamic_block_size(&mut [], hard_cap), MAX_BLOCK_SIZE);
+
+        // Small blocks never go below the base limit
+        assert_eq!(calculate_dynamic_block_size(&mut [100, 200, 300], hard_cap), MAX_BLOCK_SIZE);
 
-        // ~ current height
-        assert_eq!(get_block_dev_fee(55_000), 10);
+        // Full blocks increase the limit by 50%
+        let mut sizes = vec![MAX_BLOCK_SIZE; 40];
+        assert_eq!(calculate_dynamic_block_size(&mut sizes, hard_cap), MAX_BLOCK_SIZE * 3 / 2);
 
-        // End of the first threshold, we pass to 5%
-        assert_eq!(get_block_dev_fee(3_250_000), 5);
+        // A minority of big blocks doesn't move the median
+        let mut sizes = vec![100; 40];
+        sizes[..19].fill(hard_cap);
+        assert_eq!(calculate_dynamic_block_size(&mut sizes, hard_cap), MAX_BLOCK_SIZE);
 
-        assert_eq!(get_block_dev_fee(DEV_FEES[0].height), 10);
-        assert_eq!(get_block_dev_fee(DEV_FEES[1].height), 5);
-        assert_eq!(get_block_dev_fee(DEV_FEES[1].height + 1), 5);
+        // Limit can't go above the hard cap
+        let mut sizes = vec![hard_cap; 40];
+        assert_eq!(calculate_dynamic_block_size(&mut sizes, hard_cap), hard_cap);
     }
-}
\ No newline at end of file
+}
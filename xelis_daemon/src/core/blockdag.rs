@@ -1,6 +1,8 @@
+use std::collections::{BTreeSet, HashSet};
 use indexmap::IndexSet;
-use log::trace;
+use log::{debug, trace};
 use xelis_common::{
+    block::BlockVersion,
     difficulty::CumulativeDifficulty,
     time::TimestampMillis,
     crypto::Hash,
@@ -11,6 +13,7 @@ use super::{
         DifficultyProvider
     },
     error::BlockchainError,
+    hard_fork::Feature
 };
 
 // sort the scores by cumulative difficulty and, if equals, by hash value
@@ -126,6 +129,66 @@ where
     }
 }
 
+// Compute the median timestamp of the last N ancestors reachable from the tips
+// Ancestors are visited from the highest height, then by hash, to be deterministic
+pub async fn calculate_median_time_past<'a, D, I>(provider: &D, tips: I, window: usize) -> Result<TimestampMillis, BlockchainError>
+where
+    D: DifficultyProvider,
+    I: Iterator<Item = &'a Hash>
+{
+    trace!("calculate median time past");
+    let mut queue = BTreeSet::new();
+    let mut visited = HashSet::new();
+    for hash in tips {
+        if visited.insert(hash.clone()) {
+            queue.insert((provider.get_height_for_block_hash(hash).await?, hash.clone()));
+        }
+    }
+
+    let mut timestamps = Vec::with_capacity(window);
+    while timestamps.len() < window {
+        let Some((_, hash)) = queue.pop_last() else {
+            break;
+        };
+
+        timestamps.push(provider.get_timestamp_for_block_hash(&hash).await?);
+        for past in provider.get_past_blocks_for_block_hash(&hash).await?.iter() {
+            if visited.insert(past.clone()) {
+                queue.insert((provider.get_height_for_block_hash(past).await?, past.clone()));
+            }
+        }
+    }
+
+    if timestamps.is_empty() {
+        return Err(BlockchainError::ExpectedTips)
+    }
+
+    timestamps.sort_unstable();
+    Ok(timestamps[timestamps.len() / 2])
+}
+
+// Verify that the block timestamp isn't below the median time past of its tips
+// This is only enforced once the feature is enabled in the block version
+pub async fn verify_median_time_past<'a, D, I>(provider: &D, tips: I, timestamp: TimestampMillis, version: BlockVersion, window: usize) -> Result<(), BlockchainError>
+where
+    D: DifficultyProvider,
+    I: Iterator<Item = &'a Hash>
+{
+    trace!("verify median time past");
+    let mut tips = tips.peekable();
+    if tips.peek().is_none() || !Feature::MedianTimePast.is_enabled_in(version) {
+        return Ok(())
+    }
+
+    let median_time_past = calculate_median_time_past(provider, tips, window).await?;
+    if timestamp < median_time_past {
+        debug!("Invalid block timestamp {}, median time past is {}", timestamp, median_time_past);
+        return Err(BlockchainError::TimestampIsLessThanMedianTimePast(median_time_past, timestamp))
+    }
+
+    Ok(())
+}
+
 // Find the newest tip based on the timestamp of the blocks
 pub async fn find_newest_tip_by_timestamp<'a, D, I>(provider: &D, tips: I) -> Result<(&'a Hash, TimestampMillis), BlockchainError>
 where
@@ -156,4 +219,153 @@ where
             Ok((newest_tip.ok_or(BlockchainError::ExpectedTips)?, timestamp))
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+    use async_trait::async_trait;
+    use xelis_common::{
+        block::BlockHeader,
+        difficulty::Difficulty,
+        immutable::Immutable,
+        varuint::VarUint
+    };
+    use super::*;
+
+    // Height, timestamp and tips of each block
+    #[derive(Default)]
+    struct MockDag {
+        blocks: HashMap<Hash, (u64, TimestampMillis, IndexSet<Hash>)>
+    }
+
+    impl MockDag {
+        // Add a block on top of its tips and returns its hash
+        fn add(&mut self, id: u8, timestamp: TimestampMillis, tips: &[&Hash]) -> Hash {
+            let hash = Hash::new([id; 32]);
+            let height = tips.iter().map(|tip| self.blocks[*tip].0 + 1).max().unwrap_or(0);
+            self.blocks.insert(hash.clone(), (height, timestamp, tips.iter().copied().cloned().collect()));
+            hash
+        }
+
+        // Build a chain with the given timestamps and returns its last block
+        fn chain(&mut self, timestamps: &[TimestampMillis]) -> Hash {
+            let mut tip = self.add(0, timestamps[0], &[]);
+            for (i, timestamp) in timestamps.iter().enumerate().skip(1) {
+                tip = self.add(i as u8, *timestamp, &[&tip]);
+            }
+            tip
+        }
+
+        fn get(&self, hash: &Hash) -> Result<&(u64, TimestampMillis, IndexSet<Hash>), BlockchainError> {
+            self.blocks.get(hash).ok_or_else(|| BlockchainError::BlockNotFound(hash.clone()))
+        }
+    }
+
+    #[async_trait]
+    impl DifficultyProvider for MockDag {
+        async fn get_height_for_block_hash(&self, hash: &Hash) -> Result<u64, BlockchainError> {
+            Ok(self.get(hash)?.0)
+        }
+
+        async fn get_timestamp_for_block_hash(&self, hash: &Hash) -> Result<TimestampMillis, BlockchainError> {
+            Ok(self.get(hash)?.1)
+        }
+
+        async fn get_difficulty_for_block_hash(&self, _: &Hash) -> Result<Difficulty, BlockchainError> {
+            unimplemented!()
+        }
+
+        async fn get_cumulative_difficulty_for_block_hash(&self, _: &Hash) -> Result<CumulativeDifficulty, BlockchainError> {
+            unimplemented!()
+        }
+
+        async fn get_past_blocks_for_block_hash(&self, hash: &Hash) -> Result<Immutable<IndexSet<Hash>>, BlockchainError> {
+            Ok(Immutable::Owned(self.get(hash)?.2.clone()))
+        }
+
+        async fn get_block_header_by_hash(&self, _: &Hash) -> Result<Arc<BlockHeader>, BlockchainError> {
+            unimplemented!()
+        }
+
+        async fn get_estimated_covariance_for_block_hash(&self, _: &Hash) -> Result<VarUint, BlockchainError> {
+            unimplemented!()
+        }
+
+        async fn set_estimated_covariance_for_block_hash(&mut self, _: &Hash, _: VarUint) -> Result<(), BlockchainError> {
+            unimplemented!()
+        }
+
+        async fn set_cumulative_difficulty_for_block_hash(&mut self, _: &Hash, _: CumulativeDifficulty) -> Result<(), BlockchainError> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_median_time_past_odd_window() {
+        let mut dag = MockDag::default();
+        let tip = dag.chain(&[50, 10, 40, 20, 30]);
+        assert_eq!(calculate_median_time_past(&dag, [&tip].into_iter(), 5).await.unwrap(), 30);
+        // Only the last 3 blocks: 40, 20, 30
+        assert_eq!(calculate_median_time_past(&dag, [&tip].into_iter(), 3).await.unwrap(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_median_time_past_even_window() {
+        let mut dag = MockDag::default();
+        let tip = dag.chain(&[50, 10, 40, 20, 30]);
+        // Last 4 blocks: 10, 40, 20, 30, the upper middle is selected
+        assert_eq!(calculate_median_time_past(&dag, [&tip].into_iter(), 4).await.unwrap(), 30);
+        // Last 2 blocks: 20, 30
+        assert_eq!(calculate_median_time_past(&dag, [&tip].into_iter(), 2).await.unwrap(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_median_time_past_fewer_ancestors() {
+        let mut dag = MockDag::default();
+        let tip = dag.chain(&[10, 20, 30]);
+        assert_eq!(calculate_median_time_past(&dag, [&tip].into_iter(), 11).await.unwrap(), 20);
+
+        let genesis = Hash::new([0; 32]);
+        assert_eq!(calculate_median_time_past(&dag, [&genesis].into_iter(), 11).await.unwrap(), 10);
+
+        // No tips, no median
+        assert!(matches!(calculate_median_time_past(&dag, [].into_iter(), 11).await, Err(BlockchainError::ExpectedTips)));
+    }
+
+    #[tokio::test]
+    async fn test_median_time_past_multiple_tips() {
+        let mut dag = MockDag::default();
+        let genesis = dag.add(0, 100, &[]);
+        let a = dag.add(1, 20, &[&genesis]);
+        let b = dag.add(2, 30, &[&genesis]);
+
+        // Common ancestor is only counted once: 20, 30, 100
+        assert_eq!(calculate_median_time_past(&dag, [&a, &b].into_iter(), 11).await.unwrap(), 30);
+        // Highest blocks are visited first
+        assert_eq!(calculate_median_time_past(&dag, [&a, &b].into_iter(), 2).await.unwrap(), 30);
+
+        // Tips order doesn't change the result
+        let c = dag.add(3, 40, &[&a, &b]);
+        let d = dag.add(4, 10, &[&b]);
+        assert_eq!(calculate_median_time_past(&dag, [&c, &d].into_iter(), 3).await.unwrap(), 30);
+        assert_eq!(calculate_median_time_past(&dag, [&d, &c].into_iter(), 3).await.unwrap(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_verify_median_time_past() {
+        let mut dag = MockDag::default();
+        // Median time past of the last 3 blocks is 60
+        let tip = dag.chain(&[50, 60, 70, 10]);
+
+        let res = verify_median_time_past(&dag, [&tip].into_iter(), 20, BlockVersion::V4, 3).await;
+        assert!(matches!(res, Err(BlockchainError::TimestampIsLessThanMedianTimePast(60, 20))));
+        assert!(verify_median_time_past(&dag, [&tip].into_iter(), 60, BlockVersion::V4, 3).await.is_ok());
+
+        // Same block is accepted before the feature is enabled
+        assert!(verify_median_time_past(&dag, [&tip].into_iter(), 20, BlockVersion::V3, 3).await.is_ok());
+
+        // Genesis block has no tips
+        assert!(verify_median_time_past(&dag, [].into_iter(), 0, BlockVersion::V4, 3).await.is_ok());
+    }
+}
@@ -0,0 +1,199 @@
+use std::{path::Path, time::Instant};
+use anyhow::Context;
+use log::{debug, info};
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt}
+};
+use xelis_common::{
+    block::{Block, TopoHeight},
+    crypto::{hash, Hash, Hashable},
+    network::Network,
+    serializer::{StreamReader, StreamWriter}
+};
+use super::{
+    blockchain::Blockchain,
+    error::BlockchainError,
+    storage::{
+        BlockProvider,
+        DagOrderProvider,
+        PrunedTopoheightProvider,
+        Storage
+    }
+};
+
+// Chain bootstrap file
+//
+// Format:
+// - magic bytes
+// - format version (u8)
+// - network
+// - topoheight of the first block (u64)
+// - blocks count (u64)
+// - blocks with their transactions, in topological order
+// - checksum
+//
+// The checksum chains the hashes of all blocks: checksum = hash(checksum || block hash)
+// As a block hash commits to its transactions, it covers the full content of the file.
+
+pub const CHAIN_FILE_MAGIC: [u8; 8] = *b"XELCHAIN";
+pub const CHAIN_FILE_VERSION: u8 = 0;
+
+// Blocks written or imported between two progress logs
+const PROGRESS_INTERVAL: u64 = 10_000;
+
+// Check if the chain file is served by an HTTP mirror
+pub fn is_remote_chain_file(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+// Download a chain file from an HTTP mirror to a local path
+pub async fn download_chain_file(url: &str, path: &Path) -> Result<u64, BlockchainError> {
+    let mut response = reqwest::get(url).await
+        .and_then(|response| response.error_for_status())
+        .context("Error while requesting chain file")?;
+
+    let mut file = File::create(path).await?;
+    let mut size = 0;
+    while let Some(chunk) = response.chunk().await.context("Error while downloading chain file")? {
+        file.write_all(&chunk).await?;
+        size += chunk.len() as u64;
+    }
+    file.flush().await?;
+
+    Ok(size)
+}
+
+pub struct ChainFileSummary {
+    pub start_topoheight: TopoHeight,
+    pub blocks: u64,
+    // blocks already present in chain during an import
+    pub skipped: u64,
+    pub checksum: Hash
+}
+
+fn next_checksum(checksum: &Hash, block_hash: &Hash) -> Hash {
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(checksum.as_bytes());
+    bytes[32..].copy_from_slice(block_hash.as_bytes());
+    hash(&bytes)
+}
+
+// Export the blocks between the two topoheights (inclusive) in a chain file
+pub async fn export_chain<S: Storage, W: AsyncWrite + Unpin>(blockchain: &Blockchain<S>, writer: W, start_topoheight: TopoHeight, end_topoheight: TopoHeight) -> Result<ChainFileSummary, BlockchainError> {
+    if start_topoheight > end_topoheight || end_topoheight > blockchain.get_topo_height() {
+        return Err(BlockchainError::InvalidTopoHeightRange(start_topoheight, end_topoheight))
+    }
+
+    {
+        let storage = blockchain.get_storage().read().await;
+        if let Some(pruned_topoheight) = storage.get_pruned_topoheight().await? {
+            if start_topoheight < pruned_topoheight {
+                return Err(BlockchainError::TopoHeightPruned(start_topoheight, pruned_topoheight))
+            }
+        }
+    }
+
+    let start = Instant::now();
+    let count = end_topoheight - start_topoheight + 1;
+    let mut writer = StreamWriter::new(writer);
+    writer.write_bytes(&CHAIN_FILE_MAGIC).await?;
+    writer.write_u8(CHAIN_FILE_VERSION).await?;
+    writer.write_item(blockchain.get_network()).await?;
+    writer.write_u64(start_topoheight).await?;
+    writer.write_u64(count).await?;
+
+    let mut checksum = Hash::zero();
+    for topoheight in start_topoheight..=end_topoheight {
+        // Lock the storage per block to not block the chain during the export
+        let (block_hash, block) = {
+            let storage = blockchain.get_storage().read().await;
+            let block_hash = storage.get_hash_at_topo_height(topoheight).await?;
+            let block = storage.get_block_by_hash(&block_hash).await?;
+            (block_hash, block)
+        };
+
+        writer.write_item(&block).await?;
+        checksum = next_checksum(&checksum, &block_hash);
+
+        if (topoheight - start_topoheight + 1) % PROGRESS_INTERVAL == 0 {
+            info!("Exported {}/{} blocks", topoheight - start_topoheight + 1, count);
+        }
+    }
+
+    writer.write_item(&checksum).await?;
+    writer.finish().await?;
+    info!("{} blocks exported in {}s", count, start.elapsed().as_secs());
+
+    Ok(ChainFileSummary {
+        start_topoheight,
+        blocks: count,
+        skipped: 0,
+        checksum
+    })
+}
+
+// Import the blocks of a chain file
+// Each block is fully verified as if it was received from the network,
+// the checksum only detects a truncated or corrupted file
+// Blocks already in chain are skipped, so an interrupted import can be resumed
+pub async fn import_chain<S: Storage, R: AsyncRead + Unpin>(blockchain: &Blockchain<S>, reader: R) -> Result<ChainFileSummary, BlockchainError> {
+    let start = Instant::now();
+    let mut reader = StreamReader::new(reader);
+    if reader.read_bytes::<8>().await? != CHAIN_FILE_MAGIC {
+        return Err(BlockchainError::InvalidChainFile("invalid magic bytes"))
+    }
+
+    let version = reader.read_u8().await?;
+    if version != CHAIN_FILE_VERSION {
+        debug!("Chain file version {} is not supported", version);
+        return Err(BlockchainError::InvalidChainFile("unsupported version"))
+    }
+
+    let network: Network = reader.read_item().await?;
+    if network != *blockchain.get_network() {
+        return Err(BlockchainError::InvalidNetwork)
+    }
+
+    let start_topoheight = reader.read_u64().await?;
+    let count = reader.read_u64().await?;
+    info!("Importing {} blocks starting at topoheight {}", count, start_topoheight);
+
+    let mut checksum = Hash::zero();
+    let mut skipped = 0;
+    for i in 0..count {
+        let block: Block = reader.read_item().await?;
+        let block_hash = block.hash();
+        checksum = next_checksum(&checksum, &block_hash);
+
+        if blockchain.has_block(&block_hash).await? {
+            skipped += 1;
+            continue;
+        }
+
+        blockchain.add_new_block(block, false, false).await?;
+
+        if (i + 1) % PROGRESS_INTERVAL == 0 {
+            info!("Imported {}/{} blocks", i + 1, count);
+        }
+    }
+
+    let expected: Hash = reader.read_item().await?;
+    if expected != checksum {
+        return Err(BlockchainError::InvalidChainFile("checksum mismatch"))
+    }
+
+    // Nothing is expected after the checksum
+    if reader.read_u8().await.is_ok() {
+        return Err(BlockchainError::InvalidChainFile("unexpected data after checksum"))
+    }
+
+    info!("{} blocks imported ({} already in chain) in {}s", count - skipped, skipped, start.elapsed().as_secs());
+
+    Ok(ChainFileSummary {
+        start_topoheight,
+        blocks: count,
+        skipped,
+        checksum
+    })
+}
@@ -0,0 +1,201 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc
+    },
+    time::Duration
+};
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use log::{debug, info, warn};
+use tokio::{
+    net::UdpSocket,
+    sync::Mutex,
+    time::{interval, timeout}
+};
+use xelis_common::time::{get_current_time_in_millis, TimestampMillis, TimestampSeconds};
+use crate::config::{
+    CLOCK_SKEW_MAX_PEER_SAMPLES,
+    CLOCK_SKEW_MIN_PEER_SAMPLES,
+    NTP_CHECK_INTERVAL,
+    NTP_REQUEST_TIMEOUT_MS
+};
+
+// Seconds between the NTP epoch (1900) and the UNIX epoch (1970)
+const NTP_UNIX_EPOCH_DELTA: u64 = 2_208_988_800;
+// Size of a SNTP packet without extensions
+const NTP_PACKET_SIZE: usize = 48;
+// Leap indicator 0, version 4, client mode
+const NTP_CLIENT_HEADER: u8 = 0x23;
+
+// Convert a NTP timestamp (seconds and fraction since 1900) to UNIX millis
+fn ntp_to_unix_millis(bytes: &[u8]) -> Result<TimestampMillis> {
+    let seconds = u32::from_be_bytes(bytes[0..4].try_into()?) as u64;
+    let fraction = u32::from_be_bytes(bytes[4..8].try_into()?) as u64;
+    let seconds = seconds.checked_sub(NTP_UNIX_EPOCH_DELTA).context("NTP timestamp before UNIX epoch")?;
+    Ok(seconds * 1000 + ((fraction * 1000) >> 32))
+}
+
+// Query a NTP server and returns the offset in ms of its clock from ours
+async fn query_ntp_server(server: &str) -> Result<i64> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    socket.connect(server).await.context("Error while resolving NTP server")?;
+
+    let mut packet = [0u8; NTP_PACKET_SIZE];
+    packet[0] = NTP_CLIENT_HEADER;
+
+    let sent_at = get_current_time_in_millis() as i64;
+    socket.send(&packet).await?;
+    let read = timeout(Duration::from_millis(NTP_REQUEST_TIMEOUT_MS), socket.recv(&mut packet)).await
+        .context("NTP request timed out")??;
+    let received_at = get_current_time_in_millis() as i64;
+
+    if read < NTP_PACKET_SIZE {
+        bail!("Invalid NTP response size: {}", read)
+    }
+
+    // Server receive and transmit timestamps
+    let server_received_at = ntp_to_unix_millis(&packet[32..40])? as i64;
+    let server_sent_at = ntp_to_unix_millis(&packet[40..48])? as i64;
+
+    Ok(((server_received_at - sent_at) + (server_sent_at - received_at)) / 2)
+}
+
+// Detect if the local clock is skewed by comparing it against
+// the time reported by peers in their handshake and optionally NTP servers
+// Offsets are in ms, positive if the local clock is behind
+pub struct ClockMonitor {
+    // maximum offset tolerated in ms
+    threshold: u64,
+    // refuse to create block templates while the clock is skewed
+    refuse_mining: bool,
+    ntp_servers: Vec<String>,
+    // offset reported by each peer, key is the peer id
+    peer_offsets: Mutex<IndexMap<u64, i64>>,
+    // offset from the last successful NTP check
+    ntp_offset: Mutex<Option<i64>>,
+    skewed: AtomicBool
+}
+
+impl ClockMonitor {
+    pub fn new(threshold: u64, refuse_mining: bool, ntp_servers: Vec<String>) -> Self {
+        let ntp_servers = ntp_servers.iter()
+            .flat_map(|servers| servers.split(","))
+            .map(|server| server.trim().to_owned())
+            .filter(|server| !server.is_empty())
+            .collect();
+
+        Self {
+            threshold,
+            refuse_mining,
+            ntp_servers,
+            peer_offsets: Mutex::new(IndexMap::new()),
+            ntp_offset: Mutex::new(None),
+            skewed: AtomicBool::new(false)
+        }
+    }
+
+    pub fn has_ntp_servers(&self) -> bool {
+        !self.ntp_servers.is_empty()
+    }
+
+    // Is the offset of the local clock above the threshold
+    pub fn is_skewed(&self) -> bool {
+        self.skewed.load(Ordering::Acquire)
+    }
+
+    pub fn is_mining_allowed(&self) -> bool {
+        !(self.refuse_mining && self.is_skewed())
+    }
+
+    // Register the time sent by a peer in its handshake
+    // It is in seconds, so the offset has a precision of one second
+    pub async fn add_peer_time(&self, peer_id: u64, peer_time: TimestampSeconds) {
+        // Use the middle of the second sent to reduce the rounding error
+        let offset = (peer_time * 1000 + 500) as i64 - get_current_time_in_millis() as i64;
+        {
+            let mut offsets = self.peer_offsets.lock().await;
+            offsets.shift_remove(&peer_id);
+            if offsets.len() >= CLOCK_SKEW_MAX_PEER_SAMPLES {
+                offsets.shift_remove_index(0);
+            }
+            offsets.insert(peer_id, offset);
+        }
+
+        self.update_state().await;
+    }
+
+    // Median of the offsets reported by peers
+    pub async fn get_peers_offset(&self) -> Option<i64> {
+        let offsets = self.peer_offsets.lock().await;
+        if offsets.len() < CLOCK_SKEW_MIN_PEER_SAMPLES {
+            return None
+        }
+
+        let mut values: Vec<i64> = offsets.values().copied().collect();
+        values.sort_unstable();
+        Some(values[values.len() / 2])
+    }
+
+    pub async fn get_ntp_offset(&self) -> Option<i64> {
+        *self.ntp_offset.lock().await
+    }
+
+    // Offset of the local clock
+    // NTP is preferred as peers may report a wrong time
+    pub async fn get_offset(&self) -> Option<i64> {
+        match self.get_ntp_offset().await {
+            Some(offset) => Some(offset),
+            None => self.get_peers_offset().await
+        }
+    }
+
+    // Compare the offset against the threshold and warn on changes
+    async fn update_state(&self) {
+        let Some(offset) = self.get_offset().await else {
+            return;
+        };
+
+        let skewed = offset.unsigned_abs() > self.threshold;
+        if self.skewed.swap(skewed, Ordering::AcqRel) == skewed {
+            return;
+        }
+
+        if skewed {
+            let direction = if offset > 0 { "behind" } else { "ahead" };
+            warn!("Local clock is {}ms {} of the network, blocks may be rejected. Please synchronize your system clock", offset.unsigned_abs(), direction);
+            if self.refuse_mining {
+                warn!("Mining is disabled until the local clock is synchronized");
+            }
+        } else {
+            info!("Local clock is synchronized again (offset of {}ms)", offset);
+        }
+    }
+
+    // Query the NTP servers in order until one responds
+    pub async fn check_ntp_servers(&self) {
+        for server in self.ntp_servers.iter() {
+            match query_ntp_server(server).await {
+                Ok(offset) => {
+                    debug!("Clock offset from NTP server {}: {}ms", server, offset);
+                    *self.ntp_offset.lock().await = Some(offset);
+                    self.update_state().await;
+                    return;
+                },
+                Err(e) => debug!("Error while querying NTP server {}: {}", server, e)
+            }
+        }
+
+        warn!("No NTP server responded, only peers are used to check the local clock");
+        *self.ntp_offset.lock().await = None;
+    }
+
+    // Check periodically the local clock against the NTP servers
+    pub async fn run_ntp_checks(self: Arc<Self>) {
+        let mut interval = interval(Duration::from_secs(NTP_CHECK_INTERVAL));
+        loop {
+            interval.tick().await;
+            self.check_ntp_servers().await;
+        }
+    }
+}
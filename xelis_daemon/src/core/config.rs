@@ -1,11 +1,23 @@
 use serde::{Deserialize, Serialize};
+use xelis_common::{
+    config::MAX_TRANSACTION_SIZE,
+    rpc_server::websocket::{DEFAULT_MAX_FRAME_SIZE, DEFAULT_MAX_QUEUED_MESSAGES},
+    transaction::EXTRA_DATA_LIMIT_SUM_SIZE
+};
 
 use crate::{
     config::{
         DEFAULT_CACHE_SIZE,
+        DEFAULT_CLOCK_SKEW_THRESHOLD_MS,
+        DEFAULT_GETWORK_RATE_LIMIT_MS,
+        DEFAULT_GETWORK_SHARE_TARGET_TIME,
         DEFAULT_P2P_BIND_ADDRESS,
+        DEFAULT_PUBLISHER_MAX_QUEUED_MESSAGES,
         DEFAULT_RPC_BIND_ADDRESS,
+        DEFAULT_RPC_READY_MAX_BLOCKS_BEHIND,
+        DEFAULT_RPC_STREAM_BUDGET,
         P2P_DEFAULT_CONCURRENCY_TASK_COUNT_LIMIT,
+        P2P_DEFAULT_FAST_SYNC_VERIFICATION_PEERS,
         P2P_DEFAULT_MAX_PEERS
     },
     p2p::diffie_hellman::{KeyVerificationAction, WrappedSecret}};
@@ -26,10 +38,54 @@ fn default_rpc_bind_address() -> String {
     DEFAULT_RPC_BIND_ADDRESS.to_owned()
 }
 
+fn default_rpc_ready_max_blocks_behind() -> u64 {
+    DEFAULT_RPC_READY_MAX_BLOCKS_BEHIND
+}
+
+fn default_rpc_stream_budget() -> usize {
+    DEFAULT_RPC_STREAM_BUDGET
+}
+
+fn default_rpc_ws_max_queued_messages() -> usize {
+    DEFAULT_MAX_QUEUED_MESSAGES
+}
+
+fn default_rpc_ws_max_frame_size() -> usize {
+    DEFAULT_MAX_FRAME_SIZE
+}
+
+fn default_publisher_max_queued_messages() -> usize {
+    DEFAULT_PUBLISHER_MAX_QUEUED_MESSAGES
+}
+
+fn default_getwork_rate_limit_ms() -> u64 {
+    DEFAULT_GETWORK_RATE_LIMIT_MS
+}
+
+fn default_getwork_share_target_time() -> u64 {
+    DEFAULT_GETWORK_SHARE_TARGET_TIME
+}
+
 fn default_cache_size() -> usize {
     DEFAULT_CACHE_SIZE
 }
 
+fn default_clock_skew_threshold() -> u64 {
+    DEFAULT_CLOCK_SKEW_THRESHOLD_MS
+}
+
+fn default_max_tx_size() -> usize {
+    MAX_TRANSACTION_SIZE
+}
+
+fn default_max_tx_extra_data_size() -> usize {
+    EXTRA_DATA_LIMIT_SUM_SIZE
+}
+
+fn default_fast_sync_verification_peers() -> usize {
+    P2P_DEFAULT_FAST_SYNC_VERIFICATION_PEERS
+}
+
 fn default_p2p_concurrency_task_count_limit() -> usize {
     P2P_DEFAULT_CONCURRENCY_TASK_COUNT_LIMIT
 }
@@ -40,11 +96,36 @@ pub struct RPCConfig {
     #[clap(long)]
     #[serde(default)]
     pub disable_getwork_server: bool,
+    /// Minimum delay in milliseconds between two jobs sent to a GetWork miner
+    /// when the block template changes due to new transactions.
+    #[clap(long, default_value_t = DEFAULT_GETWORK_RATE_LIMIT_MS)]
+    #[serde(default = "default_getwork_rate_limit_ms")]
+    pub getwork_rate_limit_ms: u64,
+    /// Initial and minimum share difficulty for GetWork miners.
+    /// If set, shares below the block difficulty are accepted
+    /// and the difficulty of each miner is adjusted to its hashrate.
+    #[clap(long)]
+    pub getwork_min_share_difficulty: Option<u64>,
+    /// Expected time in seconds between two shares of a GetWork miner.
+    #[clap(long, default_value_t = DEFAULT_GETWORK_SHARE_TARGET_TIME)]
+    #[serde(default = "default_getwork_share_target_time")]
+    pub getwork_share_target_time: u64,
     /// Disable RPC Server
     /// This will also disable the GetWork Server as it is loaded on RPC server.
     #[clap(long)]
     #[serde(default)]
     pub disable_rpc_server: bool,
+    /// Enable the RPC methods to read and update the log levels at runtime.
+    /// Only enable it if the RPC server is not publicly reachable.
+    #[clap(long)]
+    #[serde(default)]
+    pub enable_rpc_log_methods: bool,
+    /// Enable the RPC methods to read and update the node parameters at runtime.
+    /// Updated parameters are persisted and applied again at the next start.
+    /// Only enable it if the RPC server is not publicly reachable.
+    #[clap(long)]
+    #[serde(default)]
+    pub enable_rpc_admin_methods: bool,
     /// Rpc bind address to listen for HTTP requests
     #[clap(long, default_value_t = String::from(DEFAULT_RPC_BIND_ADDRESS))]
     #[serde(default = "default_rpc_bind_address")]
@@ -52,7 +133,43 @@ pub struct RPCConfig {
     /// Number of workers to spawn for the HTTP server.
     /// If not provided, it will use the default value from Actix.
     #[clap(long)]
-    pub rpc_threads: Option<usize>
+    pub rpc_threads: Option<usize>,
+    /// Maximum blocks behind the network allowed for the node
+    /// to be reported as ready by the `/ready` endpoint.
+    #[clap(long, default_value_t = DEFAULT_RPC_READY_MAX_BLOCKS_BEHIND)]
+    #[serde(default = "default_rpc_ready_max_blocks_behind")]
+    pub rpc_ready_max_blocks_behind: u64,
+    /// Maximum bytes of blocks serialized per tick by the `/blocks_range` streaming endpoint.
+    /// Lower it to reduce the load of explorers backfilling the chain history.
+    #[clap(long, default_value_t = DEFAULT_RPC_STREAM_BUDGET)]
+    #[serde(default = "default_rpc_stream_budget")]
+    pub rpc_stream_budget: usize,
+    /// Maximum outbound messages queued per WebSocket connection.
+    /// A connection reaching this limit is considered as a slow consumer and is disconnected.
+    #[clap(long, default_value_t = DEFAULT_MAX_QUEUED_MESSAGES)]
+    #[serde(default = "default_rpc_ws_max_queued_messages")]
+    pub rpc_ws_max_queued_messages: usize,
+    /// Maximum size in bytes of a frame received on a WebSocket connection.
+    #[clap(long, default_value_t = DEFAULT_MAX_FRAME_SIZE)]
+    #[serde(default = "default_rpc_ws_max_frame_size")]
+    pub rpc_ws_max_frame_size: usize,
+    /// Stratum bind address to listen for miners using the Stratum protocol.
+    /// Stratum server is disabled if not provided.
+    #[clap(long)]
+    pub stratum_bind_address: Option<String>,
+    /// Minimum share difficulty for Stratum miners.
+    /// Miners can suggest a higher one, block difficulty is used if not provided.
+    #[clap(long)]
+    pub stratum_min_share_difficulty: Option<u64>,
+    /// Publisher bind address to stream raw serialized notifications (new blocks, new transactions, orphaned blocks).
+    /// Use `unix:<path>` to listen on a Unix socket. Publisher is disabled if not provided.
+    #[clap(long)]
+    pub publisher_bind_address: Option<String>,
+    /// Maximum notifications queued per publisher subscriber.
+    /// A subscriber lagging behind loses the oldest notifications.
+    #[clap(long, default_value_t = DEFAULT_PUBLISHER_MAX_QUEUED_MESSAGES)]
+    #[serde(default = "default_publisher_max_queued_messages")]
+    pub publisher_max_queued_messages: usize
 }
 
 #[derive(Debug, clap::Args, Serialize, Deserialize)]
@@ -92,6 +209,13 @@ pub struct P2pConfig {
     #[clap(long)]
     #[serde(default)]
     pub allow_fast_sync: bool,
+    /// Number of peers cross-checking each state chunk received during a fast sync.
+    /// 
+    /// Chunks are committed only if all the peers sent the same one.
+    /// Set it to 0 to trust only the selected peer.
+    #[clap(long, default_value_t = P2P_DEFAULT_FAST_SYNC_VERIFICATION_PEERS)]
+    #[serde(default = "default_fast_sync_verification_peers")]
+    pub fast_sync_verification_peers: usize,
     /// Allow boost chain sync mode.
     /// 
     /// This will request in parallel all blocks instead of sequentially.
@@ -167,5 +291,74 @@ pub struct Config {
     /// Skip the TXs verification when building a block template.
     #[clap(long)]
     #[serde(default)]
-    pub skip_block_template_txs_verification: bool
+    pub skip_block_template_txs_verification: bool,
+    /// Maximum offset in milliseconds of the local clock against peers
+    /// and NTP servers before warning about a skewed clock.
+    #[clap(long, default_value_t = DEFAULT_CLOCK_SKEW_THRESHOLD_MS)]
+    #[serde(default = "default_clock_skew_threshold")]
+    pub clock_skew_threshold: u64,
+    /// NTP servers (host:port) used to check periodically the local clock.
+    /// They are preferred over the time reported by peers.
+    #[clap(long)]
+    #[serde(default)]
+    pub ntp_servers: Vec<String>,
+    /// Refuse to create block templates while the local clock is skewed.
+    #[clap(long)]
+    #[serde(default)]
+    pub refuse_mining_on_clock_skew: bool,
+    /// Minimum fee per byte required to accept a TX in mempool.
+    /// The network fee is always required, 0 to only require it.
+    #[clap(long, default_value_t = 0)]
+    #[serde(default)]
+    pub min_tx_fee_per_byte: u64,
+    /// Maximum size in bytes of a TX accepted in mempool.
+    /// It can't be above the network limit.
+    #[clap(long, default_value_t = MAX_TRANSACTION_SIZE)]
+    #[serde(default = "default_max_tx_size")]
+    pub max_tx_size: usize,
+    /// Maximum size in bytes of all the extra data of a TX accepted in mempool.
+    /// It can't be above the network limit.
+    #[clap(long, default_value_t = EXTRA_DATA_LIMIT_SUM_SIZE)]
+    #[serde(default = "default_max_tx_extra_data_size")]
+    pub max_tx_extra_data_size: usize,
+    /// Maximum TXs pending in mempool for the same sender (0 = no limit).
+    #[clap(long, default_value_t = 0)]
+    #[serde(default)]
+    pub max_pending_txs_per_account: usize,
+    /// Maximum size in bytes of all the TXs in mempool (0 = no limit).
+    /// When full, the TXs with the lowest fee rate then the oldest are evicted
+    /// to make room for a TX paying a higher fee rate.
+    #[clap(long, default_value_t = 0)]
+    #[serde(default)]
+    pub max_mempool_size: usize,
+    /// Maximum size in bytes of the evicted TXs kept on disk (0 = disabled).
+    /// They are added back to the mempool when space frees up.
+    #[clap(long, default_value_t = 0)]
+    #[serde(default)]
+    pub mempool_spill_size: usize,
+    /// Only accept and relay blocks, loose TXs are ignored.
+    /// The TXs of the blocks are still retrieved from peers.
+    #[clap(long)]
+    #[serde(default)]
+    pub blocks_only: bool,
+    /// Archive mode: keep the full history of the chain.
+    /// Pruning and fast sync are refused so every versioned state stays available.
+    #[clap(long)]
+    #[serde(default)]
+    pub archive: bool,
+    /// Override the activation height of a block version on devnet.
+    /// Format is `version=height`, example: `--dev-fork-heights 3=100`.
+    #[clap(long)]
+    #[serde(default)]
+    pub dev_fork_heights: Vec<String>,
+    /// Override on devnet the number of ancestors used to compute
+    /// the median time past of a block.
+    #[clap(long)]
+    #[serde(default)]
+    pub dev_median_time_past_window: Option<usize>,
+    /// Override on devnet the maximum drift in milliseconds
+    /// of a block timestamp in the future.
+    #[clap(long)]
+    #[serde(default)]
+    pub dev_timestamp_future_limit: Option<u64>
 }
\ No newline at end of file
@@ -0,0 +1,278 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    future::Future,
+    ops::{Deref, DerefMut},
+    panic::Location,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Mutex
+    },
+    time::{Duration, Instant}
+};
+use tokio::{
+    runtime::Handle,
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard}
+};
+use xelis_common::api::daemon::{
+    LockAccess,
+    LockHolder,
+    LockStats,
+    RuntimeStats,
+    RuntimeWorkerStats
+};
+
+// Tools to diagnose the node freezing under load:
+// the contention on the main locks and the state of the tokio runtime
+
+struct Holder {
+    access: LockAccess,
+    location: &'static Location<'static>,
+    since: Instant
+}
+
+#[derive(Default)]
+struct LockCounters {
+    read_acquisitions: AtomicU64,
+    write_acquisitions: AtomicU64,
+    read_wait_total: AtomicU64,
+    read_wait_max: AtomicU64,
+    write_wait_total: AtomicU64,
+    write_wait_max: AtomicU64,
+    waiting: AtomicUsize,
+    next_holder_id: AtomicU64,
+    holders: Mutex<HashMap<u64, Holder>>
+}
+
+impl LockCounters {
+    // Record the lock acquisition and register its holder
+    fn acquired(&self, access: LockAccess, location: &'static Location<'static>, waited: Duration) -> u64 {
+        let waited = waited.as_micros() as u64;
+        let (acquisitions, total, max) = match access {
+            LockAccess::Read => (&self.read_acquisitions, &self.read_wait_total, &self.read_wait_max),
+            LockAccess::Write => (&self.write_acquisitions, &self.write_wait_total, &self.write_wait_max)
+        };
+        acquisitions.fetch_add(1, Ordering::Relaxed);
+        total.fetch_add(waited, Ordering::Relaxed);
+        max.fetch_max(waited, Ordering::Relaxed);
+
+        let id = self.next_holder_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut holders) = self.holders.lock() {
+            holders.insert(id, Holder { access, location, since: Instant::now() });
+        }
+        id
+    }
+
+    fn released(&self, id: u64) {
+        if let Ok(mut holders) = self.holders.lock() {
+            holders.remove(&id);
+        }
+    }
+}
+
+// Count the task as waiting until the lock is acquired or the future dropped
+struct WaitingGuard<'a>(&'a AtomicUsize);
+
+impl<'a> WaitingGuard<'a> {
+    fn new(waiting: &'a AtomicUsize) -> Self {
+        waiting.fetch_add(1, Ordering::Relaxed);
+        Self(waiting)
+    }
+}
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// RwLock recording the wait times and the current holders
+// The caller location is used to identify the holders
+pub struct InstrumentedRwLock<T> {
+    name: &'static str,
+    lock: RwLock<T>,
+    counters: LockCounters
+}
+
+impl<T> InstrumentedRwLock<T> {
+    pub fn new(name: &'static str, value: T) -> Self {
+        Self {
+            name,
+            lock: RwLock::new(value),
+            counters: LockCounters::default()
+        }
+    }
+
+    #[track_caller]
+    pub fn read(&self) -> impl Future<Output = InstrumentedReadGuard<'_, T>> + '_ {
+        let location = Location::caller();
+        async move {
+            let start = Instant::now();
+            let guard = {
+                let _waiting = WaitingGuard::new(&self.counters.waiting);
+                self.lock.read().await
+            };
+            let id = self.counters.acquired(LockAccess::Read, location, start.elapsed());
+
+            InstrumentedReadGuard { guard, counters: &self.counters, id }
+        }
+    }
+
+    #[track_caller]
+    pub fn write(&self) -> impl Future<Output = InstrumentedWriteGuard<'_, T>> + '_ {
+        let location = Location::caller();
+        async move {
+            let start = Instant::now();
+            let guard = {
+                let _waiting = WaitingGuard::new(&self.counters.waiting);
+                self.lock.write().await
+            };
+            let id = self.counters.acquired(LockAccess::Write, location, start.elapsed());
+
+            InstrumentedWriteGuard { guard, counters: &self.counters, id }
+        }
+    }
+
+    pub fn get_stats(&self) -> LockStats<'static> {
+        let counters = &self.counters;
+        let holders = match counters.holders.lock() {
+            Ok(holders) => holders.values()
+                .map(|holder| LockHolder {
+                    access: holder.access,
+                    location: Cow::Owned(holder.location.to_string()),
+                    held_for_ms: holder.since.elapsed().as_millis() as u64
+                })
+                .collect(),
+            Err(_) => Vec::new()
+        };
+
+        LockStats {
+            name: Cow::Borrowed(self.name),
+            read_acquisitions: counters.read_acquisitions.load(Ordering::Relaxed),
+            write_acquisitions: counters.write_acquisitions.load(Ordering::Relaxed),
+            read_wait_total_us: counters.read_wait_total.load(Ordering::Relaxed),
+            read_wait_max_us: counters.read_wait_max.load(Ordering::Relaxed),
+            write_wait_total_us: counters.write_wait_total.load(Ordering::Relaxed),
+            write_wait_max_us: counters.write_wait_max.load(Ordering::Relaxed),
+            waiting: counters.waiting.load(Ordering::Relaxed),
+            holders
+        }
+    }
+}
+
+pub struct InstrumentedReadGuard<'a, T> {
+    guard: RwLockReadGuard<'a, T>,
+    counters: &'a LockCounters,
+    id: u64
+}
+
+impl<T> Deref for InstrumentedReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> Drop for InstrumentedReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.counters.released(self.id);
+    }
+}
+
+pub struct InstrumentedWriteGuard<'a, T> {
+    guard: RwLockWriteGuard<'a, T>,
+    counters: &'a LockCounters,
+    id: u64
+}
+
+impl<T> Deref for InstrumentedWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for InstrumentedWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T> Drop for InstrumentedWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.counters.released(self.id);
+    }
+}
+
+// Sample the metrics of the tokio runtime running the node
+// The RPC server runs on its own runtime, so the handle is kept at creation
+pub struct RuntimeMonitor {
+    handle: Handle,
+    // time of the previous sample and busy duration of each worker
+    previous: Mutex<(Instant, Vec<Duration>)>
+}
+
+impl RuntimeMonitor {
+    pub fn new(handle: Handle) -> Self {
+        Self {
+            handle,
+            previous: Mutex::new((Instant::now(), Vec::new()))
+        }
+    }
+
+    // Workers utilization is computed since the previous sample
+    pub fn sample(&self) -> RuntimeStats {
+        let metrics = self.handle.metrics();
+        let now = Instant::now();
+        let busy: Vec<Duration> = (0..metrics.num_workers())
+            .map(|worker| metrics.worker_total_busy_duration(worker))
+            .collect();
+
+        let (elapsed, previous_busy) = match self.previous.lock() {
+            Ok(mut previous) => {
+                let (at, busy) = std::mem::replace(&mut *previous, (now, busy.clone()));
+                (now.duration_since(at), busy)
+            },
+            Err(_) => (Duration::ZERO, Vec::new())
+        };
+
+        let workers = busy.iter()
+            .enumerate()
+            .map(|(worker, busy)| {
+                let previous = previous_busy.get(worker).copied().unwrap_or_default();
+                let utilization = if elapsed.is_zero() {
+                    0.0
+                } else {
+                    (busy.saturating_sub(previous).as_secs_f64() / elapsed.as_secs_f64()).min(1.0)
+                };
+
+                #[cfg(tokio_unstable)]
+                let local_queue_depth = Some(metrics.worker_local_queue_depth(worker));
+                #[cfg(not(tokio_unstable))]
+                let local_queue_depth = None;
+
+                RuntimeWorkerStats {
+                    busy_duration_ms: busy.as_millis() as u64,
+                    utilization,
+                    park_count: metrics.worker_park_count(worker),
+                    local_queue_depth
+                }
+            })
+            .collect();
+
+        #[cfg(tokio_unstable)]
+        let blocking_queue_depth = Some(metrics.blocking_queue_depth());
+        #[cfg(not(tokio_unstable))]
+        let blocking_queue_depth = None;
+
+        RuntimeStats {
+            num_workers: metrics.num_workers(),
+            alive_tasks: metrics.num_alive_tasks(),
+            global_queue_depth: metrics.global_queue_depth(),
+            blocking_queue_depth,
+            workers
+        }
+    }
+}
@@ -5,16 +5,46 @@ use xelis_common::{
     varuint::VarUint,
     block::BlockVersion
 };
+use super::hard_fork::Feature;
 
-mod v1;
-mod v2;
+pub mod v1;
+pub mod v2;
+
+pub use v1::KalmanV1;
+pub use v2::KalmanV2;
+
+// Difficulty adjustment algorithm
+// Implementations must be deterministic as every node must agree on the difficulty
+// This allows to try alternative algorithms (see the difficulty_simulator binary)
+// without touching the validation code
+pub trait DifficultyAlgorithm: Send + Sync {
+    // Name of the algorithm, used for display
+    fn name(&self) -> &'static str;
+
+    // Initial estimate covariance
+    // It is used by first blocks on a new version
+    fn initial_covariance(&self) -> VarUint;
+
+    // Calculate the required difficulty for the next block based on the solve time of the previous block
+    // Returns the new difficulty and the new estimate covariance
+    fn calculate_difficulty(&self, solve_time: TimestampMillis, previous_difficulty: Difficulty, p: VarUint, minimum_difficulty: Difficulty) -> (Difficulty, VarUint);
+}
+
+// Default difficulty algorithm used by the chain for a block version
+pub fn get_difficulty_algorithm(version: BlockVersion) -> &'static dyn DifficultyAlgorithm {
+    if Feature::DifficultyV2.is_enabled_in(version) {
+        &KalmanV2
+    } else {
+        &KalmanV1
+    }
+}
 
 // Kalman filter with unsigned integers only
 // z: The observed value (latest hashrate calculated on current block time).
 // x_est_prev: The previous hashrate estime.
 // p_prev: The previous estimate covariance.
 // Returns the new state estimate and covariance
-fn kalman_filter(z: VarUint, x_est_prev: VarUint, p_prev: VarUint, shift: u64, left_shift: VarUint, process_noise_covar: VarUint) -> (VarUint, VarUint) {
+pub fn kalman_filter(z: VarUint, x_est_prev: VarUint, p_prev: VarUint, shift: u64, left_shift: VarUint, process_noise_covar: VarUint) -> (VarUint, VarUint) {
     trace!("z: {}, x_est_prev: {}, p_prev: {}", z, x_est_prev, p_prev);
     // Scale up
     let z = z * left_shift;
@@ -48,18 +78,30 @@ fn kalman_filter(z: VarUint, x_est_prev: VarUint, p_prev: VarUint, shift: u64, l
 // This function will determine which algorithm to use based on the version
 pub fn calculate_difficulty(parent_timestamp: TimestampMillis, timestamp: TimestampMillis, previous_difficulty: Difficulty, p: VarUint, minimum_difficulty: Difficulty, version: BlockVersion) -> (Difficulty, VarUint) {
     let solve_time = (timestamp - parent_timestamp).max(1);
-
-    match version {
-        BlockVersion::V0 => v1::calculate_difficulty(solve_time, previous_difficulty, p, minimum_difficulty),
-        _ => v2::calculate_difficulty(solve_time, previous_difficulty, p, minimum_difficulty),
-    }
+    get_difficulty_algorithm(version).calculate_difficulty(solve_time, previous_difficulty, p, minimum_difficulty)
 }
 
 // Get the process noise covariance based on the version
 // It is used by first blocks on a new version
 pub fn get_covariance_p(version: BlockVersion) -> VarUint {
-    match version {
-        BlockVersion::V0 => v1::P,
-        _ => v2::P
+    get_difficulty_algorithm(version).initial_covariance()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{BLOCK_TIME_MILLIS, MAINNET_MINIMUM_DIFFICULTY};
+    use super::*;
+
+    #[test]
+    fn test_default_algorithm_by_version() {
+        assert_eq!(get_difficulty_algorithm(BlockVersion::V0).name(), KalmanV1.name());
+        assert_eq!(get_covariance_p(BlockVersion::V0), v1::P);
+
+        let algorithm = get_difficulty_algorithm(BlockVersion::V2);
+        assert_eq!(algorithm.name(), KalmanV2.name());
+        assert_eq!(
+            calculate_difficulty(0, BLOCK_TIME_MILLIS, MAINNET_MINIMUM_DIFFICULTY, v2::P, MAINNET_MINIMUM_DIFFICULTY, BlockVersion::V2),
+            algorithm.calculate_difficulty(BLOCK_TIME_MILLIS, MAINNET_MINIMUM_DIFFICULTY, v2::P, MAINNET_MINIMUM_DIFFICULTY)
+        );
     }
 }
\ No newline at end of file
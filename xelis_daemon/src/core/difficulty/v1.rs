@@ -7,7 +7,7 @@ use xelis_common::{
     utils::format_difficulty,
     varuint::VarUint
 };
-use crate::{config::BLOCK_TIME_MILLIS, core::difficulty::kalman_filter};
+use crate::{config::BLOCK_TIME_MILLIS, core::difficulty::{kalman_filter, DifficultyAlgorithm}};
 
 const SHIFT: u64 = 32;
 // This is equal to 2 ** 32
@@ -35,6 +35,23 @@ pub fn calculate_difficulty(solve_time: TimestampMillis, previous_difficulty: Di
     (difficulty, p_new)
 }
 
+// Kalman filter based algorithm, v1 of the chain
+pub struct KalmanV1;
+
+impl DifficultyAlgorithm for KalmanV1 {
+    fn name(&self) -> &'static str {
+        "kalman-v1"
+    }
+
+    fn initial_covariance(&self) -> VarUint {
+        P
+    }
+
+    fn calculate_difficulty(&self, solve_time: TimestampMillis, previous_difficulty: Difficulty, p: VarUint, minimum_difficulty: Difficulty) -> (Difficulty, VarUint) {
+        calculate_difficulty(solve_time, previous_difficulty, p, minimum_difficulty)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::config::MAINNET_MINIMUM_DIFFICULTY;
@@ -9,7 +9,7 @@ use xelis_common::{
 };
 use crate::{
     config::{BLOCK_TIME_MILLIS, MILLIS_PER_SECOND},
-    core::difficulty::kalman_filter
+    core::difficulty::{kalman_filter, DifficultyAlgorithm}
 };
 
 const SHIFT: u64 = 20;
@@ -38,6 +38,23 @@ pub fn calculate_difficulty(solve_time: TimestampMillis, previous_difficulty: Di
     (difficulty, p_new)
 }
 
+// Kalman filter based algorithm, v2 of the chain
+pub struct KalmanV2;
+
+impl DifficultyAlgorithm for KalmanV2 {
+    fn name(&self) -> &'static str {
+        "kalman-v2"
+    }
+
+    fn initial_covariance(&self) -> VarUint {
+        P
+    }
+
+    fn calculate_difficulty(&self, solve_time: TimestampMillis, previous_difficulty: Difficulty, p: VarUint, minimum_difficulty: Difficulty) -> (Difficulty, VarUint) {
+        calculate_difficulty(solve_time, previous_difficulty, p, minimum_difficulty)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::config::MAINNET_MINIMUM_DIFFICULTY;
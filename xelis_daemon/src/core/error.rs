@@ -1,4 +1,7 @@
-use crate::p2p::error::P2pError;
+use crate::{
+    core::hard_fork::Feature,
+    p2p::error::P2pError
+};
 use std::sync::PoisonError;
 use thiserror::Error;
 use xelis_common::{
@@ -11,7 +14,8 @@ use xelis_common::{
         XelisHashError
     },
     account::Nonce,
-    block::TopoHeight,
+    block::{BlockVersion, TopoHeight},
+    contract::ModuleValidationError,
     difficulty::DifficultyError,
     prompt::PromptError,
     rpc_server::InternalRpcError,
@@ -124,6 +128,12 @@ pub enum DiskContext {
     ContractOutput,
     #[error("get contract balance")]
     ContractBalance,
+    #[error("get contract management at topoheight")]
+    ContractManagementAtTopoHeight,
+    #[error("get contract receipt")]
+    ContractReceipt,
+    #[error("get execution credits at topoheight")]
+    ExecutionCreditsAtTopoHeight,
 }
 
 #[repr(usize)]
@@ -376,7 +386,59 @@ pub enum BlockchainError {
     #[error("MultiSig not found")]
     MultiSigNotFound,
     #[error("Error in module: {}", _0)]
-    ModuleError(String)
+    ModuleError(String),
+    #[error("Only InvokeContract transactions can be simulated")]
+    NotInvokeContractTransaction,
+    #[error("Transaction {} was not executed in any block", _0)]
+    TxNotExecuted(Hash),
+    #[error("Tx {} can't be replaced, only the last pending transaction of an account without other pending transactions can be replaced", _0)]
+    TxReplacementNotAllowed(Hash),
+    #[error("Fee is too low to replace Tx {}, expected at least {}, got {}", _0, _1, _2)]
+    TxReplacementFeeTooLow(Hash, u64, u64),
+    #[error("Invalid topoheight range from {} to {}", _0, _1)]
+    InvalidTopoHeightRange(TopoHeight, TopoHeight),
+    #[error("Topoheight {} is below the pruned topoheight {}", _0, _1)]
+    TopoHeightPruned(TopoHeight, TopoHeight),
+    #[error("Invalid chain file: {}", _0)]
+    InvalidChainFile(&'static str),
+    #[error("Mempool is full")]
+    MempoolFull,
+    #[error("Caches are disabled")]
+    CacheDisabled,
+    #[error("Invalid runtime parameter: {}", _0)]
+    InvalidRuntimeParam(&'static str),
+    #[error("Local clock is skewed, mining is disabled")]
+    ClockSkewed,
+    #[error("Fee is below the node policy, expected at least {}, got {}", format_xelis(*_0), format_xelis(*_1))]
+    TxFeeBelowPolicy(u64, u64),
+    #[error("Extra data size is {} while the node policy limit is {}", human_bytes(*_0 as f64), human_bytes(*_1 as f64))]
+    TxExtraDataAbovePolicy(usize, usize),
+    #[error("Sender has reached the limit of {} pending TXs in mempool", _0)]
+    TooManyPendingTxs(usize),
+    #[error("Invalid fork schedule: {}", _0)]
+    InvalidForkSchedule(&'static str),
+    #[error("Feature {:?} is not active in block version {}", _0, _1)]
+    FeatureNotActive(Feature, BlockVersion),
+    #[error("Node is running in blocks-only mode, loose TXs are not accepted")]
+    BlocksOnlyMode,
+    #[error("Node is running in archive mode, chain can't be pruned")]
+    ArchiveMode,
+    #[error("State at topoheight {} is pruned, lowest available is {}", _0, _1)]
+    StatePruned(TopoHeight, TopoHeight),
+    #[error("Invalid contract module: {}", _0)]
+    InvalidContractModule(#[from] ModuleValidationError),
+    #[error("Contract is paused by its owner")]
+    ContractPaused,
+    #[error("Contract has no management state")]
+    ContractNotManaged,
+    #[error("Only the contract owner can manage it")]
+    NotContractOwner,
+    #[error("Contract is already in the requested state")]
+    ContractManagementNoop,
+    #[error("Insufficient execution credits, got {} requested {}", _0, _1)]
+    InsufficientExecutionCredits(u64, u64),
+    #[error("Timestamp {} is less than the median time past {}", _1, _0)]
+    TimestampIsLessThanMedianTimePast(TimestampMillis, TimestampMillis)
 }
 
 impl BlockchainError {
@@ -419,6 +481,12 @@ impl From<VerificationError<BlockchainError>> for BlockchainError {
             VerificationError::AnyError(e) => BlockchainError::Any(e),
             VerificationError::GasOverflow => BlockchainError::Overflow,
             VerificationError::InvalidInvokeContract => BlockchainError::InvalidInvokeContract,
+            VerificationError::ModuleValidation(e) => BlockchainError::InvalidContractModule(e),
+            VerificationError::ContractPaused => BlockchainError::ContractPaused,
+            VerificationError::ContractNotManaged => BlockchainError::ContractNotManaged,
+            VerificationError::NotContractOwner => BlockchainError::NotContractOwner,
+            VerificationError::ContractManagementNoop => BlockchainError::ContractManagementNoop,
+            VerificationError::InsufficientExecutionCredits(credits, amount) => BlockchainError::InsufficientExecutionCredits(credits, amount),
         }
     }
 }
\ No newline at end of file
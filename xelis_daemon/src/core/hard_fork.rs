@@ -1,45 +1,289 @@
+use std::borrow::Cow;
 use anyhow::Result;
 use xelis_common::{
     api::daemon::HardFork,
     block::{Algorithm, BlockVersion},
+    config::{MAX_BLOCK_SIZE, MAX_DYNAMIC_BLOCK_SIZE, MAX_TRANSACTION_SIZE},
+    contract::{ContractCapabilities, ContractCapability},
     network::Network,
-    transaction::TxVersion
+    time::TimestampMillis,
+    transaction::{Transaction, TransactionType, TxVersion}
 };
-use crate::config::get_hard_forks;
-
-// Get the hard fork at a given height
-pub fn get_hard_fork_at_height(network: &Network, height: u64) -> Option<&HardFork> {
-    let mut hardfork: Option<&HardFork> = None;
-    for conf in get_hard_forks(network) {
-        if height >= conf.height {
-            hardfork = Some(conf);
-        } else {
-            break;
+use crate::config::{
+    get_contract_capabilities,
+    get_hard_forks,
+    MEDIAN_TIME_PAST_WINDOW,
+    TIMESTAMP_IN_FUTURE_LIMIT
+};
+use super::error::BlockchainError;
+
+// Consensus rules that are enabled by a hard fork
+// Each feature is bound to the first block version enabling it,
+// so new rules are checked through the schedule instead of comparing versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    // xelis-hash v2 as PoW algorithm
+    XelisHashV2,
+    // second version of the difficulty adjustment algorithm
+    DifficultyV2,
+    // output balance is reported to the next topoheight instead of being flagged
+    OutputBalanceReport,
+    // TXs of the side blocks are ordered in the DAG
+    OrderedSideBlocks,
+    // a TX already included in a block tip can't be included again
+    TipsTxsDuplication,
+    // MultiSig transactions
+    MultiSig,
+    // Contracts deployment and invocation
+    SmartContracts,
+    // block size limit follows the median size of the last blocks
+    DynamicBlockSize,
+    // block timestamp can't be below the median time past of its ancestors
+    MedianTimePast,
+    // constructor called with deposits when deploying a contract
+    ContractConstructor,
+    // contracts can be paused and unpaused by their owner
+    ContractManagement,
+    // prepaid credits drawn to pay the gas of contract invocations
+    ExecutionCredits
+}
+
+impl Feature {
+    // First block version enabling the feature
+    pub const fn get_block_version(&self) -> BlockVersion {
+        match self {
+            Self::XelisHashV2
+            | Self::DifficultyV2
+            | Self::OutputBalanceReport
+            | Self::OrderedSideBlocks => BlockVersion::V1,
+            Self::TipsTxsDuplication
+            | Self::MultiSig => BlockVersion::V2,
+            Self::SmartContracts => BlockVersion::V3,
+            Self::DynamicBlockSize
+            | Self::MedianTimePast
+            | Self::ContractConstructor
+            | Self::ContractManagement
+            | Self::ExecutionCredits => BlockVersion::V4
+        }
+    }
+
+    // Is the feature enabled in the block version
+    pub fn is_enabled_in(&self, version: BlockVersion) -> bool {
+        version >= self.get_block_version()
+    }
+
+    // Feature required by a TX type, if any
+    pub fn required_by(tx: &Transaction) -> Option<Self> {
+        match tx.get_data() {
+            TransactionType::MultiSig(_) => Some(Self::MultiSig),
+            TransactionType::DeployContract(payload) if payload.invoke.is_some() => Some(Self::ContractConstructor),
+            TransactionType::ManageContract(_) => Some(Self::ContractManagement),
+            TransactionType::ExecutionCredits(_) => Some(Self::ExecutionCredits),
+            TransactionType::InvokeContract(_)
+            | TransactionType::DeployContract(_) => Some(Self::SmartContracts),
+            _ => None
         }
     }
+}
 
-    hardfork
+// Block timestamp rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimestampRules {
+    // Ancestors used to compute the median time past
+    pub median_time_past_window: usize,
+    // Maximum drift in milliseconds of a block timestamp in the future
+    pub future_drift_limit: TimestampMillis
 }
 
-// Get the version of the hard fork at a given height
-// and returns true if there is a hard fork (version change) at that height
-pub fn has_hard_fork_at_height(network: &Network, height: u64) -> (bool, BlockVersion) {
-    match get_hard_fork_at_height(network, height) {
-        Some(hard_fork) => (hard_fork.height == height, hard_fork.version),
-        None => (false, BlockVersion::V0)
+impl Default for TimestampRules {
+    fn default() -> Self {
+        Self {
+            median_time_past_window: MEDIAN_TIME_PAST_WINDOW,
+            future_drift_limit: TIMESTAMP_IN_FUTURE_LIMIT
+        }
     }
 }
 
-// This function returns the block version at a given height
-pub fn get_version_at_height(network: &Network, height: u64) -> BlockVersion {
-    has_hard_fork_at_height(network, height).1
+// Schedule of the hard forks for a network
+// It is the only place deciding which block version and consensus rules
+// are active at a height, verification and block validation must go through it
+#[derive(Debug, Clone)]
+pub struct ForkSchedule {
+    hard_forks: Cow<'static, [HardFork]>,
+    // Contract capabilities allow-list with the block version enabling them
+    contract_capabilities: &'static [(ContractCapability, BlockVersion)],
+    // Block timestamp rules
+    timestamp_rules: TimestampRules
+}
+
+impl ForkSchedule {
+    // Schedule configured for the network
+    pub fn new(network: &Network) -> Self {
+        Self {
+            hard_forks: Cow::Borrowed(get_hard_forks(network)),
+            contract_capabilities: get_contract_capabilities(network),
+            timestamp_rules: TimestampRules::default()
+        }
+    }
+
+    // Override the timestamp rules, only allowed on devnet
+    pub fn with_timestamp_overrides(mut self, network: &Network, median_time_past_window: Option<usize>, future_drift_limit: Option<TimestampMillis>) -> Result<Self, BlockchainError> {
+        if median_time_past_window.is_none() && future_drift_limit.is_none() {
+            return Ok(self)
+        }
+
+        if *network != Network::Dev {
+            return Err(BlockchainError::InvalidForkSchedule("timestamp rules can only be overridden on devnet"))
+        }
+
+        if let Some(window) = median_time_past_window {
+            if window == 0 {
+                return Err(BlockchainError::InvalidForkSchedule("median time past window can't be zero"))
+            }
+            self.timestamp_rules.median_time_past_window = window;
+        }
+
+        if let Some(limit) = future_drift_limit {
+            self.timestamp_rules.future_drift_limit = limit;
+        }
+
+        Ok(self)
+    }
+
+    // Schedule with custom activation heights, only allowed on devnet
+    // Each override is in the format `version=height`, example: `3=100`
+    pub fn with_overrides(network: &Network, overrides: &[String]) -> Result<Self, BlockchainError> {
+        let mut schedule = Self::new(network);
+        if overrides.is_empty() {
+            return Ok(schedule)
+        }
+
+        if *network != Network::Dev {
+            return Err(BlockchainError::InvalidForkSchedule("activation heights can only be overridden on devnet"))
+        }
+
+        let hard_forks = schedule.hard_forks.to_mut();
+        for value in overrides {
+            let (version, height) = value.split_once('=')
+                .ok_or(BlockchainError::InvalidForkSchedule("expected format is version=height"))?;
+
+            let version = version.trim().trim_start_matches(['v', 'V']).parse::<u8>().ok()
+                .and_then(|v| BlockVersion::try_from(v).ok())
+                .ok_or(BlockchainError::InvalidForkSchedule("unknown block version"))?;
+            let height = height.trim().parse::<u64>()
+                .map_err(|_| BlockchainError::InvalidForkSchedule("invalid height"))?;
+
+            let hard_fork = hard_forks.iter_mut()
+                .find(|hard_fork| hard_fork.version == version)
+                .ok_or(BlockchainError::InvalidForkSchedule("block version is not scheduled on this network"))?;
+            hard_fork.height = height;
+        }
+
+        hard_forks.sort_by_key(|hard_fork| hard_fork.version);
+        if hard_forks.first().map_or(true, |hard_fork| hard_fork.height != 0) {
+            return Err(BlockchainError::InvalidForkSchedule("first block version must be active at height 0"))
+        }
+
+        if hard_forks.windows(2).any(|w| w[0].height > w[1].height) {
+            return Err(BlockchainError::InvalidForkSchedule("activation heights can't decrease with the block version"))
+        }
+
+        Ok(schedule)
+    }
+
+    // All the hard forks scheduled, ordered by height
+    pub fn get_hard_forks(&self) -> &[HardFork] {
+        &self.hard_forks
+    }
+
+    // Get the hard fork at a given height
+    pub fn get_hard_fork_at_height(&self, height: u64) -> Option<&HardFork> {
+        let mut hardfork: Option<&HardFork> = None;
+        for conf in self.hard_forks.iter() {
+            if height >= conf.height {
+                hardfork = Some(conf);
+            } else {
+                break;
+            }
+        }
+
+        hardfork
+    }
+
+    // Get the version of the hard fork at a given height
+    // and returns true if there is a hard fork (version change) at that height
+    pub fn has_hard_fork_at_height(&self, height: u64) -> (bool, BlockVersion) {
+        match self.get_hard_fork_at_height(height) {
+            Some(hard_fork) => (hard_fork.height == height, hard_fork.version),
+            None => (false, BlockVersion::V0)
+        }
+    }
+
+    // This function returns the block version at a given height
+    pub fn get_version_at_height(&self, height: u64) -> BlockVersion {
+        self.has_hard_fork_at_height(height).1
+    }
+
+    // This function checks if a version is allowed at a given height
+    pub fn is_version_allowed_at_height(&self, height: u64, version: &str) -> Result<bool> {
+        for hard_fork in self.hard_forks.iter() {
+            if let Some(req) = hard_fork.version_requirement.filter(|_| hard_fork.height <= height) {
+                if !is_version_matching_requirement(version, req)? {
+                    return Ok(false);
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    // Verify if the BlockVersion is/was enabled at a given height
+    // Even if we are any version above the one requested, this function returns true
+    pub fn is_version_enabled_at_height(&self, height: u64, version: BlockVersion) -> bool {
+        self.hard_forks.iter()
+            .any(|hard_fork| hard_fork.height <= height && hard_fork.version == version)
+    }
+
+    // Is the feature active at a given height
+    pub fn is_feature_active_at_height(&self, feature: Feature, height: u64) -> bool {
+        feature.is_enabled_in(self.get_version_at_height(height))
+    }
+
+    // Block timestamp rules of the network
+    pub fn get_timestamp_rules(&self) -> &TimestampRules {
+        &self.timestamp_rules
+    }
+
+    // Contract capabilities allowed in this block version
+    pub fn get_contract_capabilities(&self, version: BlockVersion) -> ContractCapabilities {
+        self.contract_capabilities.iter()
+            .filter(|(_, activation)| version >= *activation)
+            .map(|(capability, _)| *capability)
+            .collect()
+    }
+
+    // Maximum size of a block in this version
+    // With the dynamic block size, the limit at the tips is lower or equal to it
+    pub fn get_max_block_size(&self, version: BlockVersion) -> usize {
+        if Feature::DynamicBlockSize.is_enabled_in(version) {
+            MAX_DYNAMIC_BLOCK_SIZE
+        } else {
+            MAX_BLOCK_SIZE
+        }
+    }
+
+    // Maximum size of a TX in this version
+    pub const fn get_max_transaction_size(&self, _: BlockVersion) -> usize {
+        MAX_TRANSACTION_SIZE
+    }
 }
 
 // This function returns the PoW algorithm at a given version
 pub fn get_pow_algorithm_for_version(version: BlockVersion) -> Algorithm {
-    match version {
-        BlockVersion::V0 => Algorithm::V1,
-        _ => Algorithm::V2
+    if Feature::XelisHashV2.is_enabled_in(version) {
+        Algorithm::V2
+    } else {
+        Algorithm::V1
     }
 }
 
@@ -58,35 +302,24 @@ fn is_version_matching_requirement(version: &str, req: &str) -> Result<bool> {
     Ok(r.matches(&v))
 }
 
-// This function checks if a version is allowed at a given height
-pub fn is_version_allowed_at_height(network: &Network, height: u64, version: &str) -> Result<bool> {
-    for hard_fork in get_hard_forks(network) {
-        if let Some(req) = hard_fork.version_requirement.filter(|_| hard_fork.height <= height) {
-            if !is_version_matching_requirement(version, req)? {
-                return Ok(false);
-            }
-        }
-    }
-
-    Ok(true)
+// This function checks if a transaction version is allowed in a block version
+#[inline(always)]
+pub fn is_tx_version_allowed_in_block_version(tx_version: TxVersion, block_version: BlockVersion) -> bool {
+    block_version.is_tx_version_allowed(tx_version)
 }
 
-// Verify if the BlockVersion is/was enabled at a given height
-// Even if we are any version above the one requested, this function returns true
-pub fn is_version_enabled_at_height(network: &Network, height: u64, version: BlockVersion) -> bool {
-    for hard_fork in get_hard_forks(network) {
-        if hard_fork.height <= height && hard_fork.version == version {
-            return true;
-        }
+// Check if a TX can be included in a block of this version
+// Its version must match and the features it uses must be enabled
+pub fn verify_tx_for_block_version(tx: &Transaction, block_version: BlockVersion) -> Result<(), BlockchainError> {
+    if !is_tx_version_allowed_in_block_version(tx.get_version(), block_version) {
+        return Err(BlockchainError::InvalidTxVersion)
     }
 
-    false
-}
+    if let Some(feature) = Feature::required_by(tx).filter(|feature| !feature.is_enabled_in(block_version)) {
+        return Err(BlockchainError::FeatureNotActive(feature, block_version))
+    }
 
-// This function checks if a transaction version is allowed in a block version
-#[inline(always)]
-pub fn is_tx_version_allowed_in_block_version(tx_version: TxVersion, block_version: BlockVersion) -> bool {
-    block_version.is_tx_version_allowed(tx_version)
+    Ok(())
 }
 
 #[cfg(test)]
@@ -107,60 +340,67 @@ mod tests {
 
     #[test]
     fn test_current_software_version_hard_forks_requirements() {
+        let mainnet = ForkSchedule::new(&Network::Mainnet);
+
         const VERSIONS: [&str; 3] = ["1.0.0", "1.0.0-abcdef", "1.0.0-abcdef999"];
 
         for version in VERSIONS {
-            assert!(is_version_allowed_at_height(&Network::Mainnet, 0, version).unwrap());
+            assert!(mainnet.is_version_allowed_at_height(0, version).unwrap());
         }
 
         // Should still be valid as we don't have any requirement
-        assert!(is_version_allowed_at_height(&Network::Mainnet, 0, "0.0.0").unwrap());
+        assert!(mainnet.is_version_allowed_at_height(0, "0.0.0").unwrap());
 
         // Current version should always be valid on previous versions
-        assert!(is_version_allowed_at_height(&Network::Mainnet, 0, &VERSION).unwrap());
+        assert!(mainnet.is_version_allowed_at_height(0, &VERSION).unwrap());
 
         // Should be invalid as we require >=1.13.0
         for version in VERSIONS {
-            assert!(!is_version_allowed_at_height(&Network::Mainnet, 435_000, version).unwrap());
+            assert!(!mainnet.is_version_allowed_at_height(435_000, version).unwrap());
         }
 
         // Should be valid as we require >=1.13.0
-        assert!(is_version_allowed_at_height(&Network::Mainnet, 435_000, "1.13.0").unwrap());
-        assert!(is_version_allowed_at_height(&Network::Mainnet, 435_000, VERSION).unwrap());
+        assert!(mainnet.is_version_allowed_at_height(435_000, "1.13.0").unwrap());
+        assert!(mainnet.is_version_allowed_at_height(435_000, VERSION).unwrap());
     }
 
     #[test]
     fn test_has_hard_fork_at_height() {
-        let (hard_fork, version) = has_hard_fork_at_height(&Network::Testnet, 0);
+        let testnet = ForkSchedule::new(&Network::Testnet);
+
+        let (hard_fork, version) = testnet.has_hard_fork_at_height(0);
         assert_eq!(hard_fork, true);
         assert_eq!(version, BlockVersion::V0);
 
-        let (hard_fork, version) = has_hard_fork_at_height(&Network::Testnet, 1);
+        let (hard_fork, version) = testnet.has_hard_fork_at_height(1);
         assert_eq!(hard_fork, false);
         assert_eq!(version, BlockVersion::V0);
 
 
-        let (hard_fork, version) = has_hard_fork_at_height(&Network::Testnet, 5);
+        let (hard_fork, version) = testnet.has_hard_fork_at_height(5);
         assert_eq!(hard_fork, true);
         assert_eq!(version, BlockVersion::V1);
 
-        let (hard_fork, version) = has_hard_fork_at_height(&Network::Testnet, 6);
+        let (hard_fork, version) = testnet.has_hard_fork_at_height(6);
         assert_eq!(hard_fork, false);
         assert_eq!(version, BlockVersion::V1);
     }
 
     #[test]
     fn test_get_version_at_height() {
+        let mainnet = ForkSchedule::new(&Network::Mainnet);
+        let testnet = ForkSchedule::new(&Network::Testnet);
+
         // Mainnet
-        assert_eq!(get_version_at_height(&Network::Mainnet, 0), BlockVersion::V0);
-        assert_eq!(get_version_at_height(&Network::Mainnet, 435_000), BlockVersion::V1);
-        assert_eq!(get_version_at_height(&Network::Mainnet, 2_000_000), BlockVersion::V2);
+        assert_eq!(mainnet.get_version_at_height(0), BlockVersion::V0);
+        assert_eq!(mainnet.get_version_at_height(435_000), BlockVersion::V1);
+        assert_eq!(mainnet.get_version_at_height(2_000_000), BlockVersion::V2);
 
         // Testnet
-        assert_eq!(get_version_at_height(&Network::Testnet, 0), BlockVersion::V0);
-        assert_eq!(get_version_at_height(&Network::Testnet, 6), BlockVersion::V1);
-        assert_eq!(get_version_at_height(&Network::Testnet, 10), BlockVersion::V2);
-        assert_eq!(get_version_at_height(&Network::Testnet, 50), BlockVersion::V3);
+        assert_eq!(testnet.get_version_at_height(0), BlockVersion::V0);
+        assert_eq!(testnet.get_version_at_height(6), BlockVersion::V1);
+        assert_eq!(testnet.get_version_at_height(10), BlockVersion::V2);
+        assert_eq!(testnet.get_version_at_height(50), BlockVersion::V3);
     }
 
     #[test]
@@ -187,30 +427,119 @@ mod tests {
 
     #[test]
     fn test_version_enabled() {
+        let mainnet = ForkSchedule::new(&Network::Mainnet);
+        let testnet = ForkSchedule::new(&Network::Testnet);
+
         // Mainnet
-        assert!(is_version_enabled_at_height(&Network::Mainnet, 0, BlockVersion::V0));
-        assert!(!is_version_enabled_at_height(&Network::Mainnet, 0, BlockVersion::V1));
-        assert!(!is_version_enabled_at_height(&Network::Mainnet, 0, BlockVersion::V2));
+        assert!(mainnet.is_version_enabled_at_height(0, BlockVersion::V0));
+        assert!(!mainnet.is_version_enabled_at_height(0, BlockVersion::V1));
+        assert!(!mainnet.is_version_enabled_at_height(0, BlockVersion::V2));
 
-        assert!(is_version_enabled_at_height(&Network::Mainnet, 435_000, BlockVersion::V1));
+        assert!(mainnet.is_version_enabled_at_height(435_000, BlockVersion::V1));
         // V2 is enabled
-        assert!(is_version_enabled_at_height(&Network::Mainnet, 1_376_000, BlockVersion::V2));
-        assert!(is_version_enabled_at_height(&Network::Mainnet, 2_000_000, BlockVersion::V2));
+        assert!(mainnet.is_version_enabled_at_height(1_376_000, BlockVersion::V2));
+        assert!(mainnet.is_version_enabled_at_height(2_000_000, BlockVersion::V2));
 
         // V3 is not yet enabled
-        assert!(!is_version_enabled_at_height(&Network::Mainnet, 2_000_000, BlockVersion::V3));
+        assert!(!mainnet.is_version_enabled_at_height(2_000_000, BlockVersion::V3));
 
         // Testnet
-        assert!(is_version_enabled_at_height(&Network::Testnet, 0, BlockVersion::V0));
-        assert!(!is_version_enabled_at_height(&Network::Testnet, 0, BlockVersion::V1));
-        assert!(!is_version_enabled_at_height(&Network::Testnet, 0, BlockVersion::V2));
+        assert!(testnet.is_version_enabled_at_height(0, BlockVersion::V0));
+        assert!(!testnet.is_version_enabled_at_height(0, BlockVersion::V1));
+        assert!(!testnet.is_version_enabled_at_height(0, BlockVersion::V2));
+
+        assert!(testnet.is_version_enabled_at_height(5, BlockVersion::V0));
+        assert!(testnet.is_version_enabled_at_height(5, BlockVersion::V1));
+        assert!(!testnet.is_version_enabled_at_height(5, BlockVersion::V2));
+
+        assert!(testnet.is_version_enabled_at_height(10, BlockVersion::V0));
+        assert!(testnet.is_version_enabled_at_height(10, BlockVersion::V1));
+        assert!(testnet.is_version_enabled_at_height(10, BlockVersion::V2));
+    }
+
+    #[test]
+    fn test_feature_active_at_height() {
+        let mainnet = ForkSchedule::new(&Network::Mainnet);
+
+        assert!(!mainnet.is_feature_active_at_height(Feature::XelisHashV2, 0));
+        assert!(mainnet.is_feature_active_at_height(Feature::XelisHashV2, 435_000));
+
+        assert!(!mainnet.is_feature_active_at_height(Feature::MultiSig, 435_000));
+        assert!(mainnet.is_feature_active_at_height(Feature::MultiSig, 1_376_000));
+
+        // Contracts are not yet scheduled on mainnet
+        assert!(!mainnet.is_feature_active_at_height(Feature::SmartContracts, 2_000_000));
+
+        // Dynamic block size has its own fork after the Smart Contracts one
+        let testnet = ForkSchedule::new(&Network::Testnet);
+        assert!(testnet.is_feature_active_at_height(Feature::SmartContracts, 50));
+        assert!(!testnet.is_feature_active_at_height(Feature::DynamicBlockSize, 50));
+        assert!(testnet.is_feature_active_at_height(Feature::DynamicBlockSize, 2_500_000));
+        assert!(!testnet.is_feature_active_at_height(Feature::MedianTimePast, 50));
+        assert!(testnet.is_feature_active_at_height(Feature::MedianTimePast, 2_500_000));
+
+        // Contracts deployed before V4 can't have a constructor
+        assert!(!testnet.is_feature_active_at_height(Feature::ContractConstructor, 50));
+        assert!(testnet.is_feature_active_at_height(Feature::ContractConstructor, 2_500_000));
+
+        // Contracts can't be paused before V4
+        assert!(!testnet.is_feature_active_at_height(Feature::ContractManagement, 50));
+        assert!(testnet.is_feature_active_at_height(Feature::ContractManagement, 2_500_000));
+        assert!(!testnet.is_feature_active_at_height(Feature::ExecutionCredits, 50));
+        assert!(testnet.is_feature_active_at_height(Feature::ExecutionCredits, 2_500_000));
+    }
+
+    #[test]
+    fn test_fork_schedule_overrides() {
+        let overrides = vec!["1=0".to_owned(), "2=1".to_owned(), "V3=2".to_owned()];
+        let dev = ForkSchedule::with_overrides(&Network::Dev, &overrides).unwrap();
+        assert_eq!(dev.get_version_at_height(0), BlockVersion::V1);
+        assert_eq!(dev.get_version_at_height(1), BlockVersion::V2);
+        assert_eq!(dev.get_version_at_height(2), BlockVersion::V3);
+        assert!(dev.is_feature_active_at_height(Feature::SmartContracts, 2));
+
+        // No override keeps the default schedule
+        let dev = ForkSchedule::with_overrides(&Network::Dev, &[]).unwrap();
+        assert_eq!(dev.get_version_at_height(50), BlockVersion::V3);
+
+        // Only allowed on devnet
+        assert!(ForkSchedule::with_overrides(&Network::Mainnet, &overrides).is_err());
+        assert!(ForkSchedule::with_overrides(&Network::Testnet, &overrides).is_err());
+
+        // Heights can't decrease with the version
+        assert!(ForkSchedule::with_overrides(&Network::Dev, &["3=1".to_owned()]).is_err());
+        // Genesis version must stay at height 0
+        assert!(ForkSchedule::with_overrides(&Network::Dev, &["0=1".to_owned()]).is_err());
+        // Invalid formats
+        assert!(ForkSchedule::with_overrides(&Network::Dev, &["3".to_owned()]).is_err());
+        assert!(ForkSchedule::with_overrides(&Network::Dev, &["9=10".to_owned()]).is_err());
+        assert!(ForkSchedule::with_overrides(&Network::Dev, &["3=abc".to_owned()]).is_err());
+    }
+
+    #[test]
+    fn test_timestamp_rules_overrides() {
+        let dev = ForkSchedule::new(&Network::Dev).with_timestamp_overrides(&Network::Dev, Some(5), Some(10_000)).unwrap();
+        assert_eq!(dev.get_timestamp_rules().median_time_past_window, 5);
+        assert_eq!(dev.get_timestamp_rules().future_drift_limit, 10_000);
 
-        assert!(is_version_enabled_at_height(&Network::Testnet, 5, BlockVersion::V0));
-        assert!(is_version_enabled_at_height(&Network::Testnet, 5, BlockVersion::V1));
-        assert!(!is_version_enabled_at_height(&Network::Testnet, 5, BlockVersion::V2));
+        // No override keeps the defaults
+        let mainnet = ForkSchedule::new(&Network::Mainnet).with_timestamp_overrides(&Network::Mainnet, None, None).unwrap();
+        assert_eq!(*mainnet.get_timestamp_rules(), TimestampRules::default());
 
-        assert!(is_version_enabled_at_height(&Network::Testnet, 10, BlockVersion::V0));
-        assert!(is_version_enabled_at_height(&Network::Testnet, 10, BlockVersion::V1));
-        assert!(is_version_enabled_at_height(&Network::Testnet, 10, BlockVersion::V2));
+        assert!(ForkSchedule::new(&Network::Mainnet).with_timestamp_overrides(&Network::Mainnet, Some(5), None).is_err());
+        assert!(ForkSchedule::new(&Network::Dev).with_timestamp_overrides(&Network::Dev, Some(0), None).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_contract_capabilities() {
+        let mainnet = ForkSchedule::new(&Network::Mainnet);
+        assert_eq!(mainnet.get_contract_capabilities(BlockVersion::V2), ContractCapabilities::none());
+
+        let capabilities = mainnet.get_contract_capabilities(BlockVersion::V3);
+        assert!(capabilities.allows(ContractCapability::Transfer));
+        assert!(!capabilities.allows(ContractCapability::CrossContractCall));
+
+        let testnet = ForkSchedule::new(&Network::Testnet);
+        assert_eq!(testnet.get_contract_capabilities(BlockVersion::V3), ContractCapabilities::all());
+    }
+}
@@ -28,6 +28,8 @@ use xelis_common::{
 };
 use xelis_vm::Environment;
 
+pub use xelis_common::utils::calculate_fee_rate_per_kb;
+
 // Wrap a TX with its hash and size in bytes for faster access
 // size of tx can be heavy to compute, so we store it here
 #[derive(serde::Serialize)]
@@ -67,7 +69,11 @@ pub struct Mempool {
     // store all txs waiting to be included in a block
     txs: HashMap<Arc<Hash>, SortedTx>,
     // store all sender's nonce for faster finding
-    caches: HashMap<PublicKey, AccountCache>
+    caches: HashMap<PublicKey, AccountCache>,
+    // maximum count of txs, None for no limit
+    max_txs: Option<usize>,
+    // maximum size in bytes of all txs, None for no limit
+    max_size: Option<usize>
 }
 
 impl Mempool {
@@ -76,10 +82,119 @@ impl Mempool {
         Mempool {
             mainnet: network.is_mainnet(),
             txs: HashMap::new(),
-            caches: HashMap::new()
+            caches: HashMap::new(),
+            max_txs: None,
+            max_size: None
         }
     }
 
+    pub fn get_max_size(&self) -> Option<usize> {
+        self.max_size
+    }
+
+    // Set the maximum size in bytes of all txs, txs already in mempool are kept
+    pub fn set_max_size(&mut self, max_size: Option<usize>) {
+        self.max_size = max_size;
+    }
+
+    // Total size in bytes of all txs
+    pub fn get_total_size(&self) -> usize {
+        self.txs.values().map(|tx| tx.size).sum()
+    }
+
+    // Room left before reaching the limits, in count of txs and in bytes
+    pub fn get_available_room(&self) -> (usize, usize) {
+        let count = self.max_txs.map_or(usize::MAX, |max| max.saturating_sub(self.txs.len()));
+        let size = self.max_size.map_or(usize::MAX, |max| max.saturating_sub(self.get_total_size()));
+        (count, size)
+    }
+
+    // Select the txs to evict to make room for a new tx
+    // Only the tx with the highest nonce of an account can be evicted, as next txs depend on previous ones
+    // Lowest fee rate then oldest txs are evicted first, and only if their fee rate is lower than the new tx
+    // The sender of the new tx is excluded as its tx is verified against its pending txs
+    fn select_txs_to_evict(&self, source: &PublicKey, fee_rate: u64, size: usize) -> Result<Vec<Arc<Hash>>, BlockchainError> {
+        let mut count = self.txs.len();
+        let mut total_size = self.get_total_size();
+        let is_full = |count: usize, total_size: usize| {
+            self.max_txs.is_some_and(|max| count >= max)
+                || self.max_size.is_some_and(|max| total_size + size > max)
+        };
+
+        if self.max_size.is_some_and(|max| size > max) {
+            return Err(BlockchainError::MempoolFull)
+        }
+
+        // next nonce to evict for each account
+        let mut tails: HashMap<&PublicKey, Nonce> = self.caches.iter()
+            .filter(|(key, _)| *key != source)
+            .map(|(key, cache)| (key, cache.get_max()))
+            .collect();
+
+        let mut selected = Vec::new();
+        while is_full(count, total_size) {
+            let mut lowest: Option<(&PublicKey, &Arc<Hash>, &SortedTx)> = None;
+            for (key, nonce) in tails.iter() {
+                let Some(hash) = self.caches.get(*key).and_then(|cache| cache.has_tx_with_same_nonce(*nonce)) else {
+                    continue;
+                };
+                let Some(tx) = self.txs.get(hash) else {
+                    continue;
+                };
+
+                let is_lower = lowest.map_or(true, |(_, _, current)| {
+                    let current_rate = calculate_fee_rate_per_kb(current.get_fee(), current.size);
+                    let rate = calculate_fee_rate_per_kb(tx.get_fee(), tx.size);
+                    (rate, tx.first_seen) < (current_rate, current.first_seen)
+                });
+
+                if is_lower {
+                    lowest = Some((*key, hash, tx));
+                }
+            }
+
+            let Some((key, hash, tx)) = lowest else {
+                return Err(BlockchainError::MempoolFull)
+            };
+
+            if calculate_fee_rate_per_kb(tx.get_fee(), tx.size) >= fee_rate {
+                return Err(BlockchainError::MempoolFull)
+            }
+
+            // the previous nonce becomes the tail of this account
+            let min = self.caches.get(key).map_or(0, |cache| cache.get_min());
+            let nonce = tx.get_tx().get_nonce();
+            if nonce > min {
+                tails.insert(key, nonce - 1);
+            } else {
+                tails.remove(key);
+            }
+
+            count -= 1;
+            total_size -= tx.size;
+            selected.push(hash.clone());
+        }
+
+        Ok(selected)
+    }
+
+    // Create a new empty mempool bounded in bytes
+    pub fn with_max_size(network: Network, max_size: Option<usize>) -> Self {
+        Mempool {
+            max_size,
+            ..Self::new(network)
+        }
+    }
+
+    pub fn get_max_txs(&self) -> Option<usize> {
+        self.max_txs
+    }
+
+    // Set the maximum count of txs, txs already in mempool are kept
+    pub fn set_max_txs(&mut self, max_txs: Option<usize>) {
+        self.max_txs = max_txs;
+    }
+
     fn internal_estimate_fee_rates(mut fee_rates: Vec<u64>) -> FeeRatesEstimated {
         let len = fee_rates.len();
         // Top 30%
@@ -130,10 +245,15 @@ impl Mempool {
     }
 
     // All checks are made in Blockchain before calling this function
-    pub async fn add_tx<S: Storage>(&mut self, storage: &S, environment: &Environment, stable_topoheight: TopoHeight, topoheight: TopoHeight, hash: Hash, tx: Arc<Transaction>, size: usize, block_version: BlockVersion) -> Result<(), BlockchainError> {
+    // Returns the txs evicted to make room for this one
+    pub async fn add_tx<S: Storage>(&mut self, storage: &S, environment: &Environment, stable_topoheight: TopoHeight, topoheight: TopoHeight, hash: Hash, tx: Arc<Transaction>, size: usize, block_version: BlockVersion) -> Result<Vec<(Arc<Hash>, SortedTx)>, BlockchainError> {
         let mut state = MempoolState::new(&self, storage, environment, stable_topoheight, topoheight, block_version, self.mainnet);
         tx.verify(&hash, &mut state).await?;
 
+        // Evict only once the TX is known to be valid
+        let fee_rate = calculate_fee_rate_per_kb(tx.get_fee(), size);
+        let to_evict = self.select_txs_to_evict(tx.get_source(), fee_rate, size)?;
+
         let (balances, multisig) = state.get_sender_cache(tx.get_source())
             .ok_or_else(|| BlockchainError::AccountNotFound(tx.get_source().as_address(self.mainnet)))?;
 
@@ -198,12 +318,25 @@ impl Mempool {
         // insert in map
         self.txs.insert(hash, sorted_tx);
 
-        Ok(())
+        let mut evicted = Vec::with_capacity(to_evict.len());
+        for hash in to_evict {
+            debug!("Evicting TX {} from mempool", hash);
+            let sorted_tx = self.remove_sorted_tx(&hash)?;
+            evicted.push((hash, sorted_tx));
+        }
+
+        Ok(evicted)
     }
 
     // Remove a TX using its hash from mempool
     // This will recalculate the cache bounds
     pub fn remove_tx(&mut self, hash: &Hash) -> Result<(), BlockchainError> {
+        self.remove_sorted_tx(hash).map(|_| ())
+    }
+
+    // Remove a TX and returns it
+    // Balances of its sender cache are not recomputed, they are verified again at the next clean up
+    fn remove_sorted_tx(&mut self, hash: &Hash) -> Result<SortedTx, BlockchainError> {
         let tx = self.txs.remove(hash).ok_or_else(|| BlockchainError::TxNotFound(hash.clone()))?;
         // remove the tx hash from sorted txs
         let key = tx.get_tx().get_source();
@@ -259,7 +392,7 @@ impl Mempool {
             self.caches.remove(key);
         }
 
-        Ok(())
+        Ok(tx)
     }
 
     // Get the nonce cache for all keys
@@ -637,6 +770,15 @@ impl AccountCache {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fee_rate_per_kb() {
+        assert_eq!(calculate_fee_rate_per_kb(1000, 1024), 1000);
+        assert_eq!(calculate_fee_rate_per_kb(1000, 2048), 500);
+        // TXs under 1 kB are not rounded to zero
+        assert_eq!(calculate_fee_rate_per_kb(1000, 512), 2000);
+        assert_eq!(calculate_fee_rate_per_kb(1000, 0), 1000 * BYTES_PER_KB as u64);
+    }
+
     #[test]
     fn test_estimated_fee_rates() {
         // Let say we have the following TXs:
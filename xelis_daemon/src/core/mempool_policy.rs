@@ -0,0 +1,144 @@
+use xelis_common::{
+    config::MAX_TRANSACTION_SIZE,
+    serializer::{Reader, ReaderError, Serializer, Versioned, Writer},
+    transaction::{Transaction, TransactionType, EXTRA_DATA_LIMIT_SUM_SIZE}
+};
+use super::error::BlockchainError;
+
+// Operator policy applied when admitting a TX in the mempool
+// It is only a local rule on top of the consensus rules:
+// a TX rejected by it is still valid in a block
+// It is sent to peers in the handshake so they don't relay TXs we would reject
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MempoolPolicy {
+    // minimum fee paid per byte of the TX, 0 to only apply the network fee
+    min_fee_per_byte: u64,
+    // maximum size of a TX
+    max_tx_size: usize,
+    // maximum size of all the extra data of a TX
+    max_extra_data_size: usize,
+    // maximum TXs pending in mempool for the same sender, 0 for no limit
+    max_pending_txs_per_account: usize,
+    // only the TXs of the blocks are accepted, loose TXs are neither accepted nor relayed
+    blocks_only: bool
+}
+
+impl Default for MempoolPolicy {
+    fn default() -> Self {
+        Self {
+            min_fee_per_byte: 0,
+            max_tx_size: MAX_TRANSACTION_SIZE,
+            max_extra_data_size: EXTRA_DATA_LIMIT_SUM_SIZE,
+            max_pending_txs_per_account: 0,
+            blocks_only: false
+        }
+    }
+}
+
+impl MempoolPolicy {
+    // Limits above the consensus ones are lowered to them
+    pub fn new(min_fee_per_byte: u64, max_tx_size: usize, max_extra_data_size: usize, max_pending_txs_per_account: usize, blocks_only: bool) -> Self {
+        Self {
+            min_fee_per_byte,
+            max_tx_size: max_tx_size.min(MAX_TRANSACTION_SIZE),
+            max_extra_data_size: max_extra_data_size.min(EXTRA_DATA_LIMIT_SUM_SIZE),
+            max_pending_txs_per_account,
+            blocks_only
+        }
+    }
+
+    pub fn get_min_fee_per_byte(&self) -> u64 {
+        self.min_fee_per_byte
+    }
+
+    pub fn get_max_tx_size(&self) -> usize {
+        self.max_tx_size
+    }
+
+    pub fn get_max_extra_data_size(&self) -> usize {
+        self.max_extra_data_size
+    }
+
+    pub fn get_max_pending_txs_per_account(&self) -> usize {
+        self.max_pending_txs_per_account
+    }
+
+    pub fn is_blocks_only(&self) -> bool {
+        self.blocks_only
+    }
+
+    // Minimum fee required by the policy for a TX of this size
+    pub fn get_min_fee(&self, tx_size: usize) -> u64 {
+        self.min_fee_per_byte.saturating_mul(tx_size as u64)
+    }
+
+    // Check only the fee and the size, used before relaying a TX to a peer
+    pub fn accepts(&self, fee: u64, tx_size: usize) -> bool {
+        !self.blocks_only && tx_size <= self.max_tx_size && fee >= self.get_min_fee(tx_size)
+    }
+
+    // Verify a TX before adding it in the mempool
+    // pending_txs is the count of TXs already in mempool for its sender
+    pub fn verify(&self, tx: &Transaction, tx_size: usize, pending_txs: usize) -> Result<(), BlockchainError> {
+        if tx_size > self.max_tx_size {
+            return Err(BlockchainError::TxTooBig(tx_size, self.max_tx_size))
+        }
+
+        let min_fee = self.get_min_fee(tx_size);
+        if tx.get_fee() < min_fee {
+            return Err(BlockchainError::TxFeeBelowPolicy(min_fee, tx.get_fee()))
+        }
+
+        if let TransactionType::Transfers(transfers) = tx.get_data() {
+            let extra_data_size: usize = transfers.iter()
+                .filter_map(|transfer| transfer.get_extra_data().as_ref())
+                .map(|extra_data| extra_data.size())
+                .sum();
+
+            if extra_data_size > self.max_extra_data_size {
+                return Err(BlockchainError::TxExtraDataAbovePolicy(extra_data_size, self.max_extra_data_size))
+            }
+        }
+
+        if self.max_pending_txs_per_account != 0 && pending_txs >= self.max_pending_txs_per_account {
+            return Err(BlockchainError::TooManyPendingTxs(self.max_pending_txs_per_account))
+        }
+
+        Ok(())
+    }
+}
+
+// Sent at the end of the handshake, peers not knowing it skip it
+impl Versioned for MempoolPolicy {
+    const TAG: u16 = 0x4D50;
+    const VERSION: u8 = 1;
+
+    fn write_fields(&self, writer: &mut Writer) {
+        writer.write_u64(&self.min_fee_per_byte);
+        writer.write_u64(&(self.max_tx_size as u64));
+        writer.write_u64(&(self.max_extra_data_size as u64));
+        writer.write_u64(&(self.max_pending_txs_per_account as u64));
+        writer.write_bool(self.blocks_only);
+    }
+
+    fn read_fields(reader: &mut Reader, version: u8) -> Result<Self, ReaderError> {
+        let min_fee_per_byte = reader.read_u64()?;
+        let max_tx_size = reader.read_u64()? as usize;
+        let max_extra_data_size = reader.read_u64()? as usize;
+        let max_pending_txs_per_account = reader.read_u64()? as usize;
+        // Added in version 1
+        let blocks_only = if version >= 1 {
+            reader.read_bool()?
+        } else {
+            false
+        };
+
+        Ok(Self {
+            min_fee_per_byte,
+            max_tx_size,
+            max_extra_data_size,
+            max_pending_txs_per_account,
+            blocks_only
+        })
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::Arc
+};
+use log::{debug, info, warn};
+use xelis_common::{
+    crypto::{Hash, Hashable},
+    network::Network,
+    serializer::Serializer,
+    time::{get_current_time_in_seconds, TimestampSeconds},
+    transaction::Transaction
+};
+use super::{
+    error::BlockchainError,
+    mempool::calculate_fee_rate_per_kb
+};
+
+// TXs evicted from the mempool while still valid are spilled to disk
+// They are reconsidered when space frees up in the mempool
+
+pub fn get_mempool_spill_path(dir_path: Option<&str>, network: &Network) -> String {
+    format!("{}mempool-spill-{}/", dir_path.unwrap_or_default(), network.to_string().to_lowercase())
+}
+
+// A TX stored on disk, only its metadata is kept in memory
+struct SpilledTx {
+    fee_rate: u64,
+    size: usize,
+    spilled_at: TimestampSeconds
+}
+
+// Disk-backed secondary pool, bounded in bytes
+pub struct MempoolSpill {
+    dir: String,
+    // maximum size in bytes of all spilled TXs
+    max_size: usize,
+    // current size in bytes of all spilled TXs
+    size: usize,
+    txs: HashMap<Hash, SpilledTx>
+}
+
+impl MempoolSpill {
+    // Load the TXs spilled before the last shutdown
+    pub fn new(dir: String, max_size: usize) -> Result<Self, BlockchainError> {
+        fs::create_dir_all(&dir)?;
+
+        let mut spill = Self {
+            dir,
+            max_size,
+            size: 0,
+            txs: HashMap::new()
+        };
+
+        for entry in fs::read_dir(&spill.dir)? {
+            let path = entry?.path();
+            let tx = match fs::read(&path).map_err(BlockchainError::from).and_then(|bytes| Ok(Transaction::from_bytes(&bytes)?)) {
+                Ok(tx) => tx,
+                Err(e) => {
+                    warn!("Deleting invalid spilled TX file {}: {}", path.display(), e);
+                    fs::remove_file(&path)?;
+                    continue;
+                }
+            };
+
+            let size = tx.size();
+            spill.size += size;
+            spill.txs.insert(tx.hash(), SpilledTx {
+                fee_rate: calculate_fee_rate_per_kb(tx.get_fee(), size),
+                size,
+                spilled_at: get_current_time_in_seconds()
+            });
+        }
+
+        if !spill.txs.is_empty() {
+            info!("Loaded {} spilled TXs from disk", spill.txs.len());
+        }
+
+        Ok(spill)
+    }
+
+    fn get_path(&self, hash: &Hash) -> String {
+        format!("{}{}.bin", self.dir, hash)
+    }
+
+    // Count of spilled TXs
+    pub fn count(&self) -> usize {
+        self.txs.len()
+    }
+
+    pub fn contains(&self, hash: &Hash) -> bool {
+        self.txs.contains_key(hash)
+    }
+
+    // Lowest fee rate then oldest TX, first to be dropped
+    fn get_lowest(&self) -> Option<(&Hash, &SpilledTx)> {
+        self.txs.iter()
+            .min_by_key(|(_, tx)| (tx.fee_rate, tx.spilled_at))
+    }
+
+    fn delete(&mut self, hash: &Hash) -> Result<(), BlockchainError> {
+        if let Some(tx) = self.txs.remove(hash) {
+            self.size -= tx.size;
+            let path = self.get_path(hash);
+            if Path::new(&path).exists() {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Spill an evicted TX to disk
+    // TXs with the lowest fee rate are dropped to make room, including this one
+    pub fn spill(&mut self, hash: &Hash, tx: &Transaction, size: usize) -> Result<bool, BlockchainError> {
+        if size > self.max_size || self.txs.contains_key(hash) {
+            return Ok(false)
+        }
+
+        let fee_rate = calculate_fee_rate_per_kb(tx.get_fee(), size);
+        while self.size + size > self.max_size {
+            let (lowest, lowest_fee_rate) = match self.get_lowest() {
+                Some((hash, tx)) => (hash.clone(), tx.fee_rate),
+                None => break
+            };
+
+            if lowest_fee_rate >= fee_rate {
+                debug!("Spilled TXs have a higher fee rate than TX {}, dropping it", hash);
+                return Ok(false)
+            }
+
+            debug!("Dropping spilled TX {} to spill TX {}", lowest, hash);
+            self.delete(&lowest)?;
+        }
+
+        fs::write(self.get_path(hash), tx.to_bytes())?;
+        self.size += size;
+        self.txs.insert(hash.clone(), SpilledTx {
+            fee_rate,
+            size,
+            spilled_at: get_current_time_in_seconds()
+        });
+
+        Ok(true)
+    }
+
+    // Take the spilled TXs with the highest fee rate fitting in the available room
+    // They are deleted from disk, the caller must add them back to the mempool
+    pub fn take_best(&mut self, mut max_count: usize, mut max_size: usize) -> Result<Vec<(Hash, Arc<Transaction>)>, BlockchainError> {
+        let mut candidates: Vec<(Hash, u64, usize)> = self.txs.iter()
+            .map(|(hash, tx)| (hash.clone(), tx.fee_rate, tx.size))
+            .collect();
+        candidates.sort_by(|a, b| b.1.cmp(&a.1));
+
+        let mut txs = Vec::new();
+        for (hash, _, size) in candidates {
+            if max_count == 0 {
+                break;
+            }
+
+            if size > max_size {
+                continue;
+            }
+
+            let tx = fs::read(self.get_path(&hash)).map_err(BlockchainError::from)
+                .and_then(|bytes| Ok(Transaction::from_bytes(&bytes)?));
+            self.delete(&hash)?;
+
+            match tx {
+                Ok(tx) => {
+                    max_count -= 1;
+                    max_size -= size;
+                    txs.push((hash, Arc::new(tx)));
+                },
+                Err(e) => warn!("Error while reading spilled TX {}: {}", hash, e)
+            }
+        }
+
+        // Keep the nonces order of each sender to add them back
+        txs.sort_by_key(|(_, tx)| tx.get_nonce());
+
+        Ok(txs)
+    }
+}
@@ -10,5 +10,12 @@ pub mod nonce_checker;
 pub mod tx_selector;
 pub mod state;
 pub mod merkle;
+pub mod chain_file;
+pub mod runtime_params;
+pub mod clock;
+pub mod supply_audit;
+pub mod mempool_policy;
+pub mod mempool_spill;
+pub mod diagnostics;
 
 pub mod hard_fork;
\ No newline at end of file
@@ -0,0 +1,134 @@
+use std::{fs, path::Path, str::FromStr};
+use anyhow::Context;
+use log::info;
+use tokio::sync::Mutex;
+use xelis_common::{
+    api::daemon::RuntimeParams,
+    network::Network,
+    prompt::{logger, LogLevel}
+};
+use super::{
+    blockchain::Blockchain,
+    error::BlockchainError,
+    storage::Storage
+};
+
+// Runtime parameters updated through the RPC API are persisted in a file
+// They are applied again at the next start and override the config
+
+pub fn get_runtime_params_path(dir_path: Option<&str>, network: &Network) -> String {
+    format!("{}runtime-params-{}.json", dir_path.unwrap_or_default(), network.to_string().to_lowercase())
+}
+
+// Store of the parameters updated at runtime
+pub struct RuntimeParamsStore {
+    path: String,
+    params: Mutex<RuntimeParams>
+}
+
+impl RuntimeParamsStore {
+    // Load the persisted parameters if any
+    pub fn new(path: String) -> Result<Self, BlockchainError> {
+        let params = if Path::new(&path).exists() {
+            let content = fs::read_to_string(&path)?;
+            serde_json::from_str(&content).context("Error while parsing runtime params file")?
+        } else {
+            RuntimeParams::default()
+        };
+
+        Ok(Self {
+            path,
+            params: Mutex::new(params)
+        })
+    }
+
+    pub async fn get_persisted(&self) -> RuntimeParams {
+        self.params.lock().await.clone()
+    }
+}
+
+// Current values of the runtime parameters
+pub async fn get_runtime_params<S: Storage>(blockchain: &Blockchain<S>) -> RuntimeParams {
+    let (log_level, _) = logger::get_log_levels();
+    let max_peers = match blockchain.get_p2p().read().await.as_ref() {
+        Some(p2p) => Some(p2p.get_max_peers()),
+        None => None
+    };
+
+    RuntimeParams {
+        log_level: Some(log_level.to_string()),
+        max_mempool_txs: Some(blockchain.get_mempool().read().await.get_max_txs().unwrap_or(0)),
+        max_peers,
+        cache_size: blockchain.get_storage().read().await.get_cache_size()
+    }
+}
+
+// Apply the parameters without persisting them
+// All of them are verified before applying any
+pub async fn apply_runtime_params<S: Storage>(blockchain: &Blockchain<S>, params: &RuntimeParams) -> Result<(), BlockchainError> {
+    let log_level = match params.log_level.as_ref() {
+        Some(level) => Some(LogLevel::from_str(level).map_err(BlockchainError::InvalidRuntimeParam)?),
+        None => None
+    };
+
+    let p2p = blockchain.get_p2p().read().await.clone();
+    if let Some(max_peers) = params.max_peers {
+        if max_peers == 0 {
+            return Err(BlockchainError::InvalidRuntimeParam("max peers must be above 0"))
+        }
+
+        if p2p.is_none() {
+            return Err(BlockchainError::InvalidRuntimeParam("P2p server is disabled"))
+        }
+    }
+
+    if let Some(cache_size) = params.cache_size {
+        let mut storage = blockchain.get_storage().write().await;
+        let current = storage.get_cache_size().ok_or(BlockchainError::CacheDisabled)?;
+        if current != cache_size {
+            storage.set_cache_size(cache_size).await?;
+            info!("Runtime parameter cache_size updated from {} to {}", current, cache_size);
+        }
+    }
+
+    if let Some(level) = log_level {
+        let (current, _) = logger::get_log_levels();
+        logger::set_log_level(level);
+        info!("Runtime parameter log_level updated from {} to {}", current, level);
+    }
+
+    if let Some(max_txs) = params.max_mempool_txs {
+        let mut mempool = blockchain.get_mempool().write().await;
+        let current = mempool.get_max_txs().unwrap_or(0);
+        mempool.set_max_txs(if max_txs == 0 { None } else { Some(max_txs) });
+        info!("Runtime parameter max_mempool_txs updated from {} to {}", current, max_txs);
+    }
+
+    if let (Some(max_peers), Some(p2p)) = (params.max_peers, p2p) {
+        let current = p2p.get_max_peers();
+        p2p.set_max_peers(max_peers);
+        info!("Runtime parameter max_peers updated from {} to {}", current, max_peers);
+    }
+
+    Ok(())
+}
+
+// Apply the parameters and persist them for the next start
+pub async fn update_runtime_params<S: Storage>(blockchain: &Blockchain<S>, params: RuntimeParams) -> Result<(), BlockchainError> {
+    let store = blockchain.get_runtime_params_store();
+    let mut persisted = store.params.lock().await;
+    apply_runtime_params(blockchain, &params).await?;
+
+    let updated = RuntimeParams {
+        log_level: params.log_level.or(persisted.log_level.clone()),
+        max_mempool_txs: params.max_mempool_txs.or(persisted.max_mempool_txs),
+        max_peers: params.max_peers.or(persisted.max_peers),
+        cache_size: params.cache_size.or(persisted.cache_size)
+    };
+
+    let content = serde_json::to_string_pretty(&updated).context("Error while serializing runtime params")?;
+    fs::write(&store.path, content)?;
+    *persisted = updated;
+
+    Ok(())
+}
@@ -4,15 +4,24 @@ use std::{
     ops::{Deref, DerefMut}
 };
 use async_trait::async_trait;
+use indexmap::IndexMap;
 use log::{debug, trace};
 use xelis_common::{
     account::{BalanceType, Nonce, VersionedNonce},
     block::{Block, BlockVersion, TopoHeight},
-    contract::{ChainState as ContractChainState, ContractCache, ContractOutput, DeterministicRandom},
+    contract::{
+        ChainState as ContractChainState,
+        ContractCache,
+        ContractCapabilities,
+        ContractManagementState,
+        ContractOutput,
+        ContractReceipt,
+        DeterministicRandom
+    },
     crypto::{elgamal::Ciphertext, Hash, PublicKey},
     transaction::{
         verify::{BlockchainApplyState, BlockchainVerificationState, ContractEnvironment},
-        InvokeContractPayload,
+        ContractDeposit,
         MultiSigPayload,
         Reference
     }
@@ -20,7 +29,16 @@ use xelis_common::{
 use xelis_vm::Environment;
 use crate::core::{
     error::BlockchainError,
-    storage::{Storage, VersionedContract, VersionedContractBalance, VersionedContractData, VersionedMultiSig}
+    hard_fork::Feature,
+    storage::{
+        Storage,
+        VersionedContract,
+        VersionedContractBalance,
+        VersionedContractData,
+        VersionedContractManagement,
+        VersionedExecutionCredits,
+        VersionedMultiSig
+    }
 };
 
 use super::{ChainState, StorageReference, Echange};
@@ -31,7 +49,10 @@ pub struct ApplicableChainState<'a, S: Storage> {
     block_hash: &'a Hash,
     block: &'a Block,
     contracts_outputs: HashMap<&'a Hash, Vec<ContractOutput>>,
+    contracts_receipts: HashMap<&'a Hash, Vec<ContractReceipt>>,
     contracts_cache: HashMap<&'a Hash, ContractCache>, 
+    // Capabilities allowed to the contracts in this block
+    contract_capabilities: ContractCapabilities,
     burned_supply: u64,
 }
 
@@ -135,6 +156,36 @@ impl<'a, S: Storage> BlockchainVerificationState<'a, BlockchainError> for Applic
     ) -> Result<(&xelis_vm::Module, &Environment), BlockchainError> {
         self.inner.get_contract_module_with_environment(hash).await
     }
+
+    async fn get_contract_management_state(
+        &mut self,
+        hash: &'a Hash
+    ) -> Result<Option<&ContractManagementState>, BlockchainError> {
+        self.inner.get_contract_management_state(hash).await
+    }
+
+    async fn set_contract_management_state(
+        &mut self,
+        hash: &'a Hash,
+        state: ContractManagementState
+    ) -> Result<(), BlockchainError> {
+        self.inner.set_contract_management_state(hash, state).await
+    }
+
+    async fn get_execution_credits(
+        &mut self,
+        account: &'a PublicKey
+    ) -> Result<u64, BlockchainError> {
+        self.inner.get_execution_credits(account).await
+    }
+
+    async fn set_execution_credits(
+        &mut self,
+        account: &'a PublicKey,
+        credits: u64
+    ) -> Result<(), BlockchainError> {
+        self.inner.set_execution_credits(account, credits).await
+    }
 }
 
 #[async_trait]
@@ -180,30 +231,43 @@ impl<'a, S: Storage> BlockchainApplyState<'a, S, BlockchainError> for Applicable
         Ok(())
     }
 
-    async fn get_contract_environment_for<'b>(&'b mut self, payload: &'b InvokeContractPayload, tx_hash: &'b Hash) -> Result<(ContractEnvironment<'b, S>, ContractChainState<'b>), BlockchainError> {
+    async fn set_contract_receipt(
+        &mut self,
+        tx_hash: &'a Hash,
+        receipt: ContractReceipt
+    ) -> Result<(), BlockchainError> {
+        self.contracts_receipts.entry(tx_hash)
+            .or_insert_with(Vec::new)
+            .push(receipt);
+
+        Ok(())
+    }
+
+    async fn get_contract_environment_for<'b>(&'b mut self, contract: &'b Hash, deposits: &'b IndexMap<Hash, ContractDeposit>, tx_hash: &'b Hash) -> Result<(ContractEnvironment<'b, S>, ContractChainState<'b>), BlockchainError> {
         // Find the contract module in our cache
         // We don't use the function `get_contract_module_with_environment` because we need to return the mutable storage
-        let module = self.inner.contracts.get(&payload.contract)
-            .ok_or_else(|| BlockchainError::ContractNotFound(payload.contract.clone()))
+        let module = self.inner.contracts.get(contract)
+            .ok_or_else(|| BlockchainError::ContractNotFound(contract.clone()))
             .and_then(|(_, module)| module.as_ref()
                 .map(|m| m.as_ref())
-                .ok_or_else(|| BlockchainError::ContractNotFound(payload.contract.clone()))
+                .ok_or_else(|| BlockchainError::ContractNotFound(contract.clone()))
             )?;
 
         // Find the contract cache in our cache map
-        let cache = self.contracts_cache.get(&payload.contract);
+        let cache = self.contracts_cache.get(contract);
 
         // Create a deterministic random for the contract
-        let random = DeterministicRandom::new(&payload.contract, &self.block_hash, tx_hash);
+        let random = DeterministicRandom::new(contract, &self.block_hash, tx_hash);
 
         let state = ContractChainState {
             debug_mode: true,
             mainnet: self.inner.storage.is_mainnet(),
-            contract: &payload.contract,
+            capabilities: self.contract_capabilities,
+            contract,
             topoheight: self.inner.topoheight,
             block_hash: self.block_hash,
             block: self.block,
-            deposits: &payload.deposits,
+            deposits,
             random,
             tx_hash,
             cache,
@@ -274,6 +338,7 @@ impl<'a, S: Storage> ApplicableChainState<'a, S> {
         burned_supply: u64,
         block_hash: &'a Hash,
         block: &'a Block,
+        contract_capabilities: ContractCapabilities,
     ) -> Self {
         Self {
             inner: ChainState::with(
@@ -285,7 +350,9 @@ impl<'a, S: Storage> ApplicableChainState<'a, S> {
             ),
             burned_supply,
             contracts_outputs: HashMap::new(),
+            contracts_receipts: HashMap::new(),
             contracts_cache: HashMap::new(),
+            contract_capabilities,
             block_hash,
             block
         }
@@ -296,6 +363,26 @@ impl<'a, S: Storage> ApplicableChainState<'a, S> {
         self.inner.storage.as_mut()
     }
 
+    // Get the burned supply tracked (including the one set at creation)
+    pub fn get_burned_supply(&self) -> u64 {
+        self.burned_supply
+    }
+
+    // Get the contract outputs tracked for a TX
+    pub fn get_contract_outputs_for_tx(&self, tx_hash: &Hash) -> Option<&Vec<ContractOutput>> {
+        self.contracts_outputs.get(tx_hash)
+    }
+
+    // Get the contract receipts tracked for a TX
+    pub fn get_contract_receipts_for_tx(&self, tx_hash: &Hash) -> Option<&Vec<ContractReceipt>> {
+        self.contracts_receipts.get(tx_hash)
+    }
+
+    // Get the contract cache containing all the changes made by a contract
+    pub fn get_contract_cache(&self, contract: &Hash) -> Option<&ContractCache> {
+        self.contracts_cache.get(contract)
+    }
+
     // This function is called after the verification of all needed transactions
     // This will consume ChainState and apply all changes to the storage
     // In case of incoming and outgoing transactions in same state, the final balance will be computed
@@ -374,7 +461,7 @@ impl<'a, S: Storage> ApplicableChainState<'a, S> {
                             trace!("{} has no balance for {} at topoheight {}, substract output sum", key.as_address(self.inner.storage.is_mainnet()), asset, self.inner.topoheight);
                             *new_version.get_mut_balance().computable()? -= output_sum;
 
-                            if self.inner.block_version == BlockVersion::V0 {
+                            if !Feature::OutputBalanceReport.is_enabled_in(self.inner.block_version) {
                                 new_version.set_balance_type(BalanceType::Output);
                             } else {
                                 // Report the output balance to the next topoheight
@@ -438,11 +525,34 @@ impl<'a, S: Storage> ApplicableChainState<'a, S> {
             }
         }
 
+        // Store the contracts management states updated
+        for (hash, (state, management)) in self.inner.contracts_management {
+            if let Some(management) = management.filter(|_| state.should_be_stored()) {
+                trace!("Saving contract {} management at topoheight {}", hash, self.inner.topoheight);
+                let versioned = VersionedContractManagement::new(management, state.get_topoheight());
+                self.inner.storage.set_last_contract_management_to(&hash, self.inner.topoheight, &versioned).await?;
+            }
+        }
+
+        // Store the execution credits updated
+        for (key, (state, credits)) in self.inner.execution_credits {
+            if state.should_be_stored() {
+                trace!("Saving execution credits {} for {} at topoheight {}", credits, key.as_address(self.inner.storage.is_mainnet()), self.inner.topoheight);
+                let versioned = VersionedExecutionCredits::new(credits, state.get_topoheight());
+                self.inner.storage.set_last_execution_credits_to(key, self.inner.topoheight, &versioned).await?;
+            }
+        }
+
         // Apply all the contract outputs
         for (key, outputs) in self.contracts_outputs {
             self.inner.storage.set_contract_outputs_for_tx(&key, outputs).await?;
         }
 
+        // Apply all the contract receipts
+        for (key, receipts) in self.contracts_receipts {
+            self.inner.storage.set_contract_receipts_for_tx(&key, receipts).await?;
+        }
+
         // Apply all balances changes at topoheight
         // We injected the sender balances in the receiver balances previously
         for (account, balances) in self.inner.receiver_balances {
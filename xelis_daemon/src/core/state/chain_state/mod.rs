@@ -16,6 +16,7 @@ use xelis_common::{
     },
     block::{BlockVersion, TopoHeight},
     config::XELIS_ASSET,
+    contract::ContractManagementState,
     crypto::{
         elgamal::Ciphertext,
         Hash,
@@ -118,6 +119,10 @@ pub struct ChainState<'a, S: Storage> {
     topoheight: TopoHeight,
     // All contracts updated
     contracts: HashMap<&'a Hash, (VersionedState, Option<Cow<'a, Module>>)>,
+    // All contracts management states loaded or updated
+    contracts_management: HashMap<&'a Hash, (VersionedState, Option<ContractManagementState>)>,
+    // All execution credits loaded or updated
+    execution_credits: HashMap<&'a PublicKey, (VersionedState, u64)>,
     // Block header version
     block_version: BlockVersion,
     // All gas fees tracked
@@ -140,6 +145,8 @@ impl<'a, S: Storage> ChainState<'a, S> {
             stable_topoheight,
             topoheight,
             contracts: HashMap::new(),
+            contracts_management: HashMap::new(),
+            execution_credits: HashMap::new(),
             block_version,
             gas_fee: 0
         }
@@ -314,6 +321,36 @@ impl<'a, S: Storage> ChainState<'a, S> {
             .and_then(|(_, module)| module.as_ref().map(|m| m.as_ref()).ok_or_else(|| BlockchainError::ContractNotFound(hash.clone())))
     }
 
+    // Search for a contract management state
+    // if not found, fetch it from the storage
+    async fn internal_get_contract_management(&mut self, hash: &'a Hash) -> Result<&mut (VersionedState, Option<ContractManagementState>), BlockchainError> {
+        match self.contracts_management.entry(hash) {
+            Entry::Occupied(o) => Ok(o.into_mut()),
+            Entry::Vacant(e) => {
+                let management = self.storage.get_contract_management_at_maximum_topoheight_for(hash, self.topoheight).await?
+                    .map(|(topo, version)| (VersionedState::FetchedAt(topo), Some(version.take())))
+                    .unwrap_or((VersionedState::New, None));
+
+                Ok(e.insert(management))
+            }
+        }
+    }
+
+    // Search for the execution credits of an account
+    // if not found, fetch it from the storage
+    async fn internal_get_execution_credits(&mut self, key: &'a PublicKey) -> Result<&mut (VersionedState, u64), BlockchainError> {
+        match self.execution_credits.entry(key) {
+            Entry::Occupied(o) => Ok(o.into_mut()),
+            Entry::Vacant(e) => {
+                let credits = self.storage.get_execution_credits_at_maximum_topoheight(key, self.topoheight).await?
+                    .map(|(topo, version)| (VersionedState::FetchedAt(topo), version.take()))
+                    .unwrap_or((VersionedState::New, 0));
+
+                Ok(e.insert(credits))
+            }
+        }
+    }
+
     // Reward a miner for the block mined
     pub async fn reward_miner(&mut self, miner: &'a PublicKey, reward: u64) -> Result<(), BlockchainError> {
         debug!("Rewarding miner {} with {} XEL at topoheight {}", miner.as_address(self.storage.is_mainnet()), format_xelis(reward), self.topoheight);
@@ -451,4 +488,48 @@ impl<'a, S: Storage> BlockchainVerificationState<'a, BlockchainError> for ChainS
         let module = self.internal_get_contract_module(hash).await?;
         Ok((module, self.environment))
     }
+
+    /// Get the management state of a contract
+    async fn get_contract_management_state(
+        &mut self,
+        hash: &'a Hash
+    ) -> Result<Option<&ContractManagementState>, BlockchainError> {
+        let (_, management) = self.internal_get_contract_management(hash).await?;
+        Ok(management.as_ref())
+    }
+
+    /// Set the management state of a contract
+    async fn set_contract_management_state(
+        &mut self,
+        hash: &'a Hash,
+        state: ContractManagementState
+    ) -> Result<(), BlockchainError> {
+        let (version, management) = self.internal_get_contract_management(hash).await?;
+        version.mark_updated();
+        *management = Some(state);
+
+        Ok(())
+    }
+
+    /// Get the execution credits locked by an account
+    async fn get_execution_credits(
+        &mut self,
+        account: &'a PublicKey
+    ) -> Result<u64, BlockchainError> {
+        let (_, credits) = self.internal_get_execution_credits(account).await?;
+        Ok(*credits)
+    }
+
+    /// Set the execution credits locked by an account
+    async fn set_execution_credits(
+        &mut self,
+        account: &'a PublicKey,
+        credits: u64
+    ) -> Result<(), BlockchainError> {
+        let (version, current) = self.internal_get_execution_credits(account).await?;
+        version.mark_updated();
+        *current = credits;
+
+        Ok(())
+    }
 }
\ No newline at end of file
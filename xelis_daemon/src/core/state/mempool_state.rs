@@ -3,6 +3,7 @@ use async_trait::async_trait;
 use xelis_common::{
     account::Nonce,
     block::{BlockVersion, TopoHeight},
+    contract::ContractManagementState,
     crypto::{
         elgamal::Ciphertext,
         Hash,
@@ -52,6 +53,10 @@ pub struct MempoolState<'a, S: Storage> {
     accounts: HashMap<&'a PublicKey, Account<'a>>,
     // Contract modules
     contracts: HashMap<&'a Hash, Cow<'a, Module>>,
+    // Contracts management states, None if the contract has none
+    contracts_management: HashMap<&'a Hash, Option<ContractManagementState>>,
+    // Execution credits locked by the accounts
+    execution_credits: HashMap<&'a PublicKey, u64>,
     // The current stable topoheight of the chain
     stable_topoheight: TopoHeight,
     // The current topoheight of the chain
@@ -70,6 +75,8 @@ impl<'a, S: Storage> MempoolState<'a, S> {
             receiver_balances: HashMap::new(),
             accounts: HashMap::new(),
             contracts: HashMap::new(),
+            contracts_management: HashMap::new(),
+            execution_credits: HashMap::new(),
             stable_topoheight,
             topoheight,
             block_version,
@@ -328,4 +335,57 @@ impl<'a, S: Storage> BlockchainVerificationState<'a, BlockchainError> for Mempoo
 
         Ok((module, self.environment))
     }
+
+    async fn get_contract_management_state(
+        &mut self,
+        hash: &'a Hash
+    ) -> Result<Option<&ContractManagementState>, BlockchainError> {
+        let management = match self.contracts_management.entry(hash) {
+            Entry::Occupied(o) => o.into_mut(),
+            Entry::Vacant(e) => {
+                let management = self.storage.get_contract_management_at_maximum_topoheight_for(hash, self.topoheight).await?
+                    .map(|(_, version)| version.take());
+
+                e.insert(management)
+            }
+        };
+
+        Ok(management.as_ref())
+    }
+
+    async fn set_contract_management_state(
+        &mut self,
+        hash: &'a Hash,
+        state: ContractManagementState
+    ) -> Result<(), BlockchainError> {
+        self.contracts_management.insert(hash, Some(state));
+        Ok(())
+    }
+
+    async fn get_execution_credits(
+        &mut self,
+        account: &'a PublicKey
+    ) -> Result<u64, BlockchainError> {
+        let credits = match self.execution_credits.entry(account) {
+            Entry::Occupied(o) => *o.get(),
+            Entry::Vacant(e) => {
+                let credits = self.storage.get_execution_credits_at_maximum_topoheight(account, self.topoheight).await?
+                    .map(|(_, version)| version.take())
+                    .unwrap_or(0);
+
+                *e.insert(credits)
+            }
+        };
+
+        Ok(credits)
+    }
+
+    async fn set_execution_credits(
+        &mut self,
+        account: &'a PublicKey,
+        credits: u64
+    ) -> Result<(), BlockchainError> {
+        self.execution_credits.insert(account, credits);
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -28,9 +28,9 @@ use super::{
 // We only verify the reference and the required fees
 pub (super) async fn pre_verify_tx<P: AccountProvider + BalanceProvider>(provider: &P, tx: &Transaction, stable_topoheight: TopoHeight, topoheight: TopoHeight, block_version: BlockVersion) -> Result<(), BlockchainError> {
     debug!("Pre-verify TX at topoheight {} and stable topoheight {}", topoheight, stable_topoheight);
-    if !hard_fork::is_tx_version_allowed_in_block_version(tx.get_version(), block_version) {
-        debug!("Invalid version {} in block {}", tx.get_version(), block_version);
-        return Err(BlockchainError::InvalidTxVersion);
+    if let Err(e) = hard_fork::verify_tx_for_block_version(tx, block_version) {
+        debug!("TX version {} can't be included in block {}: {}", tx.get_version(), block_version, e);
+        return Err(e);
     }
 
     let required_fees = blockchain::estimate_required_tx_fees(provider, topoheight, tx, block_version).await?;
@@ -30,10 +30,16 @@ pub trait Storage:
     + NonceProvider + AccountProvider + ClientProtocolProvider + BlockDagProvider
     + MerkleHashProvider + NetworkProvider + MultiSigProvider + TipsProvider
     + CommitPointProvider + ContractProvider + ContractDataProvider + ContractOutputsProvider
-    + ContractInfoProvider + ContractBalanceProvider + VersionedProvider + Sync + Send + 'static {
+    + ContractInfoProvider + ContractBalanceProvider + ContractManagementProvider + ContractReceiptProvider + ExecutionCreditsProvider + VersionedProvider + Sync + Send + 'static {
     // Clear caches if exists
     async fn clear_caches(&mut self) -> Result<(), BlockchainError>;
 
+    // Size of the caches, None if they are disabled
+    fn get_cache_size(&self) -> Option<usize>;
+
+    // Resize the caches, they can't be enabled at runtime
+    async fn set_cache_size(&mut self, size: usize) -> Result<(), BlockchainError>;
+
     // delete block at topoheight, and all pointers (hash_at_topo, topo_by_hash, reward, supply, diff, cumulative diff...)
     async fn delete_block_at_topoheight(&mut self, topoheight: TopoHeight) -> Result<(Hash, Arc<BlockHeader>, Vec<(Hash, Arc<Transaction>)>), BlockchainError>;
 
@@ -72,4 +78,7 @@ pub trait Storage:
 
     // Estimate the size of the DB in bytes
     async fn estimate_size(&self) -> Result<u64, BlockchainError>;
+
+    // Check if the storage is still accepting writes
+    async fn is_writable(&self) -> bool;
 }
\ No newline at end of file
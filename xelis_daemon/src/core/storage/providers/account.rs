@@ -1,3 +1,4 @@
+use std::ops::Bound;
 use async_trait::async_trait;
 use indexmap::IndexSet;
 use log::trace;
@@ -35,6 +36,10 @@ pub trait AccountProvider {
     // Get registered accounts supporting pagination and filtering by topoheight
     // Returned keys must have a nonce or a balance updated in the range given
     async fn get_registered_keys(&self, maximum: usize, skip: usize, minimum_topoheight: TopoHeight, maximum_topoheight: TopoHeight) -> Result<(IndexSet<PublicKey>, usize), BlockchainError>;
+
+    // Get registered accounts in the range given, starting after the cursor key
+    // Keys are ordered by registration topoheight, cursor must be a registered key
+    async fn get_registered_keys_after(&self, maximum: usize, cursor: Option<&PublicKey>, minimum_topoheight: TopoHeight, maximum_topoheight: TopoHeight) -> Result<IndexSet<PublicKey>, BlockchainError>;
 }
 
 fn prefixed_db_key(topoheight: TopoHeight, key: &PublicKey) -> [u8; 40] {
@@ -154,4 +159,43 @@ impl AccountProvider for SledStorage {
 
         Ok((keys, local_index))
     }
+
+    async fn get_registered_keys_after(&self, maximum: usize, cursor: Option<&PublicKey>, minimum_topoheight: TopoHeight, maximum_topoheight: TopoHeight) -> Result<IndexSet<PublicKey>, BlockchainError> {
+        trace!("get registered keys after cursor, maximum: {}, minimum_topoheight: {}, maximum_topoheight: {}", maximum, minimum_topoheight, maximum_topoheight);
+
+        // Start directly from the cursor position instead of iterating over all the previous keys
+        let start = match cursor {
+            Some(key) => {
+                let topoheight = self.get_account_registration_topoheight(key).await?;
+                Bound::Excluded(prefixed_db_key(topoheight.max(minimum_topoheight), key))
+            },
+            None => {
+                // lowest possible key at the minimum topoheight
+                let mut buf = [0u8; 40];
+                buf[0..8].copy_from_slice(&minimum_topoheight.to_bytes());
+                Bound::Included(buf)
+            }
+        };
+
+        let mut keys = IndexSet::new();
+        for el in self.registrations_prefixed.range::<[u8; 40], _>((start, Bound::Unbounded)).keys() {
+            let key = el?;
+            let topo = TopoHeight::from_bytes(&key[0..8])?;
+            // Keys are ordered by topoheight, we can stop here
+            if topo > maximum_topoheight {
+                break;
+            }
+
+            if topo < minimum_topoheight {
+                continue;
+            }
+
+            keys.insert(PublicKey::from_bytes(&key[8..40])?);
+            if keys.len() >= maximum {
+                break;
+            }
+        }
+
+        Ok(keys)
+    }
 }
\ No newline at end of file
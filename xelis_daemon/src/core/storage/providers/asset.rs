@@ -1,3 +1,4 @@
+use std::ops::Bound;
 use async_trait::async_trait;
 use indexmap::{IndexMap, IndexSet};
 use log::trace;
@@ -11,6 +12,7 @@ use crate::core::{
     error::{BlockchainError, DiskContext},
     storage::SledStorage,
 };
+use super::NetworkProvider;
 
 #[async_trait]
 pub trait AssetProvider {
@@ -31,8 +33,9 @@ pub trait AssetProvider {
     async fn get_assets(&self) -> Result<Vec<Hash>, BlockchainError>;
 
     // Get a partial list of assets supporting pagination and filtering by topoheight
+    // If a cursor is set, iteration starts right after it
     // TODO: replace with impl Iterator<Item = Result<Hash, BlockchainError>> when async trait methods are stable
-    async fn get_partial_assets_with_topoheight(&self, maximum: usize, skip: usize, minimum_topoheight: TopoHeight, maximum_topoheight: TopoHeight) -> Result<IndexMap<Hash, (TopoHeight, AssetData)>, BlockchainError>;
+    async fn get_partial_assets_with_topoheight(&self, maximum: usize, skip: usize, cursor: Option<&Hash>, minimum_topoheight: TopoHeight, maximum_topoheight: TopoHeight) -> Result<IndexMap<Hash, (TopoHeight, AssetData)>, BlockchainError>;
 
     // Get a partial list of assets supporting pagination and filtering by topoheight
     // TODO: replace with impl Iterator<Item = Result<Hash, BlockchainError>> when async trait methods are stable
@@ -46,6 +49,9 @@ pub trait AssetProvider {
     // TODO: replace with impl Iterator<Item = Result<Hash, BlockchainError>> when async trait methods are stable
     async fn get_assets_for(&self, key: &PublicKey) -> Result<Vec<Hash>, BlockchainError>;
 
+    // Get a partial list of assets for a specific key, starting after the cursor
+    async fn get_partial_assets_for(&self, key: &PublicKey, maximum: usize, cursor: Option<&Hash>) -> Result<Vec<Hash>, BlockchainError>;
+
     // Count the number of assets stored
     async fn count_assets(&self) -> Result<u64, BlockchainError>;
 
@@ -87,11 +93,15 @@ impl AssetProvider for SledStorage {
         }).collect()
     }
 
-    async fn get_partial_assets_with_topoheight(&self, maximum: usize, skip: usize, minimum_topoheight: TopoHeight, maximum_topoheight: TopoHeight) -> Result<IndexMap<Hash, (TopoHeight, AssetData)>, BlockchainError> {
+    async fn get_partial_assets_with_topoheight(&self, maximum: usize, skip: usize, cursor: Option<&Hash>, minimum_topoheight: TopoHeight, maximum_topoheight: TopoHeight) -> Result<IndexMap<Hash, (TopoHeight, AssetData)>, BlockchainError> {
         trace!("get partial assets with topoheight with maximum {} and skip {}", maximum, skip);
         let mut assets = IndexMap::new();
         let mut skip_count = 0;
-        for el in self.assets.iter() {
+        let start = match cursor {
+            Some(cursor) => Bound::Excluded(cursor.as_bytes()),
+            None => Bound::Unbounded
+        };
+        for el in self.assets.range::<&[u8; 32], _>((start, Bound::Unbounded)) {
             let (key, value) = el?;
             let topo = u64::from_bytes(&value)?;
             // check that we have a registered asset before the maximum topoheight
@@ -159,6 +169,30 @@ impl AssetProvider for SledStorage {
         }).collect()
     }
 
+    async fn get_partial_assets_for(&self, key: &PublicKey, maximum: usize, cursor: Option<&Hash>) -> Result<Vec<Hash>, BlockchainError> {
+        trace!("get partial assets for {} with maximum {}", key.as_address(self.is_mainnet()), maximum);
+        let start = match cursor {
+            Some(cursor) => Bound::Excluded(self.get_balance_key_for(key, cursor)),
+            None => Bound::Included(self.get_balance_key_for(key, &Hash::zero()))
+        };
+
+        let mut assets = Vec::new();
+        for el in self.balances.range::<[u8; 64], _>((start, Bound::Unbounded)).keys() {
+            let k = el?;
+            // We are out of the key prefix
+            if !k.starts_with(key.as_bytes()) {
+                break;
+            }
+
+            assets.push(Hash::from_bytes(&k[RISTRETTO_COMPRESSED_SIZE..])?);
+            if assets.len() >= maximum {
+                break;
+            }
+        }
+
+        Ok(assets)
+    }
+
     // count assets in storage
     async fn count_assets(&self) -> Result<u64, BlockchainError> {
         trace!("count assets");
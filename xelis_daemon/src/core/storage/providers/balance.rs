@@ -54,6 +54,10 @@ pub trait BalanceProvider: AssetProvider + NetworkProvider {
     // Get the asset versioned balances for multiple keys
     async fn get_versioned_balances<'a, I: Iterator<Item = &'a PublicKey> + Send>(&self, asset: &Hash, keys: I, maximum_topoheight: TopoHeight) -> Result<Vec<Option<VersionedBalance>>, BlockchainError>;
 
+    // Get all the versioned balances written at exactly this topoheight
+    // They are ordered by key then asset
+    async fn get_balances_at_exact_topoheight(&self, topoheight: TopoHeight) -> Result<Vec<(PublicKey, Hash, VersionedBalance)>, BlockchainError>;
+
     // Set the last topoheight for this asset and key to the requested topoheight
     fn set_last_topoheight_for_balance(&mut self, key: &PublicKey, asset: &Hash, topoheight: TopoHeight) -> Result<(), BlockchainError>;
 
@@ -293,6 +297,21 @@ impl BalanceProvider for SledStorage {
         Ok(balances)
     }
 
+    async fn get_balances_at_exact_topoheight(&self, topoheight: TopoHeight) -> Result<Vec<(PublicKey, Hash, VersionedBalance)>, BlockchainError> {
+        trace!("get balances at exact topoheight {}", topoheight);
+        // TODO: scan prefix support snapshot
+        let mut balances = Vec::new();
+        for el in self.versioned_balances.scan_prefix(&topoheight.to_be_bytes()) {
+            let (key, value) = el?;
+            let asset = Hash::from_bytes(&key[40..72])?;
+            let key = PublicKey::from_bytes(&key[8..40])?;
+            let version = VersionedBalance::from_bytes(&value)?;
+            balances.push((key, asset, version));
+        }
+
+        Ok(balances)
+    }
+
     // save the asset balance at specific topoheight
     async fn set_balance_at_topoheight(&mut self, asset: &Hash, topoheight: TopoHeight, key: &PublicKey, balance: &VersionedBalance) -> Result<(), BlockchainError> {
         trace!("set balance {} at topoheight {} for {}", asset, topoheight, key.as_address(self.is_mainnet()));
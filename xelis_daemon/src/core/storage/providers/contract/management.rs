@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use log::trace;
+use xelis_common::{
+    block::TopoHeight,
+    contract::ContractManagementState,
+    crypto::Hash,
+    serializer::Serializer,
+    versioned_type::Versioned
+};
+use crate::core::{
+    error::{BlockchainError, DiskContext},
+    storage::SledStorage
+};
+
+// Owner and pause flag of a contract, updated by the management TXs
+pub type VersionedContractManagement = Versioned<ContractManagementState>;
+
+#[async_trait]
+pub trait ContractManagementProvider {
+    // Retrieve the last topoheight for the management state of a contract
+    async fn get_last_topoheight_for_contract_management(&self, hash: &Hash) -> Result<Option<TopoHeight>, BlockchainError>;
+
+    // Retrieve the management state of a contract at a given topoheight
+    async fn get_contract_management_at_topoheight_for(&self, hash: &Hash, topoheight: TopoHeight) -> Result<VersionedContractManagement, BlockchainError>;
+
+    // Retrieve the management state of a contract at maximum topoheight
+    async fn get_contract_management_at_maximum_topoheight_for(&self, hash: &Hash, maximum_topoheight: TopoHeight) -> Result<Option<(TopoHeight, VersionedContractManagement)>, BlockchainError>;
+
+    // Store the management state of a contract at a given topoheight
+    async fn set_contract_management_at_topoheight_for(&mut self, hash: &Hash, topoheight: TopoHeight, state: &VersionedContractManagement) -> Result<(), BlockchainError>;
+
+    // Set the last topoheight for the management state of a contract
+    async fn set_last_topoheight_for_contract_management(&mut self, hash: &Hash, topoheight: TopoHeight) -> Result<(), BlockchainError>;
+
+    // Delete the last topoheight for the management state of a contract
+    async fn delete_last_topoheight_for_contract_management(&mut self, hash: &Hash) -> Result<(), BlockchainError>;
+
+    // Store the last management state of a contract
+    async fn set_last_contract_management_to(&mut self, hash: &Hash, topoheight: TopoHeight, state: &VersionedContractManagement) -> Result<(), BlockchainError> {
+        self.set_contract_management_at_topoheight_for(hash, topoheight, state).await?;
+        self.set_last_topoheight_for_contract_management(hash, topoheight).await
+    }
+}
+
+#[async_trait]
+impl ContractManagementProvider for SledStorage {
+    async fn get_last_topoheight_for_contract_management(&self, hash: &Hash) -> Result<Option<TopoHeight>, BlockchainError> {
+        trace!("get last topoheight for contract {} management", hash);
+        self.load_optional_from_disk(&self.contracts_management, hash.as_bytes())
+    }
+
+    async fn get_contract_management_at_topoheight_for(&self, hash: &Hash, topoheight: TopoHeight) -> Result<VersionedContractManagement, BlockchainError> {
+        trace!("get contract {} management at topoheight {}", hash, topoheight);
+        let key = self.get_versioned_contract_key(hash, topoheight);
+        self.load_from_disk(&self.versioned_contracts_management, &key, DiskContext::ContractManagementAtTopoHeight)
+    }
+
+    async fn get_contract_management_at_maximum_topoheight_for(&self, hash: &Hash, maximum_topoheight: TopoHeight) -> Result<Option<(TopoHeight, VersionedContractManagement)>, BlockchainError> {
+        trace!("get contract {} management at maximum topoheight {}", hash, maximum_topoheight);
+        let mut previous_topoheight = self.get_last_topoheight_for_contract_management(hash).await?;
+        while let Some(topoheight) = previous_topoheight {
+            if topoheight <= maximum_topoheight {
+                let version = self.get_contract_management_at_topoheight_for(hash, topoheight).await?;
+                return Ok(Some((topoheight, version)))
+            }
+
+            previous_topoheight = self.load_from_disk(
+                &self.versioned_contracts_management,
+                &self.get_versioned_contract_key(hash, topoheight),
+                DiskContext::ContractManagementAtTopoHeight
+            )?;
+        }
+
+        Ok(None)
+    }
+
+    async fn set_contract_management_at_topoheight_for(&mut self, hash: &Hash, topoheight: TopoHeight, state: &VersionedContractManagement) -> Result<(), BlockchainError> {
+        trace!("set contract {} management at topoheight {}", hash, topoheight);
+        let key = self.get_versioned_contract_key(hash, topoheight);
+        Self::insert_into_disk(self.snapshot.as_mut(), &self.versioned_contracts_management, &key, state.to_bytes())?;
+        Ok(())
+    }
+
+    async fn set_last_topoheight_for_contract_management(&mut self, hash: &Hash, topoheight: TopoHeight) -> Result<(), BlockchainError> {
+        trace!("set last topoheight for contract {} management to {}", hash, topoheight);
+        Self::insert_into_disk(self.snapshot.as_mut(), &self.contracts_management, hash.as_bytes(), &topoheight.to_be_bytes())?;
+        Ok(())
+    }
+
+    async fn delete_last_topoheight_for_contract_management(&mut self, hash: &Hash) -> Result<(), BlockchainError> {
+        trace!("delete last topoheight for contract {} management", hash);
+        Self::remove_from_disk_without_reading(self.snapshot.as_mut(), &self.contracts_management, hash.as_bytes())?;
+        Ok(())
+    }
+}
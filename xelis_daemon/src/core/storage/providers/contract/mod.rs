@@ -2,6 +2,8 @@ mod data;
 mod output;
 mod provider;
 mod balance;
+mod management;
+mod receipt;
 
 use std::borrow::Cow;
 
@@ -23,6 +25,8 @@ use log::trace;
 pub use data::*;
 pub use output::*;
 pub use balance::*;
+pub use management::*;
+pub use receipt::*;
 
 // A versioned contract is a contract that can be updated or deleted
 pub type VersionedContract<'a> = Versioned<Option<Cow<'a, Module>>>;
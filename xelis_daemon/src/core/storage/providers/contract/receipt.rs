@@ -0,0 +1,44 @@
+use async_trait::async_trait;
+use xelis_common::{
+    contract::ContractReceipt,
+    crypto::Hash,
+    serializer::Serializer
+};
+use crate::core::{
+    error::{BlockchainError, DiskContext},
+    storage::SledStorage
+};
+
+#[async_trait]
+pub trait ContractReceiptProvider {
+    // Retrieve the receipts of all contract invocations executed by a TX
+    async fn get_contract_receipts_for_tx(&self, tx_hash: &Hash) -> Result<Vec<ContractReceipt>, BlockchainError>;
+
+    // Check if a TX has contract receipts stored
+    async fn has_contract_receipts_for_tx(&self, tx_hash: &Hash) -> Result<bool, BlockchainError>;
+
+    async fn set_contract_receipts_for_tx(&mut self, tx_hash: &Hash, receipts: Vec<ContractReceipt>) -> Result<(), BlockchainError>;
+
+    async fn delete_contract_receipts_for_tx(&mut self, tx_hash: &Hash) -> Result<(), BlockchainError>;
+}
+
+#[async_trait]
+impl ContractReceiptProvider for SledStorage {
+    async fn get_contract_receipts_for_tx(&self, tx_hash: &Hash) -> Result<Vec<ContractReceipt>, BlockchainError> {
+        self.load_from_disk(&self.contracts_receipts, tx_hash.as_bytes(), DiskContext::ContractReceipt)
+    }
+
+    async fn has_contract_receipts_for_tx(&self, tx_hash: &Hash) -> Result<bool, BlockchainError> {
+        self.contains_data(&self.contracts_receipts, tx_hash)
+    }
+
+    async fn set_contract_receipts_for_tx(&mut self, tx_hash: &Hash, receipts: Vec<ContractReceipt>) -> Result<(), BlockchainError> {
+        Self::insert_into_disk(self.snapshot.as_mut(), &self.contracts_receipts, tx_hash.as_bytes(), receipts.to_bytes())?;
+        Ok(())
+    }
+
+    async fn delete_contract_receipts_for_tx(&mut self, tx_hash: &Hash) -> Result<(), BlockchainError> {
+        Self::remove_from_disk_without_reading(self.snapshot.as_mut(), &self.contracts_receipts, tx_hash.as_bytes())?;
+        Ok(())
+    }
+}
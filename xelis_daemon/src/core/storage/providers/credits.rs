@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use log::trace;
+use xelis_common::{
+    block::TopoHeight,
+    crypto::PublicKey,
+    serializer::Serializer,
+    versioned_type::Versioned
+};
+use crate::core::{
+    error::{BlockchainError, DiskContext},
+    storage::SledStorage
+};
+
+// XELIS locked by an account to pay the gas of its contract invocations
+pub type VersionedExecutionCredits = Versioned<u64>;
+
+#[async_trait]
+pub trait ExecutionCreditsProvider {
+    // Retrieve the last topoheight for the execution credits of an account
+    async fn get_last_topoheight_for_execution_credits(&self, key: &PublicKey) -> Result<Option<TopoHeight>, BlockchainError>;
+
+    // Retrieve the execution credits of an account at a given topoheight
+    async fn get_execution_credits_at_topoheight(&self, key: &PublicKey, topoheight: TopoHeight) -> Result<VersionedExecutionCredits, BlockchainError>;
+
+    // Retrieve the execution credits of an account at maximum topoheight
+    async fn get_execution_credits_at_maximum_topoheight(&self, key: &PublicKey, maximum_topoheight: TopoHeight) -> Result<Option<(TopoHeight, VersionedExecutionCredits)>, BlockchainError>;
+
+    // Store the execution credits of an account at a given topoheight
+    async fn set_execution_credits_at_topoheight(&mut self, key: &PublicKey, topoheight: TopoHeight, credits: &VersionedExecutionCredits) -> Result<(), BlockchainError>;
+
+    // Set the last topoheight for the execution credits of an account
+    async fn set_last_topoheight_for_execution_credits(&mut self, key: &PublicKey, topoheight: TopoHeight) -> Result<(), BlockchainError>;
+
+    // Delete the last topoheight for the execution credits of an account
+    async fn delete_last_topoheight_for_execution_credits(&mut self, key: &PublicKey) -> Result<(), BlockchainError>;
+
+    // Store the last execution credits of an account
+    async fn set_last_execution_credits_to(&mut self, key: &PublicKey, topoheight: TopoHeight, credits: &VersionedExecutionCredits) -> Result<(), BlockchainError> {
+        self.set_execution_credits_at_topoheight(key, topoheight, credits).await?;
+        self.set_last_topoheight_for_execution_credits(key, topoheight).await
+    }
+}
+
+#[async_trait]
+impl ExecutionCreditsProvider for SledStorage {
+    async fn get_last_topoheight_for_execution_credits(&self, key: &PublicKey) -> Result<Option<TopoHeight>, BlockchainError> {
+        trace!("get last topoheight for execution credits of {}", key.as_address(self.is_mainnet()));
+        self.load_optional_from_disk(&self.execution_credits, key.as_bytes())
+    }
+
+    async fn get_execution_credits_at_topoheight(&self, key: &PublicKey, topoheight: TopoHeight) -> Result<VersionedExecutionCredits, BlockchainError> {
+        trace!("get execution credits of {} at topoheight {}", key.as_address(self.is_mainnet()), topoheight);
+        let versioned_key = Self::get_versioned_key(key.as_bytes(), topoheight);
+        self.load_from_disk(&self.versioned_execution_credits, &versioned_key, DiskContext::ExecutionCreditsAtTopoHeight)
+    }
+
+    async fn get_execution_credits_at_maximum_topoheight(&self, key: &PublicKey, maximum_topoheight: TopoHeight) -> Result<Option<(TopoHeight, VersionedExecutionCredits)>, BlockchainError> {
+        trace!("get execution credits of {} at maximum topoheight {}", key.as_address(self.is_mainnet()), maximum_topoheight);
+        let mut previous_topoheight = self.get_last_topoheight_for_execution_credits(key).await?;
+        while let Some(topoheight) = previous_topoheight {
+            if topoheight <= maximum_topoheight {
+                let version = self.get_execution_credits_at_topoheight(key, topoheight).await?;
+                return Ok(Some((topoheight, version)))
+            }
+
+            previous_topoheight = self.load_from_disk(
+                &self.versioned_execution_credits,
+                &Self::get_versioned_key(key.as_bytes(), topoheight),
+                DiskContext::ExecutionCreditsAtTopoHeight
+            )?;
+        }
+
+        Ok(None)
+    }
+
+    async fn set_execution_credits_at_topoheight(&mut self, key: &PublicKey, topoheight: TopoHeight, credits: &VersionedExecutionCredits) -> Result<(), BlockchainError> {
+        trace!("set execution credits of {} at topoheight {}", key.as_address(self.is_mainnet()), topoheight);
+        let versioned_key = Self::get_versioned_key(key.as_bytes(), topoheight);
+        Self::insert_into_disk(self.snapshot.as_mut(), &self.versioned_execution_credits, &versioned_key, credits.to_bytes())?;
+        Ok(())
+    }
+
+    async fn set_last_topoheight_for_execution_credits(&mut self, key: &PublicKey, topoheight: TopoHeight) -> Result<(), BlockchainError> {
+        trace!("set last topoheight for execution credits of {} to {}", key.as_address(self.is_mainnet()), topoheight);
+        Self::insert_into_disk(self.snapshot.as_mut(), &self.execution_credits, key.as_bytes(), &topoheight.to_be_bytes())?;
+        Ok(())
+    }
+
+    async fn delete_last_topoheight_for_execution_credits(&mut self, key: &PublicKey) -> Result<(), BlockchainError> {
+        trace!("delete last topoheight for execution credits of {}", key.as_address(self.is_mainnet()));
+        Self::remove_from_disk_without_reading(self.snapshot.as_mut(), &self.execution_credits, key.as_bytes())?;
+        Ok(())
+    }
+}
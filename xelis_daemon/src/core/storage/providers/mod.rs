@@ -18,6 +18,7 @@ mod commit_point;
 mod tips_provider;
 mod db;
 mod contract;
+mod credits;
 mod versioned;
 
 pub use asset::AssetProvider;
@@ -40,4 +41,5 @@ pub use commit_point::CommitPointProvider;
 pub use tips_provider::TipsProvider;
 pub use db::DBProvider;
 pub use contract::*;
+pub use credits::*;
 pub use versioned::VersionedProvider;
\ No newline at end of file
@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use log::trace;
+use xelis_common::{
+    block::TopoHeight,
+    crypto::Hash,
+    serializer::Serializer
+};
+use crate::core::{
+    error::{BlockchainError, DiskContext},
+    storage::{
+        ContractManagementProvider,
+        SledStorage,
+        VersionedContractManagement
+    }
+};
+
+#[async_trait]
+pub trait VersionedContractManagementProvider {
+    // delete versioned contracts management at topoheight
+    async fn delete_versioned_contracts_management_at_topoheight(&mut self, topoheight: TopoHeight) -> Result<(), BlockchainError>;
+
+    // delete versioned contracts management above topoheight
+    async fn delete_versioned_contracts_management_above_topoheight(&mut self, topoheight: TopoHeight) -> Result<(), BlockchainError>;
+
+    // delete versioned contracts management below topoheight
+    async fn delete_versioned_contracts_management_below_topoheight(&mut self, topoheight: TopoHeight, keep_last: bool) -> Result<(), BlockchainError>;
+}
+
+#[async_trait]
+impl VersionedContractManagementProvider for SledStorage {
+    async fn delete_versioned_contracts_management_at_topoheight(&mut self, topoheight: TopoHeight) -> Result<(), BlockchainError> {
+        trace!("delete versioned contracts management at topoheight {}", topoheight);
+        for el in self.versioned_contracts_management.scan_prefix(&topoheight.to_be_bytes()) {
+            let (key, value) = el?;
+            // Delete this version from DB
+            Self::remove_from_disk_without_reading(self.snapshot.as_mut(), &self.versioned_contracts_management, &key)?;
+
+            // Deserialize keys part
+            let key = Hash::from_bytes(&key[8..])?;
+
+            // Because of chain reorg, it may have been already deleted
+            if let Some(last_topoheight) = self.get_last_topoheight_for_contract_management(&key).await? {
+                if last_topoheight >= topoheight {
+                    // Deserialize value, it is needed to get the previous topoheight
+                    let version = VersionedContractManagement::from_bytes(&value)?;
+                    // Now records changes
+                    if let Some(previous_topoheight) = version.get_previous_topoheight() {
+                        self.set_last_topoheight_for_contract_management(&key, previous_topoheight).await?;
+                    } else {
+                        // if there is no previous topoheight, it means that this is the first version
+                        self.delete_last_topoheight_for_contract_management(&key).await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn delete_versioned_contracts_management_above_topoheight(&mut self, topoheight: u64) -> Result<(), BlockchainError> {
+        trace!("delete versioned contracts management above topoheight {}!", topoheight);
+        Self::delete_versioned_tree_above_topoheight(&mut self.snapshot, &self.versioned_contracts_management, topoheight)
+    }
+
+    async fn delete_versioned_contracts_management_below_topoheight(&mut self, topoheight: u64, keep_last: bool) -> Result<(), BlockchainError> {
+        trace!("delete versioned contracts management below topoheight {}!", topoheight);
+        Self::delete_versioned_tree_below_topoheight(&mut self.snapshot, &self.contracts_management, &self.versioned_contracts_management, topoheight, keep_last, DiskContext::ContractManagementAtTopoHeight)
+    }
+}
@@ -1,5 +1,6 @@
 mod data;
 mod balance;
+mod management;
 
 use async_trait::async_trait;
 use log::trace;
@@ -15,6 +16,7 @@ use crate::core::{
 
 pub use data::*;
 pub use balance::*;
+pub use management::*;
 
 #[async_trait]
 pub trait VersionedContractProvider {
@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use log::trace;
+use xelis_common::{
+    block::TopoHeight,
+    crypto::PublicKey,
+    serializer::Serializer
+};
+use crate::core::{
+    error::{BlockchainError, DiskContext},
+    storage::{
+        ExecutionCreditsProvider,
+        SledStorage,
+        VersionedExecutionCredits
+    }
+};
+
+#[async_trait]
+pub trait VersionedExecutionCreditsProvider {
+    // delete versioned execution credits at topoheight
+    async fn delete_versioned_execution_credits_at_topoheight(&mut self, topoheight: TopoHeight) -> Result<(), BlockchainError>;
+
+    // delete versioned execution credits above topoheight
+    async fn delete_versioned_execution_credits_above_topoheight(&mut self, topoheight: TopoHeight) -> Result<(), BlockchainError>;
+
+    // delete versioned execution credits below topoheight
+    async fn delete_versioned_execution_credits_below_topoheight(&mut self, topoheight: TopoHeight, keep_last: bool) -> Result<(), BlockchainError>;
+}
+
+#[async_trait]
+impl VersionedExecutionCreditsProvider for SledStorage {
+    async fn delete_versioned_execution_credits_at_topoheight(&mut self, topoheight: TopoHeight) -> Result<(), BlockchainError> {
+        trace!("delete versioned execution credits at topoheight {}", topoheight);
+        for el in self.versioned_execution_credits.scan_prefix(&topoheight.to_be_bytes()) {
+            let (key, value) = el?;
+            // Delete this version from DB
+            Self::remove_from_disk_without_reading(self.snapshot.as_mut(), &self.versioned_execution_credits, &key)?;
+
+            // Deserialize keys part
+            let key = PublicKey::from_bytes(&key[8..])?;
+
+            // Because of chain reorg, it may have been already deleted
+            if let Some(last_topoheight) = self.get_last_topoheight_for_execution_credits(&key).await? {
+                if last_topoheight >= topoheight {
+                    // Deserialize value, it is needed to get the previous topoheight
+                    let version = VersionedExecutionCredits::from_bytes(&value)?;
+                    // Now records changes
+                    if let Some(previous_topoheight) = version.get_previous_topoheight() {
+                        self.set_last_topoheight_for_execution_credits(&key, previous_topoheight).await?;
+                    } else {
+                        // if there is no previous topoheight, it means that this is the first version
+                        self.delete_last_topoheight_for_execution_credits(&key).await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn delete_versioned_execution_credits_above_topoheight(&mut self, topoheight: u64) -> Result<(), BlockchainError> {
+        trace!("delete versioned execution credits above topoheight {}!", topoheight);
+        Self::delete_versioned_tree_above_topoheight(&mut self.snapshot, &self.versioned_execution_credits, topoheight)
+    }
+
+    async fn delete_versioned_execution_credits_below_topoheight(&mut self, topoheight: u64, keep_last: bool) -> Result<(), BlockchainError> {
+        trace!("delete versioned execution credits below topoheight {}!", topoheight);
+        Self::delete_versioned_tree_below_topoheight(&mut self.snapshot, &self.execution_credits, &self.versioned_execution_credits, topoheight, keep_last, DiskContext::ExecutionCreditsAtTopoHeight)
+    }
+}
@@ -4,6 +4,7 @@ mod multisig;
 mod nonce;
 mod registrations;
 mod asset;
+mod credits;
 
 use async_trait::async_trait;
 use log::{debug, trace};
@@ -26,6 +27,7 @@ use multisig::VersionedMultiSigProvider;
 use nonce::VersionedNonceProvider;
 use registrations::VersionedRegistrationsProvider;
 use asset::VersionedAssetProvider;
+use credits::VersionedExecutionCreditsProvider;
 
 // Every versioned key should start with the topoheight in order to be able to delete them easily
 #[async_trait]
@@ -37,6 +39,8 @@ pub trait VersionedProvider:
     + VersionedRegistrationsProvider
     + VersionedContractDataProvider
     + VersionedContractBalanceProvider
+    + VersionedContractManagementProvider
+    + VersionedExecutionCreditsProvider
     + VersionedAssetProvider {
 
     // Delete versioned data at topoheight
@@ -47,6 +51,8 @@ pub trait VersionedProvider:
         self.delete_versioned_registrations_at_topoheight(topoheight).await?;
         self.delete_versioned_contracts_at_topoheight(topoheight).await?;
         self.delete_versioned_contract_data_at_topoheight(topoheight).await?;
+        self.delete_versioned_contracts_management_at_topoheight(topoheight).await?;
+        self.delete_versioned_execution_credits_at_topoheight(topoheight).await?;
 
         if topoheight > 0 {
             self.delete_versioned_assets_at_topoheight(topoheight).await?;
@@ -64,6 +70,8 @@ pub trait VersionedProvider:
         self.delete_versioned_multisigs_below_topoheight(topoheight, keep_last).await?;
         self.delete_versioned_contracts_below_topoheight(topoheight, keep_last).await?;
         self.delete_versioned_contract_data_below_topoheight(topoheight, keep_last).await?;
+        self.delete_versioned_contracts_management_below_topoheight(topoheight, keep_last).await?;
+        self.delete_versioned_execution_credits_below_topoheight(topoheight, keep_last).await?;
 
         Ok(())
     }
@@ -77,6 +85,8 @@ pub trait VersionedProvider:
         self.delete_versioned_registrations_above_topoheight(topoheight).await?;
         self.delete_versioned_contracts_above_topoheight(topoheight).await?;
         self.delete_versioned_contract_data_above_topoheight(topoheight).await?;
+        self.delete_versioned_contracts_management_above_topoheight(topoheight).await?;
+        self.delete_versioned_execution_credits_above_topoheight(topoheight).await?;
         self.delete_versioned_assets_above_topoheight(topoheight).await?;
         Ok(())
     }
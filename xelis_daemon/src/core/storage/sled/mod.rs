@@ -50,6 +50,8 @@ pub(super) const ASSETS_COUNT: &[u8; 4] = b"CAST";
 pub(super) const BLOCKS_COUNT: &[u8; 4] = b"CBLK";
 pub(super) const BLOCKS_EXECUTION_ORDER_COUNT: &[u8; 4] = b"EBLK";
 pub(super) const CONTRACTS_COUNT: &[u8; 4] = b"CCON";
+// Key used to probe if the DB is still writable
+pub(super) const HEALTH_PROBE: &[u8; 4] = b"HLTH";
 
 pub struct SledStorage {
     // Network used by the storage
@@ -134,6 +136,21 @@ pub struct SledStorage {
     // Contract outputs per TX
     // Key is the TX Hash that called the contract, value is a list of contract outputs
     pub(super) contracts_outputs: Tree,
+    // Contract receipts per TX
+    // Key is the TX Hash that called the contract, value is a list of receipts
+    pub(super) contracts_receipts: Tree,
+    // Management state (owner, paused) of the contracts
+    // Key is the contract hash, value is the latest topoheight
+    pub(super) contracts_management: Tree,
+    // Key is prefixed by the topoheight for fast scan_prefix search
+    // value is the versioned management state
+    pub(super) versioned_contracts_management: Tree,
+    // Execution credits locked by the accounts
+    // Key is the account public key, value is the latest topoheight
+    pub(super) execution_credits: Tree,
+    // Key is prefixed by the topoheight for fast scan_prefix search
+    // value is the versioned credits (u64)
+    pub(super) versioned_execution_credits: Tree,
     // opened DB used for assets to create dynamic assets
     pub(super) db: sled::Db,
 
@@ -152,6 +169,8 @@ pub struct SledStorage {
     pub(super) cumulative_difficulty_cache: Option<Mutex<LruCache<Hash, CumulativeDifficulty>>>,
     // Assets cache
     pub(super) assets_cache: Option<Mutex<LruCache<Hash, ()>>>,
+    // Size of each cache above, None if disabled
+    pub(super) cache_size: Option<usize>,
     // Tips cache: current chain Tips
     pub(super) tips_cache: Tips,
     // Pruned topoheight cache
@@ -185,6 +204,14 @@ macro_rules! init_cache {
     }};
 }
 
+macro_rules! resize_cache {
+    ($cache: expr, $size: expr) => {{
+        if let Some(cache) = $cache.as_ref() {
+            cache.lock().await.resize($size);
+        }
+    }};
+}
+
 #[derive(Clone, Copy, clap::ValueEnum, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum StorageMode {
@@ -268,6 +295,11 @@ impl SledStorage {
             contracts_balances: sled.open_tree("contracts_balances")?,
             versioned_contracts_balances: sled.open_tree("versioned_contracts_balances")?,
             contracts_outputs: sled.open_tree("contracts_outputs")?,
+            contracts_receipts: sled.open_tree("contracts_receipts")?,
+            contracts_management: sled.open_tree("contracts_management")?,
+            versioned_contracts_management: sled.open_tree("versioned_contracts_management")?,
+            execution_credits: sled.open_tree("execution_credits")?,
+            versioned_execution_credits: sled.open_tree("versioned_execution_credits")?,
             db: sled,
             transactions_cache: init_cache!(cache_size),
             blocks_cache: init_cache!(cache_size),
@@ -276,6 +308,7 @@ impl SledStorage {
             hash_at_topo_cache: init_cache!(cache_size),
             cumulative_difficulty_cache: init_cache!(cache_size),
             assets_cache: init_cache!(cache_size),
+            cache_size,
             tips_cache: HashSet::new(),
             pruned_topoheight: None,
             assets_count: 0,
@@ -641,6 +674,28 @@ impl Storage for SledStorage {
         Ok(())
     }
 
+    fn get_cache_size(&self) -> Option<usize> {
+        self.cache_size
+    }
+
+    async fn set_cache_size(&mut self, size: usize) -> Result<(), BlockchainError> {
+        if self.cache_size.is_none() {
+            return Err(BlockchainError::CacheDisabled)
+        }
+
+        let size = NonZeroUsize::new(size).ok_or(BlockchainError::InvalidRuntimeParam("cache size must be above 0"))?;
+        resize_cache!(self.transactions_cache, size);
+        resize_cache!(self.blocks_cache, size);
+        resize_cache!(self.past_blocks_cache, size);
+        resize_cache!(self.topo_by_hash_cache, size);
+        resize_cache!(self.hash_at_topo_cache, size);
+        resize_cache!(self.cumulative_difficulty_cache, size);
+        resize_cache!(self.assets_cache, size);
+        self.cache_size = Some(size.get());
+
+        Ok(())
+    }
+
     // Delete the whole block using its topoheight
     async fn delete_block_at_topoheight(&mut self, topoheight: u64) -> Result<(Hash, Arc<BlockHeader>, Vec<(Hash, Arc<Transaction>)>), BlockchainError> {
         trace!("Delete block at topoheight {topoheight}");
@@ -695,6 +750,7 @@ impl Storage for SledStorage {
                 trace!("Tx {} was executed, deleting", tx_hash);
                 self.remove_tx_executed(&tx_hash)?;
                 self.delete_contract_outputs_for_tx(&tx_hash).await?;
+                self.delete_contract_receipts_for_tx(&tx_hash).await?;
             }
 
             // We have to check first as we may have already deleted it because of client protocol
@@ -920,6 +976,46 @@ impl Storage for SledStorage {
             }
         }
 
+        // Clean the contracts management
+        trace!("Cleaning contracts management");
+        for el in self.contracts_management.iter() {
+            let (key, value) = el?;
+            let topo_pointer = TopoHeight::from_bytes(&value)?;
+
+            if topo_pointer > topoheight {
+                let contract = Hash::from_bytes(&key)?;
+                match self.get_contract_management_at_maximum_topoheight_for(&contract, topoheight).await? {
+                    Some((topo, _)) => {
+                        trace!("New highest version management for contract {} is at topoheight {}", contract, topo);
+                        Self::insert_into_disk(self.snapshot.as_mut(), &self.contracts_management, &key, &topo.to_be_bytes())?;
+                    },
+                    None => {
+                        Self::remove_from_disk_without_reading(self.snapshot.as_mut(), &self.contracts_management, &key)?;
+                    }
+                }
+            }
+        }
+
+        // Clean the execution credits
+        trace!("Cleaning execution credits");
+        for el in self.execution_credits.iter() {
+            let (key, value) = el?;
+            let topo_pointer = TopoHeight::from_bytes(&value)?;
+
+            if topo_pointer > topoheight {
+                let pkey = PublicKey::from_bytes(&key)?;
+                match self.get_execution_credits_at_maximum_topoheight(&pkey, topoheight).await? {
+                    Some((topo, _)) => {
+                        trace!("New highest version execution credits for {} is at topoheight {}", pkey.as_address(self.is_mainnet()), topo);
+                        Self::insert_into_disk(self.snapshot.as_mut(), &self.execution_credits, &key, &topo.to_be_bytes())?;
+                    },
+                    None => {
+                        Self::remove_from_disk_without_reading(self.snapshot.as_mut(), &self.execution_credits, &key)?;
+                    }
+                }
+            }
+        }
+
         warn!("Blocks rewinded: {}, new topoheight: {}, new height: {}", done, topoheight, height);
 
         trace!("Cleaning versioned balances and nonces");
@@ -1032,4 +1128,17 @@ impl Storage for SledStorage {
 
         Ok(size as u64)
     }
+
+    async fn is_writable(&self) -> bool {
+        trace!("is writable");
+        // Write directly in the tree and not in the snapshot
+        // as we want to check the DB itself
+        match self.extra.insert(HEALTH_PROBE, IVec::default()).and_then(|_| self.extra.remove(HEALTH_PROBE)) {
+            Ok(_) => true,
+            Err(e) => {
+                warn!("Storage is not writable: {}", e);
+                false
+            }
+        }
+    }
 }
\ No newline at end of file
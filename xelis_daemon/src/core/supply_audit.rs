@@ -0,0 +1,224 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+    time::Instant
+};
+use log::{debug, info};
+use xelis_common::{
+    block::TopoHeight,
+    config::{TRANSACTION_FEE_BURN_PERCENT, XELIS_ASSET},
+    contract::ContractOutput,
+    crypto::Hash,
+    transaction::{DeployContractPayload, TransactionType}
+};
+use super::{
+    blockchain::Blockchain,
+    error::BlockchainError,
+    storage::{
+        BlockDagProvider,
+        BlockProvider,
+        BlocksAtHeightProvider,
+        ClientProtocolProvider,
+        ContractOutputsProvider,
+        DagOrderProvider,
+        DifficultyProvider,
+        PrunedTopoheightProvider,
+        Storage
+    }
+};
+
+// Blocks audited between two progress logs
+const PROGRESS_INTERVAL: u64 = 10_000;
+
+// Counter that didn't match the replayed value
+#[derive(Debug, Clone, Copy)]
+pub enum SupplyCounter {
+    BlockReward,
+    Supply,
+    BurnedSupply
+}
+
+impl fmt::Display for SupplyCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BlockReward => write!(f, "block reward"),
+            Self::Supply => write!(f, "supply"),
+            Self::BurnedSupply => write!(f, "burned supply")
+        }
+    }
+}
+
+pub struct SupplyDivergence {
+    pub topoheight: TopoHeight,
+    pub block_hash: Hash,
+    pub counter: SupplyCounter,
+    // value computed by the replay
+    pub expected: u64,
+    // value tracked in storage
+    pub found: u64
+}
+
+pub struct SupplyAuditReport {
+    // Counters at this topoheight are trusted as the chain is pruned below it
+    pub start_topoheight: TopoHeight,
+    pub end_topoheight: TopoHeight,
+    // Coins emitted by the audited blocks
+    pub emitted: u64,
+    // Fees paid by the executed transactions, including the gas given to miners
+    pub fees: u64,
+    // Coins burned by the audited blocks
+    pub burned: u64,
+    // Supply computed at the last audited topoheight
+    pub supply: u64,
+    // First divergence found, the audit stops on it
+    pub divergence: Option<SupplyDivergence>
+}
+
+// Gas burned and gas given to the miners by a contract call
+async fn get_tx_gas_burn_and_fee<S: Storage>(storage: &S, tx_hash: &Hash, max_gas: u64) -> Result<(u64, u64), BlockchainError> {
+    // Only the remaining gas is tracked, the used gas is deduced from it
+    let refund: u64 = storage.get_contract_outputs_for_tx(tx_hash).await?
+        .into_iter()
+        .filter_map(|output| match output {
+            ContractOutput::RefundGas { amount } => Some(amount),
+            _ => None
+        })
+        .sum();
+
+    let used_gas = max_gas.checked_sub(refund).ok_or(BlockchainError::Overflow)?;
+    let burned_gas = used_gas * TRANSACTION_FEE_BURN_PERCENT / 100;
+    Ok((burned_gas, used_gas - burned_gas))
+}
+
+// Coins burned and fees paid by the transactions executed in a block
+async fn get_block_burns_and_fees<S: Storage>(storage: &S, hash: &Hash) -> Result<(u64, u64), BlockchainError> {
+    let block = storage.get_block_by_hash(hash).await?;
+    let mut burned = 0;
+    let mut fees = 0;
+    for (tx, tx_hash) in block.get_transactions().iter().zip(block.get_txs_hashes()) {
+        if !storage.is_tx_executed_in_block(tx_hash, hash)? {
+            continue;
+        }
+
+        fees += tx.get_fee();
+        match tx.get_data() {
+            TransactionType::Burn(payload) if payload.asset == XELIS_ASSET => {
+                burned += payload.amount;
+            },
+            TransactionType::InvokeContract(payload) => {
+                let (burned_gas, gas_fee) = get_tx_gas_burn_and_fee(storage, tx_hash, payload.max_gas).await?;
+                burned += burned_gas;
+                fees += gas_fee;
+            },
+            TransactionType::DeployContract(DeployContractPayload { invoke: Some(invoke), .. }) => {
+                let (burned_gas, gas_fee) = get_tx_gas_burn_and_fee(storage, tx_hash, invoke.max_gas).await?;
+                burned += burned_gas;
+                fees += gas_fee;
+            },
+            _ => {}
+        }
+    }
+
+    Ok((burned, fees))
+}
+
+// Replay the emissions, fees and burns of the chain until the topoheight
+// and verify them against the counters tracked in storage
+// The block rewards are computed with the same rules as the DAG ordering
+pub async fn audit_supply<S: Storage>(blockchain: &Blockchain<S>, end_topoheight: TopoHeight) -> Result<SupplyAuditReport, BlockchainError> {
+    if end_topoheight > blockchain.get_topo_height() {
+        return Err(BlockchainError::InvalidTopoHeightRange(0, end_topoheight))
+    }
+
+    let start = Instant::now();
+    // Supply counters are not available below the pruned topoheight
+    let (start_topoheight, mut supply, mut burned_supply) = {
+        let storage = blockchain.get_storage().read().await;
+        match storage.get_pruned_topoheight().await? {
+            Some(pruned_topoheight) => {
+                if end_topoheight < pruned_topoheight {
+                    return Err(BlockchainError::TopoHeightPruned(end_topoheight, pruned_topoheight))
+                }
+
+                let supply = storage.get_supply_at_topo_height(pruned_topoheight).await?;
+                let burned_supply = storage.get_burned_supply_at_topo_height(pruned_topoheight).await?;
+                (Some(pruned_topoheight), supply, burned_supply)
+            },
+            None => (None, 0, 0)
+        }
+    };
+
+    let mut report = SupplyAuditReport {
+        start_topoheight: start_topoheight.unwrap_or(0),
+        end_topoheight,
+        emitted: 0,
+        fees: 0,
+        burned: 0,
+        supply,
+        divergence: None
+    };
+
+    let first_topoheight = start_topoheight.map_or(0, |topoheight| topoheight + 1);
+    // Side blocks counter per height
+    let mut side_blocks: HashMap<u64, u64> = HashMap::new();
+    for topoheight in first_topoheight..=end_topoheight {
+        // Lock the storage per block to not block the chain during the audit
+        let storage = blockchain.get_storage().read().await;
+        let hash = storage.get_hash_at_topo_height(topoheight).await?;
+
+        let is_side_block = blockchain.is_side_block_internal(&*storage, &hash, topoheight).await?;
+        let height = storage.get_height_for_block_hash(&hash).await?;
+        let side_blocks_count = match side_blocks.entry(height) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mut count = 0;
+                for block in storage.get_blocks_at_height(height).await? {
+                    if block != hash && blockchain.is_side_block_internal(&*storage, &block, topoheight).await? {
+                        count += 1;
+                    }
+                }
+                entry.insert(count)
+            }
+        };
+
+        let block_reward = blockchain.internal_get_block_reward(supply, is_side_block, *side_blocks_count).await?;
+        if is_side_block {
+            *side_blocks_count += 1;
+        }
+
+        let (burned, fees) = get_block_burns_and_fees(&*storage, &hash).await?;
+        supply += block_reward;
+        burned_supply += burned;
+
+        let checks = [
+            (SupplyCounter::BlockReward, block_reward, storage.get_block_reward_at_topo_height(topoheight)?),
+            (SupplyCounter::Supply, supply, storage.get_supply_at_topo_height(topoheight).await?),
+            (SupplyCounter::BurnedSupply, burned_supply, storage.get_burned_supply_at_topo_height(topoheight).await?)
+        ];
+
+        if let Some((counter, expected, found)) = checks.into_iter().find(|(_, expected, found)| expected != found) {
+            debug!("Divergent {} at topoheight {} for block {}: expected {}, found {}", counter, topoheight, hash, expected, found);
+            report.divergence = Some(SupplyDivergence {
+                topoheight,
+                block_hash: hash,
+                counter,
+                expected,
+                found
+            });
+            report.end_topoheight = topoheight;
+            break;
+        }
+
+        report.emitted += block_reward;
+        report.burned += burned;
+        report.fees += fees;
+        report.supply = supply;
+
+        if (topoheight - first_topoheight + 1) % PROGRESS_INTERVAL == 0 {
+            info!("Audited {}/{} blocks", topoheight - first_topoheight + 1, end_topoheight + 1 - first_topoheight);
+        }
+    }
+
+    info!("Supply audit done in {}s", start.elapsed().as_secs());
+    Ok(report)
+}
@@ -0,0 +1,9 @@
+pub mod rpc;
+pub mod p2p;
+pub mod core;
+pub mod config;
+
+use xelis_common::difficulty::Difficulty;
+use config::{BLOCK_TIME_MILLIS, MILLIS_PER_SECOND};
+
+pub const BLOCK_TIME: Difficulty = Difficulty::from_u64(BLOCK_TIME_MILLIS / MILLIS_PER_SECOND);
@@ -1,26 +1,21 @@
-pub mod rpc;
-pub mod p2p;
-pub mod core;
-pub mod config;
-
-use config::{DEV_PUBLIC_KEY, STABLE_LIMIT};
+use xelis_daemon::config::{DEV_PUBLIC_KEY, STABLE_LIMIT};
 use human_bytes::human_bytes;
 use humantime::format_duration;
 use log::{trace, error, info, warn};
-use p2p::P2pServer;
-use rpc::{
+use xelis_daemon::p2p::P2pServer;
+use xelis_daemon::rpc::{
     getwork_server::SharedGetWorkServer,
     rpc::get_block_response_for_hash
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use xelis_common::{
     async_handler,
     config::{VERSION, XELIS_ASSET},
     context::Context,
+    emission::get_block_reward,
     crypto::{
         Address,Hashable
     },
-    difficulty::Difficulty,
     network::Network,
     prompt::{
         Prompt,
@@ -28,7 +23,9 @@ use xelis_common::{
             CommandManager,
             CommandError,
             Command,
-            CommandHandler
+            CommandHandler,
+            CommandModule,
+            CommandRegistry
         },
         PromptError,
         argument::{
@@ -36,6 +33,7 @@ use xelis_common::{
             Arg,
             ArgType
         },
+        logger::{LogFormat, LogOptions, LogRotation},
         LogLevel,
         ModuleConfig,
         ShareablePrompt,
@@ -50,13 +48,12 @@ use xelis_common::{
         format_difficulty
     }
 };
-use crate::{
+use xelis_daemon::{
     core::{
         config::Config as InnerConfig,
-        blockchain::{
-            Blockchain,
-            get_block_reward
-        },
+        chain_file,
+        supply_audit,
+        blockchain::Blockchain,
         storage::{
             Storage,
             SledStorage
@@ -65,19 +62,18 @@ use crate::{
     config::{
         BLOCK_TIME_MILLIS,
         MILLIS_PER_SECOND
-    }
+    },
+    BLOCK_TIME
 };
-use core::{
+use xelis_daemon::core::{
     blockdag,
-    hard_fork::{
-        get_pow_algorithm_for_version,
-        get_version_at_height
-    },
+    hard_fork::get_pow_algorithm_for_version,
     storage::StorageMode
 };
 use std::{
     fs::File,
     io::Write,
+    marker::PhantomData,
     net::{IpAddr, SocketAddr},
     path::Path,
     sync::Arc,
@@ -98,6 +94,10 @@ fn default_logs_path() -> String {
     "logs/".to_owned()
 }
 
+fn default_log_file_max_files() -> usize {
+    10
+}
+
 #[derive(Parser, Serialize, Deserialize)]
 pub struct LogConfig {
     /// Set log level
@@ -145,6 +145,26 @@ pub struct LogConfig {
     #[clap(long)]
     #[serde(default)]
     logs_modules: Vec<ModuleConfig>,
+    /// Format of the logs
+    /// 
+    /// JSON format writes one object per line with the module, message
+    /// and structured fields such as peer, block_hash and tx_hash.
+    #[clap(long, value_enum, default_value_t = LogFormat::Text)]
+    #[serde(default)]
+    log_format: LogFormat,
+    /// Rotate the log file once it reaches this size in bytes.
+    /// It replaces the daily rotation of the log file.
+    #[clap(long)]
+    log_file_max_size: Option<u64>,
+    /// Rotate the log file after this duration in seconds.
+    /// It replaces the daily rotation of the log file.
+    #[clap(long)]
+    log_file_rotation_interval: Option<u64>,
+    /// Rotated log files to keep when rotating by size or duration.
+    /// Set it to 0 to keep all of them.
+    #[clap(long, default_value_t = 10)]
+    #[serde(default = "default_log_file_max_files")]
+    log_file_max_files: usize
 }
 
 #[derive(Parser, Serialize, Deserialize)]
@@ -168,7 +188,10 @@ pub struct CliConfig {
     #[clap(long, value_enum, default_value_t = StorageMode::LowSpace)]
     #[serde(default)]
     internal_db_mode: StorageMode,
-    /// JSON File to load the configuration from
+    /// File to load the configuration from.
+    /// TOML format is used if it ends with `.toml`, otherwise JSON.
+    /// Any option, from the file or the command line, can be overridden using an
+    /// environment variable named `XELIS_` followed by the option name in uppercase (`XELIS_RPC_BIND_ADDRESS`).
     #[clap(long)]
     #[serde(skip)]
     #[serde(default)]
@@ -177,14 +200,126 @@ pub struct CliConfig {
     #[clap(long)]
     #[serde(skip)]
     #[serde(default)]
-    generate_config_template: bool
+    generate_config_template: bool,
+    /// Print the configuration used in TOML format and exit
+    #[clap(long)]
+    #[serde(skip)]
+    #[serde(default)]
+    dump_config: bool,
+    /// Address to serve the Prometheus metrics on (`127.0.0.1:9100`)
+    /// Metrics are not exposed if not set
+    #[cfg(feature = "metrics")]
+    #[clap(long)]
+    metrics_bind_address: Option<String>
+}
+
+// Prefix of the environment variables overriding the config file
+const CONFIG_ENV_PREFIX: &str = "XELIS_";
+
+fn is_toml_file(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+}
+
+// Override the options of the config using the environment variables
+// As all options are flattened in the CLI, each option name is unique
+// The environment value is converted to the type of the option it replaces
+fn apply_env_overrides<T: DeserializeOwned>(root: &mut serde_json::Value, path: &mut Vec<String>, get_env: &impl Fn(&str) -> Option<String>) -> Result<()> {
+    let keys: Vec<String> = match pointer_mut(root, path) {
+        Some(serde_json::Value::Object(map)) => map.keys().cloned().collect(),
+        _ => return Ok(())
+    };
+
+    for key in keys {
+        path.push(key);
+        let current = pointer_mut(root, path).map(|v| v.take()).unwrap_or_default();
+        if current.is_object() {
+            set_pointer(root, path, current);
+            apply_env_overrides::<T>(root, path, get_env)?;
+            path.pop();
+            continue;
+        }
+
+        let name = format!("{}{}", CONFIG_ENV_PREFIX, path[path.len() - 1].to_uppercase());
+        let value = match get_env(&name) {
+            Some(env) => coerce_env_value::<T>(root, path, &current, env).with_context(|| format!("Invalid value for environment variable {}", name))?,
+            None => current
+        };
+        set_pointer(root, path, value);
+        path.pop();
+    }
+
+    Ok(())
 }
 
-const BLOCK_TIME: Difficulty = Difficulty::from_u64(BLOCK_TIME_MILLIS / MILLIS_PER_SECOND);
+// Convert the environment value to the type of the current value
+// Unset options (null) are parsed as JSON only if the config accepts it, otherwise as a string
+fn coerce_env_value<T: DeserializeOwned>(root: &mut serde_json::Value, path: &[String], current: &serde_json::Value, env: String) -> Result<serde_json::Value> {
+    use serde_json::Value;
+
+    let value = match current {
+        Value::String(_) => Value::String(env),
+        Value::Bool(_) => Value::Bool(env.trim().parse().context("expected a boolean")?),
+        Value::Number(_) => match serde_json::from_str(env.trim()) {
+            Ok(value @ Value::Number(_)) => value,
+            _ => return Err(anyhow::anyhow!("expected a number"))
+        },
+        Value::Array(_) => match serde_json::from_str(&env) {
+            Ok(value @ Value::Array(_)) => value,
+            // Comma separated list of strings
+            _ => Value::Array(env.split(',').map(|v| Value::String(v.trim().to_owned())).collect())
+        },
+        _ => match serde_json::from_str::<Value>(&env) {
+            Ok(value) if !value.is_string() => {
+                set_pointer(root, path, value.clone());
+                let accepted = serde_json::from_value::<T>(root.clone()).is_ok();
+                set_pointer(root, path, Value::Null);
+                if accepted {
+                    value
+                } else {
+                    Value::String(env)
+                }
+            },
+            _ => Value::String(env)
+        }
+    };
+
+    Ok(value)
+}
+
+fn pointer_mut<'a>(root: &'a mut serde_json::Value, path: &[String]) -> Option<&'a mut serde_json::Value> {
+    path.iter().try_fold(root, |value, key| value.get_mut(key))
+}
+
+fn set_pointer(root: &mut serde_json::Value, path: &[String], value: serde_json::Value) {
+    if let Some(target) = pointer_mut(root, path) {
+        *target = value;
+    }
+}
+
+// Apply the environment variables overrides to the config
+fn apply_env_config(config: &CliConfig, get_env: impl Fn(&str) -> Option<String>) -> Result<CliConfig> {
+    // Serialize it to have all the options with their current values
+    let mut value = serde_json::to_value(config).context("Error while serializing config")?;
+    apply_env_overrides::<CliConfig>(&mut value, &mut Vec::new(), &get_env)?;
+    serde_json::from_value(value).context("Error while applying environment variables to config")
+}
+
+// Load the config file
+fn load_config_file(path: &str) -> Result<CliConfig> {
+    let content = std::fs::read_to_string(path).context("Error while opening config file")?;
+    let config: CliConfig = if is_toml_file(path) {
+        toml::from_str(&content).context("Error while reading config file")?
+    } else {
+        serde_json::from_str(&content).context("Error while reading config file")?
+    };
+
+    Ok(config)
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let mut config: CliConfig = CliConfig::parse();
+    let dump_config = config.dump_config;
     if let Some(path) = config.config_file.as_ref() {
         if config.generate_config_template {
             if Path::new(path).exists() {
@@ -193,19 +328,30 @@ async fn main() -> Result<()> {
             }
 
             let mut file = File::create(path).context("Error while creating config file")?;
-            let json = serde_json::to_string_pretty(&config).context("Error while serializing config file")?;
-            file.write_all(json.as_bytes()).context("Error while writing config file")?;
+            let content = if is_toml_file(path) {
+                toml::to_string_pretty(&config).context("Error while serializing config file")?
+            } else {
+                serde_json::to_string_pretty(&config).context("Error while serializing config file")?
+            };
+            file.write_all(content.as_bytes()).context("Error while writing config file")?;
             println!("Config file template generated at {}", path);
             return Ok(());
         }
 
-        let file = File::open(path).context("Error while opening config file")?;
-        config = serde_json::from_reader(file).context("Error while reading config file")?;
+        config = load_config_file(path)?;
     } else if config.generate_config_template {
         eprintln!("Provided config file path is required to generate the template with --config-file");
         return Ok(());
     }
 
+    // Environment variables are applied with or without a config file
+    config = apply_env_config(&config, |name| std::env::var(name).ok())?;
+
+    if dump_config {
+        print!("{}", toml::to_string_pretty(&config).context("Error while serializing config")?);
+        return Ok(());
+    }
+
     let blockchain_config = config.core;
     if let Some(path) = blockchain_config.dir_path.as_ref() {
         if !(path.ends_with("/") || path.ends_with("\\")) {
@@ -219,10 +365,29 @@ async fn main() -> Result<()> {
     }
 
     let log_config = config.log;
-    let prompt = Prompt::new(log_config.log_level, &log_config.logs_path, &log_config.filename_log, log_config.disable_file_logging, log_config.disable_file_log_date_based, log_config.disable_log_color, !log_config.disable_interactive_mode, log_config.logs_modules, log_config.file_log_level.unwrap_or(log_config.log_level))?;
+    let rotation = if log_config.log_file_max_size.is_some() || log_config.log_file_rotation_interval.is_some() {
+        Some(LogRotation {
+            max_size: log_config.log_file_max_size,
+            interval: log_config.log_file_rotation_interval.map(Duration::from_secs),
+            max_files: log_config.log_file_max_files
+        })
+    } else {
+        None
+    };
+    let log_options = LogOptions {
+        format: log_config.log_format,
+        rotation
+    };
+    let prompt = Prompt::new(log_config.log_level, &log_config.logs_path, &log_config.filename_log, log_config.disable_file_logging, log_config.disable_file_log_date_based, log_config.disable_log_color, !log_config.disable_interactive_mode, log_config.logs_modules, log_config.file_log_level.unwrap_or(log_config.log_level), log_options)?;
     info!("XELIS Blockchain running version: {}", VERSION);
     info!("----------------------------------------------");
 
+    #[cfg(feature = "metrics")]
+    if let Some(address) = config.metrics_bind_address.as_deref() {
+        xelis_common::metrics::install_prometheus_exporter(address)?;
+        info!("Prometheus metrics available on http://{}/metrics", address);
+    }
+
     if blockchain_config.simulator.is_some() && config.network != Network::Dev {
         config.network = Network::Dev;
         warn!("Switching automatically to network {} because of simulator enabled", config.network);
@@ -248,6 +413,108 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+// Commands to inspect and manage the chain
+struct ChainCommands<S: Storage>(PhantomData<S>);
+
+impl<S: Storage> CommandModule for ChainCommands<S> {
+    fn name(&self) -> &str {
+        "chain"
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        vec![
+            Command::new("status", "Current daemon status", CommandHandler::Async(async_handler!(status::<S>))),
+            Command::new("top_block", "Print top block", CommandHandler::Async(async_handler!(top_block::<S>))),
+            Command::with_required_arguments("print_block", "Print block in json format", vec![Arg::new("hash", ArgType::Hash)], CommandHandler::Async(async_handler!(print_block::<S>))),
+            Command::with_required_arguments("pop_blocks", "Delete last N blocks", vec![Arg::new("amount", ArgType::Number)], CommandHandler::Async(async_handler!(pop_blocks::<S>))),
+            Command::with_required_arguments("prune_chain", "Prune the chain until the specified topoheight", vec![Arg::new("topoheight", ArgType::Number)], CommandHandler::Async(async_handler!(prune_chain::<S>))),
+            Command::with_optional_arguments("verify_chain", "Check chain supply", vec![Arg::new("topoheight", ArgType::Number)], CommandHandler::Async(async_handler!(verify_chain::<S>))),
+            Command::with_optional_arguments("audit_supply", "Replay the emissions, fees and burns to verify the supply counters", vec![Arg::new("topoheight", ArgType::Number)], CommandHandler::Async(async_handler!(audit_supply::<S>))),
+            Command::with_optional_arguments("list_assets", "List all assets registered on chain", vec![Arg::new("page", ArgType::Number)], CommandHandler::Async(async_handler!(list_assets::<S>))),
+            Command::with_arguments("show_balance", "Show balance of an address", vec![], vec![Arg::new("history", ArgType::Number)], CommandHandler::Async(async_handler!(show_balance::<S>))),
+            Command::new("print_balance", "Print the encrypted balance at a specific topoheight", CommandHandler::Async(async_handler!(print_balance::<S>))),
+            Command::new("list_unexecuted_transactions", "List all unexecuted transactions", CommandHandler::Async(async_handler!(list_unexecuted_transactions::<S>))),
+            Command::new("swap_blocks_executions_positions", "Swap the position of two blocks executions", CommandHandler::Async(async_handler!(swap_blocks_executions_positions::<S>))),
+            Command::with_optional_arguments("difficulty_dataset", "Create a dataset for difficulty from chain", vec![Arg::new("output", ArgType::String)], CommandHandler::Async(async_handler!(difficulty_dataset::<S>))),
+            Command::with_optional_arguments("mine_block", "Mine a block on testnet", vec![Arg::new("count", ArgType::Number)], CommandHandler::Async(async_handler!(mine_block::<S>))),
+            Command::with_arguments("export_chain", "Export the blocks in a bootstrap file", vec![Arg::new("path", ArgType::String)], vec![Arg::new("from", ArgType::Number), Arg::new("to", ArgType::Number)], CommandHandler::Async(async_handler!(export_chain::<S>))),
+            Command::with_required_arguments("import_chain", "Import the blocks of a bootstrap file from a path or an HTTP URL", vec![Arg::new("source", ArgType::String)], CommandHandler::Async(async_handler!(import_chain::<S>)))
+        ]
+    }
+}
+
+// Commands to manage the mempool
+struct MempoolCommands<S: Storage>(PhantomData<S>);
+
+impl<S: Storage> CommandModule for MempoolCommands<S> {
+    fn name(&self) -> &str {
+        "mempool"
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        vec![
+            Command::new("clear_mempool", "Clear all transactions in mempool", CommandHandler::Async(async_handler!(clear_mempool::<S>))),
+            Command::with_arguments("add_tx", "Add a TX in hex format in mempool", vec![Arg::new("hex", ArgType::String)], vec![Arg::new("broadcast", ArgType::Bool)], CommandHandler::Async(async_handler!(add_tx::<S>)))
+        ]
+    }
+}
+
+// Commands to manage the P2P server and its peers
+struct P2pCommands<S: Storage>(PhantomData<S>);
+
+impl<S: Storage> CommandModule for P2pCommands<S> {
+    fn name(&self) -> &str {
+        "p2p"
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        vec![
+            Command::with_optional_arguments("list_peers", "List all peers connected", vec![Arg::new("page", ArgType::Number)], CommandHandler::Async(async_handler!(list_peers::<S>))),
+            Command::with_optional_arguments("show_peerlist", "Show the stored peerlist", vec![Arg::new("page", ArgType::Number)], CommandHandler::Async(async_handler!(show_stored_peerlist::<S>))),
+            Command::with_required_arguments("add_peer", "Connect to a new peer using ip:port format", vec![Arg::new("address", ArgType::String)], CommandHandler::Async(async_handler!(add_peer::<S>))),
+            Command::with_required_arguments("kick_peer", "Kick a peer using its ip:port", vec![Arg::new("address", ArgType::String)], CommandHandler::Async(async_handler!(kick_peer::<S>))),
+            Command::with_required_arguments("temp_ban_address", "Temporarily ban an address", vec![Arg::new("address", ArgType::String), Arg::new("seconds", ArgType::Number)], CommandHandler::Async(async_handler!(temp_ban_address::<S>))),
+            Command::with_optional_arguments("blacklist", "View blacklist or add a peer address in it", vec![Arg::new("address", ArgType::String)], CommandHandler::Async(async_handler!(blacklist::<S>))),
+            Command::with_optional_arguments("whitelist", "View whitelist or add a peer address in it", vec![Arg::new("address", ArgType::String)], CommandHandler::Async(async_handler!(whitelist::<S>))),
+            Command::new("clear_p2p_connections", "Clear all P2P connections", CommandHandler::Async(async_handler!(clear_p2p_connections::<S>))),
+            Command::new("clear_p2p_peerlist", "Clear P2P peerlist", CommandHandler::Async(async_handler!(clear_p2p_peerlist::<S>))),
+            Command::new("p2p_outgoing_connections", "Accept/refuse to connect to outgoing nodes", CommandHandler::Async(async_handler!(p2p_outgoing_connections::<S>)))
+        ]
+    }
+}
+
+// Commands to manage the RPC and GetWork servers
+struct RpcCommands<S: Storage>(PhantomData<S>);
+
+impl<S: Storage> CommandModule for RpcCommands<S> {
+    fn name(&self) -> &str {
+        "rpc"
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        vec![
+            Command::with_optional_arguments("list_miners", "List all miners connected", vec![Arg::new("page", ArgType::Number)], CommandHandler::Async(async_handler!(list_miners::<S>))),
+            Command::new("clear_rpc_connections", "Clear all WS connections from RPC", CommandHandler::Async(async_handler!(clear_rpc_connections::<S>)))
+        ]
+    }
+}
+
+// Commands to manage the storage
+struct StorageCommands<S: Storage>(PhantomData<S>);
+
+impl<S: Storage> CommandModule for StorageCommands<S> {
+    fn name(&self) -> &str {
+        "storage"
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        vec![
+            Command::new("clear_caches", "Clear storage caches", CommandHandler::Async(async_handler!(clear_caches::<S>))),
+            Command::new("estimate_db_size", "Estimate the database total size", CommandHandler::Async(async_handler!(estimate_db_size::<S>)))
+        ]
+    }
+}
+
 async fn run_prompt<S: Storage>(prompt: ShareablePrompt, blockchain: Arc<Blockchain<S>>, network: Network) -> Result<(), PromptError> {
     let mut context = Context::default();
     context.store(blockchain.clone());
@@ -255,36 +522,14 @@ async fn run_prompt<S: Storage>(prompt: ShareablePrompt, blockchain: Arc<Blockch
     let command_manager = CommandManager::with_context(context, prompt.clone());
     command_manager.register_default_commands()?;
 
-    // Register all our commands
-    command_manager.add_command(Command::with_optional_arguments("list_miners", "List all miners connected", vec![Arg::new("page", ArgType::Number)], CommandHandler::Async(async_handler!(list_miners::<S>))))?;
-    command_manager.add_command(Command::with_optional_arguments("list_peers", "List all peers connected", vec![Arg::new("page", ArgType::Number)], CommandHandler::Async(async_handler!(list_peers::<S>))))?;
-    command_manager.add_command(Command::with_optional_arguments("list_assets", "List all assets registered on chain", vec![Arg::new("page", ArgType::Number)], CommandHandler::Async(async_handler!(list_assets::<S>))))?;
-    command_manager.add_command(Command::with_optional_arguments("show_peerlist", "Show the stored peerlist", vec![Arg::new("page", ArgType::Number)], CommandHandler::Async(async_handler!(show_stored_peerlist::<S>))))?;
-    command_manager.add_command(Command::with_arguments("show_balance", "Show balance of an address", vec![], vec![Arg::new("history", ArgType::Number)], CommandHandler::Async(async_handler!(show_balance::<S>))))?;
-    command_manager.add_command(Command::with_required_arguments("print_block", "Print block in json format", vec![Arg::new("hash", ArgType::Hash)], CommandHandler::Async(async_handler!(print_block::<S>))))?;
-    command_manager.add_command(Command::new("top_block", "Print top block", CommandHandler::Async(async_handler!(top_block::<S>))))?;
-    command_manager.add_command(Command::with_required_arguments("pop_blocks", "Delete last N blocks", vec![Arg::new("amount", ArgType::Number)], CommandHandler::Async(async_handler!(pop_blocks::<S>))))?;
-    command_manager.add_command(Command::new("clear_mempool", "Clear all transactions in mempool", CommandHandler::Async(async_handler!(clear_mempool::<S>))))?;
-    command_manager.add_command(Command::with_arguments("add_tx", "Add a TX in hex format in mempool", vec![Arg::new("hex", ArgType::String)], vec![Arg::new("broadcast", ArgType::Bool)], CommandHandler::Async(async_handler!(add_tx::<S>))))?;
-    command_manager.add_command(Command::with_required_arguments("prune_chain", "Prune the chain until the specified topoheight", vec![Arg::new("topoheight", ArgType::Number)], CommandHandler::Async(async_handler!(prune_chain::<S>))))?;
-    command_manager.add_command(Command::new("status", "Current daemon status", CommandHandler::Async(async_handler!(status::<S>))))?;
-    command_manager.add_command(Command::with_optional_arguments("blacklist", "View blacklist or add a peer address in it", vec![Arg::new("address", ArgType::String)], CommandHandler::Async(async_handler!(blacklist::<S>))))?;
-    command_manager.add_command(Command::with_optional_arguments("whitelist", "View whitelist or add a peer address in it", vec![Arg::new("address", ArgType::String)], CommandHandler::Async(async_handler!(whitelist::<S>))))?;
-    command_manager.add_command(Command::with_optional_arguments("verify_chain", "Check chain supply", vec![Arg::new("topoheight", ArgType::Number)], CommandHandler::Async(async_handler!(verify_chain::<S>))))?;
-    command_manager.add_command(Command::with_required_arguments("kick_peer", "Kick a peer using its ip:port", vec![Arg::new("address", ArgType::String)], CommandHandler::Async(async_handler!(kick_peer::<S>))))?;
-    command_manager.add_command(Command::with_required_arguments("temp_ban_address", "Temporarily ban an address", vec![Arg::new("address", ArgType::String), Arg::new("seconds", ArgType::Number)], CommandHandler::Async(async_handler!(temp_ban_address::<S>))))?;
-    command_manager.add_command(Command::new("clear_caches", "Clear storage caches", CommandHandler::Async(async_handler!(clear_caches::<S>))))?;
-    command_manager.add_command(Command::new("clear_rpc_connections", "Clear all WS connections from RPC", CommandHandler::Async(async_handler!(clear_rpc_connections::<S>))))?;
-    command_manager.add_command(Command::new("clear_p2p_connections", "Clear all P2P connections", CommandHandler::Async(async_handler!(clear_p2p_connections::<S>))))?;
-    command_manager.add_command(Command::new("clear_p2p_peerlist", "Clear P2P peerlist", CommandHandler::Async(async_handler!(clear_p2p_peerlist::<S>))))?;
-    command_manager.add_command(Command::with_optional_arguments("difficulty_dataset", "Create a dataset for difficulty from chain", vec![Arg::new("output", ArgType::String)], CommandHandler::Async(async_handler!(difficulty_dataset::<S>))))?;
-    command_manager.add_command(Command::with_optional_arguments("mine_block", "Mine a block on testnet", vec![Arg::new("count", ArgType::Number)], CommandHandler::Async(async_handler!(mine_block::<S>))))?;
-    command_manager.add_command(Command::new("p2p_outgoing_connections", "Accept/refuse to connect to outgoing nodes", CommandHandler::Async(async_handler!(p2p_outgoing_connections::<S>))))?;
-    command_manager.add_command(Command::with_required_arguments("add_peer", "Connect to a new peer using ip:port format", vec![Arg::new("address", ArgType::String)], CommandHandler::Async(async_handler!(add_peer::<S>))))?;
-    command_manager.add_command(Command::new("list_unexecuted_transactions", "List all unexecuted transactions", CommandHandler::Async(async_handler!(list_unexecuted_transactions::<S>))))?;
-    command_manager.add_command(Command::new("swap_blocks_executions_positions", "Swap the position of two blocks executions", CommandHandler::Async(async_handler!(swap_blocks_executions_positions::<S>))))?;
-    command_manager.add_command(Command::new("print_balance", "Print the encrypted balance at a specific topoheight", CommandHandler::Async(async_handler!(print_balance::<S>))))?;
-    command_manager.add_command(Command::new("estimate_db_size", "Estimate the database total size", CommandHandler::Async(async_handler!(estimate_db_size::<S>))))?;
+    // Register the commands of each module
+    let mut registry = CommandRegistry::new();
+    registry.register(ChainCommands::<S>(PhantomData))?;
+    registry.register(MempoolCommands::<S>(PhantomData))?;
+    registry.register(P2pCommands::<S>(PhantomData))?;
+    registry.register(RpcCommands::<S>(PhantomData))?;
+    registry.register(StorageCommands::<S>(PhantomData))?;
+    command_manager.add_registry(registry)?;
 
     // Don't keep the lock for ever
     let (p2p, getwork) = {
@@ -322,11 +567,15 @@ async fn run_prompt<S: Storage>(prompt: ShareablePrompt, blockchain: Arc<Blockch
         };
 
         trace!("Retrieving miners count");
-        let miners = match &getwork {
+        let mut miners = match &getwork {
             Some(getwork) => getwork.count_miners().await,
             None => 0
         };
 
+        if let Some(stratum) = rpc.as_ref().and_then(|rpc| rpc.stratum_server().as_ref()) {
+            miners += stratum.count_miners().await;
+        }
+
         trace!("Retrieving mempool size");
         let mempool = {
             let mempool = blockchain.get_mempool().read().await;
@@ -930,7 +1179,7 @@ async fn status<S: Storage>(manager: &CommandManager, _: ArgumentManager) -> Res
     let assets = storage.count_assets().await.context("Error while counting assets")?;
     let contracts = storage.count_contracts().await.context("Error while counting contracts")?;
     let pruned_topoheight = storage.get_pruned_topoheight().await.context("Error while retrieving pruned topoheight")?;
-    let version = get_version_at_height(blockchain.get_network(), height);
+    let version = blockchain.get_fork_schedule().get_version_at_height(height);
 
     manager.message(format!("Height: {}", height));
     manager.message(format!("Stable Height: {}", stableheight));
@@ -959,6 +1208,11 @@ async fn status<S: Storage>(manager: &CommandManager, _: ArgumentManager) -> Res
         manager.message("Chain is in full mode");
     }
 
+    match blockchain.get_clock().get_offset().await {
+        Some(offset) => manager.message(format!("Clock offset: {}ms{}", offset, if blockchain.get_clock().is_skewed() { " (skewed)" } else { "" })),
+        None => manager.message("Clock offset: unknown")
+    };
+
     let elapsed_seconds = manager.running_since().as_secs();
     let elapsed = format_duration(Duration::from_secs(elapsed_seconds)).to_string();
     manager.message(format!("Uptime: {}", elapsed));
@@ -1139,6 +1393,99 @@ async fn difficulty_dataset<S: Storage>(manager: &CommandManager, mut arguments:
     Ok(())
 }
 
+// Export the blocks until the stable topoheight by default
+async fn export_chain<S: Storage>(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let path = arguments.get_value("path")?.to_string_value()?;
+    let from = if arguments.has_argument("from") {
+        arguments.get_value("from")?.to_number()?
+    } else {
+        0
+    };
+
+    let context = manager.get_context().lock()?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let to = if arguments.has_argument("to") {
+        arguments.get_value("to")?.to_number()?
+    } else {
+        blockchain.get_stable_topoheight()
+    };
+
+    manager.message(format!("Exporting blocks from topoheight {} to {} in {}...", from, to, path));
+    let file = tokio::fs::File::create(&path).await.context("Error while creating file")?;
+    let summary = chain_file::export_chain(blockchain, file, from, to).await.context("Error while exporting chain")?;
+    manager.message(format!("{} blocks exported to {} with checksum {}", summary.blocks, path, summary.checksum));
+
+    Ok(())
+}
+
+// Import a bootstrap file, each block is verified before being added
+async fn import_chain<S: Storage>(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let source = arguments.get_value("source")?.to_string_value()?;
+
+    let context = manager.get_context().lock()?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+
+    // Files from a mirror are downloaded first, then imported
+    let (path, remove) = if chain_file::is_remote_chain_file(&source) {
+        let path = std::env::temp_dir().join("xelis-chain-import.bin");
+        manager.message(format!("Downloading {}...", source));
+        let size = chain_file::download_chain_file(&source, &path).await.context("Error while downloading chain file")?;
+        manager.message(format!("Downloaded {}", human_bytes(size as f64)));
+        (path, true)
+    } else {
+        (Path::new(&source).to_path_buf(), false)
+    };
+
+    manager.message("Importing blocks...");
+    let res = match tokio::fs::File::open(&path).await {
+        Ok(file) => chain_file::import_chain(blockchain, tokio::io::BufReader::new(file)).await,
+        Err(e) => Err(e.into())
+    };
+
+    if remove {
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            warn!("Error while deleting downloaded chain file: {}", e);
+        }
+    }
+
+    let summary = res.context("Error while importing chain")?;
+    manager.message(format!("{} blocks imported, {} were already in chain, checksum {}", summary.blocks - summary.skipped, summary.skipped, summary.checksum));
+
+    Ok(())
+}
+
+// Verify the tracked supply counters by replaying the chain until the topoheight
+async fn audit_supply<S: Storage>(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let topoheight = if arguments.has_argument("topoheight") {
+        arguments.get_value("topoheight")?.to_number()?
+    } else {
+        blockchain.get_stable_topoheight()
+    };
+
+    manager.message(format!("Auditing supply until topoheight {}...", topoheight));
+    let report = supply_audit::audit_supply(blockchain, topoheight).await.context("Error while auditing supply")?;
+    if report.start_topoheight > 0 {
+        manager.warn(format!("Chain is pruned, counters at topoheight {} are trusted", report.start_topoheight));
+    }
+
+    manager.message(format!("Emitted: {} XELIS", format_xelis(report.emitted)));
+    manager.message(format!("Fees: {} XELIS", format_xelis(report.fees)));
+    manager.message(format!("Burned: {} XELIS", format_xelis(report.burned)));
+
+    match report.divergence {
+        Some(divergence) => {
+            manager.error(format!("First divergence at topoheight {} (block {}): {} expected {} but {} is tracked", divergence.topoheight, divergence.block_hash, divergence.counter, format_xelis(divergence.expected), format_xelis(divergence.found)));
+        },
+        None => {
+            manager.message(format!("Supply of {} XELIS matches the tracked counters until topoheight {}", format_xelis(report.supply), report.end_topoheight));
+        }
+    };
+
+    Ok(())
+}
+
 // Mine a block
 async fn mine_block<S: Storage>(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
     let count = if arguments.has_argument("count") {
@@ -1206,4 +1553,62 @@ async fn add_peer<S: Storage>(manager: &CommandManager, mut args: ArgumentManage
     };
 
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use super::*;
+
+    fn apply(vars: &[(&str, &str)]) -> Result<CliConfig> {
+        let vars: HashMap<String, String> = vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        let config = CliConfig::parse_from(["xelis_daemon"]);
+        apply_env_config(&config, |name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_env_overrides_types() {
+        let config = apply(&[
+            ("XELIS_RPC_BIND_ADDRESS", "1234"),
+            ("XELIS_MAX_PEERS", "12"),
+            ("XELIS_DISABLE_P2P_SERVER", "true"),
+            ("XELIS_PRIORITY_NODES", "127.0.0.1:2125, 127.0.0.2:2125"),
+            ("XELIS_EXCLUSIVE_NODES", r#"["127.0.0.3:2125"]"#),
+            ("XELIS_NETWORK", "Testnet")
+        ]).unwrap();
+
+        // String options stay strings even if it looks like a number
+        assert_eq!(config.core.rpc.rpc_bind_address, "1234");
+        assert_eq!(config.core.p2p.max_peers, 12);
+        assert!(config.core.p2p.disable_p2p_server);
+        assert_eq!(config.core.p2p.priority_nodes, vec!["127.0.0.1:2125", "127.0.0.2:2125"]);
+        assert_eq!(config.core.p2p.exclusive_nodes, vec!["127.0.0.3:2125"]);
+        assert_eq!(config.network, Network::Testnet);
+    }
+
+    #[test]
+    fn test_env_overrides_unset_options() {
+        let config = apply(&[
+            ("XELIS_TAG", "true"),
+            ("XELIS_DIR_PATH", "1234/"),
+            ("XELIS_LOG_FILE_MAX_SIZE", "1024")
+        ]).unwrap();
+
+        assert_eq!(config.core.p2p.tag.as_deref(), Some("true"));
+        assert_eq!(config.core.dir_path.as_deref(), Some("1234/"));
+        assert_eq!(config.log.log_file_max_size, Some(1024));
+    }
+
+    #[test]
+    fn test_env_overrides_invalid() {
+        assert!(apply(&[("XELIS_MAX_PEERS", "abc")]).is_err());
+        assert!(apply(&[("XELIS_DISABLE_P2P_SERVER", "1")]).is_err());
+        assert!(apply(&[("XELIS_LOG_FILE_MAX_SIZE", "abc")]).is_err());
+
+        // Nothing set keeps the config
+        let config = apply(&[]).unwrap();
+        assert_eq!(config.core.p2p.max_peers, CliConfig::parse_from(["xelis_daemon"]).core.p2p.max_peers);
+    }
+}
@@ -1,12 +1,15 @@
 use std::{borrow::Cow, collections::HashSet, sync::Arc};
 
+use futures::future::join_all;
 use indexmap::IndexSet;
 use log::{debug, error, info, trace, warn};
+use rand::seq::SliceRandom;
 use xelis_common::{
     account::{VersionedBalance, VersionedNonce},
     contract::ContractMetadata,
     crypto::{Hash, PublicKey},
     immutable::Immutable,
+    serializer::Serializer,
     versioned_type::State,
 };
 
@@ -14,6 +17,7 @@ use crate::{
     config::PRUNE_SAFETY_LIMIT,
     core::{
         error::BlockchainError,
+        merkle::MerkleBuilder,
         storage::{
             Storage,
             VersionedContract,
@@ -36,7 +40,43 @@ use crate::{
     }
 };
 
-use super::{packet::bootstrap_chain::{StepRequest, StepResponse, MAX_ITEMS_PER_PAGE}, peer::Peer, P2pServer};
+use super::{packet::bootstrap_chain::{StepKind, StepRequest, StepResponse, MAX_ITEMS_PER_PAGE}, peer::Peer, P2pServer};
+
+// Peers used during a fast sync
+// Each step is requested to all of them and their responses
+// must be identical before being committed
+struct BootstrapPeers {
+    // selected peer for the sync
+    primary: Arc<Peer>,
+    // peers cross-checking the responses of the selected one
+    verifiers: Vec<Arc<Peer>>
+}
+
+impl BootstrapPeers {
+    // Request the step to all peers and verify that they all sent the same response
+    async fn request(&self, step: StepRequest<'_>) -> Result<StepResponse, P2pError> {
+        let kind = step.kind();
+        let verifications: Vec<_> = self.verifiers.iter()
+            .map(|peer| peer.request_boostrap_chain(step.clone()))
+            .collect();
+
+        let (response, verifications) = tokio::join!(self.primary.request_boostrap_chain(step), join_all(verifications));
+        let response = response?;
+        if self.verifiers.is_empty() {
+            return Ok(response)
+        }
+
+        let bytes = response.to_bytes();
+        for (peer, verification) in self.verifiers.iter().zip(verifications) {
+            if verification?.to_bytes() != bytes {
+                warn!("{} sent a different response than {} for bootstrap step {:?}", peer, self.primary, kind);
+                return Err(P2pError::BootstrapStepMismatch(kind))
+            }
+        }
+
+        Ok(response)
+    }
+}
 
 impl<S: Storage> P2pServer<S> {
 // Handle a bootstrap chain request
@@ -225,77 +265,121 @@ impl<S: Storage> P2pServer<S> {
         Ok(())
     }
 
+    // Select the peers cross-checking the state chunks with the selected peer
+    // They must have the stable block and must not be pruned above the blocks we request
+    async fn select_bootstrap_peers(&self, peer: &Arc<Peer>, stable_topoheight: u64) -> Result<BootstrapPeers, P2pError> {
+        let required = self.fast_sync_verification_peers;
+        let mut verifiers: Vec<Arc<Peer>> = self.peer_list.get_cloned_peers().await
+            .into_iter()
+            .filter(|p| p.get_id() != peer.get_id() && p.get_topoheight() >= stable_topoheight)
+            .filter(|p| p.get_pruned_topoheight().map_or(true, |pruned| pruned + PRUNE_SAFETY_LIMIT < stable_topoheight))
+            .collect();
+
+        if verifiers.len() < required {
+            return Err(P2pError::NotEnoughBootstrapPeers(verifiers.len(), required))
+        }
+
+        verifiers.shuffle(&mut rand::thread_rng());
+        verifiers.truncate(required);
+
+        Ok(BootstrapPeers {
+            primary: Arc::clone(peer),
+            verifiers
+        })
+    }
+
     // first, retrieve chain info of selected peer
+    // then the metadata of the last blocks until the stable one, used as checkpoint
     // We retrieve all assets through pagination,
     // then we fetch all keys with its nonces and its balances (also through pagination)
-    // and for the last step, retrieve last STABLE TOPOHEIGHT - PRUNE_SAFETY_LIMIT blocks
+    // Each state chunk is cross-checked against other peers before being committed
+    // and for the last step, save the last STABLE TOPOHEIGHT - PRUNE_SAFETY_LIMIT blocks
     // reload blockchain cache from disk, and we're ready to sync the rest of the chain
     // NOTE: it could be even faster without retrieving each TXs, but we do it in case user don't enable pruning
     pub(super) async fn bootstrap_chain(&self, peer: &Arc<Peer>) -> Result<(), BlockchainError> {
         info!("Starting fast sync with {}", peer);
 
         let mut our_topoheight = self.blockchain.get_topo_height();
-
-        let mut stable_topoheight = 0;
-        let mut step: Option<StepRequest> = {
+        let request = {
             let storage = self.blockchain.get_storage().read().await;
-            Some(StepRequest::ChainInfo(self.build_list_of_blocks_id(&*storage).await?))
+            StepRequest::ChainInfo(self.build_list_of_blocks_id(&*storage).await?)
         };
 
+        info!("Requesting step {:?}", request.kind());
         // keep them in memory, we add them when we're syncing
         // it's done to prevent any sync failure
-        let mut top_topoheight: u64 = 0;
-        let mut top_height: u64 = 0;
-        let mut top_block_hash: Option<Hash> = None;
+        let StepResponse::ChainInfo(common_point, stable_topoheight, top_height, top_block_hash) = peer.request_boostrap_chain(request).await? else {
+            // shouldn't happen
+            error!("Received an invalid StepResponse (how ?) while fetching chain info");
+            return Err(P2pError::InvalidPacket.into())
+        };
 
-        loop {
-            let response = if let Some(step) = step.take() {
-                info!("Requesting step {:?}", step.kind());
-                // This will also verify that the received step is the requested one
-                peer.request_boostrap_chain(step).await?
-            } else {
-                break;
-            };
+        // first, check the common point in case we deviated from the chain
+        if let Some(common_point) = common_point {
+            let mut storage = self.blockchain.get_storage().write().await;
+            debug!("Unverified common point found at {} with hash {}", common_point.get_topoheight(), common_point.get_hash());
+            let hash_at_topo = storage.get_hash_at_topo_height(common_point.get_topoheight()).await?;
+            if hash_at_topo != *common_point.get_hash() {
+                warn!("Common point is {} while our hash at topoheight {} is {}. Aborting", common_point.get_hash(), common_point.get_topoheight(), storage.get_hash_at_topo_height(common_point.get_topoheight()).await?);
+                return Err(BlockchainError::Unknown)
+            }
 
-            step = match response {
-                StepResponse::ChainInfo(common_point, topoheight, height, hash) => {
-                    // first, check the common point in case we deviated from the chain
-                    if let Some(common_point) = common_point {
-                        let mut storage = self.blockchain.get_storage().write().await;
-                        debug!("Unverified common point found at {} with hash {}", common_point.get_topoheight(), common_point.get_hash());
-                        let hash_at_topo = storage.get_hash_at_topo_height(common_point.get_topoheight()).await?;
-                        if hash_at_topo != *common_point.get_hash() {
-                            warn!("Common point is {} while our hash at topoheight {} is {}. Aborting", common_point.get_hash(), common_point.get_topoheight(), storage.get_hash_at_topo_height(common_point.get_topoheight()).await?);
-                            return Err(BlockchainError::Unknown)
-                        }
+            let top_block_hash = storage.get_top_block_hash().await?;
+            if *common_point.get_hash() != top_block_hash {
+                let pruned_topoheight = storage.get_pruned_topoheight().await?.unwrap_or(0);
+                
+                warn!("Common point is {} while our top block hash is {} !", common_point.get_hash(), top_block_hash);
+                // Count how much blocks we need to pop
+                let pop_count = if pruned_topoheight >= common_point.get_topoheight() {
+                    our_topoheight - pruned_topoheight
+                } else {
+                    our_topoheight - common_point.get_topoheight()
+                };
+                warn!("We need to pop {} blocks for fast sync", pop_count);
+                our_topoheight = self.blockchain.rewind_chain_for_storage(&mut *storage, pop_count, !peer.is_priority()).await?;
+                debug!("New topoheight after rewind is now {}", our_topoheight);
+            }
+        } else {
+            warn!("No common point with {} ! Not same chain ?", peer);
+            return Err(BlockchainError::Unknown)
+        }
 
-                        let top_block_hash = storage.get_top_block_hash().await?;
-                        if *common_point.get_hash() != top_block_hash {
-                            let pruned_topoheight = storage.get_pruned_topoheight().await?.unwrap_or(0);
-                            
-                            warn!("Common point is {} while our top block hash is {} !", common_point.get_hash(), top_block_hash);
-                            // Count how much blocks we need to pop
-                            let pop_count = if pruned_topoheight >= common_point.get_topoheight() {
-                                our_topoheight - pruned_topoheight
-                            } else {
-                                our_topoheight - common_point.get_topoheight()
-                            };
-                            warn!("We need to pop {} blocks for fast sync", pop_count);
-                            our_topoheight = self.blockchain.rewind_chain_for_storage(&mut *storage, pop_count, !peer.is_priority()).await?;
-                            debug!("New topoheight after rewind is now {}", our_topoheight);
-                        }
-                    } else {
-                        warn!("No common point with {} ! Not same chain ?", peer);
-                        return Err(BlockchainError::Unknown)
-                    }
+        let peers = self.select_bootstrap_peers(peer, stable_topoheight).await?;
+        info!("Fast sync at stable topoheight {} verified by {} peers", stable_topoheight, peers.verifiers.len());
 
-                    top_topoheight = topoheight;
-                    top_height = height;
-                    top_block_hash = Some(hash);
-                    stable_topoheight = topoheight;
+        // All peers must agree on the last blocks until the stable one
+        // before requesting any state at its topoheight
+        info!("Requesting step {:?}", StepKind::BlocksMetadata);
+        let StepResponse::BlocksMetadata(blocks) = peers.request(StepRequest::BlocksMetadata(stable_topoheight)).await? else {
+            // shouldn't happen
+            error!("Received an invalid StepResponse (how ?) while fetching blocks metadata");
+            return Err(P2pError::InvalidPacket.into())
+        };
 
-                    Some(StepRequest::Assets(our_topoheight, topoheight, None))
-                },
+        // Last N blocks + stable block
+        if blocks.len() != PRUNE_SAFETY_LIMIT as usize + 1 {
+            error!("Received {} blocks metadata while expecting {}", blocks.len(), PRUNE_SAFETY_LIMIT + 1);
+            return Err(P2pError::InvalidPacket.into())
+        }
+
+        if blocks.first().map(|metadata| &metadata.hash) != Some(&top_block_hash) {
+            error!("Stable block {} is not the first block of the checkpoint", top_block_hash);
+            return Err(P2pError::InvalidBootstrapCheckpoint.into())
+        }
+
+        let mut merkle = MerkleBuilder::with_capacity(blocks.len());
+        for metadata in blocks.iter() {
+            merkle.add_element(metadata);
+        }
+        info!("Fast sync checkpoint root is {}", merkle.build());
+
+        let mut step: Option<StepRequest> = Some(StepRequest::Assets(our_topoheight, stable_topoheight, None));
+        while let Some(request) = step.take() {
+            info!("Requesting step {:?}", request.kind());
+            // This will also verify that the received step is the requested one
+            let response = peers.request(request).await?;
+
+            step = match response {
                 // fetch all assets from peer
                 StepResponse::Assets(assets, next_page) => {
                     {
@@ -337,7 +421,7 @@ impl<S: Storage> P2pServer<S> {
                                 keys
                             };
 
-                            self.update_bootstrap_keys(peer, &keys, our_topoheight, stable_topoheight).await?;
+                            self.update_bootstrap_keys(&peers, &keys, our_topoheight, stable_topoheight).await?;
                             if keys.len() < MAX_ITEMS_PER_PAGE {
                                 break;
                             }
@@ -352,7 +436,7 @@ impl<S: Storage> P2pServer<S> {
                 // fetch all new accounts
                 StepResponse::Keys(keys, next_page) => {
                     debug!("Requesting nonces for keys");
-                    self.update_bootstrap_keys(peer, &keys, our_topoheight, stable_topoheight).await?;                    
+                    self.update_bootstrap_keys(&peers, &keys, our_topoheight, stable_topoheight).await?;                    
 
                     if next_page.is_some() {
                         Some(StepRequest::Keys(our_topoheight, stable_topoheight, next_page))
@@ -365,7 +449,7 @@ impl<S: Storage> P2pServer<S> {
                     info!("Requesting contract metadata for {} contracts #{}", contracts.len(), page.unwrap_or(0));
                     for contract in contracts {
                         debug!("Requesting contract metadata for {}", contract);
-                        let StepResponse::ContractMetadata(metadata) = peer.request_boostrap_chain(StepRequest::ContractMetadata(our_topoheight, stable_topoheight, Cow::Borrowed(&contract))).await? else {
+                        let StepResponse::ContractMetadata(metadata) = peers.request(StepRequest::ContractMetadata(our_topoheight, stable_topoheight, Cow::Borrowed(&contract))).await? else {
                             // shouldn't happen
                             error!("Received an invalid StepResponse (how ?) while fetching contract metadata");
                             return Err(P2pError::InvalidPacket.into())
@@ -393,87 +477,82 @@ impl<S: Storage> P2pServer<S> {
                     if page.is_some() {
                         Some(StepRequest::Contracts(our_topoheight, stable_topoheight, page))
                     } else {
-                        // Go to next step
-                        Some(StepRequest::BlocksMetadata(stable_topoheight))
+                        // All the state is synced
+                        None
                     }
                 },
-                StepResponse::BlocksMetadata(blocks) => {
-                    // Last N blocks + stable block
-                    if blocks.len() != PRUNE_SAFETY_LIMIT as usize + 1 {
-                        error!("Received {} blocks metadata while expecting {}", blocks.len(), PRUNE_SAFETY_LIMIT + 1);
-                        return Err(P2pError::InvalidPacket.into())
-                    }
-
-                    let mut lowest_topoheight = stable_topoheight;
-                    for (i, metadata) in blocks.into_iter().enumerate() {
-                        let topoheight = stable_topoheight - i as u64;
-                        trace!("Processing block metadata {} at topoheight {}", metadata.hash, topoheight);
-                        // check that we don't already have this block in storage
-                        if self.blockchain.has_block(&metadata.hash).await? {
-                            warn!("Block {} at topo {} already in storage, skipping", metadata.hash, topoheight);
-                            continue;
-                        }
+                response => { // shouldn't happens
+                    error!("Received bootstrap chain response {:?} but didn't asked for it", response);
+                    return Err(P2pError::InvalidPacket.into());
+                }
+            };
+        }
 
-                        lowest_topoheight = topoheight;
-                        debug!("Saving block metadata {}", metadata.hash);
-                        let OwnedObjectResponse::BlockHeader(header, hash) = peer.request_blocking_object(ObjectRequest::BlockHeader(metadata.hash)).await? else {
-                            error!("Received an invalid requested object while fetching blocks metadata");
-                            return Err(P2pError::InvalidPacket.into())
-                        };
+        // Blocks are verified using their hash, so they are only requested to the selected peer
+        let mut lowest_topoheight = stable_topoheight;
+        for (i, metadata) in blocks.into_iter().enumerate() {
+            let topoheight = stable_topoheight - i as u64;
+            trace!("Processing block metadata {} at topoheight {}", metadata.hash, topoheight);
+            // check that we don't already have this block in storage
+            if self.blockchain.has_block(&metadata.hash).await? {
+                warn!("Block {} at topo {} already in storage, skipping", metadata.hash, topoheight);
+                continue;
+            }
 
-                        let mut txs = Vec::with_capacity(header.get_txs_hashes().len());
-                        debug!("Retrieving {} txs for block {}", header.get_txs_count(), hash);
-                        for tx_hash in header.get_txs_hashes() {
-                            trace!("Retrieving TX {} for block {}", tx_hash, hash);
-                            let tx = if self.blockchain.has_tx(tx_hash).await? {
-                                Immutable::Arc(self.blockchain.get_tx(tx_hash).await?)
-                            } else {
-                                let OwnedObjectResponse::Transaction(tx, _) = peer.request_blocking_object(ObjectRequest::Transaction(tx_hash.clone())).await? else {
-                                    error!("Received an invalid requested object while fetching block transaction {}", tx_hash);
-                                    return Err(P2pError::InvalidObjectResponseType.into())
-                                };
-                                Immutable::Owned(tx)
-                            };
-                            trace!("TX {} ok", tx_hash);
-                            txs.push(tx);
-                        }
+            lowest_topoheight = topoheight;
+            debug!("Saving block metadata {}", metadata.hash);
+            let OwnedObjectResponse::BlockHeader(header, hash) = peer.request_blocking_object(ObjectRequest::BlockHeader(metadata.hash)).await? else {
+                error!("Received an invalid requested object while fetching blocks metadata");
+                return Err(P2pError::InvalidPacket.into())
+            };
 
-                        // link its TX to the block
-                        let mut storage = self.blockchain.get_storage().write().await;
-                        for tx_hash in header.get_txs_hashes() {
-                            storage.add_block_for_tx(tx_hash, &hash)?;
-                        }
+            let mut txs = Vec::with_capacity(header.get_txs_hashes().len());
+            debug!("Retrieving {} txs for block {}", header.get_txs_count(), hash);
+            for tx_hash in header.get_txs_hashes() {
+                trace!("Retrieving TX {} for block {}", tx_hash, hash);
+                let tx = if self.blockchain.has_tx(tx_hash).await? {
+                    Immutable::Arc(self.blockchain.get_tx(tx_hash).await?)
+                } else {
+                    let OwnedObjectResponse::Transaction(tx, _) = peer.request_blocking_object(ObjectRequest::Transaction(tx_hash.clone())).await? else {
+                        error!("Received an invalid requested object while fetching block transaction {}", tx_hash);
+                        return Err(P2pError::InvalidObjectResponseType.into())
+                    };
+                    Immutable::Owned(tx)
+                };
+                trace!("TX {} ok", tx_hash);
+                txs.push(tx);
+            }
 
-                        // save metadata of this block
-                        storage.set_supply_at_topo_height(lowest_topoheight, metadata.supply)?;
-                        storage.set_burned_supply_at_topo_height(lowest_topoheight, metadata.burned_supply)?;
-                        storage.set_block_reward_at_topo_height(lowest_topoheight, metadata.reward)?;
-                        storage.set_topo_height_for_block(&hash, lowest_topoheight).await?;
+            // link its TX to the block
+            let mut storage = self.blockchain.get_storage().write().await;
+            for tx_hash in header.get_txs_hashes() {
+                storage.add_block_for_tx(tx_hash, &hash)?;
+            }
 
-                        storage.set_cumulative_difficulty_for_block_hash(&hash, metadata.cumulative_difficulty).await?;
+            // save metadata of this block
+            storage.set_supply_at_topo_height(lowest_topoheight, metadata.supply)?;
+            storage.set_burned_supply_at_topo_height(lowest_topoheight, metadata.burned_supply)?;
+            storage.set_block_reward_at_topo_height(lowest_topoheight, metadata.reward)?;
+            storage.set_topo_height_for_block(&hash, lowest_topoheight).await?;
 
-                        // save the block with its transactions, difficulty
-                        storage.save_block(Arc::new(header), &txs, metadata.difficulty, metadata.p, hash).await?;
-                    }
+            storage.set_cumulative_difficulty_for_block_hash(&hash, metadata.cumulative_difficulty).await?;
 
-                    let mut storage = self.blockchain.get_storage().write().await;
+            // save the block with its transactions, difficulty
+            storage.save_block(Arc::new(header), &txs, metadata.difficulty, metadata.p, hash).await?;
+        }
 
-                    // Delete all old data
-                    storage.delete_versioned_data_below_topoheight(lowest_topoheight, true).await?;
+        {
+            let mut storage = self.blockchain.get_storage().write().await;
 
-                    storage.set_pruned_topoheight(lowest_topoheight).await?;
-                    storage.set_top_topoheight(top_topoheight)?;
-                    storage.set_top_height(top_height)?;
-                    storage.store_tips(&HashSet::from([top_block_hash.take().ok_or(BlockchainError::Unknown)?]))?;
+            // Delete all old data
+            storage.delete_versioned_data_below_topoheight(lowest_topoheight, true).await?;
 
-                    None
-                },
-                response => { // shouldn't happens
-                    error!("Received bootstrap chain response {:?} but didn't asked for it", response);
-                    return Err(P2pError::InvalidPacket.into());
-                }
-            };
+            storage.set_pruned_topoheight(lowest_topoheight).await?;
+            storage.set_top_topoheight(stable_topoheight)?;
+            storage.set_top_height(top_height)?;
+            storage.store_tips(&HashSet::from([top_block_hash]))?;
         }
+
         self.blockchain.reload_from_disk().await?;
         info!("Fast sync done with {}", peer);
 
@@ -481,8 +560,8 @@ impl<S: Storage> P2pServer<S> {
     }
 
     // Handle the key nonces
-    async fn handle_nonces(&self, peer: &Arc<Peer>, keys: &IndexSet<PublicKey>, our_topoheight: u64, stable_topoheight: u64) -> Result<(), P2pError> {
-        let StepResponse::Nonces(nonces) = peer.request_boostrap_chain(StepRequest::Nonces(our_topoheight, stable_topoheight, Cow::Borrowed(&keys))).await? else {
+    async fn handle_nonces(&self, peers: &BootstrapPeers, keys: &IndexSet<PublicKey>, our_topoheight: u64, stable_topoheight: u64) -> Result<(), P2pError> {
+        let StepResponse::Nonces(nonces) = peers.request(StepRequest::Nonces(our_topoheight, stable_topoheight, Cow::Borrowed(&keys))).await? else {
             // shouldn't happen
             error!("Received an invalid StepResponse (how ?) while fetching nonces");
             return Err(P2pError::InvalidPacket.into())
@@ -512,9 +591,9 @@ impl<S: Storage> P2pServer<S> {
         Ok(())
     }
 
-    async fn handle_multisigs(&self, peer: &Arc<Peer>, keys: &IndexSet<PublicKey>, our_topoheight: u64, stable_topoheight: u64) -> Result<(), P2pError> {
+    async fn handle_multisigs(&self, peers: &BootstrapPeers, keys: &IndexSet<PublicKey>, our_topoheight: u64, stable_topoheight: u64) -> Result<(), P2pError> {
         // Also request the multisigs states
-        let StepResponse::MultiSigs(multisigs) = peer.request_boostrap_chain(StepRequest::MultiSigs(our_topoheight, stable_topoheight, Cow::Borrowed(keys))).await? else {
+        let StepResponse::MultiSigs(multisigs) = peers.request(StepRequest::MultiSigs(our_topoheight, stable_topoheight, Cow::Borrowed(keys))).await? else {
             // shouldn't happen
             error!("Received an invalid StepResponse (how ?) while fetching multisigs");
             return Err(P2pError::InvalidPacket.into())
@@ -543,9 +622,9 @@ impl<S: Storage> P2pServer<S> {
         Ok(())
     }
 
-    async fn handle_balances_for_asset(&self, peer: &Arc<Peer>, asset: &Hash, keys: &IndexSet<PublicKey>, our_topoheight: u64, stable_topoheight: u64) -> Result<(), P2pError> {
+    async fn handle_balances_for_asset(&self, peers: &BootstrapPeers, asset: &Hash, keys: &IndexSet<PublicKey>, our_topoheight: u64, stable_topoheight: u64) -> Result<(), P2pError> {
         debug!("Requesting balances for asset {} at topo {}", asset, stable_topoheight);
-        let StepResponse::Balances(balances) = peer.request_boostrap_chain(StepRequest::Balances(Cow::Borrowed(&keys), Cow::Borrowed(asset), our_topoheight, stable_topoheight)).await? else {
+        let StepResponse::Balances(balances) = peers.request(StepRequest::Balances(Cow::Borrowed(&keys), Cow::Borrowed(asset), our_topoheight, stable_topoheight)).await? else {
             // shouldn't happen
             error!("Received an invalid StepResponse (how ?) while fetching balances");
             return Err(P2pError::InvalidPacket.into())
@@ -570,7 +649,7 @@ impl<S: Storage> P2pServer<S> {
                 // Go through all balance history
                 while let Some(max) = max_topoheight {
                     debug!("Requesting spendable balances for asset {} at max topo {} for {}", asset, max, key.as_address(self.blockchain.get_network().is_mainnet()));
-                    let StepResponse::SpendableBalances(balances, max_next) = peer.request_boostrap_chain(StepRequest::SpendableBalances(Cow::Borrowed(&key), Cow::Borrowed(&asset), min_topo, max)).await? else {
+                    let StepResponse::SpendableBalances(balances, max_next) = peers.request(StepRequest::SpendableBalances(Cow::Borrowed(&key), Cow::Borrowed(&asset), min_topo, max)).await? else {
                         // shouldn't happen
                         error!("Received an invalid StepResponse (how ?) while fetching balances");
                         return Err(P2pError::InvalidPacket.into())
@@ -612,14 +691,14 @@ impl<S: Storage> P2pServer<S> {
 
     // Update all keys using bootstrap request
     // This will fetch the nonce and associated balance for each asset
-    async fn update_bootstrap_keys(&self, peer: &Arc<Peer>, keys: &IndexSet<PublicKey>, our_topoheight: u64, stable_topoheight: u64) -> Result<(), P2pError> {
+    async fn update_bootstrap_keys(&self, peers: &BootstrapPeers, keys: &IndexSet<PublicKey>, our_topoheight: u64, stable_topoheight: u64) -> Result<(), P2pError> {
         if keys.is_empty() {
             warn!("No keys to update");
             return Ok(())
         }
 
-        self.handle_nonces(peer, keys, our_topoheight, stable_topoheight).await?;
-        self.handle_multisigs(peer, keys, our_topoheight, stable_topoheight).await?;
+        self.handle_nonces(peers, keys, our_topoheight, stable_topoheight).await?;
+        self.handle_multisigs(peers, keys, our_topoheight, stable_topoheight).await?;
 
         let mut page = 0;
         loop {
@@ -636,7 +715,7 @@ impl<S: Storage> P2pServer<S> {
 
             // Request every asset balances
             for asset in assets {
-                self.handle_balances_for_asset(peer, &asset, keys, our_topoheight, stable_topoheight).await?;
+                self.handle_balances_for_asset(peers, &asset, keys, our_topoheight, stable_topoheight).await?;
             }
         }
 
@@ -6,6 +6,7 @@ use xelis_common::{
     config::TIPS_LIMIT,
     crypto::Hash,
     difficulty::{
+        work_above_baseline,
         CumulativeDifficulty,
         Difficulty
     },
@@ -17,7 +18,7 @@ use crate::core::{
     blockchain::Blockchain,
     blockdag,
     error::BlockchainError,
-    hard_fork::{get_pow_algorithm_for_version, get_version_at_height},
+    hard_fork::get_pow_algorithm_for_version,
     storage::{
         BlocksAtHeightProvider,
         DagOrderProvider,
@@ -65,17 +66,30 @@ impl<'a, S: Storage> ChainValidator<'a, S> {
 
     // Check if the chain validator has a higher cumulative difficulty than our blockchain
     // This is used to determine if we should switch to the new chain by popping blocks or not
+    // Both chains are compared from the common point used as checkpoint baseline
     pub async fn has_higher_cumulative_difficulty(&self) -> Result<bool, BlockchainError> {
         let new_cumulative_difficulty = self.get_chain_cumulative_difficulty().ok_or(BlockchainError::NotEnoughBlocks)?;
 
-        // Retrieve the current cumulative difficulty
-        let current_cumulative_difficulty = {
+        // Retrieve the current cumulative difficulty and the baseline
+        let (current_cumulative_difficulty, baseline) = {
             let storage = self.blockchain.get_storage().read().await;
             let top_block_hash = self.blockchain.get_top_block_hash_for_storage(&storage).await?;
-            storage.get_cumulative_difficulty_for_block_hash(&top_block_hash).await?
+            let current = storage.get_cumulative_difficulty_for_block_hash(&top_block_hash).await?;
+
+            let baseline = if self.starting_topoheight > 0 {
+                let common_point = storage.get_hash_at_topo_height(self.starting_topoheight - 1).await?;
+                storage.get_cumulative_difficulty_for_block_hash(&common_point).await?
+            } else {
+                CumulativeDifficulty::zero()
+            };
+
+            (current, baseline)
         };
 
-        Ok(*new_cumulative_difficulty > current_cumulative_difficulty)
+        // Only the work done above the baseline is compared
+        let new_work = work_above_baseline(new_cumulative_difficulty, &baseline)?;
+        let current_work = work_above_baseline(&current_cumulative_difficulty, &baseline)?;
+        Ok(new_work > current_work)
     }
 
     // Retrieve the cumulative difficulty of the chain validator
@@ -101,7 +115,7 @@ impl<'a, S: Storage> ChainValidator<'a, S> {
         }
 
         // Verify the block version
-        let version = get_version_at_height(self.blockchain.get_network(), header.get_height());
+        let version = self.blockchain.get_fork_schedule().get_version_at_height(header.get_height());
         if version != header.get_version() {
             debug!("Block {} has version {} while expected version is {}", hash, header.get_version(), version);
             return Err(BlockchainError::InvalidBlockVersion)
@@ -21,11 +21,8 @@ use std::{
 use human_bytes::human_bytes;
 use humantime::format_duration;
 use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
-    net::{
-        tcp::{OwnedReadHalf, OwnedWriteHalf},
-        TcpStream
-    },
+    io::{split, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
     sync::Mutex,
     time::timeout
 };
@@ -37,6 +34,11 @@ use log::{debug, error, trace, warn};
 
 type P2pResult<T> = Result<T, P2pError>;
 
+// Halves of the stream used by a connection
+// It is a TCP stream, or an in-memory one for tests
+type ReadHalf = Box<dyn AsyncRead + Send + Unpin>;
+type WriteHalf = Box<dyn AsyncWrite + Send + Unpin>;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum State {
     // connection is new, no handshake received
@@ -55,9 +57,9 @@ pub struct Connection {
     // State of the connection
     state: State,
     // write to stream
-    write: Mutex<OwnedWriteHalf>,
+    write: Mutex<WriteHalf>,
     // read from stream
-    read: Mutex<OwnedReadHalf>,
+    read: Mutex<ReadHalf>,
     // TCP Address
     addr: SocketAddr,
     // total bytes read
@@ -84,6 +86,16 @@ const ROTATE_EVERY_N_BYTES: usize = 1024 * 1024 * 1024;
 impl Connection {
     pub fn new(stream: TcpStream, addr: SocketAddr, out: bool) -> Self {
         let (read, write) = stream.into_split();
+        Self::with_halves(Box::new(read), Box::new(write), addr, out)
+    }
+
+    // Create a connection over any stream, such as an in-memory one
+    pub fn from_stream<T: AsyncRead + AsyncWrite + Send + 'static>(stream: T, addr: SocketAddr, out: bool) -> Self {
+        let (read, write) = split(stream);
+        Self::with_halves(Box::new(read), Box::new(write), addr, out)
+    }
+
+    fn with_halves(read: ReadHalf, write: WriteHalf, addr: SocketAddr, out: bool) -> Self {
         Self {
             out,
             state: State::Pending,
@@ -284,7 +296,7 @@ impl Connection {
     // This function will send the packet to the peer without flushing the stream
     // Packet length is ALWAYS sent in raw (not encrypted)
     // Otherwise, we can't know how much bytes to read for each ciphertext/packet
-    async fn send_packet_bytes_internal(&self, stream: &mut OwnedWriteHalf, packet: &[u8]) -> P2pResult<()> {
+    async fn send_packet_bytes_internal(&self, stream: &mut WriteHalf, packet: &[u8]) -> P2pResult<()> {
         let packet_len = packet.len() as u32;
         stream.write_all(&packet_len.to_be_bytes()).await?;
         stream.write_all(packet).await?;
@@ -383,7 +395,7 @@ impl Connection {
 
     // Read the packet size, this is always sent in raw (not encrypted)
     // And packet size must be a u32 in big endian
-    async fn read_packet_size(&self, stream: &mut OwnedReadHalf, buf: &mut [u8], max_usize: u32) -> P2pResult<u32> {
+    async fn read_packet_size(&self, stream: &mut ReadHalf, buf: &mut [u8], max_usize: u32) -> P2pResult<u32> {
         let read = self.read_bytes_from_stream(stream, &mut buf[0..4]).await?;
         if read != 4 {
             if self.get_state() == State::Success {
@@ -407,7 +419,7 @@ impl Connection {
 
     // Read all bytes until the the buffer is full with the requested size
     // This support fragmented packets and encryption
-    async fn read_all_bytes(&self, stream: &mut OwnedReadHalf, buf: &mut [u8], mut left: u32) -> P2pResult<Vec<u8>> {
+    async fn read_all_bytes(&self, stream: &mut ReadHalf, buf: &mut [u8], mut left: u32) -> P2pResult<Vec<u8>> {
         let buf_size = buf.len() as u32;
         let mut bytes = Vec::new();
         while left > 0 {
@@ -433,7 +445,7 @@ impl Connection {
     // this function will wait until something is sent to the socket if it's in blocking mode
     // this return the size of data read & set in the buffer.
     // used to only lock one time the stream and read on it
-    async fn read_bytes_from_stream_internal(&self, stream: &mut OwnedReadHalf, buf: &mut [u8]) -> P2pResult<usize> {
+    async fn read_bytes_from_stream_internal(&self, stream: &mut ReadHalf, buf: &mut [u8]) -> P2pResult<usize> {
         let mut read = 0;
         let buf_len = buf.len();
         // Packet may have been fragmented, try to read it completely
@@ -455,7 +467,7 @@ impl Connection {
     // this return the size of data read & set in the buffer.
     // used to only lock one time the stream and read on it
     // on any error, it will considered as disconnected
-    async fn read_bytes_from_stream(&self, stream: &mut OwnedReadHalf, buf: &mut [u8]) -> P2pResult<usize> {
+    async fn read_bytes_from_stream(&self, stream: &mut ReadHalf, buf: &mut [u8]) -> P2pResult<usize> {
         match self.read_bytes_from_stream_internal(stream, buf).await {
             Ok(read) => Ok(read),
             Err(e) => {
@@ -1,8 +1,8 @@
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 
 use log::info;
 use sled::{Config, Db, Mode, Tree};
-use xelis_common::serializer::{ReaderError, Serializer};
+use xelis_common::serializer::{read_versioned_or_legacy, Envelope, ReaderError, Serializer};
 use thiserror::Error;
 
 use super::peer_list::PeerListEntry;
@@ -28,6 +28,8 @@ pub enum DiskError {
 pub struct DiskCache {
     // All known peers
     peerlist: Tree,
+    // Outgoing peers to reconnect at startup
+    anchors: Tree,
     // DB to use
     db: Db,
 }
@@ -46,6 +48,7 @@ impl DiskCache {
 
         Ok(Self {
             peerlist: db.open_tree("peerlist")?,
+            anchors: db.open_tree("anchors")?,
             db,
         })
     }
@@ -56,15 +59,16 @@ impl DiskCache {
     }
 
     // Set a peer state using its IP address
+    // Entry is stored in a versioned envelope so new fields can be added later
     pub fn set_peerlist_entry(&self, peer: &IpAddr, entry: PeerListEntry) -> Result<(), DiskError> {
-        self.peerlist.insert(peer.to_bytes(), entry.to_bytes())?;
+        self.peerlist.insert(peer.to_bytes(), Envelope(entry).to_bytes())?;
         Ok(())
     }
 
     // Get a PeerListEntry using its IP address
     pub fn get_peerlist_entry(&self, peer: &IpAddr) -> Result<PeerListEntry, DiskError> {
         let v = self.peerlist.get(peer.to_bytes())?
-            .map(|v| PeerListEntry::from_bytes(&v))
+            .map(|v| read_versioned_or_legacy::<PeerListEntry>(&v))
             .ok_or(DiskError::NotFound)??;
 
         Ok(v)
@@ -77,7 +81,7 @@ impl DiskCache {
             .map(|r| {
                 let (k, v) = r?;
                 let ip = IpAddr::from_bytes(&k)?;
-                let entry = PeerListEntry::from_bytes(&v)?;
+                let entry = read_versioned_or_legacy::<PeerListEntry>(&v)?;
                 Ok((ip, entry))
             })
     }
@@ -88,6 +92,28 @@ impl DiskCache {
         Ok(())
     }
 
+    // Replace the anchors stored
+    pub fn set_anchors(&self, anchors: &[SocketAddr]) -> Result<(), DiskError> {
+        self.anchors.clear()?;
+        for anchor in anchors {
+            self.anchors.insert(anchor.to_bytes(), &[] as &[u8])?;
+        }
+        Ok(())
+    }
+
+    // Get the anchors stored and remove them
+    // An anchor we can't reconnect to is not kept for the next run
+    pub fn take_anchors(&self) -> Result<Vec<SocketAddr>, DiskError> {
+        let mut anchors = Vec::new();
+        for res in self.anchors.iter() {
+            let (k, _) = res?;
+            anchors.push(SocketAddr::from_bytes(&k)?);
+        }
+        self.anchors.clear()?;
+
+        Ok(anchors)
+    }
+
     // Clear the peerlist
     pub async fn clear_peerlist(&self) -> Result<(), DiskError> {
         self.peerlist.clear()?;
@@ -184,6 +184,14 @@ pub enum P2pError {
     SemaphoreAcquireError(#[from] AcquireError),
     #[error(transparent)]
     EncryptionError(#[from] EncryptionError),
+    #[error("Not enough peers to verify the fast sync, found {}, expected {}", _0, _1)]
+    NotEnoughBootstrapPeers(usize, usize),
+    #[error("Bootstrap chain step {:?} differs between peers", _0)]
+    BootstrapStepMismatch(StepKind),
+    #[error("Bootstrap checkpoint doesn't match the stable block")]
+    InvalidBootstrapCheckpoint,
+    #[error("Stable topoheight {} is greater than topoheight {} in ping packet", _0, _1)]
+    InvalidStableTopoHeight(u64, u64),
 }
 
 impl From<BlockchainError> for P2pError {
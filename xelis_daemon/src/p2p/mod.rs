@@ -5,12 +5,17 @@ pub mod packet;
 pub mod peer_list;
 pub mod chain_validator;
 pub mod diffie_hellman;
+pub mod slots;
 mod tracker;
 mod encryption;
 mod disk_cache;
 mod bootstrap;
+mod tx_receipt;
+mod tx_relay;
 
 pub use encryption::EncryptionKey;
+use tx_receipt::{TxBroadcastReceipt, MAX_TX_BROADCAST_RECEIPTS};
+use tx_relay::{TxRelay, TX_RELAY_CHECK_INTERVAL};
 
 use indexmap::IndexSet;
 use lru::LruCache;
@@ -18,12 +23,12 @@ use xelis_common::{
     api::daemon::{
         Direction,
         NotifyEvent,
-        PeerPeerDisconnectedEvent
+        PeerPeerDisconnectedEvent,
+        TransactionBroadcastReceipt
     },
     block::{
         Block,
         BlockHeader,
-        BlockVersion,
         TopoHeight,
     },
     config::{TIPS_LIMIT, VERSION},
@@ -43,7 +48,8 @@ use crate::{
     core::{
         blockchain::Blockchain,
         error::BlockchainError,
-        hard_fork,
+        hard_fork::Feature,
+        mempool_policy::MempoolPolicy,
         storage::Storage
     },
     p2p::{
@@ -65,6 +71,7 @@ use self::{
     error::P2pError,
     packet::{
         chain::{BlockId, ChainRequest, ChainResponse},
+        commitment::ChainCommitment,
         handshake::Handshake,
         object::{ObjectRequest, ObjectResponse, OwnedObjectResponse},
         ping::Ping,
@@ -73,6 +80,7 @@ use self::{
     },
     peer::{Peer, TaskState, Rx},
     peer_list::{PeerList, SharedPeerList},
+    slots::{select_peer_to_evict, ConnectionClass, ConnectionSlots},
     tracker::{ObjectTracker, SharedObjectTracker}
 };
 use tokio::{
@@ -96,12 +104,12 @@ use tokio::{
 use log::{info, warn, error, debug, trace};
 use std::{
     borrow::Cow,
-    collections::hash_map::Entry,
+    collections::{hash_map::Entry, HashMap},
     io,
     net::{IpAddr, SocketAddr},
     num::NonZeroUsize,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc
     },
     time::Duration
@@ -118,7 +126,8 @@ pub struct P2pServer<S: Storage> {
     // node tag sent on handshake
     tag: Option<String>,
     // max peers accepted by this server
-    max_peers: usize,
+    // it can be updated at runtime
+    max_peers: AtomicUsize,
     // ip:port address to receive connections
     bind_address: SocketAddr,
     // all peers accepted
@@ -126,7 +135,7 @@ pub struct P2pServer<S: Storage> {
     // reference to the chain to add blocks/txs
     blockchain: Arc<Blockchain<S>>,
     // this sender allows to create a queue system in one task only
-    connections_sender: Sender<(SocketAddr, bool)>,
+    connections_sender: Sender<(SocketAddr, bool, ConnectionClass)>,
     // used to requests objects to peers and avoid requesting the same object to multiple peers
     object_tracker: SharedObjectTracker,
     // used to check if the server is running or not in tasks
@@ -135,9 +144,15 @@ pub struct P2pServer<S: Storage> {
     blocks_propagation_queue: Mutex<LruCache<Hash, ()>>,
     // Sender for the blocks processing task to have a ordered queue
     blocks_processor: Sender<(Arc<Peer>, BlockHeader, Hash)>,
+    // Propagation of the TXs hashes we announced
+    tx_broadcast_receipts: Mutex<LruCache<Hash, TxBroadcastReceipt>>,
+    // TXs hashes announced by our peers and not yet received
+    tx_relay: Mutex<TxRelay>,
     // allow fast syncing (only balances / assets / Smart Contracts changes)
     // without syncing the history
     allow_fast_sync_mode: bool,
+    // peers cross-checking the state chunks received during a fast sync
+    fast_sync_verification_peers: usize,
     // This can be used safely from a trusted node
     // to boost the sync speed by allowing to request several blocks at same time
     allow_boost_sync_mode: bool,
@@ -166,7 +181,7 @@ pub struct P2pServer<S: Storage> {
 }
 
 impl<S: Storage> P2pServer<S> {
-    pub fn new(concurrency: usize, dir_path: Option<String>, tag: Option<String>, max_peers: usize, bind_address: String, blockchain: Arc<Blockchain<S>>, use_peerlist: bool, exclusive_nodes: Vec<SocketAddr>, allow_fast_sync_mode: bool, allow_boost_sync_mode: bool, max_chain_response_size: Option<usize>, sharable: bool, disable_outgoing_connections: bool, dh_keypair: Option<diffie_hellman::DHKeyPair>, dh_action: diffie_hellman::KeyVerificationAction) -> Result<Arc<Self>, P2pError> {
+    pub fn new(concurrency: usize, dir_path: Option<String>, tag: Option<String>, max_peers: usize, bind_address: String, blockchain: Arc<Blockchain<S>>, use_peerlist: bool, exclusive_nodes: Vec<SocketAddr>, allow_fast_sync_mode: bool, fast_sync_verification_peers: usize, allow_boost_sync_mode: bool, max_chain_response_size: Option<usize>, sharable: bool, disable_outgoing_connections: bool, dh_keypair: Option<diffie_hellman::DHKeyPair>, dh_action: diffie_hellman::KeyVerificationAction) -> Result<Arc<Self>, P2pError> {
         if tag.as_ref().is_some_and(|tag| tag.len() == 0 || tag.len() > 16) {
             return Err(P2pError::InvalidTag);
         }
@@ -200,7 +215,7 @@ impl<S: Storage> P2pServer<S> {
         let server = Self {
             peer_id,
             tag,
-            max_peers,
+            max_peers: AtomicUsize::new(max_peers),
             bind_address: addr,
             peer_list,
             blockchain,
@@ -209,7 +224,10 @@ impl<S: Storage> P2pServer<S> {
             is_running: AtomicBool::new(true),
             blocks_propagation_queue: Mutex::new(LruCache::new(NonZeroUsize::new(STABLE_LIMIT as usize * TIPS_LIMIT).unwrap())),
             blocks_processor,
+            tx_broadcast_receipts: Mutex::new(LruCache::new(NonZeroUsize::new(MAX_TX_BROADCAST_RECEIPTS).unwrap())),
+            tx_relay: Mutex::new(TxRelay::new()),
             allow_fast_sync_mode,
+            fast_sync_verification_peers,
             allow_boost_sync_mode,
             max_chain_response_size: max_chain_response_size.unwrap_or(CHAIN_SYNC_DEFAULT_RESPONSE_BLOCKS),
             exclusive_nodes: IndexSet::from_iter(exclusive_nodes.into_iter()),
@@ -279,7 +297,7 @@ impl<S: Storage> P2pServer<S> {
                         break;
                     }
 
-                    let connect = if self.peer_list.size().await >= self.max_peers {
+                    let connect = if self.peer_list.size().await >= self.get_max_peers() {
                         // if we have already reached the limit, we ignore this new connection
                         None
                     } else {
@@ -307,7 +325,7 @@ impl<S: Storage> P2pServer<S> {
 
     // connect to seed nodes, start p2p server
     // and wait on all new connections
-    async fn start(self: &Arc<Self>, receiver: Receiver<(SocketAddr, bool)>, blocks_processor_receiver: Receiver<(Arc<Peer>, BlockHeader, Hash)>, event_receiver: Receiver<Arc<Peer>>, use_peerlist: bool, concurrency: usize) -> Result<(), P2pError> {
+    async fn start(self: &Arc<Self>, receiver: Receiver<(SocketAddr, bool, ConnectionClass)>, blocks_processor_receiver: Receiver<(Arc<Peer>, BlockHeader, Hash)>, event_receiver: Receiver<Arc<Peer>>, use_peerlist: bool, concurrency: usize) -> Result<(), P2pError> {
         let listener = TcpListener::bind(self.get_bind_address()).await?;
         info!("P2p Server will listen on: {}", self.get_bind_address());
 
@@ -335,6 +353,9 @@ impl<S: Storage> P2pServer<S> {
         // start another task for ping loop
         spawn_task("p2p-ping", Arc::clone(&self).ping_loop());
 
+        // start the task requesting the announced TXs again on timeout
+        spawn_task("p2p-tx-relay", Arc::clone(&self).tx_relay_loop());
+
         // start the blocks processing task to have a queued handler
         spawn_task("p2p-blocks", Arc::clone(&self).blocks_processing_task(blocks_processor_receiver));
 
@@ -345,6 +366,18 @@ impl<S: Storage> P2pServer<S> {
         // start another task for peerlist loop
         if use_peerlist {
             spawn_task("p2p-peerlist", Arc::clone(&self).peerlist_loop());
+
+            // verify periodically that the stored peers are still reachable
+            spawn_task("p2p-feeler", Arc::clone(&self).feeler_loop());
+
+            // reconnect to the outgoing peers we had before shutdown
+            match self.peer_list.take_anchors() {
+                Ok(anchors) => for anchor in anchors {
+                    debug!("Reconnecting to anchor {}", anchor);
+                    self.try_to_connect_with_class(anchor, false, ConnectionClass::Anchor).await;
+                },
+                Err(e) => error!("Error while loading anchors: {}", e)
+            };
         }
 
         let (tx, mut rx) = channel(1);
@@ -399,12 +432,12 @@ impl<S: Storage> P2pServer<S> {
         Ok(())
     }
 
-    async fn handle_outgoing_connections(self: Arc<Self>, mut priority_connections: Receiver<SocketAddr>, mut receiver: Receiver<(SocketAddr, bool)>, tx: Sender<(Peer, Rx)>) {
+    async fn handle_outgoing_connections(self: Arc<Self>, mut priority_connections: Receiver<SocketAddr>, mut receiver: Receiver<(SocketAddr, bool, ConnectionClass)>, tx: Sender<(Peer, Rx)>) {
         // only allocate one time the buffer for this packet
         let mut handshake_buffer = [0; 512];
         let mut exit_receiver = self.exit_sender.subscribe();
         loop {
-            let (addr, priority) = select! {
+            let (addr, priority, class) = select! {
                 biased;
                 _ = exit_receiver.recv() => {
                     debug!("Received exit message, exiting outgoing connections task");
@@ -413,7 +446,7 @@ impl<S: Storage> P2pServer<S> {
                 res = priority_connections.recv() => {
                     trace!("New priority connection received");
                     match res {
-                        Some(res) => (res, true),
+                        Some(res) => (res, true, ConnectionClass::Outbound),
                         None => {
                             error!("Error while receiving priority connection, exiting task");
                             break;
@@ -431,12 +464,12 @@ impl<S: Storage> P2pServer<S> {
                 }
             };
 
-            trace!("Trying to connect to {}", addr);
+            trace!("Trying to connect to {} ({})", addr, class);
             if !priority {
                 trace!("checking if connection can be accepted");
                 // check that this incoming peer isn't blacklisted
-                if !self.accept_new_connections().await {
-                    debug!("{} is not allowed, we don't accept any new connection", addr);
+                if !self.has_free_slot(class).await {
+                    debug!("{} is not allowed, we don't have any {} slot available", addr, class);
                     continue;
                 }
 
@@ -473,7 +506,7 @@ impl<S: Storage> P2pServer<S> {
                 }
             };
 
-            let peer = match self.create_verified_peer(&mut handshake_buffer, connection, priority).await {
+            let peer = match self.create_verified_peer(&mut handshake_buffer, connection, priority, class).await {
                 Ok(handshake) => handshake,
                 Err(e) => {
                     debug!("Error while verifying connection to address {}: {}", addr, e);
@@ -486,6 +519,20 @@ impl<S: Storage> P2pServer<S> {
                 }
             };
 
+            // Feeler connection is only used to verify that the peer is reachable
+            if class == ConnectionClass::Feeler {
+                let (peer, _) = peer;
+                debug!("Feeler connection to {} succeeded, disconnecting", addr);
+                if let Err(e) = self.peer_list.mark_reachable(&peer).await {
+                    error!("Error while updating feeler peer {}: {}", addr, e);
+                }
+
+                if let Err(e) = peer.get_connection().close().await {
+                    debug!("Error while closing feeler connection {}: {}", addr, e);
+                }
+                continue;
+            }
+
             // Peer is valid, send it to connect
             if let Err(e) = tx.send(peer).await {
                 error!("Error while sending new connection to listener: {}, exiting task", e);
@@ -505,9 +552,9 @@ impl<S: Storage> P2pServer<S> {
         // Verify if we can accept new connections
         let reject = !self.is_compatible_with_exclusive_nodes(&addr)
             // check that this incoming peer isn't blacklisted
-            || !self.accept_new_connections().await
             || !self.peer_list.is_allowed(&addr.ip()).await?
-            || self.is_connected_to_addr(&addr).await;
+            || self.is_connected_to_addr(&addr).await
+            || !self.make_room_for_inbound().await;
 
         // Reject connection
         if reject {
@@ -521,7 +568,7 @@ impl<S: Storage> P2pServer<S> {
         let tx = tx.clone();
         thread_pool.execute(async move {
             let mut buffer = [0; 512];
-            match zelf.create_verified_peer(&mut buffer, connection, false).await {
+            match zelf.create_verified_peer(&mut buffer, connection, false, ConnectionClass::Inbound).await {
                 Ok((peer, rx)) => {
                     if let Err(e) = tx.send((peer, rx)).await {
                         error!("Error while sending new connection to listener: {}", e);
@@ -605,7 +652,7 @@ impl<S: Storage> P2pServer<S> {
         }
 
         // check if the version of this peer is allowed
-        if !hard_fork::is_version_allowed_at_height(self.blockchain.get_network(), self.blockchain.get_height(), handshake.get_version()).map_err(|e| P2pError::InvalidP2pVersion(e.to_string()))? {
+        if !self.blockchain.get_fork_schedule().is_version_allowed_at_height(self.blockchain.get_height(), handshake.get_version()).map_err(|e| P2pError::InvalidP2pVersion(e.to_string()))? {
             return Err(P2pError::InvalidP2pVersion(handshake.get_version().clone()));
         }
 
@@ -621,12 +668,13 @@ impl<S: Storage> P2pServer<S> {
         let pruned_topoheight = storage.get_pruned_topoheight().await?;
         let cumulative_difficulty = storage.get_cumulative_difficulty_for_block_hash(&top_hash).await.unwrap_or_else(|_| CumulativeDifficulty::zero());
         let genesis_block = get_genesis_block_hash(self.blockchain.get_network());
-        let handshake = Handshake::new(Cow::Owned(VERSION.to_owned()), *self.blockchain.get_network(), Cow::Borrowed(self.get_tag()), Cow::Borrowed(&NETWORK_ID), self.get_peer_id(), self.bind_address.port(), get_current_time_in_seconds(), topoheight, block.get_height(), pruned_topoheight, Cow::Borrowed(&top_hash), Cow::Borrowed(genesis_block), Cow::Borrowed(&cumulative_difficulty), self.sharable);
+        let stable_commitment = ChainCommitment::from_chain(&self.blockchain, &storage).await;
+        let handshake = Handshake::new(Cow::Owned(VERSION.to_owned()), *self.blockchain.get_network(), Cow::Borrowed(self.get_tag()), Cow::Borrowed(&NETWORK_ID), self.get_peer_id(), self.bind_address.port(), get_current_time_in_seconds(), topoheight, block.get_height(), pruned_topoheight, Cow::Borrowed(&top_hash), Cow::Borrowed(genesis_block), Cow::Borrowed(&cumulative_difficulty), self.sharable, Some(*self.blockchain.get_mempool_policy()), stable_commitment);
         Ok(Packet::Handshake(Cow::Owned(handshake)).to_bytes())
     }
 
     // Create a valid peer using the connection, if an error happen, it will close the stream and return the error
-    async fn create_verified_peer(&self, buf: &mut [u8], mut connection: Connection, priority: bool) -> Result<(Peer, Rx), P2pError> {
+    async fn create_verified_peer(&self, buf: &mut [u8], mut connection: Connection, priority: bool, class: ConnectionClass) -> Result<(Peer, Rx), P2pError> {
         let handshake = match self.verify_connection(buf, &mut connection).await {
             Ok(handshake) => handshake,
             Err(e) => {
@@ -636,7 +684,7 @@ impl<S: Storage> P2pServer<S> {
             }
         };
 
-        let (peer, rx) = handshake.create_peer(connection, priority, self.peer_list.clone());
+        let (peer, rx) = handshake.create_peer(connection, priority, class, self.peer_list.clone());
         Ok((peer, rx))
     }
 
@@ -666,6 +714,7 @@ impl<S: Storage> P2pServer<S> {
 
         trace!("received handshake packet!");
         self.verify_handshake(connection, &mut handshake).await?;
+        self.blockchain.get_clock().add_peer_time(handshake.get_peer_id(), handshake.get_utc_time()).await;
 
         trace!("Handshake has been verified");
         // if it's a outgoing connection, don't send the handshake back
@@ -713,17 +762,43 @@ impl<S: Storage> P2pServer<S> {
     // Buffer is passed in parameter to prevent the re-allocation each time
     // No check is done, this is done at the moment of the connection
     pub async fn try_to_connect_to_peer(&self, addr: SocketAddr, priority: bool) {
-        debug!("try to connect to peer addr {}, priority: {}", addr, priority);
+        self.try_to_connect_with_class(addr, priority, ConnectionClass::Outbound).await
+    }
+
+    // Connect to a specific peer address using the slots of the connection class
+    async fn try_to_connect_with_class(&self, addr: SocketAddr, priority: bool, class: ConnectionClass) {
+        debug!("try to connect to peer addr {}, priority: {}, class: {}", addr, priority, class);
         if self.connections_sender.is_closed() {
             error!("Connection sender is closed, we can't connect to peer {}", addr);
             return;
         }
 
-        if let Err(e) = self.connections_sender.send((addr, priority)).await {
+        if let Err(e) = self.connections_sender.send((addr, priority, class)).await {
             error!("Error while trying to connect to address {} (priority = {}): {}", addr, priority, e);
         }
     }
 
+    // Register a connection established outside of the P2P server, such as an in-memory transport
+    // It goes through the same key exchange and handshake as a TCP connection
+    pub async fn add_connection(self: &Arc<Self>, connection: Connection, priority: bool) -> Result<(), P2pError> {
+        let mut buffer = [0; 512];
+        let class = if connection.is_out() {
+            ConnectionClass::Outbound
+        } else {
+            ConnectionClass::Inbound
+        };
+        let (peer, rx) = self.create_verified_peer(&mut buffer, connection, priority, class).await?;
+        let peer = Arc::new(peer);
+        if let Err(e) = self.handle_new_peer(&peer, rx).await {
+            if let Err(e) = peer.get_connection().close().await {
+                debug!("Error while closing unhandled connection: {}", e);
+            }
+            return Err(e)
+        }
+
+        Ok(())
+    }
+
     // Connect to a new peer using its socket address
     // Then we send him a handshake
     async fn connect_to_peer(&self, addr: SocketAddr) -> Result<Connection, P2pError> {
@@ -783,7 +858,8 @@ impl<S: Storage> P2pServer<S> {
         let highest_topo_height = self.blockchain.get_topo_height();
         let highest_height = self.blockchain.get_height();
         let new_peers = IndexSet::new();
-        Ping::new(Cow::Owned(block_top_hash), highest_topo_height, highest_height, pruned_topoheight, cumulative_difficulty, new_peers)
+        let stable_commitment = ChainCommitment::from_chain(&self.blockchain, storage).await;
+        Ping::new(Cow::Owned(block_top_hash), highest_topo_height, highest_height, pruned_topoheight, cumulative_difficulty, new_peers, stable_commitment)
     }
 
     // Build a generic ping packet
@@ -806,6 +882,7 @@ impl<S: Storage> P2pServer<S> {
         
         let our_height = self.blockchain.get_height();
         let our_topoheight = self.blockchain.get_topo_height();
+        let our_stable_topoheight = self.blockchain.get_stable_topoheight();
 
         // Search our cumulative difficulty
         let our_cumulative_difficulty = {
@@ -833,6 +910,19 @@ impl<S: Storage> P2pServer<S> {
                 }
             }
 
+            // Avoid selecting peers having a different stable chain than us
+            // We can't rewind our chain below our stable topoheight
+            let stable_commitment = p.get_stable_commitment().lock().await.clone();
+            if let Some(commitment) = stable_commitment.filter(|c| c.get_stable_topoheight() <= our_stable_topoheight) {
+                let storage = self.blockchain.get_storage().read().await;
+                if let Ok(hash) = storage.get_hash_at_topo_height(commitment.get_stable_topoheight()).await {
+                    if hash != *commitment.get_stable_hash() {
+                        debug!("Peer {} has a different stable chain than us ({} != {}), skipping...", p, commitment.get_stable_hash(), hash);
+                        continue;
+                    }
+                }
+            }
+
             let peer_topoheight = p.get_topoheight();
             if fast_sync {
                 // if we want to fast sync, but this peer is not compatible, we skip it
@@ -994,6 +1084,64 @@ impl<S: Storage> P2pServer<S> {
         }
     }
 
+    // Request again from another announcer the TXs not received in time
+    async fn tx_relay_loop(self: Arc<Self>) {
+        debug!("Starting TX relay loop...");
+
+        let duration = Duration::from_secs(TX_RELAY_CHECK_INTERVAL);
+        loop {
+            sleep(duration).await;
+
+            if !self.is_running() {
+                debug!("TX relay loop task is stopped!");
+                break;
+            }
+
+            let peers: HashMap<u64, Arc<Peer>> = self.peer_list.get_cloned_peers().await
+                .into_iter()
+                .filter(|peer| !peer.get_connection().is_closed())
+                .map(|peer| (peer.get_id(), peer))
+                .collect();
+
+            let requests = {
+                let mut relay = self.tx_relay.lock().await;
+                relay.retain_peers(|id| peers.contains_key(&id));
+                let requests = relay.next_requests(|_, id| peers.contains_key(&id));
+                trace!("TX relay: {} announced TXs tracked, {} to request", relay.len(), requests.len());
+                requests
+            };
+
+            for (hash, peer_id) in requests {
+                match self.blockchain.has_tx(&hash).await {
+                    Ok(true) => {
+                        self.tx_relay.lock().await.remove(&hash);
+                        continue;
+                    },
+                    Ok(false) => {},
+                    Err(e) => {
+                        debug!("Error while checking if we have TX {}: {}", hash, e);
+                        continue;
+                    }
+                }
+
+                // Still requested by the Object Tracker, it will be retried after its timeout
+                if self.object_tracker.has_requested_object(&hash).await {
+                    continue;
+                }
+
+                let Some(peer) = peers.get(&peer_id) else {
+                    continue;
+                };
+
+                debug!("Requesting TX {} from announcer {}", hash, peer);
+                if let Err(e) = self.object_tracker.request_object_from_peer(Arc::clone(peer), ObjectRequest::Transaction(hash.clone()), true).await {
+                    debug!("Error while requesting TX {} from {}: {}", hash, peer, e);
+                    self.tx_relay.lock().await.failed(&hash, peer_id);
+                }
+            }
+        }
+    }
+
     // broadcast generic ping packet every 10s
     // if we have to send our peerlist to all peers, we calculate the ping for each peer
     // instead of being done in each write task of peer, we do it one time so we don't have
@@ -1119,7 +1267,7 @@ impl<S: Storage> P2pServer<S> {
                 break;
             }
 
-            if self.accept_new_connections().await {
+            if self.has_free_slot(ConnectionClass::Outbound).await {
                 let peer = {
                     trace!("Locking peer list write mode (peerlist loop)");
                     match self.peer_list.find_peer_to_connect().await {
@@ -1142,6 +1290,32 @@ impl<S: Storage> P2pServer<S> {
         }
     }
 
+    // connect periodically to a stored peer to verify it is still reachable
+    // the connection is closed right after the handshake
+    async fn feeler_loop(self: Arc<Self>) {
+        debug!("Starting feeler task...");
+        loop {
+            sleep(Duration::from_secs(P2P_FEELER_DELAY)).await;
+            if !self.is_running() {
+                debug!("Feeler loop task is stopped!");
+                break;
+            }
+
+            if self.is_outgoing_connections_disabled() {
+                continue;
+            }
+
+            match self.peer_list.find_feeler_address().await {
+                Ok(Some(addr)) => {
+                    debug!("Starting feeler connection to {}", addr);
+                    self.try_to_connect_with_class(addr, false, ConnectionClass::Feeler).await;
+                },
+                Ok(None) => trace!("No peer found for feeler connection"),
+                Err(e) => error!("Error while finding peer for feeler connection: {}", e)
+            }
+        }
+    }
+
     // This function is used to broadcast PeerDisconnected event to listeners
     // We use a channel to avoid having to pass the Blockchain<S> to the Peerlist & Peers
     async fn event_loop(self: Arc<Self>, mut receiver: Receiver<Arc<Peer>>) {
@@ -1469,10 +1643,19 @@ impl<S: Storage> P2pServer<S> {
                 }
 
                 // Check that the tx is not in mempool or on disk already
-                if !self.blockchain.has_tx(&hash).await? {
-                    trace!("Requesting tx {} propagated because we don't have it", hash);
-                    if !self.object_tracker.request_object_from_peer(Arc::clone(peer), ObjectRequest::Transaction(hash.clone()), true).await? {
-                        debug!("TX propagated {} was already requested, ignoring", hash);
+                // In blocks-only mode, we don't want any loose TX
+                if self.blockchain.get_mempool_policy().is_blocks_only() {
+                    trace!("Ignoring TX {} propagated by {} in blocks-only mode", hash, peer);
+                } else if !self.blockchain.has_tx(&hash).await? {
+                    // Only one announcer is requested at a time,
+                    // others are kept in case it doesn't answer
+                    if self.tx_relay.lock().await.announce(&hash, peer.get_id()) {
+                        trace!("Requesting tx {} propagated because we don't have it", hash);
+                        if !self.object_tracker.request_object_from_peer(Arc::clone(peer), ObjectRequest::Transaction(hash.clone()), true).await? {
+                            debug!("TX propagated {} was already requested, ignoring", hash);
+                        }
+                    } else {
+                        debug!("TX propagated {} is already in flight, keeping {} as announcer", hash, peer);
                     }
                 }
 
@@ -1675,6 +1858,9 @@ impl<S: Storage> P2pServer<S> {
                             Ok(tx) => {
                                 debug!("tx {} found, sending it", hash);
                                 peer.send_packet(Packet::ObjectResponse(ObjectResponse::Transaction(Cow::Borrowed(&tx)))).await?;
+                                if let Some(receipt) = self.tx_broadcast_receipts.lock().await.peek_mut(hash) {
+                                    receipt.add_request(peer.get_id());
+                                }
                             },
                             Err(e) => {
                                 debug!("{} asked tx '{}' but not present in our chain: {}", peer, hash, e);
@@ -1689,6 +1875,12 @@ impl<S: Storage> P2pServer<S> {
                 let response = response.to_owned();
                 trace!("Object response received is {}", response.get_hash());
 
+                match &response {
+                    ObjectResponse::Transaction(tx) => self.tx_relay.lock().await.remove(&tx.hash()),
+                    ObjectResponse::NotFound(ObjectRequest::Transaction(hash)) => self.tx_relay.lock().await.failed(hash, peer.get_id()),
+                    _ => {}
+                }
+
                 // check if we requested it from this peer
                 let request = response.get_request();
                 if peer.has_requested_object(&request).await {
@@ -1949,10 +2141,10 @@ impl<S: Storage> P2pServer<S> {
 
                 let mut swap = false;
                 if let Some(previous_hash) = response_blocks.last() {
-                    let version = hard_fork::get_version_at_height(self.blockchain.get_network(), height);
+                    let ordered = self.blockchain.get_fork_schedule().is_feature_active_at_height(Feature::OrderedSideBlocks, height);
                     // Due to the TX being orphaned, some TXs may be in the wrong order in V1
                     // It has been sorted in V2 and should not happen anymore
-                    if version == BlockVersion::V0 && storage.has_block_position_in_order(&hash).await? && storage.has_block_position_in_order(&previous_hash).await? {
+                    if !ordered && storage.has_block_position_in_order(&hash).await? && storage.has_block_position_in_order(&previous_hash).await? {
                         if self.blockchain.is_side_block_internal(&*storage, &hash, top_topoheight).await? {
                             let position = storage.get_block_position_in_order(&hash).await?;
                             let previous_position = storage.get_block_position_in_order(&previous_hash).await?;
@@ -2339,7 +2531,12 @@ impl<S: Storage> P2pServer<S> {
 
     // Get the maximum peers count allowed to be connected
     pub fn get_max_peers(&self) -> usize {
-        self.max_peers
+        self.max_peers.load(Ordering::Acquire)
+    }
+
+    // Update the maximum peers count, connected peers above it are kept
+    pub fn set_max_peers(&self, max_peers: usize) {
+        self.max_peers.store(max_peers, Ordering::Release);
     }
 
     // Get our unique peer ID
@@ -2352,6 +2549,50 @@ impl<S: Storage> P2pServer<S> {
         self.get_peer_count().await < self.get_max_peers()
     }
 
+    // Check if a new connection of this class can be accepted
+    // Outgoing slots are not reserved if we don't create outgoing connections
+    pub async fn has_free_slot(&self, class: ConnectionClass) -> bool {
+        if class == ConnectionClass::Feeler {
+            return true
+        }
+
+        if !self.accept_new_connections().await {
+            return false
+        }
+
+        if class == ConnectionClass::Inbound && self.is_outgoing_connections_disabled() {
+            return true
+        }
+
+        let counts = self.peer_list.count_by_class().await;
+        ConnectionSlots::new(self.get_max_peers()).has_free_slot(class, &counts)
+    }
+
+    // Verify that we can accept a new inbound connection
+    // If all inbound slots are used, an inbound peer is evicted
+    // Outgoing and priority peers are never evicted
+    async fn make_room_for_inbound(&self) -> bool {
+        if self.has_free_slot(ConnectionClass::Inbound).await {
+            return true
+        }
+
+        let Some(peer_id) = select_peer_to_evict(self.peer_list.get_eviction_candidates().await) else {
+            return false
+        };
+
+        let peer = self.peer_list.get_peers().read().await.get(&peer_id).cloned();
+        match peer {
+            Some(peer) => {
+                debug!("Evicting {} for a new inbound connection", peer);
+                if let Err(e) = peer.close().await {
+                    debug!("Error while evicting {}: {}", peer, e);
+                }
+                true
+            },
+            None => false
+        }
+    }
+
     // Returns the count of peers connected
     pub async fn get_peer_count(&self) -> usize {
         self.peer_list.size().await
@@ -2400,7 +2641,8 @@ impl<S: Storage> P2pServer<S> {
     // Broadcast a new transaction hash using propagation packet
     // This is used so we don't overload the network during spam or high transactions count
     // We simply share its hash to nodes and others nodes can check if they have it already or not
-    pub async fn broadcast_tx_hash(&self, tx: Hash) {
+    // fee and size are used to skip peers whose mempool policy would reject the TX
+    pub async fn broadcast_tx_hash(&self, tx: Hash, fee: u64, tx_size: usize) {
         debug!("Broadcasting tx hash {}", tx);
         let ping = self.build_generic_ping_packet().await;
         debug!("Ping packet has been generated for tx broadcast");
@@ -2412,7 +2654,17 @@ impl<S: Storage> P2pServer<S> {
         let peers = self.peer_list.get_cloned_peers().await;
         trace!("Lock acquired for tx broadcast");
 
+        let mut receipts = self.tx_broadcast_receipts.lock().await;
+        let receipt = receipts.get_or_insert_mut(tx.clone(), TxBroadcastReceipt::new);
+        receipt.mark_announcing();
+
         for peer in peers {
+            if peer.get_mempool_policy().is_some_and(|policy| !policy.accepts(fee, tx_size)) {
+                trace!("TX {} is rejected by the mempool policy of {}, skipping", tx, peer);
+                receipt.add_skipped_by_policy();
+                continue;
+            }
+
             // check that the peer is not too far from us
             // otherwise we may spam him for nothing
             let peer_topoheight = peer.get_topoheight();
@@ -2425,23 +2677,37 @@ impl<S: Storage> P2pServer<S> {
                     trace!("Broadcasting tx hash {} to {}", tx, peer);
                     if let Err(e) = peer.send_bytes(bytes.clone()).await {
                         error!("Error while broadcasting tx hash {} to {}: {}", tx, peer, e);
+                        receipt.add_send_error();
+                    } else {
+                        receipt.add_announced();
                     }
                     trace!("Adding tx hash {} to cache for {}", tx, peer);
                     // Set it as "In" so we can't get it back as we are the sender of it
                     txs_cache.put(tx.clone(), Direction::In);
                 } else {
                     trace!("{} have tx hash {} in cache, skipping", peer, tx);
+                    receipt.add_skipped_known();
                 }
+            } else {
+                receipt.add_skipped_out_of_sync();
             }
         }
     }
 
+    // Propagation of a TX hash we announced, None if it was never announced
+    pub async fn get_tx_broadcast_receipt(&self, hash: &Hash) -> Option<TransactionBroadcastReceipt> {
+        let in_mempool = self.blockchain.get_mempool().read().await.contains_tx(hash);
+        self.tx_broadcast_receipts.lock().await
+            .peek(hash)
+            .map(|receipt| receipt.to_api(in_mempool))
+    }
+
     // broadcast block to all peers that can accept directly this new block
-    pub async fn broadcast_block(&self, block: &BlockHeader, cumulative_difficulty: CumulativeDifficulty, our_topoheight: u64, our_height: u64, pruned_topoheight: Option<u64>, hash: &Hash, lock: bool) {
+    pub async fn broadcast_block(&self, block: &BlockHeader, cumulative_difficulty: CumulativeDifficulty, our_topoheight: u64, our_height: u64, pruned_topoheight: Option<u64>, stable_commitment: Option<ChainCommitment>, hash: &Hash, lock: bool) {
         debug!("Broadcasting block {} at height {}", hash, block.get_height());
         // we build the ping packet ourself this time (we have enough data for it)
         // because this function can be call from Blockchain, which would lead to a deadlock
-        let ping = Ping::new(Cow::Borrowed(hash), our_topoheight, our_height, pruned_topoheight, cumulative_difficulty, IndexSet::new());
+        let ping = Ping::new(Cow::Borrowed(hash), our_topoheight, our_height, pruned_topoheight, cumulative_difficulty, IndexSet::new(), stable_commitment);
         let block_packet = Packet::BlockPropagation(PacketWrapper::new(Cow::Borrowed(block), Cow::Borrowed(&ping)));
         let packet_block_bytes = Bytes::from(block_packet.to_bytes());
         let packet_ping_bytes = Bytes::from(Packet::Ping(Cow::Owned(ping)).to_bytes());
@@ -2521,7 +2787,13 @@ impl<S: Storage> P2pServer<S> {
 
     // Request the inventory of a peer
     // This will sends him a request packet so we get notified of all its TXs hashes in its mempool
+    // Skipped if we or the peer are in blocks-only mode
     async fn request_inventory_of(&self, peer: &Arc<Peer>) -> Result<(), BlockchainError> {
+        if self.blockchain.get_mempool_policy().is_blocks_only() || peer.get_mempool_policy().is_some_and(MempoolPolicy::is_blocks_only) {
+            trace!("No inventory to request from {} in blocks-only mode", peer);
+            return Ok(())
+        }
+
         debug!("Requesting inventory of {}", peer);
         let packet = Cow::Owned(NotifyInventoryRequest::new(None));
         let ping = Cow::Owned(self.build_generic_ping_packet().await);
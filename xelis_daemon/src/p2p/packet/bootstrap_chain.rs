@@ -141,7 +141,7 @@ impl StepKind {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum StepRequest<'a> {
     // Request chain info (top topoheight, top height, top hash)
     ChainInfo(IndexSet<BlockId>),
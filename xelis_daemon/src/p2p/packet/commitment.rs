@@ -0,0 +1,76 @@
+use std::fmt::{self, Display, Formatter};
+use log::debug;
+use xelis_common::{
+    block::TopoHeight,
+    crypto::Hash,
+    serializer::{Reader, ReaderError, Versioned, Writer}
+};
+use crate::core::{
+    blockchain::Blockchain,
+    storage::Storage
+};
+
+// Commitment on the stable part of the chain of a peer
+// Sent in the handshake and ping packets, so peers on another stable chain
+// can be filtered before requesting any chain data from them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainCommitment {
+    // stable topoheight of the peer
+    stable_topoheight: TopoHeight,
+    // block hash at the stable topoheight
+    stable_hash: Hash
+}
+
+impl ChainCommitment {
+    pub fn new(stable_topoheight: TopoHeight, stable_hash: Hash) -> Self {
+        Self {
+            stable_topoheight,
+            stable_hash
+        }
+    }
+
+    // Build the commitment of our chain
+    // Blockchain may be locked by the caller, so the storage is given
+    pub async fn from_chain<S: Storage>(blockchain: &Blockchain<S>, storage: &S) -> Option<Self> {
+        let stable_topoheight = blockchain.get_stable_topoheight();
+        match storage.get_hash_at_topo_height(stable_topoheight).await {
+            Ok(hash) => Some(Self::new(stable_topoheight, hash)),
+            Err(e) => {
+                debug!("Couldn't get the stable hash at topoheight {} for chain commitment: {}", stable_topoheight, e);
+                None
+            }
+        }
+    }
+
+    pub fn get_stable_topoheight(&self) -> TopoHeight {
+        self.stable_topoheight
+    }
+
+    pub fn get_stable_hash(&self) -> &Hash {
+        &self.stable_hash
+    }
+}
+
+// Appended at the end of the handshake and ping packets
+impl Versioned for ChainCommitment {
+    const TAG: u16 = 0x4343;
+    const VERSION: u8 = 0;
+
+    fn write_fields(&self, writer: &mut Writer) {
+        writer.write_u64(&self.stable_topoheight);
+        writer.write_hash(&self.stable_hash);
+    }
+
+    fn read_fields(reader: &mut Reader, _: u8) -> Result<Self, ReaderError> {
+        let stable_topoheight = reader.read_u64()?;
+        let stable_hash = reader.read_hash()?;
+
+        Ok(Self::new(stable_topoheight, stable_hash))
+    }
+}
+
+impl Display for ChainCommitment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ChainCommitment[stable topoheight: {}, stable hash: {}]", self.stable_topoheight, self.stable_hash)
+    }
+}
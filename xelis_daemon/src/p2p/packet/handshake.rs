@@ -3,14 +3,19 @@ use xelis_common::{
     crypto::Hash,
     difficulty::CumulativeDifficulty,
     network::Network,
-    serializer::{Reader, ReaderError, Serializer, Writer},
+    serializer::{Envelope, Reader, ReaderError, Serializer, Writer},
     time::TimestampSeconds
 };
-use crate::p2p::{
-    connection::Connection,
-    peer::{Peer, Rx},
-    peer_list::SharedPeerList
+use crate::{
+    core::mempool_policy::MempoolPolicy,
+    p2p::{
+        connection::Connection,
+        peer::{Peer, Rx},
+        peer_list::SharedPeerList,
+        slots::ConnectionClass
+    }
 };
+use super::commitment::ChainCommitment;
 use std::{
     borrow::Cow,
     collections::HashSet,
@@ -52,14 +57,21 @@ pub struct Handshake<'a> {
     cumulative_difficulty: Cow<'a, CumulativeDifficulty>,
     // By default it's true, and peer allow to be shared to others and/or through API
     // If false, we must not share it
-    can_be_shared: bool
+    can_be_shared: bool,
+    // TXs admission policy of its mempool
+    // Not sent by older versions
+    mempool_policy: Option<MempoolPolicy>,
+    // Stable part of its chain
+    // Appended after the mempool policy, so it is only sent with it
+    stable_commitment: Option<ChainCommitment>
 } // Server reply with his own list of peers, but we remove all already known by requester for the response.
 
 impl<'a> Handshake<'a> {
     pub const MAX_LEN: usize = 16;
 
-    pub fn new(version: Cow<'a, String>, network: Network, node_tag: Cow<'a, Option<String>>, network_id: Cow<'a, [u8; 16]>, peer_id: u64, local_port: u16, utc_time: TimestampSeconds, topoheight: u64, height: u64, pruned_topoheight: Option<u64>, top_hash: Cow<'a, Hash>, genesis_hash: Cow<'a, Hash>, cumulative_difficulty: Cow<'a, CumulativeDifficulty>, can_be_shared: bool) -> Self {
+    pub fn new(version: Cow<'a, String>, network: Network, node_tag: Cow<'a, Option<String>>, network_id: Cow<'a, [u8; 16]>, peer_id: u64, local_port: u16, utc_time: TimestampSeconds, topoheight: u64, height: u64, pruned_topoheight: Option<u64>, top_hash: Cow<'a, Hash>, genesis_hash: Cow<'a, Hash>, cumulative_difficulty: Cow<'a, CumulativeDifficulty>, can_be_shared: bool, mempool_policy: Option<MempoolPolicy>, stable_commitment: Option<ChainCommitment>) -> Self {
         debug_assert!(version.len() > 0 && version.len() <= Handshake::MAX_LEN);
+        debug_assert!(stable_commitment.is_none() || mempool_policy.is_some());
         // version cannot be greater than 16 chars
         if let Some(node_tag) = node_tag.as_ref() {
             // node tag cannot be greater than 16 chars
@@ -80,14 +92,16 @@ impl<'a> Handshake<'a> {
             top_hash,
             genesis_hash,
             cumulative_difficulty,
-            can_be_shared
+            can_be_shared,
+            mempool_policy,
+            stable_commitment
         }
     }
 
     // Create a new peer using its connection and this handshake packet
-    pub fn create_peer(self, connection: Connection, priority: bool, peer_list: SharedPeerList) -> (Peer, Rx) {
+    pub fn create_peer(self, connection: Connection, priority: bool, class: ConnectionClass, peer_list: SharedPeerList) -> (Peer, Rx) {
         let peers = HashSet::new();
-        Peer::new(connection, self.get_peer_id(), self.node_tag.into_owned(), self.local_port, self.version.into_owned(), self.top_hash.into_owned(), self.topoheight, self.height, self.pruned_topoheight, priority, self.cumulative_difficulty.into_owned(), peer_list, peers, self.can_be_shared)
+        Peer::new(connection, self.get_peer_id(), self.node_tag.into_owned(), self.local_port, self.version.into_owned(), self.top_hash.into_owned(), self.topoheight, self.height, self.pruned_topoheight, priority, self.cumulative_difficulty.into_owned(), peer_list, peers, self.can_be_shared, self.mempool_policy, self.stable_commitment, class)
     }
 
     pub fn get_local_port(&self) -> u16 {
@@ -137,6 +151,14 @@ impl<'a> Handshake<'a> {
     pub fn get_pruned_topoheight(&self) -> &Option<u64> {
         &self.pruned_topoheight
     }
+
+    pub fn get_mempool_policy(&self) -> Option<&MempoolPolicy> {
+        self.mempool_policy.as_ref()
+    }
+
+    pub fn get_stable_commitment(&self) -> Option<&ChainCommitment> {
+        self.stable_commitment.as_ref()
+    }
 }
 
 impl Serializer for Handshake<'_> {
@@ -162,6 +184,14 @@ impl Serializer for Handshake<'_> {
         writer.write_hash(&self.genesis_hash); // Genesis Hash
         self.cumulative_difficulty.write(writer); // Cumulative Difficulty
         writer.write_bool(self.can_be_shared); // Can be shared
+        // Mempool policy, appended in its envelope for compatibility
+        if let Some(policy) = self.mempool_policy {
+            Envelope(policy).write(writer);
+            // Stable commitment, appended in its envelope for compatibility
+            if let Some(commitment) = self.stable_commitment.as_ref() {
+                Envelope(commitment.clone()).write(writer);
+            }
+        }
     }
 
     fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
@@ -203,8 +233,21 @@ impl Serializer for Handshake<'_> {
         let genesis_hash = reader.read_hash()?;
         let cumulative_difficulty = CumulativeDifficulty::read(reader)?;
         let can_be_shared = reader.read_bool()?;
+        let mempool_policy = reader.read_if_remaining::<Envelope<MempoolPolicy>>()?.map(Envelope::into_inner);
+        let stable_commitment = if mempool_policy.is_some() {
+            reader.read_if_remaining::<Envelope<ChainCommitment>>()?.map(Envelope::into_inner)
+        } else {
+            None
+        };
+
+        if let Some(commitment) = &stable_commitment {
+            if commitment.get_stable_topoheight() > topoheight {
+                debug!("Invalid stable topoheight {} above topoheight {} in handshake packet", commitment.get_stable_topoheight(), topoheight);
+                return Err(ReaderError::InvalidValue)
+            }
+        }
 
-        Ok(Handshake::new(Cow::Owned(version), network, Cow::Owned(node_tag), Cow::Owned(network_id), peer_id, local_port, utc_time, topoheight, height, pruned_topoheight, Cow::Owned(top_hash), Cow::Owned(genesis_hash), Cow::Owned(cumulative_difficulty), can_be_shared))
+        Ok(Handshake::new(Cow::Owned(version), network, Cow::Owned(node_tag), Cow::Owned(network_id), peer_id, local_port, utc_time, topoheight, height, pruned_topoheight, Cow::Owned(top_hash), Cow::Owned(genesis_hash), Cow::Owned(cumulative_difficulty), can_be_shared, mempool_policy, stable_commitment))
     }
 
     fn size(&self) -> usize {
@@ -235,7 +278,11 @@ impl Serializer for Handshake<'_> {
         // Cumulative Difficulty
         self.cumulative_difficulty.size() +
         // Can be shared
-        self.can_be_shared.size()
+        self.can_be_shared.size() +
+        // Mempool policy
+        self.mempool_policy.map_or(0, |policy| Envelope(policy).size()) +
+        // Stable commitment
+        self.mempool_policy.and(self.stable_commitment.as_ref()).map_or(0, |commitment| Envelope(commitment.clone()).size())
     }
 }
 
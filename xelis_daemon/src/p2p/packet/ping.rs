@@ -4,6 +4,7 @@ use xelis_common::{
     crypto::Hash,
     difficulty::CumulativeDifficulty,
     serializer::{
+        Envelope,
         Reader,
         ReaderError,
         Serializer,
@@ -21,6 +22,7 @@ use crate::{
         peer::Peer,
         is_local_address,
     },
+    p2p::packet::commitment::ChainCommitment,
     rpc::rpc::get_peer_entry
 };
 use std::{
@@ -38,18 +40,21 @@ pub struct Ping<'a> {
     height: u64,
     pruned_topoheight: Option<u64>,
     cumulative_difficulty: CumulativeDifficulty,
-    peer_list: IndexSet<SocketAddr>
+    peer_list: IndexSet<SocketAddr>,
+    // Not sent by older versions
+    stable_commitment: Option<ChainCommitment>
 }
 
 impl<'a> Ping<'a> {
-    pub fn new(top_hash: Cow<'a, Hash>, topoheight: u64, height: u64, pruned_topoheight: Option<u64>, cumulative_difficulty: CumulativeDifficulty, peer_list: IndexSet<SocketAddr>) -> Self {
+    pub fn new(top_hash: Cow<'a, Hash>, topoheight: u64, height: u64, pruned_topoheight: Option<u64>, cumulative_difficulty: CumulativeDifficulty, peer_list: IndexSet<SocketAddr>, stable_commitment: Option<ChainCommitment>) -> Self {
         Self {
             top_hash,
             topoheight,
             height,
             pruned_topoheight,
             cumulative_difficulty,
-            peer_list
+            peer_list,
+            stable_commitment
         }
     }
 
@@ -76,8 +81,18 @@ impl<'a> Ping<'a> {
             }
         }
 
+        if let Some(commitment) = &self.stable_commitment {
+            if commitment.get_stable_topoheight() > self.topoheight {
+                return Err(P2pError::InvalidStableTopoHeight(commitment.get_stable_topoheight(), self.topoheight))
+            }
+        }
+
         peer.set_pruned_topoheight(self.pruned_topoheight);
         peer.set_cumulative_difficulty(self.cumulative_difficulty).await;
+        // Older versions don't send it, keep the last one known
+        if let Some(commitment) = self.stable_commitment {
+            peer.set_stable_commitment(commitment).await;
+        }
 
         if peer.sharable() {
             trace!("Locking RPC Server to notify PeerStateUpdated event");
@@ -162,6 +177,10 @@ impl Serializer for Ping<'_> {
         for peer in &self.peer_list {
             peer.write(writer);
         }
+        // Stable commitment, appended in its envelope for compatibility
+        if let Some(commitment) = self.stable_commitment.as_ref() {
+            Envelope(commitment.clone()).write(writer);
+        }
     }
 
     fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
@@ -191,7 +210,9 @@ impl Serializer for Ping<'_> {
             }
         }
 
-        Ok(Self { top_hash, topoheight, height, pruned_topoheight, cumulative_difficulty, peer_list })
+        let stable_commitment = reader.read_if_remaining::<Envelope<ChainCommitment>>()?.map(Envelope::into_inner);
+
+        Ok(Self { top_hash, topoheight, height, pruned_topoheight, cumulative_difficulty, peer_list, stable_commitment })
     }
 
     fn size(&self) -> usize {
@@ -202,7 +223,8 @@ impl Serializer for Ping<'_> {
         self.cumulative_difficulty.size() +
         // u8 for the length of the peer list
         1 +
-        self.peer_list.iter().map(|p| p.size()).sum::<usize>()
+        self.peer_list.iter().map(|p| p.size()).sum::<usize>() +
+        self.stable_commitment.as_ref().map_or(0, |commitment| Envelope(commitment.clone()).size())
     }
 }
 
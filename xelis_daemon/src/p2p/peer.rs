@@ -5,7 +5,8 @@ use crate::{
         PEER_TIMEOUT_REQUEST_OBJECT, CHAIN_SYNC_TIMEOUT_SECS,
         PEER_PACKET_CHANNEL_SIZE
     },
-    p2p::packet::PacketWrapper
+    core::mempool_policy::MempoolPolicy,
+    p2p::packet::{commitment::ChainCommitment, PacketWrapper}
 };
 use xelis_common::{
     api::daemon::Direction,
@@ -37,6 +38,7 @@ use super::{
     },
     peer_list::SharedPeerList,
     connection::Connection,
+    slots::ConnectionClass,
     error::P2pError
 };
 use std::{
@@ -101,6 +103,8 @@ pub struct Peer {
     version: String,
     // if this node can be trusted (seed node or added manually by user)
     priority: bool,
+    // class of the connection, used for the slots and eviction
+    class: ConnectionClass,
     // current block top hash for this peer
     top_hash: Mutex<Hash>,
     // current highest topo height for this peer
@@ -148,6 +152,11 @@ pub struct Peer {
     outgoing_address: SocketAddr,
     // Determine if this peer allows to be shared to others and/or through API
     sharable: bool,
+    // TXs admission policy sent in its handshake
+    mempool_policy: Option<MempoolPolicy>,
+    // Stable part of its chain, updated by ping packets
+    // None for older versions
+    stable_commitment: Mutex<Option<ChainCommitment>>,
     // Channel to send bytes to the writer task
     tx: Tx,
     // Channel to notify the tasks to exit
@@ -158,7 +167,7 @@ pub struct Peer {
 }
 
 impl Peer {
-    pub fn new(connection: Connection, id: u64, node_tag: Option<String>, local_port: u16, version: String, top_hash: Hash, topoheight: TopoHeight, height: u64, pruned_topoheight: Option<TopoHeight>, priority: bool, cumulative_difficulty: CumulativeDifficulty, peer_list: SharedPeerList, peers_received: HashSet<SocketAddr>, sharable: bool) -> (Self, Rx) {
+    pub fn new(connection: Connection, id: u64, node_tag: Option<String>, local_port: u16, version: String, top_hash: Hash, topoheight: TopoHeight, height: u64, pruned_topoheight: Option<TopoHeight>, priority: bool, cumulative_difficulty: CumulativeDifficulty, peer_list: SharedPeerList, peers_received: HashSet<SocketAddr>, sharable: bool, mempool_policy: Option<MempoolPolicy>, stable_commitment: Option<ChainCommitment>, class: ConnectionClass) -> (Self, Rx) {
         let mut outgoing_address = *connection.get_address();
         outgoing_address.set_port(local_port);
 
@@ -180,6 +189,7 @@ impl Peer {
             topoheight: AtomicU64::new(topoheight),
             height: AtomicU64::new(height),
             priority,
+            class,
             last_fail_count: AtomicU64::new(0),
             fail_count: AtomicU8::new(0),
             last_chain_sync: AtomicU64::new(0),
@@ -200,6 +210,8 @@ impl Peer {
             sync_chain: Mutex::new(None),
             outgoing_address,
             sharable,
+            mempool_policy,
+            stable_commitment: Mutex::new(stable_commitment),
             exit_channel,
             tx,
             read_task: Mutex::new(TaskState::Inactive),
@@ -328,11 +340,32 @@ impl Peer {
         self.priority
     }
 
+    // Get the class of the connection
+    pub fn get_connection_class(&self) -> ConnectionClass {
+        self.class
+    }
+
     // Get the sharable flag of the peer
     pub fn sharable(&self) -> bool {
         self.sharable
     }
 
+    // Mempool policy of the peer, None for older versions
+    pub fn get_mempool_policy(&self) -> Option<&MempoolPolicy> {
+        self.mempool_policy.as_ref()
+    }
+
+    // Commitment on the stable part of the peer chain
+    pub fn get_stable_commitment(&self) -> &Mutex<Option<ChainCommitment>> {
+        &self.stable_commitment
+    }
+
+    // Store the stable commitment
+    // This is updated by ping packet
+    pub async fn set_stable_commitment(&self, commitment: ChainCommitment) {
+        *self.stable_commitment.lock().await = Some(commitment);
+    }
+
     // Get the last time we got a fail from the peer
     pub fn get_last_fail_count(&self) -> u64 {
         self.last_fail_count.load(Ordering::Acquire)
@@ -2,6 +2,7 @@ use crate::{
     config::{
         PEER_FAIL_TO_CONNECT_LIMIT,
         PEER_TEMP_BAN_TIME_ON_CONNECT,
+        P2P_ANCHOR_SLOTS,
         P2P_PEERLIST_RETRY_AFTER
     },
     p2p::packet::peer_disconnected::PacketPeerDisconnected
@@ -10,7 +11,8 @@ use super::{
     disk_cache::{DiskCache, DiskError},
     error::P2pError,
     packet::Packet,
-    peer::Peer
+    peer::Peer,
+    slots::{ConnectionClass, EvictionCandidate}
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -19,13 +21,14 @@ use std::{
     time::Duration
 };
 use humantime::format_duration;
+use rand::seq::IteratorRandom;
 use serde::{Serialize, Deserialize};
 use tokio::sync::{mpsc::Sender, RwLock};
 use x25519_dalek::PublicKey;
 use xelis_common::{
     api::daemon::Direction,
     block::TopoHeight,
-    serializer::{Reader, ReaderError, Serializer, Writer},
+    serializer::{Reader, ReaderError, Serializer, Versioned, Writer},
     time::{get_current_time_in_seconds, TimestampSeconds}
 };
 use std::sync::Arc;
@@ -137,7 +140,7 @@ impl PeerList {
             }
         }
 
-        info!("Peer disconnected: {}", peer);
+        info!(peer:% = peer.get_outgoing_address(); "Peer disconnected: {}", peer);
 
         // Update the peerlist entry
         self.update_peer(&peer).await?;
@@ -167,7 +170,7 @@ impl PeerList {
 
             peers.insert(peer.get_id(), Arc::clone(&peer));
         }
-        info!("New peer connected: {}", peer);
+        info!(peer:% = peer.get_outgoing_address(); "New peer connected: {}", peer);
 
         self.update_peer(&peer).await?;
 
@@ -232,6 +235,48 @@ impl PeerList {
         peers.len()
     }
 
+    // Count the connected peers per connection class
+    pub async fn count_by_class(&self) -> HashMap<ConnectionClass, usize> {
+        let peers = self.peers.read().await;
+        let mut counts = HashMap::new();
+        for peer in peers.values() {
+            *counts.entry(peer.get_connection_class()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    // Get all connected peers as eviction candidates
+    pub async fn get_eviction_candidates(&self) -> Vec<EvictionCandidate> {
+        let peers = self.peers.read().await;
+        peers.values()
+            .map(|peer| EvictionCandidate {
+                id: peer.get_id(),
+                class: peer.get_connection_class(),
+                priority: peer.is_priority(),
+                ip: peer.get_ip(),
+                connected_on: peer.get_connection().connected_on()
+            })
+            .collect()
+    }
+
+    // Get the anchors saved during the previous shutdown
+    pub fn take_anchors(&self) -> Result<Vec<SocketAddr>, P2pError> {
+        Ok(self.cache.take_anchors()?)
+    }
+
+    // Select the outgoing peers to reconnect at next startup
+    // The longest connected ones are kept, priority nodes are already reconnected
+    fn select_anchors<'a>(peers: impl Iterator<Item = &'a Arc<Peer>>) -> Vec<SocketAddr> {
+        let mut candidates = peers.filter(|peer| !peer.is_priority() && matches!(peer.get_connection_class(), ConnectionClass::Outbound | ConnectionClass::Anchor))
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|peer| peer.get_connection().connected_on());
+
+        candidates.into_iter()
+            .take(P2P_ANCHOR_SLOTS)
+            .map(|peer| *peer.get_outgoing_address())
+            .collect()
+    }
+
     pub async fn close_all(&self) {
         trace!("closing all peers");
         let peers = {
@@ -239,6 +284,12 @@ impl PeerList {
             peers.drain().collect::<Vec<(u64, Arc<Peer>)>>()
         };
 
+        let anchors = Self::select_anchors(peers.iter().map(|(_, peer)| peer));
+        debug!("Saving {} anchors", anchors.len());
+        if let Err(e) = self.cache.set_anchors(&anchors) {
+            error!("Error while saving anchors: {}", e);
+        }
+
         info!("Closing {} peers", peers.len());
         for (_, peer) in peers {
             debug!("Closing {}", peer);
@@ -492,6 +543,44 @@ impl PeerList {
     }
 
 
+    // Find a stored peer we never connected to or that wasn't seen for a while
+    // Feeler connections are used to verify it is still reachable
+    pub async fn find_feeler_address(&self) -> Result<Option<SocketAddr>, P2pError> {
+        let peers = self.peers.read().await;
+        let current_time = get_current_time_in_seconds();
+
+        let mut candidates = Vec::new();
+        for res in self.cache.get_peerlist_entries() {
+            let (ip, entry) = res?;
+            if *entry.get_state() != PeerListEntryState::Graylist || entry.get_temp_ban_until().is_some_and(|until| until > current_time) {
+                continue;
+            }
+
+            if let Some(local_port) = entry.get_local_port() {
+                let addr = SocketAddr::new(ip, local_port);
+                if entry.get_last_connection_try().unwrap_or(0) + (entry.get_fail_count() as u64 * P2P_PEERLIST_RETRY_AFTER) <= current_time && Self::internal_get_peer_by_addr(&peers, &addr).is_none() {
+                    candidates.push(addr);
+                }
+            }
+        }
+
+        let Some(addr) = candidates.into_iter().choose(&mut rand::thread_rng()) else {
+            return Ok(None)
+        };
+
+        let ip = addr.ip();
+        let mut entry = self.cache.get_peerlist_entry(&ip)?;
+        entry.set_last_connection_try(current_time);
+        self.cache.set_peerlist_entry(&ip, entry)?;
+
+        Ok(Some(addr))
+    }
+
+    // The feeler connection succeeded, update its stored entry
+    pub async fn mark_reachable(&self, peer: &Peer) -> Result<(), P2pError> {
+        self.update_peer(peer).await
+    }
+
     // increase the fail count of a peer
     // If tempban is allowed, and the fail count is at the limit, temp ban the peer
     pub async fn increase_fail_count_for_peerlist_entry(&self, ip: &IpAddr, temp_ban: bool) -> Result<(), P2pError> {
@@ -709,4 +798,20 @@ impl Serializer for PeerListEntry {
             public_key
         })
     }
-}
\ No newline at end of file
+}
+
+// Entries stored before the envelope start with a big endian timestamp,
+// so its first byte can't be the tag high byte
+impl Versioned for PeerListEntry {
+    const TAG: u16 = 0x5045;
+    const VERSION: u8 = 0;
+
+    fn write_fields(&self, writer: &mut Writer) {
+        self.write(writer);
+    }
+
+    // Fields added in next versions must be read using `read_if_remaining`
+    fn read_fields(reader: &mut Reader, _: u8) -> Result<Self, ReaderError> {
+        Self::read(reader)
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    net::IpAddr
+};
+use serde::{Deserialize, Serialize};
+use xelis_common::time::TimestampSeconds;
+use crate::config::{
+    P2P_ANCHOR_SLOTS,
+    P2P_EVICTION_PROTECTED_PEERS,
+    P2P_MAX_OUTBOUND_SLOTS
+};
+
+// Class of a connection
+// Each class has its own reserved slots so inbound connections
+// can't take the place of the outgoing ones we selected
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionClass {
+    // Connection initiated by the peer
+    Inbound,
+    // Connection initiated by us
+    Outbound,
+    // Short-lived connection to verify that a stored peer is reachable
+    Feeler,
+    // Outgoing connection restored from the previous run, never evicted
+    Anchor
+}
+
+impl ConnectionClass {
+    // Is this connection initiated by us
+    pub fn is_outgoing(&self) -> bool {
+        !matches!(self, Self::Inbound)
+    }
+}
+
+impl Display for ConnectionClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Inbound => "inbound",
+            Self::Outbound => "outbound",
+            Self::Feeler => "feeler",
+            Self::Anchor => "anchor"
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+// Slots available for each class based on the max peers
+// Feelers are disconnected right after the handshake and don't use any slot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectionSlots {
+    // outgoing connections, anchors included
+    outbound: usize,
+    // anchors restored at startup
+    anchor: usize,
+    // connections initiated by peers
+    inbound: usize
+}
+
+impl ConnectionSlots {
+    pub fn new(max_peers: usize) -> Self {
+        let outbound = P2P_MAX_OUTBOUND_SLOTS.min((max_peers / 2).max(1)).min(max_peers);
+        Self {
+            outbound,
+            anchor: P2P_ANCHOR_SLOTS.min(outbound),
+            inbound: max_peers - outbound
+        }
+    }
+
+    pub fn get_outbound(&self) -> usize {
+        self.outbound
+    }
+
+    pub fn get_anchor(&self) -> usize {
+        self.anchor
+    }
+
+    pub fn get_inbound(&self) -> usize {
+        self.inbound
+    }
+
+    // Check if a new connection of this class can be accepted
+    // based on the connections count per class
+    pub fn has_free_slot(&self, class: ConnectionClass, counts: &HashMap<ConnectionClass, usize>) -> bool {
+        let count = |class| counts.get(&class).copied().unwrap_or(0);
+        match class {
+            ConnectionClass::Inbound => count(ConnectionClass::Inbound) < self.inbound,
+            ConnectionClass::Outbound => count(ConnectionClass::Outbound) + count(ConnectionClass::Anchor) < self.outbound,
+            ConnectionClass::Anchor => count(ConnectionClass::Anchor) < self.anchor,
+            ConnectionClass::Feeler => true
+        }
+    }
+}
+
+// Connected peer that may be evicted to make room for a new inbound connection
+pub struct EvictionCandidate {
+    pub id: u64,
+    pub class: ConnectionClass,
+    pub priority: bool,
+    pub ip: IpAddr,
+    pub connected_on: TimestampSeconds
+}
+
+// Network group of an IP, peers in the same group are likely controlled by the same entity
+fn get_network_group(ip: &IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets()[..2].to_vec(),
+        IpAddr::V6(ip) => ip.octets()[..4].to_vec()
+    }
+}
+
+// Select the peer to evict to make room for a new inbound connection
+// Only inbound peers can be evicted, so our outgoing connections are always kept
+// The longest connected peers are protected, then the youngest peer
+// of the most represented network group is selected
+pub fn select_peer_to_evict(mut candidates: Vec<EvictionCandidate>) -> Option<u64> {
+    candidates.retain(|c| c.class == ConnectionClass::Inbound && !c.priority);
+
+    // protect the longest connected peers
+    candidates.sort_by_key(|c| c.connected_on);
+    if candidates.len() <= P2P_EVICTION_PROTECTED_PEERS {
+        return None
+    }
+    candidates.drain(..P2P_EVICTION_PROTECTED_PEERS);
+
+    let mut groups: HashMap<Vec<u8>, Vec<EvictionCandidate>> = HashMap::new();
+    for candidate in candidates {
+        groups.entry(get_network_group(&candidate.ip))
+            .or_default()
+            .push(candidate);
+    }
+
+    // largest group, the youngest connection in case of equality
+    groups.into_values()
+        .max_by_key(|group| (group.len(), group.iter().map(|c| c.connected_on).max()))
+        .and_then(|group| group.into_iter().max_by_key(|c| c.connected_on))
+        .map(|c| c.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: u64, class: ConnectionClass, ip: &str, connected_on: TimestampSeconds) -> EvictionCandidate {
+        EvictionCandidate {
+            id,
+            class,
+            priority: false,
+            ip: ip.parse().unwrap(),
+            connected_on
+        }
+    }
+
+    #[test]
+    fn test_slots() {
+        let slots = ConnectionSlots::new(32);
+        assert_eq!(slots.get_outbound(), P2P_MAX_OUTBOUND_SLOTS);
+        assert_eq!(slots.get_anchor(), P2P_ANCHOR_SLOTS);
+        assert_eq!(slots.get_inbound(), 32 - P2P_MAX_OUTBOUND_SLOTS);
+
+        let slots = ConnectionSlots::new(1);
+        assert_eq!(slots.get_outbound(), 1);
+        assert_eq!(slots.get_inbound(), 0);
+
+        let mut counts = HashMap::new();
+        counts.insert(ConnectionClass::Anchor, 1);
+        assert!(!slots.has_free_slot(ConnectionClass::Outbound, &counts));
+        assert!(slots.has_free_slot(ConnectionClass::Feeler, &counts));
+    }
+
+    #[test]
+    fn test_never_evict_outgoing() {
+        let candidates = (0..10)
+            .map(|i| candidate(i, ConnectionClass::Outbound, "1.1.1.1", i))
+            .collect();
+        assert_eq!(select_peer_to_evict(candidates), None);
+    }
+
+    #[test]
+    fn test_evict_largest_group() {
+        let mut candidates: Vec<_> = (0..P2P_EVICTION_PROTECTED_PEERS as u64)
+            .map(|i| candidate(i, ConnectionClass::Inbound, "1.1.1.1", i))
+            .collect();
+        candidates.push(candidate(100, ConnectionClass::Inbound, "2.2.1.1", 100));
+        candidates.push(candidate(101, ConnectionClass::Inbound, "2.2.2.2", 101));
+        candidates.push(candidate(102, ConnectionClass::Inbound, "3.3.3.3", 102));
+        candidates.push(candidate(103, ConnectionClass::Anchor, "2.2.3.3", 103));
+
+        assert_eq!(select_peer_to_evict(candidates), Some(101));
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+use xelis_common::{
+    api::daemon::TransactionBroadcastReceipt,
+    time::{get_current_time_in_millis, TimestampMillis}
+};
+
+// Maximum TXs for which the broadcast receipt is kept
+pub const MAX_TX_BROADCAST_RECEIPTS: usize = 4096;
+
+// Outcome of the broadcasts of a TX hash to our peers
+// It is used to detect a TX accepted locally but not propagating
+pub struct TxBroadcastReceipt {
+    first_announced_at: TimestampMillis,
+    last_announced_at: TimestampMillis,
+    announced_peers: usize,
+    skipped_by_policy: usize,
+    skipped_known: usize,
+    skipped_out_of_sync: usize,
+    send_errors: usize,
+    // peers ids which requested the TX
+    requested_by: HashSet<u64>,
+    first_requested_at: Option<TimestampMillis>
+}
+
+impl TxBroadcastReceipt {
+    pub fn new() -> Self {
+        let now = get_current_time_in_millis();
+        Self {
+            first_announced_at: now,
+            last_announced_at: now,
+            announced_peers: 0,
+            skipped_by_policy: 0,
+            skipped_known: 0,
+            skipped_out_of_sync: 0,
+            send_errors: 0,
+            requested_by: HashSet::new(),
+            first_requested_at: None
+        }
+    }
+
+    // A new broadcast of the same TX is starting
+    pub fn mark_announcing(&mut self) {
+        self.last_announced_at = get_current_time_in_millis();
+    }
+
+    pub fn add_announced(&mut self) {
+        self.announced_peers += 1;
+    }
+
+    pub fn add_skipped_by_policy(&mut self) {
+        self.skipped_by_policy += 1;
+    }
+
+    pub fn add_skipped_known(&mut self) {
+        self.skipped_known += 1;
+    }
+
+    pub fn add_skipped_out_of_sync(&mut self) {
+        self.skipped_out_of_sync += 1;
+    }
+
+    pub fn add_send_error(&mut self) {
+        self.send_errors += 1;
+    }
+
+    // A peer requested the TX, each peer is counted once
+    pub fn add_request(&mut self, peer_id: u64) {
+        if self.requested_by.insert(peer_id) && self.first_requested_at.is_none() {
+            self.first_requested_at = Some(get_current_time_in_millis());
+        }
+    }
+
+    pub fn to_api(&self, in_mempool: bool) -> TransactionBroadcastReceipt {
+        TransactionBroadcastReceipt {
+            first_announced_at: self.first_announced_at,
+            last_announced_at: self.last_announced_at,
+            announced_peers: self.announced_peers,
+            skipped_by_policy: self.skipped_by_policy,
+            skipped_known: self.skipped_known,
+            skipped_out_of_sync: self.skipped_out_of_sync,
+            send_errors: self.send_errors,
+            requested_by_peers: self.requested_by.len(),
+            first_requested_at: self.first_requested_at,
+            in_mempool
+        }
+    }
+}
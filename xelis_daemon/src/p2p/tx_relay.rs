@@ -0,0 +1,308 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant}
+};
+use indexmap::{IndexMap, IndexSet};
+use xelis_common::crypto::Hash;
+
+// Maximum TXs requested at the same time from a peer
+// Others announced by it wait until a slot is freed
+pub const PEER_MAX_TXS_IN_FLIGHT: usize = 128;
+// Maximum peers kept as announcers of the same TX
+pub const MAX_TX_ANNOUNCERS: usize = 8;
+// Maximum TXs announced but not yet received that are tracked
+pub const MAX_TX_ANNOUNCEMENTS: usize = 16384;
+// Time in millis before requesting the TX from another announcer
+pub const TX_IN_FLIGHT_TIMEOUT: u64 = 15_000;
+// Time in seconds before forgetting an announcement never received
+pub const TX_ANNOUNCEMENT_EXPIRATION: u64 = 5 * 60;
+// Interval in seconds between two checks of the announcements
+pub const TX_RELAY_CHECK_INTERVAL: u64 = 5;
+
+// TX announced by peers but not yet received
+struct Announcement {
+    // Peer from which it is currently requested and when
+    in_flight: Option<(u64, Instant)>,
+    // Peers which announced it and that we can still request
+    announcers: IndexSet<u64>,
+    // When it was announced the first time
+    first_seen: Instant
+}
+
+// Track the TXs hashes announced by our peers so each TX
+// is downloaded only once, from one peer at a time
+// If the request times out or the peer doesn't have it anymore,
+// it is requested from the next peer which announced it
+pub struct TxRelay {
+    announcements: IndexMap<Hash, Announcement>,
+    // Number of TXs requested per peer id
+    in_flight: HashMap<u64, usize>
+}
+
+impl TxRelay {
+    pub fn new() -> Self {
+        Self {
+            announcements: IndexMap::new(),
+            in_flight: HashMap::new()
+        }
+    }
+
+    fn in_flight_of(&self, peer_id: u64) -> usize {
+        self.in_flight.get(&peer_id).copied().unwrap_or(0)
+    }
+
+    fn release(&mut self, peer_id: u64) {
+        if let Some(count) = self.in_flight.get_mut(&peer_id) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.in_flight.remove(&peer_id);
+            }
+        }
+    }
+
+    // Register an announcement from a peer
+    // Returns true if the TX must be requested now from this peer
+    pub fn announce(&mut self, hash: &Hash, peer_id: u64) -> bool {
+        let can_request = self.in_flight_of(peer_id) < PEER_MAX_TXS_IN_FLIGHT;
+        let request = match self.announcements.get_mut(hash) {
+            Some(announcement) => {
+                if announcement.in_flight.is_some() || !can_request {
+                    if announcement.announcers.len() < MAX_TX_ANNOUNCERS {
+                        announcement.announcers.insert(peer_id);
+                    }
+                    return false
+                }
+
+                announcement.in_flight = Some((peer_id, Instant::now()));
+                true
+            },
+            None => {
+                if self.announcements.len() >= MAX_TX_ANNOUNCEMENTS {
+                    return false
+                }
+
+                let mut announcers = IndexSet::new();
+                let in_flight = if can_request {
+                    Some((peer_id, Instant::now()))
+                } else {
+                    announcers.insert(peer_id);
+                    None
+                };
+
+                self.announcements.insert(hash.clone(), Announcement {
+                    in_flight,
+                    announcers,
+                    first_seen: Instant::now()
+                });
+                can_request
+            }
+        };
+
+        if request {
+            *self.in_flight.entry(peer_id).or_insert(0) += 1;
+        }
+
+        request
+    }
+
+    // The TX has been received or is now known, forget it
+    pub fn remove(&mut self, hash: &Hash) {
+        if let Some(announcement) = self.announcements.swap_remove(hash) {
+            if let Some((peer_id, _)) = announcement.in_flight {
+                self.release(peer_id);
+            }
+        }
+    }
+
+    // The peer couldn't send us the TX, it will be requested from another announcer
+    pub fn failed(&mut self, hash: &Hash, peer_id: u64) {
+        let Some(announcement) = self.announcements.get_mut(hash) else {
+            return;
+        };
+
+        if announcement.in_flight.is_some_and(|(id, _)| id == peer_id) {
+            announcement.in_flight = None;
+            announcement.announcers.shift_remove(&peer_id);
+            self.release(peer_id);
+        }
+    }
+
+    // Forget the peers which are not connected anymore and release their requests
+    pub fn retain_peers<F: Fn(u64) -> bool>(&mut self, is_connected: F) {
+        for announcement in self.announcements.values_mut() {
+            announcement.announcers.retain(|id| is_connected(*id));
+            if announcement.in_flight.is_some_and(|(id, _)| !is_connected(id)) {
+                announcement.in_flight = None;
+            }
+        }
+        self.in_flight.retain(|id, _| is_connected(*id));
+    }
+
+    // Expire the timed out requests and select the next announcer for each TX not in flight
+    // Returns the TXs to request with the peer to request them from
+    // `is_available` is used to skip the peers that can't be requested for this TX
+    pub fn next_requests<F: FnMut(&Hash, u64) -> bool>(&mut self, mut is_available: F) -> Vec<(Hash, u64)> {
+        let timeout = Duration::from_millis(TX_IN_FLIGHT_TIMEOUT);
+        let expiration = Duration::from_secs(TX_ANNOUNCEMENT_EXPIRATION);
+
+        let in_flight = &mut self.in_flight;
+        let mut requests = Vec::new();
+        self.announcements.retain(|hash, announcement| {
+            if let Some((peer_id, requested_at)) = announcement.in_flight {
+                if requested_at.elapsed() < timeout {
+                    return true
+                }
+
+                // Timed out, don't ask this peer again
+                announcement.in_flight = None;
+                announcement.announcers.shift_remove(&peer_id);
+                if let Some(count) = in_flight.get_mut(&peer_id) {
+                    *count = count.saturating_sub(1);
+                }
+            }
+
+            if announcement.first_seen.elapsed() >= expiration {
+                return false
+            }
+
+            let next = announcement.announcers.iter()
+                .position(|id| in_flight.get(id).copied().unwrap_or(0) < PEER_MAX_TXS_IN_FLIGHT && is_available(hash, *id));
+
+            if let Some(peer_id) = next.and_then(|index| announcement.announcers.shift_remove_index(index)) {
+                announcement.in_flight = Some((peer_id, Instant::now()));
+                *in_flight.entry(peer_id).or_insert(0) += 1;
+                requests.push((hash.clone(), peer_id));
+            }
+
+            // Nobody can send it to us anymore
+            announcement.in_flight.is_some() || !announcement.announcers.is_empty()
+        });
+
+        self.in_flight.retain(|_, count| *count > 0);
+
+        requests
+    }
+
+    // Number of TXs announced but not yet received
+    pub fn len(&self) -> usize {
+        self.announcements.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Move back in time the current request of the TX
+    fn expire_request(relay: &mut TxRelay, hash: &Hash) {
+        let announcement = relay.announcements.get_mut(hash).unwrap();
+        let (peer_id, _) = announcement.in_flight.unwrap();
+        let requested_at = Instant::now() - Duration::from_millis(TX_IN_FLIGHT_TIMEOUT);
+        announcement.in_flight = Some((peer_id, requested_at));
+    }
+
+    #[test]
+    fn test_request_once() {
+        let mut relay = TxRelay::new();
+        let hash = Hash::new([1u8; 32]);
+
+        // Only the first announcer is requested
+        assert!(relay.announce(&hash, 1));
+        assert!(!relay.announce(&hash, 2));
+        assert!(!relay.announce(&hash, 3));
+        assert_eq!(relay.len(), 1);
+        assert_eq!(relay.in_flight_of(1), 1);
+        assert_eq!(relay.in_flight_of(2), 0);
+
+        // Still in flight, nothing to request again
+        assert!(relay.next_requests(|_, _| true).is_empty());
+
+        relay.remove(&hash);
+        assert_eq!(relay.len(), 0);
+        assert_eq!(relay.in_flight_of(1), 0);
+    }
+
+    #[test]
+    fn test_peer_in_flight_limit() {
+        let mut relay = TxRelay::new();
+        for i in 0..PEER_MAX_TXS_IN_FLIGHT {
+            assert!(relay.announce(&Hash::new([i as u8; 32]), 1));
+        }
+
+        // Tracked but not requested until a slot is freed
+        let hash = Hash::new([0xFF; 32]);
+        assert!(!relay.announce(&hash, 1));
+        assert!(relay.next_requests(|_, _| true).is_empty());
+
+        relay.remove(&Hash::new([0u8; 32]));
+        assert_eq!(relay.next_requests(|_, _| true), vec![(hash, 1)]);
+    }
+
+    #[test]
+    fn test_retry_on_failure() {
+        let mut relay = TxRelay::new();
+        let hash = Hash::new([1u8; 32]);
+        assert!(relay.announce(&hash, 1));
+        assert!(!relay.announce(&hash, 2));
+
+        // Failure from a peer not requested is ignored
+        relay.failed(&hash, 2);
+        assert!(relay.next_requests(|_, _| true).is_empty());
+
+        relay.failed(&hash, 1);
+        assert_eq!(relay.in_flight_of(1), 0);
+        assert_eq!(relay.next_requests(|_, _| true), vec![(hash.clone(), 2)]);
+
+        // No announcer left, the TX is forgotten
+        relay.failed(&hash, 2);
+        assert!(relay.next_requests(|_, _| true).is_empty());
+        assert_eq!(relay.len(), 0);
+    }
+
+    #[test]
+    fn test_retry_on_timeout() {
+        let mut relay = TxRelay::new();
+        let hash = Hash::new([1u8; 32]);
+        assert!(relay.announce(&hash, 1));
+        assert!(!relay.announce(&hash, 2));
+        assert!(!relay.announce(&hash, 3));
+
+        // Unavailable peers are skipped
+        expire_request(&mut relay, &hash);
+        assert_eq!(relay.next_requests(|_, id| id != 2), vec![(hash.clone(), 3)]);
+        assert_eq!(relay.in_flight_of(1), 0);
+        assert_eq!(relay.in_flight_of(3), 1);
+
+        // The timed out peer is not requested again
+        expire_request(&mut relay, &hash);
+        assert_eq!(relay.next_requests(|_, _| true), vec![(hash.clone(), 2)]);
+    }
+
+    #[test]
+    fn test_announcement_expiration() {
+        let mut relay = TxRelay::new();
+        let hash = Hash::new([1u8; 32]);
+        assert!(relay.announce(&hash, 1));
+        assert!(!relay.announce(&hash, 2));
+
+        let announcement = relay.announcements.get_mut(&hash).unwrap();
+        announcement.first_seen = Instant::now() - Duration::from_secs(TX_ANNOUNCEMENT_EXPIRATION);
+        expire_request(&mut relay, &hash);
+
+        assert!(relay.next_requests(|_, _| true).is_empty());
+        assert_eq!(relay.len(), 0);
+        assert_eq!(relay.in_flight_of(1), 0);
+    }
+
+    #[test]
+    fn test_retain_peers() {
+        let mut relay = TxRelay::new();
+        let hash = Hash::new([1u8; 32]);
+        assert!(relay.announce(&hash, 1));
+        assert!(!relay.announce(&hash, 2));
+
+        relay.retain_peers(|id| id != 1);
+        assert_eq!(relay.in_flight_of(1), 0);
+        assert_eq!(relay.next_requests(|_, _| true), vec![(hash, 2)]);
+    }
+}
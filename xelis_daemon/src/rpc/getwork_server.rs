@@ -39,6 +39,8 @@ use xelis_common::{
     config::TIPS_LIMIT,
     api::daemon::{
         GetMinerWorkResult,
+        GetWorkMinerEntry,
+        NotifyEvent,
         SubmitMinerWorkParams
     },
     block::{
@@ -50,7 +52,7 @@ use xelis_common::{
         Hashable,
         PublicKey
     },
-    difficulty::Difficulty,
+    difficulty::{check_difficulty, Difficulty},
     immutable::Immutable,
     rpc_server::{
         InternalRpcError,
@@ -77,12 +79,29 @@ use crate::{
 
 pub type SharedGetWorkServer<S> = Arc<GetWorkServer<S>>;
 
+// Shares accepted before adjusting the share difficulty of a miner
+const RETARGET_SHARES: u64 = 8;
+// Shares remembered per miner to detect duplicates
+const MAX_SUBMITTED_SHARES: usize = 1024;
+
+pub struct GetWorkConfig {
+    // Minimum delay between two jobs sent to a miner on mempool changes
+    pub rate_limit_ms: u64,
+    // If set, shares below the block difficulty are accepted
+    // It is the initial and minimum share difficulty of each miner
+    pub min_share_difficulty: Option<Difficulty>,
+    // Expected time between two shares of a miner in seconds
+    pub share_target_time: u64
+}
+
 #[derive(Serialize, PartialEq)]
 #[serde(rename_all = "snake_case")] 
 pub enum Response {
     NewJob(GetMinerWorkResult),
     BlockAccepted,
-    BlockRejected(String)
+    BlockRejected(String),
+    // Only sent if shares are enabled
+    ShareAccepted
 }
 
 impl TMessage for Response {
@@ -102,23 +121,99 @@ pub struct Miner {
     blocks_accepted: IndexSet<Hash>,
     // blocks rejected since he is connected
     blocks_rejected: usize,
+    // blocks submitted for a height already found
+    blocks_stale: usize,
     // timestamp of the last invalid block received
-    last_invalid_block: TimestampMillis
+    last_invalid_block: TimestampMillis,
+    // timestamp of the last block or share received
+    last_activity: TimestampMillis,
+    // timestamp of the last job sent
+    last_job: TimestampMillis,
+    // difficulty of the shares, None if only blocks are accepted
+    share_difficulty: Option<Difficulty>,
+    // difficulty before the last retarget, still accepted for the jobs in flight
+    previous_share_difficulty: Option<Difficulty>,
+    // shares accepted since the last retarget
+    shares_since_retarget: u64,
+    // timestamp of the last retarget
+    retarget_at: TimestampMillis,
+    shares_accepted: usize,
+    // last shares submitted to detect duplicates
+    submitted_shares: IndexSet<Hash>
 }
 
 impl Miner {
-    pub fn new(mainnet: bool, key: PublicKey, name: String) -> Self {
+    pub fn new(mainnet: bool, key: PublicKey, name: String, share_difficulty: Option<Difficulty>) -> Self {
+        let now = get_current_time_in_millis();
         Self {
             mainnet,
-            first_seen: get_current_time_in_millis(),
+            first_seen: now,
             key,
             name,
             blocks_accepted: IndexSet::new(),
             blocks_rejected: 0,
-            last_invalid_block: 0
+            blocks_stale: 0,
+            last_invalid_block: 0,
+            last_activity: now,
+            last_job: 0,
+            share_difficulty,
+            previous_share_difficulty: None,
+            shares_since_retarget: 0,
+            retarget_at: now,
+            shares_accepted: 0,
+            submitted_shares: IndexSet::new()
+        }
+    }
+
+    // Difficulty of the jobs sent to this miner
+    fn get_job_difficulty(&self, block_difficulty: Difficulty) -> Difficulty {
+        match self.share_difficulty {
+            Some(difficulty) => difficulty.min(block_difficulty),
+            None => block_difficulty
+        }
+    }
+
+    // Lowest difficulty accepted for a share of a job
+    fn get_accepted_difficulty(&self, block_difficulty: Difficulty) -> Difficulty {
+        let difficulty = self.get_job_difficulty(block_difficulty);
+        match self.previous_share_difficulty {
+            Some(previous) => difficulty.min(previous),
+            None => difficulty
         }
     }
 
+    // Adjust the share difficulty so the miner finds one share every target time
+    // based on its hashrate observed since the last retarget
+    // A miner without any share for a long time has its difficulty lowered
+    // Returns true if the difficulty changed
+    fn retarget(&mut self, config: &GetWorkConfig, now: TimestampMillis) -> bool {
+        let (Some(current), Some(min)) = (self.share_difficulty, config.min_share_difficulty) else {
+            return false;
+        };
+
+        let target = config.share_target_time.max(1) * 1000;
+        let elapsed = now.saturating_sub(self.retarget_at);
+        if self.shares_since_retarget < RETARGET_SHARES && elapsed < target * RETARGET_SHARES {
+            return false;
+        }
+
+        // hashrate = difficulty * shares / elapsed
+        let expected = (current * (self.shares_since_retarget * target)) / elapsed.max(1);
+        // limit the change to not oscillate
+        let difficulty = expected.max(current / 4).min(current * 4).max(min);
+
+        self.shares_since_retarget = 0;
+        self.retarget_at = now;
+        if difficulty == current {
+            return false;
+        }
+
+        debug!("Share difficulty of miner {} adjusted from {} to {}", self.name, current, difficulty);
+        self.previous_share_difficulty = Some(current);
+        self.share_difficulty = Some(difficulty);
+        true
+    }
+
     pub fn first_seen(&self) -> u64 {
         self.first_seen
     }
@@ -134,12 +229,54 @@ impl Miner {
     pub fn get_blocks_accepted(&self) -> usize {
         self.blocks_accepted.len()
     }
+
+    pub fn get_shares_accepted(&self) -> usize {
+        self.shares_accepted
+    }
+
+    pub fn get_share_difficulty(&self) -> Option<&Difficulty> {
+        self.share_difficulty.as_ref()
+    }
+
+    pub fn get_blocks_stale(&self) -> usize {
+        self.blocks_stale
+    }
+
+    // Stale blocks over all the blocks submitted
+    pub fn get_stale_rate(&self) -> f64 {
+        let submitted = self.blocks_accepted.len() + self.blocks_rejected;
+        if submitted == 0 {
+            return 0.0;
+        }
+
+        self.blocks_stale as f64 / submitted as f64
+    }
+
+    pub fn to_entry(&self) -> GetWorkMinerEntry<'_> {
+        GetWorkMinerEntry {
+            address: self.key.as_address(self.mainnet),
+            name: Cow::Borrowed(&self.name),
+            connected_since: self.first_seen,
+            last_activity: self.last_activity,
+            last_job: self.last_job,
+            blocks_accepted: self.blocks_accepted.len(),
+            blocks_rejected: self.blocks_rejected,
+            blocks_stale: self.blocks_stale,
+            stale_rate: self.get_stale_rate(),
+            shares_accepted: self.shares_accepted,
+            share_difficulty: self.share_difficulty
+        }
+    }
 }
 
 impl Display for Miner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let valid_blocks = self.blocks_accepted.iter().map(|h| h.to_string()).collect::<Vec<_>>().join(",");
-        write!(f, "Miner[address={}, name={}, accepted={} ({}), rejected={}]", self.key.as_address(self.mainnet), self.name, self.blocks_accepted.len(), valid_blocks, self.blocks_rejected)
+        write!(f, "Miner[address={}, name={}, accepted={} ({}), rejected={}, stale={}", self.key.as_address(self.mainnet), self.name, self.blocks_accepted.len(), valid_blocks, self.blocks_rejected, self.blocks_stale)?;
+        if let Some(difficulty) = self.share_difficulty.as_ref() {
+            write!(f, ", shares={}, share difficulty={}", self.shares_accepted, difficulty)?;
+        }
+        write!(f, "]")
     }
 }
 
@@ -218,18 +355,18 @@ pub struct GetWorkServer<S: Storage> {
     last_header_hash: Mutex<Option<Hash>>,
     // used only when a new TX is received in mempool
     last_notify: AtomicU64,
-    notify_rate_limit_ms: u64
+    config: GetWorkConfig
 }
 
 impl<S: Storage> GetWorkServer<S> {
-    pub fn new(blockchain: Arc<Blockchain<S>>) -> Self {
+    pub fn new(blockchain: Arc<Blockchain<S>>, config: GetWorkConfig) -> Self {
         Self {
             miners: Mutex::new(HashMap::new()),
             blockchain,
             mining_jobs: Mutex::new(LruCache::new(NonZeroUsize::new(STABLE_LIMIT as usize * TIPS_LIMIT).unwrap())),
             last_header_hash: Mutex::new(None),
             last_notify: AtomicU64::new(0),
-            notify_rate_limit_ms: 500 // maximum one time every 500ms
+            config
         }
     }
 
@@ -287,6 +424,18 @@ impl<S: Storage> GetWorkServer<S> {
         job.set_miner(Cow::Owned(key));
         OsRng.fill_bytes(job.get_extra_nonce());
 
+        // use the share difficulty of the miner
+        let difficulty = {
+            let mut miners = self.miners.lock().await;
+            match miners.get_mut(&addr) {
+                Some(miner) => {
+                    miner.last_job = get_current_time_in_millis();
+                    miner.get_job_difficulty(difficulty)
+                },
+                None => difficulty
+            }
+        };
+
         // get the algorithm for the current version
         let algorithm = get_pow_algorithm_for_version(version);
         let topoheight = self.blockchain.get_topo_height();
@@ -295,12 +444,29 @@ impl<S: Storage> GetWorkServer<S> {
         Ok(())
     }
 
+    // Notify the RPC clients that a miner has connected or disconnected
+    async fn notify_miner_event(&self, event: NotifyEvent, miner: &Miner) {
+        let rpc = self.blockchain.get_rpc().read().await;
+        if let Some(rpc) = rpc.as_ref() {
+            if rpc.is_event_tracked(&event).await {
+                let value = json!(miner.to_entry());
+                let rpc = rpc.clone();
+                spawn_task("rpc-notify-getwork-miner", async move {
+                    if let Err(e) = rpc.notify_clients(&event, value).await {
+                        debug!("Error while broadcasting event {:?} to websocket: {}", event, e);
+                    }
+                });
+            }
+        }
+    }
+
     pub async fn add_miner(self: &Arc<Self>, addr: Addr<GetWorkWebSocketHandler<S>>, key: PublicKey, worker: String) {
         trace!("add miner");
         {
             let mut miners = self.miners.lock().await;
-            let miner = Miner::new(self.blockchain.get_network().is_mainnet(), key.clone(), worker);
+            let miner = Miner::new(self.blockchain.get_network().is_mainnet(), key.clone(), worker, self.config.min_share_difficulty);
             debug!("Adding new miner to GetWork server: {}", miner);
+            self.notify_miner_event(NotifyEvent::GetWorkMinerConnected, &miner).await;
             miners.insert(addr.clone(), miner);
         }
 
@@ -318,6 +484,7 @@ impl<S: Storage> GetWorkServer<S> {
         let mut miners = self.miners.lock().await;
         if let Some(miner) = miners.remove(addr) {
             debug!("{} deleted", miner);
+            self.notify_miner_event(NotifyEvent::GetWorkMinerDisconnected, &miner).await;
         }
     }
 
@@ -325,26 +492,63 @@ impl<S: Storage> GetWorkServer<S> {
     // we retrieve the block header saved in cache using the mining job "header_work_hash"
     // its used to check that the job come from our server
     // when it's found, we merge the miner job inside the block header
-    async fn accept_miner_job(&self, job: MinerWork<'_>) -> Result<(Response, Hash), InternalRpcError> {
+    // A block is stale if a block at its height was already added to the chain
+    async fn accept_miner_job(&self, addr: &Addr<GetWorkWebSocketHandler<S>>, job: MinerWork<'_>, stale: &mut bool) -> Result<(Response, Hash), InternalRpcError> {
         trace!("accept miner job");
         if job.get_miner().is_none() {
             return Err(InternalRpcError::InvalidJSONRequest);
         }
 
         let mut miner_header;
+        let difficulty;
         {
             let mining_jobs = self.mining_jobs.lock().await;
-            if let Some((header, _)) = mining_jobs.peek(job.get_header_work_hash()) {
+            if let Some((header, diff)) = mining_jobs.peek(job.get_header_work_hash()) {
                 // job is found in cache, clone it and put miner data inside
                 miner_header = header.clone();
                 miner_header.apply_miner_work(job);
+                difficulty = *diff;
             } else {
                 // really old job, or miner send invalid job
                 debug!("Job {} was not found in cache", job.get_header_work_hash());
+                *stale = true;
                 return Err(InternalRpcError::InvalidParams("Job was not found in cache"))
             };
         }
 
+        // Check if it's only a share before building the block
+        if self.config.min_share_difficulty.is_some() {
+            let share_difficulty = {
+                let mut miners = self.miners.lock().await;
+                let miner = miners.get_mut(addr).ok_or(InternalRpcError::InvalidRequestStr("Miner not found"))?;
+                if !miner.submitted_shares.insert(miner_header.hash()) {
+                    return Err(InternalRpcError::InvalidParams("Share was already submitted"))
+                }
+
+                if miner.submitted_shares.len() > MAX_SUBMITTED_SHARES {
+                    miner.submitted_shares.shift_remove_index(0);
+                }
+
+                miner.get_accepted_difficulty(difficulty)
+            };
+
+            // POW hash is expensive, don't block the runtime
+            let algorithm = get_pow_algorithm_for_version(miner_header.get_version());
+            let header = miner_header.clone();
+            let pow_hash = tokio::task::spawn_blocking(move || header.get_pow_hash(algorithm)).await
+                .context("Error while waiting on POW hash")?
+                .context("Error while computing POW hash")?;
+
+            if !check_difficulty(&pow_hash, &difficulty).context("Error while checking block difficulty")? {
+                if !check_difficulty(&pow_hash, &share_difficulty).context("Error while checking share difficulty")? {
+                    return Err(InternalRpcError::InvalidParams("Share difficulty is too low"))
+                }
+
+                return Ok((Response::ShareAccepted, miner_header.hash()))
+            }
+        }
+
+        *stale = miner_header.get_height() <= self.blockchain.get_height();
         let block = self.blockchain.build_block_from_header(Immutable::Owned(miner_header)).await.context("Error while building block from header")?;
         let block_hash = block.hash();
         Ok(match self.blockchain.add_new_block(block, true, true).await {
@@ -361,8 +565,9 @@ impl<S: Storage> GetWorkServer<S> {
     // if its block is rejected, resend him the job
     pub async fn handle_block_for(self: Arc<Self>, addr: Addr<GetWorkWebSocketHandler<S>>, submitted_work: SubmitMinerWorkParams) {
         trace!("handle block for");
+        let mut stale = false;
         let (response, hash) = match MinerWork::from_hex(&submitted_work.miner_work) {
-            Ok(job) => match self.accept_miner_job(job).await {
+            Ok(job) => match self.accept_miner_job(&addr, job, &mut stale).await {
                 Ok((response, hash)) => (response, Some(hash)),
                 Err(e) => {
                     debug!("Error while accepting miner job: {}", e);
@@ -376,14 +581,29 @@ impl<S: Storage> GetWorkServer<S> {
         };
 
         // update miner stats
+        let mut retarget = false;
         {
             let mut miners = self.miners.lock().await;
             if let Some(miner) = miners.get_mut(&addr) {
+                miner.last_activity = get_current_time_in_millis();
+                if stale {
+                    debug!("Miner {} sent a stale block", miner);
+                    miner.blocks_stale += 1;
+                }
+
                 match &response {
-                    Response::BlockAccepted => {
+                    Response::BlockAccepted | Response::ShareAccepted => {
                         let hash = hash.unwrap();
-                        debug!("Miner {} found block {}!", miner, hash);
-                        miner.blocks_accepted.insert(hash);
+                        if response == Response::BlockAccepted {
+                            debug!("Miner {} found block {}!", miner, hash);
+                            miner.blocks_accepted.insert(hash);
+                        }
+
+                        if miner.share_difficulty.is_some() {
+                            miner.shares_accepted += 1;
+                            miner.shares_since_retarget += 1;
+                            retarget = miner.retarget(&self.config, get_current_time_in_millis());
+                        }
                     },
                     Response::BlockRejected(_) => {
                         debug!("Miner {} sent an invalid block", miner);
@@ -396,9 +616,10 @@ impl<S: Storage> GetWorkServer<S> {
         }
 
         spawn_task("getwork-reply", async move {
+            // a new job is also sent to apply the new share difficulty
             let resend_job = match response {
                 Response::BlockRejected(_) => true,
-                _ => false
+                _ => retarget
             };
             debug!("Sending response to the miner");
             if let Err(e) = addr.send(response).await {
@@ -432,7 +653,7 @@ impl<S: Storage> GetWorkServer<S> {
     fn is_rate_limited(&self) -> (bool, TimestampMillis) {
         let now = get_current_time_in_millis();
         let last_notify = self.last_notify.load(Ordering::SeqCst);
-        (now - last_notify < self.notify_rate_limit_ms, now)
+        (now - last_notify < self.config.rate_limit_ms, now)
     }
 
     // notify every miners connected to the getwork server
@@ -445,12 +666,19 @@ impl<S: Storage> GetWorkServer<S> {
         }
         self.last_notify.store(now, Ordering::SeqCst);
 
-        self.notify_new_job().await
+        self.notify_miners(true).await
     }
 
     // notify every miners connected to the getwork server
     // each miner have his own task so nobody wait on other
     pub async fn notify_new_job(&self) -> Result<(), InternalRpcError> {
+        self.notify_miners(false).await
+    }
+
+    // Build a new job and send it to the miners
+    // On mempool changes, the job is not sent if the template didn't change,
+    // and miners that received a job recently are skipped
+    async fn notify_miners(&self, rate_limited: bool) -> Result<(), InternalRpcError> {
         trace!("notify new job");
         // Check that there is at least one miner connected
         // otherwise, no need to build a new job
@@ -478,6 +706,10 @@ impl<S: Storage> GetWorkServer<S> {
         {
             let header_work_hash = job.get_header_work_hash();
             let mut last_header_hash = self.last_header_hash.lock().await;
+            if rate_limited && last_header_hash.as_ref() == Some(header_work_hash) {
+                debug!("Block template didn't change, no need to notify miners");
+                return Ok(());
+            }
             *last_header_hash = Some(header_work_hash.clone());
             let mut mining_jobs = self.mining_jobs.lock().await;
             mining_jobs.put(header_work_hash.clone(), (header, difficulty));
@@ -493,13 +725,21 @@ impl<S: Storage> GetWorkServer<S> {
         // This is for visual purposes only
         let topoheight = self.blockchain.get_topo_height();
 
-        for (addr, miner) in miners.iter() {
+        let now = get_current_time_in_millis();
+        for (addr, miner) in miners.iter_mut() {
+            if rate_limited && now.saturating_sub(miner.last_job) < self.config.rate_limit_ms {
+                debug!("{} received a job recently, skipping it", miner);
+                continue;
+            }
+
             debug!("Notifying {} for new job", miner);
             let addr = addr.clone();
+            miner.last_job = now;
 
             job.set_miner(Cow::Borrowed(miner.get_public_key()));
             OsRng.fill_bytes(job.get_extra_nonce());
             let template = job.to_hex();
+            let difficulty = miner.get_job_difficulty(difficulty);
 
             // New task for each miner in case a miner is slow
             // we don't want to wait for him
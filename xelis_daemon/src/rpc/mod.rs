@@ -1,13 +1,16 @@
 pub mod rpc;
 pub mod getwork_server;
+pub mod stratum_server;
+pub mod publisher;
 
 use crate::{
+    config::RPC_STREAM_TICK_INTERVAL_MILLIS,
     core::{
         storage::Storage,
         error::BlockchainError,
         blockchain::Blockchain
     },
-    rpc::getwork_server::GetWorkServer,
+    rpc::getwork_server::{GetWorkConfig, GetWorkServer},
 };
 use actix_web::{
     get,
@@ -20,16 +23,21 @@ use actix_web::{
         self,
         Path,
         Data,
-        Payload
+        Payload,
+        Query
     },
     dev::ServerHandle,
     error::Error
 };
 use actix_web_actors::ws::WsResponseBuilder;
+use anyhow::Context as AnyContext;
+use bytes::Bytes;
+use futures::stream;
 use serde_json::{Value, json};
 use tokio::sync::Mutex;
 use xelis_common::{
-    api::daemon::NotifyEvent,
+    api::daemon::{NotifyEvent, StreamBlocksRangeParams},
+    block::TopoHeight,
     config,
     crypto::Address,
     rpc_server::{
@@ -38,6 +46,7 @@ use xelis_common::{
         websocket::{
             EventWebSocketHandler,
             WebSocketServer,
+            WebSocketServerConfig,
             WebSocketServerShared
         },
         InternalRpcError,
@@ -50,6 +59,7 @@ use xelis_common::{
 use std::{
     collections::HashSet,
     sync::Arc,
+    time::Duration,
 };
 use log::{
     trace,
@@ -58,9 +68,12 @@ use log::{
     warn,
     error,
 };
-use self::getwork_server::{
-    GetWorkWebSocketHandler,
-    SharedGetWorkServer
+use self::{
+    getwork_server::{
+        GetWorkWebSocketHandler,
+        SharedGetWorkServer
+    },
+    stratum_server::SharedStratumServer
 };
 
 pub type SharedDaemonRpcServer<S> = Arc<DaemonRpcServer<S>>;
@@ -68,7 +81,12 @@ pub type SharedDaemonRpcServer<S> = Arc<DaemonRpcServer<S>>;
 pub struct DaemonRpcServer<S: Storage> {
     handle: Mutex<Option<ServerHandle>>,
     websocket: WebSocketServerShared<EventWebSocketHandler<Arc<Blockchain<S>>, NotifyEvent>>,
-    getwork: Option<SharedGetWorkServer<S>>
+    getwork: Option<SharedGetWorkServer<S>>,
+    stratum: Option<SharedStratumServer<S>>,
+    // Maximum blocks behind the network to be reported as ready
+    ready_max_blocks_behind: u64,
+    // Maximum bytes of blocks serialized per tick when streaming a range
+    stream_budget: usize
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -80,29 +98,37 @@ pub enum ApiError {
     #[error("P2p engine is not running")]
     NoP2p,
     #[error("WebSocket server is not started")]
-    NoWebSocketServer
+    NoWebSocketServer,
+    #[error("GetWork server is not started")]
+    NoGetWorkServer,
+    #[error("TX was not broadcasted by this node")]
+    NoTxBroadcastReceipt
 }
 
 impl<S: Storage> DaemonRpcServer<S> {
-    pub async fn new(bind_address: String, blockchain: Arc<Blockchain<S>>, disable_getwork_server: bool, threads: Option<usize>) -> Result<SharedDaemonRpcServer<S>, BlockchainError> {
-        let getwork: Option<SharedGetWorkServer<S>> = if !disable_getwork_server {
+    pub async fn new(bind_address: String, blockchain: Arc<Blockchain<S>>, getwork_config: Option<GetWorkConfig>, allow_log_methods: bool, allow_admin_methods: bool, threads: Option<usize>, ready_max_blocks_behind: u64, stream_budget: usize, ws_config: WebSocketServerConfig, stratum: Option<SharedStratumServer<S>>) -> Result<SharedDaemonRpcServer<S>, BlockchainError> {
+        let enable_getwork_server = getwork_config.is_some();
+        let getwork: Option<SharedGetWorkServer<S>> = if let Some(config) = getwork_config {
             info!("Creating GetWork server...");
-            Some(Arc::new(GetWorkServer::new(blockchain.clone())))
+            Some(Arc::new(GetWorkServer::new(blockchain.clone(), config)))
         } else {
             None
         };
 
         // create the RPC Handler which will register and contains all available methods
         let mut rpc_handler = RPCHandler::new(blockchain);
-        rpc::register_methods(&mut rpc_handler, !disable_getwork_server);
+        rpc::register_methods(&mut rpc_handler, enable_getwork_server, allow_log_methods, allow_admin_methods);
 
         // create the default websocket server (support event & rpc methods)
-        let ws = WebSocketServer::new(EventWebSocketHandler::new(rpc_handler));
+        let ws = WebSocketServer::with_config(EventWebSocketHandler::new(rpc_handler), ws_config);
 
         let server = Arc::new(Self {
             handle: Mutex::new(None),
             websocket: ws,
             getwork,
+            stratum,
+            ready_max_blocks_behind,
+            stream_budget
         });
 
         {
@@ -115,11 +141,20 @@ impl<S: Storage> DaemonRpcServer<S> {
                     // WebSocket support
                     .route("/json_rpc", web::get().to(websocket::<EventWebSocketHandler<Arc<Blockchain<S>>, NotifyEvent>, DaemonRpcServer<S>>))
                     .route("/getwork/{address}/{worker}", web::get().to(getwork_endpoint::<S>))
+                    // Health checks for load balancers
+                    .route("/health", web::get().to(health_endpoint))
+                    .route("/ready", web::get().to(ready_endpoint::<S>))
+                    // Streaming of blocks for explorers backfilling the history
+                    .route("/blocks_range", web::get().to(blocks_range_endpoint::<S>))
                     .service(index)
             })
             .disable_signals()
             .bind(&bind_address)?;
 
+            if stream_budget == 0 {
+                return Err(anyhow::anyhow!("The stream budget must be greater than 0").into());
+            }
+
             // set the number of threads if provided
             if let Some(threads) = threads {
                 if threads == 0 {
@@ -162,6 +197,10 @@ impl<S: Storage> DaemonRpcServer<S> {
     }
 
     pub async fn stop(&self) {
+        if let Some(stratum) = self.stratum.as_ref() {
+            stratum.stop().await;
+        }
+
         info!("Stopping RPC Server...");
         let mut handle = self.handle.lock().await;
         if let Some(handle) = handle.take() {
@@ -175,6 +214,14 @@ impl<S: Storage> DaemonRpcServer<S> {
     pub fn getwork_server(&self) -> &Option<SharedGetWorkServer<S>> {
         &self.getwork
     }
+
+    pub fn stratum_server(&self) -> &Option<SharedStratumServer<S>> {
+        &self.stratum
+    }
+
+    pub fn get_ready_max_blocks_behind(&self) -> u64 {
+        self.ready_max_blocks_behind
+    }
 }
 
 impl<S: Storage> WebSocketServerHandler<EventWebSocketHandler<Arc<Blockchain<S>>, NotifyEvent>> for DaemonRpcServer<S> {
@@ -195,6 +242,124 @@ async fn index() -> impl Responder {
     HttpResponse::Ok().body(format!("Hello, world!\nRunning on: {}", config::VERSION))
 }
 
+// Process is alive and able to answer HTTP requests
+async fn health_endpoint() -> impl Responder {
+    HttpResponse::Ok().body("OK")
+}
+
+// Node is synced, storage is writable and P2P is connected
+// Returns the node status with a 503 status code if not ready
+async fn ready_endpoint<S: Storage>(server: Data<DaemonRpcServer<S>>) -> Result<HttpResponse, Error> {
+    let blockchain = server.get_rpc_handler().get_data();
+    match rpc::get_node_status(blockchain, server.ready_max_blocks_behind).await {
+        Ok(status) => if status.ready {
+            Ok(HttpResponse::Ok().json(status))
+        } else {
+            Ok(HttpResponse::ServiceUnavailable().json(status))
+        },
+        Err(e) => {
+            debug!("Error while building node status: {}", e);
+            Ok(HttpResponse::ServiceUnavailable().body("Error while building node status"))
+        }
+    }
+}
+
+// State of a blocks range being streamed
+struct BlocksRangeStream<S: Storage> {
+    blockchain: Arc<Blockchain<S>>,
+    // next topoheight to send
+    next: TopoHeight,
+    end: TopoHeight,
+    include_txs: bool,
+    budget: usize,
+    // first chunk is sent without waiting
+    first: bool
+}
+
+impl<S: Storage> BlocksRangeStream<S> {
+    // Serialize the next blocks until the budget is reached
+    // Storage is only locked during a tick so the chain can progress between two chunks
+    async fn next_chunk(&mut self) -> Result<Vec<u8>, InternalRpcError> {
+        let storage = self.blockchain.get_storage().read().await;
+        let mut chunk = Vec::with_capacity(self.budget);
+        while self.next <= self.end && chunk.len() < self.budget {
+            let hash = storage.get_hash_at_topo_height(self.next).await.context("Error while retrieving hash at topo height")?;
+            let block = rpc::get_block_response_for_hash(&self.blockchain, &storage, &hash, self.include_txs).await?;
+
+            serde_json::to_writer(&mut chunk, &block).context("Error while serializing block")?;
+            chunk.push(b'\n');
+            self.next += 1;
+        }
+
+        Ok(chunk)
+    }
+}
+
+// Stream the blocks of a topoheight range as newline-delimited JSON
+// Blocks are serialized incrementally with a budget per tick,
+// so a range of any size can be requested without being buffered
+async fn blocks_range_endpoint<S: Storage>(server: Data<DaemonRpcServer<S>>, params: Query<StreamBlocksRangeParams>) -> Result<HttpResponse, Error> {
+    let blockchain = Arc::clone(server.get_rpc_handler().get_data());
+    let params = params.into_inner();
+
+    let current_topoheight = blockchain.get_topo_height();
+    let pruned_topoheight = {
+        let storage = blockchain.get_storage().read().await;
+        match storage.get_pruned_topoheight().await {
+            Ok(pruned_topoheight) => pruned_topoheight,
+            Err(e) => {
+                debug!("Error while retrieving pruned topoheight: {}", e);
+                return Ok(HttpResponse::InternalServerError().body("Error while retrieving pruned topoheight"))
+            }
+        }
+    };
+
+    let start = params.start_topoheight.or(pruned_topoheight).unwrap_or(0);
+    let end = params.end_topoheight.unwrap_or(current_topoheight);
+    if end < start || end > current_topoheight {
+        return Ok(HttpResponse::BadRequest().body(format!("Invalid range requested, start: {}, end: {}", start, end)))
+    }
+
+    if let Some(pruned_topoheight) = pruned_topoheight.filter(|pruned| start < *pruned) {
+        return Ok(HttpResponse::BadRequest().body(format!("Blocks below topoheight {} are pruned", pruned_topoheight)))
+    }
+
+    let state = BlocksRangeStream {
+        blockchain,
+        next: start,
+        end,
+        include_txs: params.include_txs,
+        budget: server.stream_budget,
+        first: true
+    };
+
+    let body = stream::unfold(Some(state), |state| async move {
+        let mut state = state?;
+        if state.next > state.end {
+            return None
+        }
+
+        if !state.first {
+            tokio::time::sleep(Duration::from_millis(RPC_STREAM_TICK_INTERVAL_MILLIS)).await;
+        }
+        state.first = false;
+
+        match state.next_chunk().await {
+            Ok(chunk) => Some((Ok::<_, Error>(Bytes::from(chunk)), Some(state))),
+            Err(e) => {
+                // Headers are already sent, report the error as the last line
+                debug!("Error while streaming block at topoheight {}: {}", state.next, e);
+                let line = format!("{}\n", json!({ "error": e.to_string(), "topoheight": state.next }));
+                Some((Ok(Bytes::from(line)), None))
+            }
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(body))
+}
+
 async fn getwork_endpoint<S: Storage>(server: Data<DaemonRpcServer<S>>, request: HttpRequest, stream: Payload, path: Path<(String, String)>) -> Result<HttpResponse, Error> {
     match &server.getwork {
         Some(getwork) => {
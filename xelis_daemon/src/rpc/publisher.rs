@@ -0,0 +1,229 @@
+use std::sync::Arc;
+use anyhow::Context;
+use bytes::Bytes;
+use log::{debug, error, info, trace, warn};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpListener,
+    select,
+    sync::{
+        broadcast::{self, error::RecvError},
+        Mutex
+    },
+    task::JoinHandle
+};
+use xelis_common::{
+    block::{BlockHeader, TopoHeight},
+    crypto::Hash,
+    immutable::Immutable,
+    serializer::{Serializer, Writer},
+    tokio::spawn_task,
+    transaction::Transaction
+};
+
+// Prefix of the bind address to listen on a Unix socket
+const UNIX_SOCKET_PREFIX: &str = "unix:";
+
+// Raw notifications publisher
+// It lets high frequency consumers receive the serialized data
+// without going through the JSON-RPC and WebSocket layers
+//
+// Each notification is sent as a frame:
+// [topic: u8][payload length: u32 big endian][payload]
+//
+// A subscriber can send at any time one byte being the bitmask
+// of the topics it wants to receive (bit N for the topic N)
+// All topics are sent by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PublisherTopic {
+    // block hash followed by the serialized block (header and transactions)
+    NewBlock = 0,
+    // transaction hash followed by the serialized transaction
+    NewTransaction = 1,
+    // block hash followed by its previous topoheight (u64 big endian)
+    BlockOrphaned = 2
+}
+
+impl PublisherTopic {
+    const ALL: u8 = 0b111;
+
+    fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+pub struct Publisher {
+    // Frames are shared between all subscribers
+    // A subscriber lagging behind loses the oldest frames
+    sender: broadcast::Sender<(PublisherTopic, Bytes)>,
+    handle: Mutex<Option<JoinHandle<()>>>
+}
+
+impl Publisher {
+    // Start to listen on a TCP address or on a Unix socket if prefixed by `unix:`
+    pub async fn start(bind_address: &str, max_queued_messages: usize) -> Result<Arc<Self>, anyhow::Error> {
+        let (sender, _) = broadcast::channel(max_queued_messages.max(1));
+        let handle = match bind_address.strip_prefix(UNIX_SOCKET_PREFIX) {
+            Some(path) => Self::listen_unix(path, sender.clone())?,
+            None => {
+                let listener = TcpListener::bind(bind_address).await.context("Error while binding Publisher")?;
+                let sender = sender.clone();
+                spawn_task("publisher", async move {
+                    loop {
+                        match listener.accept().await {
+                            Ok((stream, addr)) => {
+                                trace!("New Publisher subscriber from {}", addr);
+                                let (reader, writer) = stream.into_split();
+                                spawn_task("publisher-subscriber", handle_subscriber(reader, writer, sender.subscribe(), addr.to_string()));
+                            },
+                            Err(e) => error!("Error while accepting Publisher subscriber: {}", e)
+                        }
+                    }
+                })
+            }
+        };
+        info!("Publisher will listen on: {}", bind_address);
+
+        Ok(Arc::new(Self {
+            sender,
+            handle: Mutex::new(Some(handle))
+        }))
+    }
+
+    #[cfg(unix)]
+    fn listen_unix(path: &str, sender: broadcast::Sender<(PublisherTopic, Bytes)>) -> Result<JoinHandle<()>, anyhow::Error> {
+        use std::{fs, os::unix::fs::FileTypeExt};
+        use tokio::net::UnixListener;
+
+        // Remove the socket left by a previous run
+        if let Ok(metadata) = fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                anyhow::bail!("{} already exists and is not a socket", path);
+            }
+            fs::remove_file(path)?;
+        }
+
+        let listener = UnixListener::bind(path).context("Error while binding Publisher")?;
+        Ok(spawn_task("publisher", async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        trace!("New Publisher subscriber on Unix socket");
+                        let (reader, writer) = stream.into_split();
+                        spawn_task("publisher-subscriber", handle_subscriber(reader, writer, sender.subscribe(), String::from("unix")));
+                    },
+                    Err(e) => error!("Error while accepting Publisher subscriber: {}", e)
+                }
+            }
+        }))
+    }
+
+    #[cfg(not(unix))]
+    fn listen_unix(_: &str, _: broadcast::Sender<(PublisherTopic, Bytes)>) -> Result<JoinHandle<()>, anyhow::Error> {
+        anyhow::bail!("Unix sockets are not supported on this platform")
+    }
+
+    pub async fn stop(&self) {
+        if let Some(handle) = self.handle.lock().await.take() {
+            handle.abort();
+        }
+    }
+
+    // No need to serialize anything if nobody is listening
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    fn publish(&self, topic: PublisherTopic, payload: Vec<u8>) {
+        let mut frame = Vec::with_capacity(5 + payload.len());
+        frame.push(topic as u8);
+        frame.extend((payload.len() as u32).to_be_bytes());
+        frame.extend(payload);
+
+        // Only fails if there is no subscriber
+        if self.sender.send((topic, Bytes::from(frame))).is_err() {
+            trace!("No Publisher subscriber for {:?}", topic);
+        }
+    }
+
+    pub fn publish_block(&self, hash: &Hash, header: &BlockHeader, txs: &[Immutable<Transaction>]) {
+        if !self.has_subscribers() {
+            return;
+        }
+
+        let mut payload = Vec::new();
+        let mut writer = Writer::new(&mut payload);
+        writer.write_hash(hash);
+        header.write(&mut writer);
+        for tx in txs {
+            tx.write(&mut writer);
+        }
+        self.publish(PublisherTopic::NewBlock, payload);
+    }
+
+    pub fn publish_transaction(&self, hash: &Hash, tx: &Transaction) {
+        if !self.has_subscribers() {
+            return;
+        }
+
+        let mut payload = Vec::new();
+        let mut writer = Writer::new(&mut payload);
+        writer.write_hash(hash);
+        tx.write(&mut writer);
+        self.publish(PublisherTopic::NewTransaction, payload);
+    }
+
+    pub fn publish_block_orphaned(&self, hash: &Hash, old_topoheight: TopoHeight) {
+        if !self.has_subscribers() {
+            return;
+        }
+
+        let mut payload = Vec::new();
+        let mut writer = Writer::new(&mut payload);
+        writer.write_hash(hash);
+        writer.write_u64(&old_topoheight);
+        self.publish(PublisherTopic::BlockOrphaned, payload);
+    }
+}
+
+// Forward the frames of the subscribed topics until the subscriber disconnects
+async fn handle_subscriber<R, W>(mut reader: R, mut writer: W, mut receiver: broadcast::Receiver<(PublisherTopic, Bytes)>, addr: String)
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin
+{
+    let mut topics = PublisherTopic::ALL;
+    let mut buffer = [0u8; 1];
+    loop {
+        select! {
+            res = reader.read(&mut buffer) => match res {
+                Ok(0) => break,
+                Ok(_) => {
+                    topics = buffer[0];
+                    debug!("Publisher subscriber {} updated its topics to {:#010b}", addr, topics);
+                },
+                Err(e) => {
+                    debug!("Error while reading from Publisher subscriber {}: {}", addr, e);
+                    break;
+                }
+            },
+            res = receiver.recv() => match res {
+                Ok((topic, frame)) => {
+                    if topics & topic.mask() == 0 {
+                        continue;
+                    }
+
+                    if let Err(e) = writer.write_all(&frame).await {
+                        debug!("Error while sending frame to Publisher subscriber {}: {}", addr, e);
+                        break;
+                    }
+                },
+                Err(RecvError::Lagged(skipped)) => warn!("Publisher subscriber {} is too slow, {} notifications were dropped", addr, skipped),
+                Err(RecvError::Closed) => break
+            }
+        }
+    }
+
+    trace!("Publisher subscriber {} disconnected", addr);
+}
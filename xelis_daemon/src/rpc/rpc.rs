@@ -1,19 +1,15 @@
 use crate::{
     config::{
-        get_hard_forks as get_configured_hard_forks,
         BLOCK_TIME_MILLIS,
-        DEV_FEES,
+        DEFAULT_RPC_READY_MAX_BLOCKS_BEHIND,
         DEV_PUBLIC_KEY
     },
     core::{
-        blockchain::{
-            get_block_dev_fee,
-            get_block_reward,
-            Blockchain
-        },
+        blockchain::Blockchain,
         hard_fork::get_pow_algorithm_for_version,
         error::BlockchainError,
         mempool::Mempool,
+        runtime_params,
         storage::*,
     },
     p2p::peer::Peer,
@@ -24,6 +20,7 @@ use xelis_common::{
     api::{
         daemon::*,
         RPCContractOutput,
+        RPCContractReceipt,
         RPCTransaction,
         SplitAddressParams,
         SplitAddressResult,
@@ -43,12 +40,28 @@ use xelis_common::{
         XELIS_ASSET
     },
     context::Context,
-    crypto::{Address, AddressType, Hash},
+    contract::validate_module,
+    crypto::{
+        merkle::{hash_balance_leaf, merkle_root, MerkleProof},
+        Address,
+        AddressType,
+        Hash,
+        HumanReadableProof,
+        PublicKey
+    },
     difficulty::{
         CumulativeDifficulty,
         Difficulty
     },
+    emission::{
+        self,
+        get_block_dev_fee,
+        get_block_reward,
+        get_block_rewards,
+        DEV_FEES
+    },
     immutable::Immutable,
+    prompt::{logger, LogLevel},
     rpc_server::{
         parse_params,
         RPCHandler
@@ -56,16 +69,19 @@ use xelis_common::{
     serializer::Serializer,
     time::TimestampSeconds,
     transaction::{
+        ExecutionCreditsPayload,
         Transaction,
         TransactionType
     },
-    utils::format_hashrate
+    utils::{calculate_fee_rate_per_kb, format_hashrate}
 };
 use anyhow::Context as AnyContext;
 use human_bytes::human_bytes;
+use indexmap::IndexSet;
 use serde_json::{json, Value};
-use std::{sync::Arc, borrow::Cow};
+use std::{sync::Arc, borrow::Cow, collections::HashMap, str::FromStr};
 use log::{info, debug, trace};
+use xelis_vm::Module;
 
 // Get the block type using the block hash and the blockchain current state
 pub async fn get_block_type_for_block<S: Storage, P: DifficultyProvider + DagOrderProvider + BlocksAtHeightProvider + PrunedTopoheightProvider>(blockchain: &Blockchain<S>, provider: &P, hash: &Hash) -> Result<BlockType, InternalRpcError> {
@@ -155,6 +171,10 @@ where
         miner_reward,
         total_fees: Some(total_fees),
         total_size_in_bytes,
+        header_size_in_bytes: header.size(),
+        txs_count: block.get_txs_count(),
+        transfers_count: Some(block.get_transfers_count()),
+        fee_rate: Some(calculate_fee_rate_per_kb(total_fees, total_size_in_bytes.saturating_sub(header.size()))),
         extra_nonce: Cow::Borrowed(header.get_extra_nonce()),
         timestamp: header.get_timestamp(),
         nonce: header.get_nonce(),
@@ -167,15 +187,6 @@ where
     }))
 }
 
-// Get block rewards based on height and reward
-fn get_block_rewards(height: u64, reward: u64) -> (u64, u64) {
-    let dev_fee_percentage = get_block_dev_fee(height);
-    let dev_reward = reward * dev_fee_percentage / 100;
-    let miner_reward = reward - dev_reward;
-
-    (dev_reward, miner_reward)
-}
-
 // Get optional block rewards based on height and reward
 fn get_optional_block_rewards(height: u64, reward: Option<u64>) -> Option<(u64, u64)> {
     if let Some(reward) = reward {
@@ -222,6 +233,10 @@ pub async fn get_block_response_for_hash<S: Storage>(blockchain: &Blockchain<S>,
             miner_reward,
             total_fees: None,
             total_size_in_bytes,
+            header_size_in_bytes: header.size(),
+            txs_count: header.get_txs_count(),
+            transfers_count: None,
+            fee_rate: None,
             extra_nonce: Cow::Borrowed(header.get_extra_nonce()),
             timestamp: header.get_timestamp(),
             nonce: header.get_nonce(),
@@ -247,7 +262,16 @@ pub async fn get_transaction_response<S: Storage>(storage: &S, tx: &Arc<Transact
 
     let data = RPCTransaction::from_tx(tx, hash, storage.is_mainnet());
     let executed_in_block = storage.get_block_executor_for_tx(hash).ok();
-    Ok(json!(TransactionResponse { blocks, executed_in_block, data, in_mempool, first_seen }))
+
+    // Receipts are only available once the TX got executed
+    let contract_receipts = if executed_in_block.is_some() && storage.has_contract_receipts_for_tx(hash).await.context("Error while checking contract receipts")? {
+        let receipts = storage.get_contract_receipts_for_tx(hash).await.context("Error while retrieving contract receipts")?;
+        Some(receipts.into_iter().map(|receipt| RPCContractReceipt::from_receipt(receipt, storage.is_mainnet())).collect())
+    } else {
+        None
+    };
+
+    Ok(json!(TransactionResponse { blocks, executed_in_block, data, in_mempool, first_seen, contract_receipts }))
 }
 
 // first check on disk, then check in mempool
@@ -285,17 +309,83 @@ pub async fn get_peer_entry(peer: &Peer) -> PeerEntry {
     }
 }
 
+// Build the detailed node status with each sub-system state
+// Node is considered as ready if it is synced within `max_blocks_behind` blocks of the network,
+// storage is writable and P2P has at least one peer connected (if enabled)
+pub async fn get_node_status<S: Storage>(blockchain: &Blockchain<S>, max_blocks_behind: u64) -> Result<GetNodeStatusResult, InternalRpcError> {
+    let height = blockchain.get_height();
+    let topoheight = blockchain.get_topo_height();
+
+    let (writable, pruned_topoheight) = {
+        let storage = blockchain.get_storage().read().await;
+        let pruned_topoheight = storage.get_pruned_topoheight().await.context("Error while retrieving pruned topoheight")?;
+        (storage.is_writable().await, pruned_topoheight)
+    };
+
+    let p2p = { blockchain.get_p2p().read().await.clone() };
+    let (best_topoheight, syncing, p2p_status) = match p2p.as_ref() {
+        Some(p2p) => {
+            let peer_count = p2p.get_peer_count().await;
+            let status = P2pNodeStatus {
+                enabled: true,
+                peer_count,
+                max_peers: p2p.get_max_peers(),
+                connected: peer_count > 0
+            };
+            (Some(p2p.get_best_topoheight().await), p2p.is_syncing_chain(), status)
+        },
+        None => (None, false, P2pNodeStatus {
+            enabled: false,
+            peer_count: 0,
+            max_peers: 0,
+            connected: false
+        })
+    };
+
+    let blocks_behind = best_topoheight.map(|best| best.saturating_sub(topoheight)).unwrap_or(0);
+    let synced = blocks_behind <= max_blocks_behind;
+    // Without P2P, we have no network to be connected to
+    let p2p_ready = !p2p_status.enabled || p2p_status.connected;
+
+    Ok(GetNodeStatusResult {
+        ready: synced && writable && p2p_ready,
+        max_blocks_behind,
+        chain: ChainStatus {
+            height,
+            topoheight,
+            stable_height: blockchain.get_stable_height(),
+            stable_topoheight: blockchain.get_stable_topoheight(),
+            best_topoheight,
+            blocks_behind,
+            syncing,
+            synced
+        },
+        storage: StorageStatus {
+            writable,
+            pruned_topoheight
+        },
+        p2p: p2p_status,
+        mempool: MempoolStatus {
+            size: blockchain.get_mempool_size().await
+        },
+        version: VERSION.into(),
+        network: *blockchain.get_network()
+    })
+}
+
 // This function is used to register all the RPC methods
-pub fn register_methods<S: Storage>(handler: &mut RPCHandler<Arc<Blockchain<S>>>, allow_mining_methods: bool) {
+pub fn register_methods<S: Storage>(handler: &mut RPCHandler<Arc<Blockchain<S>>>, allow_mining_methods: bool, allow_log_methods: bool, allow_admin_methods: bool) {
     info!("Registering RPC methods...");
     handler.register_method("get_version", async_handler!(version::<S>));
     handler.register_method("get_height", async_handler!(get_height::<S>));
     handler.register_method("get_topoheight", async_handler!(get_topoheight::<S>));
     handler.register_method("get_pruned_topoheight", async_handler!(get_pruned_topoheight::<S>));
     handler.register_method("get_info", async_handler!(get_info::<S>));
+    handler.register_method("get_node_status", async_handler!(get_node_status_method::<S>));
     handler.register_method("get_difficulty", async_handler!(get_difficulty::<S>));
     handler.register_method("get_tips", async_handler!(get_tips::<S>));
     handler.register_method("get_dev_fee_thresholds", async_handler!(get_dev_fee_thresholds::<S>));
+    handler.register_method("simulate_emission", async_handler!(simulate_emission::<S>));
     handler.register_method("get_size_on_disk", async_handler!(get_size_on_disk::<S>));
 
     // Retro compatibility, use stable_height
@@ -308,11 +398,15 @@ pub fn register_methods<S: Storage>(handler: &mut RPCHandler<Arc<Blockchain<S>>>
     handler.register_method("get_blocks_at_height", async_handler!(get_blocks_at_height::<S>));
     handler.register_method("get_block_by_hash", async_handler!(get_block_by_hash::<S>));
     handler.register_method("get_top_block", async_handler!(get_top_block::<S>));
+    handler.register_method("get_block_execution_order", async_handler!(get_block_execution_order::<S>));
 
     handler.register_method("get_balance", async_handler!(get_balance::<S>));
     handler.register_method("get_stable_balance", async_handler!(get_stable_balance::<S>));
     handler.register_method("has_balance", async_handler!(has_balance::<S>));
     handler.register_method("get_balance_at_topoheight", async_handler!(get_balance_at_topoheight::<S>));
+    handler.register_method("get_state_at_topoheight", async_handler!(get_state_at_topoheight::<S>));
+    handler.register_method("get_balances_root_at_topoheight", async_handler!(get_balances_root_at_topoheight::<S>));
+    handler.register_method("get_balance_proof", async_handler!(get_balance_proof::<S>));
 
     handler.register_method("get_nonce", async_handler!(get_nonce::<S>));
     handler.register_method("has_nonce", async_handler!(has_nonce::<S>));
@@ -331,17 +425,27 @@ pub fn register_methods<S: Storage>(handler: &mut RPCHandler<Arc<Blockchain<S>>>
     handler.register_method("get_transaction", async_handler!(get_transaction::<S>));
     handler.register_method("get_transactions", async_handler!(get_transactions::<S>));
     handler.register_method("is_tx_executed_in_block", async_handler!(is_tx_executed_in_block::<S>));
+    handler.register_method("is_tx_executed", async_handler!(is_tx_executed::<S>));
+    handler.register_method("get_transaction_blocks", async_handler!(get_transaction_blocks::<S>));
+    handler.register_method("get_block_skipped_transactions", async_handler!(get_block_skipped_transactions::<S>));
+    handler.register_method("verify_payment_proof", async_handler!(verify_payment_proof::<S>));
+    handler.register_method("verify_reserve_proof", async_handler!(verify_reserve_proof::<S>));
+    handler.register_method("verify_audit_proof", async_handler!(verify_audit_proof::<S>));
 
     handler.register_method("p2p_status", async_handler!(p2p_status::<S>));
     handler.register_method("get_peers", async_handler!(get_peers::<S>));
 
     handler.register_method("get_mempool", async_handler!(get_mempool::<S>));
     handler.register_method("get_mempool_cache", async_handler!(get_mempool_cache::<S>));
+    handler.register_method("get_account_pending", async_handler!(get_account_pending::<S>));
+    handler.register_method("get_transaction_broadcast_receipt", async_handler!(get_transaction_broadcast_receipt::<S>));
     handler.register_method("get_estimated_fee_rates", async_handler!(get_estimated_fee_rates::<S>));
 
     handler.register_method("get_dag_order", async_handler!(get_dag_order::<S>));
+    handler.register_method("get_dag_info", async_handler!(get_dag_info::<S>));
     handler.register_method("get_blocks_range_by_topoheight", async_handler!(get_blocks_range_by_topoheight::<S>));
     handler.register_method("get_blocks_range_by_height", async_handler!(get_blocks_range_by_height::<S>));
+    handler.register_method("get_block_headers_range_by_topoheight", async_handler!(get_block_headers_range_by_topoheight::<S>));
 
     handler.register_method("get_account_history", async_handler!(get_account_history::<S>));
     handler.register_method("get_account_assets", async_handler!(get_account_assets::<S>));
@@ -369,11 +473,27 @@ pub fn register_methods<S: Storage>(handler: &mut RPCHandler<Arc<Blockchain<S>>>
     handler.register_method("get_contract_data_at_topoheight", async_handler!(get_contract_data_at_topoheight::<S>));
     handler.register_method("get_contract_balance", async_handler!(get_contract_balance::<S>));
     handler.register_method("get_contract_balance_at_topoheight", async_handler!(get_contract_balance_at_topoheight::<S>));
+    handler.register_method("get_contract_management", async_handler!(get_contract_management::<S>));
+    handler.register_method("get_execution_credits", async_handler!(get_execution_credits::<S>));
+    handler.register_method("simulate_contract_invoke", async_handler!(simulate_contract_invoke::<S>));
+    handler.register_method("validate_contract", async_handler!(validate_contract::<S>));
 
     if allow_mining_methods {
         handler.register_method("get_block_template", async_handler!(get_block_template::<S>));
         handler.register_method("get_miner_work", async_handler!(get_miner_work::<S>));
         handler.register_method("submit_block", async_handler!(submit_block::<S>));
+        handler.register_method("get_getwork_miners", async_handler!(get_getwork_miners::<S>));
+    }
+
+    if allow_log_methods {
+        handler.register_method("get_log_levels", async_handler!(get_log_levels));
+        handler.register_method("set_log_level", async_handler!(set_log_level));
+    }
+
+    if allow_admin_methods {
+        handler.register_method("get_runtime_params", async_handler!(get_runtime_params::<S>));
+        handler.register_method("set_runtime_params", async_handler!(set_runtime_params::<S>));
+        handler.register_method("get_runtime_stats", async_handler!(get_runtime_stats::<S>));
     }
 }
 
@@ -433,7 +553,7 @@ async fn get_hard_forks<S: Storage>(context: &Context, body: Value) -> Result<Va
         return Err(InternalRpcError::UnexpectedParams)
     }
     let blockchain: &Arc<Blockchain<S>> = context.get()?;
-    let hard_forks = get_configured_hard_forks(blockchain.get_network());
+    let hard_forks = blockchain.get_fork_schedule().get_hard_forks();
 
     Ok(json!(hard_forks))
 }
@@ -462,6 +582,66 @@ async fn get_top_block<S: Storage>(context: &Context, body: Value) -> Result<Val
     get_block_response_for_hash(&blockchain, &storage, &hash, params.include_txs).await
 }
 
+// Transactions of a block in the order they got processed by the DAG ordering
+async fn get_block_execution_order<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetBlockExecutionOrderParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let storage = blockchain.get_storage().read().await;
+
+    let (hash, topoheight) = match (params.hash, params.topoheight) {
+        (Some(hash), None) => {
+            let hash = hash.into_owned();
+            let topoheight = if storage.is_block_topological_ordered(&hash).await {
+                Some(storage.get_topo_height_for_hash(&hash).await.context("Error while retrieving topo height for hash")?)
+            } else {
+                None
+            };
+            (hash, topoheight)
+        },
+        (None, Some(topoheight)) => {
+            let hash = storage.get_hash_at_topo_height(topoheight).await
+                .context("Error while retrieving hash at topo height")?;
+            (hash, Some(topoheight))
+        },
+        _ => return Err(InternalRpcError::InvalidJSONRequest).context("Expected either a block hash or a topoheight")?
+    };
+
+    let header = storage.get_block_header_by_hash(&hash).await
+        .context("Error while retrieving block header")?;
+
+    let execution_position = if storage.has_block_position_in_order(&hash).await? {
+        Some(storage.get_block_position_in_order(&hash).await?)
+    } else {
+        None
+    };
+
+    let mut transactions = Vec::with_capacity(header.get_txs_count());
+    for tx_hash in header.get_transactions() {
+        let status = if storage.is_tx_executed_in_a_block(tx_hash)? {
+            let executor = storage.get_block_executor_for_tx(tx_hash)?;
+            if executor == hash {
+                TransactionExecutionStatus::Executed
+            } else {
+                TransactionExecutionStatus::ExecutedIn(Cow::Owned(executor))
+            }
+        } else {
+            TransactionExecutionStatus::NotExecuted
+        };
+
+        transactions.push(TransactionExecutionEntry {
+            hash: Cow::Borrowed(tx_hash),
+            status
+        });
+    }
+
+    Ok(json!(GetBlockExecutionOrderResult {
+        hash: Cow::Borrowed(&hash),
+        topoheight,
+        execution_position,
+        transactions
+    }))
+}
+
 async fn get_block_template<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: GetBlockTemplateParams = parse_params(body)?;
     if !params.address.is_normal() {
@@ -529,6 +709,26 @@ async fn submit_block<S: Storage>(context: &Context, body: Value) -> Result<Valu
     Ok(json!(true))
 }
 
+async fn get_getwork_miners<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let getwork = {
+        let rpc = blockchain.get_rpc().read().await;
+        rpc.as_ref().and_then(|rpc| rpc.getwork_server().clone())
+            .ok_or_else(|| InternalRpcError::InvalidParamsAny(ApiError::NoGetWorkServer.into()))?
+    };
+
+    let miners = getwork.get_miners().lock().await;
+    let entries: Vec<GetWorkMinerEntry<'_>> = miners.values()
+        .map(|miner| miner.to_entry())
+        .collect();
+
+    Ok(json!(entries))
+}
+
 async fn get_balance<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: GetBalanceParams = parse_params(body)?;
     let blockchain: &Arc<Blockchain<S>> = context.get()?;
@@ -641,6 +841,22 @@ async fn get_info<S: Storage>(context: &Context, body: Value) -> Result<Value, I
     }))
 }
 
+async fn get_node_status_method<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let max_blocks_behind = {
+        let rpc = blockchain.get_rpc().read().await;
+        rpc.as_ref().map(|rpc| rpc.get_ready_max_blocks_behind())
+            .unwrap_or(DEFAULT_RPC_READY_MAX_BLOCKS_BEHIND)
+    };
+
+    let status = get_node_status(blockchain, max_blocks_behind).await?;
+    Ok(json!(status))
+}
+
 async fn get_balance_at_topoheight<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: GetBalanceAtTopoHeightParams = parse_params(body)?;
     let blockchain: &Arc<Blockchain<S>> = context.get()?;
@@ -658,6 +874,154 @@ async fn get_balance_at_topoheight<S: Storage>(context: &Context, body: Value) -
     Ok(json!(balance))
 }
 
+const MAX_STATE_ASSETS: usize = 64;
+// Resolve the state of an account at any topoheight
+// The full history is only guaranteed in archive mode
+async fn get_state_at_topoheight<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetStateAtTopoHeightParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    if params.topoheight > blockchain.get_topo_height() {
+        return Err(InternalRpcError::UnexpectedParams).context("Topoheight cannot be greater than current chain topoheight")?
+    }
+
+    if params.address.is_mainnet() != blockchain.get_network().is_mainnet() {
+        return Err(InternalRpcError::InvalidParamsAny(BlockchainError::InvalidNetwork.into()))
+    }
+
+    if params.assets.len() > MAX_STATE_ASSETS {
+        return Err(InternalRpcError::InvalidJSONRequest).context(format!("Maximum assets requested cannot be greater than {}", MAX_STATE_ASSETS))?
+    }
+
+    let storage = blockchain.get_storage().read().await;
+    ensure_state_not_pruned(&*storage, params.topoheight).await?;
+
+    let key = params.address.get_public_key();
+    let nonce = storage.get_nonce_at_maximum_topoheight(key, params.topoheight).await
+        .context("Error while retrieving nonce at topoheight")?
+        .map(|(topoheight, version)| GetNonceResult { topoheight, version });
+
+    let assets = if params.assets.is_empty() {
+        IndexSet::from([XELIS_ASSET])
+    } else {
+        params.assets
+    };
+
+    let mut balances = HashMap::with_capacity(assets.len());
+    for asset in assets {
+        if let Some((topoheight, version)) = storage.get_balance_at_maximum_topoheight(key, &asset, params.topoheight).await.context("Error while retrieving balance at topoheight")? {
+            balances.insert(asset, GetBalanceResult { version, topoheight });
+        }
+    }
+
+    let mainnet = storage.is_mainnet();
+    let multisig = storage.get_multisig_at_maximum_topoheight_for(key, params.topoheight).await
+        .context("Error while retrieving multisig at topoheight")?
+        .map(|(topoheight, multisig)| {
+            let state = match multisig.take() {
+                Some(multisig) => {
+                    let multisig = multisig.into_owned();
+                    MultisigState::Active {
+                        participants: multisig.participants.into_iter().map(|p| p.to_address(mainnet)).collect(),
+                        threshold: multisig.threshold
+                    }
+                },
+                None => MultisigState::Deleted
+            };
+            GetMultisigResult { state, topoheight }
+        });
+
+    Ok(json!(GetStateAtTopoHeightResult {
+        topoheight: params.topoheight,
+        nonce,
+        balances,
+        multisig
+    }))
+}
+
+// Versions below the pruned topoheight are not available anymore
+async fn ensure_state_not_pruned<S: Storage>(storage: &S, topoheight: TopoHeight) -> Result<(), InternalRpcError> {
+    if let Some(pruned_topoheight) = storage.get_pruned_topoheight().await.context("Error while retrieving pruned topoheight")? {
+        if topoheight < pruned_topoheight {
+            return Err(InternalRpcError::InvalidParamsAny(BlockchainError::StatePruned(topoheight, pruned_topoheight).into()))
+        }
+    }
+
+    Ok(())
+}
+
+// Leaves of the balances merkle tree at a topoheight
+// Each balance version written at this topoheight is a leaf, ordered by key then asset
+async fn get_balances_leaves<S: Storage>(storage: &S, topoheight: TopoHeight) -> Result<(Vec<(PublicKey, Hash)>, Vec<Hash>), InternalRpcError> {
+    let balances = storage.get_balances_at_exact_topoheight(topoheight).await.context("Error while retrieving balances at topoheight")?;
+    let mut keys = Vec::with_capacity(balances.len());
+    let mut leaves = Vec::with_capacity(balances.len());
+    for (key, asset, version) in balances {
+        leaves.push(hash_balance_leaf(&key, &asset, &version));
+        keys.push((key, asset));
+    }
+
+    Ok((keys, leaves))
+}
+
+async fn get_balances_root_at_topoheight<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetBalancesRootAtTopoHeightParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    if params.topoheight > blockchain.get_topo_height() {
+        return Err(InternalRpcError::UnexpectedParams).context("Topoheight cannot be greater than current chain topoheight")?
+    }
+
+    let storage = blockchain.get_storage().read().await;
+    ensure_state_not_pruned(&*storage, params.topoheight).await?;
+
+    let block_hash = storage.get_hash_at_topo_height(params.topoheight).await.context("Error while retrieving hash at topo height")?;
+    let (_, leaves) = get_balances_leaves(&*storage, params.topoheight).await?;
+
+    Ok(json!(GetBalancesRootAtTopoHeightResult {
+        topoheight: params.topoheight,
+        block_hash,
+        root: merkle_root(&leaves),
+        leaves: leaves.len()
+    }))
+}
+
+async fn get_balance_proof<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetBalanceProofParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    if params.address.is_mainnet() != blockchain.get_network().is_mainnet() {
+        return Err(InternalRpcError::InvalidParamsAny(BlockchainError::InvalidNetwork.into()))
+    }
+
+    let current_topoheight = blockchain.get_topo_height();
+    let maximum_topoheight = params.topoheight.unwrap_or(current_topoheight);
+    if maximum_topoheight > current_topoheight {
+        return Err(InternalRpcError::UnexpectedParams).context("Topoheight cannot be greater than current chain topoheight")?
+    }
+
+    let storage = blockchain.get_storage().read().await;
+    let key = params.address.get_public_key();
+    let (topoheight, version) = storage.get_balance_at_maximum_topoheight(key, &params.asset, maximum_topoheight).await
+        .context("Error while retrieving balance at topoheight")?
+        .ok_or(InternalRpcError::InvalidParamsAny(BlockchainError::NoBalance(key.as_address(blockchain.get_network().is_mainnet())).into()))?;
+
+    // The version may have been written before the pruned topoheight
+    ensure_state_not_pruned(&*storage, topoheight).await?;
+
+    let (keys, leaves) = get_balances_leaves(&*storage, topoheight).await?;
+    let index = keys.iter()
+        .position(|(k, asset)| k == key && *asset == *params.asset)
+        .context("Balance version not found in the balances of its topoheight")?;
+    let proof = MerkleProof::new(&leaves, index).context("Error while building the balance proof")?;
+    let block_hash = storage.get_hash_at_topo_height(topoheight).await.context("Error while retrieving hash at topo height")?;
+
+    Ok(json!(GetBalanceProofResult {
+        topoheight,
+        block_hash: Cow::Owned(block_hash),
+        version,
+        proof,
+        root: merkle_root(&leaves)
+    }))
+}
+
 async fn has_nonce<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: HasNonceParams = parse_params(body)?;
     let blockchain: &Arc<Blockchain<S>> = context.get()?;
@@ -738,7 +1102,7 @@ async fn get_assets<S: Storage>(context: &Context, body: Value) -> Result<Value,
     let storage = blockchain.get_storage().read().await;
     let min = params.minimum_topoheight.unwrap_or(0);
     let max =  params.maximum_topoheight.unwrap_or_else(|| blockchain.get_topo_height());
-    let assets = storage.get_partial_assets_with_topoheight(maximum, skip, min, max).await
+    let assets = storage.get_partial_assets_with_topoheight(maximum, skip, params.cursor.as_ref(), min, max).await
         .context("Error while retrieving registered assets")?;
 
     let mut response = Vec::with_capacity(assets.len());
@@ -827,19 +1191,221 @@ async fn get_transaction_executor<S: Storage>(context: &Context, body: Value) ->
     let blockchain: &Arc<Blockchain<S>> = context.get()?;
     let storage = blockchain.get_storage().read().await;
 
+    // a TX may be included in several blocks but is executed only once
+    if !storage.is_tx_executed_in_a_block(&params.hash).context("Error while checking if tx was executed")? {
+        return Err(InternalRpcError::InvalidParamsAny(BlockchainError::TxNotExecuted(params.hash.into_owned()).into()))
+    }
+
     let block_executor = storage.get_block_executor_for_tx(&params.hash)?;
     let block_topoheight = storage.get_topo_height_for_hash(&block_executor).await?;
     let block_timestamp = storage.get_timestamp_for_block_hash(&block_executor).await?;
+    let topoheight = blockchain.get_topo_height();
 
     Ok(json!(
         GetTransactionExecutorResult {
             block_topoheight,
             block_timestamp,
-            block_hash: Cow::Borrowed(&block_executor)
+            block_hash: Cow::Borrowed(&block_executor),
+            confirmations: topoheight.saturating_sub(block_topoheight),
+            in_stable_height: block_topoheight <= blockchain.get_stable_topoheight()
         }
     ))
 }
 
+// All blocks including a TX and the one that executed it
+async fn get_transaction_blocks<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetTransactionBlocksParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let storage = blockchain.get_storage().read().await;
+
+    if !storage.has_tx_blocks(&params.hash).context("Error while checking if tx is included in blocks")? {
+        return Err(InternalRpcError::InvalidParamsAny(BlockchainError::TxNotFound(params.hash.into_owned()).into()))
+    }
+
+    let executed_in_block = storage.get_block_executor_for_tx(&params.hash).ok();
+    let mut blocks = Vec::new();
+    for hash in storage.get_blocks_for_tx(&params.hash).context("Error while retrieving in which blocks its included")? {
+        let topoheight = if storage.is_block_topological_ordered(&hash).await {
+            Some(storage.get_topo_height_for_hash(&hash).await?)
+        } else {
+            None
+        };
+
+        blocks.push(TransactionBlockEntry {
+            executed: executed_in_block.as_ref() == Some(&hash),
+            hash: Cow::Owned(hash),
+            topoheight
+        });
+    }
+
+    // Unordered blocks are put at the end
+    blocks.sort_by_key(|entry| entry.topoheight.unwrap_or(TopoHeight::MAX));
+
+    Ok(json!(GetTransactionBlocksResult {
+        blocks,
+        executed_in_block: executed_in_block.map(Cow::Owned)
+    }))
+}
+
+// TXs of a block that were not executed by it
+async fn get_block_skipped_transactions<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetBlockSkippedTransactionsParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let storage = blockchain.get_storage().read().await;
+
+    let header = storage.get_block_header_by_hash(&params.hash).await
+        .context("Error while retrieving block header")?;
+
+    let mut skipped = Vec::new();
+    for tx_hash in header.get_transactions() {
+        let executed_in_block = storage.get_block_executor_for_tx(tx_hash).ok();
+        if executed_in_block.as_ref() == Some(params.hash.as_ref()) {
+            continue;
+        }
+
+        skipped.push(SkippedTransactionEntry {
+            hash: Cow::Borrowed(tx_hash),
+            executed_in_block: executed_in_block.map(Cow::Owned)
+        });
+    }
+
+    Ok(json!(skipped))
+}
+
+async fn is_tx_executed<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: IsTxExecutedParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let storage = blockchain.get_storage().read().await;
+    Ok(json!(storage.is_tx_executed_in_a_block(&params.hash).context("Error while checking if tx was executed")?))
+}
+
+// Verify a payment proof against the transfer of an executed transaction
+async fn verify_payment_proof<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: VerifyPaymentProofParams = parse_params(body)?;
+    let HumanReadableProof::Payment { proof, transaction, index } = params.proof else {
+        return Err(InternalRpcError::InvalidParams("Expected a payment proof"))
+    };
+
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let storage = blockchain.get_storage().read().await;
+
+    // an orphaned TX is not a valid payment
+    if !storage.is_tx_executed_in_a_block(&transaction).context("Error while checking if tx was executed")? {
+        return Err(InternalRpcError::InvalidParamsAny(BlockchainError::TxNotExecuted(transaction).into()))
+    }
+
+    let tx = storage.get_transaction(&transaction).await?;
+    let transfer = tx.verify_payment_proof(&transaction, index, &proof)
+        .context("Invalid payment proof")?;
+
+    let block_executor = storage.get_block_executor_for_tx(&transaction)?;
+    let block_topoheight = storage.get_topo_height_for_hash(&block_executor).await?;
+    let mainnet = blockchain.get_network().is_mainnet();
+
+    Ok(json!(VerifyPaymentProofResult {
+        transaction: Cow::Borrowed(&transaction),
+        index,
+        source: tx.get_source().as_address(mainnet),
+        destination: transfer.get_destination().as_address(mainnet),
+        asset: Cow::Borrowed(transfer.get_asset()),
+        amount: proof.get_amount(),
+        block_topoheight
+    }))
+}
+
+// Verify a reserve proof against the balance of the account at the proof topoheight
+async fn verify_reserve_proof<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: VerifyReserveProofParams = parse_params(body)?;
+    let HumanReadableProof::Reserve { proof, asset, topoheight, challenge } = params.proof else {
+        return Err(InternalRpcError::InvalidParams("Expected a reserve proof"))
+    };
+
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    if params.address.is_mainnet() != blockchain.get_network().is_mainnet() {
+        return Err(InternalRpcError::InvalidParamsAny(BlockchainError::InvalidNetwork.into()))
+    }
+
+    if topoheight > blockchain.get_topo_height() {
+        return Err(InternalRpcError::InvalidParams("Topoheight cannot be greater than current chain topoheight"))
+    }
+
+    let storage = blockchain.get_storage().read().await;
+    let key = params.address.get_public_key();
+    let version = storage.get_balance_at_exact_topoheight(key, &asset, topoheight).await.context("Error while retrieving balance at exact topo height")?;
+    let ciphertext = version.get_balance().compress().decompress().context("Error while decompressing balance")?;
+    let public_key = key.decompress().context("Error while decompressing public key")?;
+    proof.verify(&public_key, ciphertext, &challenge).context("Invalid reserve proof")?;
+
+    let (last_topoheight, _) = storage.get_last_balance(key, &asset).await.context("Error while retrieving last balance")?;
+    Ok(json!(VerifyReserveProofResult {
+        address: params.address,
+        asset: Cow::Owned(asset),
+        amount: proof.get_amount(),
+        topoheight,
+        challenge: hex::encode(&challenge),
+        latest: last_topoheight == topoheight
+    }))
+}
+
+// Verify an audit proof against the balance or the transfer ciphertext of the account
+// The ciphertext of the audited amount is returned so the auditor can decrypt it
+async fn verify_audit_proof<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: VerifyAuditProofParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let mainnet = blockchain.get_network().is_mainnet();
+    if params.address.is_mainnet() != mainnet {
+        return Err(InternalRpcError::InvalidParamsAny(BlockchainError::InvalidNetwork.into()))
+    }
+
+    let storage = blockchain.get_storage().read().await;
+    let key = params.address.get_public_key();
+    match params.proof {
+        HumanReadableProof::Audit { proof, auditor, asset, topoheight } => {
+            if topoheight > blockchain.get_topo_height() {
+                return Err(InternalRpcError::InvalidParams("Topoheight cannot be greater than current chain topoheight"))
+            }
+
+            let version = storage.get_balance_at_exact_topoheight(key, &asset, topoheight).await.context("Error while retrieving balance at exact topo height")?;
+            let ciphertext = version.get_balance().compress().decompress().context("Error while decompressing balance")?;
+            let public_key = key.decompress().context("Error while decompressing public key")?;
+            let auditor_key = auditor.decompress().context("Error while decompressing auditor key")?;
+            proof.verify(&public_key, &auditor_key, ciphertext).context("Invalid audit proof")?;
+
+            Ok(json!(VerifyAuditProofResult {
+                address: params.address,
+                auditor: auditor.as_address(mainnet),
+                asset: Cow::Owned(asset),
+                topoheight: Some(topoheight),
+                transaction: None,
+                index: None,
+                ciphertext: proof.get_ciphertext()
+            }))
+        },
+        HumanReadableProof::TransferAudit { proof, auditor, transaction, index } => {
+            // an orphaned TX has no effect on the account
+            if !storage.is_tx_executed_in_a_block(&transaction).context("Error while checking if tx was executed")? {
+                return Err(InternalRpcError::InvalidParamsAny(BlockchainError::TxNotExecuted(transaction).into()))
+            }
+
+            let tx = storage.get_transaction(&transaction).await?;
+            let auditor_key = auditor.decompress().context("Error while decompressing auditor key")?;
+            let transfer = tx.verify_audit_proof(key, &auditor_key, index, &proof)
+                .context("Invalid audit proof")?;
+
+            Ok(json!(VerifyAuditProofResult {
+                address: params.address,
+                auditor: auditor.as_address(mainnet),
+                asset: Cow::Borrowed(transfer.get_asset()),
+                topoheight: None,
+                transaction: Some(Cow::Borrowed(&transaction)),
+                index: Some(index),
+                ciphertext: proof.get_ciphertext()
+            }))
+        },
+        _ => Err(InternalRpcError::InvalidParams("Expected an audit proof"))
+    }
+}
+
 async fn p2p_status<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     if body != Value::Null {
         return Err(InternalRpcError::UnexpectedParams)
@@ -871,6 +1437,21 @@ async fn p2p_status<S: Storage>(context: &Context, body: Value) -> Result<Value,
     }
 }
 
+// Report to how many peers a TX was announced and if any of them requested it
+async fn get_transaction_broadcast_receipt<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetTransactionBroadcastReceiptParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let p2p = { blockchain.get_p2p().read().await.clone() };
+    let Some(p2p) = p2p else {
+        return Err(InternalRpcError::InvalidParamsAny(ApiError::NoP2p.into()))
+    };
+
+    let receipt = p2p.get_tx_broadcast_receipt(&params.hash).await
+        .ok_or(InternalRpcError::InvalidParamsAny(ApiError::NoTxBroadcastReceipt.into()))?;
+
+    Ok(json!(receipt))
+}
+
 async fn get_peers<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     if body != Value::Null {
         return Err(InternalRpcError::UnexpectedParams)
@@ -967,6 +1548,90 @@ async fn get_dag_order<S: Storage>(context: &Context, body: Value) -> Result<Val
     Ok(json!(order))
 }
 
+const DEFAULT_DAG_INFO_WINDOW: u64 = 50;
+const MAX_DAG_INFO_WINDOW: u64 = 500;
+// get DAG stats for explorers: tips, orphans, average block time and reachability
+// stats are computed on the last `window` heights / topoheights
+async fn get_dag_info<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetDagInfoParams = parse_params(body)?;
+    let window = params.window.unwrap_or(DEFAULT_DAG_INFO_WINDOW);
+    if window == 0 || window > MAX_DAG_INFO_WINDOW {
+        return Err(InternalRpcError::InvalidJSONRequest).context(format!("Window must be between 1 and {}", MAX_DAG_INFO_WINDOW))?
+    }
+
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let storage = blockchain.get_storage().read().await;
+
+    let height = blockchain.get_height();
+    let topoheight = blockchain.get_topo_height();
+
+    let tips = storage.get_tips().await.context("Error while retrieving tips")?;
+    let mut dag_tips = Vec::with_capacity(tips.len());
+    let mut lowest_tip_height = height;
+    let mut highest_tip_height = 0;
+    for hash in tips.iter() {
+        let tip_height = storage.get_height_for_block_hash(hash).await.context("Error while retrieving tip height")?;
+        let tip_topoheight = if storage.is_block_topological_ordered(hash).await {
+            Some(storage.get_topo_height_for_hash(hash).await.context("Error while retrieving tip topoheight")?)
+        } else {
+            None
+        };
+        let difficulty = storage.get_difficulty_for_block_hash(hash).await.context("Error while retrieving tip difficulty")?;
+        let cumulative_difficulty = storage.get_cumulative_difficulty_for_block_hash(hash).await.context("Error while retrieving tip cumulative difficulty")?;
+
+        lowest_tip_height = lowest_tip_height.min(tip_height);
+        highest_tip_height = highest_tip_height.max(tip_height);
+        dag_tips.push(DagTip {
+            hash: Cow::Owned(hash.clone()),
+            height: tip_height,
+            topoheight: tip_topoheight,
+            difficulty,
+            cumulative_difficulty
+        });
+    }
+
+    // scan the last heights to count orphaned blocks and the DAG width
+    let mut orphaned_count = 0;
+    let mut blocks_count = 0;
+    let mut max_blocks_at_height = 0;
+    for h in height.saturating_sub(window - 1)..=height {
+        // blocks may have been pruned
+        if !storage.has_blocks_at_height(h).await.context("Error while checking blocks at height")? {
+            continue;
+        }
+
+        let blocks = storage.get_blocks_at_height(h).await.context("Error while retrieving blocks at height")?;
+        for hash in blocks.iter() {
+            if !storage.is_block_topological_ordered(hash).await {
+                orphaned_count += 1;
+            }
+        }
+
+        blocks_count += blocks.len() as u64;
+        max_blocks_at_height = max_blocks_at_height.max(blocks.len());
+    }
+
+    let average_block_time = blockchain.get_average_block_time_for_window::<S>(&storage, window).await
+        .context("Error while retrieving average block time")?;
+
+    Ok(json!(GetDagInfoResult {
+        height,
+        topoheight,
+        stable_height: blockchain.get_stable_height(),
+        stable_topoheight: blockchain.get_stable_topoheight(),
+        reachability: DagReachabilityStats {
+            tips_count: dag_tips.len(),
+            tips_height_spread: highest_tip_height.saturating_sub(lowest_tip_height),
+            blocks_count,
+            max_blocks_at_height
+        },
+        tips: dag_tips,
+        window,
+        orphaned_count,
+        average_block_time
+    }))
+}
+
 const MAX_BLOCKS: u64 = 20;
 
 fn get_range(start: Option<TopoHeight>, end: Option<TopoHeight>, maximum: u64, current: TopoHeight) -> Result<(TopoHeight, TopoHeight), InternalRpcError> {
@@ -1013,6 +1678,33 @@ async fn get_blocks_range_by_topoheight<S: Storage>(context: &Context, body: Val
     Ok(json!(blocks))
 }
 
+const MAX_HEADERS: u64 = 256;
+
+// get the serialized headers between range of topoheight
+// used by light clients to verify the chain without downloading the blocks
+async fn get_block_headers_range_by_topoheight<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetTopoHeightRangeParams = parse_params(body)?;
+
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let current_topoheight = blockchain.get_topo_height();
+    let (start_topoheight, end_topoheight) = get_range(params.start_topoheight, params.end_topoheight, MAX_HEADERS, current_topoheight)?;
+
+    let storage = blockchain.get_storage().read().await;
+    let mut headers = Vec::with_capacity((end_topoheight - start_topoheight + 1) as usize);
+    for topoheight in start_topoheight..=end_topoheight {
+        let (hash, header) = storage.get_block_header_at_topoheight(topoheight).await.context("Error while retrieving block header at topo height")?;
+        let difficulty = storage.get_difficulty_for_block_hash(&hash).await.context("Error while retrieving difficulty")?;
+        headers.push(BlockHeaderEntry {
+            topoheight,
+            hash: Cow::Owned(hash),
+            difficulty,
+            header: header.to_hex()
+        });
+    }
+
+    Ok(json!(headers))
+}
+
 // get blocks between range of height
 // if no params found, get last 20 blocks header
 // you can only request 
@@ -1249,6 +1941,33 @@ async fn get_account_history<S: Storage>(context: &Context, body: Value) -> Resu
                             block_timestamp: block_header.get_timestamp()
                         });
                     }
+                },
+                TransactionType::ManageContract(payload) => {
+                    if is_sender {
+                        history.push(AccountHistoryEntry {
+                            topoheight: topo,
+                            hash: tx_hash.clone(),
+                            history_type: AccountHistoryType::ManageContract {
+                                contract: payload.contract.clone(),
+                                action: payload.action,
+                            },
+                            block_timestamp: block_header.get_timestamp()
+                        });
+                    }
+                },
+                TransactionType::ExecutionCredits(payload) => {
+                    // Credits are only in XELIS
+                    if is_sender && params.asset == XELIS_ASSET {
+                        history.push(AccountHistoryEntry {
+                            topoheight: topo,
+                            hash: tx_hash.clone(),
+                            history_type: match payload {
+                                ExecutionCreditsPayload::Deposit { amount } => AccountHistoryType::DepositExecutionCredits { amount: *amount },
+                                ExecutionCreditsPayload::Withdraw { amount } => AccountHistoryType::WithdrawExecutionCredits { amount: *amount }
+                            },
+                            block_timestamp: block_header.get_timestamp()
+                        });
+                    }
                 }
             }
         }
@@ -1275,6 +1994,8 @@ async fn get_account_history<S: Storage>(context: &Context, body: Value) -> Resu
     Ok(json!(history))
 }
 
+const MAX_ACCOUNT_ASSETS: usize = 100;
+// retrieve the assets of an account, paginated using the last asset received as cursor
 async fn get_account_assets<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: GetAccountAssetsParams = parse_params(body)?;
     let blockchain: &Arc<Blockchain<S>> = context.get()?;
@@ -1282,9 +2003,18 @@ async fn get_account_assets<S: Storage>(context: &Context, body: Value) -> Resul
         return Err(InternalRpcError::InvalidParamsAny(BlockchainError::InvalidNetwork.into()))
     }
 
+    let maximum = if let Some(maximum) = params.maximum {
+        if maximum > MAX_ACCOUNT_ASSETS {
+            return Err(InternalRpcError::InvalidJSONRequest).context(format!("Maximum assets requested cannot be greater than {}", MAX_ACCOUNT_ASSETS))?
+        }
+        maximum
+    } else {
+        MAX_ACCOUNT_ASSETS
+    };
+
     let key = params.address.get_public_key();
     let storage = blockchain.get_storage().read().await;
-    let assets = storage.get_assets_for(key).await.context("Error while retrieving assets for account")?;
+    let assets = storage.get_partial_assets_for(key, maximum, params.cursor.as_deref()).await.context("Error while retrieving assets for account")?;
     Ok(json!(assets))
 }
 
@@ -1327,10 +2057,23 @@ async fn get_accounts<S: Storage>(context: &Context, body: Value) -> Result<Valu
 
     let storage = blockchain.get_storage().read().await;
     let mainnet = storage.is_mainnet();
-    let accounts = storage.get_registered_keys(maximum, skip, minimum_topoheight, maximum_topoheight).await
-        .context("Error while retrieving accounts")?
-        .0
-        .into_iter().map(|key| key.to_address(mainnet)).collect::<Vec<_>>();
+    let keys = if let Some(cursor) = params.cursor.as_ref() {
+        if cursor.is_mainnet() != mainnet {
+            return Err(InternalRpcError::InvalidParamsAny(BlockchainError::InvalidNetwork.into()))
+        }
+
+        // skip is ignored when a cursor is provided
+        storage.get_registered_keys_after(maximum, Some(cursor.get_public_key()), minimum_topoheight, maximum_topoheight).await
+            .context("Error while retrieving accounts")?
+    } else if skip == 0 {
+        storage.get_registered_keys_after(maximum, None, minimum_topoheight, maximum_topoheight).await
+            .context("Error while retrieving accounts")?
+    } else {
+        storage.get_registered_keys(maximum, skip, minimum_topoheight, maximum_topoheight).await
+            .context("Error while retrieving accounts")?
+            .0
+    };
+    let accounts = keys.into_iter().map(|key| key.to_address(mainnet)).collect::<Vec<_>>();
 
     Ok(json!(accounts))
 }
@@ -1379,6 +2122,28 @@ async fn get_dev_fee_thresholds<S: Storage>(_: &Context, body: Value) -> Result<
     Ok(json!(DEV_FEES))
 }
 
+// Maximum entries returned by a emission simulation
+const MAX_EMISSION_ENTRIES: u64 = 1024;
+// Maximum height that can be simulated, ~47 years with 15s block time
+const MAX_EMISSION_HEIGHT: u64 = 100_000_000;
+
+// Simulate the emission curve on a range of heights
+// It doesn't depend on the chain state, one block per height is expected
+async fn simulate_emission<S: Storage>(_: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: SimulateEmissionParams = parse_params(body)?;
+    let step = params.step.unwrap_or(1).max(1);
+    if params.end_height < params.start_height || params.end_height > MAX_EMISSION_HEIGHT {
+        return Err(InternalRpcError::InvalidJSONRequest).context(format!("Invalid range requested, start: {}, end: {}, maximum height: {}", params.start_height, params.end_height, MAX_EMISSION_HEIGHT))?
+    }
+
+    let count = (params.end_height - params.start_height) / step + 1;
+    if count > MAX_EMISSION_ENTRIES {
+        return Err(InternalRpcError::InvalidJSONRequest).context(format!("Invalid range count requested, received {} but maximum is {}", count, MAX_EMISSION_ENTRIES))?
+    }
+
+    Ok(json!(emission::simulate_emission(params.start_height, params.end_height, step)))
+}
+
 // Get size on disk of the chain database
 async fn get_size_on_disk<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     if body != Value::Null {
@@ -1414,6 +2179,78 @@ async fn get_mempool_cache<S: Storage>(context: &Context, body: Value) -> Result
     Ok(json!(cache))
 }
 
+// Pending nonce chain of an account in mempool
+// Balances after each TX are rebuilt from the final balances of the account cache
+// by adding back the outputs of the next TXs
+async fn get_account_pending<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetAccountPendingParams = parse_params(body)?;
+    if !params.address.is_normal() {
+        return Err(InternalRpcError::InvalidParamsAny(ApiError::ExpectedNormalAddress.into()))
+    }
+
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    if params.address.is_mainnet() != blockchain.get_network().is_mainnet() {
+        return Err(InternalRpcError::InvalidParamsAny(BlockchainError::InvalidNetwork.into()))
+    }
+
+    let key = params.address.get_public_key();
+    let mempool = blockchain.get_mempool().read().await;
+    let Some(cache) = mempool.get_cache_for(key) else {
+        // No pending TX, the next nonce is the one on chain
+        let storage = blockchain.get_storage().read().await;
+        let next_nonce = if storage.has_nonce(key).await.context("Error while checking nonce for account")? {
+            let (_, version) = storage.get_last_nonce(key).await.context("Error while retrieving nonce for account")?;
+            version.get_nonce()
+        } else {
+            0
+        };
+
+        return Ok(json!(GetAccountPendingResult {
+            next_nonce,
+            txs: Vec::new(),
+            balances: HashMap::new()
+        }))
+    };
+
+    let mut txs = Vec::with_capacity(cache.get_txs().len());
+    for hash in cache.get_txs() {
+        let sorted_tx = mempool.get_sorted_tx(hash).context("Error while retrieving pending TX")?;
+        txs.push((hash, sorted_tx));
+    }
+    txs.sort_by_key(|(_, sorted_tx)| sorted_tx.get_tx().get_nonce());
+
+    let mut balances = cache.get_balances().clone();
+    let mut pending = Vec::with_capacity(txs.len());
+    for (hash, sorted_tx) in txs.into_iter().rev() {
+        let tx = sorted_tx.get_tx();
+        pending.push(AccountPendingTx {
+            hash: Cow::Borrowed(hash.as_ref()),
+            nonce: tx.get_nonce(),
+            fee: tx.get_fee(),
+            size: sorted_tx.get_size(),
+            first_seen: sorted_tx.get_first_seen(),
+            balances: balances.iter().map(|(asset, ct)| (asset.clone(), ct.compress())).collect()
+        });
+
+        // Balances before this TX
+        let outputs = tx.get_expected_sender_outputs()
+            .map_err(BlockchainError::from)
+            .context("Error while computing the outputs of a pending TX")?;
+        for (asset, output) in outputs {
+            if let Some(balance) = balances.get_mut(asset) {
+                *balance += output;
+            }
+        }
+    }
+    pending.reverse();
+
+    Ok(json!(GetAccountPendingResult {
+        next_nonce: cache.get_next_nonce(),
+        txs: pending,
+        balances: cache.get_balances().iter().map(|(asset, ct)| (asset.clone(), ct.compress())).collect()
+    }))
+}
+
 async fn get_difficulty<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     if body != Value::Null {
         return Err(InternalRpcError::UnexpectedParams)
@@ -1535,8 +2372,15 @@ async fn get_multisig<S: Storage>(context: &Context, body: Value) -> Result<Valu
     let params: GetMultisigParams = parse_params(body)?;
     let blockchain: &Arc<Blockchain<S>> = context.get()?;
     let storage = blockchain.get_storage().read().await;
-    let (topoheight, multisig) = storage.get_last_multisig(&params.address.get_public_key()).await
-        .context("Error while retrieving multisig")?;
+    let key = params.address.get_public_key();
+    // Historical state if a topoheight is requested
+    let (topoheight, multisig) = match params.topoheight {
+        Some(topoheight) => storage.get_multisig_at_maximum_topoheight_for(key, topoheight).await
+            .context("Error while retrieving multisig at topoheight")?
+            .context("No multisig found at or below requested topoheight")?,
+        None => storage.get_last_multisig(key).await
+            .context("Error while retrieving multisig")?
+    };
 
     let state = match multisig.take() {
         Some(multisig) => {
@@ -1642,6 +2486,49 @@ async fn get_contract_balance<S: Storage>(context: &Context, body: Value) -> Res
     }))
 }
 
+async fn get_contract_management<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetContractModuleParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let storage = blockchain.get_storage().read().await;
+
+    let topoheight = storage.get_last_topoheight_for_contract_management(&params.contract).await?
+        .context("No management state found for contract")?;
+
+    let management = storage.get_contract_management_at_topoheight_for(&params.contract, topoheight).await?
+        .take();
+
+    Ok(json!(GetContractManagementResult {
+        owner: management.owner.to_address(storage.is_mainnet()),
+        paused: management.paused,
+        topoheight
+    }))
+}
+
+async fn get_execution_credits<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetExecutionCreditsParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    if params.address.is_mainnet() != blockchain.get_network().is_mainnet() {
+        return Err(InternalRpcError::InvalidParamsAny(BlockchainError::InvalidNetwork.into()))
+    }
+
+    let storage = blockchain.get_storage().read().await;
+    let key = params.address.get_public_key();
+    let topoheight = storage.get_last_topoheight_for_execution_credits(key).await
+        .context("Error while retrieving execution credits topoheight")?;
+
+    let credits = match topoheight {
+        Some(topoheight) => storage.get_execution_credits_at_topoheight(key, topoheight).await
+            .context("Error while retrieving execution credits")?
+            .take(),
+        None => 0
+    };
+
+    Ok(json!(GetExecutionCreditsResult {
+        credits,
+        topoheight
+    }))
+}
+
 async fn get_contract_balance_at_topoheight<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: GetContractBalanceAtTopoHeightParams = parse_params(body)?;
     let blockchain: &Arc<Blockchain<S>> = context.get()?;
@@ -1651,4 +2538,123 @@ async fn get_contract_balance_at_topoheight<S: Storage>(context: &Context, body:
         .context("Error while retrieving contract balance")?;
 
     Ok(json!(version))
-}
\ No newline at end of file
+}
+
+// Execute an InvokeContract transaction against the current chain state without applying it
+async fn simulate_contract_invoke<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: SimulateContractInvokeParams = parse_params(body)?;
+    // x2 because of hex encoding
+    if params.data.len() > MAX_TRANSACTION_SIZE * 2 {
+        return Err(InternalRpcError::InvalidJSONRequest).context(format!("Transaction size cannot be greater than {}", human_bytes(MAX_TRANSACTION_SIZE as f64)))?
+    }
+
+    let transaction = Transaction::from_hex(&params.data)
+        .map_err(|err| InternalRpcError::InvalidParamsAny(err.into()))?;
+
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let result = blockchain.simulate_contract_invoke(transaction).await?;
+
+    Ok(json!(result))
+}
+
+// Run the deploy-time validation of a contract module without creating a transaction
+async fn validate_contract<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: ValidateContractParams = parse_params(body)?;
+    // x2 because of hex encoding
+    if params.module.len() > MAX_TRANSACTION_SIZE * 2 {
+        return Err(InternalRpcError::InvalidJSONRequest).context(format!("Module size cannot be greater than {}", human_bytes(MAX_TRANSACTION_SIZE as f64)))?
+    }
+
+    let module = Module::from_hex(&params.module)
+        .map_err(|err| InternalRpcError::InvalidParamsAny(err.into()))?;
+
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    let error = validate_module(&module, blockchain.get_contract_environment()).err();
+
+    Ok(json!(ValidateContractResult {
+        valid: error.is_none(),
+        size: module.size(),
+        error: error.map(|e| e.to_string())
+    }))
+}
+
+async fn get_log_levels(_: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let (level, file_level) = logger::get_log_levels();
+    let modules = logger::get_module_log_levels().into_iter()
+        .map(|m| ModuleLogLevel {
+            module: Cow::Owned(m.module),
+            level: Cow::Owned(m.level.to_string())
+        })
+        .collect();
+
+    Ok(json!(GetLogLevelsResult {
+        level: Cow::Owned(level.to_string()),
+        file_level: Cow::Owned(file_level.to_string()),
+        modules
+    }))
+}
+
+async fn set_log_level(_: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: SetLogLevelParams = parse_params(body)?;
+    let level = match params.level {
+        Some(level) => Some(LogLevel::from_str(&level).map_err(InternalRpcError::InvalidParams)?),
+        None => None
+    };
+
+    match (params.module, level) {
+        (Some(module), Some(level)) => {
+            info!("Log level of module {} set to {}", module, level);
+            logger::set_module_log_level(module.into_owned(), level);
+        },
+        (Some(module), None) => {
+            if !logger::remove_module_log_level(&module) {
+                return Err(InternalRpcError::InvalidParams("No log level set for this module"))
+            }
+            info!("Log level of module {} removed", module);
+        },
+        (None, Some(level)) => {
+            info!("Log level set to {}", level);
+            logger::set_log_level(level);
+        },
+        (None, None) => return Err(InternalRpcError::InvalidParams("Expected a module or a level"))
+    };
+
+    Ok(json!(true))
+}
+
+async fn get_runtime_params<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    Ok(json!(runtime_params::get_runtime_params(blockchain).await))
+}
+
+async fn set_runtime_params<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: RuntimeParams = parse_params(body)?;
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    runtime_params::update_runtime_params(blockchain, params).await?;
+
+    Ok(json!(runtime_params::get_runtime_params(blockchain).await))
+}
+
+async fn get_runtime_stats<S: Storage>(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    // Don't wait on any lock so it can be used while the node is frozen
+    let blockchain: &Arc<Blockchain<S>> = context.get()?;
+    Ok(json!(GetRuntimeStatsResult {
+        runtime: blockchain.get_runtime_monitor().sample(),
+        locks: vec![
+            blockchain.get_storage().get_stats(),
+            blockchain.get_mempool().get_stats()
+        ]
+    }))
+}
@@ -0,0 +1,658 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+        Mutex as StdMutex
+    }
+};
+use anyhow::Context;
+use log::{debug, error, info, trace};
+use lru::LruCache;
+use rand::{rngs::OsRng, RngCore};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, Mutex},
+    task::JoinHandle
+};
+use xelis_common::{
+    api::stratum::*,
+    block::{Algorithm, BlockHeader, MinerWork, Worker, EXTRA_NONCE_SIZE},
+    config::TIPS_LIMIT,
+    crypto::{Address, Hash, Hashable, PublicKey},
+    difficulty::{check_difficulty, Difficulty},
+    immutable::Immutable,
+    time::{get_current_time_in_millis, TimestampMillis},
+    tokio::spawn_task
+};
+use crate::{
+    config::{DEV_PUBLIC_KEY, STABLE_LIMIT},
+    core::{
+        blockchain::Blockchain,
+        hard_fork::get_pow_algorithm_for_version,
+        storage::Storage
+    }
+};
+
+pub type SharedStratumServer<S> = Arc<StratumServer<S>>;
+
+// Job built from a block template, shared by all connections
+struct StratumJob {
+    header: BlockHeader,
+    algorithm: Algorithm,
+    difficulty: Difficulty,
+    // Shares submitted on this job, forgotten with the job
+    // The work hash contains the miner key and its extranonce1,
+    // so a share can't collide with the one of another connection
+    submitted: StdMutex<HashSet<Hash>>
+}
+
+impl StratumJob {
+    fn new(header: BlockHeader, algorithm: Algorithm, difficulty: Difficulty) -> Self {
+        Self {
+            header,
+            algorithm,
+            difficulty,
+            submitted: StdMutex::new(HashSet::new())
+        }
+    }
+
+    // Returns false if the share was already submitted
+    fn mark_submitted(&self, work_hash: Hash) -> bool {
+        match self.submitted.lock() {
+            Ok(mut submitted) => submitted.insert(work_hash),
+            Err(_) => false
+        }
+    }
+}
+
+// Rebuild the work of a share
+// The extra nonce is the extranonce1 of the connection followed by the extranonce2 of the miner
+fn build_share_work<'a>(header_work_hash: Hash, timestamp: TimestampMillis, nonce: u64, key: Cow<'a, PublicKey>, extranonce1: &[u8; STRATUM_EXTRANONCE1_SIZE], extranonce2: &[u8]) -> MinerWork<'a> {
+    let mut work = MinerWork::new(header_work_hash, timestamp);
+    work.set_nonce(nonce);
+    work.set_miner(key);
+    let extra_nonce = work.get_extra_nonce();
+    extra_nonce[..STRATUM_EXTRANONCE1_SIZE].copy_from_slice(extranonce1);
+    extra_nonce[STRATUM_EXTRANONCE1_SIZE..EXTRA_NONCE_SIZE].copy_from_slice(extranonce2);
+    work
+}
+
+// Statistics of a miner connected, exposed for pool tooling
+#[derive(Serialize, Clone)]
+pub struct StratumMinerStats {
+    pub address: String,
+    pub worker: String,
+    pub share_difficulty: Difficulty,
+    pub shares_accepted: u64,
+    pub shares_rejected: u64,
+    pub blocks_found: u64,
+    pub first_seen: TimestampMillis
+}
+
+struct Session {
+    sender: mpsc::UnboundedSender<String>,
+    extranonce1: Option<[u8; STRATUM_EXTRANONCE1_SIZE]>,
+    key: Option<PublicKey>,
+    worker: String,
+    // Difficulty requested for shares, zero if not negotiated
+    share_difficulty: Difficulty,
+    shares_accepted: u64,
+    shares_rejected: u64,
+    blocks_found: u64,
+    first_seen: TimestampMillis
+}
+
+impl Session {
+    fn new(sender: mpsc::UnboundedSender<String>, share_difficulty: Difficulty) -> Self {
+        Self {
+            sender,
+            extranonce1: None,
+            key: None,
+            worker: String::new(),
+            share_difficulty,
+            shares_accepted: 0,
+            shares_rejected: 0,
+            blocks_found: 0,
+            first_seen: get_current_time_in_millis()
+        }
+    }
+
+    // Assign a new extranonce1 to the connection
+    fn subscribe(&mut self) -> [u8; STRATUM_EXTRANONCE1_SIZE] {
+        let mut extranonce1 = [0u8; STRATUM_EXTRANONCE1_SIZE];
+        OsRng.fill_bytes(&mut extranonce1);
+        self.extranonce1 = Some(extranonce1);
+        extranonce1
+    }
+
+    // A miner must be subscribed before being authorized
+    fn authorize(&mut self, key: PublicKey, worker: &str) -> Result<(), StratumError> {
+        if self.extranonce1.is_none() {
+            return Err(StratumError::new(StratumError::NOT_SUBSCRIBED, "Not subscribed"))
+        }
+        self.key = Some(key);
+        self.worker = worker.to_owned();
+        Ok(())
+    }
+
+    // Key and extranonce1 of an authorized miner
+    fn get_credentials(&self) -> Result<(&PublicKey, &[u8; STRATUM_EXTRANONCE1_SIZE]), StratumError> {
+        match (self.key.as_ref(), self.extranonce1.as_ref()) {
+            (Some(key), Some(extranonce1)) => Ok((key, extranonce1)),
+            _ => Err(StratumError::new(StratumError::UNAUTHORIZED, "Not authorized"))
+        }
+    }
+
+    fn send(&self, message: Value) {
+        if self.sender.send(message.to_string()).is_err() {
+            debug!("Stratum connection is already closed");
+        }
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        self.send(json!(StratumRequest { id: None, method: method.to_owned(), params }));
+    }
+
+    // Difficulty required for a share of a job
+    // Block difficulty is used if lower or if no share difficulty was negotiated
+    fn get_share_difficulty(&self, block_difficulty: Difficulty) -> Difficulty {
+        if self.share_difficulty == Difficulty::default() {
+            block_difficulty
+        } else {
+            self.share_difficulty.min(block_difficulty)
+        }
+    }
+}
+
+// Stratum v1 server for miners and pool tooling
+// Jobs are built from the same block templates as the GetWork server,
+// each connection mines on its own extranonce1 range.
+// Shares are verified against the share difficulty negotiated,
+// and blocks found are submitted to the chain.
+pub struct StratumServer<S: Storage> {
+    blockchain: Arc<Blockchain<S>>,
+    sessions: Mutex<HashMap<u64, Session>>,
+    // all jobs sent to miners, kept like in GetWork server
+    jobs: Mutex<LruCache<String, Arc<StratumJob>>>,
+    last_job: Mutex<Option<String>>,
+    next_job_id: AtomicU64,
+    next_session_id: AtomicU64,
+    // Minimum share difficulty, block difficulty if not set
+    min_share_difficulty: Option<Difficulty>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    // used only when a new TX is received in mempool
+    last_notify: AtomicU64,
+    notify_rate_limit_ms: u64
+}
+
+impl<S: Storage> StratumServer<S> {
+    pub fn new(blockchain: Arc<Blockchain<S>>, min_share_difficulty: Option<Difficulty>) -> Self {
+        Self {
+            blockchain,
+            sessions: Mutex::new(HashMap::new()),
+            jobs: Mutex::new(LruCache::new(NonZeroUsize::new(STABLE_LIMIT as usize * TIPS_LIMIT).unwrap())),
+            last_job: Mutex::new(None),
+            next_job_id: AtomicU64::new(0),
+            next_session_id: AtomicU64::new(0),
+            min_share_difficulty,
+            handle: Mutex::new(None),
+            last_notify: AtomicU64::new(0),
+            notify_rate_limit_ms: 500
+        }
+    }
+
+    // Start listening for miners on the bind address
+    pub async fn start(self: &Arc<Self>, bind_address: &str) -> Result<(), anyhow::Error> {
+        let listener = TcpListener::bind(bind_address).await.context("Error while binding Stratum server")?;
+        info!("Stratum server will listen on: {}", bind_address);
+
+        let zelf = Arc::clone(self);
+        let handle = spawn_task("stratum-server", async move {
+            loop {
+                let (stream, addr) = match listener.accept().await {
+                    Ok(value) => value,
+                    Err(e) => {
+                        error!("Error while accepting Stratum connection: {}", e);
+                        continue;
+                    }
+                };
+
+                trace!("New Stratum connection from {}", addr);
+                let server = Arc::clone(&zelf);
+                spawn_task("stratum-connection", async move {
+                    if let Err(e) = server.handle_connection(stream).await {
+                        debug!("Stratum connection {} closed: {}", addr, e);
+                    }
+                });
+            }
+        });
+        *self.handle.lock().await = Some(handle);
+
+        Ok(())
+    }
+
+    pub async fn stop(&self) {
+        if let Some(handle) = self.handle.lock().await.take() {
+            handle.abort();
+            info!("Stratum server is now stopped!");
+        }
+    }
+
+    // Returns the number of miners authorized on the Stratum server
+    pub async fn count_miners(&self) -> usize {
+        self.sessions.lock().await.values()
+            .filter(|s| s.key.is_some())
+            .count()
+    }
+
+    // Returns the statistics of all miners authorized
+    pub async fn get_miners_stats(&self) -> Vec<StratumMinerStats> {
+        let mainnet = self.blockchain.get_network().is_mainnet();
+        self.sessions.lock().await.values()
+            .filter_map(|s| s.key.as_ref().map(|key| StratumMinerStats {
+                address: key.as_address(mainnet).to_string(),
+                worker: s.worker.clone(),
+                share_difficulty: s.share_difficulty,
+                shares_accepted: s.shares_accepted,
+                shares_rejected: s.shares_rejected,
+                blocks_found: s.blocks_found,
+                first_seen: s.first_seen
+            }))
+            .collect()
+    }
+
+    async fn handle_connection(self: Arc<Self>, stream: TcpStream) -> Result<(), anyhow::Error> {
+        let (read, mut write) = stream.into_split();
+        let (sender, mut receiver) = mpsc::unbounded_channel::<String>();
+
+        let id = self.next_session_id.fetch_add(1, Ordering::SeqCst);
+        self.sessions.lock().await.insert(id, Session::new(sender, self.min_share_difficulty.unwrap_or_default()));
+
+        // Writer task so a slow miner never blocks the notifications of others
+        let writer = spawn_task("stratum-writer", async move {
+            while let Some(mut line) = receiver.recv().await {
+                line.push('\n');
+                if let Err(e) = write.write_all(line.as_bytes()).await {
+                    debug!("Error while writing to Stratum connection: {}", e);
+                    break;
+                }
+            }
+        });
+
+        let res = self.read_requests(id, read).await;
+
+        writer.abort();
+        if let Some(session) = self.sessions.lock().await.remove(&id) {
+            debug!("Stratum miner {} disconnected", session.worker);
+        }
+
+        res
+    }
+
+    async fn read_requests(&self, id: u64, read: tokio::net::tcp::OwnedReadHalf) -> Result<(), anyhow::Error> {
+        let mut reader = BufReader::new(read);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = (&mut reader).take(STRATUM_MAX_LINE_SIZE as u64 + 1).read_line(&mut line).await?;
+            if read == 0 {
+                return Ok(())
+            }
+
+            if line.len() > STRATUM_MAX_LINE_SIZE {
+                return Err(anyhow::anyhow!("line exceeds {} bytes", STRATUM_MAX_LINE_SIZE))
+            }
+
+            let request: StratumRequest = serde_json::from_str(line.trim()).context("Invalid Stratum request")?;
+            let result = self.handle_request(id, &request).await;
+            if let Some(request_id) = request.id {
+                let response = match result {
+                    Ok(result) => StratumResponse { id: Some(request_id), result, error: None },
+                    Err(e) => StratumResponse { id: Some(request_id), result: Value::Null, error: Some(e) }
+                };
+
+                if let Some(session) = self.sessions.lock().await.get(&id) {
+                    session.send(json!(response));
+                }
+            }
+
+            // Job is sent once the response is received by the miner
+            if request.method == METHOD_AUTHORIZE {
+                if let Err(e) = self.send_job_to(id).await {
+                    error!("Error while sending job to Stratum miner: {}", e);
+                }
+            }
+        }
+    }
+
+    async fn handle_request(&self, id: u64, request: &StratumRequest) -> Result<Value, StratumError> {
+        match request.method.as_str() {
+            METHOD_SUBSCRIBE => {
+                let mut sessions = self.sessions.lock().await;
+                let session = sessions.get_mut(&id).ok_or_else(|| StratumError::new(StratumError::UNKNOWN, "Session not found"))?;
+                let extranonce1 = session.subscribe();
+
+                let subscription = id.to_string();
+                Ok(json!(SubscribeResult(
+                    vec![(METHOD_SET_DIFFICULTY.to_owned(), subscription.clone()), (METHOD_NOTIFY.to_owned(), subscription)],
+                    hex::encode(extranonce1),
+                    STRATUM_EXTRANONCE2_SIZE
+                )))
+            },
+            METHOD_AUTHORIZE => {
+                let AuthorizeParams(username, _) = serde_json::from_value(request.params.clone())
+                    .map_err(|e| StratumError::new(StratumError::UNKNOWN, e.to_string()))?;
+
+                let (address, worker) = split_username(&username);
+                if worker.len() > 32 {
+                    return Err(StratumError::new(StratumError::UNAUTHORIZED, "Worker name must be less or equal to 32 chars"))
+                }
+
+                let address = Address::from_string(address)
+                    .map_err(|_| StratumError::new(StratumError::UNAUTHORIZED, "Invalid miner address"))?;
+                if !address.is_normal() {
+                    return Err(StratumError::new(StratumError::UNAUTHORIZED, "Address should be in normal format"))
+                }
+
+                if address.is_mainnet() != self.blockchain.get_network().is_mainnet() {
+                    return Err(StratumError::new(StratumError::UNAUTHORIZED, "Address is not in same network state"))
+                }
+
+                let mut sessions = self.sessions.lock().await;
+                let session = sessions.get_mut(&id).ok_or_else(|| StratumError::new(StratumError::UNKNOWN, "Session not found"))?;
+                session.authorize(address.to_public_key(), worker)?;
+                debug!("Stratum miner {} authorized", username);
+
+                Ok(Value::Bool(true))
+            },
+            METHOD_SUGGEST_DIFFICULTY => {
+                let SetDifficultyParams(difficulty) = serde_json::from_value(request.params.clone())
+                    .map_err(|e| StratumError::new(StratumError::UNKNOWN, e.to_string()))?;
+
+                // Can't go below the minimum configured
+                let difficulty = match self.min_share_difficulty {
+                    Some(min) => difficulty.max(min),
+                    None => difficulty
+                };
+
+                let mut sessions = self.sessions.lock().await;
+                let session = sessions.get_mut(&id).ok_or_else(|| StratumError::new(StratumError::UNKNOWN, "Session not found"))?;
+                session.share_difficulty = difficulty;
+                session.notify(METHOD_SET_DIFFICULTY, json!(SetDifficultyParams(difficulty)));
+
+                Ok(Value::Bool(true))
+            },
+            METHOD_SUBMIT => self.handle_submit(id, &request.params).await,
+            method => {
+                debug!("Unknown Stratum method: {}", method);
+                Err(StratumError::new(StratumError::UNKNOWN, "Unknown method"))
+            }
+        }
+    }
+
+    async fn handle_submit(&self, id: u64, params: &Value) -> Result<Value, StratumError> {
+        let SubmitParams(_, job_id, extranonce2, timestamp, nonce) = serde_json::from_value(params.clone())
+            .map_err(|e| StratumError::new(StratumError::UNKNOWN, e.to_string()))?;
+
+        let job = self.jobs.lock().await.peek(&job_id).cloned()
+            .ok_or_else(|| StratumError::new(StratumError::JOB_NOT_FOUND, "Job not found"))?;
+
+        let extranonce2 = hex::decode(&extranonce2).ok()
+            .filter(|v| v.len() == STRATUM_EXTRANONCE2_SIZE)
+            .ok_or_else(|| StratumError::new(StratumError::UNKNOWN, "Invalid extranonce2"))?;
+
+        // Rebuild the work mined by the miner
+        let (work, share_difficulty) = {
+            let mut sessions = self.sessions.lock().await;
+            let session = sessions.get_mut(&id).ok_or_else(|| StratumError::new(StratumError::UNKNOWN, "Session not found"))?;
+            let (key, extranonce1) = session.get_credentials()?;
+            let work = build_share_work(job.header.get_work_hash(), timestamp, nonce, Cow::Owned(key.clone()), extranonce1, &extranonce2);
+
+            if !job.mark_submitted(work.hash()) {
+                session.shares_rejected += 1;
+                return Err(StratumError::new(StratumError::DUPLICATE_SHARE, "Duplicate share"))
+            }
+
+            (work, session.get_share_difficulty(job.difficulty))
+        };
+
+        // POW hash is expensive, don't block the runtime
+        let algorithm = job.algorithm;
+        let (work, pow_hash) = tokio::task::spawn_blocking(move || {
+            let mut worker = Worker::new();
+            worker.set_work(work, algorithm)?;
+            let hash = worker.get_pow_hash()?;
+            Ok::<_, anyhow::Error>((worker.take_work().unwrap(), hash))
+        }).await
+            .map_err(|e| StratumError::new(StratumError::UNKNOWN, e.to_string()))?
+            .map_err(|e| StratumError::new(StratumError::UNKNOWN, e.to_string()))?;
+
+        let valid_share = check_difficulty(&pow_hash, &share_difficulty).unwrap_or(false);
+        let valid_block = valid_share && check_difficulty(&pow_hash, &job.difficulty).unwrap_or(false);
+
+        let block_result = if valid_block {
+            Some(self.submit_block(&job, work).await)
+        } else {
+            None
+        };
+
+        let mut sessions = self.sessions.lock().await;
+        let session = sessions.get_mut(&id).ok_or_else(|| StratumError::new(StratumError::UNKNOWN, "Session not found"))?;
+        if !valid_share {
+            session.shares_rejected += 1;
+            return Err(StratumError::new(StratumError::LOW_DIFFICULTY_SHARE, "Low difficulty share"))
+        }
+
+        session.shares_accepted += 1;
+        match block_result {
+            Some(Ok(hash)) => {
+                session.blocks_found += 1;
+                debug!("Stratum miner {} found block {}!", session.worker, hash);
+            },
+            Some(Err(e)) => {
+                debug!("Block from Stratum miner {} rejected: {}", session.worker, e);
+                return Err(StratumError::new(StratumError::UNKNOWN, e))
+            },
+            None => {}
+        };
+
+        Ok(Value::Bool(true))
+    }
+
+    // Build the block from the job and the miner work, then add it to the chain
+    async fn submit_block(&self, job: &StratumJob, work: MinerWork<'_>) -> Result<Hash, String> {
+        let mut header = job.header.clone();
+        header.apply_miner_work(work);
+
+        let block = self.blockchain.build_block_from_header(Immutable::Owned(header)).await
+            .map_err(|e| e.to_string())?;
+        let hash = block.hash();
+        self.blockchain.add_new_block(block, true, true).await
+            .map_err(|e| e.to_string())?;
+
+        Ok(hash)
+    }
+
+    // Build a new job from the current block template
+    async fn create_job(&self) -> Result<String, anyhow::Error> {
+        let storage = self.blockchain.get_storage().read().await;
+        let header = self.blockchain.get_block_template_for_storage(&storage, DEV_PUBLIC_KEY.clone()).await.context("Error while retrieving block template")?;
+        let (difficulty, _) = self.blockchain.get_difficulty_at_tips(&*storage, header.get_tips().iter()).await.context("Error while retrieving difficulty at tips")?;
+        let algorithm = get_pow_algorithm_for_version(header.get_version());
+
+        let job_id = format!("{:x}", self.next_job_id.fetch_add(1, Ordering::SeqCst));
+        self.jobs.lock().await.put(job_id.clone(), Arc::new(StratumJob::new(header, algorithm, difficulty)));
+        *self.last_job.lock().await = Some(job_id.clone());
+
+        Ok(job_id)
+    }
+
+    // Send the difficulty and the job to a miner
+    fn send_job(&self, session: &Session, job_id: &str, job: &StratumJob, clean_jobs: bool) {
+        let (Some(key), Some(extranonce1)) = (session.key.as_ref(), session.extranonce1.as_ref()) else {
+            return;
+        };
+
+        let work = build_share_work(job.header.get_work_hash(), job.header.get_timestamp(), 0, Cow::Borrowed(key), extranonce1, &[0u8; STRATUM_EXTRANONCE2_SIZE]);
+
+        let share_difficulty = session.get_share_difficulty(job.difficulty);
+        let topoheight = self.blockchain.get_topo_height();
+        session.notify(METHOD_SET_DIFFICULTY, json!(SetDifficultyParams(share_difficulty)));
+        session.notify(METHOD_NOTIFY, json!(NotifyParams(job_id.to_owned(), work.to_hex(), job.algorithm, job.header.get_height(), topoheight, job.difficulty, clean_jobs)));
+    }
+
+    // Send the last job to a newly authorized miner
+    async fn send_job_to(&self, id: u64) -> Result<(), anyhow::Error> {
+        let last_job = self.last_job.lock().await.clone();
+        let job_id = match last_job {
+            Some(job_id) => job_id,
+            None => self.create_job().await?
+        };
+
+        let job = self.jobs.lock().await.peek(&job_id).cloned()
+            .context("No Stratum job found")?;
+
+        let sessions = self.sessions.lock().await;
+        if let Some(session) = sessions.get(&id) {
+            self.send_job(session, &job_id, &job, true);
+        }
+
+        Ok(())
+    }
+
+    // check if the last notify is older than the rate limit
+    // Returns a tuple with a boolean indicating if the rate limit is reached, and the current timestamp
+    fn is_rate_limited(&self) -> (bool, TimestampMillis) {
+        let now = get_current_time_in_millis();
+        let last_notify = self.last_notify.load(Ordering::SeqCst);
+        (now - last_notify < self.notify_rate_limit_ms, now)
+    }
+
+    // notify every miners for a new job, at most one time per rate limit
+    pub async fn notify_new_job_rate_limited(&self) -> Result<(), anyhow::Error> {
+        let (rate_limit_reached, now) = self.is_rate_limited();
+        if rate_limit_reached {
+            debug!("Rate limit reached, no need to notify Stratum miners");
+            return Ok(());
+        }
+        self.last_notify.store(now, Ordering::SeqCst);
+
+        self.notify_new_job().await
+    }
+
+    // notify every miners authorized with a new job
+    pub async fn notify_new_job(&self) -> Result<(), anyhow::Error> {
+        if self.count_miners().await == 0 {
+            debug!("No Stratum miners connected, no need to notify them");
+            return Ok(());
+        }
+
+        let job_id = self.create_job().await?;
+        let job = self.jobs.lock().await.peek(&job_id).cloned()
+            .context("No Stratum job found")?;
+
+        let sessions = self.sessions.lock().await;
+        for session in sessions.values() {
+            self.send_job(session, &job_id, &job, true);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexSet;
+    use xelis_common::{block::BlockVersion, crypto::KeyPair};
+    use super::*;
+
+    fn new_session() -> Session {
+        let (sender, _) = mpsc::unbounded_channel();
+        Session::new(sender, Difficulty::default())
+    }
+
+    fn new_job(difficulty: u64) -> StratumJob {
+        let key = KeyPair::new().get_public_key().compress();
+        let header = BlockHeader::new(BlockVersion::V2, 1, 0, IndexSet::new(), [0u8; EXTRA_NONCE_SIZE], key, IndexSet::new());
+        StratumJob::new(header, Algorithm::V2, Difficulty::from_u64(difficulty))
+    }
+
+    #[test]
+    fn test_subscribe_before_authorize() {
+        let key = KeyPair::new().get_public_key().compress();
+        let mut session = new_session();
+
+        // Not subscribed yet
+        assert_eq!(session.authorize(key.clone(), "worker").unwrap_err().0, StratumError::NOT_SUBSCRIBED);
+        assert_eq!(session.get_credentials().unwrap_err().0, StratumError::UNAUTHORIZED);
+
+        // Subscribed but not authorized
+        let extranonce1 = session.subscribe();
+        assert_eq!(session.get_credentials().unwrap_err().0, StratumError::UNAUTHORIZED);
+
+        session.authorize(key.clone(), "worker").unwrap();
+        let (session_key, session_extranonce1) = session.get_credentials().unwrap();
+        assert_eq!(*session_key, key);
+        assert_eq!(*session_extranonce1, extranonce1);
+        assert_eq!(session.worker, "worker");
+
+        // Each connection has its own extranonce1
+        assert_ne!(new_session().subscribe(), extranonce1);
+    }
+
+    #[test]
+    fn test_share_extranonce_layout() {
+        let key = KeyPair::new().get_public_key().compress();
+        let extranonce1 = [1u8; STRATUM_EXTRANONCE1_SIZE];
+        let extranonce2 = [2u8; STRATUM_EXTRANONCE2_SIZE];
+        let header_work_hash = Hash::new([3u8; 32]);
+
+        let work = build_share_work(header_work_hash.clone(), 10, 20, Cow::Borrowed(&key), &extranonce1, &extranonce2);
+        let (hash, timestamp, nonce, miner, extra_nonce) = work.take();
+        assert_eq!(hash, header_work_hash);
+        assert_eq!(timestamp, 10);
+        assert_eq!(nonce, 20);
+        assert_eq!(miner.as_deref(), Some(&key));
+        assert_eq!(extra_nonce[..STRATUM_EXTRANONCE1_SIZE], extranonce1);
+        assert_eq!(extra_nonce[STRATUM_EXTRANONCE1_SIZE..], extranonce2);
+    }
+
+    #[test]
+    fn test_share_difficulty() {
+        let block_difficulty = Difficulty::from_u64(1000);
+
+        // Not negotiated, block difficulty is used
+        let mut session = new_session();
+        assert_eq!(session.get_share_difficulty(block_difficulty), block_difficulty);
+
+        session.share_difficulty = Difficulty::from_u64(100);
+        assert_eq!(session.get_share_difficulty(block_difficulty), Difficulty::from_u64(100));
+
+        // Never above the block difficulty
+        session.share_difficulty = Difficulty::from_u64(5000);
+        assert_eq!(session.get_share_difficulty(block_difficulty), block_difficulty);
+    }
+
+    #[test]
+    fn test_duplicate_share() {
+        let key = KeyPair::new().get_public_key().compress();
+        let extranonce1 = [1u8; STRATUM_EXTRANONCE1_SIZE];
+        let job = new_job(1000);
+
+        let share = |extranonce2: u8| build_share_work(job.header.get_work_hash(), 10, 20, Cow::Borrowed(&key), &extranonce1, &[extranonce2; STRATUM_EXTRANONCE2_SIZE]).hash();
+        assert!(job.mark_submitted(share(0)));
+        assert!(!job.mark_submitted(share(0)));
+        assert!(job.mark_submitted(share(1)));
+
+        // Shares are tracked per job
+        let other = new_job(2000);
+        assert!(other.mark_submitted(share(0)));
+        assert!(!other.mark_submitted(share(0)));
+    }
+}
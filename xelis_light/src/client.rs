@@ -0,0 +1,215 @@
+use std::borrow::Cow;
+use anyhow::{bail, Context, Result};
+use log::{debug, info, warn};
+use serde::{de::DeserializeOwned, Serialize};
+use xelis_common::{
+    account::VersionedBalance,
+    api::daemon::{
+        BlockHeaderEntry,
+        GetBalanceProofParams,
+        GetBalanceProofResult,
+        GetBalancesRootAtTopoHeightParams,
+        GetBalancesRootAtTopoHeightResult,
+        GetTopoHeightRangeParams
+    },
+    block::TopoHeight,
+    crypto::{
+        merkle::hash_balance_leaf,
+        Address,
+        Hash
+    },
+    json_rpc::JsonRPCClient
+};
+use crate::{
+    config::{
+        DEFAULT_SYNC_DEPTH,
+        MAX_HEADERS_PER_REQUEST,
+        RESYNC_DEPTH
+    },
+    header_chain::{verify_header, HeaderChain}
+};
+
+// A daemon queried by the light client
+struct Daemon {
+    address: String,
+    client: JsonRPCClient
+}
+
+impl Daemon {
+    async fn call_with<P: Serialize, R: DeserializeOwned>(&self, method: &str, params: &P) -> Result<R> {
+        self.client.call_with(method, params).await
+            .with_context(|| format!("Error while calling '{}' on daemon {}", method, self.address))
+    }
+}
+
+// Light client syncing the headers only
+// Every response is compared across all the daemons: one honest daemon is enough to detect a lie
+pub struct LightClient {
+    // The first daemon serves the proofs, the others are used to cross-check it
+    daemons: Vec<Daemon>,
+    chain: HeaderChain,
+    start_topoheight: Option<TopoHeight>
+}
+
+impl LightClient {
+    pub fn new(addresses: Vec<String>, start_topoheight: Option<TopoHeight>) -> Result<Self> {
+        if addresses.is_empty() {
+            bail!("At least one daemon address is required");
+        }
+
+        let daemons = addresses.into_iter()
+            .map(|address| Daemon {
+                client: JsonRPCClient::new(format!("{}/json_rpc", address.trim_end_matches('/'))),
+                address
+            })
+            .collect();
+
+        Ok(Self {
+            daemons,
+            chain: HeaderChain::new(),
+            start_topoheight
+        })
+    }
+
+    // Topoheight of the last verified header
+    pub fn get_topoheight(&self) -> Option<TopoHeight> {
+        self.chain.get_topoheight()
+    }
+
+    // Lowest topoheight known by all the daemons
+    async fn get_common_topoheight(&self) -> Result<TopoHeight> {
+        let mut topoheight = TopoHeight::MAX;
+        for daemon in self.daemons.iter() {
+            let value: TopoHeight = daemon.client.call("get_topoheight").await
+                .with_context(|| format!("Error while retrieving topoheight of daemon {}", daemon.address))?;
+            topoheight = topoheight.min(value);
+        }
+
+        Ok(topoheight)
+    }
+
+    // Request the headers from all the daemons and check they are the same
+    async fn get_cross_checked_headers(&self, start_topoheight: TopoHeight, end_topoheight: TopoHeight) -> Result<Vec<BlockHeaderEntry<'static>>> {
+        let params = GetTopoHeightRangeParams {
+            start_topoheight: Some(start_topoheight),
+            end_topoheight: Some(end_topoheight)
+        };
+
+        let (primary, others) = self.daemons.split_first().context("No daemon available")?;
+        let headers: Vec<BlockHeaderEntry<'static>> = primary.call_with("get_block_headers_range_by_topoheight", &params).await?;
+        if headers.len() as u64 != end_topoheight - start_topoheight + 1 {
+            bail!("Daemon {} sent {} headers for range {}-{}", primary.address, headers.len(), start_topoheight, end_topoheight);
+        }
+
+        for daemon in others {
+            let entries: Vec<BlockHeaderEntry<'static>> = daemon.call_with("get_block_headers_range_by_topoheight", &params).await?;
+            if entries.len() != headers.len() {
+                bail!("Daemon {} sent {} headers but {} were expected", daemon.address, entries.len(), headers.len());
+            }
+
+            for (expected, entry) in headers.iter().zip(entries.iter()) {
+                if expected.hash != entry.hash || expected.difficulty != entry.difficulty {
+                    bail!("Daemon {} disagrees with daemon {} on block at topoheight {}", daemon.address, primary.address, expected.topoheight);
+                }
+            }
+        }
+
+        Ok(headers)
+    }
+
+    // Sync and verify the headers up to the lowest topoheight of the daemons
+    pub async fn sync(&mut self) -> Result<()> {
+        let target = self.get_common_topoheight().await?;
+        let start = match (self.chain.get_topoheight(), self.chain.get_checkpoint_topoheight()) {
+            // Re-request the last headers to detect a DAG re-ordering
+            (Some(topoheight), Some(checkpoint)) => topoheight.saturating_sub(RESYNC_DEPTH).max(checkpoint),
+            _ => self.start_topoheight.unwrap_or(target.saturating_sub(DEFAULT_SYNC_DEPTH))
+        };
+
+        if start > target {
+            warn!("Daemons are at topoheight {} but sync should start at {}", target, start);
+            return Ok(())
+        }
+
+        let mut current = start;
+        while current <= target {
+            let end = (current + MAX_HEADERS_PER_REQUEST - 1).min(target);
+            debug!("Syncing headers from topoheight {} to {}", current, end);
+            for entry in self.get_cross_checked_headers(current, end).await? {
+                if let Some(hash) = self.chain.get_hash_at_topoheight(entry.topoheight) {
+                    if *hash == *entry.hash {
+                        continue;
+                    }
+
+                    warn!("Block at topoheight {} changed from {} to {}, rewinding", entry.topoheight, hash, entry.hash);
+                    self.chain.rewind_to(entry.topoheight);
+                }
+
+                let header = verify_header(&entry)?;
+                self.chain.add_header(entry.topoheight, entry.hash.into_owned(), &header)?;
+            }
+
+            current = end + 1;
+        }
+
+        Ok(())
+    }
+
+    // Block hash at a topoheight, verified against the synced headers
+    // or against all the daemons if it is below the checkpoint
+    async fn get_verified_hash_at_topoheight(&self, topoheight: TopoHeight) -> Result<Hash> {
+        if let Some(hash) = self.chain.get_hash_at_topoheight(topoheight) {
+            return Ok(hash.clone())
+        }
+
+        let entry = self.get_cross_checked_headers(topoheight, topoheight).await?
+            .pop()
+            .context("No header received")?;
+        verify_header(&entry)?;
+
+        Ok(entry.hash.into_owned())
+    }
+
+    // Retrieve the balance of an account at the last synced topoheight and verify its proof
+    // Returns the topoheight at which the version was written
+    pub async fn verify_balance(&self, address: &Address, asset: &Hash) -> Result<(TopoHeight, VersionedBalance)> {
+        let topoheight = self.chain.get_topoheight().context("No header synced yet")?;
+        let (primary, others) = self.daemons.split_first().context("No daemon available")?;
+
+        let result: GetBalanceProofResult<'static> = primary.call_with("get_balance_proof", &GetBalanceProofParams {
+            address: Cow::Borrowed(address),
+            asset: Cow::Borrowed(asset),
+            topoheight: Some(topoheight)
+        }).await?;
+
+        if result.topoheight > topoheight {
+            bail!("Balance version at topoheight {} is above the requested topoheight {}", result.topoheight, topoheight);
+        }
+
+        // The proof must link the version to the root
+        let leaf = hash_balance_leaf(address.get_public_key(), asset, &result.version);
+        if !result.proof.verify(&leaf, &result.root) {
+            bail!("Invalid balance proof at topoheight {} from daemon {}", result.topoheight, primary.address);
+        }
+
+        // The root must be attached to a verified block
+        let block_hash = self.get_verified_hash_at_topoheight(result.topoheight).await?;
+        if block_hash != *result.block_hash {
+            bail!("Balance proof is attached to block {} but block {} is at topoheight {}", result.block_hash, block_hash, result.topoheight);
+        }
+
+        // Roots are not committed in the headers, all the daemons must agree on it
+        for daemon in others {
+            let root: GetBalancesRootAtTopoHeightResult = daemon.call_with("get_balances_root_at_topoheight", &GetBalancesRootAtTopoHeightParams {
+                topoheight: result.topoheight
+            }).await?;
+
+            if root.root != result.root || root.block_hash != block_hash {
+                bail!("Daemon {} disagrees on the balances root at topoheight {}", daemon.address, result.topoheight);
+            }
+        }
+
+        info!("Balance {} verified at topoheight {} (written at topoheight {}, root {})", asset, topoheight, result.topoheight, result.root);
+        Ok((result.topoheight, result.version))
+    }
+}
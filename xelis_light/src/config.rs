@@ -0,0 +1,18 @@
+// Default daemon RPC address
+pub const DEFAULT_DAEMON_ADDRESS: &str = "http://127.0.0.1:8080";
+
+// Headers synced before the current topoheight when no start topoheight is set
+pub const DEFAULT_SYNC_DEPTH: u64 = 1000;
+
+// Maximum headers requested per call, same limit as the daemon
+pub const MAX_HEADERS_PER_REQUEST: u64 = 256;
+
+// Headers re-requested at each round to detect a DAG re-ordering
+pub const RESYNC_DEPTH: u64 = 64;
+
+// Tips of the first headers after the checkpoint may not be synced
+// Any tip must be at most this number of heights below its block
+pub const TIPS_DEPTH: u64 = 8;
+
+// Seconds to wait between two sync rounds
+pub const DEFAULT_SYNC_INTERVAL: u64 = 5;
@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, HashMap};
+use anyhow::{bail, Context, Result};
+use log::trace;
+use xelis_common::{
+    api::daemon::BlockHeaderEntry,
+    block::{
+        Algorithm,
+        BlockHeader,
+        BlockVersion,
+        TopoHeight
+    },
+    crypto::{Hash, Hashable},
+    difficulty::check_difficulty,
+    serializer::Serializer
+};
+use crate::config::TIPS_DEPTH;
+
+// PoW algorithm used by a block version
+// Same schedule as the daemon: xelis-hash v2 since the first hard fork
+fn get_pow_algorithm_for_version(version: BlockVersion) -> Algorithm {
+    match version {
+        BlockVersion::V0 => Algorithm::V1,
+        _ => Algorithm::V2
+    }
+}
+
+// Verify a header alone: its hash and its PoW against its difficulty
+pub fn verify_header(entry: &BlockHeaderEntry) -> Result<BlockHeader> {
+    let header = BlockHeader::from_hex(&entry.header)
+        .with_context(|| format!("Invalid header at topoheight {}", entry.topoheight))?;
+
+    let hash = header.hash();
+    if hash != *entry.hash {
+        bail!("Header at topoheight {} has hash {} but {} was announced", entry.topoheight, hash, entry.hash);
+    }
+
+    let algorithm = get_pow_algorithm_for_version(header.get_version());
+    let pow_hash = header.get_pow_hash(algorithm)?;
+    if !check_difficulty(&pow_hash, &entry.difficulty)? {
+        bail!("Block {} at topoheight {} does not meet its difficulty {}", hash, entry.topoheight, entry.difficulty);
+    }
+
+    Ok(header)
+}
+
+// Verified headers of the light client, indexed by topoheight
+// The first header added is the checkpoint, it is trusted once all daemons agree on it
+pub struct HeaderChain {
+    // Topoheight and height of the checkpoint
+    checkpoint: Option<(TopoHeight, u64)>,
+    // Block hash at each topoheight
+    hashes: BTreeMap<TopoHeight, Hash>,
+    // Height of each synced block
+    heights: HashMap<Hash, u64>
+}
+
+impl HeaderChain {
+    pub fn new() -> Self {
+        Self {
+            checkpoint: None,
+            hashes: BTreeMap::new(),
+            heights: HashMap::new()
+        }
+    }
+
+    // Topoheight of the last synced header
+    pub fn get_topoheight(&self) -> Option<TopoHeight> {
+        self.hashes.last_key_value().map(|(topoheight, _)| *topoheight)
+    }
+
+    pub fn get_checkpoint_topoheight(&self) -> Option<TopoHeight> {
+        self.checkpoint.map(|(topoheight, _)| topoheight)
+    }
+
+    pub fn get_hash_at_topoheight(&self, topoheight: TopoHeight) -> Option<&Hash> {
+        self.hashes.get(&topoheight)
+    }
+
+    // Remove all the headers at and above this topoheight
+    pub fn rewind_to(&mut self, topoheight: TopoHeight) -> usize {
+        let removed = self.hashes.split_off(&topoheight);
+        for hash in removed.values() {
+            self.heights.remove(hash);
+        }
+
+        if self.hashes.is_empty() {
+            self.checkpoint = None;
+        }
+
+        removed.len()
+    }
+
+    // Add the header following the last synced one
+    // It must be verified with `verify_header` first
+    pub fn add_header(&mut self, topoheight: TopoHeight, hash: Hash, header: &BlockHeader) -> Result<()> {
+        trace!("add header {} at topoheight {}", hash, topoheight);
+        let height = header.get_height();
+        let checkpoint_height = match self.get_topoheight() {
+            Some(top) if top + 1 != topoheight => bail!("Header at topoheight {} is not following topoheight {}", topoheight, top),
+            Some(_) => self.checkpoint.map(|(_, height)| height).context("No checkpoint set")?,
+            None => {
+                self.checkpoint = Some((topoheight, height));
+                height
+            }
+        };
+
+        let mut tips_height = None;
+        let mut all_tips_known = true;
+        for tip in header.get_tips() {
+            match self.heights.get(tip) {
+                Some(tip_height) => {
+                    if *tip_height >= height {
+                        bail!("Tip {} at height {} is not below block {} at height {}", tip, tip_height, hash, height);
+                    }
+                    tips_height = tips_height.max(Some(*tip_height));
+                },
+                // Tips ordered before the checkpoint are not synced
+                None if height <= checkpoint_height + TIPS_DEPTH => all_tips_known = false,
+                None => bail!("Tip {} of block {} is unknown", tip, hash)
+            }
+        }
+
+        // Height of a block is the highest height of its tips + 1
+        if let (true, Some(tips_height)) = (all_tips_known, tips_height) {
+            if tips_height + 1 != height {
+                bail!("Block {} has height {} but its highest tip is at height {}", hash, height, tips_height);
+            }
+        }
+
+        self.hashes.insert(topoheight, hash.clone());
+        self.heights.insert(hash, height);
+
+        Ok(())
+    }
+}
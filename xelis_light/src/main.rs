@@ -0,0 +1,98 @@
+pub mod config;
+pub mod header_chain;
+pub mod client;
+
+use std::time::Duration;
+use anyhow::{Context, Result};
+use clap::Parser;
+use log::{error, info};
+use xelis_common::{
+    block::TopoHeight,
+    config::{VERSION, XELIS_ASSET},
+    crypto::{Address, Hash},
+    prompt::{
+        logger::LogOptions,
+        LogLevel,
+        Prompt
+    },
+    tokio::time::sleep
+};
+use crate::{
+    client::LightClient,
+    config::{DEFAULT_DAEMON_ADDRESS, DEFAULT_SYNC_INTERVAL}
+};
+
+#[derive(Parser)]
+#[clap(version = VERSION, about = "XELIS is an innovative cryptocurrency built from scratch with BlockDAG, Homomorphic Encryption, Zero-Knowledge Proofs, and Smart Contracts.")]
+#[command(styles = xelis_common::get_cli_styles())]
+pub struct Config {
+    /// Daemon RPC address to use
+    /// Set it several times to cross-check every response across daemons
+    /// The first one is used to request the balance proofs
+    #[clap(long = "daemon-address", default_value_t = String::from(DEFAULT_DAEMON_ADDRESS))]
+    daemon_addresses: Vec<String>,
+    /// Address to track, its balances are verified after each sync
+    #[clap(long)]
+    address: Option<String>,
+    /// Assets to verify for the tracked address
+    /// By default, only XELIS is verified
+    #[clap(long = "asset")]
+    assets: Vec<Hash>,
+    /// Topoheight of the first header to sync
+    /// It is trusted as checkpoint once all daemons agree on it
+    /// By default, the last 1000 blocks are synced
+    #[clap(long)]
+    start_topoheight: Option<TopoHeight>,
+    /// Seconds to wait between two sync rounds
+    #[clap(long, default_value_t = DEFAULT_SYNC_INTERVAL)]
+    sync_interval: u64,
+    /// Set log level
+    #[clap(long, value_enum, default_value_t = LogLevel::Info)]
+    log_level: LogLevel,
+    /// Disable the log file
+    #[clap(long)]
+    disable_file_logging: bool,
+    /// Logs directory
+    #[clap(long, default_value_t = String::from("logs/"))]
+    logs_path: String,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    let config = Config::parse();
+    let _prompt = Prompt::new(config.log_level, &config.logs_path, "xelis-light.log", config.disable_file_logging, false, false, false, Vec::new(), config.log_level, LogOptions::default())?;
+
+    let address = match config.address.as_ref() {
+        Some(address) => Some(Address::from_string(address).context("Invalid address")?),
+        None => None
+    };
+
+    let assets = if config.assets.is_empty() {
+        vec![XELIS_ASSET]
+    } else {
+        config.assets
+    };
+
+    info!("Starting light client with {} daemon(s)", config.daemon_addresses.len());
+    let mut client = LightClient::new(config.daemon_addresses, config.start_topoheight)?;
+    loop {
+        match client.sync().await {
+            Ok(()) => {
+                if let Some(topoheight) = client.get_topoheight() {
+                    info!("Headers verified up to topoheight {}", topoheight);
+                }
+
+                if let Some(address) = address.as_ref() {
+                    for asset in assets.iter() {
+                        if let Err(e) = client.verify_balance(address, asset).await {
+                            error!("Error while verifying balance {}: {:#}", asset, e);
+                        }
+                    }
+                }
+            },
+            Err(e) => error!("Error while syncing headers: {:#}", e)
+        }
+
+        sleep(Duration::from_secs(config.sync_interval)).await;
+    }
+}
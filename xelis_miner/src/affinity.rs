@@ -0,0 +1,34 @@
+use log::warn;
+
+// Check if the mining threads can be pinned to a CPU core on this platform
+pub fn is_supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+// Pin the current thread to the requested CPU core
+// Cores are used in round robin if there is more threads than cores
+// Returns false if the thread couldn't be pinned
+#[cfg(target_os = "linux")]
+pub fn pin_current_thread(core: usize) -> bool {
+    let cores = std::thread::available_parallelism()
+        .map(|v| v.get())
+        .unwrap_or(1);
+
+    // Safety: the set is zeroed and only written through the libc helpers
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core % cores, &mut set);
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            warn!("Couldn't pin thread to CPU core #{}: {}", core, std::io::Error::last_os_error());
+            return false
+        }
+    }
+
+    true
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pin_current_thread(core: usize) -> bool {
+    warn!("Couldn't pin thread to CPU core #{}: not supported on this platform", core);
+    false
+}
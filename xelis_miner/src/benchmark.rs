@@ -0,0 +1,123 @@
+use std::{fs, thread, time::Instant};
+use log::info;
+use xelis_common::{
+    block::{Algorithm, MinerWork, Worker},
+    crypto::{Hash, HASH_SIZE},
+    time::get_current_time_in_millis,
+    utils::format_hashrate
+};
+use crate::{
+    affinity,
+    hasher::{find_pow_hash, HashBackend}
+};
+
+// Energy counter of the CPU package exposed by the RAPL interface, in microjoules
+const ENERGY_COUNTER_PATH: &str = "/sys/class/powercap/intel-rapl:0/energy_uj";
+
+// Statistics of one benchmarked configuration
+pub struct BenchmarkResult {
+    pub threads: usize,
+    pub affinity: bool,
+    // Mean hashrate of all the rounds
+    pub mean: f64,
+    // Hashrate reached by at least 95% of the rounds
+    pub p95: f64,
+    // Hashes per joule, only if the energy counter is readable
+    pub efficiency: Option<f64>
+}
+
+fn read_energy_counter() -> Option<u64> {
+    fs::read_to_string(ENERGY_COUNTER_PATH).ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+// Run one round where each thread computes the requested iterations
+// Returns the hashrate and the energy consumed in joules if available
+fn run_round(threads: usize, iterations: usize, algorithm: Algorithm, backend: HashBackend, pin_threads: bool) -> (f64, Option<f64>) {
+    let energy = read_energy_counter();
+    let start = Instant::now();
+    let handles: Vec<_> = (0..threads).map(|id| {
+        thread::spawn(move || {
+            if pin_threads {
+                affinity::pin_current_thread(id);
+            }
+
+            let job = MinerWork::new(Hash::zero(), get_current_time_in_millis());
+            let mut worker = Worker::new();
+            worker.set_work(job, algorithm).unwrap();
+
+            // No hash can be below a zero target, all iterations are computed
+            find_pow_hash(backend, &mut worker, iterations as u64, &[0u8; HASH_SIZE]).unwrap();
+        })
+    }).collect();
+
+    // wait on all threads
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let hashrate = (threads * iterations) as f64 / elapsed;
+    // Counter may have wrapped around during the round
+    let joules = energy.zip(read_energy_counter())
+        .and_then(|(start, end)| end.checked_sub(start))
+        .map(|microjoules| microjoules as f64 / 1_000_000f64);
+
+    (hashrate, joules)
+}
+
+// Benchmark every thread count, with and without CPU affinity if supported
+// Each configuration is run several rounds to compute its statistics
+// Returns the configuration with the best mean hashrate
+pub fn benchmark(max_threads: usize, iterations: usize, rounds: usize, algorithm: Algorithm, backend: HashBackend) -> Option<BenchmarkResult> {
+    info!("{0: <10} | {1: <10} | {2: <13} | {3: <13} | {4: <13}", "Threads", "Affinity", "Mean", "P95", "Hashes/Joule");
+
+    let affinities: &[bool] = if affinity::is_supported() {
+        &[false, true]
+    } else {
+        &[false]
+    };
+
+    let mut best: Option<BenchmarkResult> = None;
+    for &pin_threads in affinities {
+        for threads in 1..=max_threads {
+            let mut hashrates = Vec::with_capacity(rounds);
+            let mut total_joules = Some(0f64);
+            for _ in 0..rounds.max(1) {
+                let (hashrate, joules) = run_round(threads, iterations, algorithm, backend, pin_threads);
+                hashrates.push(hashrate);
+                total_joules = total_joules.zip(joules).map(|(total, joules)| total + joules);
+            }
+
+            hashrates.sort_by(|a, b| a.total_cmp(b));
+            let mean = hashrates.iter().sum::<f64>() / hashrates.len() as f64;
+            let p95 = hashrates[(hashrates.len() - 1) / 20];
+            let total_hashes = (threads * iterations * hashrates.len()) as f64;
+            let efficiency = total_joules.filter(|joules| *joules > 0f64)
+                .map(|joules| total_hashes / joules);
+
+            info!(
+                "{0: <10} | {1: <10} | {2: <13} | {3: <13} | {4: <13}",
+                threads,
+                pin_threads,
+                format_hashrate(mean),
+                format_hashrate(p95),
+                efficiency.map(|v| format!("{:.2}", v)).unwrap_or_else(|| "N/A".to_owned())
+            );
+
+            if best.as_ref().map_or(true, |best| mean > best.mean) {
+                best = Some(BenchmarkResult {
+                    threads,
+                    affinity: pin_threads,
+                    mean,
+                    p95,
+                    efficiency
+                });
+            }
+        }
+    }
+
+    best
+}
@@ -0,0 +1,148 @@
+use anyhow::Error;
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt,
+    StreamExt
+};
+use log::{debug, warn};
+use tokio::{net::TcpStream, time::timeout};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{Error as TungsteniteError, Message},
+    MaybeTlsStream,
+    WebSocketStream
+};
+use xelis_common::{
+    api::daemon::SubmitMinerWorkParams,
+    block::MinerWork,
+    crypto::Address,
+    serializer::Serializer
+};
+use crate::{
+    endpoint::Endpoint,
+    stratum::{self, StratumReader, StratumWriter},
+    SocketMessage,
+    CONNECT_TIMEOUT
+};
+
+type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+// Connection to a getwork endpoint, using the GetWork WebSocket or the Stratum protocol
+pub enum Connection {
+    GetWork(WebSocket),
+    Stratum(StratumReader, StratumWriter)
+}
+
+impl Connection {
+    // Split the connection to read the jobs and submit the blocks at the same time
+    pub fn split(self) -> (JobReader, JobWriter) {
+        match self {
+            Self::GetWork(client) => {
+                let (write, read) = client.split();
+                (JobReader::GetWork(read), JobWriter::GetWork(write))
+            },
+            Self::Stratum(reader, writer) => (JobReader::Stratum(reader), JobWriter::Stratum(writer))
+        }
+    }
+}
+
+pub enum JobReader {
+    GetWork(SplitStream<WebSocket>),
+    Stratum(StratumReader)
+}
+
+impl JobReader {
+    // Read the next message from the endpoint, None if the connection is closed
+    pub async fn next_message(&mut self) -> Result<Option<SocketMessage>, Error> {
+        match self {
+            Self::GetWork(read) => {
+                let Some(message) = read.next().await else {
+                    return Ok(None)
+                };
+
+                debug!("Received message from daemon: {:?}", message);
+                match message? {
+                    Message::Text(text) => Ok(Some(serde_json::from_slice(text.as_bytes())?)),
+                    Message::Close(reason) => {
+                        let reason: String = if let Some(reason) = reason {
+                            reason.to_string()
+                        } else {
+                            "No reason".into()
+                        };
+                        warn!("Daemon has closed the WebSocket connection with us: {}", reason);
+                        Ok(None)
+                    },
+                    _ => {
+                        warn!("Unexpected message from WebSocket");
+                        Ok(None)
+                    }
+                }
+            },
+            Self::Stratum(reader) => reader.next_message().await
+        }
+    }
+}
+
+pub enum JobWriter {
+    GetWork(SplitSink<WebSocket, Message>),
+    Stratum(StratumWriter)
+}
+
+impl JobWriter {
+    // Submit the work found
+    pub async fn submit(&mut self, work: MinerWork<'_>) -> Result<(), Error> {
+        match self {
+            Self::GetWork(write) => {
+                let submit = serde_json::json!(SubmitMinerWorkParams { miner_work: work.to_hex() }).to_string();
+                write.send(Message::Text(submit)).await?;
+            },
+            Self::Stratum(writer) => writer.submit(work).await?
+        };
+
+        Ok(())
+    }
+
+    pub async fn close(&mut self) -> Result<(), Error> {
+        match self {
+            Self::GetWork(write) => write.close().await?,
+            Self::Stratum(writer) => writer.close().await?
+        };
+
+        Ok(())
+    }
+}
+
+// Open the connection to an endpoint
+pub async fn connect_endpoint(endpoint: &Endpoint, address: &Address, worker: &str) -> Result<Connection, Error> {
+    if endpoint.is_stratum() {
+        return match timeout(CONNECT_TIMEOUT, stratum::connect(endpoint.get_address(), address, worker)).await {
+            Ok(Ok((reader, writer))) => Ok(Connection::Stratum(reader, writer)),
+            Ok(Err(e)) => Err(Error::msg(format!("Error while connecting to {}: {:#}", endpoint.get_address(), e))),
+            Err(_) => Err(Error::msg(format!("Error while connecting to {}: timed out", endpoint.get_address())))
+        }
+    }
+
+    let url = format!("{}/getwork/{}/{}", endpoint.get_address(), address.to_string(), worker);
+    let (client, response) = match timeout(CONNECT_TIMEOUT, connect_async(url)).await {
+        Ok(Ok(value)) => value,
+        Ok(Err(e)) => {
+            if let TungsteniteError::Http(e) = e {
+                let body: String = e.into_body()
+                    .map_or(
+                        "Unknown error".to_owned(),
+                        |v| String::from_utf8_lossy(&v).to_string()
+                    );
+                return Err(Error::msg(format!("Error while connecting to {}, got an unexpected response: {}", endpoint.get_address(), body)));
+            }
+            return Err(Error::msg(format!("Error while connecting to {}: {}", endpoint.get_address(), e)));
+        },
+        Err(_) => return Err(Error::msg(format!("Error while connecting to {}: timed out", endpoint.get_address())))
+    };
+
+    let status = response.status();
+    if status.is_server_error() || status.is_client_error() {
+        return Err(Error::msg(format!("Error while connecting to {}, got an unexpected response: {}", endpoint.get_address(), status.as_str())));
+    }
+
+    Ok(Connection::GetWork(client))
+}
@@ -0,0 +1,146 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use serde::Serialize;
+use xelis_common::{
+    api::stratum::STRATUM_PREFIX,
+    utils::sanitize_daemon_address
+};
+
+// Shares statistics of one getwork endpoint
+#[derive(Serialize)]
+pub struct EndpointStats {
+    pub address: String,
+    pub connected: bool,
+    pub submitted: usize,
+    pub accepted: usize,
+    pub rejected: usize,
+    pub disconnections: usize
+}
+
+// A daemon or pool getwork endpoint
+pub struct Endpoint {
+    address: String,
+    connected: AtomicBool,
+    submitted: AtomicUsize,
+    accepted: AtomicUsize,
+    rejected: AtomicUsize,
+    disconnections: AtomicUsize
+}
+
+impl Endpoint {
+    fn new(address: &str) -> Self {
+        // Stratum addresses are used as is, others are WebSocket addresses
+        let address = if address.starts_with(STRATUM_PREFIX) {
+            address.to_owned()
+        } else {
+            sanitize_daemon_address(address)
+        };
+
+        Self {
+            address,
+            connected: AtomicBool::new(false),
+            submitted: AtomicUsize::new(0),
+            accepted: AtomicUsize::new(0),
+            rejected: AtomicUsize::new(0),
+            disconnections: AtomicUsize::new(0)
+        }
+    }
+
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+
+    // Check if the endpoint uses the Stratum protocol instead of the GetWork WebSocket
+    pub fn is_stratum(&self) -> bool {
+        self.address.starts_with(STRATUM_PREFIX)
+    }
+
+    pub fn set_connected(&self, connected: bool) {
+        if !self.connected.swap(connected, Ordering::SeqCst) || connected {
+            return;
+        }
+
+        self.disconnections.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn on_block_submitted(&self) {
+        self.submitted.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn on_block_accepted(&self) {
+        self.accepted.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn on_block_rejected(&self) {
+        self.rejected.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn get_stats(&self) -> EndpointStats {
+        EndpointStats {
+            address: self.address.clone(),
+            connected: self.connected.load(Ordering::SeqCst),
+            submitted: self.submitted.load(Ordering::SeqCst),
+            accepted: self.accepted.load(Ordering::SeqCst),
+            rejected: self.rejected.load(Ordering::SeqCst),
+            disconnections: self.disconnections.load(Ordering::SeqCst)
+        }
+    }
+}
+
+// Getwork endpoints ordered by priority, the first one is the primary
+// The miner fails over to the next one on disconnect or stale job,
+// and fails back to a higher priority one once it is reachable again
+pub struct EndpointList {
+    endpoints: Vec<Endpoint>,
+    // index of the endpoint in use
+    current: AtomicUsize
+}
+
+impl EndpointList {
+    pub fn new(primary: &str, fallbacks: &[String]) -> Self {
+        let mut endpoints = vec![Endpoint::new(primary)];
+        for address in fallbacks {
+            let endpoint = Endpoint::new(address);
+            // Ignore duplicated entries to not connect twice to the same endpoint
+            if endpoints.iter().all(|e| e.address != endpoint.address) {
+                endpoints.push(endpoint);
+            }
+        }
+
+        Self {
+            endpoints,
+            current: AtomicUsize::new(0)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub fn get(&self, index: usize) -> &Endpoint {
+        &self.endpoints[index]
+    }
+
+    pub fn get_current_index(&self) -> usize {
+        self.current.load(Ordering::SeqCst)
+    }
+
+    pub fn get_current(&self) -> &Endpoint {
+        self.get(self.get_current_index())
+    }
+
+    pub fn set_current(&self, index: usize) {
+        self.current.store(index, Ordering::SeqCst);
+    }
+
+    // Index of the next endpoint to try after the current one
+    // Returns true if all endpoints have been tried and it's back to the primary
+    pub fn next(&self) -> bool {
+        let next = (self.get_current_index() + 1) % self.endpoints.len();
+        self.set_current(next);
+        next == 0
+    }
+
+    pub fn get_stats(&self) -> Vec<EndpointStats> {
+        self.endpoints.iter().map(Endpoint::get_stats).collect()
+    }
+}
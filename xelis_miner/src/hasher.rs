@@ -0,0 +1,114 @@
+use std::fmt;
+use clap::ValueEnum;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use xelis_common::{
+    block::{Worker, WorkerError},
+    crypto::{Hash, HASH_SIZE}
+};
+
+// Instruction set used to compile the mining loop
+// Each variant is a copy of the same loop compiled with its target features enabled,
+// the one to use is selected at runtime based on the CPU features detected.
+// The generic one is always available as fallback.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashBackend {
+    // Select the best one supported by the CPU
+    Auto,
+    Generic,
+    Avx2,
+    Avx512,
+    Neon
+}
+
+impl Default for HashBackend {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+impl fmt::Display for HashBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Auto => "auto",
+            Self::Generic => "generic",
+            Self::Avx2 => "avx2",
+            Self::Avx512 => "avx512",
+            Self::Neon => "neon"
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl HashBackend {
+    // Best backend supported by the CPU
+    pub fn detect() -> Self {
+        [Self::Avx512, Self::Avx2, Self::Neon].into_iter()
+            .find(Self::is_supported)
+            .unwrap_or(Self::Generic)
+    }
+
+    // Check if the CPU supports the instructions of the backend
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Self::Auto | Self::Generic => true,
+            #[cfg(target_arch = "x86_64")]
+            Self::Avx2 => is_x86_feature_detected!("avx2"),
+            #[cfg(target_arch = "x86_64")]
+            Self::Avx512 => is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("avx512bw"),
+            #[cfg(target_arch = "aarch64")]
+            Self::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+            #[allow(unreachable_patterns)]
+            _ => false
+        }
+    }
+
+    // Resolve the requested backend to one usable on this CPU
+    pub fn resolve(self) -> Self {
+        let backend = match self {
+            Self::Auto => Self::detect(),
+            backend if !backend.is_supported() => {
+                warn!("Hash backend {} is not supported by this CPU, fallback to generic", backend);
+                Self::Generic
+            },
+            backend => backend
+        };
+
+        info!("Hash backend used: {}", backend);
+        backend
+    }
+}
+
+// Hash a batch of nonces with the selected backend
+// Returns the first hash below the target, if any
+pub fn find_pow_hash(backend: HashBackend, worker: &mut Worker, count: u64, target: &[u8; HASH_SIZE]) -> Result<Option<Hash>, WorkerError> {
+    match backend {
+        // Safety: backends are only selected if supported by the CPU, see `HashBackend::resolve`
+        #[cfg(target_arch = "x86_64")]
+        HashBackend::Avx512 => unsafe { find_pow_hash_avx512(worker, count, target) },
+        #[cfg(target_arch = "x86_64")]
+        HashBackend::Avx2 => unsafe { find_pow_hash_avx2(worker, count, target) },
+        #[cfg(target_arch = "aarch64")]
+        HashBackend::Neon => unsafe { find_pow_hash_neon(worker, count, target) },
+        _ => worker.find_pow_hash(count, target)
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn find_pow_hash_avx2(worker: &mut Worker, count: u64, target: &[u8; HASH_SIZE]) -> Result<Option<Hash>, WorkerError> {
+    worker.find_pow_hash(count, target)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,avx512f,avx512bw")]
+unsafe fn find_pow_hash_avx512(worker: &mut Worker, count: u64, target: &[u8; HASH_SIZE]) -> Result<Option<Hash>, WorkerError> {
+    worker.find_pow_hash(count, target)
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn find_pow_hash_neon(worker: &mut Worker, count: u64, target: &[u8; HASH_SIZE]) -> Result<Option<Hash>, WorkerError> {
+    worker.find_pow_hash(count, target)
+}
@@ -1,7 +1,14 @@
 pub mod config;
+pub mod hasher;
+pub mod benchmark;
+pub mod affinity;
+pub mod endpoint;
+pub mod connection;
+pub mod stratum;
+pub mod stats;
 
 use std::{
-    fs::File,
+    fs::{self, File},
     io::Write,
     path::Path,
     sync::{
@@ -11,14 +18,21 @@ use std::{
             AtomicUsize,
             Ordering
         },
+        Arc,
         RwLock
     },
     thread,
     time::Duration
 };
-use crate::config::DEFAULT_DAEMON_ADDRESS;
-use futures_util::{StreamExt, SinkExt};
+use crate::{
+    config::DEFAULT_DAEMON_ADDRESS,
+    connection::{connect_endpoint, Connection},
+    endpoint::{Endpoint, EndpointList},
+    stats::MinerStats,
+    hasher::{find_pow_hash, HashBackend}
+};
 use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
 use tokio::{
     select,
     sync::{
@@ -27,24 +41,17 @@ use tokio::{
         Mutex
     },
     task::JoinHandle,
-    time::Instant
+    time::{interval_at, Instant}
 };
 #[cfg(feature = "api_stats")]
 use tokio::{
-    io::AsyncWriteExt,
-    net::TcpListener
-};
-use tokio_tungstenite::{
-    connect_async,
-    tungstenite::{
-        Message,
-        Error as TungsteniteError
-    }
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    time::timeout
 };
 use xelis_common::{
     api::daemon::{
         GetMinerWorkResult,
-        SubmitMinerWorkParams,
     },
     async_handler,
     block::{
@@ -56,15 +63,22 @@ use xelis_common::{
     crypto::{
         Address,
         Hash,
+        HASH_SIZE
     },
     difficulty::{
-        check_difficulty_against_target,
         compute_difficulty_target,
         difficulty_from_hash,
         Difficulty
     },
     prompt::{
-        command::CommandManager,
+        argument::ArgumentManager,
+        command::{
+            Command,
+            CommandError,
+            CommandHandler,
+            CommandManager
+        },
+        logger::LogOptions,
         Color,
         LogLevel,
         ModuleConfig,
@@ -72,12 +86,10 @@ use xelis_common::{
         ShareablePrompt
     },
     serializer::Serializer,
-    time::get_current_time_in_millis,
     tokio::spawn_task,
     utils::{
         format_difficulty,
-        format_hashrate,
-        sanitize_daemon_address
+        format_hashrate
     }
 };
 use clap::Parser;
@@ -99,10 +111,22 @@ fn default_daemon_address() -> String {
     DEFAULT_DAEMON_ADDRESS.to_owned()
 }
 
+fn default_stale_job_timeout() -> u64 {
+    120
+}
+
+fn default_failback_interval() -> u64 {
+    60
+}
+
 fn default_iterations() -> usize {
     100
 }
 
+fn default_benchmark_rounds() -> usize {
+    5
+}
+
 fn default_log_filename() -> String {
     "xelis-miner.log".to_owned()
 }
@@ -173,6 +197,15 @@ pub struct BenchmarkConfig {
     #[clap(long, default_value_t = 100)]
     #[serde(default = "default_iterations")]
     iterations: usize,
+    /// Rounds to run for each configuration to compute its statistics
+    #[clap(long, default_value_t = 5)]
+    #[serde(default = "default_benchmark_rounds")]
+    benchmark_rounds: usize,
+    /// Write the best configuration found to this JSON file
+    /// It can then be used with `--config-file`
+    #[clap(long)]
+    #[serde(default)]
+    benchmark_output: Option<String>,
 }
 
 #[derive(Parser, Serialize, Deserialize)]
@@ -189,9 +222,25 @@ pub struct Config {
     #[clap(short, long)]
     miner_address: Option<Address>,
     /// Daemon address to connect to for mining
+    /// Use the `stratum+tcp://` prefix to connect to a daemon or pool using the Stratum protocol.
     #[clap(long, default_value_t = String::from(DEFAULT_DAEMON_ADDRESS))]
     #[serde(default = "default_daemon_address")]
     daemon_address: String,
+    /// Fallback daemon or pool addresses, ordered by priority
+    /// The miner switches to the next one when the current one is unreachable
+    /// or doesn't send any new job, and switches back once a higher priority one is reachable again.
+    #[clap(long)]
+    #[serde(default)]
+    fallback_daemon_addresses: Vec<String>,
+    /// Seconds without any new job before considering the current one as stale
+    /// and switching to the next address. Set to 0 to disable it.
+    #[clap(long, default_value_t = 120)]
+    #[serde(default = "default_stale_job_timeout")]
+    stale_job_timeout: u64,
+    /// Interval in seconds to check if a higher priority address is reachable again
+    #[clap(long, default_value_t = 60)]
+    #[serde(default = "default_failback_interval")]
+    failback_interval: u64,
     /// Bind address for stats API
     #[cfg(feature = "api_stats")]
     #[clap(long)]
@@ -204,6 +253,15 @@ pub struct Config {
     #[clap(short, long, default_value_t = String::from("default"))]
     #[serde(default = "default_worker_name")]
     worker: String,
+    /// Instruction set used to compute the hashes
+    /// By default, the best one supported by the CPU is detected.
+    #[clap(long, value_enum, default_value_t = HashBackend::Auto)]
+    #[serde(default)]
+    hash_backend: HashBackend,
+    /// Pin each mining thread to a CPU core
+    #[clap(long)]
+    #[serde(default)]
+    cpu_affinity: bool,
     /// JSON File to load the configuration from
     #[clap(long)]
     #[serde(skip)]
@@ -228,7 +286,8 @@ enum ThreadNotification<'a> {
 pub enum SocketMessage {
     NewJob(GetMinerWorkResult),
     BlockAccepted,
-    BlockRejected(String)
+    BlockRejected(String),
+    ShareAccepted
 }
 
 static WEBSOCKET_CONNECTED: AtomicBool = AtomicBool::new(false);
@@ -245,9 +304,13 @@ lazy_static! {
     static ref HASHRATE_LAST_TIME: Mutex<Instant> = Mutex::new(Instant::now());
 }
 
-// After how many iterations we update the timestamp of the block to avoid too much CPU usage 
+// Nonces hashed per batch before checking for a new job and updating the timestamp of the block
+// to avoid too much CPU usage
 const UPDATE_EVERY_NONCE: u64 = 10;
 
+// Maximum time to wait on a connection to a getwork endpoint
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
     let mut config: Config = Config::parse();
@@ -272,8 +335,14 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Configuration to save once tuned by the benchmark
+    let template = match config.benchmark.benchmark_output.is_some() {
+        true => Some(serde_json::to_value(&config).context("Error while serializing config")?),
+        false => None
+    };
+
     let log = config.log;
-    let prompt = Prompt::new(log.log_level, &log.logs_path, &log.filename_log, log.disable_file_logging, log.disable_file_log_date_based, log.disable_log_color, !log.disable_interactive_mode, log.logs_modules, log.file_log_level.unwrap_or(log.log_level))?;
+    let prompt = Prompt::new(log.log_level, &log.logs_path, &log.filename_log, log.disable_file_logging, log.disable_file_log_date_based, log.disable_log_color, !log.disable_interactive_mode, log.logs_modules, log.file_log_level.unwrap_or(log.log_level), LogOptions::default())?;
 
     // Prevent the user to block the program by selecting text in CLI
     #[cfg(target_os = "windows")]
@@ -294,11 +363,28 @@ async fn main() -> Result<()> {
 
 
     info!("Total threads to use: {} (detected: {})", threads, detected_threads);
+    let backend = config.hash_backend.resolve();
 
     if let Some(algorithm) = config.benchmark.benchmark {
         info!("Benchmark mode enabled, miner will try up to {} threads", threads);
-        benchmark(threads as usize, config.benchmark.iterations, algorithm);
+        let best = benchmark::benchmark(threads as usize, config.benchmark.iterations, config.benchmark.benchmark_rounds, algorithm, backend);
         info!("Benchmark finished");
+
+        if let Some(best) = best {
+            info!("Best configuration: {} threads, CPU affinity: {}, hashrate: {}", best.threads, best.affinity, format_hashrate(best.mean));
+            if let (Some(path), Some(mut template)) = (config.benchmark.benchmark_output, template) {
+                template["num_threads"] = json!(best.threads);
+                template["cpu_affinity"] = json!(best.affinity);
+                template["hash_backend"] = json!(backend);
+                // Next runs must mine and not benchmark again
+                template["benchmark"]["benchmark"] = Value::Null;
+
+                let json = serde_json::to_string_pretty(&template).context("Error while serializing config file")?;
+                fs::write(&path, json).context("Error while writing config file")?;
+                info!("Best configuration written to {}", path);
+            }
+        }
+
         return Ok(())
     }
 
@@ -308,26 +394,30 @@ async fn main() -> Result<()> {
         warn!("Attention, the number of threads used may not be optimal, recommended is: {}", detected_threads);
     }
 
+    let stats = Arc::new(MinerStats::new(threads as usize));
+
     // broadcast channel to send new jobs / exit command to all threads
     let (sender, _) = broadcast::channel::<ThreadNotification>(threads as usize);
     // mpsc channel to send from threads to the "communication" task.
     let (block_sender, block_receiver) = mpsc::channel::<MinerWork>(threads as usize);
     for id in 0..threads {
         debug!("Starting thread #{}", id);
-        if let Err(e) = start_thread(id, sender.subscribe(), block_sender.clone()) {
+        if let Err(e) = start_thread(id, sender.subscribe(), block_sender.clone(), backend, config.cpu_affinity, stats.clone()) {
             error!("Error while creating Mining Thread #{}: {}", id, e);
         }
     }
 
+    let endpoints = Arc::new(EndpointList::new(&config.daemon_address, &config.fallback_daemon_addresses));
+
     // start communication task
-    let task = spawn_task("communication", communication_task(config.daemon_address, sender.clone(), block_receiver, address, config.worker));
+    let task = spawn_task("communication", communication_task(endpoints.clone(), stats.clone(), sender.clone(), block_receiver, address, config.worker, config.stale_job_timeout, config.failback_interval));
     
     let stats_task: Option<JoinHandle<Result<()>>>;
     #[cfg(feature = "api_stats")]
     {
         // start stats task
         stats_task = match config.api_bind_address {
-            Some(addr) => Some(spawn_task("broadcast", broadcast_stats_task(addr))),
+            Some(addr) => Some(spawn_task("broadcast", broadcast_stats_task(addr, endpoints.clone(), stats.clone()))),
             None => None,
         };
     }
@@ -336,7 +426,7 @@ async fn main() -> Result<()> {
         stats_task = None;
     }
 
-    if let Err(e) = run_prompt(prompt).await {
+    if let Err(e) = run_prompt(prompt, endpoints, stats).await {
         error!("Error on running prompt: {}", e);
     }
 
@@ -357,34 +447,39 @@ async fn main() -> Result<()> {
 }
 
 // This Tokio task will runs indefinitely until the user stops the miner himself.
-// It maintains a http listener and sends stats on connection in json.
+// It maintains a http listener and sends stats on connection.
+// Stats are sent in the Prometheus text format on `/metrics`, in json otherwise.
 #[cfg(feature = "api_stats")]
-async fn broadcast_stats_task(broadcast_address: String) -> Result<()> {
+async fn broadcast_stats_task(broadcast_address: String, endpoints: Arc<EndpointList>, stats: Arc<MinerStats>) -> Result<()> {
     info!("Starting broadcast task");
     loop {
         // Start TCP listener
-        let listener = TcpListener::bind(broadcast_address).await?;
+        let listener = TcpListener::bind(&broadcast_address).await?;
         loop {
             let (mut socket, _) = listener.accept().await?;
 
-            let blocks_found = BLOCKS_FOUND.load(Ordering::SeqCst);
-            let blocks_rejected = BLOCKS_REJECTED.load(Ordering::SeqCst);
-            let hashrate = HASHRATE.load(Ordering::SeqCst);
+            // Only the request line is needed to select the format
+            let mut buffer = [0u8; 1024];
+            let read = match timeout(Duration::from_secs(5), socket.read(&mut buffer)).await {
+                Ok(Ok(read)) => read,
+                _ => {
+                    debug!("Couldn't read request on stats API");
+                    continue;
+                }
+            };
+            let request = String::from_utf8_lossy(&buffer[..read]);
+            let path = request.split_whitespace().nth(1).unwrap_or("/");
 
-            // Build JSON data
-            let data = serde_json::json!({
-                "accepted": blocks_found,
-                "rejected": blocks_rejected,
-                "hashrate": hashrate,
-                "hashrate_formatted": format_hashrate(hashrate as f64),
-            });
+            let (content_type, contents) = if path == "/metrics" {
+                ("text/plain; version=0.0.4", build_prometheus_stats(&endpoints, &stats))
+            } else {
+                ("application/json", build_json_stats(&endpoints, &stats).to_string())
+            };
 
             // Build HTTP response
             let status_line = "HTTP/1.1 200 OK\r\n";
-            let content_type = "Content-Type: application/json\r\n";
-            let contents = data.to_string();
             let length = contents.len();
-            let response = format!("{status_line}{content_type}Content-Length: {length}\r\n\r\n{contents}");
+            let response = format!("{status_line}Content-Type: {content_type}\r\nContent-Length: {length}\r\n\r\n{contents}");
 
             // Send HTTP repsonse and close socket
             AsyncWriteExt::write_all(&mut socket, response.as_bytes())
@@ -394,163 +489,238 @@ async fn broadcast_stats_task(broadcast_address: String) -> Result<()> {
     }
 }
 
+// Age of the current job in milliseconds
+#[cfg(feature = "api_stats")]
+fn get_job_age() -> Option<u128> {
+    JOB_ELAPSED.read().ok()
+        .and_then(|v| *v)
+        .map(|v| v.elapsed().as_millis())
+}
 
-// Benchmark the miner with the specified number of threads and iterations
-// It will output the total time, total iterations, time per PoW and hashrate for each number of threads
-fn benchmark(threads: usize, iterations: usize, algorithm: Algorithm) {
-    info!("{0: <10} | {1: <10} | {2: <16} | {3: <13} | {4: <13}", "Threads", "Total Time", "Total Iterations", "Time/PoW (ms)", "Hashrate");
-
-    for bench in 1..=threads {
-        let start = Instant::now();
-        let mut handles = vec![];
-        for _ in 0..bench {
-            let job = MinerWork::new(Hash::zero(), get_current_time_in_millis());
-            let mut worker = Worker::new();
-            worker.set_work(job, algorithm).unwrap();
+#[cfg(feature = "api_stats")]
+fn build_json_stats(endpoints: &EndpointList, stats: &MinerStats) -> Value {
+    let hashrate = HASHRATE.load(Ordering::SeqCst);
+    json!({
+        "accepted": BLOCKS_FOUND.load(Ordering::SeqCst),
+        "rejected": BLOCKS_REJECTED.load(Ordering::SeqCst),
+        "hashrate": hashrate,
+        "hashrate_formatted": format_hashrate(hashrate as f64),
+        "connected": WEBSOCKET_CONNECTED.load(Ordering::SeqCst),
+        "topoheight": CURRENT_TOPO_HEIGHT.load(Ordering::SeqCst),
+        "uptime": stats.get_uptime().as_secs(),
+        "job_age": get_job_age(),
+        "submit_latency": {
+            "last": stats.get_last_latency(),
+            "average": stats.get_average_latency()
+        },
+        "threads": stats.get_threads(),
+        "endpoints": endpoints.get_stats(),
+    })
+}
 
-            let handle = thread::spawn(move || {
-                for _ in 0..iterations {
-                    let _ = worker.get_pow_hash().unwrap();
-                    worker.increase_nonce().unwrap();
-                }
-            });
-            handles.push(handle);
+#[cfg(feature = "api_stats")]
+fn build_prometheus_stats(endpoints: &EndpointList, stats: &MinerStats) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, values: Vec<(String, String)>| {
+        let _ = writeln!(out, "# HELP xelis_miner_{} {}", name, help);
+        let _ = writeln!(out, "# TYPE xelis_miner_{} {}", name, kind);
+        for (labels, value) in values {
+            let _ = writeln!(out, "xelis_miner_{}{} {}", name, labels, value);
         }
+    };
 
-        for handle in handles { // wait on all threads
-            handle.join().unwrap();
-        }
-        let duration = start.elapsed().as_millis();
-        let hashrate = format_hashrate(1000f64 / (duration as f64 / (bench*iterations) as f64));
-        info!("{0: <10} | {1: <10} | {2: <16} | {3: <13} | {4: <13}", bench, duration, bench*iterations, duration/(bench*iterations) as u128, hashrate);
-    }
+    let single = |value: String| vec![(String::new(), value)];
+    metric("hashrate", "gauge", "Current hashrate in hashes per second", single(HASHRATE.load(Ordering::SeqCst).to_string()));
+    metric("accepted_total", "counter", "Blocks or shares accepted", single(BLOCKS_FOUND.load(Ordering::SeqCst).to_string()));
+    metric("rejected_total", "counter", "Blocks or shares rejected", single(BLOCKS_REJECTED.load(Ordering::SeqCst).to_string()));
+    metric("connected", "gauge", "Whether the miner is connected", single((WEBSOCKET_CONNECTED.load(Ordering::SeqCst) as u8).to_string()));
+    metric("topoheight", "gauge", "Topoheight of the daemon", single(CURRENT_TOPO_HEIGHT.load(Ordering::SeqCst).to_string()));
+    metric("uptime_seconds", "counter", "Seconds since the miner started", single(stats.get_uptime().as_secs().to_string()));
+    metric("job_age_seconds", "gauge", "Seconds since the current job was received", single(get_job_age().map_or(0f64, |v| v as f64 / 1000f64).to_string()));
+    metric("submit_latency_seconds", "gauge", "Latency of the last submit response", single((stats.get_last_latency() as f64 / 1000f64).to_string()));
+    metric("submit_latency_average_seconds", "gauge", "Average latency of the submit responses", single((stats.get_average_latency() as f64 / 1000f64).to_string()));
+
+    let threads = stats.get_threads();
+    metric("thread_hashes_total", "counter", "Hashes computed by each thread", threads.iter().map(|t| (format!("{{thread=\"{}\"}}", t.id), t.hashes.to_string())).collect());
+    metric("thread_blocks_found_total", "counter", "Blocks or shares found by each thread", threads.iter().map(|t| (format!("{{thread=\"{}\"}}", t.id), t.blocks_found.to_string())).collect());
+
+    let endpoints = endpoints.get_stats();
+    metric("endpoint_accepted_total", "counter", "Blocks or shares accepted by each endpoint", endpoints.iter().map(|e| (format!("{{endpoint=\"{}\"}}", e.address), e.accepted.to_string())).collect());
+    metric("endpoint_rejected_total", "counter", "Blocks or shares rejected by each endpoint", endpoints.iter().map(|e| (format!("{{endpoint=\"{}\"}}", e.address), e.rejected.to_string())).collect());
+    metric("endpoint_connected", "gauge", "Whether the miner is connected to each endpoint", endpoints.iter().map(|e| (format!("{{endpoint=\"{}\"}}", e.address), (e.connected as u8).to_string())).collect());
+
+    out
 }
 
 // this Tokio task will runs indefinitely until the user stop himself the miner.
-// It maintains a WebSocket connection with the daemon and notify all threads when it receive a new job.
+// It maintains a connection with the daemon or pool and notify all threads when it receive a new job.
 // Its also the task who have the job to send directly the new block found by one of the threads.
 // This allow mining threads to only focus on mining and receiving jobs through memory channels.
-async fn communication_task(daemon_address: String, job_sender: broadcast::Sender<ThreadNotification<'_>>, mut block_receiver: mpsc::Receiver<MinerWork<'_>>, address: Address, worker: String) {
+// On disconnect or stale job, it fails over to the next endpoint configured
+// and periodically tries to fail back to a higher priority one.
+async fn communication_task(endpoints: Arc<EndpointList>, stats: Arc<MinerStats>, job_sender: broadcast::Sender<ThreadNotification<'_>>, mut block_receiver: mpsc::Receiver<MinerWork<'_>>, address: Address, worker: String, stale_job_timeout: u64, failback_interval: u64) {
     info!("Starting communication task");
-    let daemon_address = sanitize_daemon_address(&daemon_address);
+    // Connection opened to a higher priority endpoint while checking for failback
+    let mut failback_connection: Option<Connection> = None;
     'main: loop {
-        info!("Trying to connect to {}", daemon_address);
-        let client = match connect_async(format!("{}/getwork/{}/{}", daemon_address, address.to_string(), worker)).await {
-            Ok((client, response)) => {
-                let status = response.status();
-                if status.is_server_error() || status.is_client_error() {
-                    error!("Error while connecting to {}, got an unexpected response: {}", daemon_address, status.as_str());
-                    warn!("Trying to connect to WebSocket again in 10 seconds...");
-                    tokio::time::sleep(Duration::from_secs(10)).await;
-                    continue 'main;
-                }
-                client
-            },
-            Err(e) => {
-                if let TungsteniteError::Http(e) = e {
-                    let body: String = e.into_body()
-                        .map_or(
-                            "Unknown error".to_owned(),
-                            |v| String::from_utf8_lossy(&v).to_string()
-                        );
-                    error!("Error while connecting to {}, got an unexpected response: {}", daemon_address, body);
-                } else {
-                    error!("Error while connecting to {}: {}", daemon_address, e);
+        let index = endpoints.get_current_index();
+        let endpoint = endpoints.get(index);
+        let connection = match failback_connection.take() {
+            Some(connection) => connection,
+            None => {
+                info!("Trying to connect to {}", endpoint.get_address());
+                match connect_endpoint(endpoint, &address, &worker).await {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        error!("{}", e);
+                        // all endpoints have been tried, wait before trying again from the primary
+                        if endpoints.next() {
+                            warn!("Trying to connect again in 10 seconds...");
+                            tokio::time::sleep(Duration::from_secs(10)).await;
+                        } else {
+                            warn!("Failing over to {}", endpoints.get_current().get_address());
+                        }
+                        continue 'main;
+                    }
                 }
-
-                warn!("Trying to connect to WebSocket again in 10 seconds...");
-                tokio::time::sleep(Duration::from_secs(10)).await;
-                continue 'main;
             }
         };
+
+        endpoint.set_connected(true);
         WEBSOCKET_CONNECTED.store(true, Ordering::SeqCst);
-        info!("Connected successfully to {}", daemon_address);
-        let (mut write, mut read) = client.split();
+        info!("Connected successfully to {}", endpoint.get_address());
+
+        let connected_at = Instant::now();
+        let failback_period = Duration::from_secs(failback_interval.max(1));
+        let mut failback_check = interval_at(connected_at + failback_period, failback_period);
+        let stale_period = Duration::from_secs(stale_job_timeout.max(1));
+        let mut stale_check = interval_at(connected_at + stale_period, stale_period);
+
+        let (mut reader, mut writer) = connection.split();
         loop {
             select! {
-                Some(message) = read.next() => { // read all messages from daemon
-                    debug!("Received message from daemon: {:?}", message);
-                    match handle_websocket_message(message, &job_sender).await {
-                        Ok(exit) => {
-                            if exit {
-                                debug!("Exiting communication task");
-                                break;
-                            }
+                message = reader.next_message() => { // read all messages from daemon
+                    match message {
+                        Ok(Some(message)) => handle_socket_message(message, &job_sender, endpoint, &stats),
+                        Ok(None) => {
+                            debug!("Connection with {} has been closed", endpoint.get_address());
+                            break;
                         },
                         Err(e) => {
-                            error!("Error while handling message from WebSocket: {}", e);
+                            error!("Error while handling message from {}: {}", endpoint.get_address(), e);
                             break;
                         }
                     }
                 },
                 Some(work) = block_receiver.recv() => { // send all valid blocks found to the daemon
                     info!("submitting new block found...");
-                    let submit = serde_json::json!(SubmitMinerWorkParams { miner_work: work.to_hex() }).to_string();
-                    if let Err(e) = write.send(Message::Text(submit)).await {
+                    if let Err(e) = writer.submit(work).await {
                         error!("Error while sending the block found to the daemon: {}", e);
                         break;
                     }
+                    endpoint.on_block_submitted();
+                    stats.on_submit();
                     debug!("Block found has been sent to daemon");
+                },
+                _ = stale_check.tick(), if stale_job_timeout > 0 => {
+                    // last job received on this connection
+                    let last_job = JOB_ELAPSED.read().ok()
+                        .and_then(|v| *v)
+                        .map_or(connected_at, |v| v.max(connected_at));
+
+                    if last_job.elapsed() >= stale_period {
+                        warn!("No new job received from {} since {} seconds", endpoint.get_address(), last_job.elapsed().as_secs());
+                        break;
+                    }
+                },
+                _ = failback_check.tick(), if index > 0 => {
+                    for i in 0..index {
+                        let candidate = endpoints.get(i);
+                        debug!("Checking if {} is reachable again", candidate.get_address());
+                        if let Ok(connection) = connect_endpoint(candidate, &address, &worker).await {
+                            info!("Failing back to {}", candidate.get_address());
+                            failback_connection = Some(connection);
+                            endpoints.set_current(i);
+                            break;
+                        }
+                    }
+
+                    if failback_connection.is_some() {
+                        if let Err(e) = writer.close().await {
+                            debug!("Error while closing connection with {}: {}", endpoint.get_address(), e);
+                        }
+                        break;
+                    }
                 }
             }
         }
 
+        endpoint.set_connected(false);
+        stats.clear_pending_submits();
         WEBSOCKET_CONNECTED.store(false, Ordering::SeqCst);
         if job_sender.send(ThreadNotification::WebSocketClosed).is_err() {
             error!("Error while sending WebSocketClosed message to threads");
         }
 
-        warn!("Trying to connect to WebSocket again in 10 seconds...");
-        tokio::time::sleep(Duration::from_secs(10)).await;
+        if failback_connection.is_none() {
+            // all endpoints have been tried, wait before trying again from the primary
+            if endpoints.next() {
+                warn!("Trying to connect again in 10 seconds...");
+                tokio::time::sleep(Duration::from_secs(10)).await;
+            } else {
+                warn!("Failing over to {}", endpoints.get_current().get_address());
+            }
+        }
     }
 }
 
-async fn handle_websocket_message(message: Result<Message, TungsteniteError>, job_sender: &broadcast::Sender<ThreadNotification<'_>>) -> Result<bool, Error> {
-    match message? {
-        Message::Text(text) => {
-            debug!("new message from daemon: {}", text);
-            match serde_json::from_slice::<SocketMessage>(text.as_bytes())? {
-                SocketMessage::NewJob(job) => {
-                    info!("New job received: difficulty {} at height {}", format_difficulty(job.difficulty), job.height);
-                    let block = MinerWork::from_hex(&job.miner_work).context("Error while decoding new job received from daemon")?;
-                    CURRENT_TOPO_HEIGHT.store(job.topoheight, Ordering::SeqCst);
-                    JOB_ELAPSED.write().unwrap().replace(Instant::now());
-
-                    if let Err(e) = job_sender.send(ThreadNotification::NewJob(job.algorithm, block, job.difficulty, job.height)) {
-                        error!("Error while sending new job to threads: {}", e);
-                    }
-                },
-                SocketMessage::BlockAccepted => {
-                    BLOCKS_FOUND.fetch_add(1, Ordering::SeqCst);
-                    info!("Block submitted has been accepted by network !");
-                },
-                SocketMessage::BlockRejected(err) => {
-                    BLOCKS_REJECTED.fetch_add(1, Ordering::SeqCst);
-                    error!("Block submitted has been rejected by network: {}", err);
+fn handle_socket_message(message: SocketMessage, job_sender: &broadcast::Sender<ThreadNotification<'_>>, endpoint: &Endpoint, stats: &MinerStats) {
+    match message {
+        SocketMessage::NewJob(job) => {
+            info!("New job received: difficulty {} at height {}", format_difficulty(job.difficulty), job.height);
+            let block = match MinerWork::from_hex(&job.miner_work) {
+                Ok(block) => block,
+                Err(e) => {
+                    error!("Error while decoding new job received from daemon: {}", e);
+                    return;
                 }
+            };
+            CURRENT_TOPO_HEIGHT.store(job.topoheight, Ordering::SeqCst);
+            JOB_ELAPSED.write().unwrap().replace(Instant::now());
+
+            if let Err(e) = job_sender.send(ThreadNotification::NewJob(job.algorithm, block, job.difficulty, job.height)) {
+                error!("Error while sending new job to threads: {}", e);
             }
         },
-        Message::Close(reason) => {
-            let reason: String = if let Some(reason) = reason {
-                reason.to_string()
-            } else {
-                "No reason".into()
-            };
-            warn!("Daemon has closed the WebSocket connection with us: {}", reason);
-            return Ok(true);
+        SocketMessage::BlockAccepted => {
+            BLOCKS_FOUND.fetch_add(1, Ordering::SeqCst);
+            endpoint.on_block_accepted();
+            stats.on_submit_response();
+            info!("Block submitted has been accepted by network !");
         },
-        _ => {
-            warn!("Unexpected message from WebSocket");
-            return Ok(true);
+        SocketMessage::BlockRejected(err) => {
+            BLOCKS_REJECTED.fetch_add(1, Ordering::SeqCst);
+            endpoint.on_block_rejected();
+            stats.on_submit_response();
+            error!("Block submitted has been rejected by network: {}", err);
+        },
+        SocketMessage::ShareAccepted => {
+            endpoint.on_block_accepted();
+            stats.on_submit_response();
+            debug!("Share submitted has been accepted");
         }
-    };
-
-    Ok(false)
+    }
 }
 
-fn start_thread(id: u16, mut job_receiver: broadcast::Receiver<ThreadNotification<'static>>, block_sender: mpsc::Sender<MinerWork<'static>>) -> Result<(), Error> {
+fn start_thread(id: u16, mut job_receiver: broadcast::Receiver<ThreadNotification<'static>>, block_sender: mpsc::Sender<MinerWork<'static>>, backend: HashBackend, cpu_affinity: bool, stats: Arc<MinerStats>) -> Result<(), Error> {
     let builder = thread::Builder::new().name(format!("Mining Thread #{}", id));
     builder.spawn(move || {
+        if cpu_affinity {
+            affinity::pin_current_thread(id as usize);
+        }
+
         let mut worker = Worker::new();
         let mut hash: Hash;
 
@@ -588,41 +758,47 @@ fn start_thread(id: u16, mut job_receiver: broadcast::Receiver<ThreadNotificatio
                     let initial_timestamp = new_job.get_timestamp();
                     worker.set_work(new_job, algorithm).unwrap();
 
-                    let difficulty_target = match compute_difficulty_target(&expected_difficulty) {
-                        Ok(value) => value,
+                    let mut difficulty_target = [0u8; HASH_SIZE];
+                    match compute_difficulty_target(&expected_difficulty) {
+                        Ok(value) => value.to_big_endian(&mut difficulty_target),
                         Err(e) => {
                             error!("Mining Thread #{}: error on difficulty target computation: {}", id, e);
                             continue 'main;
                         }
                     };
 
-                    // Solve block
-                    hash = worker.get_pow_hash().unwrap();
-                    let mut tries = 0;
-                    while !check_difficulty_against_target(&hash, &difficulty_target) {
-                        worker.increase_nonce().unwrap();
-                        // check if we have a new job pending
-                        // Only update every N iterations to avoid too much CPU usage
-                        if tries % UPDATE_EVERY_NONCE == 0 {
-                            if !job_receiver.is_empty() {
+                    // Solve block by batches of nonces
+                    loop {
+                        match find_pow_hash(backend, &mut worker, UPDATE_EVERY_NONCE, &difficulty_target) {
+                            Ok(Some(value)) => {
+                                hash = value;
+                                break;
+                            },
+                            Ok(None) => {},
+                            Err(e) => {
+                                error!("Mining Thread #{}: error while computing POW hash: {}", id, e);
                                 continue 'main;
                             }
-                            if let Ok(instant) = JOB_ELAPSED.read() {
-                                if let Some(instant) = instant.as_ref() {
-                                    worker.set_timestamp(initial_timestamp + instant.elapsed().as_millis() as u64).unwrap();
-                                }
+                        };
+                        HASHRATE_COUNTER.fetch_add(UPDATE_EVERY_NONCE as usize, Ordering::SeqCst);
+                        stats.on_hashes(id as usize, UPDATE_EVERY_NONCE);
+
+                        // check if we have a new job pending
+                        if !job_receiver.is_empty() {
+                            continue 'main;
+                        }
+                        if let Ok(instant) = JOB_ELAPSED.read() {
+                            if let Some(instant) = instant.as_ref() {
+                                worker.set_timestamp(initial_timestamp + instant.elapsed().as_millis() as u64).unwrap();
                             }
-                            HASHRATE_COUNTER.fetch_add(UPDATE_EVERY_NONCE as usize, Ordering::SeqCst);
                         }
-
-                        hash = worker.get_pow_hash().unwrap();
-                        tries += 1;
                     }
 
                     // compute the reference hash for easier finding of the block
                     let block_hash = worker.get_block_hash().unwrap();
                     info!("Thread #{}: block {} found at height {} with difficulty {}", id, block_hash, height, format_difficulty(difficulty_from_hash(&hash)));
 
+                    stats.on_block_found(id as usize);
                     let job = worker.take_work().unwrap();
                     if let Err(_) = block_sender.blocking_send(job) {
                         error!("Mining Thread #{}: error while sending block found with hash {}", id, block_hash);
@@ -637,9 +813,13 @@ fn start_thread(id: u16, mut job_receiver: broadcast::Receiver<ThreadNotificatio
     Ok(())
 }
 
-async fn run_prompt(prompt: ShareablePrompt) -> Result<()> {
+async fn run_prompt(prompt: ShareablePrompt, endpoints: Arc<EndpointList>, stats: Arc<MinerStats>) -> Result<()> {
     let command_manager = CommandManager::new(prompt.clone());
     command_manager.register_default_commands()?;
+    command_manager.store_in_context(endpoints)?;
+    command_manager.store_in_context(stats)?;
+    command_manager.add_command(Command::new("stats", "Show the mining statistics of each thread", CommandHandler::Async(async_handler!(show_stats))))?;
+    command_manager.add_command(Command::new("endpoints", "Show the getwork endpoints and their shares statistics", CommandHandler::Async(async_handler!(list_endpoints))))?;
 
     let closure = |_: &_, _: _| async {
         let topoheight_str = format!(
@@ -691,4 +871,37 @@ async fn run_prompt(prompt: ShareablePrompt) -> Result<()> {
 
     prompt.start(Duration::from_millis(1000), Box::new(async_handler!(closure)), Some(&command_manager)).await?;
     Ok(())
-}
\ No newline at end of file
+}
+async fn list_endpoints(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let endpoints: &Arc<EndpointList> = context.get()?;
+    let current = endpoints.get_current_index();
+    manager.message(format!("Endpoints ({}):", endpoints.len()));
+    for (i, stats) in endpoints.get_stats().into_iter().enumerate() {
+        manager.message(format!(
+            "#{} {}{} | Connected: {} | Submitted: {} | Accepted: {} | Rejected: {} | Disconnections: {}",
+            i,
+            stats.address,
+            if i == current { " (current)" } else { "" },
+            stats.connected,
+            stats.submitted,
+            stats.accepted,
+            stats.rejected,
+            stats.disconnections
+        ));
+    }
+
+    Ok(())
+}
+
+async fn show_stats(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let stats: &Arc<MinerStats> = context.get()?;
+    manager.message(format!("Uptime: {}s", stats.get_uptime().as_secs()));
+    manager.message(format!("Submit latency: {}ms (average: {}ms)", stats.get_last_latency(), stats.get_average_latency()));
+    for thread in stats.get_threads() {
+        manager.message(format!("Thread #{}: {} hashes | {} | Found: {}", thread.id, thread.hashes, format_hashrate(thread.hashrate), thread.blocks_found));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex
+    },
+    time::{Duration, Instant}
+};
+use serde::Serialize;
+
+// Submits remembered to compute the response latency
+const MAX_PENDING_SUBMITS: usize = 64;
+
+#[derive(Default)]
+struct ThreadStats {
+    hashes: AtomicU64,
+    blocks_found: AtomicU64
+}
+
+#[derive(Serialize)]
+pub struct ThreadStatsSnapshot {
+    pub id: usize,
+    pub hashes: u64,
+    // average since the miner started
+    pub hashrate: f64,
+    pub blocks_found: u64
+}
+
+// Mining statistics shared by the threads and the communication task
+pub struct MinerStats {
+    started_at: Instant,
+    threads: Vec<ThreadStats>,
+    // time of the submits waiting for a response
+    pending_submits: Mutex<VecDeque<Instant>>,
+    // in milliseconds
+    last_latency: AtomicU64,
+    total_latency: AtomicU64,
+    responses: AtomicU64
+}
+
+impl MinerStats {
+    pub fn new(threads: usize) -> Self {
+        Self {
+            started_at: Instant::now(),
+            threads: (0..threads).map(|_| ThreadStats::default()).collect(),
+            pending_submits: Mutex::new(VecDeque::new()),
+            last_latency: AtomicU64::new(0),
+            total_latency: AtomicU64::new(0),
+            responses: AtomicU64::new(0)
+        }
+    }
+
+    pub fn on_hashes(&self, thread: usize, count: u64) {
+        if let Some(stats) = self.threads.get(thread) {
+            stats.hashes.fetch_add(count, Ordering::Relaxed);
+        }
+    }
+
+    pub fn on_block_found(&self, thread: usize) {
+        if let Some(stats) = self.threads.get(thread) {
+            stats.blocks_found.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    // A block or share has been sent to the daemon
+    pub fn on_submit(&self) {
+        let mut pending = self.pending_submits.lock().unwrap();
+        if pending.len() >= MAX_PENDING_SUBMITS {
+            pending.pop_front();
+        }
+        pending.push_back(Instant::now());
+    }
+
+    // The daemon answered to the oldest submit
+    pub fn on_submit_response(&self) {
+        let Some(submitted_at) = self.pending_submits.lock().unwrap().pop_front() else {
+            return;
+        };
+
+        let latency = submitted_at.elapsed().as_millis() as u64;
+        self.last_latency.store(latency, Ordering::Relaxed);
+        self.total_latency.fetch_add(latency, Ordering::Relaxed);
+        self.responses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Connection has been closed, submits will never be answered
+    pub fn clear_pending_submits(&self) {
+        self.pending_submits.lock().unwrap().clear();
+    }
+
+    pub fn get_uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    // Latency of the last response in milliseconds
+    pub fn get_last_latency(&self) -> u64 {
+        self.last_latency.load(Ordering::Relaxed)
+    }
+
+    // Average latency of the responses in milliseconds
+    pub fn get_average_latency(&self) -> u64 {
+        let responses = self.responses.load(Ordering::Relaxed);
+        if responses == 0 {
+            return 0
+        }
+
+        self.total_latency.load(Ordering::Relaxed) / responses
+    }
+
+    pub fn get_threads(&self) -> Vec<ThreadStatsSnapshot> {
+        let uptime = self.get_uptime().as_secs_f64();
+        self.threads.iter().enumerate().map(|(id, stats)| {
+            let hashes = stats.hashes.load(Ordering::Relaxed);
+            ThreadStatsSnapshot {
+                id,
+                hashes,
+                hashrate: if uptime > 0f64 { hashes as f64 / uptime } else { 0f64 },
+                blocks_found: stats.blocks_found.load(Ordering::Relaxed)
+            }
+        }).collect()
+    }
+}
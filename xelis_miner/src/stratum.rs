@@ -0,0 +1,394 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex}
+};
+use anyhow::{Context, Error};
+use log::{debug, warn};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpStream
+    }
+};
+use xelis_common::{
+    api::{daemon::GetMinerWorkResult, stratum::*},
+    block::{MinerWork, EXTRA_NONCE_SIZE},
+    config::VERSION,
+    crypto::{Address, Hash},
+    difficulty::Difficulty,
+    serializer::Serializer
+};
+use crate::SocketMessage;
+
+// Jobs remembered to submit late shares
+const MAX_JOBS: usize = 16;
+
+// Submits waiting for a response, older ones are forgotten
+const MAX_PENDING_SUBMITS: usize = 64;
+
+// Extranonce2 bytes used by the mining threads for their id
+const THREAD_ID_SIZE: usize = 2;
+
+// State shared between the reader and the writer of a connection
+struct State {
+    username: String,
+    extranonce1_size: usize,
+    // Share difficulty set by the server
+    difficulty: Option<Difficulty>,
+    // Job id for each header work hash received
+    jobs: VecDeque<(Hash, String)>,
+    // Ids of the submits waiting for a response
+    pending_submits: Vec<u64>,
+    next_id: u64
+}
+
+impl State {
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+pub struct StratumReader {
+    reader: BufReader<OwnedReadHalf>,
+    // Line being read, kept between calls as reading may be cancelled
+    buffer: Vec<u8>,
+    // Messages received during the handshake
+    pending: VecDeque<SocketMessage>,
+    state: Arc<Mutex<State>>
+}
+
+pub struct StratumWriter {
+    writer: OwnedWriteHalf,
+    state: Arc<Mutex<State>>
+}
+
+async fn send_request(writer: &mut OwnedWriteHalf, id: u64, method: &str, params: Value) -> Result<(), Error> {
+    let mut line = json!(StratumRequest { id: Some(id), method: method.to_owned(), params }).to_string();
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    Ok(())
+}
+
+// Connect to a Stratum server, then subscribe and authorize the miner
+pub async fn connect(address: &str, miner: &Address, worker: &str) -> Result<(StratumReader, StratumWriter), Error> {
+    let host = address.strip_prefix(STRATUM_PREFIX).unwrap_or(address);
+    let stream = TcpStream::connect(host).await?;
+    let (read, mut writer) = stream.into_split();
+
+    let state = Arc::new(Mutex::new(State {
+        username: format!("{}.{}", miner, worker),
+        extranonce1_size: 0,
+        difficulty: None,
+        jobs: VecDeque::new(),
+        pending_submits: Vec::new(),
+        next_id: 0
+    }));
+
+    let mut reader = StratumReader {
+        reader: BufReader::new(read),
+        buffer: Vec::new(),
+        pending: VecDeque::new(),
+        state: Arc::clone(&state)
+    };
+
+    // Subscribe to receive our extranonce1
+    send_request(&mut writer, 1, METHOD_SUBSCRIBE, json!([format!("xelis-miner/{}", VERSION)])).await?;
+    let SubscribeResult(_, extranonce1, extranonce2_size) = serde_json::from_value(reader.wait_response(1).await?)
+        .context("Invalid subscribe result")?;
+
+    let extranonce1_size = extranonce1.len() / 2;
+    if extranonce1_size + extranonce2_size != EXTRA_NONCE_SIZE || extranonce2_size < THREAD_ID_SIZE {
+        return Err(Error::msg(format!("Invalid extranonce sizes: {} and {}", extranonce1_size, extranonce2_size)));
+    }
+
+    let username = {
+        let mut state = state.lock().unwrap();
+        state.extranonce1_size = extranonce1_size;
+        state.next_id = 2;
+        state.username.clone()
+    };
+
+    // Authorize using our address as username
+    send_request(&mut writer, 2, METHOD_AUTHORIZE, json!(AuthorizeParams(username, Some("x".to_owned())))).await?;
+    if reader.wait_response(2).await? != Value::Bool(true) {
+        return Err(Error::msg("Miner was not authorized"));
+    }
+
+    Ok((reader, StratumWriter { writer, state }))
+}
+
+impl StratumReader {
+    // Read the next line, None if the connection is closed
+    async fn read_line(&mut self) -> Result<Option<String>, Error> {
+        loop {
+            let limit = (STRATUM_MAX_LINE_SIZE + 1).saturating_sub(self.buffer.len()) as u64;
+            let read = (&mut self.reader).take(limit).read_until(b'\n', &mut self.buffer).await?;
+            if self.buffer.ends_with(b"\n") {
+                let line = String::from_utf8(std::mem::take(&mut self.buffer))?;
+                return Ok(Some(line))
+            }
+
+            if self.buffer.len() > STRATUM_MAX_LINE_SIZE {
+                return Err(Error::msg(format!("Line exceeds {} bytes", STRATUM_MAX_LINE_SIZE)));
+            }
+
+            if read == 0 {
+                return Ok(None)
+            }
+        }
+    }
+
+    // Wait on the response of a handshake request
+    async fn wait_response(&mut self, id: u64) -> Result<Value, Error> {
+        loop {
+            let line = self.read_line().await?
+                .context("Connection closed during handshake")?;
+
+            match serde_json::from_str::<StratumMessage>(line.trim())? {
+                StratumMessage::Response(response) if response.id == Some(id) => {
+                    if let Some(StratumError(code, message, _)) = response.error {
+                        return Err(Error::msg(format!("Error {}: {}", code, message)));
+                    }
+                    return Ok(response.result)
+                },
+                message => {
+                    if let Some(message) = self.handle_message(message)? {
+                        self.pending.push_back(message);
+                    }
+                }
+            }
+        }
+    }
+
+    // Convert a Stratum message to the message handled by the miner
+    fn handle_message(&mut self, message: StratumMessage) -> Result<Option<SocketMessage>, Error> {
+        let mut state = self.state.lock().unwrap();
+        match message {
+            StratumMessage::Request(request) => match request.method.as_str() {
+                METHOD_SET_DIFFICULTY => {
+                    let SetDifficultyParams(difficulty) = serde_json::from_value(request.params)?;
+                    debug!("Share difficulty set to {}", difficulty);
+                    state.difficulty = Some(difficulty);
+                    Ok(None)
+                },
+                METHOD_NOTIFY => {
+                    let NotifyParams(job_id, miner_work, algorithm, height, topoheight, difficulty, _) = serde_json::from_value(request.params)?;
+                    let work = MinerWork::from_hex(&miner_work).context("Error while decoding job")?;
+
+                    if state.jobs.len() >= MAX_JOBS {
+                        state.jobs.pop_front();
+                    }
+                    state.jobs.push_back((work.get_header_work_hash().clone(), job_id));
+
+                    // Threads search for shares, not blocks
+                    let difficulty = state.difficulty.unwrap_or(difficulty).min(difficulty);
+                    Ok(Some(SocketMessage::NewJob(GetMinerWorkResult { algorithm, miner_work, height, topoheight, difficulty })))
+                },
+                method => {
+                    debug!("Unsupported Stratum method: {}", method);
+                    Ok(None)
+                }
+            },
+            StratumMessage::Response(response) => {
+                let Some(index) = state.pending_submits.iter().position(|id| Some(*id) == response.id) else {
+                    debug!("Unexpected Stratum response: {:?}", response);
+                    return Ok(None)
+                };
+                state.pending_submits.remove(index);
+
+                Ok(Some(match response.error {
+                    Some(StratumError(code, message, _)) => SocketMessage::BlockRejected(format!("Error {}: {}", code, message)),
+                    None => SocketMessage::BlockAccepted
+                }))
+            }
+        }
+    }
+
+    // Read the next message to handle, None if the connection is closed
+    pub async fn next_message(&mut self) -> Result<Option<SocketMessage>, Error> {
+        if let Some(message) = self.pending.pop_front() {
+            return Ok(Some(message))
+        }
+
+        loop {
+            let Some(line) = self.read_line().await? else {
+                return Ok(None)
+            };
+
+            debug!("Received Stratum message: {}", line.trim());
+            let message = serde_json::from_str::<StratumMessage>(line.trim())?;
+            if let Some(message) = self.handle_message(message)? {
+                return Ok(Some(message))
+            }
+        }
+    }
+}
+
+impl StratumWriter {
+    // Submit a share found on a job received
+    pub async fn submit(&mut self, work: MinerWork<'_>) -> Result<(), Error> {
+        let (header_work_hash, timestamp, nonce, _, extra_nonce) = work.take();
+        let (id, params) = {
+            let mut state = self.state.lock().unwrap();
+            let Some(job_id) = state.jobs.iter().find(|(hash, _)| *hash == header_work_hash).map(|(_, id)| id.clone()) else {
+                warn!("Share found for an unknown job, ignoring it");
+                return Ok(())
+            };
+
+            let extranonce2 = hex::encode(&extra_nonce[state.extranonce1_size..]);
+            let id = state.next_id();
+            if state.pending_submits.len() >= MAX_PENDING_SUBMITS {
+                state.pending_submits.remove(0);
+            }
+            state.pending_submits.push(id);
+            (id, SubmitParams(state.username.clone(), job_id, extranonce2, timestamp, nonce))
+        };
+
+        send_request(&mut self.writer, id, METHOD_SUBMIT, json!(params)).await
+    }
+
+    pub async fn close(&mut self) -> Result<(), Error> {
+        self.writer.shutdown().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+    use xelis_common::{
+        block::Algorithm,
+        crypto::KeyPair,
+        network::Network
+    };
+    use super::*;
+
+    // Fake Stratum server answering the requests with the results given
+    struct FakeServer {
+        reader: BufReader<OwnedReadHalf>,
+        writer: OwnedWriteHalf
+    }
+
+    impl FakeServer {
+        async fn read_request(&mut self) -> StratumRequest {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).await.unwrap();
+            serde_json::from_str(line.trim()).unwrap()
+        }
+
+        async fn send(&mut self, message: Value) {
+            let mut line = message.to_string();
+            line.push('\n');
+            self.writer.write_all(line.as_bytes()).await.unwrap();
+        }
+
+        async fn respond(&mut self, id: Option<u64>, result: Value) {
+            self.send(json!(StratumResponse { id, result, error: None })).await;
+        }
+    }
+
+    async fn listen() -> (TcpListener, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("{}{}", STRATUM_PREFIX, listener.local_addr().unwrap());
+        (listener, address)
+    }
+
+    async fn accept(listener: &TcpListener) -> FakeServer {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (read, writer) = stream.into_split();
+        FakeServer { reader: BufReader::new(read), writer }
+    }
+
+    fn miner() -> Address {
+        KeyPair::new().get_public_key().to_address(Network::Dev.is_mainnet())
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_handshake_and_submit() {
+        let (listener, address) = listen().await;
+        let miner = miner();
+        let extranonce1 = [7u8; STRATUM_EXTRANONCE1_SIZE];
+        let expected_username = format!("{}.worker", miner);
+        let work = MinerWork::new(Hash::new([1u8; 32]), 10);
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+
+            // Subscribe must be sent before authorize
+            let request = server.read_request().await;
+            assert_eq!(request.method, METHOD_SUBSCRIBE);
+            server.respond(request.id, json!(SubscribeResult(Vec::new(), hex::encode(extranonce1), STRATUM_EXTRANONCE2_SIZE))).await;
+
+            let request = server.read_request().await;
+            assert_eq!(request.method, METHOD_AUTHORIZE);
+            let AuthorizeParams(username, _) = serde_json::from_value(request.params).unwrap();
+            assert_eq!(username, expected_username);
+            server.respond(request.id, Value::Bool(true)).await;
+
+            server.send(json!(StratumRequest { id: None, method: METHOD_SET_DIFFICULTY.to_owned(), params: json!(SetDifficultyParams(Difficulty::from_u64(100))) })).await;
+            server.send(json!(StratumRequest { id: None, method: METHOD_NOTIFY.to_owned(), params: json!(NotifyParams("a".to_owned(), work.to_hex(), Algorithm::V2, 1, 1, Difficulty::from_u64(1000), true)) })).await;
+
+            let request = server.read_request().await;
+            assert_eq!(request.method, METHOD_SUBMIT);
+            let params: SubmitParams = serde_json::from_value(request.params).unwrap();
+            server.respond(request.id, Value::Bool(true)).await;
+            params
+        });
+
+        let (mut reader, mut writer) = connect(&address, &miner, "worker").await.unwrap();
+
+        // Threads search for shares at the share difficulty
+        let Some(SocketMessage::NewJob(job)) = reader.next_message().await.unwrap() else {
+            panic!("expected a new job");
+        };
+        assert_eq!(job.difficulty, Difficulty::from_u64(100));
+
+        let mut work = MinerWork::from_hex(&job.miner_work).unwrap();
+        work.get_extra_nonce()[..STRATUM_EXTRANONCE1_SIZE].copy_from_slice(&extranonce1);
+        work.set_thread_id_u16(0x0102);
+        work.set_nonce(42);
+        writer.submit(work).await.unwrap();
+
+        assert!(matches!(reader.next_message().await.unwrap(), Some(SocketMessage::BlockAccepted)));
+
+        // Only the extranonce2 is submitted, thread id included
+        let SubmitParams(_, job_id, extranonce2, timestamp, nonce) = server.await.unwrap();
+        let extranonce2 = hex::decode(extranonce2).unwrap();
+        assert_eq!(job_id, "a");
+        assert_eq!(extranonce2.len(), STRATUM_EXTRANONCE2_SIZE);
+        assert_eq!(extranonce2[STRATUM_EXTRANONCE2_SIZE - 2..], [1, 2]);
+        assert_eq!(timestamp, 10);
+        assert_eq!(nonce, 42);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_invalid_extranonce_size() {
+        let (listener, address) = listen().await;
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+            let request = server.read_request().await;
+            server.respond(request.id, json!(SubscribeResult(Vec::new(), hex::encode([0u8; 4]), STRATUM_EXTRANONCE2_SIZE))).await;
+        });
+
+        assert!(connect(&address, &miner(), "worker").await.is_err());
+        server.await.unwrap();
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_unauthorized() {
+        let (listener, address) = listen().await;
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+            let request = server.read_request().await;
+            server.respond(request.id, json!(SubscribeResult(Vec::new(), hex::encode([0u8; STRATUM_EXTRANONCE1_SIZE]), STRATUM_EXTRANONCE2_SIZE))).await;
+
+            let request = server.read_request().await;
+            server.send(json!(StratumResponse { id: request.id, result: Value::Null, error: Some(StratumError::new(StratumError::UNAUTHORIZED, "Invalid miner address")) })).await;
+        });
+
+        assert!(connect(&address, &miner(), "worker").await.is_err());
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,334 @@
+// Test support to run several daemons in the same process
+//
+// Nodes run on the dev network and are connected through in-memory P2P transports,
+// so no TCP connection is made between them.
+// Blocks are mined on demand and wallets can be synced against any node with the RPC server enabled.
+
+use std::{
+    net::{Ipv4Addr, SocketAddr, TcpListener},
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc
+    },
+    time::{Duration, Instant}
+};
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use log::{debug, warn};
+use tokio::{io::duplex, time::sleep};
+use xelis_common::{
+    block::TopoHeight,
+    config::XELIS_ASSET,
+    crypto::{ecdlp, Hash, Hashable, PublicKey},
+    immutable::Immutable,
+    network::Network
+};
+use xelis_daemon::{
+    core::{
+        blockchain::Blockchain,
+        config::Config,
+        storage::{SledStorage, StorageMode}
+    },
+    p2p::{connection::Connection, P2pServer}
+};
+use xelis_wallet::{
+    precomputed_tables::{self, PrecomputedTablesShared, L1_LOW},
+    wallet::Wallet
+};
+
+pub use xelis_daemon::config::DEV_PUBLIC_KEY;
+
+pub type TestBlockchain = Arc<Blockchain<SledStorage>>;
+
+// Size of the in-memory buffer of a P2P transport in each direction
+const TRANSPORT_BUFFER_SIZE: usize = 1024 * 1024;
+// Interval between two checks while waiting on a condition
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+// Password used by all the wallets of the cluster
+const WALLET_PASSWORD: &str = "xelis";
+
+// Used to have a unique directory per cluster in the same process
+static CLUSTER_ID: AtomicUsize = AtomicUsize::new(0);
+
+// Parse the daemon config as if it was given in CLI
+#[derive(Parser)]
+struct NodeArgs {
+    #[clap(flatten)]
+    config: Config
+}
+
+// Reserve a free port on localhost for the RPC server
+fn get_free_port() -> Result<u16> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    Ok(listener.local_addr()?.port())
+}
+
+struct NoProgress;
+
+impl ecdlp::ProgressTableGenerationReportFunction for NoProgress {
+    fn report(&self, _: f64, _: ecdlp::ReportStep) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+pub struct TestNode {
+    // Fake address of the node, it is used to identify it in the peerlist of others
+    addr: SocketAddr,
+    // RPC address to connect a wallet
+    rpc_address: Option<String>,
+    blockchain: TestBlockchain
+}
+
+impl TestNode {
+    // Start a new daemon on dev network
+    // PoW verification is skipped to mine blocks instantly
+    pub async fn new(id: usize, dir: &Path, rpc: bool) -> Result<Self> {
+        let dir_path = format!("{}/", dir.display());
+        let mut args = vec![
+            "xelis_daemon".to_owned(),
+            "--dir-path".to_owned(), dir_path.clone(),
+            "--p2p-bind-address".to_owned(), "127.0.0.1:0".to_owned(),
+            "--disable-p2p-outgoing-connections".to_owned(),
+            "--skip-pow-verification".to_owned(),
+            "--disable-getwork-server".to_owned()
+        ];
+
+        let rpc_address = if rpc {
+            let bind_address = format!("127.0.0.1:{}", get_free_port()?);
+            args.push("--rpc-bind-address".to_owned());
+            args.push(bind_address.clone());
+            Some(format!("http://{}", bind_address))
+        } else {
+            args.push("--disable-rpc-server".to_owned());
+            None
+        };
+
+        let config = NodeArgs::try_parse_from(args)?.config;
+        let storage = SledStorage::new(dir_path, None, Network::Dev, None, StorageMode::default())?;
+        let blockchain = Blockchain::new(config, Network::Dev, storage).await?;
+
+        // Each node has its own IP as peers are identified by it
+        let addr = SocketAddr::from(([10, 0, (id >> 8) as u8, id as u8], 2125));
+        Ok(Self {
+            addr,
+            rpc_address,
+            blockchain
+        })
+    }
+
+    pub fn get_blockchain(&self) -> &TestBlockchain {
+        &self.blockchain
+    }
+
+    pub fn get_address(&self) -> &SocketAddr {
+        &self.addr
+    }
+
+    pub fn get_rpc_address(&self) -> Option<&String> {
+        self.rpc_address.as_ref()
+    }
+
+    pub async fn get_p2p(&self) -> Result<Arc<P2pServer<SledStorage>>> {
+        self.blockchain.get_p2p().read().await.clone()
+            .context("P2p server is not running")
+    }
+
+    pub async fn get_top_block_hash(&self) -> Result<Hash> {
+        Ok(self.blockchain.get_top_block_hash().await?)
+    }
+
+    pub fn get_topoheight(&self) -> TopoHeight {
+        self.blockchain.get_topo_height()
+    }
+
+    // Mine a block on top of the current tips and broadcast it to the peers
+    pub async fn mine_block(&self, miner: &PublicKey) -> Result<Hash> {
+        let header = self.blockchain.get_block_template(miner.clone()).await?;
+        let block = self.blockchain.build_block_from_header(Immutable::Owned(header)).await?;
+        let hash = block.hash();
+        self.blockchain.add_new_block(block, true, true).await?;
+        debug!("Block {} mined", hash);
+        Ok(hash)
+    }
+
+    pub async fn mine_blocks(&self, miner: &PublicKey, count: usize) -> Result<Vec<Hash>> {
+        let mut hashes = Vec::with_capacity(count);
+        for _ in 0..count {
+            hashes.push(self.mine_block(miner).await?);
+        }
+        Ok(hashes)
+    }
+
+    pub async fn stop(&self) {
+        self.blockchain.stop().await;
+    }
+}
+
+pub struct Cluster {
+    dir: PathBuf,
+    nodes: Vec<TestNode>,
+    // Shared by all the wallets
+    precomputed_tables: Option<PrecomputedTablesShared>,
+    wallets: Vec<Arc<Wallet>>
+}
+
+impl Cluster {
+    // Start a cluster of nodes without RPC server
+    pub async fn new(count: usize) -> Result<Self> {
+        Self::build(count, false).await
+    }
+
+    // Start a cluster of nodes with the RPC server enabled to drive wallets
+    pub async fn with_rpc(count: usize) -> Result<Self> {
+        Self::build(count, true).await
+    }
+
+    async fn build(count: usize, rpc: bool) -> Result<Self> {
+        let dir = std::env::temp_dir()
+            .join(format!("xelis_testing_{}_{}", process::id(), CLUSTER_ID.fetch_add(1, Ordering::SeqCst)));
+
+        let mut nodes = Vec::with_capacity(count);
+        for id in 0..count {
+            let node_dir = dir.join(format!("node_{}", id));
+            std::fs::create_dir_all(&node_dir)?;
+            nodes.push(TestNode::new(id + 1, &node_dir, rpc).await?);
+        }
+
+        let cluster = Self {
+            dir,
+            nodes,
+            precomputed_tables: None,
+            wallets: Vec::new()
+        };
+
+        // Connect all the nodes together
+        for i in 0..count {
+            for j in i + 1..count {
+                cluster.connect(i, j).await?;
+            }
+        }
+
+        Ok(cluster)
+    }
+
+    pub fn node(&self, index: usize) -> &TestNode {
+        &self.nodes[index]
+    }
+
+    pub fn nodes(&self) -> &[TestNode] {
+        &self.nodes
+    }
+
+    // Connect two nodes through an in-memory transport
+    pub async fn connect(&self, a: usize, b: usize) -> Result<()> {
+        let (a, b) = (&self.nodes[a], &self.nodes[b]);
+        let (a_p2p, b_p2p) = (a.get_p2p().await?, b.get_p2p().await?);
+        let (a_stream, b_stream) = duplex(TRANSPORT_BUFFER_SIZE);
+
+        // Both sides must run the handshake at the same time
+        let (res_a, res_b) = tokio::join!(
+            a_p2p.add_connection(Connection::from_stream(a_stream, b.addr, true), false),
+            b_p2p.add_connection(Connection::from_stream(b_stream, a.addr, false), false)
+        );
+        res_a?;
+        res_b?;
+
+        Ok(())
+    }
+
+    // Close the connection between two nodes, used to create a network split
+    pub async fn disconnect(&self, a: usize, b: usize) -> Result<()> {
+        let (a, b) = (&self.nodes[a], &self.nodes[b]);
+        let peer = a.get_p2p().await?.get_peer_list().get_peer_by_addr(&b.addr).await
+            .context("Nodes are not connected")?;
+        peer.close().await?;
+
+        // Wait until the other side detects the disconnection
+        let b_p2p = b.get_p2p().await?;
+        self.wait_for("disconnection", Duration::from_secs(10), || async {
+            Ok(!b_p2p.get_peer_list().is_connected_to_addr(&a.addr).await)
+        }).await
+    }
+
+    // Wait until the condition is true or the timeout is reached
+    pub async fn wait_for<F, Fut>(&self, name: &str, timeout: Duration, mut condition: F) -> Result<()>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<bool>>
+    {
+        let start = Instant::now();
+        while !condition().await? {
+            if start.elapsed() > timeout {
+                bail!("Timed out while waiting for {}", name)
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+        Ok(())
+    }
+
+    // Wait until all the nodes have the same top block
+    pub async fn wait_for_sync(&self, timeout: Duration) -> Result<()> {
+        self.wait_for("nodes to be synced", timeout, || async {
+            let expected = self.nodes[0].get_top_block_hash().await?;
+            for node in self.nodes.iter().skip(1) {
+                if node.get_top_block_hash().await? != expected {
+                    return Ok(false)
+                }
+            }
+            Ok(true)
+        }).await
+    }
+
+    // Create a new wallet synced against the node
+    pub async fn create_wallet(&mut self, node: usize, name: &str) -> Result<Arc<Wallet>> {
+        let rpc_address = self.nodes[node].get_rpc_address()
+            .context("RPC server is not enabled on this node")?
+            .clone();
+
+        let precomputed_tables = match self.precomputed_tables.as_ref() {
+            Some(tables) => tables.clone(),
+            None => {
+                let path = format!("{}/", self.dir.join("precomputed_tables").display());
+                let tables = precomputed_tables::read_or_generate_precomputed_tables(Some(&path), L1_LOW, NoProgress, false).await?;
+                self.precomputed_tables = Some(tables.clone());
+                tables
+            }
+        };
+
+        let path = self.dir.join("wallets").join(name);
+        let wallet = Wallet::create(&path.display().to_string(), WALLET_PASSWORD, None, Network::Dev, precomputed_tables)?;
+        wallet.set_online_mode(&rpc_address, true).await?;
+        self.wallets.push(wallet.clone());
+
+        Ok(wallet)
+    }
+
+    // Wait until the wallet has at least this XELIS balance
+    pub async fn wait_for_balance(&self, wallet: &Wallet, amount: u64, timeout: Duration) -> Result<()> {
+        self.wait_for("wallet balance", timeout, || async {
+            let storage = wallet.get_storage().read().await;
+            if !storage.has_balance_for(&XELIS_ASSET).await? {
+                return Ok(false)
+            }
+            Ok(storage.get_balance_for(&XELIS_ASSET).await?.amount >= amount)
+        }).await
+    }
+
+    // Stop the wallets and the nodes and delete their data
+    pub async fn stop(self) {
+        for wallet in self.wallets.iter() {
+            wallet.close().await;
+        }
+
+        for node in self.nodes.iter() {
+            node.stop().await;
+        }
+
+        if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            warn!("Error while deleting cluster directory {}: {}", self.dir.display(), e);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+use xelis_testing::{Cluster, DEV_PUBLIC_KEY};
+
+const SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_blocks_are_propagated() {
+    let cluster = Cluster::new(3).await.unwrap();
+    cluster.node(0).mine_blocks(&DEV_PUBLIC_KEY, 5).await.unwrap();
+    cluster.wait_for_sync(SYNC_TIMEOUT).await.unwrap();
+
+    for node in cluster.nodes() {
+        assert_eq!(node.get_topoheight(), 5);
+    }
+
+    cluster.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reorg_after_network_split() {
+    let cluster = Cluster::new(2).await.unwrap();
+    cluster.node(0).mine_blocks(&DEV_PUBLIC_KEY, 2).await.unwrap();
+    cluster.wait_for_sync(SYNC_TIMEOUT).await.unwrap();
+
+    // Each side mines its own chain, the longest one must win once reconnected
+    cluster.disconnect(0, 1).await.unwrap();
+    cluster.node(0).mine_blocks(&DEV_PUBLIC_KEY, 2).await.unwrap();
+    let hashes = cluster.node(1).mine_blocks(&DEV_PUBLIC_KEY, 6).await.unwrap();
+
+    cluster.connect(0, 1).await.unwrap();
+    cluster.wait_for_sync(SYNC_TIMEOUT).await.unwrap();
+    assert!(cluster.node(0).get_blockchain().has_block(hashes.last().unwrap()).await.unwrap());
+
+    cluster.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wallet_receives_rewards() {
+    let mut cluster = Cluster::with_rpc(1).await.unwrap();
+    let wallet = cluster.create_wallet(0, "miner").await.unwrap();
+    cluster.node(0).mine_blocks(wallet.get_public_key(), 3).await.unwrap();
+    cluster.wait_for_balance(&wallet, 1, SYNC_TIMEOUT).await.unwrap();
+
+    cluster.stop().await;
+}
@@ -0,0 +1,143 @@
+use std::{sync::Arc, time::Duration};
+use xelis_common::{
+    config::XELIS_ASSET,
+    crypto::Hashable,
+    transaction::{
+        builder::{FeeBuilder, TransactionTypeBuilder, TransferBuilder},
+        Transaction
+    },
+    utils::calculate_replacement_min_fee
+};
+use xelis_daemon::{config::STABLE_LIMIT, core::error::BlockchainError};
+use xelis_testing::{Cluster, DEV_PUBLIC_KEY};
+use xelis_wallet::wallet::Wallet;
+
+const SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Start a node with a wallet having stable mining rewards
+async fn setup() -> (Cluster, Arc<Wallet>) {
+    let mut cluster = Cluster::with_rpc(1).await.unwrap();
+    let wallet = cluster.create_wallet(0, "sender").await.unwrap();
+    cluster.node(0).mine_blocks(wallet.get_public_key(), 3).await.unwrap();
+    cluster.wait_for_balance(&wallet, 1, SYNC_TIMEOUT).await.unwrap();
+
+    // Make the rewards stable so the wallet builds on its last balance
+    cluster.node(0).mine_blocks(&DEV_PUBLIC_KEY, STABLE_LIMIT as usize + 1).await.unwrap();
+
+    (cluster, wallet)
+}
+
+fn transfer() -> TransactionTypeBuilder {
+    TransactionTypeBuilder::Transfers(vec![TransferBuilder {
+        asset: XELIS_ASSET,
+        amount: 1,
+        destination: DEV_PUBLIC_KEY.as_address(false),
+        extra_data: None
+    }])
+}
+
+// Build a transfer with the given nonce without applying it to the wallet storage
+async fn build_tx(wallet: &Wallet, nonce: u64, fee: FeeBuilder) -> Transaction {
+    let storage = wallet.get_storage().read().await;
+    let transaction_type = transfer();
+    let mut state = wallet.create_transaction_state_with_storage(&storage, &transaction_type, &fee, Some(nonce)).await.unwrap();
+    let tx_version = storage.get_tx_version().await.unwrap();
+    wallet.create_transaction_with(&mut state, tx_version, transaction_type, fee).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_replacement_at_min_fee() {
+    let (cluster, wallet) = setup().await;
+    let blockchain = cluster.node(0).get_blockchain();
+    let nonce = wallet.get_nonce().await;
+
+    let tx = build_tx(&wallet, nonce, FeeBuilder::default()).await;
+    let (hash, fee) = (tx.hash(), tx.get_fee());
+    blockchain.add_tx_to_mempool(tx, false).await.unwrap();
+
+    // The minimum fee is enough to replace it
+    let replacement = build_tx(&wallet, nonce, FeeBuilder::Value(calculate_replacement_min_fee(fee))).await;
+    let replacement_hash = replacement.hash();
+    blockchain.add_tx_to_mempool(replacement, false).await.unwrap();
+
+    {
+        let mempool = blockchain.get_mempool().read().await;
+        assert!(!mempool.contains_tx(&hash));
+        assert!(mempool.contains_tx(&replacement_hash));
+    }
+
+    cluster.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_replacement_fee_too_low() {
+    let (cluster, wallet) = setup().await;
+    let blockchain = cluster.node(0).get_blockchain();
+    let nonce = wallet.get_nonce().await;
+
+    let tx = build_tx(&wallet, nonce, FeeBuilder::default()).await;
+    let (hash, fee) = (tx.hash(), tx.get_fee());
+    blockchain.add_tx_to_mempool(tx, false).await.unwrap();
+
+    let min_fee = calculate_replacement_min_fee(fee);
+    let replacement = build_tx(&wallet, nonce, FeeBuilder::Value(min_fee - 1)).await;
+    match blockchain.add_tx_to_mempool(replacement, false).await {
+        Err(BlockchainError::TxReplacementFeeTooLow(previous, expected, got)) => {
+            assert_eq!(previous, hash);
+            assert_eq!(expected, min_fee);
+            assert_eq!(got, min_fee - 1);
+        },
+        res => panic!("unexpected result: {:?}", res)
+    }
+
+    assert!(blockchain.get_mempool().read().await.contains_tx(&hash));
+
+    cluster.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_replacement_not_allowed_with_pending_txs() {
+    let (cluster, wallet) = setup().await;
+    let blockchain = cluster.node(0).get_blockchain();
+    let nonce = wallet.get_nonce().await;
+
+    // Second TX is built on the balance of the first one
+    let first = wallet.create_transaction(transfer(), FeeBuilder::default()).await.unwrap();
+    let (hash, fee) = (first.hash(), first.get_fee());
+    blockchain.add_tx_to_mempool(first, false).await.unwrap();
+    let second = wallet.create_transaction(transfer(), FeeBuilder::default()).await.unwrap();
+    blockchain.add_tx_to_mempool(second, false).await.unwrap();
+
+    let replacement = build_tx(&wallet, nonce, FeeBuilder::Value(calculate_replacement_min_fee(fee))).await;
+    match blockchain.add_tx_to_mempool(replacement, false).await {
+        Err(BlockchainError::TxReplacementNotAllowed(previous)) => assert_eq!(previous, hash),
+        res => panic!("unexpected result: {:?}", res)
+    }
+
+    cluster.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_replaced_tx_restored_on_invalid_replacement() {
+    let (cluster, wallet) = setup().await;
+    let blockchain = cluster.node(0).get_blockchain();
+    let nonce = wallet.get_nonce().await;
+
+    let tx = wallet.create_transaction(transfer(), FeeBuilder::default()).await.unwrap();
+    let (hash, fee) = (tx.hash(), tx.get_fee());
+    blockchain.add_tx_to_mempool(tx, false).await.unwrap();
+
+    // Built on the unconfirmed balance left by the first TX,
+    // so it is invalid once the first TX is removed from mempool
+    let replacement = build_tx(&wallet, nonce, FeeBuilder::Value(calculate_replacement_min_fee(fee))).await;
+    let replacement_hash = replacement.hash();
+    assert!(blockchain.add_tx_to_mempool(replacement, false).await.is_err());
+
+    {
+        let mempool = blockchain.get_mempool().read().await;
+        assert!(mempool.contains_tx(&hash));
+        assert!(!mempool.contains_tx(&replacement_hash));
+    }
+
+    cluster.stop().await;
+}
@@ -0,0 +1,51 @@
+/*
+ * C bindings of the XELIS wallet, built with the `ffi` feature.
+ *
+ * Every function returns XELIS_OK on success or XELIS_ERROR on failure.
+ * The message of the last error on the calling thread is available using xelis_last_error().
+ * Strings written by the library must be released using xelis_string_free(), which wipes them from memory.
+ * Amounts are in atomic units, a NULL asset means the XELIS asset.
+ */
+
+#ifndef XELIS_WALLET_H
+#define XELIS_WALLET_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define XELIS_OK 0
+#define XELIS_ERROR -1
+
+typedef struct XelisWallet XelisWallet;
+
+const char *xelis_last_error(void);
+void xelis_string_free(char *value);
+
+/* network is one of "mainnet", "testnet" or "dev", tables_path is optional and must end with a '/' */
+int32_t xelis_wallet_open(const char *name, const char *password, const char *network, const char *tables_path, XelisWallet **out);
+/* seed is optional, a new wallet is generated if NULL */
+int32_t xelis_wallet_create(const char *name, const char *password, const char *seed, const char *network, const char *tables_path, XelisWallet **out);
+void xelis_wallet_free(XelisWallet *wallet);
+
+int32_t xelis_wallet_set_online(const XelisWallet *wallet, const char *daemon_address);
+int32_t xelis_wallet_set_offline(const XelisWallet *wallet);
+int32_t xelis_wallet_is_online(const XelisWallet *wallet, uint8_t *out);
+
+int32_t xelis_wallet_get_address(const XelisWallet *wallet, char **out);
+int32_t xelis_wallet_get_seed(const XelisWallet *wallet, uint32_t language, char **out);
+int32_t xelis_wallet_get_topoheight(const XelisWallet *wallet, uint64_t *out);
+int32_t xelis_wallet_get_balance(const XelisWallet *wallet, const char *asset, uint64_t *out);
+/* history is written in JSON format */
+int32_t xelis_wallet_get_history(const XelisWallet *wallet, char **out);
+
+/* the transaction hash is written once accepted by the daemon */
+int32_t xelis_wallet_send(const XelisWallet *wallet, const char *destination, const char *asset, uint64_t amount, char **out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,92 @@
+use std::{borrow::Cow, sync::Arc};
+use xelis_common::{
+    api::wallet::*,
+    async_handler,
+    config::VERSION,
+    context::Context,
+    rpc_server::{
+        parse_params,
+        InternalRpcError,
+        RPCHandler
+    }
+};
+use serde_json::{Value, json};
+use crate::wallet_manager::WalletManager;
+use log::info;
+
+// Register all RPC methods of the wallet manager
+// Methods of an opened wallet are available under /wallets/<name>/json_rpc
+pub fn register_methods(handler: &mut RPCHandler<Arc<WalletManager>>) {
+    info!("Registering wallet manager RPC methods...");
+    handler.register_method("get_version", async_handler!(get_version));
+    handler.register_method("get_network", async_handler!(get_network));
+    handler.register_method("open_wallet", async_handler!(open_wallet));
+    handler.register_method("create_wallet", async_handler!(create_wallet));
+    handler.register_method("close_wallet", async_handler!(close_wallet));
+    handler.register_method("list_wallets", async_handler!(list_wallets));
+}
+
+// Retrieve the version of the wallet manager
+async fn get_version(_: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+    Ok(json!(VERSION))
+}
+
+// Retrieve the network used by all the managed wallets
+async fn get_network(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let manager: &Arc<WalletManager> = context.get()?;
+    Ok(json!(manager.get_network()))
+}
+
+// Open an existing wallet or one of its sub-accounts
+async fn open_wallet(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: OpenWalletParams = parse_params(body)?;
+    let manager: &Arc<WalletManager> = context.get()?;
+    let wallet = manager.open_wallet(&params.name, &params.password, params.connection, params.account).await?;
+
+    Ok(json!(wallet.get_address()))
+}
+
+// Create a new wallet, or recover it from a seed
+async fn create_wallet(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: CreateWalletParams = parse_params(body)?;
+    let manager: &Arc<WalletManager> = context.get()?;
+    let wallet = manager.create_wallet(&params.name, &params.password, params.seed.as_deref(), params.connection).await?;
+
+    Ok(json!(wallet.get_address()))
+}
+
+// Close an opened wallet
+async fn close_wallet(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: CloseWalletParams = parse_params(body)?;
+    let manager: &Arc<WalletManager> = context.get()?;
+    manager.close_wallet(&params.name).await?;
+
+    Ok(json!(true))
+}
+
+// List all the opened wallets
+async fn list_wallets(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let manager: &Arc<WalletManager> = context.get()?;
+    let mut wallets = Vec::new();
+    for (name, wallet) in manager.get_wallets().await {
+        wallets.push(ManagedWalletInfo {
+            name: Cow::Owned(name),
+            address: wallet.get_address(),
+            online: wallet.is_online().await
+        });
+    }
+    wallets.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(json!(wallets))
+}
@@ -0,0 +1,192 @@
+use std::{collections::HashMap, sync::Arc};
+
+use actix_web_httpauth::{
+    middleware::HttpAuthentication,
+    extractors::basic::BasicAuth
+};
+use anyhow::Result;
+use log::{info, warn};
+use xelis_common::{
+    tokio::{
+        spawn_task,
+        sync::{Mutex, RwLock}
+    },
+    config,
+    rpc_server::{
+        json_rpc,
+        InternalRpcError,
+        RPCHandler,
+        RPCServerHandler,
+        RpcResponseError
+    }
+};
+use actix_web::{
+    get,
+    HttpResponse,
+    Responder,
+    HttpServer,
+    web::{Data, self},
+    App,
+    dev::{ServerHandle, ServiceRequest},
+    Error,
+    error::{ErrorUnauthorized, ErrorBadGateway, ErrorBadRequest}
+};
+use crate::{
+    error::WalletError,
+    wallet::Wallet,
+    wallet_manager::WalletManager
+};
+use super::{
+    manager_rpc,
+    register_rpc_methods,
+    AuthConfig
+};
+
+pub type WalletManagerRpcServerShared = Arc<WalletManagerRpcServer>;
+
+// RPC Server exposing a wallet manager
+// Manager methods are available under /json_rpc
+// Each opened wallet is available under /wallets/<name>/json_rpc
+pub struct WalletManagerRpcServer {
+    handle: Mutex<Option<ServerHandle>>,
+    rpc_handler: RPCHandler<Arc<WalletManager>>,
+    // RPC handlers of the opened wallets, built on first request
+    wallet_handlers: RwLock<HashMap<String, Arc<RPCHandler<Arc<Wallet>>>>>,
+    auth_config: Option<AuthConfig>
+}
+
+impl WalletManagerRpcServer {
+    pub async fn new(bind_address: String, manager: Arc<WalletManager>, auth_config: Option<AuthConfig>, threads: Option<usize>) -> Result<WalletManagerRpcServerShared> {
+        let mut rpc_handler = RPCHandler::new(manager);
+        manager_rpc::register_methods(&mut rpc_handler);
+
+        let server = Arc::new(Self {
+            handle: Mutex::new(None),
+            rpc_handler,
+            wallet_handlers: RwLock::new(HashMap::new()),
+            auth_config
+        });
+
+        {
+            let clone = Arc::clone(&server);
+            let mut builder = HttpServer::new(move || {
+                let server = Arc::clone(&clone);
+                let auth = HttpAuthentication::basic(auth);
+                App::new()
+                    .app_data(Data::from(server))
+                    .wrap(auth)
+                    .route("/json_rpc", web::post().to(json_rpc::<Arc<WalletManager>, WalletManagerRpcServer>))
+                    .route("/wallets/{name}/json_rpc", web::post().to(wallet_json_rpc))
+                    .service(index)
+            })
+            .disable_signals()
+            .bind(&bind_address)?;
+
+            if let Some(threads) = threads {
+                if threads == 0 {
+                    return Err(anyhow::anyhow!("The number of workers must be greater than 0"));
+                }
+
+                info!("Setting the number of workers to: {}", threads);
+                builder = builder.workers(threads);
+            }
+
+            let http_server = builder.run();
+            { // save the server handle to be able to stop it later
+                let handle = http_server.handle();
+                let mut lock = server.handle.lock().await;
+                *lock = Some(handle);
+            }
+            spawn_task("wallet-manager-rpc-server", http_server);
+        }
+
+        Ok(server)
+    }
+
+    pub fn get_manager(&self) -> &Arc<WalletManager> {
+        self.rpc_handler.get_data()
+    }
+
+    // Get the RPC handler of an opened wallet
+    // The cached handler is rebuilt if the wallet was closed and opened again
+    async fn get_wallet_handler(&self, name: &str) -> Result<Arc<RPCHandler<Arc<Wallet>>>, WalletError> {
+        let Some(wallet) = self.get_manager().get_wallet(name).await else {
+            self.wallet_handlers.write().await.remove(name);
+            return Err(WalletError::WalletNotOpen(name.to_owned()))
+        };
+
+        {
+            let handlers = self.wallet_handlers.read().await;
+            if let Some(handler) = handlers.get(name).filter(|h| Arc::ptr_eq(h.get_data(), &wallet)) {
+                return Ok(handler.clone())
+            }
+        }
+
+        let mut rpc_handler = RPCHandler::new(wallet);
+        register_rpc_methods(&mut rpc_handler);
+        let rpc_handler = Arc::new(rpc_handler);
+
+        let mut handlers = self.wallet_handlers.write().await;
+        handlers.insert(name.to_owned(), rpc_handler.clone());
+        Ok(rpc_handler)
+    }
+
+    async fn authenticate(&self, credentials: BasicAuth) -> Result<(), Error> {
+        if let Some(config) = &self.auth_config {
+            let user = credentials.user_id();
+            let password = credentials.password().ok_or(ErrorBadRequest("Missing password"))?;
+
+            if *config.username != *user || *config.password != *password {
+                return Err(ErrorUnauthorized("Username/password are invalid"))
+            }
+        }
+
+        Ok(())
+    }
+
+    // Stop the server and close all the opened wallets
+    pub async fn stop(&self) {
+        info!("Stopping Wallet Manager RPC Server...");
+        let mut handle = self.handle.lock().await;
+        if let Some(handle) = handle.take() {
+            handle.stop(false).await;
+            info!("Wallet Manager RPC Server is now stopped!");
+        } else {
+            warn!("Wallet Manager RPC Server is not running!");
+        }
+
+        self.wallet_handlers.write().await.clear();
+        self.get_manager().close_all().await;
+    }
+}
+
+impl RPCServerHandler<Arc<WalletManager>> for WalletManagerRpcServer {
+    fn get_rpc_handler(&self) -> &RPCHandler<Arc<WalletManager>> {
+        &self.rpc_handler
+    }
+}
+
+// JSON RPC endpoint of an opened wallet
+async fn wallet_json_rpc(server: Data<WalletManagerRpcServer>, name: web::Path<String>, body: web::Bytes) -> Result<impl Responder, RpcResponseError> {
+    let handler = server.get_wallet_handler(&name).await
+        .map_err(|e| RpcResponseError::new(None, InternalRpcError::from(e)))?;
+
+    let result = handler.handle_request(&body).await?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
+async fn auth(request: ServiceRequest, credentials: BasicAuth) -> Result<ServiceRequest, (Error, ServiceRequest)> {
+    let data: Option<&Data<WalletManagerRpcServer>> = request.app_data();
+    match data {
+        Some(server) => match server.authenticate(credentials).await {
+            Ok(_) => Ok(request),
+            Err(e) => Err((e, request))
+        },
+        None => Err((ErrorBadGateway("RPC Server was not found"), request))
+    }
+}
+
+#[get("/")]
+async fn index() -> impl Responder {
+    HttpResponse::Ok().body(format!("Hello, world!\nRunning on: {}", config::VERSION))
+}
@@ -1,5 +1,7 @@
 mod rpc;
 mod rpc_server;
+mod manager_rpc;
+mod manager_server;
 mod xswd;
 
 use serde::ser::Serialize;
@@ -7,6 +9,7 @@ use xelis_common::{api::wallet::NotifyEvent, rpc_server::WebSocketServerHandler}
 
 pub use self::{
     rpc_server::{WalletRpcServer, WalletRpcServerShared, AuthConfig},
+    manager_server::{WalletManagerRpcServer, WalletManagerRpcServerShared},
     xswd::{
         XSWD,
         AppStateShared,
@@ -11,7 +11,7 @@ use xelis_common::{
     async_handler,
     config::{VERSION, XELIS_ASSET},
     context::Context,
-    crypto::{Hashable, KeyPair},
+    crypto::{elgamal::AdaptorSecret, Hashable, KeyPair},
     rpc_server::{
         parse_params,
         websocket::WebSocketSessionShared,
@@ -28,7 +28,7 @@ use xelis_common::{
 use serde_json::{Value, json};
 use crate::{
     error::WalletError,
-    storage::Balance,
+    storage::{AddressBookEntry, Balance, TransactionLabel},
     transaction_builder::TransactionBuilderState,
     wallet::Wallet
 };
@@ -45,25 +45,83 @@ pub fn register_methods(handler: &mut RPCHandler<Arc<Wallet>>) {
     handler.register_method("get_address", async_handler!(get_address));
     handler.register_method("split_address", async_handler!(split_address));
     handler.register_method("rescan", async_handler!(rescan));
+    handler.register_method("get_rescan_progress", async_handler!(get_rescan_progress));
+    handler.register_method("pause_rescan", async_handler!(pause_rescan));
+    handler.register_method("resume_rescan", async_handler!(resume_rescan));
     handler.register_method("get_balance", async_handler!(get_balance));
     handler.register_method("has_balance", async_handler!(has_balance));
     handler.register_method("get_tracked_assets", async_handler!(get_tracked_assets));
+    handler.register_method("get_asset_filter", async_handler!(get_asset_filter));
+    handler.register_method("set_asset_filter", async_handler!(set_asset_filter));
     handler.register_method("get_asset_precision", async_handler!(get_asset_precision));
     handler.register_method("get_assets", async_handler!(get_assets));
     handler.register_method("get_asset", async_handler!(get_asset));
     handler.register_method("get_transaction", async_handler!(get_transaction));
+    handler.register_method("set_transaction_label", async_handler!(set_transaction_label));
+    handler.register_method("get_transaction_label", async_handler!(get_transaction_label));
     handler.register_method("build_transaction", async_handler!(build_transaction));
     handler.register_method("build_transaction_offline", async_handler!(build_transaction_offline));
+    handler.register_method("bump_transaction_fee", async_handler!(bump_transaction_fee));
+    handler.register_method("create_payment_proof", async_handler!(create_payment_proof));
+    handler.register_method("verify_payment_proof", async_handler!(verify_payment_proof));
+    handler.register_method("create_reserve_proof", async_handler!(create_reserve_proof));
+    handler.register_method("verify_reserve_proof", async_handler!(verify_reserve_proof));
+    handler.register_method("create_audit_proof", async_handler!(create_audit_proof));
+    handler.register_method("create_transfer_audit_proof", async_handler!(create_transfer_audit_proof));
+    handler.register_method("verify_audit_proof", async_handler!(verify_audit_proof));
+    handler.register_method("prepare_transaction", async_handler!(prepare_transaction));
+    handler.register_method("sign_prepared_transaction", async_handler!(sign_prepared_transaction));
     handler.register_method("build_unsigned_transaction", async_handler!(build_unsigned_transaction));
     handler.register_method("finalize_unsigned_transaction", async_handler!(finalize_unsigned_transaction));
     handler.register_method("sign_unsigned_transaction", async_handler!(sign_unsigned_transaction));
+    handler.register_method("create_multisig_transaction", async_handler!(create_multisig_transaction));
+    handler.register_method("get_multisig_signing_status", async_handler!(get_multisig_signing_status));
+    handler.register_method("list_pending_multisig_transactions", async_handler!(list_pending_multisig_transactions));
+    handler.register_method("add_multisig_signature", async_handler!(add_multisig_signature));
+    handler.register_method("finalize_multisig_transaction", async_handler!(finalize_multisig_transaction));
+    handler.register_method("cancel_multisig_transaction", async_handler!(cancel_multisig_transaction));
+
+    handler.register_method("set_address_book_entry", async_handler!(set_address_book_entry));
+    handler.register_method("get_address_book_entry", async_handler!(get_address_book_entry));
+    handler.register_method("list_address_book", async_handler!(list_address_book));
+    handler.register_method("delete_address_book_entry", async_handler!(delete_address_book_entry));
+
+    handler.register_method("set_scheduled_payment", async_handler!(set_scheduled_payment));
+    handler.register_method("get_scheduled_payment", async_handler!(get_scheduled_payment));
+    handler.register_method("list_scheduled_payments", async_handler!(list_scheduled_payments));
+    handler.register_method("delete_scheduled_payment", async_handler!(delete_scheduled_payment));
+
+    handler.register_method("get_sub_account_index", async_handler!(get_sub_account_index));
+    handler.register_method("get_sub_accounts", async_handler!(get_sub_accounts));
+    handler.register_method("get_sub_account_address", async_handler!(get_sub_account_address));
+
+    // Only available outside of XSWD so applications can't see or revoke other applications
+    handler.register_method("list_xswd_applications", async_handler!(list_xswd_applications));
+    handler.register_method("revoke_xswd_application", async_handler!(revoke_xswd_application));
+
+    handler.register_method("is_locked", async_handler!(is_locked));
+    handler.register_method("lock_wallet", async_handler!(lock_wallet));
+    // Only available outside of XSWD so applications can't brute force the password
+    handler.register_method("unlock_wallet", async_handler!(unlock_wallet));
+    handler.register_method("set_auto_lock_timeout", async_handler!(set_auto_lock_timeout));
 
     handler.register_method("clear_tx_cache", async_handler!(clear_tx_cache));
     handler.register_method("list_transactions", async_handler!(list_transactions));
+    handler.register_method("export_history", async_handler!(export_history));
+    handler.register_method("create_payment_uri", async_handler!(create_payment_uri));
+    handler.register_method("parse_payment_uri", async_handler!(parse_payment_uri));
     handler.register_method("is_online", async_handler!(is_online));
     handler.register_method("set_online_mode", async_handler!(set_online_mode));
     handler.register_method("set_offline_mode", async_handler!(set_offline_mode));
+    handler.register_method("set_online_mode_with_failover", async_handler!(set_online_mode_with_failover));
+    handler.register_method("get_daemon_endpoints", async_handler!(get_daemon_endpoints));
+    handler.register_method("get_sync_checkpoint", async_handler!(get_sync_checkpoint));
     handler.register_method("sign_data", async_handler!(sign_data));
+    handler.register_method("generate_adaptor_secret", async_handler!(generate_adaptor_secret));
+    handler.register_method("create_adaptor_signature", async_handler!(create_adaptor_signature));
+    handler.register_method("verify_adaptor_signature", async_handler!(verify_adaptor_signature));
+    handler.register_method("complete_adaptor_signature", async_handler!(complete_adaptor_signature));
+    handler.register_method("extract_adaptor_secret", async_handler!(extract_adaptor_secret));
     handler.register_method("estimate_fees", async_handler!(estimate_fees));
     handler.register_method("estimate_extra_data_size", async_handler!(estimate_extra_data_size));
     handler.register_method("network_info", async_handler!(network_info));
@@ -132,10 +190,11 @@ async fn get_address(context: &Context, body: Value) -> Result<Value, InternalRp
     let params: GetAddressParams = parse_params(body)?;
 
     let wallet: &Arc<Wallet> = context.get()?;
-    let address = if let Some(data) = params.integrated_data {
-        wallet.get_address_with(data)
-    } else {
-        wallet.get_address()
+    let address = match (params.integrated_data, params.payment_id) {
+        (Some(_), Some(_)) => return Err(InternalRpcError::InvalidParams("Integrated data and payment ID can't be used together")),
+        (Some(data), None) => wallet.get_address_with(data),
+        (None, Some(payment_id)) => wallet.get_address_with_payment_id(payment_id),
+        (None, None) => wallet.get_address()
     };
 
     Ok(json!(address))
@@ -223,6 +282,39 @@ async fn rescan(context: &Context, body: Value) -> Result<Value, InternalRpcErro
     Ok(json!(true))
 }
 
+// Retrieve the progress of the rescan in progress, null if none
+async fn get_rescan_progress(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    let progress = wallet.get_rescan_progress().await?;
+    Ok(json!(progress))
+}
+
+// Pause the rescan in progress, it can be resumed later
+async fn pause_rescan(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    wallet.pause_rescan().await?;
+    Ok(json!(true))
+}
+
+// Resume the rescan from its last checkpoint
+async fn resume_rescan(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    wallet.resume_rescan().await?;
+    Ok(json!(true))
+}
+
 // Retrieve the balance of the wallet for a specific asset
 // By default, it will returns 0 if no balance is found on disk
 async fn get_balance(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
@@ -256,18 +348,39 @@ async fn get_tracked_assets(context: &Context, body: Value) -> Result<Value, Int
 
     let wallet: &Arc<Wallet> = context.get()?;
     let storage = wallet.get_storage().read().await;
-    let tracked_assets = storage.get_assets().await?;
+    let tracked_assets = storage.get_tracked_assets().await?;
 
     Ok(json!(tracked_assets))
 }
 
+// Retrieve the filter of assets tracked by the wallet
+async fn get_asset_filter(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    Ok(json!(wallet.get_asset_filter().await))
+}
+
+// Set the filter of assets tracked by the wallet
+async fn set_asset_filter(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let filter: AssetFilter = parse_params(body)?;
+    if context.has::<WebSocketSessionShared<XSWDWebSocketHandler<Arc<Wallet>>>>() {
+        return Err(InternalRpcError::InvalidRequestStr("Method is not available through XSWD"))
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    wallet.set_asset_filter(filter).await?;
+    Ok(json!(true))
+}
+
 // Retrieve decimals used by an asset
 async fn get_asset_precision(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: GetAssetPrecisionParams = parse_params(body)?;
 
     let wallet: &Arc<Wallet> = context.get()?;
-    let storage = wallet.get_storage().read().await;
-    let data = storage.get_asset(&params.asset).await?;
+    let data = wallet.get_asset(&params.asset).await?;
     Ok(json!(data.get_decimals()))
 }
 
@@ -284,12 +397,12 @@ async fn get_assets(context: &Context, body: Value) -> Result<Value, InternalRpc
 }
 
 // Retrieve an asset from the wallet storage using its hash
+// If unknown, it is fetched from the daemon and stored
 async fn get_asset(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: GetAssetPrecisionParams = parse_params(body)?;
 
     let wallet: &Arc<Wallet> = context.get()?;
-    let storage = wallet.get_storage().read().await;
-    let data = storage.get_asset(&params.asset).await?;
+    let data = wallet.get_asset(&params.asset).await?;
     Ok(json!(data))
 }
 
@@ -304,6 +417,28 @@ async fn get_transaction(context: &Context, body: Value) -> Result<Value, Intern
     Ok(json!(transaction.serializable(wallet.get_network().is_mainnet())))
 }
 
+// Attach a local note and tags to a transaction, replacing the previous ones
+async fn set_transaction_label(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: SetTransactionLabelParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    wallet.set_transaction_label(&params.hash, TransactionLabel {
+        note: params.note,
+        tags: params.tags
+    }).await?;
+    Ok(json!(true))
+}
+
+// Retrieve the local note and tags of a transaction, null if none
+async fn get_transaction_label(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetTransactionParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let storage = wallet.get_storage().read().await;
+    let label = storage.get_transaction_label(&params.hash)?;
+    Ok(json!(label))
+}
+
 // Build a transaction and broadcast it if requested
 async fn build_transaction(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: BuildTransactionParams = parse_params(body)?;
@@ -339,7 +474,7 @@ async fn build_transaction(context: &Context, body: Value) -> Result<Value, Inte
         wallet.create_transaction_with(&mut state, version, params.tx_type, fee)?
     } else {
         let builder = TransactionBuilder::new(version, wallet.get_public_key().clone(), params.signers.len() as u8, params.tx_type, fee);
-        let mut unsigned = builder.build_unsigned(&mut state, wallet.get_keypair())
+        let mut unsigned = builder.build_unsigned(&mut state, &wallet.get_keypair()?)
             .context("Error while building unsigned transaction")?;
 
         for signer in params.signers {
@@ -347,7 +482,7 @@ async fn build_transaction(context: &Context, body: Value) -> Result<Value, Inte
             unsigned.sign_multisig(&keypair, signer.id);
         }
 
-        let tx = unsigned.finalize(wallet.get_keypair());
+        let tx = unsigned.finalize(&wallet.get_keypair()?);
         state.set_tx_hash_built(tx.hash());
 
         tx
@@ -381,6 +516,115 @@ async fn build_transaction(context: &Context, body: Value) -> Result<Value, Inte
     }))
 }
 
+// Replace a pending transaction by the same one with a higher fee
+async fn bump_transaction_fee(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: BumpTransactionFeeParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    if !wallet.is_online().await {
+        return Err(WalletError::NotOnlineMode)?
+    }
+
+    let tx = wallet.bump_transaction_fee(&params.hash, params.fee).await?;
+    Ok(json!(TransactionResponse {
+        tx_as_hex: if params.tx_as_hex {
+            Some(hex::encode(tx.to_bytes()))
+        } else {
+            None
+        },
+        inner: DataHash {
+            hash: Cow::Owned(tx.hash()),
+            data: Cow::Owned(tx)
+        }
+    }))
+}
+
+// Create a proof revealing the amount of a transfer sent by this wallet
+async fn create_payment_proof(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: CreatePaymentProofParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    if !wallet.is_online().await {
+        return Err(WalletError::NotOnlineMode)?
+    }
+
+    let proof = wallet.create_payment_proof(&params.hash, params.index).await?;
+    Ok(json!(proof))
+}
+
+// Verify a payment proof using the transaction fetched from the daemon
+async fn verify_payment_proof(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: VerifyPaymentProofParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    if !wallet.is_online().await {
+        return Err(WalletError::NotOnlineMode)?
+    }
+
+    let result = wallet.verify_payment_proof(&params.proof).await?;
+    Ok(json!(result))
+}
+
+// Create a proof that the wallet controls at least an amount of an asset
+async fn create_reserve_proof(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: CreateReserveProofParams = parse_params(body)?;
+    let challenge = hex::decode(&params.challenge)
+        .context("Invalid challenge hex")?;
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    if !wallet.is_online().await {
+        return Err(WalletError::NotOnlineMode)?
+    }
+
+    let proof = wallet.create_reserve_proof(&params.asset, params.amount, challenge).await?;
+    Ok(json!(proof))
+}
+
+// Verify a reserve proof of an account using its balance fetched from the daemon
+async fn verify_reserve_proof(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: VerifyReserveProofParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    if !wallet.is_online().await {
+        return Err(WalletError::NotOnlineMode)?
+    }
+
+    let result = wallet.verify_reserve_proof(&params.address, &params.proof).await?;
+    Ok(json!(result))
+}
+
+// Create an audit proof of the wallet balance for an auditor
+async fn create_audit_proof(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: CreateAuditProofParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    if !wallet.is_online().await {
+        return Err(WalletError::NotOnlineMode)?
+    }
+
+    let proof = wallet.create_audit_proof(&params.auditor, &params.asset).await?;
+    Ok(json!(proof))
+}
+
+// Create an audit proof of the amount of a transfer sent or received by the wallet
+async fn create_transfer_audit_proof(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: CreateTransferAuditProofParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    if !wallet.is_online().await {
+        return Err(WalletError::NotOnlineMode)?
+    }
+
+    let proof = wallet.create_transfer_audit_proof(&params.auditor, &params.hash, params.index).await?;
+    Ok(json!(proof))
+}
+
+// Verify an audit proof made for this wallet and decrypt the audited amount
+async fn verify_audit_proof(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: VerifyAuditProofParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    if !wallet.is_online().await {
+        return Err(WalletError::NotOnlineMode)?
+    }
+
+    let result = wallet.verify_audit_proof(&params.address, &params.proof).await?;
+    Ok(json!(result))
+}
+
 // Build a transaction by giving the encrypted balances directly
 async fn build_transaction_offline(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: BuildTransactionOfflineParams = parse_params(body)?;
@@ -414,7 +658,7 @@ async fn build_transaction_offline(context: &Context, body: Value) -> Result<Val
         wallet.create_transaction_with(&mut state, version, params.tx_type, params.fee)?
     } else {
         let builder = TransactionBuilder::new(version, wallet.get_public_key().clone(), params.signers.len() as u8, params.tx_type, params.fee);
-        let mut unsigned = builder.build_unsigned(&mut state, wallet.get_keypair())
+        let mut unsigned = builder.build_unsigned(&mut state, &wallet.get_keypair()?)
             .context("Error while building unsigned transaction")?;
 
         for signer in params.signers {
@@ -422,7 +666,7 @@ async fn build_transaction_offline(context: &Context, body: Value) -> Result<Val
             unsigned.sign_multisig(&keypair, signer.id);
         }
 
-        let tx = unsigned.finalize(wallet.get_keypair());
+        let tx = unsigned.finalize(&wallet.get_keypair()?);
         state.set_tx_hash_built(tx.hash());
 
         tx
@@ -441,6 +685,36 @@ async fn build_transaction_offline(context: &Context, body: Value) -> Result<Val
     }))
 }
 
+// Prepare a transaction to be signed by an offline wallet
+async fn prepare_transaction(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: PrepareTransactionParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let prepared = wallet.prepare_transaction(params.tx_type, params.fee.unwrap_or_default(), params.nonce, params.tx_version).await?;
+    Ok(json!(prepared))
+}
+
+// Sign a transaction prepared by an online wallet
+// The transaction is not broadcasted
+async fn sign_prepared_transaction(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: SignPreparedTransactionParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let tx = wallet.sign_prepared_transaction(params.prepared).await?;
+
+    Ok(json!(TransactionResponse {
+        tx_as_hex: if params.tx_as_hex {
+            Some(hex::encode(tx.to_bytes()))
+        } else {
+            None
+        },
+        inner: DataHash {
+            hash: Cow::Owned(tx.hash()),
+            data: Cow::Owned(tx)
+        }
+    }))
+}
+
 async fn build_unsigned_transaction(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: BuildUnsignedTransactionParams = parse_params(body)?;
     let wallet: &Arc<Wallet> = context.get()?;
@@ -461,7 +735,7 @@ async fn build_unsigned_transaction(context: &Context, body: Value) -> Result<Va
 
     // Generate the TX
     let builder = TransactionBuilder::new(version, wallet.get_public_key().clone(), threshold, params.tx_type, fee);
-    let unsigned = builder.build_unsigned(&mut state, wallet.get_keypair())
+    let unsigned = builder.build_unsigned(&mut state, &wallet.get_keypair()?)
         .context("Error while building unsigned transaction")?;
 
     state.apply_changes(&mut storage).await
@@ -495,7 +769,7 @@ async fn finalize_unsigned_transaction(context: &Context, body: Value) -> Result
         return Err(InternalRpcError::InvalidParams("Invalid source"))
     }
 
-    let keypair = wallet.get_keypair();
+    let keypair = wallet.get_keypair()?;
 
     if !params.signatures.is_empty() {
         let mut multisig = MultiSig::new();
@@ -506,7 +780,7 @@ async fn finalize_unsigned_transaction(context: &Context, body: Value) -> Result
         unsigned.set_multisig(multisig);
     }
 
-    let tx = unsigned.0.finalize(keypair);
+    let tx = unsigned.0.finalize(&keypair);
     
     let mut storage = wallet.get_storage().write().await;
     let mut state = TransactionBuilderState::from_tx(&storage, &tx, wallet.get_network().is_mainnet()).await?;
@@ -537,18 +811,255 @@ async fn finalize_unsigned_transaction(context: &Context, body: Value) -> Result
     }))
 }
 
+// Create a multisig transaction kept by the wallet until the threshold is reached
+async fn create_multisig_transaction(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: CreateMultiSigTransactionParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let unsigned = wallet.create_multisig_transaction(params.tx_type, params.fee.unwrap_or_default(), params.nonce, params.tx_version).await?;
+    let threshold = {
+        let storage = wallet.get_storage().read().await;
+        storage.get_multisig_state().await?
+            .map(|state| state.payload.threshold)
+            .unwrap_or(0)
+    };
+
+    Ok(json!(UnsignedTransactionResponse {
+        tx_as_hex: if params.tx_as_hex {
+            Some(hex::encode(unsigned.to_bytes()))
+        } else {
+            None
+        },
+        hash: unsigned.get_hash_for_multisig(),
+        inner: unsigned,
+        threshold
+    }))
+}
+
+// Get the signing status of a pending multisig transaction
+async fn get_multisig_signing_status(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: MultiSigTransactionParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let status = wallet.get_multisig_signing_status(&params.hash).await?;
+    Ok(json!(status))
+}
+
+// List all pending multisig transactions with their signing status
+async fn list_pending_multisig_transactions(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    let statuses = wallet.get_pending_multisig_transactions().await?;
+    Ok(json!(statuses))
+}
+
+// Import a co-signer signature for a pending multisig transaction
+async fn add_multisig_signature(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: AddMultiSigSignatureParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let status = wallet.add_multisig_signature(&params.hash, params.signature).await?;
+    Ok(json!(status))
+}
+
+// Finalize a pending multisig transaction once the threshold is reached
+async fn finalize_multisig_transaction(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: FinalizeMultiSigTransactionParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let mut storage = wallet.get_storage().write().await;
+    let tx = wallet.finalize_multisig_transaction_with_storage(&storage, &params.hash).await?;
+    let mut state = TransactionBuilderState::from_tx(&storage, &tx, wallet.get_network().is_mainnet()).await?;
+
+    if params.broadcast {
+        if let Err(e) = wallet.submit_transaction(&tx).await {
+            warn!("Clearing Tx cache & unconfirmed balances because of broadcasting error: {}", e);
+            debug!("TX HEX: {}", tx.to_hex());
+            storage.clear_tx_cache();
+            storage.delete_unconfirmed_balances().await;
+            return Err(e.into());
+        }
+    }
+
+    state.apply_changes(&mut storage).await
+        .context("Error while applying state changes")?;
+
+    storage.delete_pending_multisig_transaction(&params.hash)
+        .context("Error while deleting pending multisig transaction")?;
+
+    Ok(json!(TransactionResponse {
+        tx_as_hex: if params.tx_as_hex {
+            Some(hex::encode(tx.to_bytes()))
+        } else {
+            None
+        },
+        inner: DataHash {
+            hash: Cow::Owned(tx.hash()),
+            data: Cow::Owned(tx)
+        }
+    }))
+}
+
+// Delete a pending multisig transaction
+async fn cancel_multisig_transaction(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: MultiSigTransactionParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let mut storage = wallet.get_storage().write().await;
+    if !storage.has_pending_multisig_transaction(&params.hash)? {
+        return Err(WalletError::PendingMultiSigNotFound(params.hash).into())
+    }
+
+    storage.delete_pending_multisig_transaction(&params.hash)?;
+    Ok(json!(true))
+}
+
 // Sign a unsigned transaction as a multisig member
 async fn sign_unsigned_transaction(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: SignUnsignedTransactionParams = parse_params(body)?;
     let wallet: &Arc<Wallet> = context.get()?;
 
-    let signature = wallet.sign_data(params.hash.as_bytes());
+    let signature = wallet.sign_data(params.hash.as_bytes())?;
     Ok(json!(SignatureId {
         id: params.signer_id,
         signature
     }))
 }
 
+// Save a contact in the address book, overwriting the entry with the same name
+async fn set_address_book_entry(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: SetAddressBookEntryParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    wallet.set_address_book_entry(AddressBookEntry {
+        name: params.name,
+        address: params.address,
+        default_asset: params.default_asset,
+        notes: params.notes
+    }).await?;
+    Ok(json!(true))
+}
+
+// Retrieve a contact from the address book using its name
+async fn get_address_book_entry(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: AddressBookEntryParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let storage = wallet.get_storage().read().await;
+    if !storage.has_address_book_entry(&params.name)? {
+        return Err(WalletError::AddressBookEntryNotFound(params.name).into())
+    }
+
+    let entry = storage.get_address_book_entry(&params.name)?;
+    Ok(json!(entry))
+}
+
+// List all contacts from the address book
+async fn list_address_book(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    let storage = wallet.get_storage().read().await;
+    let entries = storage.get_address_book_entries()?;
+    Ok(json!(entries))
+}
+
+// Delete a contact from the address book
+async fn delete_address_book_entry(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: AddressBookEntryParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    wallet.delete_address_book_entry(&params.name).await?;
+    Ok(json!(true))
+}
+
+// Create or replace a recurring payment
+async fn set_scheduled_payment(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: SetScheduledPaymentParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let asset = params.asset.unwrap_or(XELIS_ASSET);
+    let payment = wallet.set_scheduled_payment(params.name, params.destination, asset, params.amount, params.interval, params.start, params.count).await?;
+    Ok(json!(payment))
+}
+
+// Get a recurring payment using its name
+async fn get_scheduled_payment(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: ScheduledPaymentParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let storage = wallet.get_storage().read().await;
+    if !storage.has_scheduled_payment(&params.name)? {
+        return Err(WalletError::ScheduledPaymentNotFound(params.name).into())
+    }
+
+    let payment = storage.get_scheduled_payment(&params.name)?;
+    Ok(json!(payment))
+}
+
+// List all recurring payments
+async fn list_scheduled_payments(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    let storage = wallet.get_storage().read().await;
+    let payments = storage.get_scheduled_payments()?;
+    Ok(json!(payments))
+}
+
+// Delete a recurring payment
+async fn delete_scheduled_payment(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: ScheduledPaymentParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    wallet.delete_scheduled_payment(&params.name).await?;
+    Ok(json!(true))
+}
+
+// Index of the opened account, 0 for the master account
+async fn get_sub_account_index(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    Ok(json!(wallet.get_sub_account_index().await?))
+}
+
+// List the sub-accounts created from this wallet
+async fn get_sub_accounts(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    let accounts: Vec<SubAccountEntry> = wallet.get_sub_accounts().await?
+        .into_iter()
+        .map(|(index, address)| SubAccountEntry { index, address })
+        .collect();
+
+    Ok(json!(accounts))
+}
+
+// Derive the address of a sub-account, even if it was never opened
+async fn get_sub_account_address(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: GetSubAccountAddressParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    if wallet.get_sub_account_index().await? != 0 {
+        return Err(WalletError::NotMasterAccount)?
+    }
+
+    Ok(json!(wallet.get_sub_account_address(params.index)?))
+}
+
 // Clear the transaction cache
 async fn clear_tx_cache(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     if body != Value::Null {
@@ -585,7 +1096,7 @@ async fn list_transactions(context: &Context, body: Value) -> Result<Value, Inte
     let opt_key = params.address.map(|addr| addr.to_public_key());
     
     let mainnet = wallet.get_network().is_mainnet();
-    let txs = storage.get_filtered_transactions(opt_key.as_ref(), params.asset.as_ref(), params.min_topoheight, params.max_topoheight, params.accept_incoming, params.accept_outgoing, params.accept_coinbase, params.accept_burn, params.query.as_ref())?
+    let txs = storage.get_filtered_transactions(opt_key.as_ref(), params.asset.as_ref(), params.min_topoheight, params.max_topoheight, params.accept_incoming, params.accept_outgoing, params.accept_coinbase, params.accept_burn, params.query.as_ref(), params.tag.as_deref(), params.payment_id)?
         .into_iter()
         .map(|tx| tx.serializable(mainnet))
         .collect::<Vec<_>>();
@@ -593,6 +1104,42 @@ async fn list_transactions(context: &Context, body: Value) -> Result<Value, Inte
     Ok(json!(txs))
 }
 
+// Export the whole history in CSV or JSON format
+// The export is returned as a string
+async fn export_history(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: ExportHistoryParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let mut output = Vec::new();
+    wallet.export_history(params.format, params.fiat_valuation, &mut output).await?;
+    let content = String::from_utf8(output).context("Error while converting export to string")?;
+    Ok(json!(content))
+}
+
+// Create a payment URI to receive funds on this wallet
+async fn create_payment_uri(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: CreatePaymentUriParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let mut request = wallet.create_payment_request(params.amount, params.asset, params.extra_data);
+    request.label = params.label;
+    request.message = params.message;
+
+    Ok(json!(CreatePaymentUriResult {
+        uri: request.to_uri(),
+        request
+    }))
+}
+
+// Parse a payment URI, for example scanned from a QR code
+async fn parse_payment_uri(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: ParsePaymentUriParams = parse_params(body)?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let request = wallet.parse_payment_uri(&params.uri)?;
+    Ok(json!(request))
+}
+
 // Check if the wallet is currently connected to a daemon
 async fn is_online(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     if body != Value::Null {
@@ -634,15 +1181,173 @@ async fn set_offline_mode(context: &Context, body: Value) -> Result<Value, Inter
     Ok(json!(true))
 }
 
+// Connect the wallet to the healthiest daemon of a list
+// The wallet is moved to another daemon if the current one is offline or lagging
+async fn set_online_mode_with_failover(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: SetOnlineModeWithFailoverParams = parse_params(body)?;
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    if wallet.is_online().await {
+        return Err(InternalRpcError::InvalidRequestStr("Wallet is already connected to a daemon"))
+    }
+
+    wallet.set_online_mode_with_failover(params.daemon_addresses).await?;
+
+    Ok(json!(true))
+}
+
+// Retrieve the status of the daemons used by the failover
+async fn get_daemon_endpoints(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    let endpoints = wallet.get_daemon_endpoints().await?;
+    Ok(json!(endpoints))
+}
+
+// Get the topoheight from which the wallet was restored using the checkpoint sync
+async fn get_sync_checkpoint(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    let checkpoint = wallet.get_sync_checkpoint().await?;
+    Ok(json!(checkpoint))
+}
+
 // Sign any data converted in bytes format
 async fn sign_data(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
     let params: DataElement = parse_params(body)?;
 
     let wallet: &Arc<Wallet> = context.get()?;
-    let signature = wallet.sign_data(&params.to_bytes());
+    let signature = wallet.sign_data(&params.to_bytes())?;
+    Ok(json!(signature))
+}
+
+// Generate a new adaptor secret and its point to start an atomic swap
+async fn generate_adaptor_secret(_: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let secret = AdaptorSecret::generate_new();
+    let adaptor = secret.to_point();
+    Ok(json!(GenerateAdaptorSecretResult { secret, adaptor }))
+}
+
+// Create a pre-signature of any data for the adaptor point of the counterparty
+async fn create_adaptor_signature(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: CreateAdaptorSignatureParams = parse_params(body)?;
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    let signature = wallet.sign_adaptor_data(&params.data.to_bytes(), &params.adaptor)?;
     Ok(json!(signature))
 }
 
+// Verify a pre-signature received from the counterparty
+async fn verify_adaptor_signature(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: VerifyAdaptorSignatureParams = parse_params(body)?;
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    if params.address.is_mainnet() != wallet.get_network().is_mainnet() {
+        return Err(WalletError::InvalidAddressNetwork)?
+    }
+
+    let key = params.address.get_public_key()
+        .decompress()
+        .context("Error while decompressing public key")?;
+    Ok(json!(params.signature.verify(&params.data.to_bytes(), &key, &params.adaptor)))
+}
+
+// Complete a pre-signature using the adaptor secret, this reveals the secret to the signer
+async fn complete_adaptor_signature(_: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: CompleteAdaptorSignatureParams = parse_params(body)?;
+    Ok(json!(params.signature.complete(&params.secret)))
+}
+
+// Extract the adaptor secret from the signature published by the counterparty
+async fn extract_adaptor_secret(_: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: ExtractAdaptorSecretParams = parse_params(body)?;
+    let secret = params.pre_signature.extract_secret(&params.signature, &params.adaptor)
+        .ok_or(WalletError::InvalidAdaptorSignature)?;
+    Ok(json!(secret))
+}
+
+// Check if the keypair is wiped from memory
+async fn is_locked(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    Ok(json!(wallet.is_locked()))
+}
+
+// Wipe the keypair from memory until the wallet is unlocked
+async fn lock_wallet(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    wallet.lock().await?;
+    Ok(json!(true))
+}
+
+// Unlock the wallet using its password
+async fn unlock_wallet(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: UnlockWalletParams = parse_params(body)?;
+    if context.has::<WebSocketSessionShared<XSWDWebSocketHandler<Arc<Wallet>>>>() {
+        return Err(InternalRpcError::InvalidRequestStr("Method is not available through XSWD"))
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    wallet.unlock(&params.password).await?;
+    Ok(json!(true))
+}
+
+// Configure the inactivity before locking the wallet
+async fn set_auto_lock_timeout(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: SetAutoLockTimeoutParams = parse_params(body)?;
+    if context.has::<WebSocketSessionShared<XSWDWebSocketHandler<Arc<Wallet>>>>() {
+        return Err(InternalRpcError::InvalidRequestStr("Method is not available through XSWD"))
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    wallet.set_auto_lock_timeout(params.timeout).await;
+    Ok(json!(true))
+}
+
+// List all the XSWD applications with granted permissions
+async fn list_xswd_applications(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    if body != Value::Null {
+        return Err(InternalRpcError::UnexpectedParams)
+    }
+
+    if context.has::<WebSocketSessionShared<XSWDWebSocketHandler<Arc<Wallet>>>>() {
+        return Err(InternalRpcError::InvalidRequestStr("Method is not available through XSWD"))
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    let applications = wallet.get_xswd_applications().await?;
+    Ok(json!(applications))
+}
+
+// Revoke the permissions granted to a XSWD application
+async fn revoke_xswd_application(context: &Context, body: Value) -> Result<Value, InternalRpcError> {
+    let params: XSWDApplicationParams = parse_params(body)?;
+    if context.has::<WebSocketSessionShared<XSWDWebSocketHandler<Arc<Wallet>>>>() {
+        return Err(InternalRpcError::InvalidRequestStr("Method is not available through XSWD"))
+    }
+
+    let wallet: &Arc<Wallet> = context.get()?;
+    wallet.revoke_xswd_application(&params.id).await?;
+    Ok(json!(true))
+}
+
 // In EncryptedStorage, custom trees are already prefixed
 async fn get_tree_name(context: &Context, tree: String) -> Result<String, InternalRpcError> {
     // If the API is not used through XSWD, we don't need to prefix the tree name with the app id
@@ -45,12 +45,18 @@ use xelis_common::{
         spawn_task
     },
     api::{
-        wallet::NotifyEvent,
+        wallet::{
+            BuildTransactionParams,
+            NotifyEvent,
+            XSWDApplicationScope
+        },
         EventResult
     },
+    config::XELIS_ASSET,
     context::Context,
     crypto::{
         elgamal::PublicKey as DecompressedPublicKey,
+        Hash,
         Signature,
         SIGNATURE_SIZE
     },
@@ -72,7 +78,8 @@ use xelis_common::{
         ReaderError,
         Serializer,
         Writer
-    }
+    },
+    transaction::builder::TransactionTypeBuilder
 };
 use serde::{Deserialize, Serialize};
 use crate::config::XSWD_BIND_ADDRESS;
@@ -132,7 +139,9 @@ pub enum XSWDError {
     #[error("Application permissions are not signed")]
     ApplicationPermissionsNotSigned,
     #[error("Invalid signature for application data")]
-    InvalidSignatureForApplicationData
+    InvalidSignatureForApplicationData,
+    #[error("Asset is not allowed for this application")]
+    AssetNotAllowed
 }
 
 impl From<XSWDError> for InternalRpcError {
@@ -151,6 +160,13 @@ pub trait XSWDPermissionHandler {
     async fn cancel_request_permission(&self, app_state: &AppStateShared) -> Result<(), Error>;
     // Public key to use to verify the signature
     async fn get_public_key(&self) -> Result<&DecompressedPublicKey, Error>;
+    // Scope of permissions persisted for this application
+    async fn get_application_scope(&self, app_state: &AppStateShared) -> Result<Option<XSWDApplicationScope>, Error>;
+    // Persist the scope of permissions granted by the user to this application
+    async fn set_application_scope(&self, app_state: &AppStateShared, scope: XSWDApplicationScope) -> Result<(), Error>;
+    // Add the amounts to the daily spending of the application
+    // Returns false without updating it if a daily limit would be exceeded
+    async fn consume_application_budget(&self, app_state: &AppStateShared, amounts: &IndexMap<Hash, u64>) -> Result<bool, Error>;
 }
 
 #[async_trait]
@@ -229,6 +245,10 @@ pub struct ApplicationData {
     permissions: IndexMap<String, Permission>,
     // signature of all data
     signature: Option<Signature>,
+    // Scope of permissions requested to be persisted by the wallet
+    // It is not part of the signed data as the user must approve it
+    #[serde(default)]
+    scope: Option<XSWDApplicationScope>
 }
 
 impl ApplicationData {
@@ -255,6 +275,10 @@ impl ApplicationData {
     pub fn get_signature(&self) -> &Option<Signature> {
         &self.signature
     }
+
+    pub fn get_scope(&self) -> &Option<XSWDApplicationScope> {
+        &self.scope
+    }
 }
 
 // This serializer is only used to sign/verify a signature!
@@ -276,7 +300,8 @@ impl Serializer for ApplicationData {
             description,
             url,
             permissions,
-            signature: None
+            signature: None,
+            scope: None
         })
     }
 
@@ -374,7 +399,9 @@ impl Permission {
 pub enum PermissionRequest<'a> {
     // bool tell if it was already signed or not
     Application(bool),
-    Request(&'a RpcRequest)
+    Request(&'a RpcRequest),
+    // Scope of permissions to persist for the application
+    Scope(&'a XSWDApplicationScope)
 }
 
 pub enum PermissionResult {
@@ -470,6 +497,10 @@ where
             return Err(RpcResponseError::new(request.id.clone(), XSWDError::ApplicationNotFound))
         }
 
+        if self.is_allowed_by_scope(app, request).await? {
+            return Ok(())
+        }
+
         let permission = permissions.get(&request.method).map(|v| *v).unwrap_or(Permission::Ask);
         match permission {
             // Request permission from user
@@ -498,9 +529,49 @@ where
         }
     }
 
+    // verify the request against the scope persisted for the application
+    // returns true if the request is allowed without asking the user
+    async fn is_allowed_by_scope(&self, app: &AppStateShared, request: &RpcRequest) -> Result<bool, RpcResponseError> {
+        let handler = self.handler.get_data();
+        let scope = handler.get_application_scope(app).await
+            .map_err(|err| RpcResponseError::new(request.id.clone(), InternalRpcError::CustomAny(0, err)))?;
+
+        let Some(scope) = scope else {
+            return Ok(false)
+        };
+
+        // Assets restriction is applied to every request, even if the user is asked
+        if !scope.assets.is_empty() {
+            if let Some(asset) = get_request_assets(request).into_iter().find(|asset| !scope.assets.contains(asset)) {
+                debug!("Application {} is not allowed to access asset {}", app.get_name(), asset);
+                return Err(RpcResponseError::new(request.id.clone(), XSWDError::AssetNotAllowed))
+            }
+        }
+
+        if !scope.methods.contains(&request.method) {
+            return Ok(false)
+        }
+
+        if request.method != "build_transaction" {
+            return Ok(true)
+        }
+
+        // Transactions are only allowed within the daily limits
+        let Some(amounts) = get_request_amounts(request) else {
+            return Ok(false)
+        };
+
+        if amounts.keys().any(|asset| !scope.daily_limits.contains_key(asset)) {
+            return Ok(false)
+        }
+
+        handler.consume_application_budget(app, &amounts).await
+            .map_err(|err| RpcResponseError::new(request.id.clone(), InternalRpcError::CustomAny(0, err)))
+    }
+
     // register a new application
     // if the application is already registered, it will return an error
-    async fn add_application(&self, session: &WebSocketSessionShared<Self>, app_data: ApplicationData) -> Result<Value, RpcResponseError> {
+    async fn add_application(&self, session: &WebSocketSessionShared<Self>, mut app_data: ApplicationData) -> Result<Value, RpcResponseError> {
         // Sanity check
         {
             if app_data.id.len() != 64 {
@@ -568,6 +639,7 @@ where
         }
 
         let has_signature = app_data.signature.is_some();
+        let requested_scope = app_data.scope.take();
         let state = Arc::new(AppState::new(app_data));
         {
             let mut applications = self.applications.write().await;
@@ -595,6 +667,11 @@ where
             return Err(RpcResponseError::new(None, XSWDError::PermissionDenied))
         }
 
+        // Ask the user to persist the requested scope if it changed
+        if let Some(scope) = requested_scope {
+            self.request_scope(&state, scope).await;
+        }
+
         Ok(json!({
             "jsonrpc": "2.0",
             "id": Value::Null,
@@ -605,6 +682,65 @@ where
         }))
     }
 
+    // request the user to grant a scope of permissions to the application
+    // if refused, the application is still registered but every request is asked
+    async fn request_scope(&self, state: &AppStateShared, scope: XSWDApplicationScope) {
+        let wallet = self.handler.get_data();
+        match wallet.get_application_scope(state).await {
+            Ok(Some(current)) if current == scope => return,
+            Err(e) => {
+                error!("Error while retrieving scope of application {}: {}", state.get_name(), e);
+                return
+            },
+            _ => {}
+        };
+
+        state.set_requesting(true);
+        let result = wallet.request_permission(state, PermissionRequest::Scope(&scope)).await;
+        state.set_requesting(false);
+
+        match result {
+            Ok(result) if result.is_positive() => {
+                if let Err(e) = wallet.set_application_scope(state, scope).await {
+                    error!("Error while saving scope of application {}: {}", state.get_name(), e);
+                }
+            },
+            Ok(_) => debug!("Scope requested by application {} was refused", state.get_name()),
+            Err(e) => debug!("Error while requesting scope: {}", e)
+        }
+    }
+
+    // disconnect the application with the requested ID
+    // it is used when its permissions are revoked
+    pub async fn disconnect_application(&self, id: &str) -> bool {
+        let session = {
+            let mut applications = self.applications.write().await;
+            let session = applications.iter()
+                .find(|(_, app)| app.get_id() == id)
+                .map(|(session, _)| session.clone());
+
+            if let Some(session) = &session {
+                applications.remove(session);
+            }
+            session
+        };
+
+        let Some(session) = session else {
+            return false
+        };
+
+        {
+            let mut listeners = self.listeners.lock().await;
+            listeners.remove(&session);
+        }
+
+        if let Err(e) = session.close(None).await {
+            debug!("Error while closing session of application {}: {}", id, e);
+        }
+
+        true
+    }
+
     // register a new event listener for the specified connection/application
     async fn subscribe_session_to_event(&self, session: &WebSocketSessionShared<Self>, event: NotifyEvent, id: Option<Id>) -> Result<(), RpcResponseError> {
         let mut listeners = self.listeners.lock().await;
@@ -759,6 +895,56 @@ where
     }
 }
 
+// Assets accessed by a request
+fn get_request_assets(request: &RpcRequest) -> Vec<Hash> {
+    if request.method == "build_transaction" {
+        return get_request_amounts(request)
+            .map(|amounts| amounts.into_keys().collect())
+            .unwrap_or_default()
+    }
+
+    let asset = request.params.as_ref()
+        .and_then(|params| params.get("asset"))
+        .filter(|asset| !asset.is_null());
+
+    match asset {
+        Some(asset) => serde_json::from_value(asset.clone()).ok().into_iter().collect(),
+        // Balance methods use XELIS by default
+        None if request.method == "get_balance" || request.method == "has_balance" => vec![XELIS_ASSET],
+        None => Vec::new()
+    }
+}
+
+// Amounts per asset spent by a transaction request
+// Returns None if the transaction type can't be covered by a daily limit
+fn get_request_amounts(request: &RpcRequest) -> Option<IndexMap<Hash, u64>> {
+    let params: BuildTransactionParams = serde_json::from_value(request.params.clone()?).ok()?;
+    let mut amounts = IndexMap::new();
+    match params.tx_type {
+        TransactionTypeBuilder::Transfers(transfers) => {
+            for transfer in transfers {
+                let amount = amounts.entry(transfer.asset).or_insert(0u64);
+                *amount = amount.checked_add(transfer.amount)?;
+            }
+        },
+        TransactionTypeBuilder::Burn(payload) => {
+            amounts.insert(payload.asset, payload.amount);
+        },
+        TransactionTypeBuilder::InvokeContract(payload) => {
+            for (asset, deposit) in payload.deposits {
+                amounts.insert(asset, deposit.amount);
+            }
+
+            // Gas is paid in XELIS
+            let amount = amounts.entry(XELIS_ASSET).or_insert(0u64);
+            *amount = amount.checked_add(payload.max_gas)?;
+        },
+        _ => return None
+    };
+
+    Some(amounts)
+}
+
 #[get("/")]
 async fn index() -> Result<impl Responder, actix_web::Error> {
     Ok(HttpResponse::Ok().body("XSWD is running !"))
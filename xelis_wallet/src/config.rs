@@ -11,6 +11,49 @@ pub const KEY_SIZE: usize = 32;
 pub const DEFAULT_DAEMON_ADDRESS: &str = "http://127.0.0.1:8080";
 // Auto reconnect interval in seconds for Network Handler
 pub const AUTO_RECONNECT_INTERVAL: u64 = 5;
+// Maximum size in bytes of a name in the address book
+pub const MAX_ADDRESS_BOOK_NAME_SIZE: usize = 64;
+// Maximum size in bytes of the notes of an address book entry
+pub const MAX_ADDRESS_BOOK_NOTES_SIZE: usize = 1024;
+// Maximum size in bytes of a note attached to a transaction
+pub const MAX_TRANSACTION_NOTE_SIZE: usize = 1024;
+// Maximum size in bytes of a tag attached to a transaction
+pub const MAX_TRANSACTION_TAG_SIZE: usize = 32;
+// Maximum tags attached to a transaction
+pub const MAX_TRANSACTION_TAGS: usize = 16;
+// Maximum size in bytes of a scheduled payment name
+pub const MAX_SCHEDULED_PAYMENT_NAME_SIZE: usize = 64;
+// Minimum interval in seconds between two scheduled payments
+pub const MIN_SCHEDULED_PAYMENT_INTERVAL: u64 = 60;
+// Interval in seconds between two checks of the scheduled payments
+pub const SCHEDULED_PAYMENTS_CHECK_INTERVAL: u64 = 30;
+// Delay in seconds before retrying a failed scheduled payment
+pub const SCHEDULED_PAYMENT_RETRY_DELAY: u64 = 300;
+// Tag set on the transactions sent for a scheduled payment
+pub const SCHEDULED_PAYMENT_TAG: &str = "scheduled";
+// Maximum size in bytes of a wallet name in the wallet manager
+pub const MAX_WALLET_NAME_SIZE: usize = 64;
+// Maximum size in bytes of a local asset ticker
+pub const MAX_ASSET_TICKER_SIZE: usize = 8;
+// Ticker used for XELIS asset when no local ticker is set
+pub const XELIS_TICKER: &str = "XEL";
+// Current schema version of the wallet storage
+// Older storages are migrated when opened
+pub const STORAGE_VERSION: u16 = 1;
+// Changes below the synced topoheight minus this limit are pruned during a compaction
+// The highest one below it is kept to detect a DAG reorg
+pub const PRUNE_CHANGES_SAFETY_LIMIT: u64 = 80;
+// Interval in seconds between two health checks of the daemons when failover is enabled
+pub const DAEMON_HEALTH_CHECK_INTERVAL: u64 = 30;
+// Maximum topoheight difference with the best daemon before switching to it
+pub const MAX_DAEMON_TOPOHEIGHT_LAG: u64 = 20;
+// Interval in seconds between two inactivity checks when auto-lock is enabled
+pub const AUTO_LOCK_CHECK_INTERVAL: u64 = 5;
+// Delay in seconds before another unlock attempt after a failure
+// It is doubled on each consecutive failure
+pub const UNLOCK_FAILURE_DELAY: u64 = 2;
+// Maximum delay in seconds between two unlock attempts
+pub const MAX_UNLOCK_FAILURE_DELAY: u64 = 300;
 
 lazy_static! {
     pub static ref PASSWORD_ALGORITHM: Argon2<'static> = {
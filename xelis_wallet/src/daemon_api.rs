@@ -157,9 +157,30 @@ impl DaemonAPI {
 
     pub async fn get_account_assets(&self, address: &Address) -> Result<HashSet<Hash>> {
         trace!("get_account_assets");
-        let assets = self.client.call_with("get_account_assets", &GetAccountAssetsParams {
-            address: Cow::Borrowed(address)
-        }).await?;
+        let mut assets = HashSet::new();
+        let mut cursor: Option<Hash> = None;
+        // Daemon is limiting the assets returned per request, fetch all pages
+        loop {
+            let page: Vec<Hash> = self.client.call_with("get_account_assets", &GetAccountAssetsParams {
+                address: Cow::Borrowed(address),
+                cursor: cursor.as_ref().map(Cow::Borrowed),
+                maximum: None
+            }).await?;
+
+            let last = match page.last() {
+                Some(last) => last.clone(),
+                None => break
+            };
+
+            let len = assets.len();
+            assets.extend(page);
+            // No new asset, we reached the end
+            if assets.len() == len {
+                break;
+            }
+            cursor = Some(last);
+        }
+
         Ok(assets)
     }
 
@@ -174,6 +195,7 @@ impl DaemonAPI {
         let assets = self.client.call_with("get_assets", &GetAssetsParams {
             maximum,
             skip,
+            cursor: None,
             minimum_topoheight,
             maximum_topoheight
         }).await?;
@@ -249,6 +271,14 @@ impl DaemonAPI {
         Ok(nonce)
     }
 
+    pub async fn is_tx_executed(&self, hash: &Hash) -> Result<bool> {
+        trace!("is_tx_executed");
+        let is_executed = self.client.call_with("is_tx_executed", &IsTxExecutedParams {
+            hash: Cow::Borrowed(hash)
+        }).await?;
+        Ok(is_executed)
+    }
+
     pub async fn is_tx_executed_in_block(&self, tx_hash: &Hash, block_hash: &Hash) -> Result<bool> {
         trace!("is_tx_executed_in_block");
         let is_executed = self.client.call_with("is_tx_executed_in_block", &IsTxExecutedInBlockParams {
@@ -266,6 +296,14 @@ impl DaemonAPI {
         Ok(cache)
     }
 
+    pub async fn get_account_pending(&self, address: &Address) -> Result<GetAccountPendingResult<'static>> {
+        trace!("get_account_pending");
+        let pending = self.client.call_with("get_account_pending", &GetAccountPendingParams {
+            address: Cow::Borrowed(address)
+        }).await?;
+        Ok(pending)
+    }
+
     pub async fn is_account_registered(&self, address: &Address, in_stable_height: bool) -> Result<bool> {
         trace!("is_account_registered");
         let is_registered = self.client.call_with("is_account_registered", &IsAccountRegisteredParams {
@@ -302,6 +340,7 @@ impl DaemonAPI {
         trace!("get_multisig");
         let multisig = self.client.call_with("get_multisig", &GetMultisigParams {
             address: Cow::Borrowed(address),
+            topoheight: None
         }).await?;
         Ok(multisig)
     }
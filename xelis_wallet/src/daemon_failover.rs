@@ -0,0 +1,208 @@
+use std::{sync::Arc, time::Duration};
+use anyhow::Error;
+use log::{debug, error, info, trace, warn};
+use xelis_common::{
+    api::wallet::DaemonEndpointStatus,
+    tokio::{
+        spawn_task,
+        sync::Mutex,
+        task::JoinHandle,
+        time::sleep
+    },
+    utils::sanitize_daemon_address
+};
+use crate::{
+    config::DAEMON_HEALTH_CHECK_INTERVAL,
+    daemon_api::DaemonAPI,
+    wallet::Wallet
+};
+
+struct DaemonEndpoint {
+    address: String,
+    // Connection is created on the first health check
+    api: Mutex<Option<Arc<DaemonAPI>>>,
+    // Topoheight reported during the last health check
+    // None if the daemon couldn't be reached
+    topoheight: Mutex<Option<u64>>
+}
+
+// Keep the wallet connected to the healthiest daemon of a list
+// Daemons are checked periodically and the network handler is moved to another one
+// if the current daemon is offline or lagging behind the others
+pub struct DaemonFailover {
+    // Endpoints sorted by priority
+    endpoints: Vec<DaemonEndpoint>,
+    // Index of the endpoint used by the network handler
+    active: Mutex<Option<usize>>,
+    // Maximum topoheight difference with the best daemon
+    max_lag: u64,
+    // tokio task doing the health checks
+    task: Mutex<Option<JoinHandle<()>>>
+}
+
+impl DaemonFailover {
+    pub fn new(addresses: Vec<String>, max_lag: u64) -> Self {
+        let endpoints = addresses.into_iter()
+            .map(|address| DaemonEndpoint {
+                address,
+                api: Mutex::new(None),
+                topoheight: Mutex::new(None)
+            })
+            .collect();
+
+        Self {
+            endpoints,
+            active: Mutex::new(None),
+            max_lag,
+            task: Mutex::new(None)
+        }
+    }
+
+    // Get the connection to an endpoint, connecting or reconnecting it if needed
+    async fn get_api(&self, index: usize) -> Result<Arc<DaemonAPI>, Error> {
+        let endpoint = &self.endpoints[index];
+        let mut lock = endpoint.api.lock().await;
+        if let Some(api) = lock.as_ref() {
+            if !api.is_online() {
+                debug!("Daemon {} is offline, trying to reconnect", endpoint.address);
+                api.reconnect().await?;
+            }
+            return Ok(api.clone())
+        }
+
+        debug!("Connecting to daemon {}", endpoint.address);
+        let api = Arc::new(DaemonAPI::new(format!("{}/json_rpc", sanitize_daemon_address(&endpoint.address))).await?);
+        *lock = Some(api.clone());
+        Ok(api)
+    }
+
+    // Query the topoheight of every endpoint
+    async fn check_endpoints(&self) -> Vec<Option<u64>> {
+        let mut topoheights = Vec::with_capacity(self.endpoints.len());
+        for (index, endpoint) in self.endpoints.iter().enumerate() {
+            let topoheight = match self.get_api(index).await {
+                Ok(api) => match api.get_info().await {
+                    Ok(info) => Some(info.topoheight),
+                    Err(e) => {
+                        debug!("Couldn't get info from daemon {}: {}", endpoint.address, e);
+                        None
+                    }
+                },
+                Err(e) => {
+                    debug!("Couldn't connect to daemon {}: {}", endpoint.address, e);
+                    None
+                }
+            };
+
+            *endpoint.topoheight.lock().await = topoheight;
+            topoheights.push(topoheight);
+        }
+
+        topoheights
+    }
+
+    // Select the endpoint to use
+    // The active one is kept as long as it is online and not lagging
+    // Otherwise, the first endpoint at the best topoheight is selected
+    fn select_endpoint(&self, topoheights: &[Option<u64>], active: Option<usize>) -> Option<usize> {
+        let best = topoheights.iter().flatten().max().copied()?;
+        if let Some(index) = active {
+            if topoheights[index].is_some_and(|topoheight| best - topoheight <= self.max_lag) {
+                return Some(index)
+            }
+        }
+
+        topoheights.iter().position(|topoheight| *topoheight == Some(best))
+    }
+
+    // Select the healthiest daemon and returns its connection
+    // This doesn't change the active endpoint
+    pub async fn select(&self) -> Option<(usize, Arc<DaemonAPI>)> {
+        let topoheights = self.check_endpoints().await;
+        let active = *self.active.lock().await;
+        let index = self.select_endpoint(&topoheights, active)?;
+        let api = self.get_api(index).await.ok()?;
+        Some((index, api))
+    }
+
+    // Set the endpoint used by the network handler
+    pub async fn set_active(&self, index: usize) {
+        info!("Using daemon {}", self.endpoints[index].address);
+        *self.active.lock().await = Some(index);
+    }
+
+    // Start the health checks, the wallet is moved to another daemon if needed
+    pub async fn start(self: &Arc<Self>, wallet: Arc<Wallet>) {
+        trace!("Starting daemon failover");
+        let zelf = Arc::clone(self);
+        *self.task.lock().await = Some(spawn_task("daemon-failover", async move {
+            loop {
+                sleep(Duration::from_secs(DAEMON_HEALTH_CHECK_INTERVAL)).await;
+
+                // Syncing is paused while the wallet is locked
+                if wallet.is_locked() {
+                    continue;
+                }
+
+                let Some((index, api)) = zelf.select().await else {
+                    warn!("No daemon is available, trying again in {} seconds", DAEMON_HEALTH_CHECK_INTERVAL);
+                    continue;
+                };
+
+                let active = *zelf.active.lock().await;
+                if active == Some(index) && wallet.is_online().await {
+                    continue;
+                }
+
+                match active {
+                    Some(previous) => warn!("Daemon {} is unhealthy, switching to {}", zelf.endpoints[previous].address, zelf.endpoints[index].address),
+                    None => info!("Reconnecting to daemon {}", zelf.endpoints[index].address)
+                };
+
+                if let Err(e) = wallet.replace_daemon_api(api).await {
+                    error!("Error while switching to daemon {}: {}", zelf.endpoints[index].address, e);
+                    continue;
+                }
+                zelf.set_active(index).await;
+            }
+        }));
+    }
+
+    // Stop the health checks
+    pub async fn stop(&self) {
+        trace!("Stopping daemon failover");
+        if let Some(handle) = self.task.lock().await.take() {
+            handle.abort();
+        }
+    }
+
+    // Close the connections to the daemons
+    // Connections still used by a network handler are kept open
+    pub async fn disconnect(&self) {
+        trace!("Disconnecting daemon failover");
+        for endpoint in self.endpoints.iter() {
+            if let Some(api) = endpoint.api.lock().await.take() {
+                if let Err(e) = api.disconnect().await {
+                    debug!("Error while closing connection to daemon {}: {}", endpoint.address, e);
+                }
+            }
+        }
+    }
+
+    // Status of every endpoint from the last health check
+    pub async fn get_status(&self) -> Vec<DaemonEndpointStatus> {
+        let active = *self.active.lock().await;
+        let mut status = Vec::with_capacity(self.endpoints.len());
+        for (index, endpoint) in self.endpoints.iter().enumerate() {
+            let topoheight = *endpoint.topoheight.lock().await;
+            status.push(DaemonEndpointStatus {
+                address: endpoint.address.clone(),
+                online: topoheight.is_some(),
+                topoheight,
+                active: active == Some(index)
+            });
+        }
+
+        status
+    }
+}
@@ -1,11 +1,14 @@
 use indexmap::{IndexMap, IndexSet};
 use xelis_common::{
     time::TimestampMillis,
-    api::wallet::{
-        TransactionEntry as RPCTransactionEntry,
-        EntryType as RPCEntryType,
-        TransferIn as RPCTransferIn,
-        TransferOut as RPCTransferOut
+    api::{
+        wallet::{
+            TransactionEntry as RPCTransactionEntry,
+            EntryType as RPCEntryType,
+            TransferIn as RPCTransferIn,
+            TransferOut as RPCTransferOut
+        },
+        PaymentId
     },
     config::XELIS_ASSET,
     crypto::{
@@ -18,7 +21,11 @@ use xelis_common::{
         Serializer,
         Writer
     },
-    transaction::extra_data::PlaintextExtraData,
+    transaction::{
+        extra_data::PlaintextExtraData,
+        ContractManagementAction,
+        ExecutionCreditsPayload
+    },
     utils::{
         format_coin,
         format_xelis
@@ -74,6 +81,11 @@ impl TransferOut {
     pub fn get_extra_data(&self) -> &Option<PlaintextExtraData> {
         &self.extra_data
     }
+
+    // Payment ID standardized in the extra data
+    pub fn get_payment_id(&self) -> Option<PaymentId> {
+        self.extra_data.as_ref().and_then(|e| e.data().get_payment_id())
+    }
 }
 
 
@@ -97,6 +109,11 @@ impl TransferIn {
     pub fn get_extra_data(&self) -> &Option<PlaintextExtraData> {
         &self.extra_data
     }
+
+    // Payment ID standardized in the extra data
+    pub fn get_payment_id(&self) -> Option<PaymentId> {
+        self.extra_data.as_ref().and_then(|e| e.data().get_payment_id())
+    }
 }
 
 impl Serializer for TransferOut {
@@ -208,6 +225,24 @@ pub enum EntryData {
         fee: u64,
         // Nonce used
         nonce: u64
+    },
+    ManageContract {
+        // Contract address
+        contract: Hash,
+        // Action applied
+        action: ContractManagementAction,
+        // Fee paid
+        fee: u64,
+        // Nonce used
+        nonce: u64
+    },
+    ExecutionCredits {
+        // Deposit or withdraw of credits
+        payload: ExecutionCreditsPayload,
+        // Fee paid
+        fee: u64,
+        // Nonce used
+        nonce: u64
     }
 }
 
@@ -275,6 +310,19 @@ impl Serializer for EntryData {
                 let fee = reader.read_u64()?;
                 let nonce = reader.read_u64()?;
                 Self::DeployContract { fee, nonce }
+            },
+            7 => {
+                let contract = reader.read_hash()?;
+                let action = ContractManagementAction::read(reader)?;
+                let fee = reader.read_u64()?;
+                let nonce = reader.read_u64()?;
+                Self::ManageContract { contract, action, fee, nonce }
+            },
+            8 => {
+                let payload = ExecutionCreditsPayload::read(reader)?;
+                let fee = reader.read_u64()?;
+                let nonce = reader.read_u64()?;
+                Self::ExecutionCredits { payload, fee, nonce }
             }
             _ => return Err(ReaderError::InvalidValue)
         }) 
@@ -339,6 +387,19 @@ impl Serializer for EntryData {
                 writer.write_u8(6);
                 writer.write_u64(fee);
                 writer.write_u64(nonce);
+            },
+            Self::ManageContract { contract, action, fee, nonce } => {
+                writer.write_u8(7);
+                writer.write_hash(contract);
+                action.write(writer);
+                writer.write_u64(fee);
+                writer.write_u64(nonce);
+            },
+            Self::ExecutionCredits { payload, fee, nonce } => {
+                writer.write_u8(8);
+                payload.write(writer);
+                writer.write_u64(fee);
+                writer.write_u64(nonce);
             }
         }
     }
@@ -361,6 +422,12 @@ impl Serializer for EntryData {
             },
             Self::DeployContract { fee, nonce } => {
                 fee.size() + nonce.size()
+            },
+            Self::ManageContract { contract, action, fee, nonce } => {
+                contract.size() + action.size() + fee.size() + nonce.size()
+            },
+            Self::ExecutionCredits { payload, fee, nonce } => {
+                payload.size() + fee.size() + nonce.size()
             }
         }
     }
@@ -436,6 +503,7 @@ impl TransactionEntry {
                 EntryData::Burn { asset, amount, fee, nonce } => RPCEntryType::Burn { asset, amount, fee, nonce },
                 EntryData::Incoming { from, transfers } => {
                     let transfers = transfers.into_iter().map(|t| RPCTransferIn {
+                        payment_id: t.get_payment_id(),
                         asset: t.asset,
                         amount: t.amount,
                         extra_data: t.extra_data
@@ -444,6 +512,7 @@ impl TransactionEntry {
                 },
                 EntryData::Outgoing { transfers, fee, nonce } => {
                     let transfers = transfers.into_iter().map(|t| RPCTransferOut {
+                        payment_id: t.get_payment_id(),
                         destination: t.destination.to_address(mainnet),
                         asset: t.asset,
                         amount: t.amount,
@@ -460,6 +529,12 @@ impl TransactionEntry {
                 },
                 EntryData::DeployContract { fee, nonce } => {
                     RPCEntryType::DeployContract { fee, nonce }
+                },
+                EntryData::ManageContract { contract, action, fee, nonce } => {
+                    RPCEntryType::ManageContract { contract, action, fee, nonce }
+                },
+                EntryData::ExecutionCredits { payload, fee, nonce } => {
+                    RPCEntryType::ExecutionCredits { payload, fee, nonce }
                 }
             }
         }
@@ -481,6 +556,10 @@ impl TransactionEntry {
                         let data = storage.get_asset(transfer.get_asset()).await?;
                         str.push_str(&format!("Received {} {} from {}", format_coin(transfer.get_amount(), data.get_decimals()), transfer.get_asset(), from.as_address(mainnet)));
                     }
+
+                    if let Some(payment_id) = transfer.get_payment_id() {
+                        str.push_str(&format!(" with payment ID {}", payment_id));
+                    }
                 }
                 str
             },
@@ -515,6 +594,14 @@ impl TransactionEntry {
             },
             EntryData::DeployContract { fee, nonce } => {
                 format!("Fee: {}, Nonce: {} Deploy contract", format_xelis(*fee), nonce)
+            },
+            EntryData::ManageContract { contract, action, fee, nonce } => {
+                let action = if action.is_pause() { "Pause" } else { "Unpause" };
+                format!("Fee: {}, Nonce: {} {} contract {}", format_xelis(*fee), nonce, action, contract)
+            },
+            EntryData::ExecutionCredits { payload, fee, nonce } => {
+                let action = if payload.is_deposit() { "Deposit" } else { "Withdraw" };
+                format!("Fee: {}, Nonce: {} {} {} XELIS of execution credits", format_xelis(*fee), nonce, action, format_xelis(payload.get_amount()))
             }
         };
 
@@ -576,4 +663,11 @@ impl<'a> Transfer<'a> {
             Transfer::Out(t) => &t.extra_data
         }
     }
+
+    pub fn get_payment_id(&self) -> Option<PaymentId> {
+        match self {
+            Transfer::In(t) => t.get_payment_id(),
+            Transfer::Out(t) => t.get_payment_id()
+        }
+    }
 }
@@ -3,7 +3,12 @@ use chacha20poly1305::Error as CryptoError;
 #[cfg(feature = "network_handler")]
 use super::network_handler::NetworkError;
 use xelis_common::{
-    crypto::Hash,
+    crypto::{
+        proofs::{ProofGenerationError, ProofVerificationError},
+        Address,
+        Hash
+    },
+    payment_uri::PaymentUriError,
     transaction::extra_data::CipherFormatError,
     utils::{format_coin, format_xelis}
 };
@@ -92,6 +97,118 @@ pub enum WalletError {
     NoNetworkHandler,
     #[error("Poison error")]
     PoisonError,
+    #[error("This action is not allowed on a watch-only wallet")]
+    WatchOnlyWallet,
+    #[error("Prepared transaction source {} doesn't match this wallet", _0)]
+    InvalidPreparedTransactionSource(Address),
+    #[error("No multisig is configured for this wallet")]
+    NoMultiSigConfigured,
+    #[error("MultiSig transactions require at least transaction version V1")]
+    MultiSigInvalidTxVersion,
+    #[error("Pending multisig transaction {} was not found", _0)]
+    PendingMultiSigNotFound(Hash),
+    #[error("Invalid multisig signer ID {}", _0)]
+    InvalidMultiSigSigner(u8),
+    #[error("Invalid signature for multisig signer ID {}", _0)]
+    InvalidMultiSigSignature(u8),
+    #[error("Multisig signer ID {} has already signed", _0)]
+    MultiSigAlreadySigned(u8),
+    #[error("Multisig threshold not reached: {} signatures for a threshold of {}", _0, _1)]
+    MultiSigThresholdNotReached(usize, u8),
+    #[error("No rescan in progress")]
+    NoRescanInProgress,
+    #[error("Invalid address book name, it must be between 1 and {} bytes", _0)]
+    InvalidAddressBookName(usize),
+    #[error("Address book notes are too long, maximum {} bytes", _0)]
+    AddressBookNotesTooLong(usize),
+    #[error("Address book entry {} was not found", _0)]
+    AddressBookEntryNotFound(String),
+    #[error("Address is not on the same network as the wallet")]
+    InvalidAddressNetwork,
+    #[error("Transaction {} was not found in wallet history", _0)]
+    TransactionNotFound(Hash),
+    #[error("Transaction note is too long, maximum {} bytes", _0)]
+    TransactionNoteTooLong(usize),
+    #[error("Invalid transaction tag, it must be between 1 and {} bytes", _0)]
+    InvalidTransactionTag(usize),
+    #[error("Too many tags for a transaction, maximum {}", _0)]
+    TooManyTransactionTags(usize),
+    #[error("Invalid payment URI: {}", _0)]
+    InvalidPaymentUri(#[from] PaymentUriError),
+    #[error("Invalid scheduled payment name, it must be between 1 and {} bytes", _0)]
+    InvalidScheduledPaymentName(usize),
+    #[error("Scheduled payment interval is too low, minimum is {} seconds", _0)]
+    ScheduledPaymentIntervalTooLow(u64),
+    #[error("Scheduled payment {} was not found", _0)]
+    ScheduledPaymentNotFound(String),
+    #[error("Scheduled payments are not supported with a multisig setup")]
+    ScheduledPaymentMultiSig,
+    #[error("Scheduled payment count must be greater than zero")]
+    InvalidScheduledPaymentCount,
+    #[error("Invalid wallet name, only alphanumeric characters, '-' and '_' are allowed up to {} bytes", _0)]
+    InvalidWalletName(usize),
+    #[error("Wallet {} is already open", _0)]
+    WalletAlreadyOpen(String),
+    #[error("Wallet {} is not open", _0)]
+    WalletNotOpen(String),
+    #[error("No shared daemon connection configured")]
+    NoSharedDaemonConnection,
+    #[error("XSWD application {} was not found", _0)]
+    XSWDApplicationNotFound(String),
+    #[error("Transaction {} is not the last pending transaction created by this wallet", _0)]
+    PendingTransactionNotFound(Hash),
+    #[error("Transaction {} can't be replaced as previous transactions are not executed yet", _0)]
+    PendingTransactionNotReplaceable(Hash),
+    #[error("Fee is too low to replace the transaction, expected at least {}, got {}", _0, _1)]
+    ReplacementFeeTooLow(u64, u64),
+    #[error("Asset {} was not found", _0)]
+    AssetNotFound(Hash),
+    #[error("Invalid amount, asset supports up to {} decimals", _0)]
+    InvalidAmount(u8),
+    #[error("Invalid asset ticker, it must be alphanumeric and up to {} bytes", _0)]
+    InvalidAssetTicker(usize),
+    #[error("Transaction {} was not sent by this wallet", _0)]
+    TransactionNotSentByWallet(Hash),
+    #[error("Transfer #{} was not found in transaction {}", _1, _0)]
+    TransferNotFound(Hash, u8),
+    #[error("Daemon returned another transaction than {}", _0)]
+    InvalidDaemonTransaction(Hash),
+    #[error("Expected a payment proof")]
+    ExpectedPaymentProof,
+    #[error("Invalid payment proof: {}", _0)]
+    InvalidPaymentProof(ProofVerificationError),
+    #[error("Invalid reserve proof challenge, maximum {} bytes", _0)]
+    InvalidReserveChallenge(usize),
+    #[error("Error while generating reserve proof: {}", _0)]
+    ReserveProofGeneration(ProofGenerationError),
+    #[error("Expected a reserve proof")]
+    ExpectedReserveProof,
+    #[error("Invalid reserve proof: {}", _0)]
+    InvalidReserveProof(ProofVerificationError),
+    #[error("Unsupported storage version {}, this wallet supports up to version {}", _0, _1)]
+    UnsupportedStorageVersion(u16, u16),
+    #[error("No daemon address provided")]
+    NoDaemonAddress,
+    #[error("No daemon is available")]
+    NoDaemonAvailable,
+    #[error("Daemon failover is not enabled")]
+    DaemonFailoverNotEnabled,
+    #[error("Sub-accounts can only be derived from the master account")]
+    NotMasterAccount,
+    #[error("Wallet is locked, unlock it using its password")]
+    WalletLocked,
+    #[error("Too many failed unlock attempts, try again in {} seconds", _0)]
+    UnlockRateLimited(u64),
+    #[error("Expected an audit proof")]
+    ExpectedAuditProof,
+    #[error("Invalid audit proof: {}", _0)]
+    InvalidAuditProof(ProofVerificationError),
+    #[error("This wallet is not the auditor designated by the proof")]
+    NotDesignatedAuditor,
+    #[error("Transfer #{} of transaction {} was not sent or received by this wallet", _1, _0)]
+    TransferNotForWallet(Hash, u8),
+    #[error("Signature was not completed from this adaptor signature")]
+    InvalidAdaptorSignature,
 }
 
 impl WalletError {
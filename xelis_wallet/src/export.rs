@@ -0,0 +1,223 @@
+use std::io::Write;
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::Serialize;
+use xelis_common::{
+    api::wallet::HistoryExportFormat,
+    block::TopoHeight,
+    config::{COIN_DECIMALS, XELIS_ASSET},
+    crypto::Hash,
+    time::TimestampMillis,
+    utils::format_coin
+};
+use crate::{
+    entry::{EntryData, TransactionEntry},
+    error::WalletError,
+    storage::EncryptedStorage
+};
+
+// Source of fiat prices used to annotate the exported history
+// It can be implemented by any application embedding the wallet
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    // Fiat currency of the prices (USD, EUR...)
+    fn currency(&self) -> &str;
+
+    // Price of one whole unit of the asset at the given time
+    // Returns None if no price is known
+    async fn get_price(&self, asset: &Hash, timestamp: TimestampMillis) -> Result<Option<f64>, anyhow::Error>;
+}
+
+#[derive(Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryDirection {
+    Incoming,
+    Outgoing,
+    Coinbase,
+    Burn,
+    #[serde(rename = "multisig")]
+    MultiSig,
+    InvokeContract,
+    DeployContract,
+    ManageContract,
+    ExecutionCredits
+}
+
+impl HistoryDirection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Incoming => "incoming",
+            Self::Outgoing => "outgoing",
+            Self::Coinbase => "coinbase",
+            Self::Burn => "burn",
+            Self::MultiSig => "multisig",
+            Self::InvokeContract => "invoke_contract",
+            Self::DeployContract => "deploy_contract",
+            Self::ManageContract => "manage_contract",
+            Self::ExecutionCredits => "execution_credits"
+        }
+    }
+}
+
+// One line of the exported history
+// A transaction with several transfers produces one line per transfer
+// Its fee is only set on the first line to not count it twice
+#[derive(Serialize, Clone, Debug)]
+pub struct HistoryExportEntry {
+    pub timestamp: TimestampMillis,
+    pub topoheight: TopoHeight,
+    pub hash: Hash,
+    pub direction: HistoryDirection,
+    // Sender, destination, contract or participants
+    pub counterparty: Option<String>,
+    pub asset: Option<Hash>,
+    // Amount in atomic units
+    pub amount: Option<u64>,
+    pub decimals: Option<u8>,
+    // Fee paid in XELIS atomic units
+    pub fee: Option<u64>,
+    // Fiat value of the amount at the time of the transaction
+    pub fiat_value: Option<f64>,
+    pub fiat_currency: Option<String>
+}
+
+impl HistoryExportEntry {
+    fn new(tx: &TransactionEntry, direction: HistoryDirection, counterparty: Option<String>, fee: Option<u64>) -> Self {
+        Self {
+            timestamp: tx.get_timestamp(),
+            topoheight: tx.get_topoheight(),
+            hash: tx.get_hash().clone(),
+            direction,
+            counterparty,
+            asset: None,
+            amount: None,
+            decimals: None,
+            fee,
+            fiat_value: None,
+            fiat_currency: None
+        }
+    }
+
+    fn with_amount(mut self, asset: Hash, amount: u64, decimals: u8) -> Self {
+        self.asset = Some(asset);
+        self.amount = Some(amount);
+        self.decimals = Some(decimals);
+        self
+    }
+}
+
+// Build all the export lines from the wallet history sorted by topoheight
+pub async fn build_history_export(storage: &EncryptedStorage, mut transactions: Vec<TransactionEntry>, mainnet: bool, price_source: Option<&dyn PriceSource>) -> Result<Vec<HistoryExportEntry>, WalletError> {
+    transactions.sort_by(|a, b| a.get_topoheight().cmp(&b.get_topoheight()));
+
+    let mut entries = Vec::with_capacity(transactions.len());
+    for tx in transactions.iter() {
+        match tx.get_entry() {
+            EntryData::Coinbase { reward } => {
+                entries.push(HistoryExportEntry::new(tx, HistoryDirection::Coinbase, None, None)
+                    .with_amount(XELIS_ASSET, *reward, COIN_DECIMALS));
+            },
+            EntryData::Burn { asset, amount, fee, .. } => {
+                let decimals = storage.get_asset(asset).await?.get_decimals();
+                entries.push(HistoryExportEntry::new(tx, HistoryDirection::Burn, None, Some(*fee))
+                    .with_amount(asset.clone(), *amount, decimals));
+            },
+            EntryData::Incoming { from, transfers } => {
+                let from = from.as_address(mainnet).to_string();
+                for transfer in transfers {
+                    let decimals = storage.get_asset(transfer.get_asset()).await?.get_decimals();
+                    entries.push(HistoryExportEntry::new(tx, HistoryDirection::Incoming, Some(from.clone()), None)
+                        .with_amount(transfer.get_asset().clone(), transfer.get_amount(), decimals));
+                }
+            },
+            EntryData::Outgoing { transfers, fee, .. } => {
+                for (i, transfer) in transfers.iter().enumerate() {
+                    let decimals = storage.get_asset(transfer.get_asset()).await?.get_decimals();
+                    let destination = transfer.get_destination().as_address(mainnet).to_string();
+                    let fee = if i == 0 { Some(*fee) } else { None };
+                    entries.push(HistoryExportEntry::new(tx, HistoryDirection::Outgoing, Some(destination), fee)
+                        .with_amount(transfer.get_asset().clone(), transfer.get_amount(), decimals));
+                }
+            },
+            EntryData::MultiSig { participants, fee, .. } => {
+                let participants: Vec<String> = participants.iter().map(|p| p.as_address(mainnet).to_string()).collect();
+                entries.push(HistoryExportEntry::new(tx, HistoryDirection::MultiSig, Some(participants.join("|")), Some(*fee)));
+            },
+            EntryData::InvokeContract { contract, deposits, fee, .. } => {
+                if deposits.is_empty() {
+                    entries.push(HistoryExportEntry::new(tx, HistoryDirection::InvokeContract, Some(contract.to_string()), Some(*fee)));
+                }
+
+                for (i, (asset, amount)) in deposits.iter().enumerate() {
+                    let decimals = storage.get_asset(asset).await?.get_decimals();
+                    let fee = if i == 0 { Some(*fee) } else { None };
+                    entries.push(HistoryExportEntry::new(tx, HistoryDirection::InvokeContract, Some(contract.to_string()), fee)
+                        .with_amount(asset.clone(), *amount, decimals));
+                }
+            },
+            EntryData::DeployContract { fee, .. } => {
+                entries.push(HistoryExportEntry::new(tx, HistoryDirection::DeployContract, None, Some(*fee)));
+            },
+            EntryData::ManageContract { contract, fee, .. } => {
+                entries.push(HistoryExportEntry::new(tx, HistoryDirection::ManageContract, Some(contract.to_string()), Some(*fee)));
+            },
+            EntryData::ExecutionCredits { payload, fee, .. } => {
+                entries.push(HistoryExportEntry::new(tx, HistoryDirection::ExecutionCredits, None, Some(*fee))
+                    .with_amount(XELIS_ASSET, payload.get_amount(), COIN_DECIMALS));
+            }
+        }
+    }
+
+    if let Some(source) = price_source {
+        for entry in entries.iter_mut() {
+            let (Some(asset), Some(amount), Some(decimals)) = (&entry.asset, entry.amount, entry.decimals) else {
+                continue;
+            };
+
+            if let Some(price) = source.get_price(asset, entry.timestamp).await? {
+                entry.fiat_value = Some(amount as f64 / 10f64.powi(decimals as i32) * price);
+                entry.fiat_currency = Some(source.currency().to_owned());
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+// Write the export lines in the requested format
+pub fn write_history_export<W: Write>(entries: &[HistoryExportEntry], format: HistoryExportFormat, w: &mut W) -> Result<(), WalletError> {
+    match format {
+        HistoryExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut *w, entries).context("Error while writing JSON")?;
+        },
+        HistoryExportFormat::Csv => {
+            writeln!(w, "Timestamp,Date,TopoHeight,Hash,Direction,Counterparty,Asset,Amount,Fee,FiatValue,FiatCurrency").context("Error while writing headers")?;
+            for entry in entries {
+                let date = chrono::DateTime::from_timestamp_millis(entry.timestamp as i64)
+                    .ok_or(WalletError::InvalidDatetime)?
+                    .to_rfc3339();
+                let amount = match (entry.amount, entry.decimals) {
+                    (Some(amount), Some(decimals)) => format_coin(amount, decimals),
+                    _ => "-".to_owned()
+                };
+
+                writeln!(w, "{},{},{},{},{},{},{},{},{},{},{}",
+                    entry.timestamp,
+                    date,
+                    entry.topoheight,
+                    entry.hash,
+                    entry.direction.as_str(),
+                    entry.counterparty.as_deref().unwrap_or("-"),
+                    entry.asset.as_ref().map(|a| a.to_string()).unwrap_or_else(|| "-".to_owned()),
+                    amount,
+                    entry.fee.map(|fee| format_coin(fee, COIN_DECIMALS)).unwrap_or_else(|| "-".to_owned()),
+                    entry.fiat_value.map(|v| v.to_string()).unwrap_or_else(|| "-".to_owned()),
+                    entry.fiat_currency.as_deref().unwrap_or("-")
+                ).context("Error while writing csv line")?;
+            }
+        }
+    }
+
+    w.flush().context("Error while flushing export")?;
+    Ok(())
+}
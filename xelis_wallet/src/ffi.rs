@@ -0,0 +1,318 @@
+// Safety requirements are the same for every function, see below
+#![allow(clippy::missing_safety_doc)]
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    ops::ControlFlow,
+    ptr,
+    sync::Arc
+};
+use anyhow::{anyhow, bail, Context, Error};
+use log::{info, warn};
+use zeroize::Zeroize;
+use xelis_common::{
+    config::XELIS_ASSET,
+    crypto::{ecdlp, Address, Hash, Hashable},
+    network::Network,
+    serializer::Serializer,
+    tokio::runtime::{Builder, Runtime},
+    transaction::builder::{FeeBuilder, TransactionTypeBuilder, TransferBuilder}
+};
+use crate::{
+    precomputed_tables::{self, PrecomputedTablesShared},
+    wallet::{RecoverOption, Wallet}
+};
+
+// C ABI exposing the wallet to mobile applications (see include/xelis_wallet.h)
+// Every function returns a status code and writes its result in the out parameters
+// The message of the last error on the calling thread is available using xelis_last_error
+// Strings returned by the library must be released using xelis_string_free
+// Pointers given by the caller must be valid for the duration of the call
+
+pub const XELIS_OK: i32 = 0;
+pub const XELIS_ERROR: i32 = -1;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+// Opaque handle of an opened wallet
+// Each wallet has its own runtime to execute the async calls
+pub struct XelisWallet {
+    runtime: Runtime,
+    wallet: Arc<Wallet>
+}
+
+// Log the progress of the tables generation
+struct LogProgressReport;
+
+impl ecdlp::ProgressTableGenerationReportFunction for LogProgressReport {
+    fn report(&self, progress: f64, step: ecdlp::ReportStep) -> ControlFlow<()> {
+        info!("Progress: {:.2}% on step {:?}", progress * 100.0, step);
+        ControlFlow::Continue(())
+    }
+}
+
+// Convert the result to a status code, the error is kept for xelis_last_error
+fn status(res: Result<(), Error>) -> i32 {
+    match res {
+        Ok(()) => XELIS_OK,
+        Err(e) => {
+            let message = CString::new(format!("{:#}", e)).unwrap_or_default();
+            LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+            XELIS_ERROR
+        }
+    }
+}
+
+unsafe fn read_str<'a>(value: *const c_char) -> Result<&'a str, Error> {
+    if value.is_null() {
+        bail!("Unexpected null pointer")
+    }
+
+    CStr::from_ptr(value).to_str().context("Invalid UTF-8 string")
+}
+
+unsafe fn read_optional_str<'a>(value: *const c_char) -> Result<Option<&'a str>, Error> {
+    if value.is_null() {
+        return Ok(None)
+    }
+
+    read_str(value).map(Some)
+}
+
+unsafe fn write<T>(out: *mut T, value: T) -> Result<(), Error> {
+    if out.is_null() {
+        bail!("Unexpected null output pointer")
+    }
+
+    *out = value;
+    Ok(())
+}
+
+unsafe fn write_string(out: *mut *mut c_char, value: String) -> Result<(), Error> {
+    let value = CString::new(value).context("String contains a nul byte")?;
+    write(out, value.into_raw())
+}
+
+unsafe fn get_wallet<'a>(wallet: *const XelisWallet) -> Result<&'a XelisWallet, Error> {
+    wallet.as_ref().context("Unexpected null wallet")
+}
+
+// Build the runtime and load the precomputed tables before opening the wallet
+unsafe fn init_wallet<F>(network: *const c_char, tables_path: *const c_char, out: *mut *mut XelisWallet, f: F) -> Result<(), Error>
+where
+    F: FnOnce(Network, PrecomputedTablesShared) -> Result<Arc<Wallet>, Error>
+{
+    let network: Network = read_str(network)?.parse()
+        .map_err(|e: String| anyhow!(e))?;
+    let tables_path = read_optional_str(tables_path)?;
+
+    let runtime = Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .context("Error while building runtime")?;
+
+    let tables = runtime.block_on(precomputed_tables::read_or_generate_precomputed_tables(tables_path, precomputed_tables::L1_LOW, LogProgressReport, true))?;
+    let wallet = {
+        let _guard = runtime.enter();
+        f(network, tables)?
+    };
+
+    let handle = Box::new(XelisWallet {
+        runtime,
+        wallet
+    });
+    write(out, Box::into_raw(handle))
+}
+
+// Retrieve the message of the last error that happened on this thread
+// The pointer is valid until the next error, it must not be released
+#[no_mangle]
+pub extern "C" fn xelis_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+// Release a string returned by the library
+#[no_mangle]
+pub unsafe extern "C" fn xelis_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        // Returned strings may contain a seed, wipe them before releasing them
+        let mut bytes = CString::from_raw(value).into_bytes();
+        bytes.zeroize();
+    }
+}
+
+// Open an existing wallet
+// Network is one of "mainnet", "testnet" or "dev"
+// Tables path is optional and must end with a '/'
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_open(name: *const c_char, password: *const c_char, network: *const c_char, tables_path: *const c_char, out: *mut *mut XelisWallet) -> i32 {
+    status((|| {
+        let name = read_str(name)?;
+        let password = read_str(password)?;
+        init_wallet(network, tables_path, out, |network, tables| Wallet::open(name, password, network, tables))
+    })())
+}
+
+// Create a new wallet, recovered from the seed if not null
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_create(name: *const c_char, password: *const c_char, seed: *const c_char, network: *const c_char, tables_path: *const c_char, out: *mut *mut XelisWallet) -> i32 {
+    status((|| {
+        let name = read_str(name)?;
+        let password = read_str(password)?;
+        let seed = read_optional_str(seed)?;
+        init_wallet(network, tables_path, out, |network, tables| Wallet::create(name, password, seed.map(RecoverOption::Seed), network, tables))
+    })())
+}
+
+// Close the wallet and release its handle
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_free(wallet: *mut XelisWallet) {
+    if !wallet.is_null() {
+        let handle = Box::from_raw(wallet);
+        handle.runtime.block_on(handle.wallet.close());
+    }
+}
+
+// Connect the wallet to a daemon to synchronize it
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_set_online(wallet: *const XelisWallet, daemon_address: *const c_char) -> i32 {
+    status((|| {
+        let handle = get_wallet(wallet)?;
+        let daemon_address = read_str(daemon_address)?.to_owned();
+        handle.runtime.block_on(handle.wallet.set_online_mode(&daemon_address, true))?;
+        Ok(())
+    })())
+}
+
+// Disconnect the wallet from its daemon
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_set_offline(wallet: *const XelisWallet) -> i32 {
+    status((|| {
+        let handle = get_wallet(wallet)?;
+        handle.runtime.block_on(handle.wallet.set_offline_mode())?;
+        Ok(())
+    })())
+}
+
+// Write 1 if the wallet is connected to a daemon, 0 otherwise
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_is_online(wallet: *const XelisWallet, out: *mut u8) -> i32 {
+    status((|| {
+        let handle = get_wallet(wallet)?;
+        let online = handle.runtime.block_on(handle.wallet.is_online());
+        write(out, online as u8)
+    })())
+}
+
+// Retrieve the address of the wallet
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_get_address(wallet: *const XelisWallet, out: *mut *mut c_char) -> i32 {
+    status((|| {
+        let handle = get_wallet(wallet)?;
+        write_string(out, handle.wallet.get_address().to_string())
+    })())
+}
+
+// Retrieve the seed of the wallet in the requested language
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_get_seed(wallet: *const XelisWallet, language: u32, out: *mut *mut c_char) -> i32 {
+    status((|| {
+        let handle = get_wallet(wallet)?;
+        write_string(out, handle.wallet.get_seed(language as usize)?.to_string())
+    })())
+}
+
+// Retrieve the topoheight at which the wallet is synced
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_get_topoheight(wallet: *const XelisWallet, out: *mut u64) -> i32 {
+    status((|| {
+        let handle = get_wallet(wallet)?;
+        let topoheight = handle.runtime.block_on(async {
+            let storage = handle.wallet.get_storage().read().await;
+            storage.get_synced_topoheight()
+        })?;
+        write(out, topoheight)
+    })())
+}
+
+// Retrieve the balance in atomic units of an asset, XELIS is used if the asset is null
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_get_balance(wallet: *const XelisWallet, asset: *const c_char, out: *mut u64) -> i32 {
+    status((|| {
+        let handle = get_wallet(wallet)?;
+        let asset = match read_optional_str(asset)? {
+            Some(asset) => Hash::from_hex(asset).context("Invalid asset")?,
+            None => XELIS_ASSET
+        };
+
+        let balance = handle.runtime.block_on(async {
+            let storage = handle.wallet.get_storage().read().await;
+            storage.get_plaintext_balance_for(&asset).await.unwrap_or(0)
+        });
+        write(out, balance)
+    })())
+}
+
+// Retrieve the whole history of the wallet in JSON format
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_get_history(wallet: *const XelisWallet, out: *mut *mut c_char) -> i32 {
+    status((|| {
+        let handle = get_wallet(wallet)?;
+        let mainnet = handle.wallet.get_network().is_mainnet();
+        let transactions = handle.runtime.block_on(async {
+            let storage = handle.wallet.get_storage().read().await;
+            storage.get_filtered_transactions(None, None, None, None, true, true, true, true, None, None, None)
+        })?;
+
+        let transactions: Vec<_> = transactions.into_iter()
+            .map(|tx| tx.serializable(mainnet))
+            .collect();
+        write_string(out, serde_json::to_string(&transactions)?)
+    })())
+}
+
+// Send an amount in atomic units of an asset to an address, XELIS is used if the asset is null
+// The hash of the transaction is written once it has been accepted by the daemon
+#[no_mangle]
+pub unsafe extern "C" fn xelis_wallet_send(wallet: *const XelisWallet, destination: *const c_char, asset: *const c_char, amount: u64, out: *mut *mut c_char) -> i32 {
+    status((|| {
+        let handle = get_wallet(wallet)?;
+        let destination = Address::from_string(&read_str(destination)?.to_owned())
+            .context("Invalid destination address")?;
+        let asset = match read_optional_str(asset)? {
+            Some(asset) => Hash::from_hex(asset).context("Invalid asset")?,
+            None => XELIS_ASSET
+        };
+
+        let transfer = TransferBuilder {
+            destination,
+            amount,
+            asset,
+            extra_data: None
+        };
+
+        let hash = handle.runtime.block_on(async {
+            let wallet = &handle.wallet;
+            let tx = wallet.create_transaction(TransactionTypeBuilder::Transfers(vec![transfer]), FeeBuilder::default()).await?;
+            if let Err(e) = wallet.submit_transaction(&tx).await {
+                warn!("Couldn't submit transaction {}: {}", tx.hash(), e);
+                // Maybe cache is corrupted, clear it
+                let mut storage = wallet.get_storage().write().await;
+                storage.clear_tx_cache();
+                storage.delete_unconfirmed_balances().await;
+                return Err(e.into())
+            }
+
+            Ok::<_, Error>(tx.hash())
+        })?;
+
+        write_string(out, hash.to_hex())
+    })())
+}
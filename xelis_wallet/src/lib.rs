@@ -6,6 +6,7 @@ pub mod entry;
 pub mod mnemonics;
 pub mod transaction_builder;
 pub mod error;
+pub mod export;
 
 pub mod precomputed_tables;
 
@@ -15,5 +16,25 @@ pub mod daemon_api;
 #[cfg(feature = "network_handler")]
 pub mod network_handler;
 
+#[cfg(feature = "network_handler")]
+pub mod daemon_failover;
+
+#[cfg(feature = "network_handler")]
+pub mod wallet_manager;
+
 #[cfg(feature = "api_server")]
-pub mod api;
\ No newline at end of file
+pub mod api;
+
+#[cfg(all(
+    target_arch = "wasm32",
+    target_vendor = "unknown",
+    target_os = "unknown",
+    feature = "wasm_bindings"
+))]
+pub mod wasm;
+
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    feature = "ffi"
+))]
+pub mod ffi;
\ No newline at end of file
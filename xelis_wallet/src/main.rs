@@ -8,12 +8,15 @@ use std::{
 };
 use anyhow::{Result, Context};
 use indexmap::IndexSet;
-use log::{error, info};
+use log::{error, info, warn};
 use clap::Parser;
 use serde::{Deserialize, Serialize};
+use qrcode::{render::unicode, QrCode};
 use xelis_common::{
+    api::wallet::{AssetFilter, HistoryExportFormat, PreparedTransaction},
     async_handler,
     config::{
+        COIN_DECIMALS,
         VERSION,
         XELIS_ASSET
     },
@@ -21,6 +24,7 @@ use xelis_common::{
         ecdlp,
         Address,
         Hashable,
+        HumanReadableProof,
         Signature
     },
     network::Network,
@@ -36,6 +40,7 @@ use xelis_common::{
             CommandHandler,
             CommandManager
         },
+        logger::LogOptions,
         Color,
         LogLevel,
         ModuleConfig,
@@ -53,14 +58,16 @@ use xelis_common::{
         TxVersion
     },
     utils::{
-        format_coin,
         format_xelis,
         from_coin
     }
 };
 use xelis_wallet::{
     config::DIR_PATH,
-    precomputed_tables,
+    mnemonics,
+    precomputed_tables::{self, PrecomputedTablesShared},
+    storage::AddressBookEntry,
+    transaction_builder::TransactionBuilderState,
     wallet::{
         RecoverOption,
         Wallet
@@ -76,11 +83,15 @@ use {
         api::{
             AuthConfig,
             PermissionResult,
-            AppStateShared
+            AppStateShared,
+            WalletManagerRpcServer,
+            WalletManagerRpcServerShared
         },
         wallet::XSWDEvent,
+        wallet_manager::WalletManager,
     },
     xelis_common::{
+        api::wallet::XSWDApplicationScope,
         rpc_server::RpcRequest,
         prompt::ShareablePrompt,
         tokio::{
@@ -108,7 +119,12 @@ pub struct RPCConfig {
     rpc_password: Option<String>,
     /// Number of threads to use for the RPC Server
     #[clap(long)]
-    rpc_threads: Option<usize>
+    rpc_threads: Option<usize>,
+    /// Start the RPC Server in multi-wallet mode
+    /// Wallets are opened through the RPC Server and share the daemon connection
+    #[clap(long)]
+    #[serde(default)]
+    rpc_multi_wallet: bool
 }
 
 // Functions Helpers
@@ -135,6 +151,13 @@ pub struct NetworkConfig {
     #[clap(long, default_value_t = String::from(DEFAULT_DAEMON_ADDRESS))]
     #[serde(default = "default_daemon_address")]
     daemon_address: String,
+    /// Fallback daemon address, can be set several times
+    /// 
+    /// The wallet is moved to another daemon if the current one is offline or lagging behind.
+    #[cfg(feature = "network_handler")]
+    #[clap(long)]
+    #[serde(default)]
+    daemon_fallback_address: Vec<String>,
     /// Disable online mode
     #[cfg(feature = "network_handler")]
     #[clap(long)]
@@ -155,6 +178,29 @@ pub struct PrecomputedTablesConfig {
     /// By default, it will be from current directory.
     #[clap(long)]
     precomputed_tables_path: Option<String>,
+    /// Memory-map the precomputed tables file instead of loading it in RAM
+    /// 
+    /// Useful for low memory devices, decoding may be slower.
+    #[clap(long)]
+    #[serde(default)]
+    precomputed_tables_mmap: bool,
+    /// Select the L1 size based on the available memory
+    /// 
+    /// If the tables are not generated yet, the smallest ones are used
+    /// until the selected size is generated in background.
+    #[clap(long)]
+    #[serde(default)]
+    precomputed_tables_auto: bool,
+    /// Mirrors to download the precomputed tables from instead of generating them
+    /// 
+    /// Each mirror must serve the file as `<mirror>/precomputed_tables_<l1>.bin`.
+    /// Requires `precomputed_tables_checksum` to be set.
+    #[clap(long)]
+    #[serde(default)]
+    precomputed_tables_mirrors: Vec<String>,
+    /// Expected BLAKE3 checksum in hex of the precomputed tables to download
+    #[clap(long)]
+    precomputed_tables_checksum: Option<String>,
 }
 
 #[derive(Debug, clap::Args, Serialize, Deserialize)]
@@ -232,6 +278,11 @@ pub struct Config {
     /// Restore wallet using seed
     #[clap(long)]
     seed: Option<String>,
+    /// Sub-account to open, derived from the wallet seed
+    /// 0 is the master account
+    #[clap(long, default_value_t = 0)]
+    #[serde(default)]
+    account: u32,
     /// Network selected for chain
     #[clap(long, value_enum, default_value_t = Network::Mainnet)]
     #[serde(default)]
@@ -247,6 +298,17 @@ pub struct Config {
     #[clap(long)]
     #[serde(default)]
     disable_history_scan: bool,
+    /// Sync a wallet that never synced from the latest stable topoheight
+    /// Balances and nonce are retrieved from the daemon but the history below this topoheight is not scanned
+    /// Useful to restore quickly an old wallet, the full history can be retrieved later using the rescan command
+    #[clap(long)]
+    #[serde(default)]
+    checkpoint_sync: bool,
+    /// Lock the wallet after this inactivity in seconds, 0 to disable it
+    /// The keypair is wiped from memory and the password is required to unlock it
+    #[clap(long, default_value_t = 0)]
+    #[serde(default)]
+    auto_lock_timeout: u64,
     /// Force the wallet to use a stable balance only during transactions creation.
     /// This will prevent the wallet to use unstable balance and prevent any orphaned transaction due to DAG reorg.
     /// This is only working if the wallet is in online mode.
@@ -262,7 +324,12 @@ pub struct Config {
     #[clap(long)]
     #[serde(skip)]
     #[serde(default)]
-    generate_config_template: bool
+    generate_config_template: bool,
+    /// Address to serve the Prometheus metrics on (`127.0.0.1:9101`)
+    /// Metrics are not exposed if not set
+    #[cfg(feature = "metrics")]
+    #[clap(long)]
+    metrics_bind_address: Option<String>
 }
 
 /// This struct is used to log the progress of the table generation
@@ -275,6 +342,47 @@ impl ecdlp::ProgressTableGenerationReportFunction for LogProgressTableGeneration
     }
 }
 
+// Load the precomputed tables using the configured mode
+async fn load_precomputed_tables(config: &PrecomputedTablesConfig, mut l1: usize) -> Result<PrecomputedTablesShared> {
+    let path = config.precomputed_tables_path.as_deref();
+
+    // Try to download the tables before generating them
+    if !config.precomputed_tables_mirrors.is_empty() && !precomputed_tables::has_precomputed_tables(path, l1).await? {
+        if let Some(checksum) = config.precomputed_tables_checksum.as_deref() {
+            let full_path = precomputed_tables::get_precomputed_tables_path(path, l1);
+            if let Err(e) = precomputed_tables::download_precomputed_tables(&config.precomputed_tables_mirrors, &full_path, l1, checksum).await {
+                warn!("Error while downloading precomputed tables, they will be generated: {:#}", e);
+            }
+        } else {
+            warn!("Precomputed tables mirrors are set without a checksum, skipping download");
+        }
+    }
+
+    if config.precomputed_tables_auto {
+        if let Some(available) = precomputed_tables::get_available_memory() {
+            let max_l1 = precomputed_tables::select_l1_for_memory(available);
+            if max_l1 < l1 {
+                info!("Not enough memory available for precomputed tables L1 {}, using L1 {}", l1, max_l1);
+                l1 = max_l1;
+            }
+        }
+
+        // Use the smallest tables until the selected ones are generated
+        if l1 > precomputed_tables::L1_LOW && !precomputed_tables::has_precomputed_tables(path, l1).await? {
+            info!("Precomputed tables L1 {} not found, using L1 {} until they are generated", l1, precomputed_tables::L1_LOW);
+            let tables = precomputed_tables::read_or_generate_precomputed_tables(path, precomputed_tables::L1_LOW, LogProgressTableGenerationReportFunction, true).await?;
+            precomputed_tables::upgrade_precomputed_tables_in_background(tables.clone(), path.map(String::from), l1, LogProgressTableGenerationReportFunction, config.precomputed_tables_mmap);
+            return Ok(tables)
+        }
+    }
+
+    if config.precomputed_tables_mmap {
+        precomputed_tables::read_or_generate_precomputed_tables_mmap(path, l1, LogProgressTableGenerationReportFunction).await
+    } else {
+        precomputed_tables::read_or_generate_precomputed_tables(path, l1, LogProgressTableGenerationReportFunction, true).await
+    }
+}
+
 const ELEMENTS_PER_PAGE: usize = 10;
 
 #[tokio::main]
@@ -302,7 +410,13 @@ async fn main() -> Result<()> {
     }
 
     let log_config = &config.log;
-    let prompt = Prompt::new(log_config.log_level, &log_config.logs_path, &log_config.filename_log, log_config.disable_file_logging, log_config.disable_file_log_date_based, log_config.disable_log_color, !log_config.disable_interactive_mode, log_config.logs_modules.clone(), log_config.file_log_level.unwrap_or(log_config.log_level))?;
+    let prompt = Prompt::new(log_config.log_level, &log_config.logs_path, &log_config.filename_log, log_config.disable_file_logging, log_config.disable_file_log_date_based, log_config.disable_log_color, !log_config.disable_interactive_mode, log_config.logs_modules.clone(), log_config.file_log_level.unwrap_or(log_config.log_level), LogOptions::default())?;
+
+    #[cfg(feature = "metrics")]
+    if let Some(address) = config.metrics_bind_address.as_deref() {
+        xelis_common::metrics::install_prometheus_exporter(address)?;
+        info!("Prometheus metrics available on http://{}/metrics", address);
+    }
 
     #[cfg(feature = "api_server")]
     {
@@ -324,12 +438,26 @@ async fn main() -> Result<()> {
             error!("Invalid parameters configuration: usernamd AND password must be provided");
             return Ok(())
         }
+
+        // check that multi-wallet mode has a RPC Server and no wallet to open
+        if config.rpc.rpc_multi_wallet && (config.rpc.rpc_bind_address.is_none() || config.wallet_path.is_some()) {
+            error!("Invalid parameters configuration: multi-wallet mode requires a RPC bind address and no wallet path");
+            return Ok(())
+        }
     }
 
     let command_manager = CommandManager::new(prompt.clone());
     command_manager.store_in_context(config.network)?;
 
-    if let Some(path) = config.wallet_path.as_ref() {
+    if is_multi_wallet_mode(&config) {
+        command_manager.register_default_commands()?;
+
+        #[cfg(feature = "api_server")]
+        {
+            let server = start_wallet_manager_server(&config).await?;
+            command_manager.store_in_context(server)?;
+        }
+    } else if let Some(path) = config.wallet_path.as_ref() {
         // read password from option or ask him
         let password = if let Some(password) = config.password.as_ref() {
             password.clone()
@@ -337,11 +465,11 @@ async fn main() -> Result<()> {
             prompt.read_input(format!("Enter Password for '{}': ", path), true).await?
         };
 
-        let precomputed_tables = precomputed_tables::read_or_generate_precomputed_tables(config.precomputed_tables.precomputed_tables_path.as_deref(), config.precomputed_tables.precomputed_tables_l1, LogProgressTableGenerationReportFunction, true).await?;
+        let precomputed_tables = load_precomputed_tables(&config.precomputed_tables, config.precomputed_tables.precomputed_tables_l1).await?;
         let p = Path::new(path);
         let wallet = if p.exists() && p.is_dir() && Path::new(&format!("{}/db", path)).exists() {
             info!("Opening wallet {}", path);
-            Wallet::open(path, &password, config.network, precomputed_tables)?
+            Wallet::open_sub_account(path, &password, config.account, config.network, precomputed_tables)?
         } else {
             info!("Creating a new wallet at {}", path);
             Wallet::create(path, &password, config.seed.as_deref().map(RecoverOption::Seed), config.network, precomputed_tables)?
@@ -363,16 +491,61 @@ async fn main() -> Result<()> {
         if let Ok(wallet) = context.get::<Arc<Wallet>>() {
             wallet.close().await;
         }
+
+        #[cfg(feature = "api_server")]
+        if let Ok(server) = context.get::<WalletManagerRpcServerShared>() {
+            server.stop().await;
+        }
     }
 
     Ok(())
 }
 
+#[cfg(feature = "api_server")]
+fn is_multi_wallet_mode(config: &Config) -> bool {
+    config.rpc.rpc_multi_wallet
+}
+
+#[cfg(not(feature = "api_server"))]
+fn is_multi_wallet_mode(_: &Config) -> bool {
+    false
+}
+
+// Start the RPC Server managing several wallets at once
+#[cfg(feature = "api_server")]
+async fn start_wallet_manager_server(config: &Config) -> Result<WalletManagerRpcServerShared> {
+    let bind_address = config.rpc.rpc_bind_address.clone().context("RPC bind address is required in multi-wallet mode")?;
+    let precomputed_tables = load_precomputed_tables(&config.precomputed_tables, config.precomputed_tables.precomputed_tables_l1).await?;
+
+    let daemon_address = if config.network_handler.offline_mode {
+        None
+    } else {
+        Some(config.network_handler.daemon_address.as_str())
+    };
+    let manager = WalletManager::new(DIR_PATH.to_owned(), config.network, precomputed_tables, daemon_address).await
+        .context("Error while creating the wallet manager")?;
+
+    let auth_config = if let (Some(username), Some(password)) = (config.rpc.rpc_username.clone(), config.rpc.rpc_password.clone()) {
+        Some(AuthConfig {
+            username,
+            password
+        })
+    } else {
+        None
+    };
+
+    info!("Enabling Wallet Manager RPC Server on {} {}", bind_address, if auth_config.is_some() { "with authentication" } else { "without authentication" });
+    WalletManagerRpcServer::new(bind_address, manager, auth_config, config.rpc.rpc_threads).await
+}
+
 async fn register_default_commands(manager: &CommandManager) -> Result<(), CommandError> {
     manager.add_command(Command::new("open", "Open a wallet", CommandHandler::Async(async_handler!(open_wallet))))?;
     manager.add_command(Command::new("create", "Create a new wallet", CommandHandler::Async(async_handler!(create_wallet))))?;
     manager.add_command(Command::new("recover_seed", "Recover a wallet using a seed", CommandHandler::Async(async_handler!(recover_seed))))?;
     manager.add_command(Command::new("recover_private_key", "Recover a wallet using a private key", CommandHandler::Async(async_handler!(recover_private_key))))?;
+    manager.add_command(Command::new("recover_view_key", "Open a watch-only wallet using a view key", CommandHandler::Async(async_handler!(recover_view_key))))?;
+    manager.add_command(Command::new("recover_bip39", "Recover a wallet using a BIP39 mnemonic", CommandHandler::Async(async_handler!(recover_bip39))))?;
+    manager.add_command(Command::new("compact", "Compact the storage of a wallet", CommandHandler::Async(async_handler!(compact_wallet))))?;
 
     manager.register_default_commands()?;
     // Display available commands
@@ -406,6 +579,12 @@ async fn xswd_handler(mut receiver: UnboundedReceiver<XSWDEvent>, prompt: Sharea
                 if callback.send(res).is_err() {
                     error!("Error while sending permission response back to XSWD");
                 }
+            },
+            XSWDEvent::RequestScope(app_state, scope, callback) => {
+                let res = xswd_handle_request_scope(&prompt, app_state, scope).await;
+                if callback.send(res).is_err() {
+                    error!("Error while sending scope response back to XSWD");
+                }
             }
         };
     }
@@ -425,6 +604,40 @@ async fn xswd_handle_request_application(prompt: &ShareablePrompt, app_state: Ap
     }
 }
 
+#[cfg(feature = "api_server")]
+async fn xswd_handle_request_scope(prompt: &ShareablePrompt, app_state: AppStateShared, scope: XSWDApplicationScope) -> Result<PermissionResult, Error> {
+    let methods = if scope.methods.is_empty() {
+        "none".to_owned()
+    } else {
+        scope.methods.iter().cloned().collect::<Vec<_>>().join(", ")
+    };
+
+    let assets = if scope.assets.is_empty() {
+        "all".to_owned()
+    } else {
+        scope.assets.iter().map(|asset| asset.to_string()).collect::<Vec<_>>().join(", ")
+    };
+
+    let mut message = format!(
+        "XSWD: Application {} ({}) requests permanent permissions\r\nMethods allowed without confirmation: {}\r\nAssets accessible: {}\r\n",
+        app_state.get_name(),
+        app_state.get_id(),
+        methods,
+        assets
+    );
+    for (asset, limit) in &scope.daily_limits {
+        message.push_str(&format!("Daily limit for {}: {} (atomic units)\r\n", asset, limit));
+    }
+    message.push_str("Do you want to grant these permissions ? (Y/N): ");
+
+    let accepted = prompt.read_valid_str_value(prompt.colorize_string(Color::Blue, &message), vec!["y", "n"]).await? == "y";
+    if accepted {
+        Ok(PermissionResult::Allow)
+    } else {
+        Ok(PermissionResult::Deny)
+    }
+}
+
 #[cfg(feature = "api_server")]
 async fn xswd_handle_request_permission(prompt: &ShareablePrompt, app_state: AppStateShared, request: RpcRequest) -> Result<PermissionResult, Error> {
     let params = if let Some(params) = request.params {
@@ -452,10 +665,23 @@ async fn xswd_handle_request_permission(prompt: &ShareablePrompt, app_state: App
 
 // Apply the config passed in params
 async fn apply_config(config: Config, wallet: &Arc<Wallet>, #[cfg(feature = "api_server")] prompt: &ShareablePrompt) {
+    // Must be set before going online
+    wallet.set_checkpoint_sync(config.checkpoint_sync);
+
     #[cfg(feature = "network_handler")]
     if !config.network_handler.offline_mode {
-        info!("Trying to connect to daemon at '{}'", config.network_handler.daemon_address);
-        if let Err(e) = wallet.set_online_mode(&config.network_handler.daemon_address, true).await {
+        let network = config.network_handler;
+        let res = if network.daemon_fallback_address.is_empty() {
+            info!("Trying to connect to daemon at '{}'", network.daemon_address);
+            wallet.set_online_mode(&network.daemon_address, true).await
+        } else {
+            info!("Trying to connect to daemon at '{}' with {} fallback daemons", network.daemon_address, network.daemon_fallback_address.len());
+            let mut addresses = vec![network.daemon_address];
+            addresses.extend(network.daemon_fallback_address);
+            wallet.set_online_mode_with_failover(addresses).await
+        };
+
+        if let Err(e) = res {
             error!("Couldn't connect to daemon: {:#}", e);
             info!("You can activate online mode using 'online_mode [daemon_address]'");
         } else {
@@ -465,6 +691,7 @@ async fn apply_config(config: Config, wallet: &Arc<Wallet>, #[cfg(feature = "api
 
     wallet.set_history_scan(!config.disable_history_scan);
     wallet.set_stable_balance(config.force_stable_balance);
+    wallet.set_auto_lock_timeout(config.auto_lock_timeout).await;
 
     #[cfg(feature = "api_server")]
     {
@@ -506,7 +733,14 @@ async fn setup_wallet_command_manager(wallet: Arc<Wallet>, command_manager: &Com
     command_manager.remove_command("open")?;
     command_manager.remove_command("recover_seed")?;
     command_manager.remove_command("recover_private_key")?;
+    command_manager.remove_command("recover_view_key")?;
+    command_manager.remove_command("recover_bip39")?;
     command_manager.remove_command("create")?;
+    command_manager.remove_command("compact")?;
+
+    if wallet.is_watch_only() {
+        command_manager.warn("Wallet is opened in watch-only mode: transactions and signatures are disabled");
+    }
 
     // Add wallet commands
     command_manager.add_command(Command::new(
@@ -545,9 +779,10 @@ async fn setup_wallet_command_manager(wallet: Arc<Wallet>, command_manager: &Com
         ],    
         CommandHandler::Async(async_handler!(burn))
     ))?;
-    command_manager.add_command(Command::new(
+    command_manager.add_command(Command::with_optional_arguments(
         "display_address",
-        "Show your wallet address",
+        "Show your wallet address or its integrated address for a payment ID",
+        vec![Arg::new("payment_id", ArgType::Number)],
         CommandHandler::Async(async_handler!(display_address))
     ))?;
     command_manager.add_command(Command::with_optional_arguments(
@@ -559,15 +794,63 @@ async fn setup_wallet_command_manager(wallet: Arc<Wallet>, command_manager: &Com
     command_manager.add_command(Command::with_optional_arguments(
         "history",
         "Show all your transactions",
-        vec![Arg::new("page", ArgType::Number)],
+        vec![
+            Arg::new("page", ArgType::Number),
+            Arg::new("tag", ArgType::String)
+        ],
         CommandHandler::Async(async_handler!(history))
     ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "set_tx_note",
+        "Set a local note on a transaction, leave it empty to remove it",
+        vec![Arg::new("tx_hash", ArgType::Hash)],
+        CommandHandler::Async(async_handler!(set_tx_note))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "tag_tx",
+        "Add a local tag on a transaction",
+        vec![
+            Arg::new("tx_hash", ArgType::Hash),
+            Arg::new("tag", ArgType::String)
+        ],
+        CommandHandler::Async(async_handler!(tag_tx))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "untag_tx",
+        "Remove a local tag from a transaction",
+        vec![
+            Arg::new("tx_hash", ArgType::Hash),
+            Arg::new("tag", ArgType::String)
+        ],
+        CommandHandler::Async(async_handler!(untag_tx))
+    ))?;
     command_manager.add_command(Command::with_optional_arguments(
         "seed",
         "Show seed of selected language",
         vec![Arg::new("language", ArgType::Number)],
         CommandHandler::Async(async_handler!(seed))
     ))?;
+    command_manager.add_command(Command::with_optional_arguments(
+        "bip39_seed",
+        "Show the seed as a BIP39 mnemonic of selected language",
+        vec![Arg::new("language", ArgType::Number)],
+        CommandHandler::Async(async_handler!(bip39_seed))
+    ))?;
+    command_manager.add_command(Command::new(
+        "lock",
+        "Wipe the keypair from memory until the wallet is unlocked",
+        CommandHandler::Async(async_handler!(lock))
+    ))?;
+    command_manager.add_command(Command::new(
+        "unlock",
+        "Unlock the wallet using its password",
+        CommandHandler::Async(async_handler!(unlock))
+    ))?;
+    command_manager.add_command(Command::new(
+        "export_view_key",
+        "Show the view key to open this wallet in watch-only mode",
+        CommandHandler::Async(async_handler!(export_view_key))
+    ))?;
     command_manager.add_command(Command::new(
         "nonce",
         "Show current nonce",
@@ -594,18 +877,90 @@ async fn setup_wallet_command_manager(wallet: Arc<Wallet>, command_manager: &Com
         vec![Arg::new("filename", ArgType::String)],
         CommandHandler::Async(async_handler!(export_transactions_csv))
     ))?;
+    command_manager.add_command(Command::with_arguments(
+        "export_history",
+        "Export all your history entries in a CSV or JSON file",
+        vec![Arg::new("filename", ArgType::String)],
+        vec![Arg::new("format", ArgType::String)],
+        CommandHandler::Async(async_handler!(export_history))
+    ))?;
+    command_manager.add_command(Command::with_optional_arguments(
+        "payment_request",
+        "Create a payment URI and its QR code to receive funds",
+        vec![
+            Arg::new("amount", ArgType::String),
+            Arg::new("asset", ArgType::Hash)
+        ],
+        CommandHandler::Async(async_handler!(payment_request))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "pay_uri",
+        "Pay a payment request from its URI",
+        vec![Arg::new("uri", ArgType::String)],
+        CommandHandler::Async(async_handler!(pay_uri))
+    ))?;
+    command_manager.add_command(Command::with_arguments(
+        "prepare_transfer",
+        "Prepare a transfer in a file to be signed by an offline wallet",
+        vec![
+            Arg::new("address", ArgType::String),
+            Arg::new("amount", ArgType::String),
+            Arg::new("filename", ArgType::String)
+        ],
+        vec![Arg::new("asset", ArgType::Hash)],
+        CommandHandler::Async(async_handler!(prepare_transfer))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "sign_prepared_transaction",
+        "Sign a prepared transaction file and write it in hex format to another file",
+        vec![
+            Arg::new("filename", ArgType::String),
+            Arg::new("output", ArgType::String)
+        ],
+        CommandHandler::Async(async_handler!(sign_prepared_transaction))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "broadcast_transaction",
+        "Broadcast a signed transaction from a file in hex format",
+        vec![Arg::new("filename", ArgType::String)],
+        CommandHandler::Async(async_handler!(broadcast_transaction))
+    ))?;
     command_manager.add_command(Command::with_required_arguments(
         "set_asset_name",
         "Set the name of an asset",
         vec![Arg::new("asset", ArgType::Hash)],
         CommandHandler::Async(async_handler!(set_asset_name))
     ))?;
+    command_manager.add_command(Command::with_arguments(
+        "set_asset_ticker",
+        "Set the local ticker of an asset, leave it empty to reset it",
+        vec![Arg::new("asset", ArgType::Hash)],
+        vec![Arg::new("ticker", ArgType::String)],
+        CommandHandler::Async(async_handler!(set_asset_ticker))
+    ))?;
     command_manager.add_command(Command::with_optional_arguments(
         "list_assets",
         "List all registered assets",
         vec![Arg::new("page", ArgType::Number)],
         CommandHandler::Async(async_handler!(list_assets))
     ))?;
+    command_manager.add_command(Command::new(
+        "asset_filter",
+        "Show which assets are tracked by the wallet",
+        CommandHandler::Async(async_handler!(asset_filter))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "track_asset",
+        "Track again an asset during the sync",
+        vec![Arg::new("asset", ArgType::Hash)],
+        CommandHandler::Async(async_handler!(track_asset))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "ignore_asset",
+        "Stop tracking an asset and hide it from balances and history",
+        vec![Arg::new("asset", ArgType::Hash)],
+        CommandHandler::Async(async_handler!(ignore_asset))
+    ))?;
 
     #[cfg(feature = "network_handler")]
     {
@@ -620,12 +975,89 @@ async fn setup_wallet_command_manager(wallet: Arc<Wallet>, command_manager: &Com
             "Set your wallet in offline mode",
             CommandHandler::Async(async_handler!(offline_mode))
         ))?;
+        command_manager.add_command(Command::new(
+            "daemons",
+            "Show the status of the daemons used by the failover",
+            CommandHandler::Async(async_handler!(daemons))
+        ))?;
         command_manager.add_command(Command::with_optional_arguments(
             "rescan",
             "Rescan balance and transactions",
             vec![Arg::new("topoheight", ArgType::Number)],
             CommandHandler::Async(async_handler!(rescan))
         ))?;
+        command_manager.add_command(Command::new(
+            "rescan_status",
+            "Show the progress of the rescan in progress",
+            CommandHandler::Async(async_handler!(rescan_status))
+        ))?;
+        command_manager.add_command(Command::new(
+            "pause_rescan",
+            "Pause the rescan in progress",
+            CommandHandler::Async(async_handler!(pause_rescan))
+        ))?;
+        command_manager.add_command(Command::new(
+            "resume_rescan",
+            "Resume the rescan from its last checkpoint",
+            CommandHandler::Async(async_handler!(resume_rescan))
+        ))?;
+        command_manager.add_command(Command::with_arguments(
+            "bump_fee",
+            "Replace a pending transaction by the same one with a higher fee",
+            vec![Arg::new("tx_hash", ArgType::Hash)],
+            vec![Arg::new("fee", ArgType::String)],
+            CommandHandler::Async(async_handler!(bump_fee))
+        ))?;
+        command_manager.add_command(Command::with_arguments(
+            "payment_proof",
+            "Create a proof of the amount sent in a transfer of a transaction",
+            vec![Arg::new("tx_hash", ArgType::Hash)],
+            vec![Arg::new("index", ArgType::Number)],
+            CommandHandler::Async(async_handler!(payment_proof))
+        ))?;
+        command_manager.add_command(Command::with_required_arguments(
+            "verify_payment_proof",
+            "Verify a payment proof against the network",
+            vec![Arg::new("proof", ArgType::String)],
+            CommandHandler::Async(async_handler!(verify_payment_proof))
+        ))?;
+        command_manager.add_command(Command::with_required_arguments(
+            "reserve_proof",
+            "Create a proof that the wallet controls at least an amount of an asset",
+            vec![
+                Arg::new("asset", ArgType::Hash),
+                Arg::new("amount", ArgType::String),
+                Arg::new("challenge", ArgType::String)
+            ],
+            CommandHandler::Async(async_handler!(reserve_proof))
+        ))?;
+        command_manager.add_command(Command::with_required_arguments(
+            "verify_reserve_proof",
+            "Verify a reserve proof of an account against the network",
+            vec![
+                Arg::new("address", ArgType::String),
+                Arg::new("proof", ArgType::String)
+            ],
+            CommandHandler::Async(async_handler!(verify_reserve_proof))
+        ))?;
+        command_manager.add_command(Command::with_required_arguments(
+            "audit_proof",
+            "Create a proof of the balance of an asset decryptable by an auditor",
+            vec![
+                Arg::new("auditor", ArgType::String),
+                Arg::new("asset", ArgType::Hash)
+            ],
+            CommandHandler::Async(async_handler!(audit_proof))
+        ))?;
+        command_manager.add_command(Command::with_required_arguments(
+            "verify_audit_proof",
+            "Verify an audit proof made for this wallet and decrypt its amount",
+            vec![
+                Arg::new("address", ArgType::String),
+                Arg::new("proof", ArgType::String)
+            ],
+            CommandHandler::Async(async_handler!(verify_audit_proof))
+        ))?;
     }
 
     #[cfg(feature = "api_server")]
@@ -678,6 +1110,119 @@ async fn setup_wallet_command_manager(wallet: Arc<Wallet>, command_manager: &Com
         "Show the current state of multisig",
         CommandHandler::Async(async_handler!(multisig_show))
     ))?;
+    command_manager.add_command(Command::with_arguments(
+        "multisig_transfer",
+        "Create a multisig transfer waiting for the participants signatures",
+        vec![
+            Arg::new("address", ArgType::String),
+            Arg::new("amount", ArgType::String)
+        ],
+        vec![Arg::new("asset", ArgType::Hash)],
+        CommandHandler::Async(async_handler!(multisig_transfer))
+    ))?;
+    command_manager.add_command(Command::new(
+        "multisig_pending",
+        "Show all multisig transactions waiting for signatures",
+        CommandHandler::Async(async_handler!(multisig_pending))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "multisig_export",
+        "Export a pending multisig transaction in hex format to a file",
+        vec![
+            Arg::new("tx_hash", ArgType::Hash),
+            Arg::new("filename", ArgType::String)
+        ],
+        CommandHandler::Async(async_handler!(multisig_export))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "multisig_add_signature",
+        "Add the signature of a participant to a pending multisig transaction",
+        vec![
+            Arg::new("tx_hash", ArgType::Hash),
+            Arg::new("signer_id", ArgType::Number),
+            Arg::new("signature", ArgType::String)
+        ],
+        CommandHandler::Async(async_handler!(multisig_add_signature))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "multisig_finalize",
+        "Finalize and broadcast a pending multisig transaction",
+        vec![Arg::new("tx_hash", ArgType::Hash)],
+        CommandHandler::Async(async_handler!(multisig_finalize))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "multisig_cancel",
+        "Delete a pending multisig transaction",
+        vec![Arg::new("tx_hash", ArgType::Hash)],
+        CommandHandler::Async(async_handler!(multisig_cancel))
+    ))?;
+
+    command_manager.add_command(Command::new(
+        "address_book",
+        "Show all contacts saved in the address book",
+        CommandHandler::Async(async_handler!(address_book))
+    ))?;
+    command_manager.add_command(Command::with_arguments(
+        "address_book_add",
+        "Save a contact in the address book, its name can be used instead of the address in transfer commands",
+        vec![
+            Arg::new("name", ArgType::String),
+            Arg::new("address", ArgType::String)
+        ],
+        vec![Arg::new("asset", ArgType::Hash)],
+        CommandHandler::Async(async_handler!(address_book_add))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "address_book_remove",
+        "Delete a contact from the address book",
+        vec![Arg::new("name", ArgType::String)],
+        CommandHandler::Async(async_handler!(address_book_remove))
+    ))?;
+
+    command_manager.add_command(Command::new(
+        "scheduled_payments",
+        "Show all the recurring payments",
+        CommandHandler::Async(async_handler!(scheduled_payments))
+    ))?;
+    command_manager.add_command(Command::with_arguments(
+        "schedule_payment",
+        "Send automatically a payment every interval (in seconds) while the wallet is online",
+        vec![
+            Arg::new("name", ArgType::String),
+            Arg::new("address", ArgType::String),
+            Arg::new("amount", ArgType::String),
+            Arg::new("interval", ArgType::Number)
+        ],
+        vec![
+            Arg::new("asset", ArgType::Hash),
+            Arg::new("count", ArgType::Number)
+        ],
+        CommandHandler::Async(async_handler!(schedule_payment))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "unschedule_payment",
+        "Delete a recurring payment",
+        vec![Arg::new("name", ArgType::String)],
+        CommandHandler::Async(async_handler!(unschedule_payment))
+    ))?;
+
+    command_manager.add_command(Command::new(
+        "sub_accounts",
+        "Show the sub-accounts derived from the wallet seed",
+        CommandHandler::Async(async_handler!(sub_accounts))
+    ))?;
+
+    command_manager.add_command(Command::new(
+        "xswd_applications",
+        "Show the XSWD applications with granted permissions",
+        CommandHandler::Async(async_handler!(xswd_applications))
+    ))?;
+    command_manager.add_command(Command::with_required_arguments(
+        "xswd_revoke",
+        "Revoke the permissions granted to a XSWD application",
+        vec![Arg::new("id", ArgType::String)],
+        CommandHandler::Async(async_handler!(xswd_revoke))
+    ))?;
 
     command_manager.add_command(Command::new(
         "tx_version",
@@ -783,8 +1328,8 @@ async fn open_wallet(manager: &CommandManager, _: ArgumentManager) -> Result<(),
     let wallet = {
         let context = manager.get_context().lock()?;
         let network = context.get::<Network>()?;
-        let precomputed_tables = precomputed_tables::read_or_generate_precomputed_tables(config.precomputed_tables.precomputed_tables_path.as_deref(), config.precomputed_tables.precomputed_tables_l1, LogProgressTableGenerationReportFunction, true).await?;
-        Wallet::open(&dir, &password, *network, precomputed_tables)?
+        let precomputed_tables = load_precomputed_tables(&config.precomputed_tables, config.precomputed_tables.precomputed_tables_l1).await?;
+        Wallet::open_sub_account(&dir, &password, config.account, *network, precomputed_tables)?
     };
 
     manager.message("Wallet sucessfully opened");
@@ -795,6 +1340,50 @@ async fn open_wallet(manager: &CommandManager, _: ArgumentManager) -> Result<(),
     Ok(())
 }
 
+// Compact the storage of a wallet by requesting name, password
+// The wallet must be closed, previous files are kept as a backup
+async fn compact_wallet(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let prompt = manager.get_prompt();
+    let config: Config = Config::parse();
+    let dir = if let Some(path) = config.wallet_path.as_ref() {
+        path.clone()
+    } else {
+        let name = prompt.read_input("Wallet name: ", false)
+            .await.context("Error while reading wallet name")?;
+
+        if name.is_empty() {
+            manager.error("Wallet name cannot be empty");
+            return Ok(())
+        }
+        format!("{}{}", DIR_PATH, name)
+    };
+
+    if !Path::new(&dir).is_dir() {
+        manager.message("No wallet found with this name");
+        return Ok(())
+    }
+
+    let password = prompt.read_input("Password: ", true)
+        .await.context("Error while reading wallet password")?;
+
+    let network = {
+        let context = manager.get_context().lock()?;
+        *context.get::<Network>()?
+    };
+
+    manager.message("Compacting wallet storage, this may take a while...");
+    let compaction = Wallet::compact(&dir, &password, network)?;
+    manager.message(format!(
+        "Wallet storage compacted: {} topoheight changes pruned, {} bytes => {} bytes",
+        compaction.pruned_changes,
+        compaction.size_before,
+        compaction.size_after
+    ));
+    manager.message(format!("Previous files have been kept in {}.backup", dir));
+
+    Ok(())
+}
+
 // Create a wallet by requesting name, password
 async fn create_wallet(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
     let prompt = manager.get_prompt();
@@ -831,7 +1420,7 @@ async fn create_wallet(manager: &CommandManager, _: ArgumentManager) -> Result<(
     let wallet = {
         let context = manager.get_context().lock()?;
         let network = context.get::<Network>()?;
-        let precomputed_tables = precomputed_tables::read_or_generate_precomputed_tables(config.precomputed_tables.precomputed_tables_path.as_deref(), precomputed_tables::L1_FULL, LogProgressTableGenerationReportFunction, true).await?;
+        let precomputed_tables = load_precomputed_tables(&config.precomputed_tables, precomputed_tables::L1_FULL).await?;
         Wallet::create(&dir, &password, None, *network, precomputed_tables)?
     };
  
@@ -841,7 +1430,7 @@ async fn create_wallet(manager: &CommandManager, _: ArgumentManager) -> Result<(
     // Display the seed in prompt
     {
         let seed = wallet.get_seed(0)?; // TODO language index
-        prompt.read_input(format!("Seed: {}\r\nPress ENTER to continue", seed), false)
+        prompt.read_input(format!("Seed: {}\r\nPress ENTER to continue", seed.as_str()), false)
             .await.context("Error while displaying seed")?;
     }
 
@@ -850,8 +1439,17 @@ async fn create_wallet(manager: &CommandManager, _: ArgumentManager) -> Result<(
     Ok(())
 }
 
-// Recover a wallet by requesting its seed or private key, name and password
-async fn recover_wallet(manager: &CommandManager, _: ArgumentManager, seed: bool) -> Result<(), CommandError> {
+// Secret used to recover a wallet
+enum RecoverType {
+    Seed,
+    PrivateKey,
+    // Watch-only wallet
+    ViewKey,
+    Bip39
+}
+
+// Recover a wallet by requesting its seed, private key or view key, name and password
+async fn recover_wallet(manager: &CommandManager, _: ArgumentManager, recover_type: RecoverType) -> Result<(), CommandError> {
     let prompt = manager.get_prompt();
     let config: Config = Config::parse();
     let dir = if let Some(path) = config.wallet_path.as_ref() {
@@ -872,25 +1470,58 @@ async fn recover_wallet(manager: &CommandManager, _: ArgumentManager, seed: bool
         return Ok(())
     }
 
-    let content = if seed {
-        let seed = prompt.read_input("Seed: ", false)
-            .await.context("Error while reading seed")?;
-    
-        let words_count = seed.split_whitespace().count();
-        if words_count != 25 && words_count != 24 {
-            manager.error("Seed must be 24 or 25 (checksum) words long");
-            return Ok(())
-        }
-        seed
-    } else {
-        let private_key = prompt.read_input("Private Key: ", false)
-            .await.context("Error while reading private key")?;
-    
-        if private_key.len() != 64 {
-            manager.error("Private key must be 64 characters long");
-            return Ok(())
+    // BIP39 passphrase, only for mnemonics generated by other wallets
+    let mut passphrase = None;
+    let content = match recover_type {
+        RecoverType::Seed => {
+            let seed = prompt.read_input("Seed: ", false)
+                .await.context("Error while reading seed")?;
+
+            let words_count = seed.split_whitespace().count();
+            if words_count != 25 && words_count != 24 {
+                manager.error("Seed must be 24 or 25 (checksum) words long");
+                return Ok(())
+            }
+            seed
+        },
+        RecoverType::PrivateKey => {
+            let private_key = prompt.read_input("Private Key: ", false)
+                .await.context("Error while reading private key")?;
+
+            if private_key.len() != 64 {
+                manager.error("Private key must be 64 characters long");
+                return Ok(())
+            }
+            private_key
+        },
+        RecoverType::ViewKey => {
+            let view_key = prompt.read_input("View Key: ", false)
+                .await.context("Error while reading view key")?;
+
+            if view_key.len() != 64 {
+                manager.error("View key must be 64 characters long");
+                return Ok(())
+            }
+            view_key
+        },
+        RecoverType::Bip39 => {
+            let mnemonic = prompt.read_input("BIP39 mnemonic: ", false)
+                .await.context("Error while reading mnemonic")?;
+
+            let words_count = mnemonic.split_whitespace().count();
+            if ![12, 15, 18, 21, 24].contains(&words_count) {
+                manager.error("Mnemonic must be 12, 15, 18, 21 or 24 words long");
+                return Ok(())
+            }
+
+            let message = "Was this mnemonic exported from a XELIS wallet? (Y/N): ";
+            let exported = prompt.read_valid_str_value(prompt.colorize_string(Color::Blue, message), vec!["y", "n"]).await? == "y";
+            if !exported {
+                passphrase = Some(prompt.read_input("Passphrase (empty if none): ", true)
+                    .await.context("Error while reading passphrase")?);
+            }
+            mnemonic
         }
-        private_key
     };
 
     // ask and verify password
@@ -907,12 +1538,16 @@ async fn recover_wallet(manager: &CommandManager, _: ArgumentManager, seed: bool
     let wallet = {
         let context = manager.get_context().lock()?;
         let network = context.get::<Network>()?;
-        let precomputed_tables = precomputed_tables::read_or_generate_precomputed_tables(config.precomputed_tables.precomputed_tables_path.as_deref(), config.precomputed_tables.precomputed_tables_l1, LogProgressTableGenerationReportFunction, true).await?;
-
-        let recover = if seed {
-            RecoverOption::Seed(&content)
-        } else {
-            RecoverOption::PrivateKey(&content)
+        let precomputed_tables = load_precomputed_tables(&config.precomputed_tables, config.precomputed_tables.precomputed_tables_l1).await?;
+
+        let recover = match recover_type {
+            RecoverType::Seed => RecoverOption::Seed(&content),
+            RecoverType::PrivateKey => RecoverOption::PrivateKey(&content),
+            RecoverType::ViewKey => RecoverOption::ViewKey(&content),
+            RecoverType::Bip39 => match passphrase.as_deref() {
+                Some(passphrase) => RecoverOption::Bip39WithPassphrase(&content, passphrase),
+                None => RecoverOption::Bip39(&content)
+            }
         };
         Wallet::create(&dir, &password, Some(recover), *network, precomputed_tables)?
     };
@@ -926,11 +1561,19 @@ async fn recover_wallet(manager: &CommandManager, _: ArgumentManager, seed: bool
 }
 
 async fn recover_seed(manager: &CommandManager, args: ArgumentManager) -> Result<(), CommandError> {
-    recover_wallet(manager, args, true).await
+    recover_wallet(manager, args, RecoverType::Seed).await
 }
 
 async fn recover_private_key(manager: &CommandManager, args: ArgumentManager) -> Result<(), CommandError> {
-    recover_wallet(manager, args, false).await
+    recover_wallet(manager, args, RecoverType::PrivateKey).await
+}
+
+async fn recover_view_key(manager: &CommandManager, args: ArgumentManager) -> Result<(), CommandError> {
+    recover_wallet(manager, args, RecoverType::ViewKey).await
+}
+
+async fn recover_bip39(manager: &CommandManager, args: ArgumentManager) -> Result<(), CommandError> {
+    recover_wallet(manager, args, RecoverType::Bip39).await
 }
 
 // Set the asset name
@@ -949,6 +1592,23 @@ async fn set_asset_name(manager: &CommandManager, mut args: ArgumentManager) ->
     Ok(())
 }
 
+// Set the local ticker used to display the amounts of an asset
+async fn set_asset_ticker(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let asset = args.get_value("asset")?.to_hash()?;
+    let ticker = if args.has_argument("ticker") {
+        Some(args.get_value("ticker")?.to_string_value()?)
+    } else {
+        None
+    };
+
+    wallet.set_asset_ticker(&asset, ticker).await.context("Error while setting asset ticker")?;
+    manager.message(format!("Asset ticker is now {}", wallet.get_asset_ticker(&asset).await.context("Error while retrieving asset ticker")?));
+    Ok(())
+}
+
 async fn list_assets(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
     let context = manager.get_context().lock()?;
     let wallet: &Arc<Wallet> = context.get()?;
@@ -984,6 +1644,70 @@ async fn list_assets(manager: &CommandManager, mut args: ArgumentManager) -> Res
     Ok(())
 }
 
+// Show which assets are tracked by the wallet
+async fn asset_filter(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    match wallet.get_asset_filter().await {
+        AssetFilter::All => manager.message("All assets are tracked"),
+        AssetFilter::Allow(assets) => {
+            manager.message(format!("Only {} asset(s) are tracked besides XELIS:", assets.len()));
+            for asset in assets {
+                manager.message(format!("- {}", asset));
+            }
+        },
+        AssetFilter::Deny(assets) => {
+            manager.message(format!("{} asset(s) are ignored:", assets.len()));
+            for asset in assets {
+                manager.message(format!("- {}", asset));
+            }
+        }
+    };
+
+    Ok(())
+}
+
+// Track again an asset during the sync
+async fn track_asset(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let asset = args.get_value("asset")?.to_hash()?;
+    let mut filter = wallet.get_asset_filter().await;
+    if filter.is_tracked(&asset) {
+        manager.message("Asset is already tracked");
+        return Ok(())
+    }
+
+    filter.track(asset.clone());
+    wallet.set_asset_filter(filter).await.context("Error while saving asset filter")?;
+    manager.message(format!("Asset {} is now tracked, rescan to recover its balance and history", asset));
+    Ok(())
+}
+
+// Stop tracking an asset and hide it from balances and history
+async fn ignore_asset(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let asset = args.get_value("asset")?.to_hash()?;
+    if asset == XELIS_ASSET {
+        return Err(CommandError::InvalidArgument("XELIS asset is always tracked".to_string()))
+    }
+
+    let mut filter = wallet.get_asset_filter().await;
+    if !filter.is_tracked(&asset) {
+        manager.message("Asset is already ignored");
+        return Ok(())
+    }
+
+    filter.ignore(asset.clone());
+    wallet.set_asset_filter(filter).await.context("Error while saving asset filter")?;
+    manager.message(format!("Asset {} is now ignored", asset));
+    Ok(())
+}
+
 // Change wallet password
 async fn change_password(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
     let context = manager.get_context().lock()?;
@@ -1063,7 +1787,7 @@ async fn create_transaction_with_multisig(manager: &CommandManager, prompt: &Pro
 
     unsigned.set_multisig(multisig);
 
-    let tx = unsigned.finalize(wallet.get_keypair());
+    let tx = unsigned.finalize(&wallet.get_keypair().context("Error while retrieving keypair")?);
     state.set_tx_hash_built(tx.hash());
 
     state.apply_changes(&mut storage).await.context("Error while applying changes")?;
@@ -1082,39 +1806,47 @@ async fn transfer(manager: &CommandManager, mut args: ArgumentManager) -> Result
         args.get_value("address")?.to_string_value()?
     } else {
         prompt.read_input(
-            prompt.colorize_str(Color::Green, "Address: "),
+            prompt.colorize_str(Color::Green, "Address or contact name: "),
             false
         ).await.context("Error while reading address")?
     };
-    let address = Address::from_string(&str_address).context("Invalid address")?;
+    let (address, default_asset) = wallet.resolve_address(&str_address).await.context("Invalid address")?;
 
     let asset = if args.has_argument("asset") {
         args.get_value("asset")?.to_hash()?
+    } else if let Some(default_asset) = default_asset {
+        prompt.read_hash(
+            prompt.colorize_string(Color::Green, &format!("Asset (default {}): ", default_asset))
+        ).await.unwrap_or(default_asset)
     } else {
         prompt.read_hash(
             prompt.colorize_str(Color::Green, "Asset (default XELIS): ")
         ).await.unwrap_or(XELIS_ASSET)
     };
 
-    let (max_balance, decimals, multisig) = {
+    let (max_balance, multisig) = {
         let storage = wallet.get_storage().read().await;
         let balance = storage.get_plaintext_balance_for(&asset).await.unwrap_or(0);
-        let decimals = storage.get_asset(&asset).await?.get_decimals();
         let multisig = storage.get_multisig_state().await.context("Error while reading multisig state")?;
-        (balance, decimals, multisig)
+        (balance, multisig)
     };
 
     // read amount
     let amount = if args.has_argument("amount") {
         args.get_value("amount")?.to_string_value()?
     } else {
+        let max = wallet.format_amount(&asset, max_balance).await.context("Error while formatting balance")?;
         prompt.read(
-            prompt.colorize_string(Color::Green, &format!("Amount (max: {}): ", format_coin(max_balance, decimals)))
+            prompt.colorize_string(Color::Green, &format!("Amount (max: {}): ", max))
         ).await.context("Error while reading amount")?
     };
 
-    let amount = from_coin(amount, decimals).context("Invalid amount")?;
-    manager.message(format!("Sending {} of {} to {}", format_coin(amount, decimals), asset, address.to_string()));
+    let amount = wallet.parse_amount(&asset, &amount).await.context("Invalid amount")?;
+    let formatted = wallet.format_amount(&asset, amount).await.context("Error while formatting amount")?;
+    manager.message(format!("Sending {} ({}) to {}", formatted, asset, address.to_string()));
+    if let Some(payment_id) = address.get_payment_id() {
+        manager.message(format!("Payment ID {} will be sent in the encrypted extra data", payment_id));
+    }
 
     if !args.get_flag("confirm")? && !prompt.ask_confirmation().await.context("Error while confirming action")? {
         manager.message("Transaction has been aborted");
@@ -1157,27 +1889,27 @@ async fn transfer_all(manager: &CommandManager, mut args: ArgumentManager) -> Re
         args.get_value("address")?.to_string_value()?
     } else {
         prompt.read_input(
-            prompt.colorize_str(Color::Green, "Address: "),
+            prompt.colorize_str(Color::Green, "Address or contact name: "),
             false
         ).await.context("Error while reading address")?
     };
-    let address = Address::from_string(&str_address).context("Invalid address")?;
+    let (address, default_asset) = wallet.resolve_address(&str_address).await.context("Invalid address")?;
 
     let mut asset = args.get_value("asset").and_then(|v| v.to_hash()).ok();
     if asset.is_none() {
+        let default_asset = default_asset.as_ref().map(|a| a.to_string()).unwrap_or_else(|| "XELIS".to_owned());
         asset = prompt.read_hash(
-           prompt.colorize_str(Color::Green, "Asset (default XELIS): ")
+           prompt.colorize_string(Color::Green, &format!("Asset (default {}): ", default_asset))
        ).await.ok();
     }
 
-    let asset = asset.unwrap_or(XELIS_ASSET);
-    let (mut amount, decimals, multisig) = {
+    let asset = asset.or(default_asset).unwrap_or(XELIS_ASSET);
+    let (mut amount, multisig) = {
         let storage = wallet.get_storage().read().await;
         let amount = storage.get_plaintext_balance_for(&asset).await.unwrap_or(0);
-        let decimals = storage.get_asset(&asset).await?.get_decimals();
         let multisig = storage.get_multisig_state().await
             .context("Error while reading multisig state")?;
-        (amount, decimals, multisig)
+        (amount, multisig)
     };
 
     let transfer = TransferBuilder {
@@ -1193,7 +1925,8 @@ async fn transfer_all(manager: &CommandManager, mut args: ArgumentManager) -> Re
         amount = amount.checked_sub(estimated_fees).context("Insufficient balance to pay fees")?;
     }
 
-    manager.message(format!("Sending {} of {} to {} (fees: {})", format_coin(amount, decimals), asset, address.to_string(), format_xelis(estimated_fees)));
+    let formatted = wallet.format_amount(&asset, amount).await.context("Error while formatting amount")?;
+    manager.message(format!("Sending {} ({}) to {} (fees: {})", formatted, asset, address.to_string(), format_xelis(estimated_fees)));
 
     if !args.get_flag("confirm")? && !prompt.ask_confirmation().await.context("Error while confirming action")? {
         manager.message("Transaction has been aborted");
@@ -1237,26 +1970,27 @@ async fn burn(manager: &CommandManager, mut args: ArgumentManager) -> Result<(),
         ).await.unwrap_or(XELIS_ASSET)
     };
 
-    let (max_balance, decimals, multisig) = {
+    let (max_balance, multisig) = {
         let storage = wallet.get_storage().read().await;
         let balance = storage.get_plaintext_balance_for(&asset).await.unwrap_or(0);
-        let decimals = storage.get_asset(&asset).await?.get_decimals();
         let multisig = storage.get_multisig_state().await
             .context("Error while reading multisig state")?;
-        (balance, decimals, multisig)
+        (balance, multisig)
     };
 
     // read amount
     let amount = if args.has_argument("amount") {
         args.get_value("amount")?.to_string_value()?
     } else {
+        let max = wallet.format_amount(&asset, max_balance).await.context("Error while formatting balance")?;
         prompt.read(
-            prompt.colorize_string(Color::Green, &format!("Amount (max: {}): ", format_coin(max_balance, decimals)))
+            prompt.colorize_string(Color::Green, &format!("Amount (max: {}): ", max))
         ).await.context("Error while reading amount")?
     };
 
-    let amount = from_coin(amount, decimals).context("Invalid amount")?;
-    manager.message(format!("Burning {} of {}", format_coin(amount, decimals), asset));
+    let amount = wallet.parse_amount(&asset, &amount).await.context("Invalid amount")?;
+    let formatted = wallet.format_amount(&asset, amount).await.context("Error while formatting amount")?;
+    manager.message(format!("Burning {} ({})", formatted, asset));
     if !args.get_flag("confirm")? && !prompt.ask_confirmation().await.context("Error while confirming action")? {
         manager.message("Transaction has been aborted");
         return Ok(())
@@ -1286,10 +2020,15 @@ async fn burn(manager: &CommandManager, mut args: ArgumentManager) -> Result<(),
 }
 
 // Show current wallet address
-async fn display_address(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+async fn display_address(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
     let context = manager.get_context().lock()?;
     let wallet: &Arc<Wallet> = context.get()?;
-    manager.message(format!("Wallet address: {}", wallet.get_address()));
+    if arguments.has_argument("payment_id") {
+        let payment_id = arguments.get_value("payment_id")?.to_number()?;
+        manager.message(format!("Integrated address for payment ID {}: {}", payment_id, wallet.get_address_with_payment_id(payment_id)));
+    } else {
+        manager.message(format!("Wallet address: {}", wallet.get_address()));
+    }
     Ok(())
 }
 
@@ -1297,20 +2036,28 @@ async fn display_address(manager: &CommandManager, _: ArgumentManager) -> Result
 async fn balance(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
     let context = manager.get_context().lock()?;
     let wallet: &Arc<Wallet> = context.get()?;
-    let storage = wallet.get_storage().read().await;
 
-    if arguments.has_argument("asset") {
-        let asset = arguments.get_value("asset")?.to_hash()?;
-        let balance = storage.get_plaintext_balance_for(&asset).await?;
-        let data = storage.get_asset(&asset).await?;
-        manager.message(format!("Balance for asset {}: {}", asset, format_coin(balance, data.get_decimals())));
-    } else {
-        for (asset, data) in storage.get_assets_with_data().await? {
-            let balance = storage.get_plaintext_balance_for(&asset).await.unwrap_or(0);
-            if balance > 0 {
-                manager.message(format!("Balance for asset {}: {}", asset, format_coin(balance, data.get_decimals())));
+    let balances = {
+        let storage = wallet.get_storage().read().await;
+        if arguments.has_argument("asset") {
+            let asset = arguments.get_value("asset")?.to_hash()?;
+            let balance = storage.get_plaintext_balance_for(&asset).await?;
+            vec![(asset, balance)]
+        } else {
+            let mut balances = Vec::new();
+            for asset in storage.get_tracked_assets().await? {
+                let balance = storage.get_plaintext_balance_for(&asset).await.unwrap_or(0);
+                if balance > 0 {
+                    balances.push((asset, balance));
+                }
             }
+            balances
         }
+    };
+
+    for (asset, balance) in balances {
+        let formatted = wallet.format_amount(&asset, balance).await.context("Error while formatting balance")?;
+        manager.message(format!("Balance for asset {}: {}", asset, formatted));
     }
 
     Ok(())
@@ -1331,7 +2078,12 @@ async fn history(manager: &CommandManager, mut arguments: ArgumentManager) -> Re
     let context = manager.get_context().lock()?;
     let wallet: &Arc<Wallet> = context.get()?;
     let storage = wallet.get_storage().read().await;
-    let mut transactions = storage.get_transactions()?;
+    let mut transactions = if arguments.has_argument("tag") {
+        let tag = arguments.get_value("tag")?.to_string_value()?;
+        storage.get_filtered_transactions(None, None, None, None, true, true, true, true, None, Some(tag.as_str()), None)?
+    } else {
+        storage.get_transactions()?
+    };
 
     // if we don't have any txs, no need proceed further
     if transactions.is_empty() {
@@ -1353,8 +2105,202 @@ async fn history(manager: &CommandManager, mut arguments: ArgumentManager) -> Re
     manager.message(format!("Transactions (total {}) page {}/{}:", transactions.len(), page, max_pages));
     for tx in transactions.iter().skip((page - 1) * ELEMENTS_PER_PAGE).take(ELEMENTS_PER_PAGE) {
         manager.message(format!("- {}", tx.summary(wallet.get_network().is_mainnet(), &*storage).await?));
+        if let Some(label) = storage.get_transaction_label(tx.get_hash())? {
+            if let Some(note) = label.note {
+                manager.message(format!("  Note: {}", note));
+            }
+            if !label.tags.is_empty() {
+                manager.message(format!("  Tags: {}", label.tags.into_iter().collect::<Vec<_>>().join(", ")));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Set the local note of a transaction
+async fn set_tx_note(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let prompt = manager.get_prompt();
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let tx_hash = arguments.get_value("tx_hash")?.to_hash()?;
+    let note = prompt.read_input("Note: ", false)
+        .await.context("Error while reading note")?;
+
+    let mut label = {
+        let storage = wallet.get_storage().read().await;
+        storage.get_transaction_label(&tx_hash)?.unwrap_or_default()
+    };
+    label.note = if note.is_empty() {
+        None
+    } else {
+        Some(note)
+    };
+
+    wallet.set_transaction_label(&tx_hash, label).await.context("Error while saving transaction note")?;
+    manager.message("Transaction note has been saved");
+    Ok(())
+}
+
+// Add a local tag on a transaction
+async fn tag_tx(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let tx_hash = arguments.get_value("tx_hash")?.to_hash()?;
+    let tag = arguments.get_value("tag")?.to_string_value()?;
+    let mut label = {
+        let storage = wallet.get_storage().read().await;
+        storage.get_transaction_label(&tx_hash)?.unwrap_or_default()
+    };
+
+    if !label.tags.insert(tag.clone()) {
+        manager.warn(format!("Transaction is already tagged with {}", tag));
+        return Ok(())
+    }
+
+    wallet.set_transaction_label(&tx_hash, label).await.context("Error while saving transaction tag")?;
+    manager.message(format!("Tag {} added", tag));
+    Ok(())
+}
+
+// Remove a local tag from a transaction
+async fn untag_tx(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let tx_hash = arguments.get_value("tx_hash")?.to_hash()?;
+    let tag = arguments.get_value("tag")?.to_string_value()?;
+    let mut label = {
+        let storage = wallet.get_storage().read().await;
+        storage.get_transaction_label(&tx_hash)?.unwrap_or_default()
+    };
+
+    if !label.tags.shift_remove(&tag) {
+        manager.warn(format!("Transaction is not tagged with {}", tag));
+        return Ok(())
+    }
+
+    wallet.set_transaction_label(&tx_hash, label).await.context("Error while saving transaction tags")?;
+    manager.message(format!("Tag {} removed", tag));
+    Ok(())
+}
+
+// Export all history entries in the requested format (csv by default)
+async fn export_history(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let filename = arguments.get_value("filename")?.to_string_value()?;
+    let format = if arguments.has_argument("format") {
+        match arguments.get_value("format")?.to_string_value()?.to_lowercase().as_str() {
+            "csv" => HistoryExportFormat::Csv,
+            "json" => HistoryExportFormat::Json,
+            _ => return Err(CommandError::InvalidArgument("Format must be csv or json".to_string()))
+        }
+    } else {
+        HistoryExportFormat::Csv
+    };
+
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    let mut file = File::create(&filename).context("Error while creating export file")?;
+    wallet.export_history(format, true, &mut file).await.context("Error while exporting history")?;
+
+    manager.message(format!("History exported to {}", filename));
+    Ok(())
+}
+
+// Create a payment request to this wallet and display it as URI and QR code
+async fn payment_request(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let asset = if arguments.has_argument("asset") {
+        Some(arguments.get_value("asset")?.to_hash()?)
+    } else {
+        None
+    };
+
+    let amount = if arguments.has_argument("amount") {
+        let amount = arguments.get_value("amount")?.to_string_value()?;
+        Some(wallet.parse_amount(asset.as_ref().unwrap_or(&XELIS_ASSET), &amount).await.context("Invalid amount")?)
+    } else {
+        None
+    };
+
+    let uri = wallet.create_payment_request(amount, asset, None).to_uri();
+    let qr_code = QrCode::new(uri.as_bytes()).context("Error while generating QR code")?
+        .render::<unicode::Dense1x2>()
+        .dark_color(unicode::Dense1x2::Light)
+        .light_color(unicode::Dense1x2::Dark)
+        .build();
+
+    manager.message(format!("Payment URI: {}", uri));
+    manager.message(qr_code);
+    Ok(())
+}
+
+// Pay a payment request received as URI or scanned from a QR code
+async fn pay_uri(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let prompt = manager.get_prompt();
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let uri = arguments.get_value("uri")?.to_string_value()?;
+    let request = wallet.parse_payment_uri(&uri).context("Invalid payment URI")?;
+    let asset = request.asset.unwrap_or(XELIS_ASSET);
+
+    let multisig = {
+        let storage = wallet.get_storage().read().await;
+        storage.get_multisig_state().await.context("Error while reading multisig state")?
+    };
+
+    if let Some(label) = &request.label {
+        manager.message(format!("Recipient: {}", label));
+    }
+
+    if let Some(message) = &request.message {
+        manager.message(format!("Message: {}", message));
+    }
+
+    let amount = match request.amount {
+        Some(amount) => amount,
+        None => {
+            let amount = prompt.read_input(
+                prompt.colorize_str(Color::Green, "Amount: "),
+                false
+            ).await.context("Error while reading amount")?;
+            wallet.parse_amount(&asset, &amount).await.context("Invalid amount")?
+        }
+    };
+
+    let formatted = wallet.format_amount(&asset, amount).await.context("Error while formatting amount")?;
+    manager.message(format!("Sending {} ({}) to {}", formatted, asset, request.address));
+    if !prompt.ask_confirmation().await.context("Error while confirming action")? {
+        manager.message("Transaction has been aborted");
+        return Ok(())
     }
 
+    manager.message("Building transaction...");
+    let transfer = TransferBuilder {
+        destination: request.address,
+        amount,
+        asset,
+        extra_data: request.extra_data
+    };
+    let tx_type = TransactionTypeBuilder::Transfers(vec![transfer]);
+    let tx = if let Some(multisig) = multisig {
+        create_transaction_with_multisig(manager, prompt, wallet, tx_type, multisig.payload).await?
+    } else {
+        match wallet.create_transaction(tx_type, FeeBuilder::default()).await {
+            Ok(tx) => tx,
+            Err(e) => {
+                manager.error(&format!("Error while creating transaction: {}", e));
+                return Ok(())
+            }
+        }
+    };
+
+    broadcast_tx(wallet, manager, tx).await;
     Ok(())
 }
 
@@ -1373,6 +2319,78 @@ async fn export_transactions_csv(manager: &CommandManager, mut arguments: Argume
     Ok(())
 }
 
+// Prepare a transfer to be signed by an offline wallet
+async fn prepare_transfer(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let (address, default_asset) = wallet.resolve_address(&arguments.get_value("address")?.to_string_value()?).await.context("Invalid address")?;
+    let asset = if arguments.has_argument("asset") {
+        arguments.get_value("asset")?.to_hash()?
+    } else {
+        default_asset.unwrap_or(XELIS_ASSET)
+    };
+    let filename = arguments.get_value("filename")?.to_string_value()?;
+
+    let amount = wallet.parse_amount(&asset, &arguments.get_value("amount")?.to_string_value()?).await.context("Invalid amount")?;
+
+    let transfer = TransferBuilder {
+        destination: address,
+        amount,
+        asset,
+        extra_data: None
+    };
+    let prepared = wallet.prepare_transaction(TransactionTypeBuilder::Transfers(vec![transfer]), FeeBuilder::default(), None, None).await
+        .context("Error while preparing transaction")?;
+
+    let json = serde_json::to_string_pretty(&prepared).context("Error while serializing prepared transaction")?;
+    let mut file = File::create(&filename).context("Error while creating prepared transaction file")?;
+    file.write_all(json.as_bytes()).context("Error while writing prepared transaction file")?;
+
+    manager.message(format!("Transaction prepared with {} fees and nonce {}, saved to {}", format_xelis(prepared.fee), prepared.nonce, filename));
+    Ok(())
+}
+
+// Sign a prepared transaction, this can be done while being offline
+async fn sign_prepared_transaction(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let filename = arguments.get_value("filename")?.to_string_value()?;
+    let output = arguments.get_value("output")?.to_string_value()?;
+
+    let file = File::open(&filename).context("Error while opening prepared transaction file")?;
+    let prepared: PreparedTransaction = serde_json::from_reader(file).context("Error while reading prepared transaction file")?;
+
+    let tx = wallet.sign_prepared_transaction(prepared).await.context("Error while signing prepared transaction")?;
+
+    let mut file = File::create(&output).context("Error while creating transaction file")?;
+    file.write_all(tx.to_hex().as_bytes()).context("Error while writing transaction file")?;
+
+    manager.message(format!("Transaction {} has been signed and saved to {}", tx.hash(), output));
+    Ok(())
+}
+
+// Broadcast a signed transaction stored in hex format
+async fn broadcast_transaction(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let filename = arguments.get_value("filename")?.to_string_value()?;
+    let hex = std::fs::read_to_string(&filename).context("Error while reading transaction file")?;
+    let tx = Transaction::from_hex(hex.trim()).context("Invalid transaction")?;
+
+    if !wallet.is_online().await {
+        manager.error("Wallet must be online to broadcast a transaction");
+        return Ok(())
+    }
+
+    let tx_hash = tx.hash();
+    wallet.submit_transaction(&tx).await.context("Error while broadcasting transaction")?;
+    manager.message(format!("Transaction {} has been broadcasted", tx_hash));
+    Ok(())
+}
+
 async fn clear_tx_cache(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
     let context = manager.get_context().lock()?;
     let wallet: &Arc<Wallet> = context.get()?;
@@ -1416,6 +2434,26 @@ async fn offline_mode(manager: &CommandManager, _: ArgumentManager) -> Result<()
     Ok(())
 }
 
+// Show the status of the daemons used by the failover
+#[cfg(feature = "network_handler")]
+async fn daemons(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    let endpoints = wallet.get_daemon_endpoints().await.context("Error while retrieving daemons")?;
+
+    manager.message(format!("Daemons ({}):", endpoints.len()));
+    for endpoint in endpoints {
+        let status = match endpoint.topoheight {
+            Some(topoheight) => format!("online at topoheight {}", topoheight),
+            None => "offline".to_owned()
+        };
+        let active = if endpoint.active { " (active)" } else { "" };
+        manager.message(format!("- {}: {}{}", endpoint.address, status, active));
+    }
+
+    Ok(())
+}
+
 // Show current wallet address
 #[cfg(feature = "network_handler")]
 async fn rescan(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
@@ -1435,15 +2473,198 @@ async fn rescan(manager: &CommandManager, mut arguments: ArgumentManager) -> Res
     Ok(())
 }
 
-async fn seed(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+// Show the progress of the rescan in progress
+#[cfg(feature = "network_handler")]
+async fn rescan_status(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
     let context = manager.get_context().lock()?;
     let wallet: &Arc<Wallet> = context.get()?;
-    let prompt =  manager.get_prompt();
-
-    let password = prompt.read_input("Password: ", true)
-        .await.context("Error while reading password")?;
-    // check if password is valid
-    wallet.is_valid_password(&password).await?;
+    match wallet.get_rescan_progress().await.context("Error while retrieving rescan progress")? {
+        Some(progress) => {
+            manager.message(format!("Rescan until topoheight {}: {}/{} assets done{}", progress.start_topoheight, progress.completed_assets, progress.total_assets, if progress.paused { " (paused)" } else { "" }));
+            if let (Some(asset), Some(topoheight)) = (progress.asset, progress.topoheight) {
+                manager.message(format!("Asset {} rescanned down to topoheight {}", asset, topoheight));
+            }
+        },
+        None => manager.message("No rescan in progress")
+    }
+    Ok(())
+}
+
+// Pause the rescan in progress
+#[cfg(feature = "network_handler")]
+async fn pause_rescan(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    wallet.pause_rescan().await.context("Error while pausing rescan")?;
+    manager.message("Rescan will be paused at the next checkpoint");
+    Ok(())
+}
+
+// Resume the rescan from its last checkpoint
+#[cfg(feature = "network_handler")]
+async fn resume_rescan(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    wallet.resume_rescan().await.context("Error while resuming rescan")?;
+    manager.message("Rescan has been resumed");
+    Ok(())
+}
+
+// Replace a pending transaction by the same one with a higher fee
+#[cfg(feature = "network_handler")]
+async fn bump_fee(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let tx_hash = arguments.get_value("tx_hash")?.to_hash()?;
+    let fee = if arguments.has_argument("fee") {
+        let value = from_coin(arguments.get_value("fee")?.to_string_value()?, COIN_DECIMALS).context("Invalid fee")?;
+        Some(FeeBuilder::Value(value))
+    } else {
+        None
+    };
+
+    let tx = wallet.bump_transaction_fee(&tx_hash, fee).await
+        .context("Error while bumping transaction fee")?;
+    manager.message(format!("Transaction {} replaced by {} (fees: {})", tx_hash, tx.hash(), format_xelis(tx.get_fee())));
+    Ok(())
+}
+
+// Prove the amount sent in a transfer, first transfer is used by default
+#[cfg(feature = "network_handler")]
+async fn payment_proof(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let tx_hash = arguments.get_value("tx_hash")?.to_hash()?;
+    let index = if arguments.has_argument("index") {
+        u8::try_from(arguments.get_value("index")?.to_number()?).context("Invalid transfer index")?
+    } else {
+        0
+    };
+
+    let proof = wallet.create_payment_proof(&tx_hash, index).await
+        .context("Error while creating payment proof")?;
+    manager.message(format!("Payment proof: {}", proof));
+    Ok(())
+}
+
+// Verify a payment proof received from a sender
+#[cfg(feature = "network_handler")]
+async fn verify_payment_proof(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let proof = HumanReadableProof::from_string(&arguments.get_value("proof")?.to_string_value()?)
+        .context("Invalid proof")?;
+    let result = wallet.verify_payment_proof(&proof).await
+        .context("Error while verifying payment proof")?;
+
+    let amount = wallet.format_amount(&result.asset, result.amount).await
+        .context("Error while formatting amount")?;
+    manager.message(format!(
+        "Valid payment proof: {} sent from {} to {} in transaction {} #{} at topoheight {}",
+        amount, result.source, result.destination, result.transaction, result.index, result.block_topoheight
+    ));
+    Ok(())
+}
+
+// Prove the reserve of an asset, challenge is given in hex by the verifier
+#[cfg(feature = "network_handler")]
+async fn reserve_proof(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let asset = arguments.get_value("asset")?.to_hash()?;
+    let amount = wallet.parse_amount(&asset, &arguments.get_value("amount")?.to_string_value()?).await
+        .context("Invalid amount")?;
+    let challenge = hex::decode(arguments.get_value("challenge")?.to_string_value()?)
+        .context("Invalid challenge hex")?;
+
+    let proof = wallet.create_reserve_proof(&asset, amount, challenge).await
+        .context("Error while creating reserve proof")?;
+    manager.message(format!("Reserve proof: {}", proof));
+    Ok(())
+}
+
+// Verify a reserve proof received from an account
+#[cfg(feature = "network_handler")]
+async fn verify_reserve_proof(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let address = Address::from_string(&arguments.get_value("address")?.to_string_value()?)
+        .context("Invalid address")?;
+    let proof = HumanReadableProof::from_string(&arguments.get_value("proof")?.to_string_value()?)
+        .context("Invalid proof")?;
+    let result = wallet.verify_reserve_proof(&address, &proof).await
+        .context("Error while verifying reserve proof")?;
+
+    let amount = wallet.format_amount(&result.asset, result.amount).await
+        .context("Error while formatting amount")?;
+    manager.message(format!(
+        "Valid reserve proof: {} owns at least {} at topoheight {} with challenge {}",
+        result.address, amount, result.topoheight, result.challenge
+    ));
+    if !result.latest {
+        manager.warn("Balance has changed since the proof topoheight");
+    }
+    Ok(())
+}
+
+// Prove the balance of an asset to an auditor
+#[cfg(feature = "network_handler")]
+async fn audit_proof(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let auditor = Address::from_string(&arguments.get_value("auditor")?.to_string_value()?)
+        .context("Invalid auditor address")?;
+    let asset = arguments.get_value("asset")?.to_hash()?;
+
+    let proof = wallet.create_audit_proof(&auditor, &asset).await
+        .context("Error while creating audit proof")?;
+    manager.message(format!("Audit proof: {}", proof));
+    Ok(())
+}
+
+// Verify an audit proof received from an account
+#[cfg(feature = "network_handler")]
+async fn verify_audit_proof(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let address = Address::from_string(&arguments.get_value("address")?.to_string_value()?)
+        .context("Invalid address")?;
+    let proof = HumanReadableProof::from_string(&arguments.get_value("proof")?.to_string_value()?)
+        .context("Invalid proof")?;
+    let result = wallet.verify_audit_proof(&address, &proof).await
+        .context("Error while verifying audit proof")?;
+
+    let amount = wallet.format_amount(&result.asset, result.amount).await
+        .context("Error while formatting amount")?;
+    match (result.transaction, result.index) {
+        (Some(transaction), Some(index)) => manager.message(format!(
+            "Valid audit proof: {} transferred {} in transaction {} #{}",
+            result.address, amount, transaction, index
+        )),
+        _ => manager.message(format!(
+            "Valid audit proof: {} owns {} at topoheight {}",
+            result.address, amount, result.topoheight.unwrap_or_default()
+        ))
+    };
+    Ok(())
+}
+
+async fn seed(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    let prompt =  manager.get_prompt();
+
+    let password = prompt.read_input("Password: ", true)
+        .await.context("Error while reading password")?;
+    // check if password is valid
+    wallet.is_valid_password(&password).await?;
 
     let language = if arguments.has_argument("language") {
         arguments.get_value("language")?.to_number()?
@@ -1453,12 +2674,82 @@ async fn seed(manager: &CommandManager, mut arguments: ArgumentManager) -> Resul
 
     let seed = wallet.get_seed(language as usize)?;
     prompt.read_input(
-        prompt.colorize_string(Color::Green, &format!("Seed: {}\r\nPress ENTER to continue", seed)),
+        prompt.colorize_string(Color::Green, &format!("Seed: {}\r\nPress ENTER to continue", seed.as_str())),
         false
     ).await.context("Error while printing seed")?;
     Ok(())
 }
 
+async fn bip39_seed(manager: &CommandManager, mut arguments: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    let prompt =  manager.get_prompt();
+
+    let password = prompt.read_input("Password: ", true)
+        .await.context("Error while reading password")?;
+    // check if password is valid
+    wallet.is_valid_password(&password).await?;
+
+    let language = if arguments.has_argument("language") {
+        arguments.get_value("language")?.to_number()?
+    } else {
+        0
+    };
+
+    let language_name = mnemonics::bip39::get_language_name(language as usize).context("Invalid language")?;
+    let mnemonic = wallet.get_bip39_mnemonic(language as usize)?;
+    prompt.read_input(
+        prompt.colorize_string(Color::Green, &format!("BIP39 mnemonic ({}): {}\r\nPress ENTER to continue", language_name, mnemonic.as_str())),
+        false
+    ).await.context("Error while printing mnemonic")?;
+    Ok(())
+}
+
+async fn lock(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    wallet.lock().await.context("Error while locking wallet")?;
+    manager.message("Wallet is now locked");
+    Ok(())
+}
+
+async fn unlock(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    let prompt = manager.get_prompt();
+
+    if !wallet.is_locked() {
+        manager.message("Wallet is not locked");
+        return Ok(())
+    }
+
+    let password = prompt.read_input("Password: ", true)
+        .await.context("Error while reading password")?;
+    wallet.unlock(&password).await.context("Error while unlocking wallet")?;
+    manager.message("Wallet is now unlocked");
+    Ok(())
+}
+
+async fn export_view_key(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+    let prompt =  manager.get_prompt();
+
+    let password = prompt.read_input("Password: ", true)
+        .await.context("Error while reading password")?;
+    // check if password is valid
+    wallet.is_valid_password(&password).await?;
+
+    manager.warn("Balances are encrypted with the account key: anyone holding the view key could spend the funds using another software");
+    let view_key = wallet.export_view_key().context("Error while exporting view key")?;
+    prompt.read_input(
+        prompt.colorize_string(Color::Green, &format!("View Key: {}\r\nPress ENTER to continue", view_key)),
+        false
+    ).await.context("Error while printing view key")?;
+    Ok(())
+}
+
 async fn nonce(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
     let context = manager.get_context().lock()?;
     let wallet: &Arc<Wallet> = context.get()?;
@@ -1714,7 +3005,7 @@ async fn multisig_sign(manager: &CommandManager, mut args: ArgumentManager) -> R
         prompt.read("Transaction hash: ").await.context("Error while reading transaction hash")?
     };
 
-    let signature = wallet.sign_data(tx_hash.as_bytes());
+    let signature = wallet.sign_data(tx_hash.as_bytes()).context("Error while signing data")?;
     prompt.read_input(format!("Signature: {}\r\nPress ENTER to continue", signature.to_hex()), false).await
         .context("Error while displaying seed")?;
 
@@ -1739,6 +3030,360 @@ async fn multisig_show(manager: &CommandManager, _: ArgumentManager) -> Result<(
     Ok(())
 }
 
+// Create a multisig transfer saved until enough participants have signed it
+async fn multisig_transfer(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let (address, default_asset) = wallet.resolve_address(&args.get_value("address")?.to_string_value()?).await.context("Invalid address")?;
+    let asset = if args.has_argument("asset") {
+        args.get_value("asset")?.to_hash()?
+    } else {
+        default_asset.unwrap_or(XELIS_ASSET)
+    };
+
+    let amount = wallet.parse_amount(&asset, &args.get_value("amount")?.to_string_value()?).await.context("Invalid amount")?;
+
+    let transfer = TransferBuilder {
+        destination: address,
+        amount,
+        asset,
+        extra_data: None
+    };
+    let unsigned = wallet.create_multisig_transaction(TransactionTypeBuilder::Transfers(vec![transfer]), FeeBuilder::default(), None, None).await
+        .context("Error while creating multisig transaction")?;
+
+    manager.message(format!("Multisig transaction created, hash to sign by participants: {}", unsigned.get_hash_for_multisig()));
+    Ok(())
+}
+
+// Show all pending multisig transactions with their signers
+async fn multisig_pending(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let pending = wallet.get_pending_multisig_transactions().await
+        .context("Error while retrieving pending multisig transactions")?;
+
+    if pending.is_empty() {
+        manager.message("No pending multisig transaction");
+        return Ok(())
+    }
+
+    for status in pending {
+        manager.message(format!("- {}: {}/{} signatures (signed: {:?}, missing: {:?}){}", status.hash, status.signers.len(), status.threshold, status.signers, status.missing, if status.ready { " ready" } else { "" }));
+    }
+
+    Ok(())
+}
+
+// Export a pending multisig transaction to be reviewed by participants
+async fn multisig_export(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let tx_hash = args.get_value("tx_hash")?.to_hash()?;
+    let filename = args.get_value("filename")?.to_string_value()?;
+
+    let unsigned = {
+        let storage = wallet.get_storage().read().await;
+        if !storage.has_pending_multisig_transaction(&tx_hash)? {
+            manager.error("Pending multisig transaction not found");
+            return Ok(())
+        }
+        storage.get_pending_multisig_transaction(&tx_hash)?
+    };
+
+    let mut file = File::create(&filename).context("Error while creating file")?;
+    file.write_all(unsigned.to_hex().as_bytes()).context("Error while writing file")?;
+
+    manager.message(format!("Multisig transaction {} exported to {}", tx_hash, filename));
+    Ok(())
+}
+
+// Import the signature of a participant
+async fn multisig_add_signature(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let tx_hash = args.get_value("tx_hash")?.to_hash()?;
+    let id = args.get_value("signer_id")?.to_number()?;
+    let id = u8::try_from(id).context("Invalid signer ID")?;
+    let signature = Signature::from_hex(&args.get_value("signature")?.to_string_value()?).context("Invalid signature")?;
+
+    let status = wallet.add_multisig_signature(&tx_hash, SignatureId { id, signature }).await
+        .context("Error while adding multisig signature")?;
+
+    manager.message(format!("Signature added: {}/{} signatures", status.signers.len(), status.threshold));
+    if status.ready {
+        manager.message("Threshold reached, you can now finalize the transaction with 'multisig_finalize'");
+    }
+
+    Ok(())
+}
+
+// Finalize a pending multisig transaction and broadcast it
+async fn multisig_finalize(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let tx_hash = args.get_value("tx_hash")?.to_hash()?;
+    let tx = {
+        let mut storage = wallet.get_storage().write().await;
+        let tx = wallet.finalize_multisig_transaction_with_storage(&storage, &tx_hash).await
+            .context("Error while finalizing multisig transaction")?;
+
+        let mut state = TransactionBuilderState::from_tx(&storage, &tx, wallet.get_network().is_mainnet()).await
+            .context("Error while building transaction state")?;
+        state.apply_changes(&mut storage).await.context("Error while applying changes")?;
+        storage.delete_pending_multisig_transaction(&tx_hash)?;
+
+        tx
+    };
+
+    broadcast_tx(wallet, manager, tx).await;
+    Ok(())
+}
+
+// Delete a pending multisig transaction
+async fn multisig_cancel(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let tx_hash = args.get_value("tx_hash")?.to_hash()?;
+    let mut storage = wallet.get_storage().write().await;
+    if !storage.has_pending_multisig_transaction(&tx_hash)? {
+        manager.error("Pending multisig transaction not found");
+        return Ok(())
+    }
+
+    storage.delete_pending_multisig_transaction(&tx_hash)?;
+    manager.message(format!("Pending multisig transaction {} deleted", tx_hash));
+    Ok(())
+}
+
+// Show all contacts saved in the address book
+async fn address_book(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let storage = wallet.get_storage().read().await;
+    let entries = storage.get_address_book_entries()
+        .context("Error while retrieving address book")?;
+
+    if entries.is_empty() {
+        manager.message("Address book is empty");
+        return Ok(())
+    }
+
+    for entry in entries {
+        let mut line = format!("- {}: {}", entry.name, entry.address);
+        if let Some(asset) = entry.default_asset {
+            line.push_str(&format!(" (default asset: {})", asset));
+        }
+        if let Some(notes) = entry.notes {
+            line.push_str(&format!(" - {}", notes));
+        }
+        manager.message(line);
+    }
+
+    Ok(())
+}
+
+// Save a contact in the address book
+async fn address_book_add(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let prompt = manager.get_prompt();
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let name = args.get_value("name")?.to_string_value()?;
+    let address = Address::from_string(&args.get_value("address")?.to_string_value()?).context("Invalid address")?;
+    let default_asset = if args.has_argument("asset") {
+        Some(args.get_value("asset")?.to_hash()?)
+    } else {
+        None
+    };
+
+    let notes = prompt.read_input("Notes (optional): ", false)
+        .await.context("Error while reading notes")?;
+    let notes = if notes.is_empty() {
+        None
+    } else {
+        Some(notes)
+    };
+
+    wallet.set_address_book_entry(AddressBookEntry {
+        name: name.clone(),
+        address,
+        default_asset,
+        notes
+    }).await.context("Error while saving contact")?;
+
+    manager.message(format!("Contact {} saved", name));
+    Ok(())
+}
+
+// Delete a contact from the address book
+async fn address_book_remove(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let name = args.get_value("name")?.to_string_value()?;
+    wallet.delete_address_book_entry(&name).await.context("Error while deleting contact")?;
+    manager.message(format!("Contact {} deleted", name));
+    Ok(())
+}
+
+// Show the sub-accounts created from the wallet
+async fn sub_accounts(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let index = wallet.get_sub_account_index().await.context("Error while retrieving account index")?;
+    if index != 0 {
+        manager.message(format!("This is the sub-account {}, open the master account to see all sub-accounts", index));
+        return Ok(())
+    }
+
+    let accounts = wallet.get_sub_accounts().await.context("Error while retrieving sub-accounts")?;
+    if accounts.is_empty() {
+        manager.message("No sub-account created, use --account to open one");
+        return Ok(())
+    }
+
+    manager.message(format!("Sub-accounts ({}):", accounts.len()));
+    for (index, address) in accounts {
+        manager.message(format!("- {}: {}", index, address));
+    }
+
+    Ok(())
+}
+
+// Show all the recurring payments
+async fn scheduled_payments(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let payments = {
+        let storage = wallet.get_storage().read().await;
+        storage.get_scheduled_payments()
+            .context("Error while retrieving scheduled payments")?
+    };
+
+    if payments.is_empty() {
+        manager.message("No scheduled payment");
+        return Ok(())
+    }
+
+    for payment in payments {
+        let amount = wallet.format_amount(&payment.asset, payment.amount).await.context("Error while formatting amount")?;
+        let next = chrono::DateTime::from_timestamp_millis(payment.next_execution as i64)
+            .map(|date| date.to_rfc3339())
+            .unwrap_or_else(|| payment.next_execution.to_string());
+
+        let mut line = format!("- {}: {} ({}) to {} every {} seconds, next at {}", payment.name, amount, payment.asset, payment.destination, payment.interval, next);
+        if let Some(remaining) = payment.remaining {
+            line.push_str(&format!(" ({} left)", remaining));
+        }
+        if let Some(error) = payment.last_error {
+            line.push_str(&format!(" - {} failure(s), last error: {}", payment.failures, error));
+        }
+        manager.message(line);
+    }
+
+    Ok(())
+}
+
+// Create or replace a recurring payment
+async fn schedule_payment(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let prompt = manager.get_prompt();
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let name = args.get_value("name")?.to_string_value()?;
+    let (address, default_asset) = wallet.resolve_address(&args.get_value("address")?.to_string_value()?).await.context("Invalid address")?;
+    let asset = if args.has_argument("asset") {
+        args.get_value("asset")?.to_hash()?
+    } else {
+        default_asset.unwrap_or(XELIS_ASSET)
+    };
+
+    let amount = wallet.parse_amount(&asset, &args.get_value("amount")?.to_string_value()?).await.context("Invalid amount")?;
+    let interval = args.get_value("interval")?.to_number()?;
+    let count = if args.has_argument("count") {
+        Some(args.get_value("count")?.to_number()?)
+    } else {
+        None
+    };
+
+    let formatted = wallet.format_amount(&asset, amount).await.context("Error while formatting amount")?;
+    manager.message(format!("Sending {} ({}) to {} every {} seconds{}", formatted, asset, address, interval, count.map(|c| format!(", {} times", c)).unwrap_or_default()));
+    if !prompt.ask_confirmation().await.context("Error while confirming action")? {
+        manager.message("Scheduled payment has been aborted");
+        return Ok(())
+    }
+
+    wallet.set_scheduled_payment(name.clone(), address, asset, amount, interval, None, count).await
+        .context("Error while saving scheduled payment")?;
+
+    manager.message(format!("Scheduled payment {} saved, it will be sent while the wallet is online", name));
+    Ok(())
+}
+
+// Delete a recurring payment
+async fn unschedule_payment(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let name = args.get_value("name")?.to_string_value()?;
+    wallet.delete_scheduled_payment(&name).await.context("Error while deleting scheduled payment")?;
+    manager.message(format!("Scheduled payment {} deleted", name));
+    Ok(())
+}
+
+// Show all XSWD applications with granted permissions
+async fn xswd_applications(manager: &CommandManager, _: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let applications = wallet.get_xswd_applications().await
+        .context("Error while retrieving XSWD applications")?;
+
+    if applications.is_empty() {
+        manager.message("No XSWD application");
+        return Ok(())
+    }
+
+    for app in applications {
+        manager.message(format!("- {} ({}){}", app.name, app.id, app.url.map(|url| format!(" {}", url)).unwrap_or_default()));
+        if !app.scope.methods.is_empty() {
+            manager.message(format!("  Methods: {}", app.scope.methods.iter().cloned().collect::<Vec<_>>().join(", ")));
+        }
+        if !app.scope.assets.is_empty() {
+            manager.message(format!("  Assets: {}", app.scope.assets.iter().map(|asset| asset.to_string()).collect::<Vec<_>>().join(", ")));
+        }
+        for (asset, limit) in &app.scope.daily_limits {
+            let spent = app.spent.get(asset).copied().unwrap_or(0);
+            let limit = wallet.format_amount(asset, *limit).await.context("Error while formatting daily limit")?;
+            let spent = wallet.format_amount(asset, spent).await.context("Error while formatting spent amount")?;
+            manager.message(format!("  Daily limit for {}: {} ({} spent)", asset, limit, spent));
+        }
+    }
+
+    Ok(())
+}
+
+// Revoke the permissions of a XSWD application
+async fn xswd_revoke(manager: &CommandManager, mut args: ArgumentManager) -> Result<(), CommandError> {
+    let context = manager.get_context().lock()?;
+    let wallet: &Arc<Wallet> = context.get()?;
+
+    let id = args.get_value("id")?.to_string_value()?;
+    wallet.revoke_xswd_application(&id).await.context("Error while revoking XSWD application")?;
+    manager.message(format!("Permissions of XSWD application {} revoked", id));
+    Ok(())
+}
+
 // broadcast tx if possible
 // submit_transaction increase the local nonce in storage in case of success
 async fn broadcast_tx(wallet: &Wallet, manager: &CommandManager, tx: Transaction) {
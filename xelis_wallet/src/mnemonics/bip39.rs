@@ -0,0 +1,107 @@
+use bip39::{Language, Mnemonic};
+use zeroize::{Zeroize, Zeroizing};
+use xelis_common::{
+    crypto::PrivateKey,
+    serializer::Serializer
+};
+use super::{MnemonicsError, KEY_SIZE, SEED_LENGTH};
+
+// BIP39 mnemonics to exchange seeds with wallets and backup tools of other ecosystems
+//
+// A private key is exported as the entropy of a 24 words mnemonic,
+// it can be imported back using `mnemonic_to_key` without any loss.
+// Mnemonics generated by other wallets don't contain a valid private key:
+// they are imported using the standard BIP39 seed derived with the passphrase.
+
+// Languages supported, indexed as in `key_to_mnemonic`
+pub fn get_languages() -> &'static [Language] {
+    Language::all()
+}
+
+// Name of a language, like "English" or "French"
+pub fn get_language_name(language_index: usize) -> Result<String, MnemonicsError> {
+    let language = get_languages().get(language_index).ok_or(MnemonicsError::InvalidLanguageIndex)?;
+    Ok(format!("{:?}", language))
+}
+
+// Export a private key as a 24 words BIP39 mnemonic in the requested language
+pub fn key_to_mnemonic(key: &PrivateKey, language_index: usize) -> Result<Zeroizing<String>, MnemonicsError> {
+    let language = *get_languages().get(language_index).ok_or(MnemonicsError::InvalidLanguageIndex)?;
+
+    let bytes = key.to_zeroizing_bytes();
+    if bytes.len() != KEY_SIZE {
+        return Err(MnemonicsError::InvalidKeySize);
+    }
+
+    let mnemonic = Mnemonic::from_entropy_in(language, &bytes)?;
+    Ok(Zeroizing::new(mnemonic.to_string()))
+}
+
+// Import a private key exported using `key_to_mnemonic`
+// Language is detected automatically
+pub fn mnemonic_to_key(mnemonic: &str) -> Result<PrivateKey, MnemonicsError> {
+    let mnemonic = Mnemonic::parse(mnemonic)?;
+    if mnemonic.word_count() != SEED_LENGTH {
+        return Err(MnemonicsError::InvalidWordsCount);
+    }
+
+    let (mut entropy, len) = mnemonic.to_entropy_array();
+    // A zero private key is not valid
+    let key = if entropy[..len].iter().all(|b| *b == 0) {
+        Err(MnemonicsError::InvalidKeyFromBytes)
+    } else {
+        PrivateKey::from_bytes(&entropy[..len]).map_err(|_| MnemonicsError::InvalidKeyFromBytes)
+    };
+    entropy.zeroize();
+
+    key
+}
+
+// Derive a private key from any BIP39 mnemonic and its passphrase
+// The 64 bytes BIP39 seed is reduced to a private key
+pub fn mnemonic_seed_to_key(mnemonic: &str, passphrase: &str) -> Result<PrivateKey, MnemonicsError> {
+    let mnemonic = Mnemonic::parse(mnemonic)?;
+    let mut seed = mnemonic.to_seed(passphrase);
+    let key = PrivateKey::from_wide_bytes(&seed);
+    seed.zeroize();
+
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use xelis_common::crypto::KeyPair;
+
+    #[test]
+    fn test_export_import() {
+        let (_, key) = KeyPair::new().split();
+        for i in 0..super::get_languages().len() {
+            let mnemonic = super::key_to_mnemonic(&key, i).unwrap();
+            assert_eq!(mnemonic.split_whitespace().count(), 24);
+
+            let nkey = super::mnemonic_to_key(&mnemonic).unwrap();
+            assert_eq!(key.as_scalar(), nkey.as_scalar());
+        }
+    }
+
+    #[test]
+    fn test_seed_passphrase() {
+        // BIP39 test vector
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let key = super::mnemonic_seed_to_key(mnemonic, "TREZOR").unwrap();
+        let same = super::mnemonic_seed_to_key(mnemonic, "TREZOR").unwrap();
+        let other = super::mnemonic_seed_to_key(mnemonic, "").unwrap();
+
+        assert_eq!(key.as_scalar(), same.as_scalar());
+        assert_ne!(key.as_scalar(), other.as_scalar());
+
+        // 12 words can't contain a private key
+        assert!(super::mnemonic_to_key(mnemonic).is_err());
+    }
+
+    #[test]
+    fn test_invalid_checksum() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+        assert!(super::mnemonic_seed_to_key(mnemonic, "").is_err());
+    }
+}
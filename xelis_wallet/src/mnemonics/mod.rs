@@ -1,7 +1,10 @@
 pub mod languages;
+#[cfg(feature = "bip39")]
+pub mod bip39;
 
 use thiserror::Error;
 use log::debug;
+use zeroize::Zeroizing;
 use xelis_common::{
     crypto::PrivateKey,
     serializer::Serializer
@@ -50,7 +53,10 @@ pub enum MnemonicsError {
     #[error("Word list sanity check error")]
     WordListSanityCheckError,
     #[error("Out of bounds")]
-    OutOfBounds
+    OutOfBounds,
+    #[cfg(feature = "bip39")]
+    #[error("Invalid BIP39 mnemonic: {0}")]
+    Bip39(#[from] ::bip39::Error)
 }
 
 pub struct Language<'a> {
@@ -141,7 +147,7 @@ pub fn words_to_key(words: &[&str]) -> Result<PrivateKey, MnemonicsError> {
     let (indices, language_index) = find_indices(words)?.ok_or(MnemonicsError::NoIndicesFound)?;
     debug!("Language found: {}", LANGUAGES[language_index].name);
 
-    let mut dest = Vec::with_capacity(KEY_SIZE);
+    let mut dest = Zeroizing::new(Vec::with_capacity(KEY_SIZE));
     for i in (0..SEED_LENGTH).step_by(3) {
         let a = indices.get(i).ok_or(MnemonicsError::OutOfBounds)?;
         let b = indices.get(i + 1).ok_or(MnemonicsError::OutOfBounds)?;
@@ -171,7 +177,7 @@ pub fn key_to_words_with_language<'a>(key: &PrivateKey, language: &'a Language)
         return Err(MnemonicsError::InvalidLanguage);
     }
 
-    let bytes = key.to_bytes();
+    let bytes = key.to_zeroizing_bytes();
     if bytes.len() != KEY_SIZE {
         return Err(MnemonicsError::InvalidKeySize);
     }
@@ -3,7 +3,10 @@ use std::{
         HashMap,
         HashSet
     },
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc
+    },
     time::Duration
 };
 use indexmap::IndexMap;
@@ -18,7 +21,8 @@ use xelis_common::{
             MultisigState,
             NewBlockEvent
         },
-        wallet::BalanceChanged,
+        wallet::{AssetFilter, BalanceChanged, RescanProgress},
+        RPCTransaction,
         RPCTransactionType
     },
     asset::AssetData,
@@ -39,7 +43,7 @@ use xelis_common::{
     utils::sanitize_daemon_address
 };
 use crate::{
-    config::AUTO_RECONNECT_INTERVAL,
+    config::{AUTO_RECONNECT_INTERVAL, SCHEDULED_PAYMENTS_CHECK_INTERVAL},
     daemon_api::DaemonAPI,
     entry::{
         EntryData,
@@ -47,7 +51,7 @@ use crate::{
         TransferIn,
         TransferOut
     },
-    storage::{Balance, MultiSig},
+    storage::{Balance, MultiSig, RescanState},
     wallet::{
         Event, Wallet
     }
@@ -78,7 +82,13 @@ pub struct NetworkHandler {
     // api to communicate with daemon
     // It is behind a Arc to be shared across several wallets
     // in case someone make a custom service and don't want to create a new connection
-    api: Arc<DaemonAPI>
+    api: Arc<DaemonAPI>,
+    // Set to true to pause the rescan at the next checkpoint
+    rescan_paused: AtomicBool,
+    // Prevent running the rescan twice at the same time
+    rescan_lock: Mutex<()>,
+    // tokio task sending the scheduled payments
+    scheduler: Mutex<Option<JoinHandle<()>>>
 }
 
 impl NetworkHandler {
@@ -99,7 +109,10 @@ impl NetworkHandler {
         Ok(Arc::new(Self {
             task: Mutex::new(None),
             wallet,
-            api
+            api,
+            rescan_paused: AtomicBool::new(false),
+            rescan_lock: Mutex::new(()),
+            scheduler: Mutex::new(None)
         }))
     }
 
@@ -155,12 +168,31 @@ impl NetworkHandler {
             }
         }));
 
+        // Send the scheduled payments as long as we are online
+        let zelf = Arc::clone(&self);
+        *self.scheduler.lock().await = Some(spawn_task("scheduled-payments", async move {
+            loop {
+                sleep(Duration::from_secs(SCHEDULED_PAYMENTS_CHECK_INTERVAL)).await;
+                if !zelf.api.is_online() {
+                    continue;
+                }
+
+                if let Err(e) = zelf.wallet.process_scheduled_payments().await {
+                    error!("Error while processing scheduled payments: {}", e);
+                }
+            }
+        }));
+
         Ok(())
     }
 
     // Stop the internal loop to stop syncing
     pub async fn stop(&self, api: bool) -> Result<(), NetworkError> {
         trace!("Stopping network handler");
+        if let Some(handle) = self.scheduler.lock().await.take() {
+            handle.abort();
+        }
+
         if let Some(handle) = self.task.lock().await.take() {
             if handle.is_finished() {
                 debug!("Network handler is already finished");
@@ -193,6 +225,56 @@ impl NetworkHandler {
         &self.api
     }
 
+    // Pause the rescan in progress, its progress is kept in storage
+    pub fn pause_rescan(&self) {
+        self.rescan_paused.store(true, Ordering::SeqCst);
+    }
+
+    // Check if the rescan is paused
+    pub fn is_rescan_paused(&self) -> bool {
+        self.rescan_paused.load(Ordering::SeqCst)
+    }
+
+    // Resume the rescan from its last checkpoint
+    pub async fn resume_rescan(self: &Arc<Self>) {
+        self.rescan_paused.store(false, Ordering::SeqCst);
+        if !self.is_running().await {
+            debug!("Network handler is not running, rescan will be resumed at next sync");
+            return;
+        }
+
+        let zelf = Arc::clone(&self);
+        spawn_task("rescan-resume", async move {
+            let address = zelf.wallet.get_address();
+            let mut topoheight_processed = HashSet::new();
+            let mut highest_nonce = None;
+            if let Err(e) = zelf.rescan_history(&address, &mut topoheight_processed, &mut highest_nonce).await {
+                error!("Error while resuming rescan: {}", e);
+            }
+        });
+    }
+
+    // Build the rescan progress to be reported
+    pub async fn get_rescan_progress(&self, state: &RescanState) -> Result<RescanProgress, Error> {
+        let total_assets = {
+            let storage = self.wallet.get_storage().read().await;
+            storage.get_assets().await?.len()
+        };
+
+        Ok(self.build_rescan_progress(state, total_assets))
+    }
+
+    fn build_rescan_progress(&self, state: &RescanState, total_assets: usize) -> RescanProgress {
+        RescanProgress {
+            start_topoheight: state.start_topoheight,
+            asset: state.current.as_ref().map(|(asset, _)| asset.clone()),
+            topoheight: state.current.as_ref().map(|(_, topoheight)| *topoheight),
+            completed_assets: state.completed_assets.len(),
+            total_assets,
+            paused: self.is_rescan_paused()
+        }
+    }
+
     // check if the network handler is running (that we have a task and its not finished)
     pub async fn is_running(&self) -> bool {
         let task = self.task.lock().await;
@@ -216,6 +298,12 @@ impl NetworkHandler {
         }
 
         let mut assets_changed = HashSet::new();
+        // Assets ignored by the user are not decrypted and not synced
+        let asset_filter = {
+            let storage = self.wallet.get_storage().read().await;
+            storage.get_asset_filter().clone()
+        };
+
         // Miner address to verify if we mined the block
         let miner = block.miner.into_owned().to_public_key();
 
@@ -264,8 +352,11 @@ impl NetworkHandler {
         // Highest nonce we found in this block
         let mut our_highest_nonce = None;
 
+        // Decrypt in parallel all the amounts of the transfers related to us
+        let mut amounts = self.decrypt_block_amounts(address, &block.transactions, &asset_filter).await?;
+
         // Verify all TXs one by one to find one for us
-        'main: for tx in block.transactions.into_iter() {
+        'main: for (tx_index, tx) in block.transactions.into_iter().enumerate() {
             trace!("Checking transaction {}", tx.hash);
             let is_owner = *tx.source.get_public_key() == *address.get_public_key();
             if is_owner {
@@ -282,7 +373,9 @@ impl NetworkHandler {
                             continue 'main;
                         }
 
-                        assets_changed.insert(payload.asset.clone());
+                        if asset_filter.is_tracked(&payload.asset) {
+                            assets_changed.insert(payload.asset.clone());
+                        }
                         Some(EntryData::Burn { asset: payload.asset, amount: payload.amount, fee: tx.fee, nonce: tx.nonce })
                     } else {
                         None
@@ -294,7 +387,7 @@ impl NetworkHandler {
 
                     // Used to check only once if we have processed this TX already
                     let mut checked = false;
-                    for transfer in txs {
+                    for (transfer_index, transfer) in txs.into_iter().enumerate() {
                         let destination = transfer.destination.to_public_key();
                         if is_owner || destination == *address.get_public_key() {
                             // Check only once if we have processed this TX already
@@ -307,29 +400,15 @@ impl NetworkHandler {
                                 checked = true;
                             }
 
-                            // Get the right handle
-                            let (role, handle) = if is_owner {
-                                (Role::Sender, transfer.sender_handle)
+                            let role = if is_owner {
+                                Role::Sender
                             } else {
-                                (Role::Receiver, transfer.receiver_handle)
-                            };
-
-                            // Decompress commitment it if possible
-                            let commitment = match transfer.commitment.decompress() {
-                                Ok(c) => c,
-                                Err(e) => {
-                                    error!("Error while decompressing commitment of TX {}: {}", tx.hash, e);
-                                    continue;
-                                }
+                                Role::Receiver
                             };
 
-                            // Same for handle
-                            let handle = match handle.decompress() {
-                                Ok(h) => h,
-                                Err(e) => {
-                                    error!("Error while decompressing handle of TX {}: {}", tx.hash, e);
-                                    continue;
-                                }
+                            // Amount was decrypted before, if not present the ciphertext was invalid
+                            let Some(amount) = amounts.remove(&(tx_index, transfer_index)) else {
+                                continue;
                             };
 
                             let extra_data = if let Some(cipher) = transfer.extra_data.into_owned() {
@@ -338,12 +417,10 @@ impl NetworkHandler {
                                 None
                             };
 
-                            debug!("Decrypting amount from TX {}", tx.hash);
-                            let ciphertext = Ciphertext::new(commitment, handle);
-                            let amount = Arc::clone(&self.wallet).decrypt_ciphertext(ciphertext).await?;
-
                             let asset = transfer.asset.into_owned();
-                            assets_changed.insert(asset.clone());
+                            if asset_filter.is_tracked(&asset) {
+                                assets_changed.insert(asset.clone());
+                            }
 
                             if is_owner {
                                 let transfer = TransferOut::new(destination, asset, amount, extra_data);
@@ -386,7 +463,9 @@ impl NetworkHandler {
 
                         let mut deposits = IndexMap::new();
                         for (asset, deposit) in payload.deposits {
-                            assets_changed.insert(asset.clone());
+                            if asset_filter.is_tracked(&asset) {
+                                assets_changed.insert(asset.clone());
+                            }
 
                             match deposit {
                                 ContractDeposit::Public(amount) => {
@@ -416,6 +495,30 @@ impl NetworkHandler {
                     } else {
                         None
                     }
+                },
+                RPCTransactionType::ManageContract(payload) => {
+                    if is_owner {
+                        if self.has_tx_stored(&tx.hash).await? {
+                            debug!("Transaction manage contract {} was already stored, skipping it", tx.hash);
+                            continue 'main;
+                        }
+
+                        Some(EntryData::ManageContract { contract: payload.contract.clone(), action: payload.action, fee: tx.fee, nonce: tx.nonce })
+                    } else {
+                        None
+                    }
+                },
+                RPCTransactionType::ExecutionCredits(payload) => {
+                    if is_owner {
+                        if self.has_tx_stored(&tx.hash).await? {
+                            debug!("Transaction execution credits {} was already stored, skipping it", tx.hash);
+                            continue 'main;
+                        }
+
+                        Some(EntryData::ExecutionCredits { payload: payload.into_owned(), fee: tx.fee, nonce: tx.nonce })
+                    } else {
+                        None
+                    }
                 }
             };
 
@@ -508,6 +611,71 @@ impl NetworkHandler {
         }
     }
 
+    // Decrypt all the transfers amounts related to us in a block
+    // Incoming transfers of ignored assets are skipped
+    // Returns the amounts by transaction index and transfer index
+    async fn decrypt_block_amounts(&self, address: &Address, transactions: &[RPCTransaction<'_>], asset_filter: &AssetFilter) -> Result<HashMap<(usize, usize), u64>, Error> {
+        let mut keys = Vec::new();
+        let mut ciphertexts = Vec::new();
+        for (tx_index, tx) in transactions.iter().enumerate() {
+            let RPCTransactionType::Transfers(transfers) = &tx.data else {
+                continue;
+            };
+
+            let is_owner = *tx.source.get_public_key() == *address.get_public_key();
+            let mut checked = false;
+            for (transfer_index, transfer) in transfers.iter().enumerate() {
+                if !is_owner && (*transfer.destination.get_public_key() != *address.get_public_key() || !asset_filter.is_tracked(&transfer.asset)) {
+                    continue;
+                }
+
+                // Don't decrypt TXs already stored
+                if !checked {
+                    if self.has_tx_stored(&tx.hash).await? {
+                        break;
+                    }
+                    checked = true;
+                }
+
+                let handle = if is_owner {
+                    &transfer.sender_handle
+                } else {
+                    &transfer.receiver_handle
+                };
+
+                // Decompress commitment it if possible
+                let commitment = match transfer.commitment.decompress() {
+                    Ok(c) => c,
+                    Err(e) => {
+                        error!("Error while decompressing commitment of TX {}: {}", tx.hash, e);
+                        continue;
+                    }
+                };
+
+                // Same for handle
+                let handle = match handle.decompress() {
+                    Ok(h) => h,
+                    Err(e) => {
+                        error!("Error while decompressing handle of TX {}: {}", tx.hash, e);
+                        continue;
+                    }
+                };
+
+                keys.push((tx_index, transfer_index));
+                ciphertexts.push(Ciphertext::new(commitment, handle));
+            }
+        }
+
+        let mut amounts = HashMap::with_capacity(keys.len());
+        if !ciphertexts.is_empty() {
+            debug!("Decrypting {} amounts", ciphertexts.len());
+            let decrypted = self.wallet.decrypt_ciphertexts(ciphertexts).await?;
+            amounts.extend(keys.into_iter().zip(decrypted));
+        }
+
+        Ok(amounts)
+    }
+
     // Check if a transaction is stored in the wallet
     async fn has_tx_stored(&self, hash: &Hash) -> Result<bool, Error> {
         let storage = self.wallet.get_storage().read().await;
@@ -516,20 +684,35 @@ impl NetworkHandler {
 
     // Scan the chain using a specific balance asset, this helps us to get a list of version to only requests blocks where changes happened
     // When the block is requested, we don't limit the syncing to asset in parameter
-    async fn get_balance_and_transactions(&self, topoheight_processed: &mut HashSet<u64>, address: &Address, asset: &Hash, min_topoheight: u64, balances: bool, highest_nonce: &mut Option<u64>) -> Result<(), Error> {
-        // Retrieve the highest version
-        let (mut topoheight, mut version) = self.api.get_balance(address, asset).await.map(|res| (res.topoheight, res.version))?;
+    // If a rescan state is provided, progress is saved after each topoheight
+    // Returns false if the rescan got paused before reaching the minimum topoheight
+    async fn get_balance_and_transactions(&self, topoheight_processed: &mut HashSet<u64>, address: &Address, asset: &Hash, min_topoheight: u64, balances: bool, highest_nonce: &mut Option<u64>, mut rescan: Option<(&mut RescanState, usize)>) -> Result<bool, Error> {
+        // Determine if its the highest version of balance or not
+        // This is used to save the latest balance
+        let mut highest_version = true;
+
+        // Retrieve the highest version or the version from the rescan checkpoint
+        let checkpoint = rescan.as_ref()
+            .and_then(|(state, _)| state.current.as_ref())
+            .filter(|(current, _)| current == asset)
+            .map(|(_, topoheight)| *topoheight);
+        let (mut topoheight, mut version) = if let Some(topoheight) = checkpoint {
+            debug!("Resuming rescan from topoheight {} for asset {}", topoheight, asset);
+            // This topoheight was already processed
+            topoheight_processed.insert(topoheight);
+            highest_version = false;
+            (topoheight, self.api.get_balance_at_topoheight(address, asset, topoheight).await?)
+        } else {
+            self.api.get_balance(address, asset).await.map(|res| (res.topoheight, res.version))?
+        };
         debug!("Starting sync from topoheight {} for asset {}", topoheight, asset);
 
         // don't sync already synced blocks
         if min_topoheight >= topoheight {
             debug!("Reached minimum topoheight {}, topo: {}", min_topoheight, topoheight);
-            return Ok(())
+            return Ok(true)
         }
 
-        // Determine if its the highest version of balance or not
-        // This is used to save the latest balance
-        let mut highest_version = true;
         loop {
             let (mut balance, _, _, previous_topoheight) = version.consume();
             // add this topoheight in cache to not re-process it (blocks are independant of asset to have faster sync)
@@ -581,6 +764,23 @@ impl NetworkHandler {
                         })).await;
                     }
                 }
+
+                // Save the rescan progress
+                if let Some((state, total_assets)) = rescan.as_mut() {
+                    state.current = Some((asset.clone(), topoheight));
+                    {
+                        let mut storage = self.wallet.get_storage().write().await;
+                        storage.set_rescan_state(state)?;
+                    }
+
+                    let progress = self.build_rescan_progress(state, *total_assets);
+                    self.wallet.propagate_event(Event::RescanProgress(progress)).await;
+
+                    if self.is_rescan_paused() {
+                        info!("Rescan paused at topoheight {} for asset {}", topoheight, asset);
+                        return Ok(false)
+                    }
+                }
             }
 
             // Prepare a new iteration
@@ -602,7 +802,7 @@ impl NetworkHandler {
             highest_version = false;
         }
 
-        Ok(())
+        Ok(true)
     }
 
     // Locate the last topoheight valid for syncing, this support soft forks, DAG reorgs, etc...
@@ -732,6 +932,35 @@ impl NetworkHandler {
         Ok((daemon_topoheight, daemon_block_hash, maximum))
     }
 
+    // Start a wallet that never synced from the stable topoheight of the daemon
+    // Blocks below it can't be reorganized, so only the history above it is scanned
+    // while balances and nonce are fetched from the head state as usual
+    // Returns the topoheight from which the history is scanned
+    async fn sync_from_checkpoint(&self) -> Result<u64, Error> {
+        {
+            let storage = self.wallet.get_storage().read().await;
+            // A rescan is in charge of the whole history
+            if storage.get_rescan_state()?.is_some() {
+                return Ok(0)
+            }
+        }
+
+        let topoheight = self.api.get_stable_topoheight().await?;
+        let block_hash = self.api.get_block_at_topoheight(topoheight).await?.hash.into_owned();
+        info!("Syncing from stable checkpoint at topoheight {}, history below it is not scanned", topoheight);
+
+        let mut storage = self.wallet.get_storage().write().await;
+        storage.set_sync_checkpoint(topoheight)?;
+        storage.set_synced_topoheight(topoheight)?;
+        storage.set_top_block_hash(&block_hash)?;
+        // Used as the lowest valid point in case of reorg
+        if !storage.has_topoheight_in_changes(topoheight)? {
+            storage.add_topoheight_to_changes(topoheight, &block_hash)?;
+        }
+
+        Ok(topoheight)
+    }
+
     // Sync the latest version of our balances and nonces and determine if we should parse all blocks
     // If assets are provided, we'll only sync these assets
     // TODO: this may bug with Smart Contract integration as we could receive a new asset and not detect it
@@ -799,6 +1028,13 @@ impl NetworkHandler {
             self.api.get_account_assets(address).await?
         };
 
+        // Skip the assets ignored by the user
+        let assets: HashSet<Hash> = {
+            let storage = self.wallet.get_storage().read().await;
+            let asset_filter = storage.get_asset_filter();
+            assets.into_iter().filter(|asset| asset_filter.is_tracked(asset)).collect()
+        };
+
         trace!("assets: {}", assets.len());
 
         let mut balances: HashMap<&Hash, (CiphertextCache, u64)> = HashMap::new();
@@ -938,7 +1174,13 @@ impl NetworkHandler {
         } else {
             debug!("No event received, verify that we are on the right chain");
             // First, locate the last topoheight valid for syncing
-            (daemon_topoheight, daemon_block_hash, wallet_topoheight) = self.locate_sync_topoheight_and_clean().await?;
+            let (topoheight, block_hash, synced_topoheight) = self.locate_sync_topoheight_and_clean().await?;
+            (daemon_topoheight, daemon_block_hash) = (topoheight, block_hash);
+            wallet_topoheight = if synced_topoheight == 0 && self.wallet.get_checkpoint_sync() {
+                self.sync_from_checkpoint().await?
+            } else {
+                synced_topoheight
+            };
             debug!("Daemon topoheight: {}, wallet topoheight: {}", daemon_topoheight, wallet_topoheight);
 
             trace!("sync head state");
@@ -1067,19 +1309,38 @@ impl NetworkHandler {
 
     // Sync all new blocks until the current topoheight
     async fn sync_new_blocks(&self, address: &Address, current_topoheight: u64, balances: bool) -> Result<(), Error> {
-        let assets = {
+        let (assets, rescan_start) = {
             let storage = self.wallet.get_storage().read().await;
-            storage.get_assets().await?
+            let rescan_start = storage.get_rescan_state()?.map(|state| state.start_topoheight);
+            (storage.get_tracked_assets().await?, rescan_start)
         };
-        
-        debug!("Scanning history for each asset");
+
         // cache for all topoheight we already processed
         // this will prevent us to request more than one time the same topoheight
         let mut topoheight_processed = HashSet::new();
         let mut highest_nonce = None;
+
+        // A rescan is in progress, it is in charge of the history until the current topoheight
+        if let Some(start_topoheight) = rescan_start {
+            if !self.is_rescan_paused() {
+                self.rescan_history(address, &mut topoheight_processed, &mut highest_nonce).await?;
+            }
+
+            if current_topoheight <= start_topoheight {
+                return Ok(())
+            }
+        }
+
+        debug!("Scanning history for each asset");
         for asset in assets {
+            // Asset metadata may be stored without any balance on this account
+            if !self.wallet.get_storage().read().await.has_balance_for(&asset).await? {
+                debug!("No balance for asset {}, skipping it", asset);
+                continue;
+            }
+
             debug!("calling get balances and transactions {}", current_topoheight);
-            if let Err(e) = self.get_balance_and_transactions(&mut topoheight_processed, &address, &asset, current_topoheight, balances, &mut highest_nonce).await {
+            if let Err(e) = self.get_balance_and_transactions(&mut topoheight_processed, &address, &asset, current_topoheight, balances, &mut highest_nonce, None).await {
                 error!("Error while syncing balance for asset {}: {}", asset, e);
             }
         }
@@ -1088,4 +1349,55 @@ impl NetworkHandler {
 
         Ok(())
     }
+
+    // Continue the rescan from its checkpoint stored in wallet
+    // The checkpoint is deleted once all assets are rescanned
+    async fn rescan_history(&self, address: &Address, topoheight_processed: &mut HashSet<u64>, highest_nonce: &mut Option<u64>) -> Result<(), Error> {
+        let _lock = self.rescan_lock.lock().await;
+        let (assets, state) = {
+            let storage = self.wallet.get_storage().read().await;
+            (storage.get_tracked_assets().await?, storage.get_rescan_state()?)
+        };
+
+        let Some(mut state) = state else {
+            debug!("No rescan in progress");
+            return Ok(())
+        };
+
+        info!("Rescanning history until topoheight {}", state.start_topoheight);
+        let total_assets = assets.len();
+        for asset in assets {
+            if state.completed_assets.contains(&asset) {
+                continue;
+            }
+
+            let start_topoheight = state.start_topoheight;
+            match self.get_balance_and_transactions(topoheight_processed, address, &asset, start_topoheight, true, highest_nonce, Some((&mut state, total_assets))).await {
+                Ok(true) => {},
+                Ok(false) => return Ok(()),
+                Err(e) => error!("Error while rescanning balance for asset {}: {}", asset, e)
+            };
+
+            state.current = None;
+            state.completed_assets.insert(asset);
+            {
+                let mut storage = self.wallet.get_storage().write().await;
+                storage.set_rescan_state(&state)?;
+            }
+
+            info!("Rescan progress: {}/{} assets", state.completed_assets.len(), total_assets);
+            let progress = self.build_rescan_progress(&state, total_assets);
+            self.wallet.propagate_event(Event::RescanProgress(progress)).await;
+        }
+
+        {
+            let mut storage = self.wallet.get_storage().write().await;
+            storage.delete_rescan_state()?;
+        }
+
+        info!("Rescan completed until topoheight {}", state.start_topoheight);
+        self.wallet.propagate_event(Event::HistorySynced { topoheight: state.start_topoheight }).await;
+
+        Ok(())
+    }
 }
\ No newline at end of file
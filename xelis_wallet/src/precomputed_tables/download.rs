@@ -0,0 +1,129 @@
+use std::{
+    fs::{create_dir_all, remove_file, rename, File, OpenOptions},
+    io::{Read, Write},
+    path::Path
+};
+
+use anyhow::{bail, Context, Result};
+use log::{info, warn};
+use reqwest::{header::RANGE, StatusCode};
+use xelis_common::crypto::ecdlp;
+
+// Size of the buffer used to hash the downloaded file
+const HASH_BUFFER_SIZE: usize = 1024 * 1024;
+
+// Download the precomputed tables from the first mirror available
+// Each mirror must serve the file as `{mirror}/precomputed_tables_{l1}.bin`
+// A partial download is kept in a `.part` file and resumed on the next try
+// The file is only moved to `full_path` once its size and BLAKE3 checksum are verified
+pub async fn download_precomputed_tables(mirrors: &[String], full_path: &str, l1: usize, checksum: &str) -> Result<()> {
+    let checksum = checksum.to_lowercase();
+    let expected_size = ecdlp::ECDLPTables::get_required_sizes(l1).0 as u64;
+    let part_path = format!("{full_path}.part");
+    if let Some(parent) = Path::new(full_path).parent() {
+        create_dir_all(parent)?;
+    }
+
+    let client = reqwest::Client::new();
+    for mirror in mirrors {
+        let url = format!("{}/precomputed_tables_{l1}.bin", mirror.trim_end_matches('/'));
+        info!("Downloading precomputed tables from {}", url);
+
+        if let Err(e) = download_file(&client, &url, &part_path, expected_size).await {
+            warn!("Error while downloading precomputed tables from {}: {:#}", url, e);
+            continue;
+        }
+
+        let hash = hash_file(&part_path)?;
+        if hash != checksum {
+            warn!("Invalid checksum for precomputed tables from {}: expected {}, got {}", url, checksum, hash);
+            remove_file(&part_path).context("Error while deleting invalid precomputed tables")?;
+            continue;
+        }
+
+        rename(&part_path, full_path).context("Error while moving downloaded precomputed tables")?;
+        info!("Precomputed tables downloaded and verified at {}", full_path);
+        return Ok(())
+    }
+
+    bail!("No mirror was able to provide valid precomputed tables")
+}
+
+// Download the file, resuming from the partial file if any
+async fn download_file(client: &reqwest::Client, url: &str, part_path: &str, expected_size: u64) -> Result<()> {
+    let mut downloaded = if Path::new(part_path).exists() {
+        std::fs::metadata(part_path)?.len()
+    } else {
+        0
+    };
+
+    // Partial file is bigger than expected, start again
+    if downloaded > expected_size {
+        remove_file(part_path)?;
+        downloaded = 0;
+    }
+
+    if downloaded < expected_size {
+        let mut request = client.get(url);
+        if downloaded > 0 {
+            info!("Resuming download at {} bytes", downloaded);
+            request = request.header(RANGE, format!("bytes={downloaded}-"));
+        }
+
+        let mut response = request.send().await?
+            .error_for_status()?;
+
+        // Server doesn't support range requests, restart from zero
+        let append = response.status() == StatusCode::PARTIAL_CONTENT;
+        if !append {
+            downloaded = 0;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(part_path)?;
+
+        let mut last_report = 0;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk)?;
+            downloaded += chunk.len() as u64;
+            if downloaded > expected_size {
+                bail!("Received more data than expected");
+            }
+
+            // Report every 10%
+            let progress = downloaded * 10 / expected_size;
+            if progress > last_report {
+                last_report = progress;
+                info!("Downloaded {}%", progress * 10);
+            }
+        }
+
+        file.flush()?;
+    }
+
+    if downloaded != expected_size {
+        bail!("Incomplete download: {} bytes out of {}", downloaded, expected_size);
+    }
+
+    Ok(())
+}
+
+// Compute the BLAKE3 hash of a file in hex
+fn hash_file(path: &str) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finalize().to_hex().to_string())
+}
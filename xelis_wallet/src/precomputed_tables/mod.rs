@@ -1,4 +1,5 @@
 use std::sync::{Arc, RwLock};
+use anyhow::{bail, Result};
 use xelis_common::crypto::ecdlp;
 
 #[cfg(all(
@@ -29,6 +30,18 @@ mod native;
 )))]
 pub use native::*;
 
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    feature = "precomputed_tables_download"
+))]
+mod download;
+
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    feature = "precomputed_tables_download"
+))]
+pub use download::*;
+
 // ECDLP Tables L1 size
 pub const L1_LOW: usize = 13;
 
@@ -40,5 +53,56 @@ pub const L1_MEDIUM: usize = 18;
 pub const L1_FULL: usize = 26;
 
 
+// L1 sizes supported, from the biggest to the smallest
+pub const L1_SIZES: [usize; 3] = [L1_FULL, L1_MEDIUM, L1_LOW];
+
+// Select the biggest L1 size fitting in the available memory
+// We keep at least half of the memory available for the system
+pub fn select_l1_for_memory(available_memory: u64) -> usize {
+    for l1 in L1_SIZES {
+        let (size, _) = ecdlp::ECDLPTables::get_required_sizes(l1);
+        if size as u64 <= available_memory / 2 {
+            return l1;
+        }
+    }
+
+    L1_LOW
+}
+
+// Backend holding the precomputed tables
+// Native can use a memory-mapped file while WASM keeps them in memory
+pub trait PrecomputedTables: Send + Sync {
+    // View used to decode the points
+    fn view(&self) -> ecdlp::ECDLPTablesFileView<'_>;
+}
+
+// Fully loaded in memory
+impl PrecomputedTables for ecdlp::ECDLPTables {
+    fn view(&self) -> ecdlp::ECDLPTablesFileView<'_> {
+        ecdlp::ECDLPTables::view(self)
+    }
+}
+
 // Allows to be used in several wallets at the same time
-pub type PrecomputedTablesShared = Arc<RwLock<ecdlp::ECDLPTables>>;
+pub type PrecomputedTablesShared = Arc<RwLock<Box<dyn PrecomputedTables>>>;
+
+// Generate the precomputed tables in memory only
+// Nothing is read or written, the caller is responsible to keep them if needed
+// NOTE: generation is implemented by the curve25519-dalek fork which selects its SIMD
+// backend at runtime on supported CPUs, a GPU path would have to be added there
+pub fn generate_precomputed_tables<P: ecdlp::ProgressTableGenerationReportFunction>(l1: usize, progress_report: P) -> Result<PrecomputedTablesShared> {
+    let tables = ecdlp::ECDLPTables::generate_with_progress_report(l1, progress_report)?;
+    Ok(Arc::new(RwLock::new(Box::new(tables))))
+}
+
+// Load the precomputed tables from bytes provided by the caller
+// This allows environments without a filesystem to store them where they want
+pub fn precomputed_tables_from_bytes(l1: usize, bytes: &[u8]) -> Result<PrecomputedTablesShared> {
+    let expected = ecdlp::ECDLPTables::get_required_sizes(l1).0;
+    if bytes.len() != expected {
+        bail!("Invalid precomputed tables size: expected {} bytes, got {}", expected, bytes.len());
+    }
+
+    let tables = ecdlp::ECDLPTables::from_bytes(l1, bytes);
+    Ok(Arc::new(RwLock::new(Box::new(tables))))
+}
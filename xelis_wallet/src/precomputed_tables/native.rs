@@ -1,26 +1,61 @@
 use std::{
-    fs::create_dir_all,
+    fs::{create_dir_all, read_to_string, File},
     path::Path,
     sync::Arc
 };
 
 use anyhow::{bail, Result};
-use log::info;
-use xelis_common::crypto::ecdlp;
+use log::{error, info};
+use memmap2::Mmap;
+use xelis_common::{
+    crypto::ecdlp,
+    tokio::{spawn_task, task::spawn_blocking}
+};
 
 use super::*;
 
-// Check if the precomputed tables exists
-pub async fn has_precomputed_tables(path: Option<&str>, l1: usize) -> Result<bool> {
-    let path = path.unwrap_or_default();
-    let full_path = format!("{path}precomputed_tables_{l1}.bin");
+// Precomputed tables backed by a memory-mapped file
+// Pages are loaded by the OS on demand instead of
+// allocating the whole table in RAM
+pub struct MmapPrecomputedTables {
+    l1: usize,
+    mmap: Mmap
+}
 
-    Ok(Path::new(&full_path).exists())
+impl MmapPrecomputedTables {
+    // Map the precomputed tables file in memory
+    pub fn open(l1: usize, path: &str) -> Result<Self> {
+        let file = File::open(path)?;
+        let expected = ecdlp::ECDLPTables::get_required_sizes(l1).0;
+        let size = file.metadata()?.len() as usize;
+        if size != expected {
+            bail!("Invalid precomputed tables file size: expected {} bytes, got {}", expected, size);
+        }
+
+        // SAFETY: the file must not be modified while being mapped
+        // It is only written once during the generation
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Self {
+            l1,
+            mmap
+        })
+    }
 }
 
-// This will read from file if exists, or generate and store it in file
-// This must be call only one time, and can be cloned to be shared through differents wallets
-pub async fn read_or_generate_precomputed_tables<P: ecdlp::ProgressTableGenerationReportFunction>(path: Option<&str>, l1: usize, progress_report: P, store_on_disk: bool) -> Result<PrecomputedTablesShared> {
+impl PrecomputedTables for MmapPrecomputedTables {
+    fn view(&self) -> ecdlp::ECDLPTablesFileView<'_> {
+        ecdlp::ECDLPTablesFileView::from_bytes(self.l1, &self.mmap)
+    }
+}
+
+// Build the full path of the precomputed tables file
+pub fn get_precomputed_tables_path(path: Option<&str>, l1: usize) -> String {
+    let path = path.unwrap_or_default();
+    format!("{path}precomputed_tables_{l1}.bin")
+}
+
+// Verify the path and create the directory if needed
+fn prepare_precomputed_tables_path(path: Option<&str>) -> Result<()> {
     if let Some(p) = &path {
         if !p.ends_with('/') {
             bail!("Path must end with /");
@@ -32,23 +67,105 @@ pub async fn read_or_generate_precomputed_tables<P: ecdlp::ProgressTableGenerati
         }
     }
 
-    let path = path.unwrap_or_default();
-    let full_path = format!("{path}precomputed_tables_{l1}.bin");
+    Ok(())
+}
+
+// Read the memory available on the system in bytes
+// Only Linux is supported for now
+pub fn get_available_memory() -> Option<u64> {
+    let content = read_to_string("/proc/meminfo").ok()?;
+    content.lines()
+        .find(|line| line.starts_with("MemAvailable:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
 
-    let tables = if Path::new(&full_path).exists() {
+// Load the tables in memory from the file or generate them
+fn load_or_generate_tables<P: ecdlp::ProgressTableGenerationReportFunction>(full_path: &str, l1: usize, progress_report: P, store_on_disk: bool) -> Result<ecdlp::ECDLPTables> {
+    if Path::new(full_path).exists() {
         info!("Loading precomputed tables from {}", full_path);
-        ecdlp::ECDLPTables::load_from_file(l1, full_path.as_str())?
-    } else {
-        // File does not exists, generate and store it
+        return Ok(ecdlp::ECDLPTables::load_from_file(l1, full_path)?)
+    }
+
+    // File does not exists, generate and store it
+    info!("Generating precomputed tables");
+    let tables = ecdlp::ECDLPTables::generate_with_progress_report(l1, progress_report)?;
+    if store_on_disk {
+        info!("Precomputed tables generated, storing to {}", full_path);
+        tables.write_to_file(full_path)?;
+    }
+
+    Ok(tables)
+}
+
+// Map the tables file in memory, generate and store it if needed
+// Memory used for the generation is released before mapping it
+fn map_or_generate_tables<P: ecdlp::ProgressTableGenerationReportFunction>(full_path: &str, l1: usize, progress_report: P) -> Result<MmapPrecomputedTables> {
+    if !Path::new(full_path).exists() {
         info!("Generating precomputed tables");
         let tables = ecdlp::ECDLPTables::generate_with_progress_report(l1, progress_report)?;
-        if store_on_disk {
-            info!("Precomputed tables generated, storing to {}", full_path);
-            tables.write_to_file(full_path.as_str())?;
-        }
+        info!("Precomputed tables generated, storing to {}", full_path);
+        tables.write_to_file(full_path)?;
+    }
+
+    info!("Mapping precomputed tables from {}", full_path);
+    MmapPrecomputedTables::open(l1, full_path)
+}
+
+// Check if the precomputed tables exists
+pub async fn has_precomputed_tables(path: Option<&str>, l1: usize) -> Result<bool> {
+    let full_path = get_precomputed_tables_path(path, l1);
+
+    Ok(Path::new(&full_path).exists())
+}
+
+// This will read from file if exists, or generate and store it in file
+// This must be call only one time, and can be cloned to be shared through differents wallets
+pub async fn read_or_generate_precomputed_tables<P: ecdlp::ProgressTableGenerationReportFunction>(path: Option<&str>, l1: usize, progress_report: P, store_on_disk: bool) -> Result<PrecomputedTablesShared> {
+    prepare_precomputed_tables_path(path)?;
+    let full_path = get_precomputed_tables_path(path, l1);
+    let tables = load_or_generate_tables(&full_path, l1, progress_report, store_on_disk)?;
+
+    Ok(Arc::new(RwLock::new(Box::new(tables))))
+}
+
+// Same as read_or_generate_precomputed_tables but the file is memory-mapped
+// If the file doesn't exist, tables are generated and stored on disk first
+pub async fn read_or_generate_precomputed_tables_mmap<P: ecdlp::ProgressTableGenerationReportFunction>(path: Option<&str>, l1: usize, progress_report: P) -> Result<PrecomputedTablesShared> {
+    prepare_precomputed_tables_path(path)?;
+    let full_path = get_precomputed_tables_path(path, l1);
+    let tables = map_or_generate_tables(&full_path, l1, progress_report)?;
+
+    Ok(Arc::new(RwLock::new(Box::new(tables))))
+}
 
-        tables
-    };
+// Generate the tables with the requested L1 in background
+// and replace the shared ones once ready
+// All wallets using these shared tables will benefit from the upgrade
+pub fn upgrade_precomputed_tables_in_background<P: ecdlp::ProgressTableGenerationReportFunction + Send + 'static>(shared: PrecomputedTablesShared, path: Option<String>, l1: usize, progress_report: P, memory_mapped: bool) {
+    spawn_task("precomputed-tables-upgrade", async move {
+        info!("Upgrading precomputed tables to L1 {} in background", l1);
+        let res = spawn_blocking(move || -> Result<Box<dyn PrecomputedTables>> {
+            prepare_precomputed_tables_path(path.as_deref())?;
+            let full_path = get_precomputed_tables_path(path.as_deref(), l1);
+            Ok(if memory_mapped {
+                Box::new(map_or_generate_tables(&full_path, l1, progress_report)?)
+            } else {
+                Box::new(load_or_generate_tables(&full_path, l1, progress_report, true)?)
+            })
+        }).await;
 
-    Ok(Arc::new(RwLock::new(tables)))
-}
\ No newline at end of file
+        match res {
+            Ok(Ok(tables)) => match shared.write() {
+                Ok(mut lock) => {
+                    *lock = tables;
+                    info!("Precomputed tables upgraded to L1 {}", l1);
+                },
+                Err(e) => error!("Error while upgrading precomputed tables: {}", e)
+            },
+            Ok(Err(e)) => error!("Error while generating precomputed tables: {}", e),
+            Err(e) => error!("Error while running precomputed tables upgrade: {}", e)
+        }
+    });
+}
@@ -56,6 +56,11 @@ macro_rules! execute {
     };
 }
 
+// Available memory is not exposed in a reliable way to the browsers
+pub fn get_available_memory() -> Option<u64> {
+    None
+}
+
 // Check if the precomputed tables exists
 pub async fn has_precomputed_tables(_: Option<&str>, l1: usize) -> Result<bool> {
     let path = format!("precomputed_tables_{l1}.bin");
@@ -147,7 +152,7 @@ pub async fn read_or_generate_precomputed_tables<P: ecdlp::ProgressTableGenerati
         }
     };
 
-    Ok(Arc::new(RwLock::new(tables)))
+    Ok(Arc::new(RwLock::new(Box::new(tables))))
 }
 
 // Generate the tables and store them in a file if API is available
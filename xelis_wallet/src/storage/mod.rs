@@ -2,9 +2,15 @@ mod backend;
 mod types;
 
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
     num::NonZeroUsize
 };
+#[cfg(not(all(
+    target_arch = "wasm32",
+    target_vendor = "unknown",
+    target_os = "unknown"
+)))]
+use std::{fs, path::Path};
 use indexmap::IndexMap;
 use lru::LruCache;
 use xelis_common::{
@@ -13,8 +19,10 @@ use xelis_common::{
             Query,
             QueryResult
         },
+        wallet::AssetFilter,
         DataElement,
-        DataValue
+        DataValue,
+        PaymentId
     },
     asset::AssetData,
     config::XELIS_ASSET,
@@ -30,7 +38,10 @@ use xelis_common::{
         Serializer,
     },
     tokio::sync::Mutex,
-    transaction::TxVersion
+    transaction::{
+        builder::UnsignedTransaction,
+        TxVersion
+    }
 };
 use anyhow::{
     Context,
@@ -39,7 +50,7 @@ use anyhow::{
 };
 use crate::{
     cipher::Cipher,
-    config::SALT_SIZE,
+    config::{SALT_SIZE, STORAGE_VERSION},
     entry::{
         EntryData,
         TransactionEntry,
@@ -47,7 +58,7 @@ use crate::{
     },
     error::WalletError
 };
-use log::{trace, debug, error};
+use log::{trace, debug, error, info};
 
 use backend::{Db, Tree};
 
@@ -74,6 +85,21 @@ const LCRT: &[u8] = b"LCRT";
 const MULTISIG: &[u8] = b"MSIG";
 // TX version to determine which version of TX we need
 const TX_VERSION: &[u8] = b"TXV";
+// Flag set when the wallet got created from a view key
+const WATCH_ONLY: &[u8] = b"WTCH";
+// Rescan checkpoint to resume it
+const RESCAN_STATE: &[u8] = b"RSCN";
+// Assets tracked by the wallet
+const ASSET_FILTER: &[u8] = b"AFLT";
+// Schema version of the storage
+const STORAGE_VERSION_KEY: &[u8] = b"SVER";
+// Index of the sub-account, not set for the master account
+const SUB_ACCOUNT_INDEX: &[u8] = b"SACI";
+// Sub-accounts derived from the master account
+const SUB_ACCOUNTS: &[u8] = b"SACS";
+// Stable topoheight from which the wallet was restored
+// History below it was not scanned
+const SYNC_CHECKPOINT: &[u8] = b"SCKP";
 
 // Default cache size
 const DEFAULT_CACHE_SIZE: usize = 100;
@@ -97,6 +123,18 @@ pub struct EncryptedStorage {
     assets: Tree,
     // This tree is used to store all topoheight where a change in the wallet occured
     changes_topoheight: Tree,
+    // Multisig transactions waiting for the signatures of participants
+    pending_multisig: Tree,
+    // Saved contacts, key is the name
+    address_book: Tree,
+    // Notes and tags attached to transactions, key is the TX hash
+    transactions_labels: Tree,
+    // Recurring payments, key is the name
+    scheduled_payments: Tree,
+    // Permissions granted to XSWD applications
+    xswd_applications: Tree,
+    // Local tickers of the assets, key is the asset hash
+    assets_tickers: Tree,
     // The inner storage
     inner: Storage,
     // Caches
@@ -117,7 +155,9 @@ pub struct EncryptedStorage {
     // use a stable balance or not
     last_coinbase_reward_topoheight: Option<u64>,
     // Transaction version to use
-    tx_version: TxVersion
+    tx_version: TxVersion,
+    // Assets tracked during the sync
+    asset_filter: AssetFilter
 }
 
 impl EncryptedStorage {
@@ -129,6 +169,12 @@ impl EncryptedStorage {
             extra: inner.db.open_tree(&cipher.hash_key("extra"))?,
             assets: inner.db.open_tree(&cipher.hash_key("assets"))?,
             changes_topoheight: inner.db.open_tree(&cipher.hash_key("changes_topoheight"))?,
+            pending_multisig: inner.db.open_tree(&cipher.hash_key("pending_multisig"))?,
+            address_book: inner.db.open_tree(&cipher.hash_key("address_book"))?,
+            transactions_labels: inner.db.open_tree(&cipher.hash_key("transactions_labels"))?,
+            scheduled_payments: inner.db.open_tree(&cipher.hash_key("scheduled_payments"))?,
+            xswd_applications: inner.db.open_tree(&cipher.hash_key("xswd_applications"))?,
+            assets_tickers: inner.db.open_tree(&cipher.hash_key("assets_tickers"))?,
             cipher,
             inner,
             balances_cache: Mutex::new(LruCache::new(NonZeroUsize::new(DEFAULT_CACHE_SIZE).unwrap())),
@@ -137,7 +183,8 @@ impl EncryptedStorage {
             assets_cache: Mutex::new(LruCache::new(NonZeroUsize::new(DEFAULT_CACHE_SIZE).unwrap())),
             synced_topoheight: None,
             last_coinbase_reward_topoheight: None,
-            tx_version: TxVersion::V0
+            tx_version: TxVersion::V0,
+            asset_filter: AssetFilter::All
         };
 
         if storage.has_network()? {
@@ -145,8 +192,12 @@ impl EncryptedStorage {
             if storage_network != network {
                 return Err(anyhow!("Network mismatch for this wallet storage (stored: {})!", storage_network));
             }
+
+            storage.migrate()?;
         } else {
+            // New storage is already using the latest schema
             storage.set_network(&network)?;
+            storage.set_storage_version(STORAGE_VERSION)?;
         }
 
         // Load one-time the last coinbase reward topoheight
@@ -159,9 +210,49 @@ impl EncryptedStorage {
             storage.tx_version = storage.load_from_disk(&storage.extra, TX_VERSION)?;
         }
 
+        // Load one-time the asset filter
+        if storage.contains_data(&storage.extra, ASSET_FILTER)? {
+            storage.asset_filter = storage.load_from_disk(&storage.extra, ASSET_FILTER)?;
+        }
+
         Ok(storage)
     }
 
+    // Get the schema version of the storage
+    // Storages created before the versioning are at version 0
+    pub fn get_storage_version(&self) -> Result<u16> {
+        trace!("get storage version");
+        Ok(self.load_from_disk_optional(&self.extra, STORAGE_VERSION_KEY)?.unwrap_or(0))
+    }
+
+    fn set_storage_version(&mut self, version: u16) -> Result<()> {
+        trace!("set storage version to {}", version);
+        self.save_to_disk(&self.extra, STORAGE_VERSION_KEY, &version.to_bytes())
+    }
+
+    // Apply one by one the migrations to reach the current schema version
+    // Each version is saved once migrated so an interrupted migration is resumed
+    fn migrate(&mut self) -> Result<()> {
+        let mut version = self.get_storage_version()?;
+        if version > STORAGE_VERSION {
+            return Err(WalletError::UnsupportedStorageVersion(version, STORAGE_VERSION).into())
+        }
+
+        while version < STORAGE_VERSION {
+            info!("Migrating wallet storage from version {} to {}", version, version + 1);
+            match version {
+                // Version 1 is the first versioned schema, no data to migrate
+                0 => {},
+                _ => return Err(WalletError::UnsupportedStorageVersion(version, STORAGE_VERSION).into())
+            }
+
+            version += 1;
+            self.set_storage_version(version)?;
+        }
+
+        Ok(())
+    }
+
     // Flush on disk to make sure it is saved
     pub fn flush(&mut self) -> Result<()> {
         trace!("Flushing storage");
@@ -463,6 +554,186 @@ impl EncryptedStorage {
         self.contains_data(&self.extra, MULTISIG)
     }
 
+    // Set the rescan checkpoint
+    pub fn set_rescan_state(&mut self, state: &RescanState) -> Result<()> {
+        trace!("set rescan state");
+        self.save_to_disk(&self.extra, RESCAN_STATE, &state.to_bytes())?;
+        Ok(())
+    }
+
+    // Get the rescan checkpoint if a rescan is in progress
+    pub fn get_rescan_state(&self) -> Result<Option<RescanState>> {
+        trace!("get rescan state");
+        if !self.contains_data(&self.extra, RESCAN_STATE)? {
+            return Ok(None);
+        }
+
+        let state: RescanState = self.load_from_disk(&self.extra, RESCAN_STATE)?;
+        Ok(Some(state))
+    }
+
+    // Delete the rescan checkpoint once the rescan is done
+    pub fn delete_rescan_state(&mut self) -> Result<()> {
+        trace!("delete rescan state");
+        self.delete_from_disk(&self.extra, RESCAN_STATE)?;
+        Ok(())
+    }
+
+    // Save a multisig transaction waiting for signatures
+    // Key is the hash to sign by each participant
+    pub fn set_pending_multisig_transaction(&mut self, hash: &Hash, unsigned: &UnsignedTransaction) -> Result<()> {
+        trace!("set pending multisig transaction {}", hash);
+        self.save_to_disk_with_encrypted_key(&self.pending_multisig, hash.as_bytes(), &unsigned.to_bytes())
+    }
+
+    // Get a multisig transaction waiting for signatures
+    pub fn get_pending_multisig_transaction(&self, hash: &Hash) -> Result<UnsignedTransaction> {
+        trace!("get pending multisig transaction {}", hash);
+        self.load_from_disk_with_encrypted_key(&self.pending_multisig, hash.as_bytes())
+    }
+
+    // Check if a multisig transaction is waiting for signatures
+    pub fn has_pending_multisig_transaction(&self, hash: &Hash) -> Result<bool> {
+        trace!("has pending multisig transaction {}", hash);
+        self.contains_encrypted_data(&self.pending_multisig, hash.as_bytes())
+    }
+
+    // Delete a multisig transaction waiting for signatures
+    pub fn delete_pending_multisig_transaction(&mut self, hash: &Hash) -> Result<()> {
+        trace!("delete pending multisig transaction {}", hash);
+        self.delete_from_disk_with_encrypted_key(&self.pending_multisig, hash.as_bytes())
+    }
+
+    // Retrieve all multisig transactions waiting for signatures
+    pub fn get_pending_multisig_transactions(&self) -> Result<Vec<(Hash, UnsignedTransaction)>> {
+        trace!("get pending multisig transactions");
+        let mut transactions = Vec::new();
+        for res in self.pending_multisig.iter() {
+            let (key, value) = res?;
+            let hash = Hash::from_bytes(&self.cipher.decrypt_value(&key)?)?;
+            let unsigned = UnsignedTransaction::from_bytes(&self.cipher.decrypt_value(&value)?)?;
+            transactions.push((hash, unsigned));
+        }
+
+        Ok(transactions)
+    }
+
+    // Save an entry in the address book
+    // It will overwrite the entry with the same name
+    pub fn set_address_book_entry(&mut self, entry: &AddressBookEntry) -> Result<()> {
+        trace!("set address book entry {}", entry.name);
+        self.save_to_disk_with_encrypted_key(&self.address_book, entry.name.as_bytes(), &entry.to_bytes())
+    }
+
+    // Get an entry from the address book using its name
+    pub fn get_address_book_entry(&self, name: &str) -> Result<AddressBookEntry> {
+        trace!("get address book entry {}", name);
+        self.load_from_disk_with_encrypted_key(&self.address_book, name.as_bytes())
+    }
+
+    // Check if an entry exists in the address book
+    pub fn has_address_book_entry(&self, name: &str) -> Result<bool> {
+        trace!("has address book entry {}", name);
+        self.contains_encrypted_data(&self.address_book, name.as_bytes())
+    }
+
+    // Delete an entry from the address book
+    pub fn delete_address_book_entry(&mut self, name: &str) -> Result<()> {
+        trace!("delete address book entry {}", name);
+        self.delete_from_disk_with_encrypted_key(&self.address_book, name.as_bytes())
+    }
+
+    // Retrieve all entries from the address book sorted by name
+    pub fn get_address_book_entries(&self) -> Result<Vec<AddressBookEntry>> {
+        trace!("get address book entries");
+        let mut entries = Vec::new();
+        for res in self.address_book.iter() {
+            let (_, value) = res?;
+            let entry = AddressBookEntry::from_bytes(&self.cipher.decrypt_value(&value)?)?;
+            entries.push(entry);
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(entries)
+    }
+
+    // Save a scheduled payment
+    // It will overwrite the scheduled payment with the same name
+    pub fn set_scheduled_payment(&mut self, payment: &ScheduledPayment) -> Result<()> {
+        trace!("set scheduled payment {}", payment.name);
+        self.save_to_disk_with_encrypted_key(&self.scheduled_payments, payment.name.as_bytes(), &payment.to_bytes())
+    }
+
+    // Get a scheduled payment using its name
+    pub fn get_scheduled_payment(&self, name: &str) -> Result<ScheduledPayment> {
+        trace!("get scheduled payment {}", name);
+        self.load_from_disk_with_encrypted_key(&self.scheduled_payments, name.as_bytes())
+    }
+
+    // Check if a scheduled payment exists
+    pub fn has_scheduled_payment(&self, name: &str) -> Result<bool> {
+        trace!("has scheduled payment {}", name);
+        self.contains_encrypted_data(&self.scheduled_payments, name.as_bytes())
+    }
+
+    // Delete a scheduled payment
+    pub fn delete_scheduled_payment(&mut self, name: &str) -> Result<()> {
+        trace!("delete scheduled payment {}", name);
+        self.delete_from_disk_with_encrypted_key(&self.scheduled_payments, name.as_bytes())
+    }
+
+    // Retrieve all scheduled payments sorted by next execution
+    pub fn get_scheduled_payments(&self) -> Result<Vec<ScheduledPayment>> {
+        trace!("get scheduled payments");
+        let mut payments = Vec::new();
+        for res in self.scheduled_payments.iter() {
+            let (_, value) = res?;
+            let payment = ScheduledPayment::from_bytes(&self.cipher.decrypt_value(&value)?)?;
+            payments.push(payment);
+        }
+        payments.sort_by(|a, b| a.next_execution.cmp(&b.next_execution));
+
+        Ok(payments)
+    }
+
+    // Save the permissions granted to a XSWD application
+    pub fn set_xswd_application(&mut self, entry: &XSWDApplicationEntry) -> Result<()> {
+        trace!("set xswd application {}", entry.id);
+        self.save_to_disk_with_encrypted_key(&self.xswd_applications, entry.id.as_bytes(), &entry.to_bytes())
+    }
+
+    // Get the permissions granted to a XSWD application using its ID
+    pub fn get_xswd_application(&self, id: &str) -> Result<XSWDApplicationEntry> {
+        trace!("get xswd application {}", id);
+        self.load_from_disk_with_encrypted_key(&self.xswd_applications, id.as_bytes())
+    }
+
+    // Check if permissions were granted to a XSWD application
+    pub fn has_xswd_application(&self, id: &str) -> Result<bool> {
+        trace!("has xswd application {}", id);
+        self.contains_encrypted_data(&self.xswd_applications, id.as_bytes())
+    }
+
+    // Revoke the permissions granted to a XSWD application
+    pub fn delete_xswd_application(&mut self, id: &str) -> Result<()> {
+        trace!("delete xswd application {}", id);
+        self.delete_from_disk_with_encrypted_key(&self.xswd_applications, id.as_bytes())
+    }
+
+    // Retrieve all XSWD applications with granted permissions
+    pub fn get_xswd_applications(&self) -> Result<Vec<XSWDApplicationEntry>> {
+        trace!("get xswd applications");
+        let mut applications = Vec::new();
+        for res in self.xswd_applications.iter() {
+            let (_, value) = res?;
+            let entry = XSWDApplicationEntry::from_bytes(&self.cipher.decrypt_value(&value)?)?;
+            applications.push(entry);
+        }
+        applications.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(applications)
+    }
+
     // Set the TX Version
     pub async fn set_tx_version(&mut self, version: TxVersion) -> Result<()> {
         trace!("set tx version");
@@ -477,6 +748,20 @@ impl EncryptedStorage {
         Ok(self.tx_version)
     }
 
+    // Set the assets tracked by the wallet
+    pub fn set_asset_filter(&mut self, filter: AssetFilter) -> Result<()> {
+        trace!("set asset filter");
+        self.save_to_disk(&self.extra, ASSET_FILTER, &filter.to_bytes())?;
+        self.asset_filter = filter;
+        Ok(())
+    }
+
+    // Get the assets tracked by the wallet
+    pub fn get_asset_filter(&self) -> &AssetFilter {
+        trace!("get asset filter");
+        &self.asset_filter
+    }
+
     // this function is specific because we save the key in encrypted form (and not hashed as others)
     // returns all saved assets
     pub async fn get_assets(&self) -> Result<HashSet<Hash>> {
@@ -507,6 +792,14 @@ impl EncryptedStorage {
         Ok(assets)
     }
 
+    // Returns all saved assets that are not ignored by the asset filter
+    pub async fn get_tracked_assets(&self) -> Result<HashSet<Hash>> {
+        trace!("get tracked assets");
+        let mut assets = self.get_assets().await?;
+        assets.retain(|asset| self.asset_filter.is_tracked(asset));
+        Ok(assets)
+    }
+
     // Retrieve all assets with their data
     pub async fn get_assets_with_data(&self) -> Result<Vec<(Hash, AssetData)>> {
         trace!("get assets with decimals");
@@ -594,6 +887,28 @@ impl EncryptedStorage {
         Ok(())
     }
 
+    // Set the local ticker of an asset
+    pub fn set_asset_ticker(&mut self, asset: &Hash, ticker: String) -> Result<()> {
+        trace!("set asset ticker {}", asset);
+        self.save_to_disk(&self.assets_tickers, asset.as_bytes(), &ticker.to_bytes())
+    }
+
+    // Get the local ticker of an asset if set
+    pub fn get_asset_ticker(&self, asset: &Hash) -> Result<Option<String>> {
+        trace!("get asset ticker {}", asset);
+        if !self.contains_data(&self.assets_tickers, asset.as_bytes())? {
+            return Ok(None)
+        }
+
+        self.load_from_disk(&self.assets_tickers, asset.as_bytes()).map(Some)
+    }
+
+    // Delete the local ticker of an asset
+    pub fn delete_asset_ticker(&mut self, asset: &Hash) -> Result<()> {
+        trace!("delete asset ticker {}", asset);
+        self.delete_from_disk(&self.assets_tickers, asset.as_bytes())
+    }
+
     // Retrieve the plaintext balance for this asset
     pub async fn get_plaintext_balance_for(&self, asset: &Hash) -> Result<u64> {
         trace!("get plaintext balance for {}", asset);
@@ -752,7 +1067,7 @@ impl EncryptedStorage {
     // read whole disk and returns all transactions
     pub fn get_transactions(&self) -> Result<Vec<TransactionEntry>> {
         trace!("get transactions");
-        self.get_filtered_transactions(None, None, None, None, true, true, true, true, None)
+        self.get_filtered_transactions(None, None, None, None, true, true, true, true, None, None, None)
     }
 
     // Find the last outgoing transaction created
@@ -824,7 +1139,7 @@ impl EncryptedStorage {
     }
 
     // Filter when the data is deserialized to not load all transactions in memory
-    pub fn get_filtered_transactions(&self, address: Option<&PublicKey>, asset: Option<&Hash>, min_topoheight: Option<u64>, max_topoheight: Option<u64>, accept_incoming: bool, accept_outgoing: bool, accept_coinbase: bool, accept_burn: bool, query: Option<&Query>) -> Result<Vec<TransactionEntry>> {
+    pub fn get_filtered_transactions(&self, address: Option<&PublicKey>, asset: Option<&Hash>, min_topoheight: Option<u64>, max_topoheight: Option<u64>, accept_incoming: bool, accept_outgoing: bool, accept_coinbase: bool, accept_burn: bool, query: Option<&Query>, tag: Option<&str>, payment_id: Option<PaymentId>) -> Result<Vec<TransactionEntry>> {
         trace!("get filtered transactions");
         let mut transactions = Vec::new();
         for el in self.transactions.iter().values() {
@@ -836,6 +1151,15 @@ impl EncryptedStorage {
                 }
             }
 
+            // Filter by local tag
+            if let Some(tag) = tag {
+                let has_tag = self.get_transaction_label(entry.get_hash())?
+                    .is_some_and(|label| label.tags.contains(tag));
+                if !has_tag {
+                    continue;
+                }
+            }
+
             if let Some(topoheight) = max_topoheight {
                 if entry.get_topoheight() > topoheight {
                     continue;
@@ -865,6 +1189,9 @@ impl EncryptedStorage {
                         t.retain(|transfer| *transfer.get_asset() == *asset);
                     }
 
+                    // Hide the ignored assets
+                    t.retain(|transfer| self.asset_filter.is_tracked(transfer.get_asset()));
+
                     transfers = Some(t.iter_mut().map(|t| Transfer::In(t)).collect());
 
                 },
@@ -900,6 +1227,8 @@ impl EncryptedStorage {
                     }
                 },
                 EntryData::DeployContract { .. } if accept_outgoing => {},
+                EntryData::ManageContract { .. } if accept_outgoing => {},
+                EntryData::ExecutionCredits { .. } if accept_outgoing => {},
                 _ => continue,
             };
 
@@ -919,6 +1248,15 @@ impl EncryptedStorage {
                 }
             }
 
+            // Check if it has the requested payment ID
+            if let Some(payment_id) = payment_id {
+                if let Some(transfers) = transfers.as_mut() {
+                    transfers.retain(|transfer| transfer.get_payment_id() == Some(payment_id));
+                } else {
+                    continue;
+                }
+            }
+
             // Keep only transactions entries that have one transfer at least
             match transfers {
                 // Transfers which are not empty
@@ -937,6 +1275,23 @@ impl EncryptedStorage {
         Ok(transactions)
     }
 
+    // Set the label of a transaction, an empty label is deleted
+    // Labels are kept even if the transaction is deleted by a rescan
+    pub fn set_transaction_label(&mut self, hash: &Hash, label: &TransactionLabel) -> Result<()> {
+        trace!("set transaction label {}", hash);
+        if label.is_empty() {
+            return self.delete_from_disk(&self.transactions_labels, hash.as_bytes())
+        }
+
+        self.save_to_disk(&self.transactions_labels, hash.as_bytes(), &label.to_bytes())
+    }
+
+    // Get the label of a transaction if any
+    pub fn get_transaction_label(&self, hash: &Hash) -> Result<Option<TransactionLabel>> {
+        trace!("get transaction label {}", hash);
+        self.load_from_disk_optional(&self.transactions_labels, hash.as_bytes())
+    }
+
     // Delete a transaction saved in wallet using its hash
     pub fn delete_transaction(&mut self, hash: &Hash) -> Result<()> {
         trace!("delete transaction {}", hash);
@@ -951,6 +1306,15 @@ impl EncryptedStorage {
         Ok(())
     }
 
+    // Delete the balance of an asset
+    pub async fn delete_balance_for(&mut self, asset: &Hash) -> Result<()> {
+        trace!("delete balance for {}", asset);
+        self.delete_from_disk(&self.balances, asset.as_bytes())?;
+        self.unconfirmed_balances_cache.lock().await.remove(asset);
+        self.balances_cache.lock().await.pop(asset);
+        Ok(())
+    }
+
     // Delete all balances from this wallet
     pub async fn delete_balances(&mut self) -> Result<()> {
         trace!("delete balances");
@@ -1062,7 +1426,7 @@ impl EncryptedStorage {
     // Store the private key
     pub fn set_private_key(&mut self, private_key: &PrivateKey) -> Result<()> {
         trace!("set private key");
-        self.save_to_disk(&self.extra, PRIVATE_KEY, &private_key.to_bytes())
+        self.save_to_disk(&self.extra, PRIVATE_KEY, &private_key.to_zeroizing_bytes())
     }
 
     // Retrieve the keypair of this wallet
@@ -1071,6 +1435,66 @@ impl EncryptedStorage {
         self.load_from_disk(&self.extra, PRIVATE_KEY)
     }
 
+    // Mark this wallet as watch-only
+    pub fn set_watch_only(&mut self) -> Result<()> {
+        trace!("set watch only");
+        self.save_to_disk(&self.extra, WATCH_ONLY, &[1])
+    }
+
+    // Is this wallet opened from a view key
+    pub fn is_watch_only(&self) -> Result<bool> {
+        trace!("is watch only");
+        self.contains_data(&self.extra, WATCH_ONLY)
+    }
+
+    // Set the index of the sub-account derived from the master account
+    pub fn set_sub_account_index(&mut self, index: u32) -> Result<()> {
+        trace!("set sub account index {}", index);
+        self.save_to_disk(&self.extra, SUB_ACCOUNT_INDEX, &index.to_bytes())
+    }
+
+    // Get the index of the sub-account, 0 for the master account
+    pub fn get_sub_account_index(&self) -> Result<u32> {
+        trace!("get sub account index");
+        Ok(self.load_from_disk_optional(&self.extra, SUB_ACCOUNT_INDEX)?.unwrap_or(0))
+    }
+
+    // Register a sub-account derived from this account
+    pub fn add_sub_account(&mut self, index: u32) -> Result<()> {
+        trace!("add sub account {}", index);
+        let mut accounts = self.get_sub_accounts()?;
+        if accounts.insert(index) {
+            self.save_to_disk(&self.extra, SUB_ACCOUNTS, &accounts.to_bytes())?;
+        }
+        Ok(())
+    }
+
+    // Get the indexes of the sub-accounts derived from this account
+    pub fn get_sub_accounts(&self) -> Result<BTreeSet<u32>> {
+        trace!("get sub accounts");
+        Ok(self.load_from_disk_optional(&self.extra, SUB_ACCOUNTS)?.unwrap_or_default())
+    }
+
+    // Set the checkpoint topoheight from which the wallet was restored
+    pub fn set_sync_checkpoint(&mut self, topoheight: u64) -> Result<()> {
+        trace!("set sync checkpoint {}", topoheight);
+        self.save_to_disk(&self.extra, SYNC_CHECKPOINT, &topoheight.to_be_bytes())
+    }
+
+    // Get the checkpoint topoheight from which the wallet was restored
+    // None if the whole history was scanned
+    pub fn get_sync_checkpoint(&self) -> Result<Option<u64>> {
+        trace!("get sync checkpoint");
+        self.load_from_disk_optional(&self.extra, SYNC_CHECKPOINT)
+    }
+
+    // Delete the checkpoint once the history below it is scanned again
+    pub fn delete_sync_checkpoint(&mut self) -> Result<()> {
+        trace!("delete sync checkpoint");
+        self.delete_from_disk(&self.extra, SYNC_CHECKPOINT)?;
+        Ok(())
+    }
+
     // Set the topoheight until which the wallet is synchronized
     pub fn set_synced_topoheight(&mut self, topoheight: u64) -> Result<()> {
         trace!("set synced topoheight to {}", topoheight);
@@ -1189,6 +1613,33 @@ impl EncryptedStorage {
         Ok(())
     }
 
+    // Delete all changes below topoheight except the highest one
+    // which is kept to find the last valid topoheight in case of DAG reorg
+    // Returns the number of changes deleted
+    pub fn prune_changes_below_topoheight(&mut self, topoheight: u64) -> Result<usize> {
+        trace!("prune changes below topoheight {}", topoheight);
+        let mut changes = Vec::new();
+        for res in self.changes_topoheight.iter().keys() {
+            let key = res?;
+            let raw = self.cipher.decrypt_value(&key).context("Error while decrypting key from disk")?;
+            let topo = u64::from_bytes(&raw)?;
+            if topo < topoheight {
+                changes.push((topo, key));
+            }
+        }
+
+        // Keep the highest change below topoheight
+        changes.sort_by_key(|(topo, _)| *topo);
+        changes.pop();
+
+        for (topo, key) in changes.iter() {
+            trace!("pruning topoheight changes at {}", topo);
+            self.changes_topoheight.remove(key)?;
+        }
+
+        Ok(changes.len())
+    }
+
     // Retrieve topoheight changes 
     pub fn get_topoheight_changes<'a>(&'a self) -> impl Iterator<Item = Result<(u64, Hash)>> + 'a {
         trace!("get topoheight changes");
@@ -1226,6 +1677,49 @@ impl Storage {
         })
     }
 
+    // Rewrite the database files of a closed wallet to reclaim the space of deleted entries
+    // Previous files are kept in '<name>.backup' until the next compaction
+    // Returns the size on disk before and after the rewrite
+    #[cfg(not(all(
+        target_arch = "wasm32",
+        target_vendor = "unknown",
+        target_os = "unknown"
+    )))]
+    pub fn compact(name: &str) -> Result<(u64, u64)> {
+        let backup = format!("{}.backup", name);
+        if Path::new(&backup).exists() {
+            debug!("Deleting previous backup {}", backup);
+            fs::remove_dir_all(&backup).context("Error while deleting previous backup")?;
+        }
+
+        fs::rename(name, &backup).context("Error while moving wallet files to backup")?;
+        let res = Self::rewrite(&backup, name);
+        if let Err(e) = &res {
+            error!("Error while rewriting wallet files, restoring backup: {}", e);
+            if Path::new(name).exists() {
+                fs::remove_dir_all(name).context("Error while deleting rewritten files")?;
+            }
+            fs::rename(&backup, name).context("Error while restoring backup")?;
+        }
+
+        res
+    }
+
+    // Copy all the trees of a database into a new one
+    #[cfg(not(all(
+        target_arch = "wasm32",
+        target_vendor = "unknown",
+        target_os = "unknown"
+    )))]
+    fn rewrite(from: &str, to: &str) -> Result<(u64, u64)> {
+        let previous = backend::open(from)?;
+        let db = backend::open(to)?;
+        db.import(previous.export());
+        db.flush()?;
+
+        Ok((previous.size_on_disk()?, db.size_on_disk()?))
+    }
+
     // save the encrypted form of the master key
     // it can only be decrypted using the password-based key
     pub fn set_encrypted_master_key(&mut self, encrypted_key: &[u8]) -> Result<()> {
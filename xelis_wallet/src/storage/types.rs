@@ -1,15 +1,18 @@
+use indexmap::{IndexMap, IndexSet};
 use serde::{Deserialize, Serialize};
 use xelis_common::{
     account::CiphertextCache,
+    api::wallet::XSWDApplicationScope,
     block::TopoHeight,
-    crypto::Hash,
+    crypto::{Address, Hash},
     serializer::{
         Reader,
         ReaderError,
         Serializer,
         Writer
     },
-    transaction::{MultiSigPayload, Reference}
+    time::TimestampMillis,
+    transaction::{builder::TransactionTypeBuilder, MultiSigPayload, Reference}
 };
 
 
@@ -54,6 +57,17 @@ pub struct TxCache {
     // Last transaction hash created
     // This is used to determine if we should erase the last unconfirmed balance or not
     pub last_tx_hash_created: Option<Hash>,
+    // Last transaction created, kept to be able to replace it with a higher fee
+    pub pending_transaction: Option<PendingTransaction>,
+}
+
+// Transaction created by the wallet and not yet executed
+#[derive(Debug, Clone)]
+pub struct PendingTransaction {
+    pub hash: Hash,
+    pub nonce: u64,
+    pub fee: u64,
+    pub transaction_type: TransactionTypeBuilder
 }
 
 // A multisig state in the wallet DB
@@ -77,4 +91,238 @@ impl Serializer for MultiSig {
             topoheight
         })
     }
-}
\ No newline at end of file
+}
+
+// Checkpoint of a rescan stored in the wallet DB
+// It allows to resume the rescan after a pause or a restart
+#[derive(Debug, Clone)]
+pub struct RescanState {
+    // Topoheight until which the history is rescanned
+    pub start_topoheight: TopoHeight,
+    // Assets fully rescanned
+    pub completed_assets: IndexSet<Hash>,
+    // Asset being rescanned and the last topoheight processed for it
+    pub current: Option<(Hash, TopoHeight)>
+}
+
+impl RescanState {
+    pub fn new(start_topoheight: TopoHeight) -> Self {
+        Self {
+            start_topoheight,
+            completed_assets: IndexSet::new(),
+            current: None
+        }
+    }
+}
+
+impl Serializer for RescanState {
+    fn write(&self, writer: &mut Writer) {
+        self.start_topoheight.write(writer);
+        self.completed_assets.write(writer);
+        self.current.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let start_topoheight = TopoHeight::read(reader)?;
+        let completed_assets = IndexSet::read(reader)?;
+        let current = Option::read(reader)?;
+        Ok(Self {
+            start_topoheight,
+            completed_assets,
+            current
+        })
+    }
+}
+
+// Result of a storage compaction
+#[derive(Debug, Clone)]
+pub struct StorageCompaction {
+    // Topoheight changes pruned
+    pub pruned_changes: usize,
+    // Size on disk in bytes before the compaction
+    pub size_before: u64,
+    // Size on disk in bytes after the compaction
+    pub size_after: u64
+}
+
+// A saved contact in the address book
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressBookEntry {
+    // Unique name used to resolve the address
+    pub name: String,
+    pub address: Address,
+    // Asset to use by default when sending to this address
+    pub default_asset: Option<Hash>,
+    pub notes: Option<String>
+}
+
+impl Serializer for AddressBookEntry {
+    fn write(&self, writer: &mut Writer) {
+        self.name.write(writer);
+        self.address.write(writer);
+        self.default_asset.write(writer);
+        self.notes.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let name = String::read(reader)?;
+        let address = Address::read(reader)?;
+        let default_asset = Option::read(reader)?;
+        let notes = Option::read(reader)?;
+        Ok(Self {
+            name,
+            address,
+            default_asset,
+            notes
+        })
+    }
+}
+
+// Local label attached to a transaction of the history
+// It is never shared and only used for bookkeeping
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionLabel {
+    pub note: Option<String>,
+    // Categories of the transaction
+    pub tags: IndexSet<String>
+}
+
+impl TransactionLabel {
+    // No note and no tag set
+    pub fn is_empty(&self) -> bool {
+        self.note.is_none() && self.tags.is_empty()
+    }
+}
+
+impl Serializer for TransactionLabel {
+    fn write(&self, writer: &mut Writer) {
+        self.note.write(writer);
+        self.tags.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let note = Option::read(reader)?;
+        let tags = IndexSet::read(reader)?;
+        Ok(Self {
+            note,
+            tags
+        })
+    }
+}
+
+// Payment sent automatically at a fixed interval while the wallet is online
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledPayment {
+    // Unique name of the scheduled payment
+    pub name: String,
+    pub destination: Address,
+    pub asset: Hash,
+    // Amount in atomic units
+    pub amount: u64,
+    // Interval between two payments in seconds
+    pub interval: u64,
+    // Next time the payment is due
+    pub next_execution: TimestampMillis,
+    // Payments left to send, unlimited if not set
+    pub remaining: Option<u64>,
+    // Payments sent successfully
+    pub executions: u64,
+    // Last transaction sent for this payment
+    pub last_transaction: Option<Hash>,
+    // Consecutive failures since the last successful payment
+    pub failures: u32,
+    pub last_error: Option<String>
+}
+
+impl Serializer for ScheduledPayment {
+    fn write(&self, writer: &mut Writer) {
+        self.name.write(writer);
+        self.destination.write(writer);
+        self.asset.write(writer);
+        self.amount.write(writer);
+        self.interval.write(writer);
+        self.next_execution.write(writer);
+        self.remaining.write(writer);
+        self.executions.write(writer);
+        self.last_transaction.write(writer);
+        self.failures.write(writer);
+        self.last_error.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let name = String::read(reader)?;
+        let destination = Address::read(reader)?;
+        let asset = Hash::read(reader)?;
+        let amount = u64::read(reader)?;
+        let interval = u64::read(reader)?;
+        let next_execution = TimestampMillis::read(reader)?;
+        let remaining = Option::read(reader)?;
+        let executions = u64::read(reader)?;
+        let last_transaction = Option::read(reader)?;
+        let failures = u32::read(reader)?;
+        let last_error = Option::read(reader)?;
+        Ok(Self {
+            name,
+            destination,
+            asset,
+            amount,
+            interval,
+            next_execution,
+            remaining,
+            executions,
+            last_transaction,
+            failures,
+            last_error
+        })
+    }
+}
+
+// Permissions granted to a XSWD application with its daily spending
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XSWDApplicationEntry {
+    // Application ID in hexadecimal format
+    pub id: String,
+    pub name: String,
+    pub url: Option<String>,
+    pub scope: XSWDApplicationScope,
+    // Day (in days since UNIX epoch) of the spent amounts
+    pub day: u64,
+    // Amounts transferred per asset during the day
+    pub spent: IndexMap<Hash, u64>
+}
+
+impl Serializer for XSWDApplicationEntry {
+    fn write(&self, writer: &mut Writer) {
+        self.id.write(writer);
+        self.name.write(writer);
+        self.url.write(writer);
+        self.scope.methods.write(writer);
+        self.scope.assets.write(writer);
+        self.scope.daily_limits.write(writer);
+        self.day.write(writer);
+        self.spent.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, ReaderError> {
+        let id = String::read(reader)?;
+        let name = String::read(reader)?;
+        let url = Option::read(reader)?;
+        let methods = IndexSet::read(reader)?;
+        let assets = IndexSet::read(reader)?;
+        let daily_limits = IndexMap::read(reader)?;
+        let day = u64::read(reader)?;
+        let spent = IndexMap::read(reader)?;
+        Ok(Self {
+            id,
+            name,
+            url,
+            scope: XSWDApplicationScope {
+                methods,
+                assets,
+                daily_limits
+            },
+            day,
+            spent
+        })
+    }
+}
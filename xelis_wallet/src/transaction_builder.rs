@@ -5,7 +5,7 @@ use xelis_common::{
     crypto::{elgamal::Ciphertext, Hash, Hashable, PublicKey},
     transaction::{builder::{AccountState, FeeHelper}, Reference, Transaction}
 };
-use crate::{error::WalletError, storage::{Balance, EncryptedStorage, TxCache}};
+use crate::{error::WalletError, storage::{Balance, EncryptedStorage, PendingTransaction, TxCache}};
 
 // State used to estimate fees for a transaction
 // Because fees can be higher if a destination account is not registered
@@ -54,6 +54,8 @@ pub struct TransactionBuilderState {
     nonce: u64,
     // The hash of the transaction that has been built
     tx_hash_built: Option<Hash>,
+    // The transaction that has been built with its type, used to replace it later
+    pending_transaction: Option<PendingTransaction>,
     // The stable topoheight detected during the TX building
     // This is used to update the last coinbase reward topoheight
     stable_topoheight: Option<u64>,
@@ -70,6 +72,7 @@ impl TransactionBuilderState {
             reference,
             nonce,
             tx_hash_built: None,
+            pending_transaction: None,
             stable_topoheight: None,
         }
     }
@@ -101,6 +104,10 @@ impl TransactionBuilderState {
         self.balances.insert(asset, balance);
     }
 
+    pub fn get_balances(&self) -> &HashMap<Hash, Balance> {
+        &self.balances
+    }
+
     pub fn set_registered_keys(&mut self, registered_keys: HashSet<PublicKey>) {
         self.inner.registered_keys = registered_keys;
     }
@@ -114,6 +121,11 @@ impl TransactionBuilderState {
         self.tx_hash_built = Some(tx_hash);
     }
 
+    // Keep the transaction built to be able to replace it with a higher fee
+    pub fn set_pending_transaction(&mut self, pending_transaction: PendingTransaction) {
+        self.pending_transaction = Some(pending_transaction);
+    }
+
     // Set the stable topoheight detected during the TX building
     pub fn set_stable_topoheight(&mut self, stable_topoheight: u64) {
         self.stable_topoheight = Some(stable_topoheight);
@@ -132,6 +144,7 @@ impl TransactionBuilderState {
             reference: self.reference.clone(),
             nonce: self.nonce,
             last_tx_hash_created: self.tx_hash_built.take(),
+            pending_transaction: self.pending_transaction.take(),
         });
 
         // Lets verify if the last coinbase reward topoheight is still valid
@@ -1,33 +1,51 @@
 use std::{
     io::Write,
-    sync::{atomic::{AtomicBool, Ordering},
-    Arc}
+    sync::{atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    Arc,
+    RwLock as StdRwLock},
+    time::Duration
 };
 use anyhow::{Error, Context};
 use chrono::TimeZone;
 use serde::Serialize;
+use zeroize::Zeroizing;
 use xelis_common::{
-    tokio::sync::{
-        broadcast::{
-            Sender as BroadcastSender,
-            Receiver as BroadcastReceiver
+    tokio::{
+        sync::{
+            broadcast::{
+                Sender as BroadcastSender,
+                Receiver as BroadcastReceiver
+            },
+            Mutex,
+            RwLock,
+            broadcast,
         },
-        Mutex,
-        RwLock,
-        broadcast,
+        spawn_task,
+        task::JoinHandle,
+        time::sleep
     },
     api::{
         wallet::{
+            AssetFilter,
             BalanceChanged,
+            HistoryExportFormat,
+            MultiSigSigningStatus,
             NotifyEvent,
+            PreparedTransaction,
+            RescanProgress,
+            ScheduledPaymentExecuted,
+            ScheduledPaymentFailed,
             TransactionEntry
         },
-        DataElement
+        DataElement,
+        PaymentId
     },
-    asset::RPCAssetData,
+    asset::{AssetData, RPCAssetData},
+    config::XELIS_ASSET,
     crypto::{
-        elgamal::Ciphertext,
+        elgamal::{AdaptorPoint, AdaptorSignature, Ciphertext, PublicKey as DecompressedPublicKey},
         Address,
+        Hash,
         Hashable,
         KeyPair,
         PublicKey,
@@ -35,36 +53,62 @@ use xelis_common::{
         Signature
     },
     network::Network,
+    payment_uri::PaymentRequest,
     transaction::{
         builder::{
+            AccountState,
             FeeBuilder,
             TransactionBuilder,
             TransactionTypeBuilder,
+            TransferBuilder,
             UnsignedTransaction
         },
         TxVersion,
         extra_data::{UnknownExtraDataFormat, PlaintextExtraData},
+        multisig::{MultiSig, SignatureId},
+        MultiSigPayload,
         Reference,
         Role,
         Transaction
     },
     serializer::Serializer,
-    utils::{format_xelis, format_coin}
+    time::{get_current_time_in_millis, TimestampMillis},
+    utils::{format_xelis, format_coin, from_coin}
 };
 use crate::{
     cipher::Cipher,
     config::{
+        AUTO_LOCK_CHECK_INTERVAL,
+        MAX_ADDRESS_BOOK_NAME_SIZE,
+        MAX_ADDRESS_BOOK_NOTES_SIZE,
+        MAX_ASSET_TICKER_SIZE,
+        MAX_TRANSACTION_NOTE_SIZE,
+        MAX_TRANSACTION_TAG_SIZE,
+        MAX_TRANSACTION_TAGS,
+        MAX_SCHEDULED_PAYMENT_NAME_SIZE,
+        MAX_UNLOCK_FAILURE_DELAY,
+        MIN_SCHEDULED_PAYMENT_INTERVAL,
         PASSWORD_ALGORITHM,
         PASSWORD_HASH_SIZE,
-        SALT_SIZE
+        PRUNE_CHANGES_SAFETY_LIMIT,
+        SALT_SIZE,
+        UNLOCK_FAILURE_DELAY,
+        XELIS_TICKER
     },
     entry::{EntryData, TransactionEntry as InnerTransactionEntry},
     error::WalletError,
+    export::{build_history_export, write_history_export, PriceSource},
     mnemonics,
-    precomputed_tables::PrecomputedTablesShared,
+    precomputed_tables::{PrecomputedTables, PrecomputedTablesShared},
     storage::{
+        AddressBookEntry,
         EncryptedStorage,
-        Storage
+        PendingTransaction,
+        ScheduledPayment,
+        Storage,
+        StorageCompaction,
+        TransactionLabel,
+        XSWDApplicationEntry
     },
     transaction_builder::{
         EstimateFeesState,
@@ -73,16 +117,31 @@ use crate::{
 };
 #[cfg(feature = "network_handler")]
 use {
-    log::warn,
+    std::borrow::Cow,
+    indexmap::IndexSet,
+    log::{info, warn},
     crate::{
+        config::{MAX_DAEMON_TOPOHEIGHT_LAG, SCHEDULED_PAYMENT_RETRY_DELAY, SCHEDULED_PAYMENT_TAG},
+        daemon_failover::DaemonFailover,
         network_handler::{
             NetworkHandler,
             SharedNetworkHandler
         },
         daemon_api::DaemonAPI,
-        storage::Balance,
+        storage::{Balance, RescanState},
+    },
+    xelis_common::{
+        api::{
+            daemon::{VerifyPaymentProofResult, VerifyReserveProofResult},
+            wallet::{AuditProofResult, DaemonEndpointStatus}
+        },
+        crypto::{
+            proofs::{AuditProof, PaymentProof, ReserveProof, MAX_RESERVE_CHALLENGE_SIZE},
+            HumanReadableProof
+        },
+        transaction::TransactionType,
+        utils::calculate_replacement_min_fee
     },
-    xelis_common::config::XELIS_ASSET,
 };
 use rand::{rngs::OsRng, RngCore};
 use log::{
@@ -95,6 +154,7 @@ use log::{
 use {
     serde_json::{json, Value},
     async_trait::async_trait,
+    indexmap::IndexMap,
     crate::api::{
         XSWDNodeMethodHandler,
         register_rpc_methods,
@@ -123,7 +183,8 @@ use {
             },
             oneshot,
         },
-        crypto::elgamal::PublicKey as DecompressedPublicKey
+        api::wallet::XSWDApplicationScope,
+        time::get_current_time_in_seconds
     }
 };
 
@@ -137,7 +198,15 @@ use xelis_common::tokio::task::spawn_blocking;
 // Recover option for wallet creation
 pub enum RecoverOption<'a> {
     Seed(&'a str),
-    PrivateKey(&'a str)
+    PrivateKey(&'a str),
+    // Create a watch-only wallet
+    ViewKey(&'a str),
+    // BIP39 mnemonic exported from a XELIS wallet
+    #[cfg(feature = "bip39")]
+    Bip39(&'a str),
+    // BIP39 mnemonic from another wallet and its passphrase
+    #[cfg(feature = "bip39")]
+    Bip39WithPassphrase(&'a str, &'a str)
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -170,7 +239,17 @@ pub enum Event {
     // Wallet is now in online mode
     Online,
     // Wallet is now in offline mode
-    Offline
+    Offline,
+    // When a rescan made progress or got paused
+    RescanProgress(RescanProgress),
+    // When a scheduled payment has been sent
+    ScheduledPaymentExecuted(ScheduledPaymentExecuted),
+    // When a scheduled payment couldn't be sent
+    ScheduledPaymentFailed(ScheduledPaymentFailed),
+    // Wallet got locked, its keypair is wiped from memory
+    Locked,
+    // Wallet got unlocked using its password
+    Unlocked
 }
 
 impl Event {
@@ -183,7 +262,12 @@ impl Event {
             Event::Rescan { .. } => NotifyEvent::Rescan,
             Event::HistorySynced { .. } => NotifyEvent::HistorySynced,
             Event::Online => NotifyEvent::Online,
-            Event::Offline => NotifyEvent::Offline
+            Event::Offline => NotifyEvent::Offline,
+            Event::RescanProgress(_) => NotifyEvent::RescanProgress,
+            Event::ScheduledPaymentExecuted(_) => NotifyEvent::ScheduledPaymentExecuted,
+            Event::ScheduledPaymentFailed(_) => NotifyEvent::ScheduledPaymentFailed,
+            Event::Locked => NotifyEvent::Locked,
+            Event::Unlocked => NotifyEvent::Unlocked
         }
     }
 
@@ -198,6 +282,9 @@ pub struct Wallet {
     // network handler for online mode to keep wallet synced
     #[cfg(feature = "network_handler")]
     network_handler: Mutex<Option<SharedNetworkHandler>>,
+    // Health checks of the daemons when several are configured
+    #[cfg(feature = "network_handler")]
+    daemon_failover: Mutex<Option<Arc<DaemonFailover>>>,
     // network on which we are connected
     network: Network,
     // RPC Server
@@ -211,17 +298,38 @@ pub struct Wallet {
     // If the wallet should scan also blocks and transactions history
     // Set to true by default
     history_scan: AtomicBool,
+    // If a wallet never synced should start from the latest stable topoheight
+    // instead of scanning its whole history
+    checkpoint_sync: AtomicBool,
     // flag to prioritize the usage of stable balance version when its online
     force_stable_balance: AtomicBool,
+    // Watch-only wallet opened from a view key
+    // It can decrypt balances and scan history but can't build nor sign anything
+    watch_only: bool,
+    // Source of fiat prices used when exporting the history
+    price_source: Mutex<Option<Arc<dyn PriceSource>>>,
+    // Inactivity in seconds before locking the wallet, 0 to disable it
+    auto_lock_timeout: AtomicU64,
+    // Timestamp in milliseconds of the last use of the keypair
+    last_activity: AtomicU64,
+    // tokio task locking the wallet after inactivity
+    auto_lock_task: Mutex<Option<JoinHandle<()>>>,
+    // Failed unlock attempts since the last successful one
+    unlock_failures: AtomicU32,
+    // Timestamp in milliseconds before which an unlock attempt is refused
+    next_unlock_attempt: AtomicU64
 }
 
 struct InnerAccount {
     // Precomputed tables byte array
     precomputed_tables: PrecomputedTablesShared,
     // Private & Public key linked for this wallet
-    keypair: KeyPair,
+    // None while the wallet is locked
+    keypair: StdRwLock<Option<Arc<KeyPair>>>,
     // Compressed public key
     public_key: PublicKey,
+    // Decompressed public key, available even if the wallet is locked
+    decompressed_public_key: DecompressedPublicKey
 }
 
 impl InnerAccount {
@@ -229,34 +337,73 @@ impl InnerAccount {
         Arc::new(Self {
             precomputed_tables,
             public_key: keypair.get_public_key().compress(),
-            keypair,
+            decompressed_public_key: keypair.get_public_key().clone(),
+            keypair: StdRwLock::new(Some(Arc::new(keypair))),
         })
     }
 
+    // Get the keypair, it is dropped from memory once unused if the wallet got locked
+    fn get_keypair(&self) -> Result<Arc<KeyPair>, WalletError> {
+        let lock = self.keypair.read()
+            .map_err(|_| WalletError::PoisonError)?;
+        lock.clone().ok_or(WalletError::WalletLocked)
+    }
+
+    fn set_keypair(&self, keypair: Option<KeyPair>) -> Result<(), WalletError> {
+        let mut lock = self.keypair.write()
+            .map_err(|_| WalletError::PoisonError)?;
+        *lock = keypair.map(Arc::new);
+        Ok(())
+    }
+
+    fn is_locked(&self) -> bool {
+        self.keypair.read()
+            .map(|lock| lock.is_none())
+            .unwrap_or(true)
+    }
+
     pub fn decrypt_ciphertext(&self, ciphertext: &Ciphertext) -> Result<u64, WalletError> {
         trace!("decrypt ciphertext");
+        let keypair = self.get_keypair()?;
         let lock = self.precomputed_tables.read()
             .map_err(|_| WalletError::PoisonError)?;
         let view = lock.view();
-        self.keypair.get_private_key()
+        keypair.get_private_key()
             .decrypt(&view, &ciphertext)
             .ok_or(WalletError::CiphertextDecode)
     }
+
+    // Decrypt several ciphertexts at once using the batch ECDLP decoding
+    pub fn decrypt_ciphertexts(&self, ciphertexts: &[Ciphertext], threads: usize) -> Result<Vec<u64>, WalletError> {
+        trace!("decrypt ciphertexts batch");
+        let keypair = self.get_keypair()?;
+        let lock = self.precomputed_tables.read()
+            .map_err(|_| WalletError::PoisonError)?;
+        let view = lock.view();
+        keypair.get_private_key()
+            .decrypt_batch(&view, ciphertexts, threads)
+            .into_iter()
+            .map(|amount| amount.ok_or(WalletError::CiphertextDecode))
+            .collect()
+    }
 }
 
-pub fn hash_password(password: &str, salt: &[u8]) -> Result<[u8; PASSWORD_HASH_SIZE], WalletError> {
-    let mut output = [0; PASSWORD_HASH_SIZE];
-    PASSWORD_ALGORITHM.hash_password_into(password.as_bytes(), salt, &mut output).map_err(|e| WalletError::AlgorithmHashingError(e.to_string()))?;
+// Hashed password is wiped from memory once dropped
+pub fn hash_password(password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; PASSWORD_HASH_SIZE]>, WalletError> {
+    let mut output = Zeroizing::new([0; PASSWORD_HASH_SIZE]);
+    PASSWORD_ALGORITHM.hash_password_into(password.as_bytes(), salt, output.as_mut_slice()).map_err(|e| WalletError::AlgorithmHashingError(e.to_string()))?;
     Ok(output)
 }
 
 impl Wallet {
     // Create a new wallet with the specificed storage, keypair and its network
-    fn new(storage: EncryptedStorage, keypair: KeyPair, network: Network, precomputed_tables: PrecomputedTablesShared) -> Arc<Self> {
+    fn new(storage: EncryptedStorage, keypair: KeyPair, network: Network, precomputed_tables: PrecomputedTablesShared, watch_only: bool) -> Arc<Self> {
         let zelf = Self {
             storage: RwLock::new(storage),
             #[cfg(feature = "network_handler")]
             network_handler: Mutex::new(None),
+            #[cfg(feature = "network_handler")]
+            daemon_failover: Mutex::new(None),
             network,
             #[cfg(feature = "api_server")]
             api_server: Mutex::new(None),
@@ -264,7 +411,15 @@ impl Wallet {
             xswd_channel: RwLock::new(None),
             event_broadcaster: Mutex::new(None),
             history_scan: AtomicBool::new(true),
+            checkpoint_sync: AtomicBool::new(false),
             force_stable_balance: AtomicBool::new(false),
+            watch_only,
+            price_source: Mutex::new(None),
+            auto_lock_timeout: AtomicU64::new(0),
+            last_activity: AtomicU64::new(get_current_time_in_millis()),
+            auto_lock_task: Mutex::new(None),
+            unlock_failures: AtomicU32::new(0),
+            next_unlock_attempt: AtomicU64::new(0),
             inner: InnerAccount::new(precomputed_tables, keypair)
         };
 
@@ -277,6 +432,8 @@ impl Wallet {
             return Err(WalletError::EmptyName.into())
         }
 
+        let watch_only = matches!(seed, Some(RecoverOption::ViewKey(_)));
+
         // generate random keypair or recover it from seed
         let keypair = if let Some(seed) = seed {
             debug!("Retrieving keypair from seed...");
@@ -284,9 +441,20 @@ impl Wallet {
                 RecoverOption::PrivateKey(hex) => {
                     PrivateKey::from_hex(hex).context("Invalid private key provided")?
                 },
+                RecoverOption::ViewKey(hex) => {
+                    PrivateKey::from_hex(hex).context("Invalid view key provided")?
+                },
                 RecoverOption::Seed(seed) => {
                     let words: Vec<&str> = seed.trim().split_whitespace().collect();
                     mnemonics::words_to_key(&words)?
+                },
+                #[cfg(feature = "bip39")]
+                RecoverOption::Bip39(mnemonic) => {
+                    mnemonics::bip39::mnemonic_to_key(mnemonic.trim())?
+                },
+                #[cfg(feature = "bip39")]
+                RecoverOption::Bip39WithPassphrase(mnemonic, passphrase) => {
+                    mnemonics::bip39::mnemonic_seed_to_key(mnemonic.trim(), passphrase)?
                 }
             };
             KeyPair::from_private_key(key)
@@ -295,6 +463,12 @@ impl Wallet {
             KeyPair::new()
         };
 
+        let storage = Self::create_storage(name, password, &keypair, network, watch_only)?;
+        Ok(Self::new(storage, keypair, network, precomputed_tables, watch_only))
+    }
+
+    // Create the encrypted storage of a new wallet and store its private key
+    fn create_storage(name: &str, password: &str, keypair: &KeyPair, network: Network, watch_only: bool) -> Result<EncryptedStorage, Error> {
         // generate random salt for hashed password
         let mut salt: [u8; SALT_SIZE] = [0; SALT_SIZE];
         OsRng.fill_bytes(&mut salt);
@@ -307,16 +481,16 @@ impl Wallet {
         let mut inner = Storage::new(name)?;
 
         // generate the Cipher
-        let cipher = Cipher::new(&hashed_password, None)?;
+        let cipher = Cipher::new(hashed_password.as_slice(), None)?;
 
         // save the salt used for password
         debug!("Save password salt in public storage");
         inner.set_password_salt(&salt)?;
 
         // generate the master key which is used for storage and then save it in encrypted form
-        let mut master_key: [u8; 32] = [0; 32];
-        OsRng.fill_bytes(&mut master_key);
-        let encrypted_master_key = cipher.encrypt_value(&master_key)?;
+        let mut master_key = Zeroizing::new([0u8; 32]);
+        OsRng.fill_bytes(master_key.as_mut_slice());
+        let encrypted_master_key = cipher.encrypt_value(master_key.as_slice())?;
         debug!("Save encrypted master key in public storage");
         inner.set_encrypted_master_key(&encrypted_master_key)?;
         
@@ -327,19 +501,112 @@ impl Wallet {
         inner.set_encrypted_storage_salt(&encrypted_storage_salt)?;
 
         debug!("Creating encrypted storage");
-        let mut storage = EncryptedStorage::new(inner, &master_key, storage_salt, network)?;
+        let mut storage = EncryptedStorage::new(inner, master_key.as_slice(), storage_salt, network)?;
 
         // Store the private key
         storage.set_private_key(&keypair.get_private_key())?;
 
+        if watch_only {
+            debug!("Marking wallet as watch-only");
+            storage.set_watch_only()?;
+        }
+
         // Flush the storage to be sure its written on disk
         storage.flush()?;
 
-        Ok(Self::new(storage, keypair, network, precomputed_tables))
+        Ok(storage)
+    }
+
+    // Path of the storage of a sub-account derived from the wallet at this path
+    // Index 0 is the master account itself
+    pub fn get_sub_account_path(name: &str, index: u32) -> String {
+        if index == 0 {
+            return name.to_owned()
+        }
+
+        format!("{}.{}", name, index)
+    }
+
+    // Open a sub-account derived from the wallet at this path
+    // The sub-account is created on first use and is protected by the same password
+    // Each sub-account has its own storage, address, balances and nonce
+    pub fn open_sub_account(name: &str, password: &str, index: u32, network: Network, precomputed_tables: PrecomputedTablesShared) -> Result<Arc<Self>, Error> {
+        if index == 0 {
+            return Self::open(name, password, network, precomputed_tables)
+        }
+
+        let mut master = Self::open_storage(name, password, network)?;
+        if master.get_sub_account_index()? != 0 {
+            return Err(WalletError::NotMasterAccount.into())
+        }
+
+        if master.is_watch_only()? {
+            return Err(WalletError::WatchOnlyWallet.into())
+        }
+
+        let path = Self::get_sub_account_path(name, index);
+        if master.get_sub_accounts()?.contains(&index) {
+            debug!("Opening sub-account {} of {}", index, name);
+            drop(master);
+            return Self::open(&path, password, network, precomputed_tables)
+        }
+
+        debug!("Creating sub-account {} of {}", index, name);
+        let keypair = KeyPair::from_private_key(master.get_private_key()?.derive_sub_account(index));
+        let mut storage = Self::create_storage(&path, password, &keypair, network, false)?;
+        storage.set_sub_account_index(index)?;
+        storage.flush()?;
+
+        master.add_sub_account(index)?;
+        master.flush()?;
+
+        Ok(Self::new(storage, keypair, network, precomputed_tables, false))
     }
 
     // Open an existing wallet on disk
     pub fn open(name: &str, password: &str, network: Network, precomputed_tables: PrecomputedTablesShared) -> Result<Arc<Self>, Error> {
+        let storage = Self::open_storage(name, password, network)?;
+        debug!("Retrieving private key from encrypted storage");
+        let private_key =  storage.get_private_key()?;
+        let keypair = KeyPair::from_private_key(private_key);
+        let watch_only = storage.is_watch_only()?;
+
+        Ok(Self::new(storage, keypair, network, precomputed_tables, watch_only))
+    }
+
+    // Compact the storage of a closed wallet
+    // Superseded topoheight changes are pruned, then the database files are rewritten
+    // The previous files are kept as a backup next to the wallet
+    #[cfg(not(all(
+        target_arch = "wasm32",
+        target_vendor = "unknown",
+        target_os = "unknown"
+    )))]
+    pub fn compact(name: &str, password: &str, network: Network) -> Result<StorageCompaction, Error> {
+        let pruned_changes = {
+            let mut storage = Self::open_storage(name, password, network)?;
+            let topoheight = storage.get_synced_topoheight()
+                .unwrap_or(0)
+                .saturating_sub(PRUNE_CHANGES_SAFETY_LIMIT);
+
+            let pruned = storage.prune_changes_below_topoheight(topoheight)?;
+            storage.flush()?;
+            pruned
+        };
+
+        debug!("Pruned {} topoheight changes, rewriting wallet files", pruned_changes);
+        let (size_before, size_after) = Storage::compact(name)?;
+
+        Ok(StorageCompaction {
+            pruned_changes,
+            size_before,
+            size_after
+        })
+    }
+
+    // Open and decrypt the storage of an existing wallet
+    // The storage is migrated to the latest schema if needed
+    fn open_storage(name: &str, password: &str, network: Network) -> Result<EncryptedStorage, Error> {
         if name.is_empty() {
             return Err(WalletError::EmptyName.into())
         }
@@ -358,8 +625,8 @@ impl Wallet {
         let hashed_password = hash_password(password, &salt)?;
 
         // decrypt the encrypted master key using the hashed password (used as key)
-        let cipher = Cipher::new(&hashed_password, None)?;
-        let master_key = cipher.decrypt_value(&encrypted_master_key).context("Invalid password provided for this wallet")?;
+        let cipher = Cipher::new(hashed_password.as_slice(), None)?;
+        let master_key = Zeroizing::new(cipher.decrypt_value(&encrypted_master_key).context("Invalid password provided for this wallet")?);
 
         // Retrieve the encrypted storage salt
         let encrypted_storage_salt = storage.get_encrypted_storage_salt()?;
@@ -374,11 +641,7 @@ impl Wallet {
 
         debug!("Creating encrypted storage");
         let storage = EncryptedStorage::new(storage, &master_key, salt, network)?;
-        debug!("Retrieving private key from encrypted storage");
-        let private_key =  storage.get_private_key()?;
-        let keypair = KeyPair::from_private_key(private_key);
-
-        Ok(Self::new(storage, keypair, network, precomputed_tables))
+        Ok(storage)
     }
 
     // Close the wallet
@@ -387,6 +650,11 @@ impl Wallet {
     pub async fn close(&self) {
         trace!("Closing wallet");
 
+        // Stop the auto-lock
+        if let Some(handle) = self.auto_lock_task.lock().await.take() {
+            handle.abort();
+        }
+
         #[cfg(feature = "api_server")]
         {
             // Close API server
@@ -409,12 +677,21 @@ impl Wallet {
         // Stop gracefully the network handler
         #[cfg(feature = "network_handler")]
         {
+            let failover = self.daemon_failover.lock().await.take();
+            if let Some(failover) = failover.as_ref() {
+                failover.stop().await;
+            }
+
             let mut lock = self.network_handler.lock().await;
             if let Some(handler) = lock.take() {
                 if let Err(e) = handler.stop(true).await {
                     error!("Error while stopping network handler: {}", e);
                 }
             }
+
+            if let Some(failover) = failover {
+                failover.disconnect().await;
+            }
         }
 
         // Stop gracefully the storage
@@ -439,6 +716,24 @@ impl Wallet {
         self.history_scan.load(Ordering::SeqCst)
     }
 
+    // Disable/enable the checkpoint sync
+    // When enabled, a wallet syncing for the first time only scans the history above the stable topoheight
+    pub fn set_checkpoint_sync(&self, value: bool) {
+        self.checkpoint_sync.store(value, Ordering::SeqCst);
+    }
+
+    // Get the checkpoint sync flag
+    pub fn get_checkpoint_sync(&self) -> bool {
+        self.checkpoint_sync.load(Ordering::SeqCst)
+    }
+
+    // Topoheight from which the wallet was restored using the checkpoint sync
+    // History below it can be retrieved using a rescan
+    pub async fn get_sync_checkpoint(&self) -> Result<Option<u64>, Error> {
+        let storage = self.storage.read().await;
+        storage.get_sync_checkpoint()
+    }
+
     // Disable/enable the stable balance flag
     pub fn set_stable_balance(&self, value: bool) {
         self.force_stable_balance.store(value, Ordering::SeqCst);
@@ -550,9 +845,9 @@ impl Wallet {
         let storage = encrypted_storage.get_mutable_public_storage();
         let salt = storage.get_password_salt()?;
         let hashed_password = hash_password(password, &salt)?;
-        let cipher = Cipher::new(&hashed_password, None)?;
+        let cipher = Cipher::new(hashed_password.as_slice(), None)?;
         let encrypted_master_key = storage.get_encrypted_master_key()?;
-        let _ = cipher.decrypt_value(&encrypted_master_key).context("Invalid password provided")?;
+        let _ = Zeroizing::new(cipher.decrypt_value(&encrypted_master_key).context("Invalid password provided")?);
         Ok(())
     }
 
@@ -569,8 +864,8 @@ impl Wallet {
             let encrypted_storage_salt = storage.get_encrypted_storage_salt()?;
 
             // decrypt the encrypted master key using the provided password
-            let cipher = Cipher::new(&hashed_password, None)?;
-            let master_key = cipher.decrypt_value(&encrypted_master_key).context("Invalid password provided")?;
+            let cipher = Cipher::new(hashed_password.as_slice(), None)?;
+            let master_key = Zeroizing::new(cipher.decrypt_value(&encrypted_master_key).context("Invalid password provided")?);
             let storage_salt = cipher.decrypt_value(&encrypted_storage_salt)?;
             (master_key, storage_salt)
         };
@@ -581,7 +876,7 @@ impl Wallet {
 
         // generate the password-based derivated key to encrypt the master key
         let hashed_password = hash_password(password, &salt)?;
-        let cipher = Cipher::new(&hashed_password, None)?;
+        let cipher = Cipher::new(hashed_password.as_slice(), None)?;
 
         // encrypt the master key using the new password
         let encrypted_key = cipher.encrypt_value(&master_key)?;
@@ -621,10 +916,38 @@ impl Wallet {
         }
     }
 
+    // Decrypt several ciphertexts in parallel
+    // Identical ciphertexts are decoded only once and the threads pick the next one to decode
+    // Amounts are returned in the same order as the ciphertexts
+    pub async fn decrypt_ciphertexts(&self, ciphertexts: Vec<Ciphertext>) -> Result<Vec<u64>, WalletError> {
+        trace!("decrypt {} ciphertexts", ciphertexts.len());
+        #[cfg(not(all(
+            target_arch = "wasm32",
+            target_vendor = "unknown",
+            target_os = "unknown"
+        )))]
+        {
+            let threads = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1);
+
+            let account = Arc::clone(&self.inner);
+            spawn_blocking(move || account.decrypt_ciphertexts(&ciphertexts, threads)).await.context("Error while decrypting ciphertexts")?
+        }
+        #[cfg(all(
+            target_arch = "wasm32",
+            target_vendor = "unknown",
+            target_os = "unknown"
+        ))]
+        {
+            self.inner.decrypt_ciphertexts(&ciphertexts, 1)
+        }
+    }
+
     // Decrypt the extra data from a transfer
     pub fn decrypt_extra_data(&self, cipher: UnknownExtraDataFormat, role: Role) -> Result<PlaintextExtraData, WalletError> {
         trace!("decrypt extra data");
-        cipher.decrypt_v2(&self.inner.keypair.get_private_key(), role).map_err(|_| WalletError::CiphertextDecode)
+        cipher.decrypt_v2(self.inner.get_keypair()?.get_private_key(), role).map_err(|_| WalletError::CiphertextDecode)
     }
 
     // Create a transaction with the given transaction type and fee
@@ -795,195 +1118,862 @@ impl Wallet {
     pub fn create_transaction_with(&self, state: &mut TransactionBuilderState, tx_version: TxVersion, transaction_type: TransactionTypeBuilder, fee: FeeBuilder) -> Result<Transaction, WalletError> {
         // Create the transaction builder
         // TODO: support multisig
-        let builder = TransactionBuilder::new(tx_version, self.get_public_key().clone(), 0, transaction_type, fee);
+        let builder = TransactionBuilder::new(tx_version, self.get_public_key().clone(), 0, transaction_type.clone(), fee);
 
         // Build the final transaction
-        let transaction = builder.build(state, &self.inner.keypair)
+        let transaction = builder.build(state, &self.get_keypair()?)
             .map_err(|e| WalletError::Any(e.into()))?;
 
         let tx_hash = transaction.hash();
         debug!("Transaction created: {} with nonce {} and reference {}", tx_hash, transaction.get_nonce(), transaction.get_reference());
-        state.set_tx_hash_built(tx_hash);
+        state.set_tx_hash_built(tx_hash.clone());
+        state.set_pending_transaction(PendingTransaction {
+            hash: tx_hash,
+            nonce: transaction.get_nonce(),
+            fee: transaction.get_fee(),
+            transaction_type
+        });
 
         Ok(transaction)
     }
 
-    // Create an unsigned transaction with the given transaction type and fee
-    pub fn create_unsigned_transaction(&self, state: &mut TransactionBuilderState, threshold: u8, transaction_type: TransactionTypeBuilder, fee: FeeBuilder, tx_version: TxVersion) -> Result<UnsignedTransaction, WalletError> {
-        trace!("create unsigned transaction");
-        let builder = TransactionBuilder::new(tx_version, self.get_public_key().clone(), threshold, transaction_type, fee);
-        let unsigned = builder.build_unsigned(state, &self.inner.keypair)
+    // Replace a pending transaction by the same one with a higher fee
+    // The pending transaction is only kept in memory, so only the last transaction
+    // created in the current session can be bumped, not one created before the wallet was reopened
+    // All the previous transactions must also be executed
+    // If no fee is provided, the estimated fee is used or the minimum fee accepted
+    // by the daemon for a replacement if the estimation is lower
+    // The replaced transaction is dropped locally once the new one is submitted
+    #[cfg(feature = "network_handler")]
+    pub async fn bump_transaction_fee(&self, hash: &Hash, fee: Option<FeeBuilder>) -> Result<Transaction, WalletError> {
+        trace!("bump transaction fee {}", hash);
+        let mut storage = self.storage.write().await;
+        let pending = storage.get_tx_cache()
+            .and_then(|cache| cache.pending_transaction.as_ref())
+            .filter(|pending| pending.hash == *hash)
+            .cloned()
+            .ok_or_else(|| WalletError::PendingTransactionNotFound(hash.clone()))?;
+
+        // The replacement is built on the confirmed balances
+        // so no other transaction must be pending
+        if storage.get_nonce()? != pending.nonce {
+            return Err(WalletError::PendingTransactionNotReplaceable(hash.clone()))
+        }
+
+        let is_fee_provided = fee.is_some();
+        let fee_builder = fee.unwrap_or_default();
+        let mut state = self.create_replacement_state(&storage, &pending, &fee_builder).await?;
+        let tx_version = storage.get_tx_version().await?;
+
+        // Resolve the fee before building so the transaction is built and signed only once
+        let min_fee = calculate_replacement_min_fee(pending.fee);
+        let estimated_fee = TransactionBuilder::new(tx_version, self.get_public_key().clone(), 0, pending.transaction_type.clone(), fee_builder)
+            .estimate_fees(&mut state)
             .map_err(|e| WalletError::Any(e.into()))?;
 
-        Ok(unsigned)
+        let fee = if !is_fee_provided {
+            estimated_fee.max(min_fee)
+        } else if estimated_fee < min_fee {
+            return Err(WalletError::ReplacementFeeTooLow(min_fee, estimated_fee))
+        } else {
+            estimated_fee
+        };
+
+        let transaction = self.create_transaction_with(&mut state, tx_version, pending.transaction_type.clone(), FeeBuilder::Value(fee))?;
+        self.submit_transaction(&transaction).await?;
+
+        // Forget the unconfirmed balances of the replaced transaction
+        storage.delete_unconfirmed_balances().await;
+        state.apply_changes(&mut storage).await?;
+
+        Ok(transaction)
     }
 
+    // Prepare the state to build the replacement of a pending transaction from the confirmed balances
+    #[cfg(feature = "network_handler")]
+    async fn create_replacement_state(&self, storage: &EncryptedStorage, pending: &PendingTransaction, fee: &FeeBuilder) -> Result<TransactionBuilderState, WalletError> {
+        let reference = Reference {
+            topoheight: storage.get_synced_topoheight()?,
+            hash: storage.get_top_block_hash()?
+        };
 
-    // submit a transaction to the network through the connection to daemon
-    // It will increase the local nonce by 1 if the TX is accepted by the daemon
-    // returns error if the wallet is in offline mode or if the TX is rejected
-    pub async fn submit_transaction(&self, transaction: &Transaction) -> Result<(), WalletError> {
-        trace!("submit transaction {}", transaction.hash());
-        #[cfg(feature = "network_handler")]
-        {
-            let network_handler = self.network_handler.lock().await;
-            if let Some(network_handler) = network_handler.as_ref() {
-                network_handler.get_api().submit_transaction(transaction).await?;
-                return Ok(())
-            }
+        let mut state = TransactionBuilderState::new(self.network.is_mainnet(), reference, pending.nonce);
+        for asset in pending.transaction_type.used_assets() {
+            let balance = storage.get_balance_for(asset).await?;
+            state.add_balance(asset.clone(), balance);
         }
-        Err(WalletError::NotOnlineMode)
+
+        self.add_registered_keys_for_fees_estimation(state.as_mut(), fee, &pending.transaction_type).await?;
+
+        Ok(state)
     }
 
-    // Search if possible all registered keys for the transaction type
+    // Fetch a transaction from the daemon and check that it's the requested one
     #[cfg(feature = "network_handler")]
-    pub async fn add_registered_keys_for_fees_estimation(&self, state: &mut EstimateFeesState, fee: &FeeBuilder, transaction_type: &TransactionTypeBuilder) -> Result<(), WalletError> {
-        trace!("add registered keys for fees estimation");
-        if let FeeBuilder::Multiplier(_) = fee {
-            // To pay exact fees needed, we must verify that we don't have to pay more than needed
-            let used_keys = transaction_type.used_keys();
-            if !used_keys.is_empty() {
-                trace!("Checking if destination keys are registered");
-                if let Some(network_handler) = self.network_handler.lock().await.as_ref() {
-                    if network_handler.is_running().await {
-                        trace!("Network handler is running, checking if keys are registered");
-                        for key in used_keys {
-                            let addr = key.as_address(self.network.is_mainnet());
-                            trace!("Checking if {} is registered in stable height", addr);
-                            let registered = network_handler.get_api().is_account_registered(&addr, true).await?;
-                            trace!("registered: {}", registered);
-                            if registered {
-                                state.add_registered_key(addr.to_public_key());
-                            }
-                        }
-                    }
-                }
-            }
+    async fn fetch_transaction(&self, hash: &Hash) -> Result<Transaction, WalletError> {
+        let network_handler = self.network_handler.lock().await;
+        let api = network_handler.as_ref()
+            .ok_or(WalletError::NotOnlineMode)?
+            .get_api();
+
+        let transaction = api.get_transaction(hash).await?;
+        if transaction.hash() != *hash {
+            return Err(WalletError::InvalidDaemonTransaction(hash.clone()))
         }
 
-        Ok(())
+        Ok(transaction)
     }
 
-    // Estimate fees for a given transaction type
-    // Estimated fees returned are the minimum required to be valid on chain
-    pub async fn estimate_fees(&self, tx_type: TransactionTypeBuilder) -> Result<u64, WalletError> {
-        trace!("estimate fees");
-        let mut state = EstimateFeesState::new();
+    // Create a proof that the transfer at index in a transaction was sent by this wallet
+    // It reveals the amount of this transfer only, destination and asset are already public
+    // The amount is decrypted from the sender ciphertext so it doesn't rely on the history
+    #[cfg(feature = "network_handler")]
+    pub async fn create_payment_proof(&self, hash: &Hash, index: u8) -> Result<HumanReadableProof, WalletError> {
+        trace!("create payment proof for transfer {} in {}", index, hash);
+        let keypair = self.get_keypair()?;
+        let transaction = self.fetch_transaction(hash).await?;
+        if transaction.get_source() != self.get_public_key() {
+            return Err(WalletError::TransactionNotSentByWallet(hash.clone()))
+        }
 
-        #[cfg(feature = "network_handler")]
-        self.add_registered_keys_for_fees_estimation(&mut state, &FeeBuilder::default(), &tx_type).await?;
+        let transfer = match transaction.get_data() {
+            TransactionType::Transfers(transfers) => transfers.get(index as usize),
+            _ => None
+        }.ok_or_else(|| WalletError::TransferNotFound(hash.clone(), index))?;
 
-        // TODO: support multisig
-        let builder = TransactionBuilder::new(TxVersion::V0, self.get_public_key().clone(), 0, tx_type, FeeBuilder::default());
-        let estimated_fees = builder.estimate_fees(&mut state)
-            .map_err(|e| WalletError::Any(e.into()))?;
+        let ciphertext = transfer.get_ciphertext(Role::Sender)
+            .decompress()
+            .context("Error while decompressing transfer ciphertext")?;
+        let amount = self.decrypt_ciphertext(ciphertext.clone()).await?;
 
-        Ok(estimated_fees)
+        let proof = PaymentProof::new(&keypair, hash, index, amount, ciphertext);
+        Ok(HumanReadableProof::Payment { proof, transaction: hash.clone(), index })
     }
 
-    // Export all transactions in CSV format to the given writer
-    // This will sort the transactions by topoheight before exporting
-    pub async fn export_transactions_in_csv<W: Write>(&self, storage: &EncryptedStorage, mut transactions: Vec<InnerTransactionEntry>, w: &mut W) -> Result<(), WalletError> {
-        trace!("export transactions in csv");
+    // Verify a payment proof against the transaction fetched from the daemon
+    // The transaction must be executed in a block to be considered as a valid payment
+    #[cfg(feature = "network_handler")]
+    pub async fn verify_payment_proof(&self, proof: &HumanReadableProof) -> Result<VerifyPaymentProofResult<'static>, WalletError> {
+        trace!("verify payment proof");
+        let HumanReadableProof::Payment { proof, transaction: hash, index } = proof else {
+            return Err(WalletError::ExpectedPaymentProof)
+        };
 
-        // Sort transactions by topoheight
-        transactions.sort_by(|a, b| a.get_topoheight().cmp(&b.get_topoheight()));
+        let transaction = self.fetch_transaction(hash).await?;
+        let transfer = transaction.verify_payment_proof(hash, *index, proof)
+            .map_err(WalletError::InvalidPaymentProof)?;
 
-        writeln!(w, "Date,TopoHeight,Hash,Type,From/To,Asset,Amount,Fee,Nonce").context("Error while writing headers")?;
-        for tx in transactions {
-            match tx.get_entry() {
-                EntryData::Burn { asset, amount, fee, nonce } => {
-                    let data = storage.get_asset(&asset).await?;
-                    writeln!(w, "{},{},{},{},{},-,{},{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "Burn", data.get_name(), format_coin(*amount, data.get_decimals()), format_xelis(*fee), nonce).context("Error while writing csv line")?;
-                },
-                EntryData::Coinbase { reward } => {
-                    writeln!(w, "{},{},{},{},{},-,{},-,-", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "Coinbase", "XELIS", format_xelis(*reward)).context("Error while writing csv line")?;
-                },
-                EntryData::Incoming { from, transfers } => {
-                    for transfer in transfers {
-                        let data = storage.get_asset(&transfer.get_asset()).await?;
-                        writeln!(w, "{},{},{},{},{},{},{},-,-", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "Incoming", from.as_address(self.get_network().is_mainnet()), data.get_name(), format_coin(transfer.get_amount(), data.get_decimals())).context("Error while writing csv line")?;
-                    }
-                },
-                EntryData::Outgoing { transfers, fee, nonce } => {
-                    for transfer in transfers {
-                        let data = storage.get_asset(&transfer.get_asset()).await?;
-                        writeln!(w, "{},{},{},{},{},{},{},{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "Outgoing", transfer.get_destination().as_address(self.get_network().is_mainnet()), data.get_name(), format_coin(transfer.get_amount(), data.get_decimals()), format_xelis(*fee), nonce).context("Error while writing csv line")?;
-                    }
-                },
-                EntryData::MultiSig { participants, threshold, fee, nonce } => {
-                    let str_participants: Vec<String> = participants.iter().map(|p| p.as_address(self.get_network().is_mainnet()).to_string()).collect();
-                    writeln!(w, "{},{},{},{},{},{},-,{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "MultiSig", str_participants.join("|"), threshold, format_xelis(*fee), nonce).context("Error while writing csv line")?;
-                },
-                EntryData::InvokeContract { contract, deposits, chunk_id, fee, nonce } => {
-                    let mut str_deposits = Vec::new();
-                    for (asset, amount) in deposits {
-                        let data = storage.get_asset(&asset).await?;
-                        str_deposits.push(format!("{}:{}", data.get_name(), format_coin(*amount, data.get_decimals())));
-                    }
+        // Fails if the transaction is not executed
+        let executor = {
+            let network_handler = self.network_handler.lock().await;
+            let api = network_handler.as_ref()
+                .ok_or(WalletError::NotOnlineMode)?
+                .get_api();
 
-                    writeln!(w, "{},{},{},{},{},{},{},{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "InvokeContract", contract, str_deposits.join("|"), chunk_id, format_xelis(*fee), nonce).context("Error while writing csv line")?;
-                },
-                EntryData::DeployContract { fee, nonce } => {
-                    writeln!(w, "{},{},{},{},-,-,-,{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "DeployContract", format_xelis(*fee), nonce).context("Error while writing csv line")?;
-                },
-            }
-        }
-    
-        w.flush().context("Error while flushing CSV file")?;
-        Ok(())
+            api.get_transaction_executor(hash).await?
+        };
+
+        let mainnet = self.network.is_mainnet();
+        Ok(VerifyPaymentProofResult {
+            transaction: Cow::Owned(hash.clone()),
+            index: *index,
+            source: transaction.get_source().as_address(mainnet),
+            destination: transfer.get_destination().as_address(mainnet),
+            asset: Cow::Owned(transfer.get_asset().clone()),
+            amount: proof.get_amount(),
+            block_topoheight: executor.block_topoheight
+        })
     }
 
-    // set wallet in online mode: start a communication task which will keep the wallet synced
+    // Create a proof that this wallet controls at least the amount of an asset
+    // The last balance is fetched from the daemon so the proof can be verified against the chain
+    // The challenge is chosen by the verifier to ensure the proof was freshly created
     #[cfg(feature = "network_handler")]
-    pub async fn set_online_mode(self: &Arc<Self>, daemon_address: &String, auto_reconnect: bool) -> Result<(), WalletError> {
-        trace!("Set online mode to daemon {} with auto reconnect set to {}", daemon_address, auto_reconnect);
-        if self.is_online().await {
-            // user have to set in offline mode himself first
-            return Err(WalletError::AlreadyOnlineMode)
+    pub async fn create_reserve_proof(&self, asset: &Hash, amount: u64, challenge: Vec<u8>) -> Result<HumanReadableProof, WalletError> {
+        trace!("create reserve proof of {} for {}", amount, asset);
+        let keypair = self.get_keypair()?;
+        if challenge.len() > MAX_RESERVE_CHALLENGE_SIZE {
+            return Err(WalletError::InvalidReserveChallenge(MAX_RESERVE_CHALLENGE_SIZE))
         }
 
-        // create the network handler
-        let network_handler = NetworkHandler::new(Arc::clone(&self), daemon_address).await?;
-        // start the task
-        network_handler.start(auto_reconnect).await?;
-        *self.network_handler.lock().await = Some(network_handler);
-        Ok(())
-    }
+        let result = {
+            let network_handler = self.network_handler.lock().await;
+            let api = network_handler.as_ref()
+                .ok_or(WalletError::NotOnlineMode)?
+                .get_api();
 
-    // set the wallet in online mode using a shared daemon API
-    // this allows to share the same connection/Daemon API across several wallets to save resources
-    #[cfg(feature = "network_handler")]
-    pub async fn set_online_mode_with_api(self: &Arc<Self>, daemon_api: Arc<DaemonAPI>, auto_reconnect: bool) -> Result<(), WalletError> {
-        trace!("Set online mode with API with auto reconnect set to {}", auto_reconnect);
-        if self.is_online().await {
-            // user have to set in offline mode himself first
-            return Err(WalletError::AlreadyOnlineMode)
-        }
+            api.get_balance(&self.get_address(), asset).await?
+        };
 
-        // create the network handler
-        let network_handler = NetworkHandler::with_api(Arc::clone(&self), daemon_api).await?;
-        // start the task
-        network_handler.start(auto_reconnect).await?;
-        *self.network_handler.lock().await = Some(network_handler);
-        Ok(())
+        let ciphertext = result.version.get_balance()
+            .compress()
+            .decompress()
+            .context("Error while decompressing balance")?;
+        let balance = self.decrypt_ciphertext(ciphertext.clone()).await?;
+
+        let proof = ReserveProof::new(&keypair, balance, amount, ciphertext, &challenge)
+            .map_err(WalletError::ReserveProofGeneration)?;
+        Ok(HumanReadableProof::Reserve { proof, asset: asset.clone(), topoheight: result.topoheight, challenge })
     }
 
-    // set wallet in offline mode: stop communication task if exists
+    // Verify a reserve proof of an account against its balance fetched from the daemon
     #[cfg(feature = "network_handler")]
-    pub async fn set_offline_mode(&self) -> Result<(), WalletError> {
-        trace!("Set offline mode");
+    pub async fn verify_reserve_proof(&self, address: &Address, proof: &HumanReadableProof) -> Result<VerifyReserveProofResult<'static>, WalletError> {
+        trace!("verify reserve proof of {}", address);
+        let HumanReadableProof::Reserve { proof, asset, topoheight, challenge } = proof else {
+            return Err(WalletError::ExpectedReserveProof)
+        };
+
+        if address.is_mainnet() != self.network.is_mainnet() {
+            return Err(WalletError::InvalidAddressNetwork)
+        }
+
+        let (version, last) = {
+            let network_handler = self.network_handler.lock().await;
+            let api = network_handler.as_ref()
+                .ok_or(WalletError::NotOnlineMode)?
+                .get_api();
+
+            (api.get_balance_at_topoheight(address, asset, *topoheight).await?, api.get_balance(address, asset).await?)
+        };
+
+        let ciphertext = version.get_balance()
+            .compress()
+            .decompress()
+            .context("Error while decompressing balance")?;
+        let public_key = address.get_public_key()
+            .decompress()
+            .context("Error while decompressing public key")?;
+        proof.verify(&public_key, ciphertext, challenge)
+            .map_err(WalletError::InvalidReserveProof)?;
+
+        Ok(VerifyReserveProofResult {
+            address: Cow::Owned(address.clone()),
+            asset: Cow::Owned(asset.clone()),
+            amount: proof.get_amount(),
+            topoheight: *topoheight,
+            challenge: hex::encode(challenge),
+            latest: last.topoheight == *topoheight
+        })
+    }
+
+    // Create a proof re-encrypting the last balance of an asset for a designated auditor
+    // The auditor decrypts it using its own key, so it can't sign on behalf of this wallet
+    #[cfg(feature = "network_handler")]
+    pub async fn create_audit_proof(&self, auditor: &Address, asset: &Hash) -> Result<HumanReadableProof, WalletError> {
+        trace!("create audit proof of {} for {}", asset, auditor);
+        let keypair = self.get_keypair()?;
+        if auditor.is_mainnet() != self.network.is_mainnet() {
+            return Err(WalletError::InvalidAddressNetwork)
+        }
+
+        let auditor_key = auditor.get_public_key()
+            .decompress()
+            .context("Error while decompressing auditor key")?;
+
+        let result = {
+            let network_handler = self.network_handler.lock().await;
+            let api = network_handler.as_ref()
+                .ok_or(WalletError::NotOnlineMode)?
+                .get_api();
+
+            api.get_balance(&self.get_address(), asset).await?
+        };
+
+        let ciphertext = result.version.get_balance()
+            .compress()
+            .decompress()
+            .context("Error while decompressing balance")?;
+        let balance = self.decrypt_ciphertext(ciphertext.clone()).await?;
+
+        let proof = AuditProof::new(&keypair, &auditor_key, balance, ciphertext);
+        Ok(HumanReadableProof::Audit { proof, auditor: auditor.get_public_key().clone(), asset: asset.clone(), topoheight: result.topoheight })
+    }
+
+    // Create a proof re-encrypting the amount of a transfer sent or received by this wallet for a designated auditor
+    #[cfg(feature = "network_handler")]
+    pub async fn create_transfer_audit_proof(&self, auditor: &Address, hash: &Hash, index: u8) -> Result<HumanReadableProof, WalletError> {
+        trace!("create audit proof of transfer {} in {} for {}", index, hash, auditor);
+        let keypair = self.get_keypair()?;
+        if auditor.is_mainnet() != self.network.is_mainnet() {
+            return Err(WalletError::InvalidAddressNetwork)
+        }
+
+        let auditor_key = auditor.get_public_key()
+            .decompress()
+            .context("Error while decompressing auditor key")?;
+
+        let transaction = self.fetch_transaction(hash).await?;
+        let transfer = match transaction.get_data() {
+            TransactionType::Transfers(transfers) => transfers.get(index as usize),
+            _ => None
+        }.ok_or_else(|| WalletError::TransferNotFound(hash.clone(), index))?;
+
+        let role = if transaction.get_source() == self.get_public_key() {
+            Role::Sender
+        } else if transfer.get_destination() == self.get_public_key() {
+            Role::Receiver
+        } else {
+            return Err(WalletError::TransferNotForWallet(hash.clone(), index))
+        };
+
+        let ciphertext = transfer.get_ciphertext(role)
+            .decompress()
+            .context("Error while decompressing transfer ciphertext")?;
+        let amount = self.decrypt_ciphertext(ciphertext.clone()).await?;
+
+        let proof = AuditProof::new(&keypair, &auditor_key, amount, ciphertext);
+        Ok(HumanReadableProof::TransferAudit { proof, auditor: auditor.get_public_key().clone(), transaction: hash.clone(), index })
+    }
+
+    // Verify an audit proof of an account and decrypt the audited amount
+    // This wallet must be the auditor designated by the proof
+    #[cfg(feature = "network_handler")]
+    pub async fn verify_audit_proof(&self, address: &Address, proof: &HumanReadableProof) -> Result<AuditProofResult<'static>, WalletError> {
+        trace!("verify audit proof of {}", address);
+        if address.is_mainnet() != self.network.is_mainnet() {
+            return Err(WalletError::InvalidAddressNetwork)
+        }
+
+        let public_key = address.get_public_key()
+            .decompress()
+            .context("Error while decompressing public key")?;
+        let auditor_key = self.get_public_key()
+            .decompress()
+            .context("Error while decompressing public key")?;
+
+        let (proof, asset, topoheight, transfer) = match proof {
+            HumanReadableProof::Audit { proof, auditor, asset, topoheight } => {
+                if auditor != self.get_public_key() {
+                    return Err(WalletError::NotDesignatedAuditor)
+                }
+
+                let version = {
+                    let network_handler = self.network_handler.lock().await;
+                    let api = network_handler.as_ref()
+                        .ok_or(WalletError::NotOnlineMode)?
+                        .get_api();
+
+                    api.get_balance_at_topoheight(address, asset, *topoheight).await?
+                };
+
+                let ciphertext = version.get_balance()
+                    .compress()
+                    .decompress()
+                    .context("Error while decompressing balance")?;
+                proof.verify(&public_key, &auditor_key, ciphertext)
+                    .map_err(WalletError::InvalidAuditProof)?;
+
+                (proof, asset.clone(), Some(*topoheight), None)
+            },
+            HumanReadableProof::TransferAudit { proof, auditor, transaction: hash, index } => {
+                if auditor != self.get_public_key() {
+                    return Err(WalletError::NotDesignatedAuditor)
+                }
+
+                let transaction = self.fetch_transaction(hash).await?;
+                let transfer = transaction.verify_audit_proof(address.get_public_key(), &auditor_key, *index, proof)
+                    .map_err(WalletError::InvalidAuditProof)?;
+
+                // Fails if the transaction is not executed
+                {
+                    let network_handler = self.network_handler.lock().await;
+                    let api = network_handler.as_ref()
+                        .ok_or(WalletError::NotOnlineMode)?
+                        .get_api();
+
+                    api.get_transaction_executor(hash).await?;
+                }
+
+                (proof, transfer.get_asset().clone(), None, Some((hash.clone(), *index)))
+            },
+            _ => return Err(WalletError::ExpectedAuditProof)
+        };
+
+        let ciphertext = proof.decompress_ciphertext()
+            .context("Error while decompressing audit ciphertext")?;
+        let amount = self.decrypt_ciphertext(ciphertext).await?;
+
+        let (transaction, index) = transfer.unzip();
+        Ok(AuditProofResult {
+            address: Cow::Owned(address.clone()),
+            asset: Cow::Owned(asset),
+            topoheight,
+            transaction: transaction.map(Cow::Owned),
+            index,
+            amount
+        })
+    }
+
+    // Prepare a transaction to be signed by an offline wallet
+    // This fetch the balances, reference and nonce, and compute the fee
+    // No changes are applied to the storage
+    pub async fn prepare_transaction(&self, transaction_type: TransactionTypeBuilder, fee: FeeBuilder, nonce: Option<u64>, tx_version: Option<TxVersion>) -> Result<PreparedTransaction, WalletError> {
+        trace!("prepare transaction");
+        let storage = self.storage.read().await;
+        let mut state = self.create_transaction_state_with_storage(&storage, &transaction_type, &fee, nonce).await?;
+        let tx_version = match tx_version {
+            Some(v) => v,
+            None => storage.get_tx_version().await?
+        };
+
+        // Compute the fee now as the offline wallet
+        // doesn't know which accounts are registered
+        let builder = TransactionBuilder::new(tx_version, self.get_public_key().clone(), 0, transaction_type.clone(), fee);
+        let fee = builder.estimate_fees(&mut state)
+            .map_err(|e| WalletError::Any(e.into()))?;
+
+        let balances = state.get_balances()
+            .iter()
+            .map(|(asset, balance)| (asset.clone(), balance.ciphertext.clone()))
+            .collect();
+
+        Ok(PreparedTransaction {
+            source: self.get_address(),
+            tx_type: transaction_type,
+            fee,
+            tx_version,
+            balances,
+            reference: state.get_reference(),
+            nonce: state.get_nonce()?
+        })
+    }
+
+    // Sign a transaction prepared by an online wallet
+    // This doesn't require any connection to a daemon
+    pub async fn sign_prepared_transaction(&self, prepared: PreparedTransaction) -> Result<Transaction, WalletError> {
+        trace!("sign prepared transaction");
+        if *prepared.source.get_public_key() != *self.get_public_key() || prepared.source.is_mainnet() != self.get_network().is_mainnet() {
+            return Err(WalletError::InvalidPreparedTransactionSource(prepared.source))
+        }
+
+        let mut state = TransactionBuilderState::new(self.get_network().is_mainnet(), prepared.reference, prepared.nonce);
+        for (asset, mut ciphertext) in prepared.balances {
+            let compressed = ciphertext.decompressed()
+                .map_err(|e| WalletError::Any(e.into()))?;
+            let amount = self.decrypt_ciphertext(compressed.clone()).await?;
+
+            state.add_balance(asset, Balance {
+                amount,
+                ciphertext
+            });
+        }
+
+        self.create_transaction_with(&mut state, prepared.tx_version, prepared.tx_type, FeeBuilder::Value(prepared.fee))
+    }
+
+    // Create an unsigned transaction with the given transaction type and fee
+    pub fn create_unsigned_transaction(&self, state: &mut TransactionBuilderState, threshold: u8, transaction_type: TransactionTypeBuilder, fee: FeeBuilder, tx_version: TxVersion) -> Result<UnsignedTransaction, WalletError> {
+        trace!("create unsigned transaction");
+        let builder = TransactionBuilder::new(tx_version, self.get_public_key().clone(), threshold, transaction_type, fee);
+        let unsigned = builder.build_unsigned(state, &self.get_keypair()?)
+            .map_err(|e| WalletError::Any(e.into()))?;
+
+        Ok(unsigned)
+    }
+
+
+    // Create a multisig transaction and save it as pending
+    // until enough participants have signed it
+    // Changes are applied to the storage once the transaction is finalized
+    pub async fn create_multisig_transaction(&self, transaction_type: TransactionTypeBuilder, fee: FeeBuilder, nonce: Option<u64>, tx_version: Option<TxVersion>) -> Result<UnsignedTransaction, WalletError> {
+        trace!("create multisig transaction");
+        let mut storage = self.storage.write().await;
+        let multisig = storage.get_multisig_state().await?
+            .ok_or(WalletError::NoMultiSigConfigured)?;
+
+        let tx_version = match tx_version {
+            Some(v) => v,
+            None => storage.get_tx_version().await?
+        };
+
+        // Multisig is only verified by the daemon starting V1
+        if tx_version < TxVersion::V1 {
+            return Err(WalletError::MultiSigInvalidTxVersion)
+        }
+
+        let mut state = self.create_transaction_state_with_storage(&storage, &transaction_type, &fee, nonce).await?;
+        let unsigned = self.create_unsigned_transaction(&mut state, multisig.payload.threshold, transaction_type, fee, tx_version)?;
+
+        let hash = unsigned.get_hash_for_multisig();
+        debug!("Multisig transaction {} created, waiting for {} signatures", hash, multisig.payload.threshold);
+        storage.set_pending_multisig_transaction(&hash, &unsigned)?;
+
+        Ok(unsigned)
+    }
+
+    // Get the signing status of a pending multisig transaction
+    pub async fn get_multisig_signing_status(&self, hash: &Hash) -> Result<MultiSigSigningStatus, WalletError> {
+        trace!("get multisig signing status {}", hash);
+        let storage = self.storage.read().await;
+        let (unsigned, payload) = self.get_pending_multisig_with_storage(&storage, hash).await?;
+
+        Ok(build_multisig_signing_status(hash.clone(), &unsigned, &payload))
+    }
+
+    // Get the signing status of all pending multisig transactions
+    pub async fn get_pending_multisig_transactions(&self) -> Result<Vec<MultiSigSigningStatus>, WalletError> {
+        trace!("get pending multisig transactions");
+        let storage = self.storage.read().await;
+        let multisig = storage.get_multisig_state().await?
+            .ok_or(WalletError::NoMultiSigConfigured)?;
+
+        let statuses = storage.get_pending_multisig_transactions()?
+            .into_iter()
+            .map(|(hash, unsigned)| build_multisig_signing_status(hash, &unsigned, &multisig.payload))
+            .collect();
+
+        Ok(statuses)
+    }
+
+    // Import the signature of a co-signer for a pending multisig transaction
+    // The signature is verified against the participant key before being saved
+    pub async fn add_multisig_signature(&self, hash: &Hash, signature: SignatureId) -> Result<MultiSigSigningStatus, WalletError> {
+        trace!("add multisig signature for {}", hash);
+        let mut storage = self.storage.write().await;
+        let (mut unsigned, payload) = self.get_pending_multisig_with_storage(&storage, hash).await?;
+
+        let id = signature.id;
+        let key = payload.participants.get_index(id as usize)
+            .ok_or(WalletError::InvalidMultiSigSigner(id))?;
+        let decompressed = key.decompress()
+            .map_err(|e| WalletError::Any(e.into()))?;
+
+        if !signature.signature.verify(hash.as_bytes(), &decompressed) {
+            return Err(WalletError::InvalidMultiSigSignature(id))
+        }
+
+        let mut multisig = unsigned.multisig().cloned().unwrap_or_else(MultiSig::new);
+        if !multisig.add_signature(signature) {
+            return Err(WalletError::MultiSigAlreadySigned(id))
+        }
+        unsigned.set_multisig(multisig);
+
+        storage.set_pending_multisig_transaction(hash, &unsigned)?;
+
+        Ok(build_multisig_signing_status(hash.clone(), &unsigned, &payload))
+    }
+
+    // Finalize a pending multisig transaction once the threshold is reached
+    // It is not removed from the pending ones, this must be done once it has been broadcasted
+    pub async fn finalize_multisig_transaction_with_storage(&self, storage: &EncryptedStorage, hash: &Hash) -> Result<Transaction, WalletError> {
+        trace!("finalize multisig transaction {}", hash);
+        let (mut unsigned, payload) = self.get_pending_multisig_with_storage(storage, hash).await?;
+
+        let threshold = payload.threshold as usize;
+        let signatures = unsigned.multisig()
+            .map(|m| m.len())
+            .unwrap_or(0);
+
+        if signatures < threshold {
+            return Err(WalletError::MultiSigThresholdNotReached(signatures, payload.threshold))
+        }
+
+        // Daemon expects exactly the threshold of signatures
+        if signatures > threshold {
+            if let Some(current) = unsigned.multisig().cloned() {
+                let mut multisig = MultiSig::new();
+                for signature in current.get_signatures().iter().take(threshold) {
+                    multisig.add_signature(signature.clone());
+                }
+                unsigned.set_multisig(multisig);
+            }
+        }
+
+        Ok(unsigned.finalize(&self.get_keypair()?))
+    }
+
+    // Load a pending multisig transaction with the current multisig setup
+    async fn get_pending_multisig_with_storage(&self, storage: &EncryptedStorage, hash: &Hash) -> Result<(UnsignedTransaction, MultiSigPayload), WalletError> {
+        let multisig = storage.get_multisig_state().await?
+            .ok_or(WalletError::NoMultiSigConfigured)?;
+
+        if !storage.has_pending_multisig_transaction(hash)? {
+            return Err(WalletError::PendingMultiSigNotFound(hash.clone()))
+        }
+
+        let unsigned = storage.get_pending_multisig_transaction(hash)?;
+        Ok((unsigned, multisig.payload))
+    }
+
+    // submit a transaction to the network through the connection to daemon
+    // It will increase the local nonce by 1 if the TX is accepted by the daemon
+    // returns error if the wallet is in offline mode or if the TX is rejected
+    pub async fn submit_transaction(&self, transaction: &Transaction) -> Result<(), WalletError> {
+        trace!("submit transaction {}", transaction.hash());
+        #[cfg(feature = "network_handler")]
+        {
+            let network_handler = self.network_handler.lock().await;
+            if let Some(network_handler) = network_handler.as_ref() {
+                network_handler.get_api().submit_transaction(transaction).await?;
+                return Ok(())
+            }
+        }
+        Err(WalletError::NotOnlineMode)
+    }
+
+    // Search if possible all registered keys for the transaction type
+    #[cfg(feature = "network_handler")]
+    pub async fn add_registered_keys_for_fees_estimation(&self, state: &mut EstimateFeesState, fee: &FeeBuilder, transaction_type: &TransactionTypeBuilder) -> Result<(), WalletError> {
+        trace!("add registered keys for fees estimation");
+        if let FeeBuilder::Multiplier(_) = fee {
+            // To pay exact fees needed, we must verify that we don't have to pay more than needed
+            let used_keys = transaction_type.used_keys();
+            if !used_keys.is_empty() {
+                trace!("Checking if destination keys are registered");
+                if let Some(network_handler) = self.network_handler.lock().await.as_ref() {
+                    if network_handler.is_running().await {
+                        trace!("Network handler is running, checking if keys are registered");
+                        for key in used_keys {
+                            let addr = key.as_address(self.network.is_mainnet());
+                            trace!("Checking if {} is registered in stable height", addr);
+                            let registered = network_handler.get_api().is_account_registered(&addr, true).await?;
+                            trace!("registered: {}", registered);
+                            if registered {
+                                state.add_registered_key(addr.to_public_key());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Estimate fees for a given transaction type
+    // Estimated fees returned are the minimum required to be valid on chain
+    pub async fn estimate_fees(&self, tx_type: TransactionTypeBuilder) -> Result<u64, WalletError> {
+        trace!("estimate fees");
+        let mut state = EstimateFeesState::new();
+
+        #[cfg(feature = "network_handler")]
+        self.add_registered_keys_for_fees_estimation(&mut state, &FeeBuilder::default(), &tx_type).await?;
+
+        // TODO: support multisig
+        let builder = TransactionBuilder::new(TxVersion::V0, self.get_public_key().clone(), 0, tx_type, FeeBuilder::default());
+        let estimated_fees = builder.estimate_fees(&mut state)
+            .map_err(|e| WalletError::Any(e.into()))?;
+
+        Ok(estimated_fees)
+    }
+
+    // Export all transactions in CSV format to the given writer
+    // This will sort the transactions by topoheight before exporting
+    pub async fn export_transactions_in_csv<W: Write>(&self, storage: &EncryptedStorage, mut transactions: Vec<InnerTransactionEntry>, w: &mut W) -> Result<(), WalletError> {
+        trace!("export transactions in csv");
+
+        // Sort transactions by topoheight
+        transactions.sort_by(|a, b| a.get_topoheight().cmp(&b.get_topoheight()));
+
+        writeln!(w, "Date,TopoHeight,Hash,Type,From/To,Asset,Amount,Fee,Nonce").context("Error while writing headers")?;
+        for tx in transactions {
+            match tx.get_entry() {
+                EntryData::Burn { asset, amount, fee, nonce } => {
+                    let data = storage.get_asset(&asset).await?;
+                    writeln!(w, "{},{},{},{},{},-,{},{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "Burn", data.get_name(), format_coin(*amount, data.get_decimals()), format_xelis(*fee), nonce).context("Error while writing csv line")?;
+                },
+                EntryData::Coinbase { reward } => {
+                    writeln!(w, "{},{},{},{},{},-,{},-,-", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "Coinbase", "XELIS", format_xelis(*reward)).context("Error while writing csv line")?;
+                },
+                EntryData::Incoming { from, transfers } => {
+                    for transfer in transfers {
+                        let data = storage.get_asset(&transfer.get_asset()).await?;
+                        writeln!(w, "{},{},{},{},{},{},{},-,-", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "Incoming", from.as_address(self.get_network().is_mainnet()), data.get_name(), format_coin(transfer.get_amount(), data.get_decimals())).context("Error while writing csv line")?;
+                    }
+                },
+                EntryData::Outgoing { transfers, fee, nonce } => {
+                    for transfer in transfers {
+                        let data = storage.get_asset(&transfer.get_asset()).await?;
+                        writeln!(w, "{},{},{},{},{},{},{},{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "Outgoing", transfer.get_destination().as_address(self.get_network().is_mainnet()), data.get_name(), format_coin(transfer.get_amount(), data.get_decimals()), format_xelis(*fee), nonce).context("Error while writing csv line")?;
+                    }
+                },
+                EntryData::MultiSig { participants, threshold, fee, nonce } => {
+                    let str_participants: Vec<String> = participants.iter().map(|p| p.as_address(self.get_network().is_mainnet()).to_string()).collect();
+                    writeln!(w, "{},{},{},{},{},{},-,{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "MultiSig", str_participants.join("|"), threshold, format_xelis(*fee), nonce).context("Error while writing csv line")?;
+                },
+                EntryData::InvokeContract { contract, deposits, chunk_id, fee, nonce } => {
+                    let mut str_deposits = Vec::new();
+                    for (asset, amount) in deposits {
+                        let data = storage.get_asset(&asset).await?;
+                        str_deposits.push(format!("{}:{}", data.get_name(), format_coin(*amount, data.get_decimals())));
+                    }
+
+                    writeln!(w, "{},{},{},{},{},{},{},{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "InvokeContract", contract, str_deposits.join("|"), chunk_id, format_xelis(*fee), nonce).context("Error while writing csv line")?;
+                },
+                EntryData::DeployContract { fee, nonce } => {
+                    writeln!(w, "{},{},{},{},-,-,-,{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "DeployContract", format_xelis(*fee), nonce).context("Error while writing csv line")?;
+                },
+                EntryData::ManageContract { contract, action, fee, nonce } => {
+                    let action = if action.is_pause() { "pause" } else { "unpause" };
+                    writeln!(w, "{},{},{},{},{},-,{},{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "ManageContract", contract, action, format_xelis(*fee), nonce).context("Error while writing csv line")?;
+                },
+                EntryData::ExecutionCredits { payload, fee, nonce } => {
+                    let action = if payload.is_deposit() { "deposit" } else { "withdraw" };
+                    writeln!(w, "{},{},{},{},-,{},{},{},{}", datetime_from_timestamp(tx.get_timestamp())?, tx.get_topoheight(), tx.get_hash(), "ExecutionCredits", format_xelis(payload.get_amount()), action, format_xelis(*fee), nonce).context("Error while writing csv line")?;
+                },
+            }
+        }
+    
+        w.flush().context("Error while flushing CSV file")?;
+        Ok(())
+    }
+
+    // Set the source of fiat prices used to annotate the exported history
+    pub async fn set_price_source(&self, source: Option<Arc<dyn PriceSource>>) {
+        *self.price_source.lock().await = source;
+    }
+
+    // Export the whole history in the requested format to the given writer
+    // If requested and a price source is set, entries are annotated with their fiat value
+    pub async fn export_history<W: Write>(&self, format: HistoryExportFormat, fiat_valuation: bool, w: &mut W) -> Result<(), WalletError> {
+        trace!("export history");
+        let price_source = if fiat_valuation {
+            self.price_source.lock().await.clone()
+        } else {
+            None
+        };
+
+        let entries = {
+            let storage = self.storage.read().await;
+            let transactions = storage.get_transactions()?;
+            build_history_export(&storage, transactions, self.get_network().is_mainnet(), price_source.as_deref()).await?
+        };
+
+        write_history_export(&entries, format, w)
+    }
+
+    // set wallet in online mode: start a communication task which will keep the wallet synced
+    #[cfg(feature = "network_handler")]
+    pub async fn set_online_mode(self: &Arc<Self>, daemon_address: &String, auto_reconnect: bool) -> Result<(), WalletError> {
+        trace!("Set online mode to daemon {} with auto reconnect set to {}", daemon_address, auto_reconnect);
+        if self.is_online().await {
+            // user have to set in offline mode himself first
+            return Err(WalletError::AlreadyOnlineMode)
+        }
+
+        // balances can't be decrypted while locked
+        if self.is_locked() {
+            return Err(WalletError::WalletLocked)
+        }
+
+        // create the network handler
+        let network_handler = NetworkHandler::new(Arc::clone(&self), daemon_address).await?;
+        // start the task
+        network_handler.start(auto_reconnect).await?;
+        *self.network_handler.lock().await = Some(network_handler);
+        Ok(())
+    }
 
+    // set the wallet in online mode using a shared daemon API
+    // this allows to share the same connection/Daemon API across several wallets to save resources
+    #[cfg(feature = "network_handler")]
+    pub async fn set_online_mode_with_api(self: &Arc<Self>, daemon_api: Arc<DaemonAPI>, auto_reconnect: bool) -> Result<(), WalletError> {
+        trace!("Set online mode with API with auto reconnect set to {}", auto_reconnect);
+        if self.is_online().await {
+            // user have to set in offline mode himself first
+            return Err(WalletError::AlreadyOnlineMode)
+        }
+
+        // balances can't be decrypted while locked
+        if self.is_locked() {
+            return Err(WalletError::WalletLocked)
+        }
+
+        // create the network handler
+        let network_handler = NetworkHandler::with_api(Arc::clone(&self), daemon_api).await?;
+        // start the task
+        network_handler.start(auto_reconnect).await?;
+        *self.network_handler.lock().await = Some(network_handler);
+        Ok(())
+    }
+
+    // set the wallet in online mode using the healthiest daemon of the list
+    // daemons are checked periodically and the wallet is moved to another one
+    // if the current daemon goes offline or is lagging behind the others
+    #[cfg(feature = "network_handler")]
+    pub async fn set_online_mode_with_failover(self: &Arc<Self>, daemon_addresses: Vec<String>) -> Result<(), WalletError> {
+        trace!("Set online mode with failover across {} daemons", daemon_addresses.len());
+        if self.is_online().await {
+            // user have to set in offline mode himself first
+            return Err(WalletError::AlreadyOnlineMode)
+        }
+
+        // balances can't be decrypted while locked
+        if self.is_locked() {
+            return Err(WalletError::WalletLocked)
+        }
+
+        if daemon_addresses.is_empty() {
+            return Err(WalletError::NoDaemonAddress)
+        }
+
+        let failover = Arc::new(DaemonFailover::new(daemon_addresses, MAX_DAEMON_TOPOHEIGHT_LAG));
+        let Some((index, api)) = failover.select().await else {
+            failover.disconnect().await;
+            return Err(WalletError::NoDaemonAvailable)
+        };
+
+        if let Err(e) = self.set_online_mode_with_api(api, true).await {
+            failover.disconnect().await;
+            return Err(e)
+        }
+
+        failover.set_active(index).await;
+        failover.start(Arc::clone(self)).await;
+        *self.daemon_failover.lock().await = Some(failover);
+        Ok(())
+    }
+
+    // Move the network handler to another daemon connection
+    // Used by the daemon failover when the current daemon is unhealthy
+    #[cfg(feature = "network_handler")]
+    pub(crate) async fn replace_daemon_api(self: &Arc<Self>, daemon_api: Arc<DaemonAPI>) -> Result<(), WalletError> {
+        trace!("Replace daemon API");
         let mut handler = self.network_handler.lock().await;
         if let Some(network_handler) = handler.take() {
-            network_handler.stop(true).await?;
-        } else {
-            return Err(WalletError::NotOnlineMode)
+            if let Err(e) = network_handler.stop(true).await {
+                debug!("Error while stopping network handler: {}", e);
+            }
         }
 
+        let network_handler = NetworkHandler::with_api(Arc::clone(&self), daemon_api).await?;
+        network_handler.start(true).await?;
+        *handler = Some(network_handler);
         Ok(())
     }
 
+    // Status of the daemons used by the failover
+    #[cfg(feature = "network_handler")]
+    pub async fn get_daemon_endpoints(&self) -> Result<Vec<DaemonEndpointStatus>, WalletError> {
+        let failover = self.daemon_failover.lock().await;
+        let failover = failover.as_ref().ok_or(WalletError::DaemonFailoverNotEnabled)?;
+        Ok(failover.get_status().await)
+    }
+
+    // set wallet in offline mode: stop communication task if exists
+    #[cfg(feature = "network_handler")]
+    pub async fn set_offline_mode(&self) -> Result<(), WalletError> {
+        trace!("Set offline mode");
+
+        let failover = self.daemon_failover.lock().await.take();
+        if let Some(failover) = failover.as_ref() {
+            failover.stop().await;
+        }
+
+        let mut handler = self.network_handler.lock().await;
+        let res = match handler.take() {
+            Some(network_handler) => network_handler.stop(true).await.map_err(WalletError::from),
+            None => Err(WalletError::NotOnlineMode)
+        };
+
+        if let Some(failover) = failover {
+            failover.disconnect().await;
+        }
+
+        res
+    }
+
     // rescan the wallet from the given topoheight
     // that will delete all transactions above the given topoheight and all balances
     // then it will re-fetch all transactions and balances from daemon
@@ -1015,6 +2005,12 @@ impl Wallet {
             {
                 debug!("set synced topoheight to {}", topoheight);
                 storage.set_synced_topoheight(topoheight)?;
+                // checkpoint to resume the rescan if interrupted
+                storage.set_rescan_state(&RescanState::new(topoheight))?;
+                // history below the sync checkpoint is going to be scanned
+                if storage.get_sync_checkpoint()?.is_some_and(|checkpoint| topoheight <= checkpoint) {
+                    storage.delete_sync_checkpoint()?;
+                }
                 storage.delete_top_block_hash()?;
                 // balances will be re-fetched from daemon
                 storage.delete_balances().await?;
@@ -1035,12 +2031,63 @@ impl Wallet {
                 }
             }
             debug!("Starting again network handler");
+            network_handler.resume_rescan().await;
             network_handler.start(auto_reconnect).await?;
         } else {
             return Err(WalletError::NotOnlineMode)
         }
 
-        Ok(())
+        Ok(())
+    }
+
+    // Pause the rescan in progress
+    // It will stop at the next checkpoint and can be resumed later
+    #[cfg(feature = "network_handler")]
+    pub async fn pause_rescan(&self) -> Result<(), WalletError> {
+        let storage = self.get_storage().read().await;
+        if storage.get_rescan_state()?.is_none() {
+            return Err(WalletError::NoRescanInProgress)
+        }
+
+        let handler = self.network_handler.lock().await;
+        let network_handler = handler.as_ref().ok_or(WalletError::NotOnlineMode)?;
+        network_handler.pause_rescan();
+
+        Ok(())
+    }
+
+    // Resume the rescan from its last checkpoint
+    #[cfg(feature = "network_handler")]
+    pub async fn resume_rescan(&self) -> Result<(), WalletError> {
+        {
+            let storage = self.get_storage().read().await;
+            if storage.get_rescan_state()?.is_none() {
+                return Err(WalletError::NoRescanInProgress)
+            }
+        }
+
+        let handler = self.network_handler.lock().await;
+        let network_handler = handler.as_ref().ok_or(WalletError::NotOnlineMode)?;
+        network_handler.resume_rescan().await;
+
+        Ok(())
+    }
+
+    // Get the progress of the rescan in progress if any
+    #[cfg(feature = "network_handler")]
+    pub async fn get_rescan_progress(&self) -> Result<Option<RescanProgress>, WalletError> {
+        let state = {
+            let storage = self.get_storage().read().await;
+            storage.get_rescan_state()?
+        };
+
+        let Some(state) = state else {
+            return Ok(None)
+        };
+
+        let handler = self.network_handler.lock().await;
+        let network_handler = handler.as_ref().ok_or(WalletError::NotOnlineMode)?;
+        Ok(Some(network_handler.get_rescan_progress(&state).await?))
     }
 
     // Check if the wallet is in online mode
@@ -1061,8 +2108,14 @@ impl Wallet {
     }
 
     // Create a signature of the given data
-    pub fn sign_data(&self, data: &[u8]) -> Signature {
-        self.inner.keypair.sign(data)
+    pub fn sign_data(&self, data: &[u8]) -> Result<Signature, WalletError> {
+        Ok(self.get_keypair()?.sign(data))
+    }
+
+    // Create a pre-signature of the given data for an adaptor point
+    // It becomes a valid signature once completed with the adaptor secret
+    pub fn sign_adaptor_data(&self, data: &[u8], adaptor: &AdaptorPoint) -> Result<AdaptorSignature, WalletError> {
+        Ok(AdaptorSignature::sign(&self.get_keypair()?, data, adaptor))
     }
 
     // Get the public key of the wallet
@@ -1070,9 +2123,178 @@ impl Wallet {
         &self.inner.public_key
     }
 
-    // Get the keypair of the wallet
-    pub fn get_keypair(&self) -> &KeyPair {
-        &self.inner.keypair
+    // Get the keypair of the wallet to sign data or transactions
+    // Returns an error if the wallet is watch-only or locked
+    pub fn get_keypair(&self) -> Result<Arc<KeyPair>, WalletError> {
+        if self.watch_only {
+            return Err(WalletError::WatchOnlyWallet)
+        }
+
+        let keypair = self.inner.get_keypair()?;
+        self.touch();
+        Ok(keypair)
+    }
+
+    // Register an activity to delay the auto-lock
+    pub fn touch(&self) {
+        self.last_activity.store(get_current_time_in_millis(), Ordering::SeqCst);
+    }
+
+    // Is the keypair wiped from memory
+    pub fn is_locked(&self) -> bool {
+        self.inner.is_locked()
+    }
+
+    // Wipe the keypair from memory
+    // Syncing is paused as balances can't be decrypted anymore
+    // The password is required to unlock the wallet
+    pub async fn lock(&self) -> Result<(), WalletError> {
+        if self.is_locked() {
+            return Ok(())
+        }
+
+        debug!("Locking wallet");
+        self.inner.set_keypair(None)?;
+
+        #[cfg(feature = "network_handler")]
+        {
+            let handler = self.network_handler.lock().await;
+            if let Some(network_handler) = handler.as_ref() {
+                if let Err(e) = network_handler.stop(false).await {
+                    debug!("Error while pausing network handler: {}", e);
+                }
+            }
+        }
+
+        self.propagate_event(Event::Locked).await;
+        Ok(())
+    }
+
+    // Restore the keypair from the storage using the password
+    // Failed attempts are rate limited: each failure doubles the delay before the next attempt
+    pub async fn unlock(&self, password: &str) -> Result<(), Error> {
+        let now = get_current_time_in_millis();
+        let next_attempt = self.next_unlock_attempt.load(Ordering::SeqCst);
+        if now < next_attempt {
+            return Err(WalletError::UnlockRateLimited((next_attempt - now).div_ceil(1000)).into())
+        }
+
+        if let Err(e) = self.is_valid_password(password).await {
+            let failures = self.unlock_failures.fetch_add(1, Ordering::SeqCst);
+            let delay = UNLOCK_FAILURE_DELAY.saturating_mul(1 << failures.min(16)).min(MAX_UNLOCK_FAILURE_DELAY);
+            self.next_unlock_attempt.store(now + delay * 1000, Ordering::SeqCst);
+            return Err(e)
+        }
+        self.unlock_failures.store(0, Ordering::SeqCst);
+
+        if !self.is_locked() {
+            return Ok(())
+        }
+
+        debug!("Unlocking wallet");
+        let keypair = {
+            let storage = self.storage.read().await;
+            KeyPair::from_private_key(storage.get_private_key()?)
+        };
+        self.inner.set_keypair(Some(keypair))?;
+        self.touch();
+
+        #[cfg(feature = "network_handler")]
+        {
+            let handler = self.network_handler.lock().await;
+            if let Some(network_handler) = handler.as_ref() {
+                if !network_handler.is_running().await {
+                    network_handler.start(true).await?;
+                }
+            }
+        }
+
+        self.propagate_event(Event::Unlocked).await;
+        Ok(())
+    }
+
+    // Lock the wallet after a period of inactivity in seconds, 0 to disable it
+    pub async fn set_auto_lock_timeout(self: &Arc<Self>, timeout: u64) {
+        self.auto_lock_timeout.store(timeout, Ordering::SeqCst);
+        self.touch();
+
+        let mut task = self.auto_lock_task.lock().await;
+        if let Some(handle) = task.take() {
+            handle.abort();
+        }
+
+        if timeout == 0 {
+            return
+        }
+
+        debug!("Auto-lock enabled after {} seconds of inactivity", timeout);
+        // Weak reference so the task doesn't keep the wallet alive
+        let wallet = Arc::downgrade(self);
+        *task = Some(spawn_task("wallet-auto-lock", async move {
+            loop {
+                sleep(Duration::from_secs(AUTO_LOCK_CHECK_INTERVAL)).await;
+                let Some(wallet) = wallet.upgrade() else {
+                    break;
+                };
+
+                let inactivity = get_current_time_in_millis().saturating_sub(wallet.last_activity.load(Ordering::SeqCst));
+                if !wallet.is_locked() && inactivity >= timeout * 1000 {
+                    if let Err(e) = wallet.lock().await {
+                        error!("Error while locking wallet: {}", e);
+                    }
+                }
+            }
+        }));
+    }
+
+    // Inactivity in seconds before locking the wallet, 0 if disabled
+    pub fn get_auto_lock_timeout(&self) -> u64 {
+        self.auto_lock_timeout.load(Ordering::SeqCst)
+    }
+
+    // Index of this account, 0 for the master account
+    pub async fn get_sub_account_index(&self) -> Result<u32, Error> {
+        let storage = self.storage.read().await;
+        storage.get_sub_account_index()
+    }
+
+    // Sub-accounts derived from this wallet with their addresses
+    // Only available on the master account
+    pub async fn get_sub_accounts(&self) -> Result<Vec<(u32, Address)>, Error> {
+        let storage = self.storage.read().await;
+        if storage.get_sub_account_index()? != 0 {
+            return Err(WalletError::NotMasterAccount.into())
+        }
+
+        let accounts = storage.get_sub_accounts()?;
+        let mut addresses = Vec::with_capacity(accounts.len());
+        for index in accounts {
+            addresses.push((index, self.get_sub_account_address(index)?));
+        }
+
+        Ok(addresses)
+    }
+
+    // Address of a sub-account derived from this wallet
+    // The wallet must be the master account to get the right address
+    pub fn get_sub_account_address(&self, index: u32) -> Result<Address, WalletError> {
+        let private_key = self.get_keypair()?.get_private_key().derive_sub_account(index);
+        let keypair = KeyPair::from_private_key(private_key);
+        Ok(keypair.get_public_key().compress().to_address(self.get_network().is_mainnet()))
+    }
+
+    // Is this wallet a watch-only wallet
+    pub fn is_watch_only(&self) -> bool {
+        self.watch_only
+    }
+
+    // Export the view key of this wallet in hex format
+    // It can be used to create a watch-only wallet through RecoverOption::ViewKey
+    // NOTE: balances are encrypted using the account key, so the view key
+    // is the same secret as the private key: only the watch-only wallet
+    // prevents any transaction or signature to be built with it
+    pub fn export_view_key(&self) -> Result<String, WalletError> {
+        Ok(self.inner.get_keypair()?.get_private_key().to_hex())
     }
 
     // Get the address of the wallet using its network used
@@ -1085,10 +2307,412 @@ impl Wallet {
         self.get_public_key().clone().to_address_with(self.get_network().is_mainnet(), data)
     }
 
+    // Get the integrated address for a payment ID
+    // Transfers to it will have the payment ID in their extra data
+    pub fn get_address_with_payment_id(&self, payment_id: PaymentId) -> Address {
+        self.get_address_with(DataElement::from_payment_id(payment_id))
+    }
+
+    // Save a contact in the address book, overwriting any entry with the same name
+    pub async fn set_address_book_entry(&self, entry: AddressBookEntry) -> Result<(), WalletError> {
+        trace!("set address book entry {}", entry.name);
+        if entry.name.is_empty() || entry.name.len() > MAX_ADDRESS_BOOK_NAME_SIZE {
+            return Err(WalletError::InvalidAddressBookName(MAX_ADDRESS_BOOK_NAME_SIZE))
+        }
+
+        if entry.notes.as_ref().is_some_and(|notes| notes.len() > MAX_ADDRESS_BOOK_NOTES_SIZE) {
+            return Err(WalletError::AddressBookNotesTooLong(MAX_ADDRESS_BOOK_NOTES_SIZE))
+        }
+
+        if entry.address.is_mainnet() != self.get_network().is_mainnet() {
+            return Err(WalletError::InvalidAddressNetwork)
+        }
+
+        let mut storage = self.storage.write().await;
+        storage.set_address_book_entry(&entry)?;
+
+        Ok(())
+    }
+
+    // Delete a contact from the address book
+    pub async fn delete_address_book_entry(&self, name: &str) -> Result<(), WalletError> {
+        trace!("delete address book entry {}", name);
+        let mut storage = self.storage.write().await;
+        if !storage.has_address_book_entry(name)? {
+            return Err(WalletError::AddressBookEntryNotFound(name.to_owned()))
+        }
+
+        storage.delete_address_book_entry(name)?;
+        Ok(())
+    }
+
+    // Attach a local note and tags to a transaction from the history
+    // An empty label removes the existing one
+    pub async fn set_transaction_label(&self, hash: &Hash, label: TransactionLabel) -> Result<(), WalletError> {
+        trace!("set transaction label {}", hash);
+        if label.note.as_ref().is_some_and(|note| note.len() > MAX_TRANSACTION_NOTE_SIZE) {
+            return Err(WalletError::TransactionNoteTooLong(MAX_TRANSACTION_NOTE_SIZE))
+        }
+
+        if label.tags.len() > MAX_TRANSACTION_TAGS {
+            return Err(WalletError::TooManyTransactionTags(MAX_TRANSACTION_TAGS))
+        }
+
+        if label.tags.iter().any(|tag| tag.is_empty() || tag.len() > MAX_TRANSACTION_TAG_SIZE) {
+            return Err(WalletError::InvalidTransactionTag(MAX_TRANSACTION_TAG_SIZE))
+        }
+
+        let mut storage = self.storage.write().await;
+        if !storage.has_transaction(hash)? {
+            return Err(WalletError::TransactionNotFound(hash.clone()))
+        }
+
+        storage.set_transaction_label(hash, &label)?;
+        Ok(())
+    }
+
+    // Set the assets tracked by the wallet
+    // Balances of the ignored assets are deleted, a rescan is required to recover them once tracked again
+    pub async fn set_asset_filter(&self, filter: AssetFilter) -> Result<(), WalletError> {
+        trace!("set asset filter");
+        let mut storage = self.storage.write().await;
+        for asset in storage.get_assets().await? {
+            if !filter.is_tracked(&asset) && storage.has_balance_for(&asset).await? {
+                debug!("Deleting balance of ignored asset {}", asset);
+                storage.delete_balance_for(&asset).await?;
+            }
+        }
+
+        storage.set_asset_filter(filter)?;
+        Ok(())
+    }
+
+    // Get the assets tracked by the wallet
+    pub async fn get_asset_filter(&self) -> AssetFilter {
+        trace!("get asset filter");
+        let storage = self.storage.read().await;
+        storage.get_asset_filter().clone()
+    }
+
+    // Retrieve the metadata of an asset
+    // If the asset is unknown, it is fetched from the daemon and stored
+    pub async fn get_asset(&self, asset: &Hash) -> Result<AssetData, WalletError> {
+        trace!("get asset {}", asset);
+        {
+            let storage = self.storage.read().await;
+            if storage.contains_asset(asset).await? {
+                return Ok(storage.get_asset(asset).await?)
+            }
+        }
+
+        #[cfg(feature = "network_handler")]
+        {
+            let network_handler = self.network_handler.lock().await;
+            if let Some(network_handler) = network_handler.as_ref() {
+                let data = network_handler.get_api().get_asset(asset).await?;
+                let data = AssetData::new(data.decimals, data.name.into_owned(), data.max_supply);
+
+                let mut storage = self.storage.write().await;
+                // It may have been added by the sync in the meantime
+                if !storage.contains_asset(asset).await? {
+                    debug!("Storing metadata of asset {}", asset);
+                    storage.add_asset(asset, data.clone()).await?;
+                }
+
+                return Ok(data)
+            }
+        }
+
+        Err(WalletError::AssetNotFound(asset.clone()))
+    }
+
+    // Set the local ticker of an asset, none to reset it
+    pub async fn set_asset_ticker(&self, asset: &Hash, ticker: Option<String>) -> Result<(), WalletError> {
+        trace!("set asset ticker {}", asset);
+        let mut storage = self.storage.write().await;
+        match ticker {
+            Some(ticker) => {
+                if ticker.is_empty() || ticker.len() > MAX_ASSET_TICKER_SIZE || !ticker.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return Err(WalletError::InvalidAssetTicker(MAX_ASSET_TICKER_SIZE))
+                }
+
+                storage.set_asset_ticker(asset, ticker)?;
+            },
+            None => storage.delete_asset_ticker(asset)?
+        };
+
+        Ok(())
+    }
+
+    // Get the ticker to display for an asset
+    // Fallback on the asset name if no local ticker is set
+    pub async fn get_asset_ticker(&self, asset: &Hash) -> Result<String, WalletError> {
+        trace!("get asset ticker {}", asset);
+        {
+            let storage = self.storage.read().await;
+            if let Some(ticker) = storage.get_asset_ticker(asset)? {
+                return Ok(ticker)
+            }
+        }
+
+        if *asset == XELIS_ASSET {
+            return Ok(XELIS_TICKER.to_owned())
+        }
+
+        let data = self.get_asset(asset).await?;
+        Ok(data.get_name().to_owned())
+    }
+
+    // Format an amount in atomic units using the decimals and ticker of the asset
+    pub async fn format_amount(&self, asset: &Hash, amount: u64) -> Result<String, WalletError> {
+        let data = self.get_asset(asset).await?;
+        let ticker = self.get_asset_ticker(asset).await?;
+        Ok(format!("{} {}", format_coin(amount, data.get_decimals()), ticker))
+    }
+
+    // Parse an amount to atomic units using the decimals of the asset
+    pub async fn parse_amount(&self, asset: &Hash, value: &str) -> Result<u64, WalletError> {
+        let decimals = self.get_asset(asset).await?.get_decimals();
+        from_coin(value, decimals).ok_or(WalletError::InvalidAmount(decimals))
+    }
+
+    // Create or replace a recurring payment
+    // It is sent automatically once due while the wallet is online
+    pub async fn set_scheduled_payment(&self, name: String, destination: Address, asset: Hash, amount: u64, interval: u64, start: Option<TimestampMillis>, count: Option<u64>) -> Result<ScheduledPayment, WalletError> {
+        trace!("set scheduled payment {}", name);
+        if name.is_empty() || name.len() > MAX_SCHEDULED_PAYMENT_NAME_SIZE {
+            return Err(WalletError::InvalidScheduledPaymentName(MAX_SCHEDULED_PAYMENT_NAME_SIZE))
+        }
+
+        if interval < MIN_SCHEDULED_PAYMENT_INTERVAL {
+            return Err(WalletError::ScheduledPaymentIntervalTooLow(MIN_SCHEDULED_PAYMENT_INTERVAL))
+        }
+
+        if count == Some(0) {
+            return Err(WalletError::InvalidScheduledPaymentCount)
+        }
+
+        if destination.is_mainnet() != self.get_network().is_mainnet() {
+            return Err(WalletError::InvalidAddressNetwork)
+        }
+
+        let payment = ScheduledPayment {
+            name,
+            destination,
+            asset,
+            amount,
+            interval,
+            next_execution: start.unwrap_or_else(get_current_time_in_millis),
+            remaining: count,
+            executions: 0,
+            last_transaction: None,
+            failures: 0,
+            last_error: None
+        };
+
+        let mut storage = self.storage.write().await;
+        storage.set_scheduled_payment(&payment)?;
+        Ok(payment)
+    }
+
+    // Delete a recurring payment
+    pub async fn delete_scheduled_payment(&self, name: &str) -> Result<(), WalletError> {
+        trace!("delete scheduled payment {}", name);
+        let mut storage = self.storage.write().await;
+        if !storage.has_scheduled_payment(name)? {
+            return Err(WalletError::ScheduledPaymentNotFound(name.to_owned()))
+        }
+
+        storage.delete_scheduled_payment(name)?;
+        Ok(())
+    }
+
+    // Get all the XSWD applications with granted permissions
+    pub async fn get_xswd_applications(&self) -> Result<Vec<XSWDApplicationEntry>, WalletError> {
+        trace!("get xswd applications");
+        let storage = self.storage.read().await;
+        let applications = storage.get_xswd_applications()?;
+        Ok(applications)
+    }
+
+    // Revoke the permissions granted to a XSWD application
+    // The application is disconnected if it is currently connected
+    pub async fn revoke_xswd_application(&self, id: &str) -> Result<(), WalletError> {
+        trace!("revoke xswd application {}", id);
+        {
+            let mut storage = self.storage.write().await;
+            if !storage.has_xswd_application(id)? {
+                return Err(WalletError::XSWDApplicationNotFound(id.to_owned()))
+            }
+
+            storage.delete_xswd_application(id)?;
+        }
+
+        #[cfg(feature = "api_server")]
+        if let Some(APIServer::XSWD(xswd)) = self.api_server.lock().await.as_ref() {
+            if xswd.get_handler().disconnect_application(id).await {
+                debug!("XSWD application {} has been disconnected", id);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Send all the scheduled payments that are due
+    // Executions missed while the wallet was closed are not replayed:
+    // only one payment is sent and the next one is planned in the future
+    #[cfg(feature = "network_handler")]
+    pub async fn process_scheduled_payments(&self) -> Result<(), WalletError> {
+        trace!("process scheduled payments");
+        let now = get_current_time_in_millis();
+        let payments = {
+            let storage = self.storage.read().await;
+            storage.get_scheduled_payments()?
+        };
+
+        for mut payment in payments.into_iter().filter(|p| p.next_execution <= now) {
+            let event = match self.send_scheduled_payment(&payment).await {
+                Ok(hash) => {
+                    info!("Scheduled payment {} sent in transaction {}", payment.name, hash);
+                    let interval = payment.interval * 1000;
+                    let missed = (now - payment.next_execution) / interval;
+                    payment.next_execution += (missed + 1) * interval;
+                    payment.remaining = payment.remaining.map(|r| r.saturating_sub(1));
+                    payment.executions += 1;
+                    payment.last_transaction = Some(hash.clone());
+                    payment.failures = 0;
+                    payment.last_error = None;
+
+                    let finished = payment.remaining == Some(0);
+                    Event::ScheduledPaymentExecuted(ScheduledPaymentExecuted {
+                        name: payment.name.clone(),
+                        hash,
+                        next_execution: if finished { None } else { Some(payment.next_execution) }
+                    })
+                },
+                Err(e) => {
+                    warn!("Error while sending scheduled payment {}: {}", payment.name, e);
+                    payment.next_execution = now + SCHEDULED_PAYMENT_RETRY_DELAY * 1000;
+                    payment.failures += 1;
+                    payment.last_error = Some(e.to_string());
+
+                    Event::ScheduledPaymentFailed(ScheduledPaymentFailed {
+                        name: payment.name.clone(),
+                        error: e.to_string(),
+                        failures: payment.failures,
+                        next_retry: payment.next_execution
+                    })
+                }
+            };
+
+            {
+                let mut storage = self.storage.write().await;
+                // It may have been deleted while sending it
+                if storage.has_scheduled_payment(&payment.name)? {
+                    if payment.remaining == Some(0) {
+                        storage.delete_scheduled_payment(&payment.name)?;
+                    } else {
+                        storage.set_scheduled_payment(&payment)?;
+                    }
+                }
+            }
+
+            self.propagate_event(event).await;
+        }
+
+        Ok(())
+    }
+
+    // Build, sign and broadcast the transaction of a scheduled payment
+    // The transaction is tagged in the history to link it with the payment
+    #[cfg(feature = "network_handler")]
+    async fn send_scheduled_payment(&self, payment: &ScheduledPayment) -> Result<Hash, WalletError> {
+        trace!("send scheduled payment {}", payment.name);
+        let mut storage = self.storage.write().await;
+        if storage.get_multisig_state().await?.is_some() {
+            return Err(WalletError::ScheduledPaymentMultiSig)
+        }
+
+        let transfer = TransferBuilder {
+            destination: payment.destination.clone(),
+            amount: payment.amount,
+            asset: payment.asset.clone(),
+            extra_data: None
+        };
+        let (tx, mut state) = self.create_transaction_with_storage(&storage, TransactionTypeBuilder::Transfers(vec![transfer]), FeeBuilder::default()).await?;
+
+        if let Err(e) = self.submit_transaction(&tx).await {
+            storage.clear_tx_cache();
+            storage.delete_unconfirmed_balances().await;
+            return Err(e)
+        }
+
+        state.apply_changes(&mut storage).await?;
+
+        let hash = tx.hash();
+        let mut tags = IndexSet::new();
+        tags.insert(SCHEDULED_PAYMENT_TAG.to_owned());
+        let label = TransactionLabel {
+            note: Some(format!("Scheduled payment {}", payment.name)),
+            tags
+        };
+        storage.set_transaction_label(&hash, &label)?;
+
+        Ok(hash)
+    }
+
+    // Resolve an address or a name saved in the address book
+    // Returns the default asset of the contact if any
+    pub async fn resolve_address(&self, value: &str) -> Result<(Address, Option<Hash>), WalletError> {
+        trace!("resolve address {}", value);
+        if let Ok(address) = Address::from_string(&value.to_owned()) {
+            return Ok((address, None))
+        }
+
+        let storage = self.storage.read().await;
+        if !storage.has_address_book_entry(value)? {
+            return Err(WalletError::AddressBookEntryNotFound(value.to_owned()))
+        }
+
+        let entry = storage.get_address_book_entry(value)?;
+        Ok((entry.address, entry.default_asset))
+    }
+
+    // Build a payment request to this wallet
+    pub fn create_payment_request(&self, amount: Option<u64>, asset: Option<Hash>, extra_data: Option<DataElement>) -> PaymentRequest {
+        PaymentRequest {
+            address: self.get_address(),
+            amount,
+            asset,
+            extra_data,
+            label: None,
+            message: None
+        }
+    }
+
+    // Parse a payment URI and verify it is for the same network
+    pub fn parse_payment_uri(&self, uri: &str) -> Result<PaymentRequest, WalletError> {
+        trace!("parse payment uri {}", uri);
+        let request = PaymentRequest::from_uri(uri)?;
+        if request.address.is_mainnet() != self.get_network().is_mainnet() {
+            return Err(WalletError::InvalidAddressNetwork)
+        }
+
+        Ok(request)
+    }
+
     // Returns the seed using the language index provided
-    pub fn get_seed(&self, language_index: usize) -> Result<String, Error> {
-        let words = mnemonics::key_to_words(self.inner.keypair.get_private_key(), language_index)?;
-        Ok(words.join(" "))
+    // Seed is wiped from memory once dropped
+    pub fn get_seed(&self, language_index: usize) -> Result<Zeroizing<String>, Error> {
+        let words = mnemonics::key_to_words(self.get_keypair()?.get_private_key(), language_index)?;
+        Ok(Zeroizing::new(words.join(" ")))
+    }
+
+    // Export the private key as a BIP39 mnemonic in the requested language
+    #[cfg(feature = "bip39")]
+    pub fn get_bip39_mnemonic(&self, language_index: usize) -> Result<Zeroizing<String>, Error> {
+        let mnemonic = mnemonics::bip39::key_to_mnemonic(self.get_keypair()?.get_private_key(), language_index)?;
+        Ok(mnemonic)
     }
 
     // Current account nonce for transactions
@@ -1109,6 +2733,25 @@ impl Wallet {
     }
 }
 
+// Build the signing status of a pending multisig transaction
+fn build_multisig_signing_status(hash: Hash, unsigned: &UnsignedTransaction, payload: &MultiSigPayload) -> MultiSigSigningStatus {
+    let signers: Vec<u8> = unsigned.multisig()
+        .map(|m| m.get_signatures().iter().map(|s| s.id).collect())
+        .unwrap_or_default();
+
+    let missing = (0..payload.participants.len() as u8)
+        .filter(|id| !signers.contains(id))
+        .collect();
+
+    MultiSigSigningStatus {
+        hash,
+        threshold: payload.threshold,
+        ready: signers.len() >= payload.threshold as usize,
+        signers,
+        missing
+    }
+}
+
 // Parse a datetime from a timestamp
 fn datetime_from_timestamp(timestamp: u64) -> Result<chrono::DateTime<chrono::Local>, WalletError> {
     match chrono::Local.timestamp_millis_opt(timestamp as i64) {
@@ -1122,6 +2765,8 @@ pub enum XSWDEvent {
     RequestPermission(AppStateShared, RpcRequest, oneshot::Sender<Result<PermissionResult, Error>>),
     // bool represents if it was signed or not
     RequestApplication(AppStateShared, bool, oneshot::Sender<Result<PermissionResult, Error>>),
+    // Scope of permissions to persist for the application
+    RequestScope(AppStateShared, XSWDApplicationScope, oneshot::Sender<Result<PermissionResult, Error>>),
     CancelRequest(AppStateShared, oneshot::Sender<Result<(), Error>>)
 }
 
@@ -1136,7 +2781,8 @@ impl XSWDPermissionHandler for Arc<Wallet> {
             let (callback, receiver) = oneshot::channel();
             let event = match request {
                 PermissionRequest::Application(signed) => XSWDEvent::RequestApplication(app_state, signed, callback),
-                PermissionRequest::Request(request) => XSWDEvent::RequestPermission(app_state, request.clone(), callback)
+                PermissionRequest::Request(request) => XSWDEvent::RequestPermission(app_state, request.clone(), callback),
+                PermissionRequest::Scope(scope) => XSWDEvent::RequestScope(app_state, scope.clone(), callback)
             };
 
             // Send the XSWD Message
@@ -1165,7 +2811,68 @@ impl XSWDPermissionHandler for Arc<Wallet> {
     }
 
     async fn get_public_key(&self) -> Result<&DecompressedPublicKey, Error> {
-        Ok(self.inner.keypair.get_public_key())
+        Ok(&self.inner.decompressed_public_key)
+    }
+
+    async fn get_application_scope(&self, app_state: &AppStateShared) -> Result<Option<XSWDApplicationScope>, Error> {
+        let storage = self.storage.read().await;
+        if !storage.has_xswd_application(app_state.get_id())? {
+            return Ok(None)
+        }
+
+        let entry = storage.get_xswd_application(app_state.get_id())?;
+        Ok(Some(entry.scope))
+    }
+
+    async fn set_application_scope(&self, app_state: &AppStateShared, scope: XSWDApplicationScope) -> Result<(), Error> {
+        let mut storage = self.storage.write().await;
+        // Keep the spending of the day if a scope was already granted
+        let (day, spent) = if storage.has_xswd_application(app_state.get_id())? {
+            let entry = storage.get_xswd_application(app_state.get_id())?;
+            (entry.day, entry.spent)
+        } else {
+            (0, IndexMap::new())
+        };
+
+        storage.set_xswd_application(&XSWDApplicationEntry {
+            id: app_state.get_id().clone(),
+            name: app_state.get_name().clone(),
+            url: app_state.get_url().clone(),
+            scope,
+            day,
+            spent
+        })
+    }
+
+    async fn consume_application_budget(&self, app_state: &AppStateShared, amounts: &IndexMap<Hash, u64>) -> Result<bool, Error> {
+        let mut storage = self.storage.write().await;
+        if !storage.has_xswd_application(app_state.get_id())? {
+            return Ok(false)
+        }
+
+        let mut entry = storage.get_xswd_application(app_state.get_id())?;
+        // Daily spending is reset every day at midnight UTC
+        let today = get_current_time_in_seconds() / 86400;
+        if entry.day != today {
+            entry.day = today;
+            entry.spent.clear();
+        }
+
+        for (asset, amount) in amounts {
+            let limit = entry.scope.daily_limits.get(asset).copied().unwrap_or(0);
+            let spent = entry.spent.get(asset).copied().unwrap_or(0);
+            match spent.checked_add(*amount) {
+                Some(total) if total <= limit => {},
+                _ => return Ok(false)
+            }
+        }
+
+        for (asset, amount) in amounts {
+            *entry.spent.entry(asset.clone()).or_insert(0) += amount;
+        }
+
+        storage.set_xswd_application(&entry)?;
+        Ok(true)
     }
 }
 
@@ -0,0 +1,163 @@
+use std::{collections::HashMap, sync::Arc};
+use anyhow::Error;
+use log::{debug, info, warn};
+use xelis_common::{
+    api::wallet::ManagedWalletConnection,
+    network::Network,
+    tokio::sync::RwLock,
+    utils::sanitize_daemon_address
+};
+use crate::{
+    config::MAX_WALLET_NAME_SIZE,
+    daemon_api::DaemonAPI,
+    error::WalletError,
+    precomputed_tables::PrecomputedTablesShared,
+    wallet::{RecoverOption, Wallet}
+};
+
+// Manage several wallets opened at the same time in a single process
+// Each wallet can use the shared daemon connection or its own one
+pub struct WalletManager {
+    // Directory where the wallets are stored
+    directory: String,
+    network: Network,
+    precomputed_tables: PrecomputedTablesShared,
+    // Daemon connection shared between the wallets
+    daemon_api: Option<Arc<DaemonAPI>>,
+    wallets: RwLock<HashMap<String, Arc<Wallet>>>
+}
+
+impl WalletManager {
+    // Create a new wallet manager
+    // If a daemon address is provided, a connection is opened to be shared between the wallets
+    pub async fn new(directory: String, network: Network, precomputed_tables: PrecomputedTablesShared, daemon_address: Option<&str>) -> Result<Arc<Self>, Error> {
+        let daemon_api = match daemon_address {
+            Some(address) => {
+                info!("Connecting to shared daemon at '{}'", address);
+                let api = DaemonAPI::new(format!("{}/json_rpc", sanitize_daemon_address(address))).await?;
+                Some(Arc::new(api))
+            },
+            None => None
+        };
+
+        Ok(Arc::new(Self {
+            directory,
+            network,
+            precomputed_tables,
+            daemon_api,
+            wallets: RwLock::new(HashMap::new())
+        }))
+    }
+
+    pub fn get_network(&self) -> &Network {
+        &self.network
+    }
+
+    // Open an existing wallet or one of its sub-accounts and connect it to a daemon
+    // A sub-account is managed under the name '<name>.<account>'
+    pub async fn open_wallet(&self, name: &str, password: &str, connection: ManagedWalletConnection, account: u32) -> Result<Arc<Wallet>, Error> {
+        let path = self.get_wallet_path(name)?;
+        let name = Wallet::get_sub_account_path(name, account);
+        let mut wallets = self.wallets.write().await;
+        if wallets.contains_key(&name) {
+            return Err(WalletError::WalletAlreadyOpen(name).into())
+        }
+
+        debug!("Opening wallet {} at {}", name, path);
+        let wallet = Wallet::open_sub_account(&path, password, account, self.network, self.precomputed_tables.clone())?;
+        self.connect(&wallet, connection).await?;
+
+        info!("Wallet {} is now open", name);
+        wallets.insert(name, wallet.clone());
+        Ok(wallet)
+    }
+
+    // Create a new wallet, recovered from a seed if provided, and connect it to a daemon
+    pub async fn create_wallet(&self, name: &str, password: &str, seed: Option<&str>, connection: ManagedWalletConnection) -> Result<Arc<Wallet>, Error> {
+        let path = self.get_wallet_path(name)?;
+        let mut wallets = self.wallets.write().await;
+        if wallets.contains_key(name) {
+            return Err(WalletError::WalletAlreadyOpen(name.to_owned()).into())
+        }
+
+        debug!("Creating wallet {} at {}", name, path);
+        let wallet = Wallet::create(&path, password, seed.map(RecoverOption::Seed), self.network, self.precomputed_tables.clone())?;
+        self.connect(&wallet, connection).await?;
+
+        wallets.insert(name.to_owned(), wallet.clone());
+        info!("Wallet {} has been created", name);
+        Ok(wallet)
+    }
+
+    // Close an opened wallet
+    pub async fn close_wallet(&self, name: &str) -> Result<(), WalletError> {
+        let wallet = {
+            let mut wallets = self.wallets.write().await;
+            wallets.remove(name).ok_or_else(|| WalletError::WalletNotOpen(name.to_owned()))?
+        };
+
+        wallet.close().await;
+        info!("Wallet {} has been closed", name);
+        Ok(())
+    }
+
+    // Close all the opened wallets
+    pub async fn close_all(&self) {
+        let wallets: Vec<_> = {
+            let mut wallets = self.wallets.write().await;
+            wallets.drain().collect()
+        };
+
+        for (name, wallet) in wallets {
+            debug!("Closing wallet {}", name);
+            wallet.close().await;
+        }
+    }
+
+    // Get an opened wallet by its name
+    pub async fn get_wallet(&self, name: &str) -> Option<Arc<Wallet>> {
+        let wallets = self.wallets.read().await;
+        wallets.get(name).cloned()
+    }
+
+    // Get all the opened wallets with their names
+    pub async fn get_wallets(&self) -> Vec<(String, Arc<Wallet>)> {
+        let wallets = self.wallets.read().await;
+        wallets.iter()
+            .map(|(name, wallet)| (name.clone(), wallet.clone()))
+            .collect()
+    }
+
+    async fn connect(&self, wallet: &Arc<Wallet>, connection: ManagedWalletConnection) -> Result<(), Error> {
+        let res = match connection {
+            ManagedWalletConnection::Shared => match self.daemon_api.as_ref() {
+                Some(api) => wallet.set_online_mode_with_api(api.clone(), true).await,
+                None => Err(WalletError::NoSharedDaemonConnection)
+            },
+            ManagedWalletConnection::Dedicated { daemon_address } => wallet.set_online_mode(&daemon_address, true).await,
+            ManagedWalletConnection::Offline => Ok(())
+        };
+
+        if let Err(e) = res {
+            warn!("Couldn't connect wallet to daemon: {}", e);
+            // Release the storage so the wallet can be opened again
+            wallet.close().await;
+            return Err(e.into())
+        }
+
+        Ok(())
+    }
+
+    // Names are used as directory names, only allow safe characters
+    fn get_wallet_path(&self, name: &str) -> Result<String, WalletError> {
+        let valid = !name.is_empty()
+            && name.len() <= MAX_WALLET_NAME_SIZE
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        if !valid {
+            return Err(WalletError::InvalidWalletName(MAX_WALLET_NAME_SIZE))
+        }
+
+        Ok(format!("{}{}", self.directory, name))
+    }
+}
@@ -0,0 +1,240 @@
+use std::{fmt::Display, ops::ControlFlow};
+use log::info;
+use wasm_bindgen::prelude::*;
+use xelis_common::{
+    api::{wallet::PreparedTransaction, DataElement, SplitAddressResult},
+    crypto::{
+        ecdlp,
+        elgamal::CompressedCiphertext,
+        Address,
+        KeyPair,
+        PrivateKey
+    },
+    serializer::Serializer,
+    transaction::builder::{FeeBuilder, TransactionBuilder}
+};
+use crate::{
+    mnemonics,
+    precomputed_tables::{self, PrecomputedTablesShared},
+    storage::Balance,
+    transaction_builder::TransactionBuilderState
+};
+
+// Bindings exposing the wallet core to web wallets
+// Nothing here requires a storage, a filesystem or a daemon connection:
+// balances are provided by the caller and results are returned as hex or JSON strings
+
+fn js_error<E: Display>(e: E) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+// Log the progress of the tables generation in the console
+struct LogProgressReport;
+
+impl ecdlp::ProgressTableGenerationReportFunction for LogProgressReport {
+    fn report(&self, progress: f64, step: ecdlp::ReportStep) -> ControlFlow<()> {
+        info!("Progress: {:.2}% on step {:?}", progress * 100.0, step);
+        ControlFlow::Continue(())
+    }
+}
+
+// Precomputed tables used to decrypt the ciphertexts
+#[wasm_bindgen]
+pub struct WasmPrecomputedTables {
+    inner: PrecomputedTablesShared
+}
+
+#[wasm_bindgen]
+impl WasmPrecomputedTables {
+    // Generate the tables in memory
+    pub fn generate(l1: usize) -> Result<WasmPrecomputedTables, JsError> {
+        let inner = precomputed_tables::generate_precomputed_tables(l1, LogProgressReport)
+            .map_err(js_error)?;
+
+        Ok(Self { inner })
+    }
+
+    // Load the tables from bytes previously exported
+    pub fn from_bytes(l1: usize, bytes: &[u8]) -> Result<WasmPrecomputedTables, JsError> {
+        let inner = precomputed_tables::precomputed_tables_from_bytes(l1, bytes)
+            .map_err(js_error)?;
+
+        Ok(Self { inner })
+    }
+
+    // Generate the tables and return their bytes
+    // The caller can store them and load them later using `from_bytes`
+    pub fn generate_bytes(l1: usize) -> Result<Vec<u8>, JsError> {
+        let tables = ecdlp::ECDLPTables::generate_with_progress_report(l1, LogProgressReport)
+            .map_err(js_error)?;
+
+        Ok(tables.as_slice().to_vec())
+    }
+}
+
+#[wasm_bindgen]
+pub struct WasmKeyPair {
+    inner: KeyPair
+}
+
+#[wasm_bindgen]
+impl WasmKeyPair {
+    // Generate a new random key pair
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmKeyPair {
+        Self {
+            inner: KeyPair::new()
+        }
+    }
+
+    // Recover a key pair from its private key in hex
+    pub fn from_private_key(hex: &str) -> Result<WasmKeyPair, JsError> {
+        let private_key = PrivateKey::from_hex(hex)
+            .map_err(js_error)?;
+
+        Ok(Self {
+            inner: KeyPair::from_private_key(private_key)
+        })
+    }
+
+    // Recover a key pair from its seed, words are separated by spaces
+    pub fn from_seed(seed: &str) -> Result<WasmKeyPair, JsError> {
+        let words: Vec<&str> = seed.split_whitespace().collect();
+        let private_key = mnemonics::words_to_key(&words)
+            .map_err(js_error)?;
+
+        Ok(Self {
+            inner: KeyPair::from_private_key(private_key)
+        })
+    }
+
+    // Private key in hex
+    pub fn private_key(&self) -> String {
+        self.inner.get_private_key().to_hex()
+    }
+
+    // Compressed public key in hex
+    pub fn public_key(&self) -> String {
+        self.inner.get_public_key().compress().to_hex()
+    }
+
+    // Seed of the private key in the requested language
+    pub fn seed(&self, language: usize) -> Result<String, JsError> {
+        let words = mnemonics::key_to_words(self.inner.get_private_key(), language)
+            .map_err(js_error)?;
+
+        Ok(words.join(" "))
+    }
+
+    // Address of the key pair for the requested network
+    pub fn address(&self, mainnet: bool) -> String {
+        self.inner.get_public_key()
+            .compress()
+            .to_address(mainnet)
+            .to_string()
+    }
+
+    // Integrated address of the key pair
+    // Data must be a DataElement in JSON format
+    pub fn integrated_address(&self, mainnet: bool, data: &str) -> Result<String, JsError> {
+        let data: DataElement = serde_json::from_str(data)
+            .map_err(js_error)?;
+
+        Ok(self.inner.get_public_key()
+            .compress()
+            .to_address_with(mainnet, data)
+            .to_string())
+    }
+
+    // Sign a message, the signature is returned in hex
+    pub fn sign(&self, message: &[u8]) -> String {
+        self.inner.sign(message).to_hex()
+    }
+
+    // Decrypt a compressed ciphertext provided in hex
+    pub fn decrypt_ciphertext(&self, tables: &WasmPrecomputedTables, ciphertext: &str) -> Result<u64, JsError> {
+        let ciphertext = CompressedCiphertext::from_hex(ciphertext)
+            .map_err(js_error)?
+            .decompress()
+            .map_err(js_error)?;
+
+        let lock = tables.inner.read()
+            .map_err(|_| JsError::new("precomputed tables are poisoned"))?;
+        self.inner.decrypt(&lock.view(), &ciphertext)
+            .ok_or_else(|| JsError::new("ciphertext couldn't be decoded"))
+    }
+
+    // Sign a transaction prepared by an online wallet (see `prepare_transaction` RPC method)
+    // The prepared transaction is expected in JSON format and the signed transaction is returned in hex
+    pub fn sign_prepared_transaction(&self, tables: &WasmPrecomputedTables, prepared: &str) -> Result<String, JsError> {
+        let prepared: PreparedTransaction = serde_json::from_str(prepared)
+            .map_err(js_error)?;
+
+        let public_key = self.inner.get_public_key().compress();
+        if *prepared.source.get_public_key() != public_key {
+            return Err(JsError::new("prepared transaction source doesn't match the key pair"))
+        }
+
+        let lock = tables.inner.read()
+            .map_err(|_| JsError::new("precomputed tables are poisoned"))?;
+        let view = lock.view();
+
+        let mut state = TransactionBuilderState::new(prepared.source.is_mainnet(), prepared.reference, prepared.nonce);
+        for (asset, mut ciphertext) in prepared.balances {
+            let decompressed = ciphertext.decompressed()
+                .map_err(js_error)?;
+            let amount = self.inner.decrypt(&view, decompressed)
+                .ok_or_else(|| JsError::new("balance couldn't be decoded"))?;
+
+            state.add_balance(asset, Balance::new(amount, ciphertext));
+        }
+
+        let builder = TransactionBuilder::new(prepared.tx_version, public_key, 0, prepared.tx_type, FeeBuilder::Value(prepared.fee));
+        let transaction = builder.build(&mut state, &self.inner)
+            .map_err(js_error)?;
+
+        Ok(transaction.to_hex())
+    }
+}
+
+// Verify if the address is valid
+#[wasm_bindgen]
+pub fn is_valid_address(address: &str) -> bool {
+    Address::from_string(&address.to_owned()).is_ok()
+}
+
+// Verify if the address is valid and for mainnet
+#[wasm_bindgen]
+pub fn is_mainnet_address(address: &str) -> Result<bool, JsError> {
+    let address = Address::from_string(&address.to_owned())
+        .map_err(js_error)?;
+
+    Ok(address.is_mainnet())
+}
+
+// Compressed public key of the address in hex
+#[wasm_bindgen]
+pub fn address_public_key(address: &str) -> Result<String, JsError> {
+    let address = Address::from_string(&address.to_owned())
+        .map_err(js_error)?;
+
+    Ok(address.get_public_key().to_hex())
+}
+
+// Split an integrated address into its address and data
+// The result is returned in JSON format
+#[wasm_bindgen]
+pub fn split_address(address: &str) -> Result<String, JsError> {
+    let address = Address::from_string(&address.to_owned())
+        .map_err(js_error)?;
+
+    let (data, address) = address.extract_data();
+    let integrated_data = data.ok_or_else(|| JsError::new("address is not an integrated address"))?;
+    let size = integrated_data.size();
+
+    serde_json::to_string(&SplitAddressResult {
+        address,
+        integrated_data,
+        size
+    }).map_err(js_error)
+}